@@ -114,6 +114,21 @@ pub(crate) mod function {
                     &mut err,
                 )?;
             }
+            Status::Downloaded { update_refs, .. } => {
+                let remote = repo
+                    .find_default_remote(gix::remote::Direction::Fetch)
+                    .expect("one origin remote")?;
+                let ref_specs = remote.refspecs(gix::remote::Direction::Fetch);
+                print_updates(
+                    &repo,
+                    &Default::default(),
+                    update_refs,
+                    ref_specs,
+                    fetch_outcome.ref_map,
+                    &mut out,
+                    &mut err,
+                )?;
+            }
         };
 
         if let Some(gix::worktree::state::checkout::Outcome { collisions, errors, .. }) = outcome {
@@ -53,6 +53,10 @@ pub fn describe(
         max_candidates,
         long_format,
         dirty_suffix,
+        broken_suffix,
+        contains,
+        matching,
+        excluding,
     }: describe::Options,
 ) -> Result<()> {
     repo.object_cache_size_if_unset(4 * 1024 * 1024);
@@ -74,6 +78,9 @@ pub fn describe(
         .traverse_first_parent(first_parent)
         .id_as_fallback(always)
         .max_candidates(max_candidates)
+        .contains(contains)
+        .matching(matching)
+        .excluding(excluding)
         .try_resolve()?
         .with_context(|| format!("Did not find a single candidate ref for naming id '{}'", commit.id))?;
 
@@ -81,8 +88,12 @@ pub fn describe(
         writeln!(err, "traversed {} commits", resolution.outcome.commits_seen)?;
     }
 
-    let mut describe_id = resolution.format_with_dirty_suffix(dirty_suffix)?;
-    describe_id.long(long_format);
+    let mut describe_id = if contains {
+        resolution.format()?
+    } else {
+        resolution.format_with_dirty_or_broken_suffix(dirty_suffix, broken_suffix)?
+    };
+    describe_id.long(long_format && !contains);
 
     writeln!(out, "{describe_id}")?;
     Ok(())
@@ -99,5 +110,9 @@ pub mod describe {
         pub statistics: bool,
         pub max_candidates: usize,
         pub dirty_suffix: Option<String>,
+        pub broken_suffix: Option<String>,
+        pub contains: bool,
+        pub matching: Vec<String>,
+        pub excluding: Vec<String>,
     }
 }
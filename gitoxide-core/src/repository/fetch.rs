@@ -12,6 +12,8 @@ pub struct Options {
     pub handshake_info: bool,
     pub negotiation_info: bool,
     pub open_negotiation_graph: Option<std::path::PathBuf>,
+    /// If `Some(enabled)`, override `fetch.prune` and `remote.<name>.prune`.
+    pub prune: Option<bool>,
 }
 
 pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=3;
@@ -46,6 +48,7 @@ pub(crate) mod function {
             open_negotiation_graph,
             shallow,
             ref_specs,
+            prune,
         }: Options,
     ) -> anyhow::Result<()>
     where
@@ -61,12 +64,15 @@ pub(crate) mod function {
             remote.replace_refspecs(ref_specs.iter(), gix::remote::Direction::Fetch)?;
             remote = remote.with_fetch_tags(gix::remote::fetch::Tags::None);
         }
-        let res: gix::remote::fetch::Outcome = remote
+        let mut prepare = remote
             .connect(gix::remote::Direction::Fetch)?
             .prepare_fetch(&mut progress, Default::default())?
             .with_dry_run(dry_run)
-            .with_shallow(shallow)
-            .receive(&mut progress, &gix::interrupt::IS_INTERRUPTED)?;
+            .with_shallow(shallow);
+        if let Some(prune) = prune {
+            prepare = prepare.with_prune(prune);
+        }
+        let res: gix::remote::fetch::Outcome = prepare.receive(&mut progress, &gix::interrupt::IS_INTERRUPTED)?;
 
         if handshake_info {
             writeln!(out, "Handshake Information")?;
@@ -103,6 +109,7 @@ pub(crate) mod function {
             Status::Change {
                 update_refs,
                 write_pack_bundle,
+                packfile_uris,
                 negotiate,
             } => {
                 print_updates(&repo, &negotiate, update_refs, ref_specs, res.ref_map, &mut out, err)?;
@@ -112,6 +119,17 @@ pub(crate) mod function {
                 if let Some(index_path) = write_pack_bundle.index_path {
                     writeln!(out, "index file: \"{}\"", index_path.display()).ok();
                 }
+                for packfile_uri in packfile_uris {
+                    if let Some(data_path) = packfile_uri.write_pack_bundle.data_path {
+                        writeln!(
+                            out,
+                            "pack  file: \"{}\" (from {})",
+                            data_path.display(),
+                            packfile_uri.uri
+                        )
+                        .ok();
+                    }
+                }
                 if negotiation_info {
                     print_negotiate_info(&mut out, Some(&negotiate))?;
                 }
@@ -120,6 +138,24 @@ pub(crate) mod function {
                 }
                 Ok(())
             }
+            Status::Downloaded { update_refs, download } => {
+                print_updates(
+                    &repo,
+                    &Default::default(),
+                    update_refs,
+                    ref_specs,
+                    res.ref_map,
+                    &mut out,
+                    err,
+                )?;
+                writeln!(out, "loose objects: {}", download.loose_objects).ok();
+                for pack in download.packs {
+                    if let Some(data_path) = pack.data_path {
+                        writeln!(out, "pack  file: \"{}\"", data_path.display()).ok();
+                    }
+                }
+                Ok(())
+            }
         }?;
         if dry_run {
             writeln!(out, "DRY-RUN: No ref was updated and no pack was received.").ok();
@@ -290,6 +326,9 @@ pub(crate) mod function {
             }?;
         }
         consume_skipped_tags(&mut skipped_due_to_implicit_tag, &mut out)?;
+        for name in update_refs.iter_pruned() {
+            writeln!(out, "\t{} [pruned]", name.as_bstr())?;
+        }
         if !map.fixes.is_empty() {
             writeln!(
                 err,
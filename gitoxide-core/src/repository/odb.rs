@@ -1,5 +1,5 @@
 use std::io;
-use std::sync::atomic::Ordering;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use anyhow::bail;
 
@@ -271,3 +271,135 @@ pub fn entries(repo: gix::Repository, format: OutputFormat, mut out: impl io::Wr
 
     Ok(())
 }
+
+pub mod repack {
+    use crate::OutputFormat;
+
+    pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=3;
+
+    #[derive(Debug, Copy, Clone)]
+    pub struct Options {
+        pub format: OutputFormat,
+        pub mode: gix::repack::Mode,
+        pub write_multi_pack_index: bool,
+        pub thread_limit: Option<usize>,
+    }
+}
+
+pub fn repack(
+    repo: gix::Repository,
+    mut progress: impl gix::NestedProgress + 'static,
+    mut out: impl io::Write,
+    should_interrupt: &AtomicBool,
+    repack::Options {
+        format,
+        mode,
+        write_multi_pack_index,
+        thread_limit,
+    }: repack::Options,
+) -> anyhow::Result<()> {
+    if format != OutputFormat::Human {
+        bail!("Only human output format is supported at the moment");
+    }
+
+    let outcome = repo.repack(
+        &mut progress,
+        should_interrupt,
+        gix::repack::Options {
+            mode,
+            write_multi_pack_index,
+            thread_limit,
+        },
+    )?;
+    match &outcome.index_path {
+        Some(index_path) => writeln!(
+            out,
+            "Consolidated {} packs into {} with {} objects",
+            outcome.consolidated_packs.len(),
+            index_path.display(),
+            outcome.num_objects
+        )?,
+        None => writeln!(out, "There are no packs to consolidate")?,
+    }
+    for index_path in &outcome.retained_packs {
+        writeln!(out, "Could not remove redundant pack {}", index_path.display())?;
+    }
+    if let Some(path) = outcome.multi_pack_index_path {
+        writeln!(out, "Wrote multi-pack index to {}", path.display())?;
+    }
+    Ok(())
+}
+
+pub mod gc {
+    use crate::OutputFormat;
+
+    pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=3;
+
+    #[derive(Debug, Clone)]
+    pub struct Options {
+        pub format: OutputFormat,
+        pub prune: Option<String>,
+        pub force: bool,
+        pub thread_limit: Option<usize>,
+    }
+}
+
+pub fn gc(
+    repo: gix::Repository,
+    mut progress: impl gix::NestedProgress + 'static,
+    mut out: impl io::Write,
+    should_interrupt: &AtomicBool,
+    gc::Options {
+        format,
+        prune,
+        force,
+        thread_limit,
+    }: gc::Options,
+) -> anyhow::Result<()> {
+    if format != OutputFormat::Human {
+        bail!("Only human output format is supported at the moment");
+    }
+
+    let mut options = repo.gc_options()?;
+    if let Some(prune) = prune {
+        options.prune_expiry = gix::config::tree::Gc::PRUNE_EXPIRE.try_into_expiry(
+            gix::bstr::BStr::new(prune.as_str()).into(),
+            std::time::SystemTime::now(),
+        )?;
+    }
+    options.force = force;
+    options.thread_limit = thread_limit;
+    let outcome = repo.gc(&mut progress, should_interrupt, options)?;
+
+    writeln!(out, "Packed {} references", outcome.packed_refs)?;
+    writeln!(out, "Expired {} reflog entries", outcome.expired_reflog_entries)?;
+    writeln!(out, "Found {} reachable objects", outcome.num_reachable_objects)?;
+    if let Some(index_path) = &outcome.loose_objects_index_path {
+        writeln!(
+            out,
+            "Packed {} loose objects into {}",
+            outcome.packed_loose_objects,
+            index_path.display()
+        )?;
+    }
+    writeln!(
+        out,
+        "Removed {} loose objects that were already packed and pruned {} unreachable ones",
+        outcome.redundant_loose_objects, outcome.pruned_loose_objects
+    )?;
+    if let Some(repack) = outcome.repack {
+        if let Some(index_path) = &repack.index_path {
+            writeln!(
+                out,
+                "Consolidated {} packs into {} with {} objects",
+                repack.consolidated_packs.len(),
+                index_path.display(),
+                repack.num_objects
+            )?;
+        }
+        for index_path in &repack.retained_packs {
+            writeln!(out, "Could not remove redundant pack {}", index_path.display())?;
+        }
+    }
+    Ok(())
+}
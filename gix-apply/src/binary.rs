@@ -0,0 +1,205 @@
+use std::io::Write;
+
+use crate::patch::{BinaryHunk, BinaryMethod};
+
+const ALPHABET: &[u8; 85] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz!#$%&()*+-;<=>?@^_`{|}~";
+
+///
+pub mod decode {
+    /// The error returned when decoding the data of binary patches.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Expected a 'literal' or 'delta' line after 'GIT binary patch'")]
+        MissingHunk,
+        #[error("Could not parse the size of the binary hunk")]
+        Size,
+        #[error("Invalid length indicator {byte:?} in base85 line")]
+        LineLength { byte: char },
+        #[error("The base85 line has an invalid length")]
+        Base85Length,
+        #[error("Invalid base85 character {byte:?}")]
+        Base85Character { byte: char },
+        #[error("A base85 group overflowed")]
+        Base85Overflow,
+        #[error(transparent)]
+        Inflate(#[from] gix_features::zlib::inflate::Error),
+        #[error("Expected {expected} bytes after decompression, but got {actual}")]
+        InflatedSize { expected: usize, actual: usize },
+    }
+
+    use super::ALPHABET;
+
+    /// Decode a single `line` of base85 data as used in `GIT binary patch` hunks, whose first character indicates
+    /// the amount of decoded bytes, and append them to `out`.
+    pub fn base85_line(line: &[u8], out: &mut Vec<u8>) -> Result<(), Error> {
+        let (&length, data) = line.split_first().ok_or(Error::Base85Length)?;
+        let mut remaining = match length {
+            b'A'..=b'Z' => length - b'A' + 1,
+            b'a'..=b'z' => length - b'a' + 27,
+            _ => {
+                return Err(Error::LineLength {
+                    byte: char::from(length),
+                })
+            }
+        } as usize;
+        if data.len() != (remaining + 3) / 4 * 5 {
+            return Err(Error::Base85Length);
+        }
+        for group in data.chunks(5) {
+            let mut acc = 0u32;
+            for &byte in group {
+                let value = ALPHABET
+                    .iter()
+                    .position(|c| *c == byte)
+                    .ok_or(Error::Base85Character { byte: char::from(byte) })?;
+                acc = acc
+                    .checked_mul(85)
+                    .and_then(|acc| acc.checked_add(value as u32))
+                    .ok_or(Error::Base85Overflow)?;
+            }
+            let bytes = acc.to_be_bytes();
+            let take = remaining.min(4);
+            out.extend_from_slice(&bytes[..take]);
+            remaining -= take;
+        }
+        Ok(())
+    }
+
+    /// Decompress the zlib-compressed `data` which is expected to be exactly `size` bytes when decompressed.
+    pub fn inflate(data: &[u8], size: usize) -> Result<Vec<u8>, Error> {
+        let mut out = vec![0; size];
+        let (_status, _consumed, produced) = gix_features::zlib::Inflate::default().once(data, &mut out)?;
+        if produced != size {
+            return Err(Error::InflatedSize {
+                expected: size,
+                actual: produced,
+            });
+        }
+        Ok(out)
+    }
+}
+
+///
+pub mod apply {
+    /// The error returned by [`apply()`](super::apply()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The delta expects a base of {expected} bytes, but it has {actual} bytes")]
+        BaseSize { expected: u64, actual: usize },
+        #[error("The delta is malformed")]
+        Malformed,
+        #[error("The delta produced {actual} bytes, but {expected} bytes were expected")]
+        ResultSize { expected: u64, actual: usize },
+    }
+}
+
+/// Write a `GIT binary patch` that turns `old` into `new` and back to `out`, just like `git diff --binary` does,
+/// except that the data is always stored literally instead of as delta.
+pub fn encode(old: &[u8], new: &[u8], out: &mut dyn Write) -> std::io::Result<()> {
+    out.write_all(b"GIT binary patch\n")?;
+    encode_literal(new, out)?;
+    encode_literal(old, out)
+}
+
+fn encode_literal(data: &[u8], out: &mut dyn Write) -> std::io::Result<()> {
+    writeln!(out, "literal {}", data.len())?;
+    let mut deflate = gix_features::zlib::stream::deflate::Write::new(Vec::new());
+    deflate.write_all(data)?;
+    deflate.flush()?;
+    for line in deflate.into_inner().chunks(52) {
+        let len = line.len() as u8;
+        out.write_all(&[if len <= 26 { b'A' + len - 1 } else { b'a' + len - 27 }])?;
+        for group in line.chunks(4) {
+            let mut acc = (0..4).fold(0u32, |acc, pos| {
+                acc << 8 | u32::from(group.get(pos).copied().unwrap_or(0))
+            });
+            let mut encoded = [0u8; 5];
+            for byte in encoded.iter_mut().rev() {
+                *byte = ALPHABET[(acc % 85) as usize];
+                acc /= 85;
+            }
+            out.write_all(&encoded)?;
+        }
+        out.write_all(b"\n")?;
+    }
+    out.write_all(b"\n")
+}
+
+/// Produce the new content from `old` according to `hunk` and write it into `out`, which is cleared beforehand.
+pub fn apply(hunk: &BinaryHunk, old: &[u8], out: &mut Vec<u8>) -> Result<(), apply::Error> {
+    out.clear();
+    match hunk.method {
+        BinaryMethod::Literal => {
+            out.extend_from_slice(&hunk.data);
+            Ok(())
+        }
+        BinaryMethod::Delta => apply_delta(old, &hunk.data, out),
+    }
+}
+
+/// Apply a delta in the format also used in packs, with a header of base and result size followed by copy and insert
+/// instructions.
+fn apply_delta(base: &[u8], delta: &[u8], out: &mut Vec<u8>) -> Result<(), apply::Error> {
+    use apply::Error;
+    let (base_size, consumed) = size(delta).ok_or(Error::Malformed)?;
+    let mut delta = &delta[consumed..];
+    if base_size != base.len() as u64 {
+        return Err(Error::BaseSize {
+            expected: base_size,
+            actual: base.len(),
+        });
+    }
+    let (result_size, consumed) = size(delta).ok_or(Error::Malformed)?;
+    delta = &delta[consumed..];
+
+    while let Some((&cmd, rest)) = delta.split_first() {
+        delta = rest;
+        if cmd & 0x80 != 0 {
+            let mut read = |bit: u8, shift: u32| -> Result<usize, Error> {
+                if cmd & bit == 0 {
+                    return Ok(0);
+                }
+                let (&byte, rest) = delta.split_first().ok_or(Error::Malformed)?;
+                delta = rest;
+                Ok(usize::from(byte) << shift)
+            };
+            let offset = read(0x01, 0)? | read(0x02, 8)? | read(0x04, 16)? | read(0x08, 24)?;
+            let size = match read(0x10, 0)? | read(0x20, 8)? | read(0x40, 16)? {
+                0 => 0x10000,
+                size => size,
+            };
+            let copy = offset
+                .checked_add(size)
+                .and_then(|end| base.get(offset..end))
+                .ok_or(Error::Malformed)?;
+            out.extend_from_slice(copy);
+        } else if cmd != 0 {
+            let size = usize::from(cmd);
+            let insert = delta.get(..size).ok_or(Error::Malformed)?;
+            out.extend_from_slice(insert);
+            delta = &delta[size..];
+        } else {
+            return Err(Error::Malformed);
+        }
+    }
+    if out.len() as u64 != result_size {
+        return Err(Error::ResultSize {
+            expected: result_size,
+            actual: out.len(),
+        });
+    }
+    Ok(())
+}
+
+fn size(data: &[u8]) -> Option<(u64, usize)> {
+    let mut size = 0u64;
+    for (consumed, byte) in data.iter().enumerate().take(10) {
+        size |= u64::from(byte & 0x7f) << (consumed * 7);
+        if byte & 0x80 == 0 {
+            return Some((size, consumed + 1));
+        }
+    }
+    None
+}
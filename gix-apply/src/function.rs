@@ -0,0 +1,206 @@
+use std::collections::BTreeMap;
+
+use bstr::{BStr, BString, ByteSlice};
+use gix_object::tree::EntryKind;
+
+use crate::patch::{Body, Hunk, Operation, Patch};
+use crate::{Change, Entry, Error, Options, Outcome, Rejected};
+
+/// Apply all `patches` in order, reading the files they apply to with `read`, and return the resulting state of each path
+/// they touch without changing anything.
+///
+/// `read` is called with a path relative to the root of the repository and returns its [entry](Entry), or `None` if there
+/// is no file at that path. It is only called once per path, as the result of earlier patches is used by later ones.
+///
+/// `find_preimage` is used for [three-way merges](Options::three_way) and is called with the possibly abbreviated
+/// hexadecimal id of the blob the patch was created for, as recorded in the patch. It returns the blob's data if it's available.
+///
+/// Use the [`tree`](crate::tree), [`index`](crate::index) or [`worktree`](crate::worktree) modules to apply patches to
+/// these directly.
+pub fn apply<E>(
+    patches: &[Patch],
+    mut read: impl FnMut(&BStr) -> Result<Option<Entry>, E>,
+    mut find_preimage: impl FnMut(&BStr) -> Option<Vec<u8>>,
+    options: Options,
+) -> Result<Outcome, Error>
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+{
+    let mut state = BTreeMap::<BString, Change>::new();
+    let mut current = |state: &BTreeMap<BString, Change>, path: &BStr| -> Result<Option<Entry>, Error> {
+        Ok(match state.get(path) {
+            Some(Change::Write(entry)) => Some(entry.clone()),
+            Some(Change::Conflict { mode, merged, .. }) => Some(Entry {
+                mode: *mode,
+                data: merged.clone(),
+            }),
+            Some(Change::Remove) => None,
+            None => read(path).map_err(|err| Error::Read {
+                path: path.to_owned(),
+                source: err.into(),
+            })?,
+        })
+    };
+
+    let mut rejected = Vec::new();
+    for patch in patches {
+        let reversed;
+        let patch = if options.reverse {
+            reversed = patch.reversed();
+            &reversed
+        } else {
+            patch
+        };
+
+        let source = match patch.source_path() {
+            Some(path) => Some(current(&state, path)?.ok_or_else(|| Error::MissingSource { path: path.to_owned() })?),
+            None => None,
+        };
+        if let Some(new_path) = patch
+            .new_path
+            .as_ref()
+            .filter(|path| patch.source_path() != Some(path.as_ref()))
+        {
+            if current(&state, new_path.as_ref())?.is_some() {
+                return Err(Error::AlreadyExists { path: new_path.clone() });
+            }
+        }
+
+        let path = patch.path();
+        let old = source.as_ref().map_or(&[][..], |entry| entry.data.as_slice());
+        let mode = patch
+            .new_mode
+            .or(source.as_ref().map(|entry| entry.mode))
+            .unwrap_or(EntryKind::Blob);
+        let mut has_rejected_hunks = false;
+        let change = match &patch.body {
+            Body::Empty => Change::Write(Entry {
+                mode,
+                data: old.to_vec(),
+            }),
+            Body::Text(hunks) => match three_way(patch, hunks, old, mode, &mut find_preimage, options) {
+                Some(change) => change,
+                None => {
+                    let mut data = Vec::new();
+                    let failed = crate::text::apply(hunks, old, options.text, &mut data);
+                    if !failed.is_empty() {
+                        if !options.reject {
+                            return Err(Error::HunksFailed {
+                                path: path.to_owned(),
+                                hunks: failed,
+                                total: hunks.len(),
+                            });
+                        }
+                        has_rejected_hunks = true;
+                        rejected.push(Rejected {
+                            path: path.to_owned(),
+                            hunks: failed.into_iter().map(|idx| hunks[idx].clone()).collect(),
+                        });
+                    }
+                    Change::Write(Entry { mode, data })
+                }
+            },
+            Body::Binary { forward, .. } => {
+                if patch.operation != Operation::Add && !matches_full_id(patch.old_id.as_ref(), old) {
+                    return Err(Error::BinaryPreimageMismatch { path: path.to_owned() });
+                }
+                let mut data = Vec::new();
+                crate::binary::apply(forward, old, &mut data).map_err(|source| Error::Binary {
+                    path: path.to_owned(),
+                    source,
+                })?;
+                if patch.operation != Operation::Delete && !matches_full_id(patch.new_id.as_ref(), &data) {
+                    return Err(Error::BinaryPostimageMismatch { path: path.to_owned() });
+                }
+                Change::Write(Entry { mode, data })
+            }
+            Body::BinaryWithoutData => return Err(Error::BinaryWithoutData { path: path.to_owned() }),
+        };
+
+        match (patch.operation, patch.old_path.as_ref(), patch.new_path.as_ref()) {
+            (Operation::Delete, Some(old_path), _) => {
+                if has_rejected_hunks {
+                    state.insert(old_path.clone(), change);
+                } else {
+                    if matches!(&change, Change::Write(entry) if !entry.data.is_empty()) {
+                        return Err(Error::NotEmptyAfterDeletion { path: old_path.clone() });
+                    }
+                    state.insert(old_path.clone(), Change::Remove);
+                }
+            }
+            (Operation::Rename, Some(old_path), Some(new_path)) => {
+                state.insert(old_path.clone(), Change::Remove);
+                state.insert(new_path.clone(), change);
+            }
+            (_, _, Some(new_path)) => {
+                state.insert(new_path.clone(), change);
+            }
+            (_, _, None) => unreachable!("only deletions have no new path"),
+        }
+    }
+
+    Ok(Outcome {
+        changes: state.into_iter().collect(),
+        rejected,
+    })
+}
+
+/// Merge the changes of `hunks` into `ours` using the version of the file the patch was created for, if it's available.
+fn three_way(
+    patch: &Patch,
+    hunks: &[Hunk],
+    ours: &[u8],
+    mode: EntryKind,
+    find_preimage: &mut dyn FnMut(&BStr) -> Option<Vec<u8>>,
+    options: Options,
+) -> Option<Change> {
+    let merge_options = options.three_way?;
+    if patch.operation == Operation::Add {
+        return None;
+    }
+    let base = find_preimage(patch.old_id.as_ref()?.as_ref())?;
+    let mut theirs = Vec::new();
+    if !crate::text::apply(hunks, &base, options.text, &mut theirs).is_empty() {
+        return None;
+    }
+    let mut merged = Vec::new();
+    let resolution = gix_merge::blob::merge(
+        &base,
+        ours,
+        &theirs,
+        gix_merge::blob::Labels {
+            ancestor: None,
+            current: Some("ours".into()),
+            other: Some("theirs".into()),
+        },
+        merge_options,
+        &mut merged,
+    );
+    Some(match resolution {
+        gix_merge::blob::Resolution::Complete => Change::Write(Entry { mode, data: merged }),
+        gix_merge::blob::Resolution::Conflict => Change::Conflict {
+            mode,
+            merged,
+            base,
+            ours: ours.to_vec(),
+            theirs,
+        },
+    })
+}
+
+/// Return `true` if `id` isn't a full-length hexadecimal id, or if it is the id of the blob with `data`.
+fn matches_full_id(id: Option<&BString>, data: &[u8]) -> bool {
+    let Some(id) = id.and_then(|id| gix_hash::ObjectId::from_hex(id).ok()) else {
+        return true;
+    };
+    gix_object::compute_hash(id.kind(), gix_object::Kind::Blob, data) == id
+}
+
+pub(crate) fn submodule_data(id: &gix_hash::oid) -> Vec<u8> {
+    format!("Subproject commit {id}\n").into_bytes()
+}
+
+pub(crate) fn submodule_id(data: &[u8]) -> Option<gix_hash::ObjectId> {
+    let hex = data.strip_prefix(b"Subproject commit ")?.trim_end();
+    gix_hash::ObjectId::from_hex(hex).ok()
+}
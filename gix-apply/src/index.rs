@@ -0,0 +1,142 @@
+use bstr::{BStr, BString};
+use gix_hash::ObjectId;
+use gix_index::entry::{Flags, Mode, Stage, Stat};
+use gix_object::{tree::EntryKind, FindExt};
+
+use crate::function::{submodule_data, submodule_id};
+use crate::patch::Patch;
+use crate::{Change, Entry, Options, Rejected};
+
+/// The error returned by [apply()] and [apply_changes()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Apply(#[from] crate::Error),
+    #[error(transparent)]
+    FindBlob(#[from] gix_object::find::existing_object::Error),
+    #[error("The patch to '{path}' doesn't contain a valid submodule commit")]
+    InvalidSubmodule { path: BString },
+    #[error("Could not write object")]
+    WriteObject(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+}
+
+/// The outcome of [apply()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// All hunks that couldn't be applied, if [`Options::reject`] is enabled.
+    pub rejected: Vec<Rejected>,
+    /// All paths that were merged with conflicts, if [`Options::three_way`] is enabled, and which are now
+    /// present in the index with their base, *ours* and *theirs* stages.
+    pub conflicts: Vec<BString>,
+}
+
+/// Apply `patches` to the entries of `index` without touching the worktree, similar to `git apply --cached`.
+///
+/// `objects` is used to lookup blobs, and `write` is called with the kind and the serialized data of each object
+/// that needs to be written, returning its id.
+///
+/// See [`apply()`](crate::apply()) for details on `find_preimage` and `options`.
+pub fn apply<E>(
+    index: &mut gix_index::State,
+    patches: &[Patch],
+    objects: &impl FindExt,
+    write: impl FnMut(gix_object::Kind, &[u8]) -> Result<ObjectId, E>,
+    find_preimage: impl FnMut(&BStr) -> Option<Vec<u8>>,
+    options: Options,
+) -> Result<Outcome, Error>
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+{
+    let outcome = crate::apply(patches, |path| read(index, path, objects), find_preimage, options)?;
+    apply_changes(index, &outcome.changes, write)?;
+    Ok(Outcome {
+        conflicts: outcome.conflicts().map(ToOwned::to_owned).collect(),
+        rejected: outcome.rejected,
+    })
+}
+
+/// Change the entries of `index` to match `changes` as obtained by [`apply()`](crate::apply()), writing blobs with `write`.
+///
+/// Conflicts are recorded as entries with base, *ours* and *theirs* stages.
+/// Note that the `TREE` extension is removed if there are changes, as it would be out of date otherwise.
+pub fn apply_changes<E>(
+    index: &mut gix_index::State,
+    changes: &[(BString, Change)],
+    mut write: impl FnMut(gix_object::Kind, &[u8]) -> Result<ObjectId, E>,
+) -> Result<(), Error>
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+{
+    if changes.is_empty() {
+        return Ok(());
+    }
+    index.remove_entries(|_, path, _| {
+        changes
+            .binary_search_by(|(changed_path, _)| changed_path.as_slice().cmp(path))
+            .is_ok()
+    });
+    let mut id_for = |path: &BString, mode: EntryKind, data: &[u8]| -> Result<ObjectId, Error> {
+        if mode == EntryKind::Commit {
+            submodule_id(data).ok_or_else(|| Error::InvalidSubmodule { path: path.clone() })
+        } else {
+            write(gix_object::Kind::Blob, data).map_err(|err| Error::WriteObject(err.into()))
+        }
+    };
+    for (path, change) in changes {
+        match change {
+            Change::Remove => {}
+            Change::Write(Entry { mode, data }) => {
+                let id = id_for(path, *mode, data)?;
+                index.dangerously_push_entry(
+                    Stat::default(),
+                    id,
+                    Flags::empty(),
+                    Mode::from(gix_object::tree::EntryMode::from(*mode)),
+                    path.as_ref(),
+                );
+            }
+            Change::Conflict {
+                mode,
+                base,
+                ours,
+                theirs,
+                ..
+            } => {
+                for (stage, data) in [(Stage::Base, base), (Stage::Ours, ours), (Stage::Theirs, theirs)] {
+                    let id = id_for(path, *mode, data)?;
+                    let flags = Flags::from_bits_retain((stage as u32) << 12);
+                    index.dangerously_push_entry(
+                        Stat::default(),
+                        id,
+                        flags,
+                        Mode::from(gix_object::tree::EntryMode::from(*mode)),
+                        path.as_ref(),
+                    );
+                }
+            }
+        }
+    }
+    index.sort_entries();
+    index.remove_tree();
+    Ok(())
+}
+
+fn read(index: &gix_index::State, path: &BStr, objects: &impl FindExt) -> Result<Option<Entry>, Error> {
+    let Some(entry) = index.entry_by_path_and_stage(path, Stage::Unconflicted) else {
+        return Ok(None);
+    };
+    let Some(mode) = entry.mode.to_tree_entry_mode().map(|mode| mode.kind()) else {
+        return Ok(None);
+    };
+    Ok(Some(match mode {
+        EntryKind::Commit => Entry {
+            mode,
+            data: submodule_data(&entry.id),
+        },
+        _ => Entry {
+            mode,
+            data: objects.find_blob(&entry.id, &mut Vec::new())?.data.to_vec(),
+        },
+    }))
+}
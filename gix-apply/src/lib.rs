@@ -0,0 +1,37 @@
+//! Parse patches in the unified and git formats, including binary patches, and apply them to trees, indices and worktrees
+//! similar to `git apply`.
+//!
+//! Patches are parsed with [`patch::parse()`] and applied with [`apply()`], which computes the new state of each file
+//! without changing anything. The [`tree`], [`index`] and [`worktree`] modules apply patches to these directly.
+//!
+//! Patches sent as email can be split from mailboxes and parsed into commit information with the [`mailbox`] module,
+//! which also helps writing them like `git format-patch` does.
+#![deny(rust_2018_idioms, missing_docs)]
+#![forbid(unsafe_code)]
+
+///
+pub mod patch;
+
+///
+pub mod text;
+
+///
+pub mod binary;
+
+///
+pub mod mailbox;
+
+///
+pub mod tree;
+
+///
+pub mod index;
+
+///
+pub mod worktree;
+
+mod types;
+pub use types::{Change, Entry, Error, Options, Outcome, Rejected};
+
+mod function;
+pub use function::apply;
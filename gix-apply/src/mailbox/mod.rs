@@ -0,0 +1,92 @@
+use bstr::{BString, ByteSlice};
+
+///
+pub mod parse;
+pub use parse::function::parse;
+
+///
+pub mod write;
+
+/// An email with a patch as parsed from a mailbox with [`parse()`], with everything needed to turn it into a commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mail {
+    /// The id of the commit the patch was created from, as seen in the `From <id> Mon Sep 17 00:00:00 2001` line
+    /// written by `git format-patch`.
+    pub id: Option<gix_hash::ObjectId>,
+    /// The author of the patch as taken from the `From` and `Date` headers, or from the same headers at the
+    /// beginning of the body which take precedence.
+    pub author: gix_actor::Signature,
+    /// The commit message, made from the subject with any `[PATCH]` prefixes removed and the body up to
+    /// the patch, with whitespace cleaned up just like `git am` does.
+    pub message: BString,
+    /// Everything after the commit message, starting with the `---` separator or the first diff, which
+    /// can be parsed with [`patch::parse()`](crate::patch::parse()).
+    pub patch: BString,
+}
+
+impl Mail {
+    /// Return the commit message for parsing its title, body and trailers.
+    pub fn message_ref(&self) -> gix_object::commit::MessageRef<'_> {
+        gix_object::commit::MessageRef::from_bytes(&self.message)
+    }
+}
+
+/// Return an iterator over all mails in `mbox`, each including its `From ` line, similar to `git mailsplit`.
+///
+/// If `mbox` doesn't start with a `From ` line, it's returned as single mail.
+pub fn split(mbox: &[u8]) -> Split<'_> {
+    Split { remaining: mbox }
+}
+
+/// An iterator over the mails in a mailbox, as returned by [`split()`].
+#[derive(Debug, Clone)]
+pub struct Split<'a> {
+    remaining: &'a [u8],
+}
+
+impl<'a> Iterator for Split<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining.is_empty() {
+            return None;
+        }
+        let mut end = 0;
+        for (line_index, line) in self.remaining.lines_with_terminator().enumerate() {
+            if line_index != 0 && is_from_line(line) {
+                break;
+            }
+            end += line.len();
+        }
+        let (mail, remaining) = self.remaining.split_at(end);
+        self.remaining = remaining;
+        Some(mail)
+    }
+}
+
+/// Return `true` if `line` looks like `From <anything> <date>`, which separates mails in a mailbox.
+///
+/// Just like `git`, we only check for a time and a year at the end of the line.
+pub(crate) fn is_from_line(line: &[u8]) -> bool {
+    let line = line.trim_end_with(|c| c == '\n' || c == '\r');
+    if line.len() < 19 || !line.starts_with(b"From ") {
+        return false;
+    }
+    let Some(colon) = line[5..].rfind_byte(b':').map(|pos| pos + 5) else {
+        return false;
+    };
+    let digit_at = |pos: Option<usize>| pos.and_then(|pos| line.get(pos)).map_or(false, u8::is_ascii_digit);
+    if ![colon.checked_sub(4), colon.checked_sub(2), colon.checked_sub(1)]
+        .into_iter()
+        .chain([Some(colon + 1), Some(colon + 2)])
+        .all(digit_at)
+    {
+        return false;
+    }
+    let year = line[colon + 3..].trim_start();
+    let year = &year[..year.iter().take_while(|b| b.is_ascii_digit()).count()];
+    year.to_str()
+        .ok()
+        .and_then(|year| year.parse::<u32>().ok())
+        .map_or(false, |year| year > 90)
+}
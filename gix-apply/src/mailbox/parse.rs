@@ -0,0 +1,438 @@
+use bstr::BString;
+
+/// The error returned by [parse()](crate::mailbox::parse()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The mail has no 'From' header with a valid email address")]
+    MissingAuthor,
+    #[error("The mail has no 'Date' header")]
+    MissingDate,
+    #[error("Could not parse the date {date:?}")]
+    Date {
+        date: BString,
+        source: gix_date::parse::Error,
+    },
+    #[error("Multipart mails are not supported")]
+    Multipart,
+    #[error("Could not decode the body of the mail as {encoding:?}")]
+    TransferEncoding { encoding: BString },
+}
+
+/// Options for [parse()](crate::mailbox::parse()).
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// If `true`, keep the subject as is instead of removing `Re:` and bracketed prefixes like `[PATCH 1/2]`,
+    /// similar to `git am --keep`.
+    pub keep_subject: bool,
+}
+
+pub(crate) mod function {
+    use std::borrow::Cow;
+
+    use bstr::{BStr, BString, ByteSlice, ByteVec};
+
+    use super::{Error, Options};
+    use crate::mailbox::{is_from_line, Mail};
+
+    /// Parse a single `mail`, like one of those returned by [`split()`](crate::mailbox::split()), into the author,
+    /// the commit message and the patch, similar to `git mailinfo`.
+    ///
+    /// Headers are unfolded and decoded if they use the encoding of RFC 2047, and the body is decoded if it's
+    /// sent as `quoted-printable` or `base64`. `From`, `Subject` and `Date` lines at the beginning of the body
+    /// override the headers of the mail. The message ends at the first line that looks like the beginning of a patch,
+    /// like `---` or `diff -`.
+    pub fn parse(mail: &[u8], options: Options) -> Result<Mail, Error> {
+        let mut lines = mail.lines_with_terminator().peekable();
+        let id = lines
+            .next_if(|line| is_from_line(line))
+            .and_then(|line| line[5..].split_str(" ").next())
+            .and_then(|hex| gix_hash::ObjectId::from_hex(hex).ok());
+
+        let mut headers = Headers::default();
+        let mut body_start = mail.len();
+        let mut offset = mail.len() - lines.clone().map(<[u8]>::len).sum::<usize>();
+        while let Some(line) = lines.next() {
+            let trimmed = line.trim_end_with(|c| c == '\n' || c == '\r');
+            if trimmed.is_empty() {
+                body_start = offset + line.len();
+                break;
+            }
+            if !headers.push(trimmed) {
+                body_start = offset;
+                break;
+            }
+            offset += line.len();
+            while let Some(continuation) = lines.next_if(|line| line.starts_with(b" ") || line.starts_with(b"\t")) {
+                headers.continue_last(continuation);
+                offset += continuation.len();
+            }
+        }
+
+        if headers
+            .get(b"content-type")
+            .map_or(false, |value| value.to_ascii_lowercase().starts_with(b"multipart/"))
+        {
+            return Err(Error::Multipart);
+        }
+        let body = &mail[body_start..];
+        let body: Cow<'_, [u8]> = match headers.get(b"content-transfer-encoding") {
+            Some(encoding) if encoding.eq_ignore_ascii_case(b"quoted-printable") => {
+                Cow::Owned(decode_quoted_printable(body, false))
+            }
+            Some(encoding) if encoding.eq_ignore_ascii_case(b"base64") => {
+                Cow::Owned(decode_base64(body).ok_or_else(|| Error::TransferEncoding {
+                    encoding: encoding.to_owned(),
+                })?)
+            }
+            _ => Cow::Borrowed(body),
+        };
+        let body = match headers.get(b"content-type").and_then(charset) {
+            Some(charset) => to_utf8(body, &charset),
+            None => body,
+        };
+
+        let mut body_lines = body.lines_with_terminator().peekable();
+        while body_lines.next_if(|line| line.trim().is_empty()).is_some() {}
+        let mut in_body_headers = Headers::default();
+        while let Some(line) = body_lines.peek().copied() {
+            let trimmed = line.trim_end_with(|c| c == '\n' || c == '\r');
+            let is_known_header = [&b"from:"[..], b"subject:", b"date:"].iter().any(|name| {
+                trimmed
+                    .get(..name.len())
+                    .map_or(false, |prefix| prefix.eq_ignore_ascii_case(name))
+            });
+            if is_known_header && in_body_headers.push(trimmed) {
+                body_lines.next();
+                while let Some(continuation) =
+                    body_lines.next_if(|line| line.starts_with(b" ") || line.starts_with(b"\t"))
+                {
+                    in_body_headers.continue_last(continuation);
+                }
+                continue;
+            }
+            if !in_body_headers.headers.is_empty() && trimmed.is_empty() {
+                body_lines.next();
+            }
+            break;
+        }
+
+        let mut message = BString::default();
+        let mut patch = BString::default();
+        for line in body_lines.by_ref() {
+            if is_patch_break(line) {
+                patch.push_str(line);
+                break;
+            }
+            message.push_str(line);
+        }
+        for line in body_lines {
+            patch.push_str(line);
+        }
+
+        let header = |name: &[u8]| {
+            in_body_headers
+                .get(name)
+                .or_else(|| headers.get(name))
+                .map(decode_header)
+        };
+        let (name, email) = header(b"from")
+            .and_then(|from| author(from.as_ref()))
+            .ok_or(Error::MissingAuthor)?;
+        let date = header(b"date").ok_or(Error::MissingDate)?;
+        let time = gix_date::parse(date.to_str_lossy().trim(), None).map_err(|err| Error::Date {
+            date: date.clone(),
+            source: err,
+        })?;
+        let mut subject = header(b"subject").unwrap_or_default();
+        if !options.keep_subject {
+            subject = cleanup_subject(subject.as_ref());
+        }
+
+        let mut full_message = subject;
+        full_message.push_str("\n\n");
+        full_message.push_str(&message);
+        Ok(Mail {
+            id,
+            author: gix_actor::Signature { name, email, time },
+            message: strip_space(full_message.as_ref()),
+            patch,
+        })
+    }
+
+    #[derive(Default)]
+    struct Headers {
+        headers: Vec<(BString, BString)>,
+    }
+
+    impl Headers {
+        /// Add `line` as header and return `true` if it is one.
+        fn push(&mut self, line: &[u8]) -> bool {
+            let Some((name, value)) = line.split_once_str(":") else {
+                return false;
+            };
+            if name.is_empty() || name.iter().any(|b| b.is_ascii_whitespace() || !b.is_ascii_graphic()) {
+                return false;
+            }
+            self.headers
+                .push((name.to_ascii_lowercase().into(), value.trim().as_bstr().to_owned()));
+            true
+        }
+
+        /// Unfold a `continuation` line by appending it to the last header, with its leading whitespace turned into a space.
+        fn continue_last(&mut self, continuation: &[u8]) {
+            if let Some((_, value)) = self.headers.last_mut() {
+                value.push_byte(b' ');
+                value.push_str(continuation[1..].trim_end());
+            }
+        }
+
+        fn get(&self, name: &[u8]) -> Option<&BStr> {
+            self.headers
+                .iter()
+                .find_map(|(header, value)| (header == name).then_some(value.as_bstr()))
+        }
+    }
+
+    /// Return `true` if `line` starts a patch, either with a diff header or a `---` separator.
+    fn is_patch_break(line: &[u8]) -> bool {
+        if line.starts_with(b"diff -") || line.starts_with(b"Index: ") {
+            return true;
+        }
+        match line.strip_prefix(b"---") {
+            Some(rest) if rest.len() > 1 && rest[0] == b' ' && !rest[1].is_ascii_whitespace() => true,
+            Some(rest) => {
+                let trimmed = rest.trim_start_with(|c| c.is_ascii_whitespace() && c != '\n');
+                trimmed.first() == Some(&b'\n')
+            }
+            None => false,
+        }
+    }
+
+    /// Extract the name and email from a `From` header in any of the common forms,
+    /// `Name <email>`, `"Name" <email>` or `email (Name)`.
+    fn author(from: &BStr) -> Option<(BString, BString)> {
+        let from = unquote(from);
+        let at = from.find_byte(b'@')?;
+        let mut start = at;
+        let mut angle_bracket = false;
+        while start > 0 {
+            let byte = from[start - 1];
+            if byte.is_ascii_whitespace() {
+                break;
+            }
+            if byte == b'<' {
+                angle_bracket = true;
+                break;
+            }
+            start -= 1;
+        }
+        let end = at
+            + from[at..]
+                .iter()
+                .position(|b| b.is_ascii_whitespace() || *b == b'>')
+                .unwrap_or(from.len() - at);
+        let email: BString = from[start..end].into();
+
+        let mut name = BString::from(&from[..start - usize::from(angle_bracket)]);
+        name.push_byte(b' ');
+        name.push_str(
+            from.get(end + usize::from(from.get(end) == Some(&b'>'))..)
+                .unwrap_or_default(),
+        );
+        let mut name: BString = name.fields().collect::<Vec<_>>().join(&b' ').into();
+        if name.starts_with(b"(") && name.ends_with(b")") {
+            name = name[1..name.len() - 1].into();
+        }
+        if name.is_empty() || name.len() > 60 || name.iter().any(|b| b"@<>".contains(b)) {
+            name = email.clone();
+        }
+        Some((name, email))
+    }
+
+    /// Remove the double-quotes of quoted strings along with their backslash escapes.
+    fn unquote(input: &BStr) -> Cow<'_, BStr> {
+        if !input.contains(&b'"') {
+            return Cow::Borrowed(input);
+        }
+        let mut out = BString::default();
+        let mut in_quotes = false;
+        let mut bytes = input.iter();
+        while let Some(&byte) = bytes.next() {
+            match byte {
+                b'"' => in_quotes = !in_quotes,
+                b'\\' if in_quotes => {
+                    if let Some(&escaped) = bytes.next() {
+                        out.push_byte(escaped);
+                    }
+                }
+                _ => out.push_byte(byte),
+            }
+        }
+        Cow::Owned(out)
+    }
+
+    /// Remove `Re:` and bracketed prefixes like `[PATCH 1/2]` from the beginning of `subject`.
+    fn cleanup_subject(mut subject: &BStr) -> BString {
+        loop {
+            subject = match subject.first() {
+                Some(b'r' | b'R')
+                    if subject.len() > 3 && subject[1].eq_ignore_ascii_case(&b'e') && subject[2] == b':' =>
+                {
+                    subject[3..].as_bstr()
+                }
+                Some(b' ' | b'\t' | b':') => subject[1..].as_bstr(),
+                Some(b'[') => match subject.find_byte(b']') {
+                    Some(end) => subject[end + 1..].as_bstr(),
+                    None => break,
+                },
+                _ => break,
+            };
+        }
+        subject.trim().as_bstr().to_owned()
+    }
+
+    /// Remove trailing whitespace from all lines, leading and trailing empty lines, and collapse consecutive empty lines,
+    /// making sure the result ends with a newline if it's not empty.
+    fn strip_space(message: &BStr) -> BString {
+        let mut out = BString::default();
+        let mut empty_lines = 0;
+        for line in message.lines() {
+            let line = line.trim_end();
+            if line.is_empty() {
+                empty_lines += 1;
+                continue;
+            }
+            if empty_lines > 0 && !out.is_empty() {
+                out.push_byte(b'\n');
+            }
+            empty_lines = 0;
+            out.push_str(line);
+            out.push_byte(b'\n');
+        }
+        out
+    }
+
+    /// Decode all encoded-words in a header `value`, dropping the whitespace between adjacent ones.
+    fn decode_header(value: &BStr) -> BString {
+        let mut out = BString::default();
+        let mut rest: &[u8] = value;
+        let mut previous_was_encoded = false;
+        while let Some(start) = rest.find(b"=?") {
+            let Some((decoded, consumed)) = decode_encoded_word(&rest[start..]) else {
+                out.push_str(&rest[..start + 2]);
+                rest = &rest[start + 2..];
+                previous_was_encoded = false;
+                continue;
+            };
+            let before = &rest[..start];
+            if !(previous_was_encoded && before.iter().all(u8::is_ascii_whitespace)) {
+                out.push_str(before);
+            }
+            out.push_str(decoded);
+            rest = &rest[start + consumed..];
+            previous_was_encoded = true;
+        }
+        out.push_str(rest);
+        out
+    }
+
+    /// Decode `=?charset?encoding?text?=` at the beginning of `input` and return the decoded text along with the amount
+    /// of bytes consumed.
+    fn decode_encoded_word(input: &[u8]) -> Option<(BString, usize)> {
+        let mut parts = input[2..].splitn_str(3, "?");
+        let charset = parts.next()?;
+        let encoding = parts.next()?;
+        let text_and_rest = parts.next()?;
+        let text_len = text_and_rest.find("?=")?;
+        let text = &text_and_rest[..text_len];
+        let consumed = 2 + charset.len() + 1 + encoding.len() + 1 + text_len + 2;
+        let decoded = match encoding {
+            b"q" | b"Q" => decode_quoted_printable(text, true),
+            b"b" | b"B" => decode_base64(text)?,
+            _ => return None,
+        };
+        let charset = charset.split_str("*").next().unwrap_or_default();
+        Some((to_utf8(decoded.into(), charset).into_owned().into(), consumed))
+    }
+
+    /// Decode quoted-printable `input`, with underscores meaning spaces if `is_header` is `true`.
+    fn decode_quoted_printable(input: &[u8], is_header: bool) -> Vec<u8> {
+        let mut out = Vec::with_capacity(input.len());
+        let mut pos = 0;
+        while let Some(&byte) = input.get(pos) {
+            pos += 1;
+            match byte {
+                b'_' if is_header => out.push(b' '),
+                b'=' => {
+                    let next = input.get(pos..).unwrap_or_default();
+                    if let Some(rest) = next.strip_prefix(b"\r\n").or_else(|| next.strip_prefix(b"\n")) {
+                        pos = input.len() - rest.len();
+                    } else if let Some(value) = next
+                        .get(..2)
+                        .and_then(|hex| std::str::from_utf8(hex).ok())
+                        .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                    {
+                        out.push(value);
+                        pos += 2;
+                    } else {
+                        out.push(byte);
+                    }
+                }
+                _ => out.push(byte),
+            }
+        }
+        out
+    }
+
+    /// Decode base64 `input`, ignoring whitespace, or return `None` if it's invalid.
+    fn decode_base64(input: &[u8]) -> Option<Vec<u8>> {
+        let value = |byte: u8| -> Option<u32> {
+            Some(match byte {
+                b'A'..=b'Z' => byte - b'A',
+                b'a'..=b'z' => byte - b'a' + 26,
+                b'0'..=b'9' => byte - b'0' + 52,
+                b'+' => 62,
+                b'/' => 63,
+                _ => return None,
+            } as u32)
+        };
+        let mut out = Vec::with_capacity(input.len() / 4 * 3);
+        let (mut acc, mut bits) = (0u32, 0);
+        for &byte in input.iter().filter(|b| !b.is_ascii_whitespace()) {
+            if byte == b'=' {
+                break;
+            }
+            acc = (acc << 6) | value(byte)?;
+            bits += 6;
+            if bits >= 8 {
+                bits -= 8;
+                out.push((acc >> bits) as u8);
+                acc &= (1 << bits) - 1;
+            }
+        }
+        Some(out)
+    }
+
+    /// Extract the `charset` parameter from the value of a `Content-Type` header.
+    fn charset(content_type: &BStr) -> Option<BString> {
+        content_type.split_str(";").skip(1).find_map(|parameter| {
+            let (name, value) = parameter.split_once_str("=")?;
+            name.trim()
+                .eq_ignore_ascii_case(b"charset")
+                .then(|| value.trim().trim_with(|c| c == '"').as_bstr().to_owned())
+        })
+    }
+
+    /// Convert `data` in `charset` to UTF-8, which is only done for Latin-1 as all other charsets are assumed
+    /// to be compatible with UTF-8 already.
+    fn to_utf8<'a>(data: Cow<'a, [u8]>, charset: &[u8]) -> Cow<'a, [u8]> {
+        let is_latin1 = [&b"iso-8859-1"[..], b"latin1", b"latin-1"]
+            .iter()
+            .any(|name| charset.eq_ignore_ascii_case(name));
+        if !is_latin1 || data.is_ascii() {
+            return data;
+        }
+        Cow::Owned(data.iter().map(|&b| char::from(b)).collect::<String>().into_bytes())
+    }
+}
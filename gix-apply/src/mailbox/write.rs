@@ -0,0 +1,229 @@
+use std::io::Write;
+
+use bstr::{BStr, ByteSlice};
+
+/// The maximum width of lines in mail headers.
+const MAX_HEADER_WIDTH: usize = 78;
+/// The maximum width of lines that contain encoded words.
+const MAX_ENCODED_WIDTH: usize = 76;
+
+/// The information needed to [write the headers](header()) of a mail that contains a patch.
+#[derive(Debug, Copy, Clone)]
+pub struct Header<'a> {
+    /// The id of the commit the patch was created from, or the tip of the series for a cover letter.
+    pub id: &'a gix_hash::oid,
+    /// The author of the patch, used for the `From` and `Date` headers.
+    pub from: gix_actor::SignatureRef<'a>,
+    /// The text in brackets that precedes the subject, like `PATCH 1/2`, or empty to have no brackets at all.
+    pub subject_prefix: &'a BStr,
+    /// The subject, which is the title of the commit message with its lines joined by spaces.
+    pub subject: &'a BStr,
+    /// If `true`, the body contains non-ASCII characters and needs `MIME-Version` and `Content-Type` headers
+    /// to declare it as UTF-8.
+    pub utf8_body: bool,
+}
+
+/// Write the headers of a mail described by `header` to `out` just like `git format-patch` would, starting with the
+/// `From <id> Mon Sep 17 00:00:00 2001` line that separates mails in a mailbox, and followed by an empty line.
+///
+/// The name of the author and the subject are encoded according to RFC 2047 if they contain non-ASCII characters,
+/// and wrapped to fit the line length limits of emails otherwise.
+pub fn header(out: &mut dyn Write, header: &Header<'_>) -> std::io::Result<()> {
+    let mut buf = Vec::new();
+    writeln!(buf, "From {} Mon Sep 17 00:00:00 2001", header.id)?;
+
+    buf.extend_from_slice(b"From: ");
+    let name = header.from.name;
+    let mut max_width = MAX_HEADER_WIDTH;
+    if needs_rfc2047_encoding(name) {
+        add_rfc2047(&mut buf, name, true);
+        max_width = MAX_ENCODED_WIDTH;
+    } else if name.iter().any(|b| b"()<>[]:;@,.\"\\".contains(b)) {
+        let mut quoted = vec![b'"'];
+        for &byte in name.iter() {
+            if byte == b'"' || byte == b'\\' {
+                quoted.push(b'\\');
+            }
+            quoted.push(byte);
+        }
+        quoted.push(b'"');
+        wrapped_text(&mut buf, quoted.as_bstr(), -6, 1, MAX_HEADER_WIDTH);
+    } else {
+        wrapped_text(&mut buf, name, -6, 1, MAX_HEADER_WIDTH);
+    }
+    if max_width < last_line_width(&buf) + " <".len() + header.from.email.len() + ">".len() {
+        buf.push(b'\n');
+    }
+    buf.extend_from_slice(b" <");
+    buf.extend_from_slice(header.from.email);
+    buf.extend_from_slice(b">\n");
+    writeln!(
+        buf,
+        "Date: {}",
+        header.from.time.format(gix_date::time::format::GIT_RFC2822)
+    )?;
+
+    buf.extend_from_slice(b"Subject: ");
+    if !header.subject_prefix.is_empty() {
+        buf.push(b'[');
+        buf.extend_from_slice(header.subject_prefix);
+        buf.extend_from_slice(b"] ");
+    }
+    if needs_rfc2047_encoding(header.subject) {
+        add_rfc2047(&mut buf, header.subject, false);
+    } else {
+        let indent = last_line_width(&buf) as isize;
+        wrapped_text(&mut buf, header.subject, -indent, 1, MAX_HEADER_WIDTH);
+    }
+    buf.push(b'\n');
+    if header.utf8_body {
+        buf.extend_from_slice(
+            b"MIME-Version: 1.0\nContent-Type: text/plain; charset=UTF-8\nContent-Transfer-Encoding: 8bit\n",
+        );
+    }
+    buf.push(b'\n');
+    out.write_all(&buf)
+}
+
+/// Return the name of the file for the patch with the given `number` and `subject`, like `0001-fix-the-bug.patch`,
+/// just like `git format-patch` would name it.
+pub fn file_name(number: usize, subject: &BStr) -> String {
+    const MAX_LEN: usize = 64 - ".patch".len() - 1;
+    let mut name = format!("{number:04}-");
+    let start = name.len();
+    let mut needs_dash = false;
+    let mut bytes = subject.iter().peekable();
+    while let Some(&byte) = bytes.next() {
+        if byte.is_ascii_alphanumeric() || byte == b'.' || byte == b'_' {
+            if needs_dash && name.len() > start {
+                name.push('-');
+            }
+            needs_dash = false;
+            name.push(char::from(byte));
+            if byte == b'.' {
+                while bytes.next_if(|b| **b == b'.').is_some() {}
+            }
+        } else {
+            needs_dash = true;
+        }
+    }
+    let trimmed_len = start + name[start..].trim_end_matches(['.', '-']).len();
+    name.truncate(trimmed_len.min(MAX_LEN));
+    name.push_str(".patch");
+    name
+}
+
+/// Append `text` to `out`, wrapped at whitespace so lines don't exceed `width` if possible, with each line indented
+/// by `indent` spaces, except for the first one which is indented by `first_indent`.
+///
+/// If `first_indent` is negative, the first line isn't indented, but is assumed to already have the given amount
+/// of characters. Newlines followed by alphanumeric characters are treated as spaces, similar to `git`'s wrapping
+/// of subjects and shortlogs.
+pub fn wrapped_text(out: &mut Vec<u8>, text: &BStr, first_indent: isize, indent: usize, width: usize) {
+    let at = |pos: usize| text.get(pos).copied();
+    let mut line_start = 0;
+    let mut pos = 0;
+    let mut current_indent = first_indent.max(0) as usize;
+    let mut line_width = first_indent.unsigned_abs();
+    let mut space = (first_indent < 0).then_some(0);
+    loop {
+        let byte = at(pos);
+        if byte.map_or(true, |b| b.is_ascii_whitespace()) {
+            let mut new_line = false;
+            if line_width <= width || space.is_none() {
+                if byte.is_none() && pos == line_start {
+                    return;
+                }
+                let start = match space {
+                    Some(space) => space,
+                    None => {
+                        out.extend(std::iter::repeat(b' ').take(current_indent));
+                        line_start
+                    }
+                };
+                out.extend_from_slice(&text[start..pos]);
+                let Some(byte) = byte else {
+                    return;
+                };
+                space = Some(pos);
+                if byte == b'\t' {
+                    line_width |= 0x07;
+                } else if byte == b'\n' {
+                    space = Some(pos + 1);
+                    match at(pos + 1) {
+                        Some(b'\n') => {
+                            out.push(b'\n');
+                            new_line = true;
+                        }
+                        Some(next) if next.is_ascii_alphanumeric() => out.push(b' '),
+                        _ => new_line = true,
+                    }
+                }
+                if !new_line {
+                    line_width += 1;
+                    pos += 1;
+                }
+            } else {
+                new_line = true;
+            }
+            if new_line {
+                out.push(b'\n');
+                let space = space.take().expect("set when wrapping");
+                pos = space + usize::from(at(space).map_or(false, |b| b.is_ascii_whitespace()));
+                line_start = pos;
+                line_width = indent;
+                current_indent = indent;
+            }
+            continue;
+        }
+        line_width += 1;
+        pos += text[pos..].char_indices().next().map_or(1, |(_, end, _)| end);
+    }
+}
+
+fn last_line_width(buf: &[u8]) -> usize {
+    buf.len() - buf.rfind_byte(b'\n').map_or(0, |pos| pos + 1)
+}
+
+fn needs_rfc2047_encoding(text: &BStr) -> bool {
+    text.iter().any(|&b| !b.is_ascii() || b == 0x1b || b == b'\n') || text.contains_str("=?")
+}
+
+/// Append `text` to `buf` as encoded-words using the `Q` encoding, folding lines so they don't exceed the
+/// length limit of RFC 2047.
+///
+/// If `is_address` is `true`, the text is used within an address and more characters have to be encoded.
+fn add_rfc2047(buf: &mut Vec<u8>, text: &BStr, is_address: bool) {
+    const START: &[u8] = b"=?UTF-8?q?";
+    let mut line_width = last_line_width(buf) + START.len();
+    buf.extend_from_slice(START);
+    for (start, end, _) in text.char_indices() {
+        let char_bytes = &text[start..end];
+        let is_special = char_bytes.len() > 1 || is_rfc2047_special(char_bytes[0], is_address);
+        let width = if is_special { 3 * char_bytes.len() } else { 1 };
+        if line_width + 2 + width > MAX_ENCODED_WIDTH {
+            buf.extend_from_slice(b"?=\n ");
+            buf.extend_from_slice(START);
+            line_width = START.len() + 1;
+        }
+        if is_special {
+            for byte in char_bytes.iter() {
+                write!(buf, "={byte:02X}").expect("writing to a vec never fails");
+            }
+        } else {
+            buf.push(char_bytes[0]);
+        }
+        line_width += width;
+    }
+    buf.extend_from_slice(b"?=");
+}
+
+fn is_rfc2047_special(byte: u8, is_address: bool) -> bool {
+    if !byte.is_ascii() || !(byte.is_ascii_graphic() || byte == b' ') {
+        return true;
+    }
+    if byte.is_ascii_whitespace() || byte == b'=' || byte == b'?' || byte == b'_' {
+        return true;
+    }
+    is_address && !(byte.is_ascii_alphanumeric() || b"!*+-/".contains(&byte))
+}
@@ -0,0 +1,241 @@
+use bstr::{BStr, BString};
+use gix_object::tree::EntryKind;
+
+///
+pub mod parse;
+pub use parse::function::parse;
+
+/// What a [`Patch`] does to the file it refers to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Operation {
+    /// The file at [`Patch::old_path`] is changed in place.
+    Modify,
+    /// The file at [`Patch::new_path`] is created.
+    Add,
+    /// The file at [`Patch::old_path`] is deleted.
+    Delete,
+    /// The file at [`Patch::old_path`] is moved to [`Patch::new_path`], possibly with changes.
+    Rename,
+    /// The file at [`Patch::old_path`] is copied to [`Patch::new_path`], possibly with changes.
+    Copy,
+}
+
+/// The kind of a [`Line`] in a [`Hunk`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum LineKind {
+    /// A line that is the same before and after, prefixed with ` `.
+    Context,
+    /// A line that only exists before, prefixed with `-`.
+    Removal,
+    /// A line that only exists after, prefixed with `+`.
+    Addition,
+}
+
+/// A single line of a [`Hunk`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Line {
+    /// The kind of line.
+    pub kind: LineKind,
+    /// The content of the line without its prefix, but with its line terminator unless it was marked
+    /// with `\ No newline at end of file`.
+    pub content: BString,
+}
+
+/// A hunk of a text patch, introduced with `@@ -old_start,old_lines +new_start,new_lines @@`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Hunk {
+    /// The 1-based line at which the hunk starts in the old file, or 0 if it is empty.
+    pub old_start: u32,
+    /// The amount of context and removed lines.
+    pub old_lines: u32,
+    /// The 1-based line at which the hunk starts in the new file, or 0 if it is empty.
+    pub new_start: u32,
+    /// The amount of context and added lines.
+    pub new_lines: u32,
+    /// The text after the closing `@@`, typically the name of the enclosing function, without leading space.
+    pub section: BString,
+    /// All lines of the hunk in order.
+    pub lines: Vec<Line>,
+}
+
+/// The way the data of a [`BinaryHunk`] is encoded.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum BinaryMethod {
+    /// The data is the complete new content.
+    Literal,
+    /// The data is a delta to apply to the old content, in the same format that is used in packs.
+    Delta,
+}
+
+/// A decoded hunk of a `GIT binary patch`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BinaryHunk {
+    /// The way to interpret `data`.
+    pub method: BinaryMethod,
+    /// The decoded and decompressed data.
+    pub data: Vec<u8>,
+}
+
+/// The changes a [`Patch`] makes to the content of its file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Body {
+    /// There are no content changes, as is the case for pure renames, copies and mode changes.
+    Empty,
+    /// Line-based changes.
+    Text(Vec<Hunk>),
+    /// A `GIT binary patch` with the hunk to turn the old content into the new one, and possibly the
+    /// hunk to go the other way.
+    Binary {
+        /// The hunk to produce the new content from the old content.
+        forward: BinaryHunk,
+        /// The hunk to produce the old content from the new content, which is always present in patches written by git.
+        reverse: Option<BinaryHunk>,
+    },
+    /// A binary file changed, but the patch doesn't contain the data needed to apply it, as indicated by
+    /// `Binary files a/… and b/… differ`.
+    BinaryWithoutData,
+}
+
+/// A patch to a single file, as parsed from unified or git-style diffs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Patch {
+    /// What the patch does to its file.
+    pub operation: Operation,
+    /// The path of the file before the change, or `None` if it's [added](Operation::Add).
+    pub old_path: Option<BString>,
+    /// The path of the file after the change, or `None` if it's [deleted](Operation::Delete).
+    pub new_path: Option<BString>,
+    /// The kind of file before the change, if known.
+    pub old_mode: Option<EntryKind>,
+    /// The kind of file after the change, if known. If `None`, it stays what it was.
+    pub new_mode: Option<EntryKind>,
+    /// The possibly abbreviated hexadecimal id of the blob before the change, as seen in the `index` line.
+    pub old_id: Option<BString>,
+    /// The possibly abbreviated hexadecimal id of the blob after the change, as seen in the `index` line.
+    pub new_id: Option<BString>,
+    /// The similarity in percent between source and destination of renames and copies.
+    pub similarity: Option<u8>,
+    /// The changes to the content.
+    pub body: Body,
+}
+
+impl Patch {
+    /// The path of the file the patch reads from, which is the path it writes to if it's not [added](Operation::Add).
+    pub fn source_path(&self) -> Option<&BStr> {
+        self.old_path.as_ref().map(AsRef::as_ref)
+    }
+
+    /// The path of the file that results from the patch, or the one that is deleted if it is a [deletion](Operation::Delete).
+    pub fn path(&self) -> &BStr {
+        self.new_path
+            .as_ref()
+            .or(self.old_path.as_ref())
+            .expect("parser assures at least one path is set")
+            .as_ref()
+    }
+
+    /// Return a patch that undoes this one, like `git apply --reverse` would apply it.
+    ///
+    /// Note that binary patches can only be reversed if they contain a reverse hunk.
+    pub fn reversed(&self) -> Patch {
+        let operation = match self.operation {
+            Operation::Add => Operation::Delete,
+            Operation::Delete => Operation::Add,
+            Operation::Modify | Operation::Rename | Operation::Copy => self.operation,
+        };
+        let body = match &self.body {
+            Body::Empty => Body::Empty,
+            Body::BinaryWithoutData => Body::BinaryWithoutData,
+            Body::Text(hunks) => Body::Text(
+                hunks
+                    .iter()
+                    .map(|hunk| Hunk {
+                        old_start: hunk.new_start,
+                        old_lines: hunk.new_lines,
+                        new_start: hunk.old_start,
+                        new_lines: hunk.old_lines,
+                        section: hunk.section.clone(),
+                        lines: hunk
+                            .lines
+                            .iter()
+                            .map(|line| Line {
+                                kind: match line.kind {
+                                    LineKind::Context => LineKind::Context,
+                                    LineKind::Removal => LineKind::Addition,
+                                    LineKind::Addition => LineKind::Removal,
+                                },
+                                content: line.content.clone(),
+                            })
+                            .collect(),
+                    })
+                    .collect(),
+            ),
+            Body::Binary { forward, reverse } => match reverse {
+                Some(reverse) => Body::Binary {
+                    forward: reverse.clone(),
+                    reverse: Some(forward.clone()),
+                },
+                None => Body::BinaryWithoutData,
+            },
+        };
+        let (old_mode, new_mode) = match self.operation {
+            Operation::Add => (self.new_mode, None),
+            Operation::Delete => (None, self.old_mode),
+            Operation::Modify | Operation::Rename | Operation::Copy => match self.new_mode {
+                Some(new_mode) => (Some(new_mode), self.old_mode),
+                None => (self.old_mode, None),
+            },
+        };
+        Patch {
+            operation,
+            old_path: self.new_path.clone(),
+            new_path: self.old_path.clone(),
+            old_mode,
+            new_mode,
+            old_id: self.new_id.clone(),
+            new_id: self.old_id.clone(),
+            similarity: self.similarity,
+            body,
+        }
+    }
+}
+
+impl Hunk {
+    /// Write this hunk as it would appear in a patch, including its header, to `out`.
+    pub fn write_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        write!(
+            out,
+            "@@ -{} +{} @@",
+            Range(self.old_start, self.old_lines),
+            Range(self.new_start, self.new_lines)
+        )?;
+        if !self.section.is_empty() {
+            out.write_all(b" ")?;
+            out.write_all(&self.section)?;
+        }
+        out.write_all(b"\n")?;
+        for line in &self.lines {
+            out.write_all(match line.kind {
+                LineKind::Context => b" ",
+                LineKind::Removal => b"-",
+                LineKind::Addition => b"+",
+            })?;
+            out.write_all(&line.content)?;
+            if !line.content.ends_with(b"\n") {
+                out.write_all(b"\n\\ No newline at end of file\n")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+struct Range(u32, u32);
+
+impl std::fmt::Display for Range {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.1 {
+            1 => write!(f, "{}", self.0),
+            lines => write!(f, "{},{}", self.0, lines),
+        }
+    }
+}
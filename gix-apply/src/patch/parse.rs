@@ -0,0 +1,416 @@
+use bstr::BString;
+
+/// The error returned by [parse()](crate::patch::parse()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Line {line}: invalid hunk header: {header:?}")]
+    HunkHeader { line: usize, header: BString },
+    #[error("Line {line}: the hunk ended before all of its lines were seen")]
+    TruncatedHunk { line: usize },
+    #[error("Line {line}: invalid file mode: {mode:?}")]
+    Mode { line: usize, mode: BString },
+    #[error("Line {line}: could not unquote path")]
+    Unquote {
+        line: usize,
+        source: gix_quote::ansi_c::undo::Error,
+    },
+    #[error("Line {line}: could not determine the path of the file the patch applies to")]
+    MissingPath { line: usize },
+    #[error("Line {line}: could not decode binary patch")]
+    Binary {
+        line: usize,
+        source: crate::binary::decode::Error,
+    },
+}
+
+/// Options for [parse()](crate::patch::parse()).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The amount of leading path components to remove from the paths in the `diff --git`, `---` and `+++` lines,
+    /// similar to `git apply -p`. Defaults to 1 to remove the `a/` and `b/` prefixes.
+    pub strip: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options { strip: 1 }
+    }
+}
+
+pub(crate) mod function {
+    use std::borrow::Cow;
+
+    use bstr::{BStr, BString, ByteSlice};
+    use gix_object::tree::EntryKind;
+
+    use super::{Error, Options};
+    use crate::patch::{BinaryHunk, BinaryMethod, Body, Hunk, Line, LineKind, Operation, Patch};
+
+    /// Parse all patches in `input`, which may be the output of `git diff`, `git format-patch` or `diff -u`, and return them
+    /// in order of appearance.
+    ///
+    /// Everything that isn't part of a patch, like email headers, commit messages and diff statistics, is ignored.
+    /// Paths are adjusted according to `options`.
+    pub fn parse(input: &[u8], options: Options) -> Result<Vec<Patch>, Error> {
+        let lines: Vec<&[u8]> = input.lines_with_terminator().collect();
+        let mut out = Vec::new();
+        let mut cursor = 0;
+        while let Some(line) = lines.get(cursor) {
+            if line.starts_with(b"diff --git ") {
+                let (patch, next) = git_patch(&lines, cursor, options)?;
+                out.push(patch);
+                cursor = next;
+            } else if line.starts_with(b"--- ")
+                && lines.get(cursor + 1).map_or(false, |l| l.starts_with(b"+++ "))
+                && lines.get(cursor + 2).map_or(false, |l| l.starts_with(b"@@ -"))
+            {
+                let (patch, next) = traditional_patch(&lines, cursor, options)?;
+                out.push(patch);
+                cursor = next;
+            } else {
+                cursor += 1;
+            }
+        }
+        Ok(out)
+    }
+
+    fn git_patch(lines: &[&[u8]], start: usize, options: Options) -> Result<(Patch, usize), Error> {
+        let header = trim_newline(&lines[start][b"diff --git ".len()..]);
+        let (mut old_path, mut new_path) = match git_header_paths(header, start + 1)? {
+            Some((old, new)) => (strip(old.as_ref(), options.strip), strip(new.as_ref(), options.strip)),
+            None => (None, None),
+        };
+        let mut patch = Patch {
+            operation: Operation::Modify,
+            old_path: None,
+            new_path: None,
+            old_mode: None,
+            new_mode: None,
+            old_id: None,
+            new_id: None,
+            similarity: None,
+            body: Body::Empty,
+        };
+
+        let mut cursor = start + 1;
+        while let Some(line) = lines.get(cursor) {
+            let line_number = cursor + 1;
+            let line = trim_newline(line);
+            if let Some(mode) = line.strip_prefix(b"old mode ") {
+                patch.old_mode = Some(parse_mode(mode, line_number)?);
+            } else if let Some(mode) = line.strip_prefix(b"new mode ") {
+                patch.new_mode = Some(parse_mode(mode, line_number)?);
+            } else if let Some(mode) = line.strip_prefix(b"deleted file mode ") {
+                patch.operation = Operation::Delete;
+                patch.old_mode = Some(parse_mode(mode, line_number)?);
+            } else if let Some(mode) = line.strip_prefix(b"new file mode ") {
+                patch.operation = Operation::Add;
+                patch.new_mode = Some(parse_mode(mode, line_number)?);
+            } else if let Some(path) = line.strip_prefix(b"rename from ") {
+                patch.operation = Operation::Rename;
+                old_path = Some(unquote(path, line_number)?);
+            } else if let Some(path) = line.strip_prefix(b"rename to ") {
+                patch.operation = Operation::Rename;
+                new_path = Some(unquote(path, line_number)?);
+            } else if let Some(path) = line.strip_prefix(b"copy from ") {
+                patch.operation = Operation::Copy;
+                old_path = Some(unquote(path, line_number)?);
+            } else if let Some(path) = line.strip_prefix(b"copy to ") {
+                patch.operation = Operation::Copy;
+                new_path = Some(unquote(path, line_number)?);
+            } else if let Some(score) = line.strip_prefix(b"similarity index ") {
+                patch.similarity = score
+                    .strip_suffix(b"%")
+                    .and_then(|score| score.to_str().ok())
+                    .and_then(|score| score.parse().ok());
+            } else if line.starts_with(b"dissimilarity index ") {
+            } else if let Some(ids) = line.strip_prefix(b"index ") {
+                let (ids, mode) = match ids.split_once_str(" ") {
+                    Some((ids, mode)) => (ids, Some(parse_mode(mode, line_number)?)),
+                    None => (ids, None),
+                };
+                if let Some((old, new)) = ids.split_once_str("..") {
+                    patch.old_id = Some(old.into());
+                    patch.new_id = Some(new.into());
+                }
+                if let Some(mode) = mode {
+                    patch.old_mode.get_or_insert(mode);
+                    patch.new_mode.get_or_insert(mode);
+                }
+            } else if let Some(path) = line.strip_prefix(b"--- ") {
+                old_path = traditional_path(path, options.strip, line_number)?;
+            } else if let Some(path) = line.strip_prefix(b"+++ ") {
+                new_path = traditional_path(path, options.strip, line_number)?;
+            } else if line.starts_with(b"@@ -") {
+                let (hunks, next) = hunks(lines, cursor)?;
+                patch.body = Body::Text(hunks);
+                cursor = next;
+                break;
+            } else if line == b"GIT binary patch" {
+                let (body, next) = binary(lines, cursor + 1)?;
+                patch.body = body;
+                cursor = next;
+                break;
+            } else if line.starts_with(b"Binary files ") {
+                patch.body = Body::BinaryWithoutData;
+                cursor += 1;
+                break;
+            } else {
+                break;
+            }
+            cursor += 1;
+        }
+
+        match patch.operation {
+            Operation::Add => new_path = new_path.or(old_path.take()),
+            Operation::Delete => old_path = old_path.or(new_path.take()),
+            Operation::Modify | Operation::Rename | Operation::Copy => {
+                if old_path.is_none() || new_path.is_none() {
+                    return Err(Error::MissingPath { line: start + 1 });
+                }
+            }
+        }
+        patch.old_path = old_path.filter(|_| patch.operation != Operation::Add);
+        patch.new_path = new_path.filter(|_| patch.operation != Operation::Delete);
+        if patch.old_path.is_none() && patch.new_path.is_none() {
+            return Err(Error::MissingPath { line: start + 1 });
+        }
+        Ok((patch, cursor))
+    }
+
+    fn traditional_patch(lines: &[&[u8]], start: usize, options: Options) -> Result<(Patch, usize), Error> {
+        let old_path = traditional_path(&trim_newline(lines[start])[4..], options.strip, start + 1)?;
+        let new_path = traditional_path(&trim_newline(lines[start + 1])[4..], options.strip, start + 2)?;
+        let (operation, old_path, new_path) = match (old_path, new_path) {
+            (None, Some(new)) => (Operation::Add, None, Some(new)),
+            (Some(old), None) => (Operation::Delete, Some(old), None),
+            (Some(_), Some(new)) => (Operation::Modify, Some(new.clone()), Some(new)),
+            (None, None) => return Err(Error::MissingPath { line: start + 1 }),
+        };
+        let (hunks, next) = hunks(lines, start + 2)?;
+        Ok((
+            Patch {
+                operation,
+                old_path,
+                new_path,
+                old_mode: None,
+                new_mode: None,
+                old_id: None,
+                new_id: None,
+                similarity: None,
+                body: Body::Text(hunks),
+            },
+            next,
+        ))
+    }
+
+    fn hunks(lines: &[&[u8]], mut cursor: usize) -> Result<(Vec<Hunk>, usize), Error> {
+        let mut out = Vec::new();
+        while let Some(header) = lines.get(cursor).filter(|line| line.starts_with(b"@@ -")) {
+            let mut hunk = hunk_header(trim_newline(header)).ok_or_else(|| Error::HunkHeader {
+                line: cursor + 1,
+                header: trim_newline(header).into(),
+            })?;
+            cursor += 1;
+            let (mut old, mut new) = (hunk.old_lines, hunk.new_lines);
+            while old > 0 || new > 0 {
+                let line = lines.get(cursor).ok_or(Error::TruncatedHunk { line: cursor + 1 })?;
+                let (kind, content) = match line.first() {
+                    Some(b' ') => (LineKind::Context, &line[1..]),
+                    Some(b'\n' | b'\r') => (LineKind::Context, &line[..]),
+                    Some(b'-') => (LineKind::Removal, &line[1..]),
+                    Some(b'+') => (LineKind::Addition, &line[1..]),
+                    Some(b'\\') => {
+                        remove_line_terminator(&mut hunk.lines);
+                        cursor += 1;
+                        continue;
+                    }
+                    _ => return Err(Error::TruncatedHunk { line: cursor + 1 }),
+                };
+                match kind {
+                    LineKind::Context if old > 0 && new > 0 => {
+                        old -= 1;
+                        new -= 1;
+                    }
+                    LineKind::Removal if old > 0 => old -= 1,
+                    LineKind::Addition if new > 0 => new -= 1,
+                    _ => return Err(Error::TruncatedHunk { line: cursor + 1 }),
+                }
+                let mut content = BString::from(content);
+                if cursor + 1 == lines.len() && !content.ends_with(b"\n") {
+                    content.push(b'\n');
+                }
+                hunk.lines.push(Line { kind, content });
+                cursor += 1;
+            }
+            if lines.get(cursor).map_or(false, |line| line.starts_with(b"\\")) {
+                remove_line_terminator(&mut hunk.lines);
+                cursor += 1;
+            }
+            out.push(hunk);
+        }
+        Ok((out, cursor))
+    }
+
+    fn remove_line_terminator(lines: &mut [Line]) {
+        if let Some(line) = lines.last_mut() {
+            let len = line.content.len();
+            if line.content.ends_with(b"\r\n") {
+                line.content.truncate(len - 2);
+            } else if line.content.ends_with(b"\n") {
+                line.content.pop();
+            }
+        }
+    }
+
+    fn hunk_header(line: &[u8]) -> Option<Hunk> {
+        let rest = line.strip_prefix(b"@@ -")?;
+        let (old, rest) = rest.split_once_str(" +")?;
+        let (new, rest) = rest.split_once_str(" @@")?;
+        let (old_start, old_lines) = range(old)?;
+        let (new_start, new_lines) = range(new)?;
+        Some(Hunk {
+            old_start,
+            old_lines,
+            new_start,
+            new_lines,
+            section: rest.strip_prefix(b" ").unwrap_or(rest).into(),
+            lines: Vec::new(),
+        })
+    }
+
+    fn range(range: &[u8]) -> Option<(u32, u32)> {
+        let number = |n: &[u8]| n.to_str().ok()?.parse().ok();
+        match range.split_once_str(",") {
+            Some((start, lines)) => Some((number(start)?, number(lines)?)),
+            None => Some((number(range)?, 1)),
+        }
+    }
+
+    fn binary(lines: &[&[u8]], mut cursor: usize) -> Result<(Body, usize), Error> {
+        let forward = binary_hunk(lines, &mut cursor)?.ok_or(Error::Binary {
+            line: cursor + 1,
+            source: crate::binary::decode::Error::MissingHunk,
+        })?;
+        let reverse = binary_hunk(lines, &mut cursor)?;
+        Ok((Body::Binary { forward, reverse }, cursor))
+    }
+
+    fn binary_hunk(lines: &[&[u8]], cursor: &mut usize) -> Result<Option<BinaryHunk>, Error> {
+        let Some(header) = lines.get(*cursor).map(|line| trim_newline(line)) else {
+            return Ok(None);
+        };
+        let (method, size) = if let Some(size) = header.strip_prefix(b"literal ") {
+            (BinaryMethod::Literal, size)
+        } else if let Some(size) = header.strip_prefix(b"delta ") {
+            (BinaryMethod::Delta, size)
+        } else {
+            return Ok(None);
+        };
+        let header_line = *cursor + 1;
+        let size: usize = size
+            .to_str()
+            .ok()
+            .and_then(|size| size.parse().ok())
+            .ok_or(Error::Binary {
+                line: header_line,
+                source: crate::binary::decode::Error::Size,
+            })?;
+        *cursor += 1;
+
+        let mut compressed = Vec::new();
+        while let Some(line) = lines.get(*cursor).map(|line| trim_newline(line)) {
+            *cursor += 1;
+            if line.is_empty() {
+                break;
+            }
+            crate::binary::decode::base85_line(line, &mut compressed)
+                .map_err(|source| Error::Binary { line: *cursor, source })?;
+        }
+        let data = crate::binary::decode::inflate(&compressed, size).map_err(|source| Error::Binary {
+            line: header_line,
+            source,
+        })?;
+        Ok(Some(BinaryHunk { method, data }))
+    }
+
+    fn parse_mode(mode: &[u8], line: usize) -> Result<EntryKind, Error> {
+        let err = || Error::Mode {
+            line,
+            mode: mode.into(),
+        };
+        let mode = u32::from_str_radix(mode.trim().to_str().map_err(|_| err())?, 8).map_err(|_| err())?;
+        Ok(match mode & 0o170_000 {
+            0o120_000 => EntryKind::Link,
+            0o160_000 => EntryKind::Commit,
+            0o100_000 if mode & 0o111 != 0 => EntryKind::BlobExecutable,
+            0o100_000 => EntryKind::Blob,
+            _ => return Err(err()),
+        })
+    }
+
+    /// Parse the paths in the `diff --git` line, which are ambiguous if they aren't quoted and contain spaces.
+    /// In that case, the paths are expected to be the same, which is true unless there is a rename or a copy,
+    /// in which case the paths will be provided by extended header lines.
+    fn git_header_paths(header: &[u8], line: usize) -> Result<Option<(BString, BString)>, Error> {
+        if header.starts_with(b"\"") {
+            let (old, consumed) =
+                gix_quote::ansi_c::undo(header.as_bstr()).map_err(|source| Error::Unquote { line, source })?;
+            let rest = header[consumed..].trim_start();
+            let new = unquote(rest, line)?;
+            return Ok(Some((old.into_owned(), new)));
+        }
+        if let Some(pos) = header.find(b" \"") {
+            let new = unquote(&header[pos + 1..], line)?;
+            return Ok(Some((header[..pos].into(), new)));
+        }
+        let strip_first = |path: &[u8]| path.find_byte(b'/').map(|pos| path[pos + 1..].to_owned());
+        for pos in header.find_iter(b" ") {
+            let (old, new) = (&header[..pos], &header[pos + 1..]);
+            if strip_first(old).is_some() && strip_first(old) == strip_first(new) {
+                return Ok(Some((old.into(), new.into())));
+            }
+        }
+        Ok(None)
+    }
+
+    fn traditional_path(path: &[u8], strip_count: usize, line: usize) -> Result<Option<BString>, Error> {
+        let path = if path.starts_with(b"\"") {
+            Cow::Owned(unquote(path, line)?)
+        } else {
+            let path = path.split_str("\t").next().unwrap_or(path).trim_end();
+            Cow::Borrowed(path.as_bstr())
+        };
+        if path.as_ref() == "/dev/null" {
+            return Ok(None);
+        }
+        strip(path.as_ref(), strip_count)
+            .map(Some)
+            .ok_or(Error::MissingPath { line })
+    }
+
+    fn unquote(path: &[u8], line: usize) -> Result<BString, Error> {
+        if path.starts_with(b"\"") {
+            Ok(gix_quote::ansi_c::undo(path.as_bstr())
+                .map_err(|source| Error::Unquote { line, source })?
+                .0
+                .into_owned())
+        } else {
+            Ok(path.into())
+        }
+    }
+
+    fn strip(path: &BStr, count: usize) -> Option<BString> {
+        let mut path: &[u8] = path;
+        for _ in 0..count {
+            path = &path[path.find_byte(b'/')? + 1..];
+        }
+        (!path.is_empty()).then(|| path.into())
+    }
+
+    fn trim_newline(line: &[u8]) -> &[u8] {
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        line.strip_suffix(b"\r").unwrap_or(line)
+    }
+}
@@ -0,0 +1,202 @@
+use std::borrow::Cow;
+
+use bstr::ByteSlice;
+
+use crate::patch::{Hunk, LineKind};
+
+/// Options to control how [hunks are applied](apply()).
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// If `Some(n)`, allow context lines to be ignored at the beginning and end of a hunk until only `n` of them are left
+    /// on each side if it wouldn't apply otherwise, similar to `git apply -C<n>`, which is also known as *fuzz*.
+    ///
+    /// If `None`, all context lines have to match.
+    pub min_context: Option<usize>,
+    /// If `true`, changes to the amount of whitespace in context and removed lines don't prevent a hunk from applying,
+    /// as long as there is whitespace in the same places,
+    /// similar to `git apply --ignore-whitespace`. The lines in the result will be the ones that were found in the file.
+    pub ignore_whitespace: bool,
+    /// If `true`, remove trailing whitespace from added lines, similar to `git apply --whitespace=fix`.
+    pub fix_whitespace: bool,
+    /// If `true`, don't assume that hunks without leading or trailing context have to match at the beginning or end of
+    /// the file, similar to `git apply --unidiff-zero`. This is needed for patches created with `git diff -U0`.
+    pub unidiff_zero: bool,
+}
+
+/// Apply all `hunks` to `old` in order and write the result into `out`, which is cleared beforehand.
+///
+/// Each hunk is first searched for at the line it was written for, and then at increasingly distant lines
+/// if the file changed since the patch was created. Hunks that can't be found are skipped,
+/// and their indices are returned, which means the patch was applied completely if the returned list is empty.
+pub fn apply(hunks: &[Hunk], old: &[u8], options: Options, out: &mut Vec<u8>) -> Vec<usize> {
+    let mut image: Vec<Cow<'_, [u8]>> = old.lines_with_terminator().map(Cow::Borrowed).collect();
+    let mut rejected = Vec::new();
+    for (hunk_index, hunk) in hunks.iter().enumerate() {
+        if !apply_hunk(hunk, &mut image, options) {
+            rejected.push(hunk_index);
+        }
+    }
+    out.clear();
+    for line in image {
+        out.extend_from_slice(&line);
+    }
+    rejected
+}
+
+fn apply_hunk<'a>(hunk: &'a Hunk, image: &mut Vec<Cow<'a, [u8]>>, options: Options) -> bool {
+    let is_context = |kind: &LineKind| *kind == LineKind::Context;
+    let mut leading = hunk.lines.iter().take_while(|line| is_context(&line.kind)).count();
+    let mut trailing = hunk
+        .lines
+        .iter()
+        .rev()
+        .take_while(|line| is_context(&line.kind))
+        .count();
+    if leading == hunk.lines.len() {
+        trailing = 0;
+    }
+    let mut match_beginning = hunk.old_start == 0 || (hunk.old_start == 1 && !options.unidiff_zero);
+    let mut match_end = !options.unidiff_zero && trailing == 0;
+    let mut lines = &hunk.lines[..];
+    let mut pos = hunk.new_start.saturating_sub(1) as usize;
+
+    loop {
+        let preimage: Vec<&[u8]> = lines
+            .iter()
+            .filter(|line| line.kind != LineKind::Addition)
+            .map(|line| line.content.as_slice())
+            .collect();
+        if let Some(found) = find_position(image, &preimage, pos, match_beginning, match_end, options) {
+            let mut replacement = Vec::with_capacity(lines.len());
+            let mut image_line = found;
+            for line in lines {
+                match line.kind {
+                    LineKind::Context => {
+                        replacement.push(image[image_line].clone());
+                        image_line += 1;
+                    }
+                    LineKind::Removal => image_line += 1,
+                    LineKind::Addition => replacement.push(if options.fix_whitespace {
+                        Cow::Owned(without_trailing_whitespace(&line.content))
+                    } else {
+                        Cow::Borrowed(line.content.as_slice())
+                    }),
+                }
+            }
+            image.splice(found..found + preimage.len(), replacement);
+            return true;
+        }
+
+        let min_context = options.min_context.unwrap_or(usize::MAX);
+        if leading <= min_context && trailing <= min_context {
+            return false;
+        }
+        if match_beginning || match_end {
+            match_beginning = false;
+            match_end = false;
+            continue;
+        }
+        if leading >= trailing {
+            lines = &lines[1..];
+            pos = pos.saturating_sub(1);
+            leading -= 1;
+        }
+        if trailing > leading {
+            lines = &lines[..lines.len() - 1];
+            trailing -= 1;
+        }
+    }
+}
+
+/// Find `preimage` in `image`, starting at `pos` and alternating between looking at later and earlier lines.
+fn find_position(
+    image: &[Cow<'_, [u8]>],
+    preimage: &[&[u8]],
+    pos: usize,
+    match_beginning: bool,
+    match_end: bool,
+    options: Options,
+) -> Option<usize> {
+    if preimage.len() > image.len() {
+        return None;
+    }
+    let start = if match_beginning {
+        0
+    } else if match_end {
+        image.len() - preimage.len()
+    } else {
+        pos.min(image.len())
+    };
+    let matches = |candidate: usize| {
+        if (match_beginning && candidate != 0) || (match_end && candidate + preimage.len() != image.len()) {
+            return false;
+        }
+        image.get(candidate..candidate + preimage.len()).map_or(false, |lines| {
+            lines.iter().zip(preimage).all(|(actual, expected)| {
+                actual.as_ref() == *expected
+                    || (options.ignore_whitespace && whitespace_insensitive_eq(actual, expected))
+            })
+        })
+    };
+
+    let (mut backwards, mut forwards) = (start, start);
+    if matches(start) {
+        return Some(start);
+    }
+    while backwards > 0 || forwards < image.len() {
+        if forwards < image.len() {
+            forwards += 1;
+            if matches(forwards) {
+                return Some(forwards);
+            }
+        }
+        if backwards > 0 {
+            backwards -= 1;
+            if matches(backwards) {
+                return Some(backwards);
+            }
+        }
+    }
+    None
+}
+
+/// Compare `a` and `b` while treating runs of whitespace of any length as equal, and ignoring line endings.
+fn whitespace_insensitive_eq(a: &[u8], b: &[u8]) -> bool {
+    let without_line_ending = |line: &[u8]| line.trim_end_with(|c| c == '\r' || c == '\n').len();
+    let (a, b) = (&a[..without_line_ending(a)], &b[..without_line_ending(b)]);
+    let (mut a_pos, mut b_pos) = (0, 0);
+    while a_pos < a.len() && b_pos < b.len() {
+        if a[a_pos].is_ascii_whitespace() {
+            if !b[b_pos].is_ascii_whitespace() {
+                return false;
+            }
+            while a.get(a_pos).map_or(false, u8::is_ascii_whitespace) {
+                a_pos += 1;
+            }
+            while b.get(b_pos).map_or(false, u8::is_ascii_whitespace) {
+                b_pos += 1;
+            }
+            continue;
+        }
+        if a[a_pos] != b[b_pos] {
+            return false;
+        }
+        a_pos += 1;
+        b_pos += 1;
+    }
+    a_pos == a.len() && b_pos == b.len()
+}
+
+fn without_trailing_whitespace(line: &[u8]) -> Vec<u8> {
+    let (content, terminator) = match line.strip_suffix(b"\r\n") {
+        Some(content) => (content, &b"\r\n"[..]),
+        None => match line.strip_suffix(b"\n") {
+            Some(content) => (content, &b"\n"[..]),
+            None => (line, &b""[..]),
+        },
+    };
+    let content = content.trim_end_with(|c| c == ' ' || c == '\t');
+    let mut out = content.to_vec();
+    out.extend_from_slice(terminator);
+    out
+}
@@ -0,0 +1,127 @@
+use bstr::{BStr, BString, ByteSlice};
+use gix_hash::{oid, ObjectId};
+use gix_object::{tree::EntryKind, FindExt};
+
+use crate::function::{submodule_data, submodule_id};
+use crate::patch::Patch;
+use crate::{Change, Entry, Options, Rejected};
+
+/// The error returned by [apply()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Apply(#[from] crate::Error),
+    #[error(transparent)]
+    FindBlob(#[from] gix_object::find::existing_object::Error),
+    #[error(transparent)]
+    EditTree(#[from] gix_object::tree::editor::Error),
+    #[error("The patch to '{path}' doesn't contain a valid submodule commit")]
+    InvalidSubmodule { path: BString },
+    #[error("Could not write object")]
+    WriteObject(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+}
+
+/// The outcome of [apply()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The id of the tree with all patches applied.
+    pub tree: ObjectId,
+    /// All hunks that couldn't be applied, if [`Options::reject`] is enabled.
+    pub rejected: Vec<Rejected>,
+    /// All paths that were merged with conflicts, if [`Options::three_way`] is enabled.
+    pub conflicts: Vec<BString>,
+}
+
+/// Apply `patches` to the `tree` and write the resulting tree, without touching the worktree or an index,
+/// similar to applying to the index with `git apply --cached`.
+///
+/// `objects` is used to lookup trees and blobs, and `write` is called with the kind and the serialized data of each object
+/// that needs to be written to produce the new tree, returning its id.
+/// Files with conflicts are written with conflict markers.
+///
+/// See [`apply()`](crate::apply()) for details on `find_preimage` and `options`.
+pub fn apply<E>(
+    tree: &oid,
+    patches: &[Patch],
+    objects: &impl FindExt,
+    mut write: impl FnMut(gix_object::Kind, &[u8]) -> Result<ObjectId, E>,
+    find_preimage: impl FnMut(&BStr) -> Option<Vec<u8>>,
+    options: Options,
+) -> Result<Outcome, Error>
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+{
+    let mut buf = Vec::new();
+    let root = if tree == ObjectId::empty_tree(tree.kind()) {
+        gix_object::Tree::empty()
+    } else {
+        objects.find_tree(tree, &mut buf)?.into()
+    };
+    let outcome = crate::apply(patches, |path| read(&root, path, objects), find_preimage, options)?;
+
+    let mut editor = gix_object::tree::Editor::new(root, objects, tree.kind());
+    let mut conflicts = Vec::new();
+    for (path, change) in &outcome.changes {
+        let components = path.split(|b| *b == b'/');
+        let (mode, data) = match change {
+            Change::Remove => {
+                editor.remove(components)?;
+                continue;
+            }
+            Change::Write(Entry { mode, data }) => (*mode, data),
+            Change::Conflict { mode, merged, .. } => {
+                conflicts.push(path.clone());
+                (*mode, merged)
+            }
+        };
+        let id = if mode == EntryKind::Commit {
+            submodule_id(data).ok_or_else(|| Error::InvalidSubmodule { path: path.clone() })?
+        } else {
+            write(gix_object::Kind::Blob, data).map_err(|err| Error::WriteObject(err.into()))?
+        };
+        editor.upsert(components, mode, id)?;
+    }
+
+    let tree = editor.write(|tree| {
+        buf.clear();
+        gix_object::WriteTo::write_to(tree, &mut buf).expect("write to memory works");
+        write(gix_object::Kind::Tree, &buf).map_err(|err| Error::WriteObject(err.into()))
+    })?;
+    Ok(Outcome {
+        tree,
+        rejected: outcome.rejected,
+        conflicts,
+    })
+}
+
+/// Find the non-tree entry at `path` in `root` and return it.
+fn read(root: &gix_object::Tree, path: &BStr, objects: &impl FindExt) -> Result<Option<Entry>, Error> {
+    let mut buf = Vec::new();
+    let mut tree = root.clone();
+    let mut components = path.split(|b| *b == b'/').peekable();
+    while let Some(component) = components.next() {
+        let Some(entry) = tree.entries.iter().find(|entry| entry.filename == component.as_bstr()) else {
+            return Ok(None);
+        };
+        let is_last = components.peek().is_none();
+        match (is_last, entry.mode.kind()) {
+            (false, EntryKind::Tree) => {
+                let id = entry.oid;
+                tree = objects.find_tree(&id, &mut buf)?.into();
+            }
+            (false, _) | (true, EntryKind::Tree) => return Ok(None),
+            (true, EntryKind::Commit) => {
+                return Ok(Some(Entry {
+                    mode: EntryKind::Commit,
+                    data: submodule_data(&entry.oid),
+                }))
+            }
+            (true, mode) => {
+                let data = objects.find_blob(&entry.oid, &mut buf)?.data.to_vec();
+                return Ok(Some(Entry { mode, data }));
+            }
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,131 @@
+use bstr::{BStr, BString};
+use gix_object::tree::EntryKind;
+
+use crate::patch::Hunk;
+
+/// The error returned by [apply()](crate::apply()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not read '{path}'")]
+    Read {
+        path: BString,
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[error("'{path}' does not exist")]
+    MissingSource { path: BString },
+    #[error("'{path}' already exists")]
+    AlreadyExists { path: BString },
+    #[error("{} of {total} hunks of the patch to '{path}' did not apply", hunks.len())]
+    HunksFailed {
+        path: BString,
+        hunks: Vec<usize>,
+        total: usize,
+    },
+    #[error("The patch to '{path}' removes the file, but it is not empty afterwards")]
+    NotEmptyAfterDeletion { path: BString },
+    #[error("The binary patch to '{path}' has no data, which is required to apply it")]
+    BinaryWithoutData { path: BString },
+    #[error("The binary patch to '{path}' was made for a different version of the file")]
+    BinaryPreimageMismatch { path: BString },
+    #[error("The binary patch to '{path}' did not produce the expected result")]
+    BinaryPostimageMismatch { path: BString },
+    #[error("Could not apply binary patch to '{path}'")]
+    Binary {
+        path: BString,
+        source: crate::binary::apply::Error,
+    },
+}
+
+/// Options for [apply()](crate::apply()).
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// Control how text hunks are applied.
+    pub text: crate::text::Options,
+    /// If `true`, undo the patches instead of applying them, similar to `git apply --reverse`.
+    pub reverse: bool,
+    /// If `true`, apply the hunks that can be applied and record those that can't in [`Outcome::rejected`],
+    /// similar to `git apply --reject`. Otherwise, a single hunk that can't be applied fails the whole operation.
+    pub reject: bool,
+    /// If `Some(options)`, perform a three-way merge between the version of a file that the patch was created for,
+    /// the current version of the file and the patched version of the former, similar to `git apply --3way`.
+    ///
+    /// This is tried first and only possible if the patch records the id of the file it applies to,
+    /// and if that version is available. Otherwise, the patch is applied directly.
+    pub three_way: Option<gix_merge::blob::Options>,
+}
+
+/// The state of a file, as read before applying patches and as written afterwards.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Entry {
+    /// The kind of file.
+    pub mode: EntryKind,
+    /// The content of a blob, the target of a symbolic link, or `Subproject commit <hex>\n` for a submodule,
+    /// just like it is represented in patches.
+    pub data: Vec<u8>,
+}
+
+/// What to do to a path to apply the patches.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Change {
+    /// Write the entry, replacing whatever was there.
+    Write(Entry),
+    /// Delete the entry.
+    Remove,
+    /// A [three-way merge](Options::three_way) was performed, but it couldn't be completed without conflict.
+    Conflict {
+        /// The kind of file to write.
+        mode: EntryKind,
+        /// The content with conflict markers.
+        merged: Vec<u8>,
+        /// The version of the file the patch was created for.
+        base: Vec<u8>,
+        /// The version of the file before applying the patch.
+        ours: Vec<u8>,
+        /// The version of the file the patch was created for, with the patch applied.
+        theirs: Vec<u8>,
+    },
+}
+
+/// The hunks of a patch that couldn't be applied, which is only possible if [`Options::reject`] is enabled.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Rejected {
+    /// The path of the file the patch was applied to, i.e. the path after a rename.
+    pub path: BString,
+    /// The hunks that couldn't be applied.
+    pub hunks: Vec<Hunk>,
+}
+
+impl Rejected {
+    /// Write all rejected hunks with a header, just like the `<path>.rej` files written by `git apply --reject`.
+    pub fn write_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        out.write_all(b"diff a/")?;
+        out.write_all(&self.path)?;
+        out.write_all(b" b/")?;
+        out.write_all(&self.path)?;
+        out.write_all(b"\t(rejected hunks)\n")?;
+        for hunk in &self.hunks {
+            hunk.write_to(out)?;
+        }
+        Ok(())
+    }
+}
+
+/// The outcome of [apply()](crate::apply()).
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The final state of each path that was touched by a patch, sorted by path.
+    pub changes: Vec<(BString, Change)>,
+    /// All hunks that couldn't be applied, by path and in the order of the patches, if [`Options::reject`] is enabled.
+    pub rejected: Vec<Rejected>,
+}
+
+impl Outcome {
+    /// Return all paths that have conflicts due to a [three-way merge](Options::three_way).
+    pub fn conflicts(&self) -> impl Iterator<Item = &BStr> {
+        self.changes
+            .iter()
+            .filter(|(_, change)| matches!(change, Change::Conflict { .. }))
+            .map(|(path, _)| path.as_ref())
+    }
+}
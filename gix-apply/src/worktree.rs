@@ -0,0 +1,162 @@
+use std::path::{Path, PathBuf};
+
+use bstr::{BStr, BString, ByteSlice};
+use gix_object::tree::EntryKind;
+
+use crate::patch::Patch;
+use crate::{Change, Entry, Options, Outcome, Rejected};
+
+/// The error returned by [apply()] and [apply_changes()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Apply(#[from] crate::Error),
+    #[error("Could not read '{}'", path.display())]
+    Read { path: PathBuf, source: std::io::Error },
+    #[error("Could not write '{}'", path.display())]
+    Write { path: PathBuf, source: std::io::Error },
+    #[error("Could not delete '{}'", path.display())]
+    Remove { path: PathBuf, source: std::io::Error },
+}
+
+/// Apply `patches` to the files in the worktree at `root` and write the result back, similar to `git apply`.
+///
+/// If hunks are rejected, they are written to `<path>.rej` files next to the file they were meant for.
+/// Files with conflicts are written with conflict markers. Submodules are never changed.
+///
+/// See [`apply()`](crate::apply()) for details on `find_preimage` and `options`.
+pub fn apply(
+    root: &Path,
+    patches: &[Patch],
+    find_preimage: impl FnMut(&BStr) -> Option<Vec<u8>>,
+    options: Options,
+) -> Result<Outcome, Error> {
+    let outcome = crate::apply(patches, |path| read(root, path), find_preimage, options)?;
+    apply_changes(root, &outcome.changes, &outcome.rejected)?;
+    Ok(outcome)
+}
+
+/// Change the files in the worktree at `root` to match `changes` as obtained by [`apply()`](crate::apply()),
+/// and write all `rejected` hunks into `<path>.rej` files.
+pub fn apply_changes(root: &Path, changes: &[(BString, Change)], rejected: &[Rejected]) -> Result<(), Error> {
+    for (path, change) in changes {
+        let path = root.join(gix_path::from_bstr(path.as_bstr()));
+        let (mode, data) = match change {
+            Change::Remove => {
+                remove(&path)?;
+                remove_empty_parents(root, &path);
+                continue;
+            }
+            Change::Write(Entry { mode, data }) => (*mode, data),
+            Change::Conflict { mode, merged, .. } => (*mode, merged),
+        };
+        write(&path, mode, data)?;
+    }
+    for rejected in rejected {
+        let mut path = root.join(gix_path::from_bstr(rejected.path.as_bstr())).into_os_string();
+        path.push(".rej");
+        let path = PathBuf::from(path);
+        let mut out = Vec::new();
+        rejected.write_to(&mut out).expect("write to memory works");
+        std::fs::write(&path, out).map_err(|source| Error::Write { path, source })?;
+    }
+    Ok(())
+}
+
+fn read(root: &Path, rela_path: &BStr) -> Result<Option<Entry>, Error> {
+    let path = root.join(gix_path::from_bstr(rela_path));
+    let err = |source| Error::Read {
+        path: path.clone(),
+        source,
+    };
+    let meta = match std::fs::symlink_metadata(&path) {
+        Ok(meta) => meta,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(source) => return Err(Error::Read { path, source }),
+    };
+    Ok(if meta.is_symlink() {
+        let target = std::fs::read_link(&path).map_err(err)?;
+        Some(Entry {
+            mode: EntryKind::Link,
+            data: gix_path::into_bstr(target).into_owned().into(),
+        })
+    } else if meta.is_file() {
+        Some(Entry {
+            mode: if gix_fs::is_executable(&meta) {
+                EntryKind::BlobExecutable
+            } else {
+                EntryKind::Blob
+            },
+            data: std::fs::read(&path).map_err(err)?,
+        })
+    } else {
+        None
+    })
+}
+
+fn write(path: &Path, mode: EntryKind, data: &[u8]) -> Result<(), Error> {
+    let err = |source| Error::Write {
+        path: path.to_owned(),
+        source,
+    };
+    if mode == EntryKind::Commit {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(err)?;
+    }
+    if let Ok(meta) = std::fs::symlink_metadata(path) {
+        if meta.is_symlink() || mode == EntryKind::Link {
+            remove(path)?;
+        }
+    }
+    match mode {
+        EntryKind::Link => {
+            let target = gix_path::try_from_byte_slice(data).map_err(|_| {
+                err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "symlink target is not a valid path",
+                ))
+            })?;
+            gix_fs::symlink::create(target, path).map_err(err)?;
+        }
+        _ => {
+            std::fs::write(path, data).map_err(err)?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                let mut perm = std::fs::metadata(path).map_err(err)?.permissions();
+                let bits = perm.mode();
+                perm.set_mode(if mode == EntryKind::BlobExecutable {
+                    bits | ((bits & 0o444) >> 2)
+                } else {
+                    bits & !0o111
+                });
+                std::fs::set_permissions(path, perm).map_err(err)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn remove(path: &Path) -> Result<(), Error> {
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(source) => Err(Error::Remove {
+            path: path.to_owned(),
+            source,
+        }),
+    }
+}
+
+fn remove_empty_parents(root: &Path, path: &Path) {
+    let mut dir = path.parent();
+    while let Some(parent) = dir.filter(|dir| *dir != root && dir.starts_with(root)) {
+        if std::fs::remove_dir(parent).is_err() {
+            break;
+        }
+        dir = parent.parent();
+    }
+}
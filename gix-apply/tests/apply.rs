@@ -0,0 +1,44 @@
+use std::collections::HashMap;
+
+pub use gix_testtools::Result;
+
+mod mailbox;
+mod patch;
+mod tree;
+mod worktree;
+
+pub type Odb = gix_odb::memory::Proxy<gix_odb::Handle>;
+
+pub fn odb_and_trees(name: &str) -> Result<(Odb, HashMap<String, gix_hash::ObjectId>)> {
+    let root = gix_testtools::scripted_fixture_read_only("make_patches.sh")?.join(name);
+    let odb = gix_odb::memory::Proxy::new(gix_odb::at(root.join(".git").join("objects"))?, gix_hash::Kind::Sha1);
+    Ok((odb, trees_at(&root)?))
+}
+
+pub fn trees_at(root: &std::path::Path) -> Result<HashMap<String, gix_hash::ObjectId>> {
+    Ok(std::fs::read_to_string(root.join("trees"))?
+        .lines()
+        .map(|line| {
+            let (name, hex) = line.split_once(' ').expect("name and id");
+            (
+                name.to_owned(),
+                gix_hash::ObjectId::from_hex(hex.as_bytes()).expect("valid hex"),
+            )
+        })
+        .collect())
+}
+
+pub fn patches_at(root: &std::path::Path, file: &str) -> Result<Vec<gix_apply::patch::Patch>> {
+    let data = std::fs::read(root.join(file))?;
+    Ok(gix_apply::patch::parse(&data, Default::default())?)
+}
+
+/// Resolve the abbreviated blob ids in `index` lines like `git apply --3way` does.
+pub fn find_preimage(odb: &Odb) -> impl FnMut(&bstr::BStr) -> Option<Vec<u8>> + '_ {
+    use gix_object::FindExt;
+    |hex| {
+        let prefix = gix_hash::Prefix::from_hex(std::str::from_utf8(hex).ok()?).ok()?;
+        let id = odb.lookup_prefix(prefix, None).ok()??.ok()?;
+        Some(odb.find_blob(&id, &mut Vec::new()).ok()?.data.to_vec())
+    }
+}
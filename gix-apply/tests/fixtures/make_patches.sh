@@ -0,0 +1,133 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function write_trees() {
+  {
+    for name in "$@"; do
+      echo "$name $(git rev-parse $name^{tree})"
+    done
+  } > trees
+}
+
+git init -q all-kinds
+(cd all-kinds
+  seq 1 20 > modified
+  echo "content" > deleted
+  echo "#!/bin/sh" > mode-change
+  seq 1 30 > renamed-from
+  seq 40 70 > renamed-with-change-from
+  printf 'a\nb' > no-newline
+  printf 'binary\0data\0that\0will\0change\n%.0s' $(seq 1 20) > binary
+  ln -s modified link
+  git add -A && git commit -qm base
+  git branch base
+
+  sed -i.bak 's/^2$/two/;s/^19$/nineteen/' modified && rm modified.bak
+  git rm -q deleted
+  chmod +x mode-change
+  git mv renamed-from renamed-to
+  mkdir dir && git mv renamed-with-change-from dir/renamed-with-change-to
+  echo 71 >> dir/renamed-with-change-to
+  printf 'a\nc' > no-newline
+  printf 'binary\0data\0that\0has\0changed\n%.0s' $(seq 1 20) > binary
+  printf 'new\0binary' > new-binary
+  echo "added" > dir/added
+  ln -sf mode-change link
+  git add -A && git commit -qm changed
+  git branch changed
+
+  git diff --binary -M base changed > patch
+  git format-patch -1 --binary -M --stdout changed > mail
+  write_trees base changed
+)
+
+git init -q drifted
+(cd drifted
+  seq -f "line %g" 1 30 > file
+  git add file && git commit -qm base
+  git branch base
+
+  sed -i.bak 's/^line 5$/line five/;s/^line 15$/line fifteen/;s/^line 27$/line twenty-seven/' file && rm file.bak
+  git commit -qam change
+  git branch change
+  git diff base change > patch
+
+  git checkout -q --detach base
+  { echo "new first line"; echo "new second line"; cat file; } > file.new && mv file.new file
+  git commit -qam offset
+  git branch offset
+  git apply --index patch
+  git commit -qm offset-applied
+  git branch offset-applied
+
+  git checkout -q --detach base
+  sed -i.bak 's/^line 13$/line thirteen/;s/^line 25$/line twenty-five/' file && rm file.bak
+  git commit -qam fuzz
+  git branch fuzz
+  git apply --index -C1 patch
+  git commit -qm fuzz-applied
+  git branch fuzz-applied
+
+  git checkout -q --detach base
+  sed -i.bak 's/^line 14$/line  14/;s/^line 26$/line\t26/' file && rm file.bak
+  git commit -qam whitespace
+  git branch whitespace
+  git apply --index --ignore-whitespace patch
+  git commit -qm whitespace-applied
+  git branch whitespace-applied
+
+  git checkout -q --detach base
+  sed -i.bak 's/^line 15$/line FIFTEEN/' file && rm file.bak
+  git commit -qam reject
+  git branch reject
+  git apply --reject patch 2>/dev/null || :
+  mv file.rej ../drifted.rej
+  git commit -qam reject-applied
+  git branch reject-applied
+
+  git checkout -q --detach base
+  sed -i.bak 's/^line 12$/line twelve/;s/^line 18$/line eighteen/' file && rm file.bak
+  git commit -qam three-way
+  git branch three-way
+  git apply --3way patch
+  git commit -qm three-way-applied
+  git branch three-way-applied
+
+  git checkout -q --detach base
+  write_trees base change offset offset-applied fuzz fuzz-applied whitespace whitespace-applied reject reject-applied three-way three-way-applied
+)
+mv drifted.rej drifted/rejected-hunks
+
+git init -q mailbox
+(cd mailbox
+  echo base > file
+  git add file && git commit -qm base
+  git branch base
+
+  echo one >> file
+  GIT_AUTHOR_NAME="Jöhn Dœ" GIT_AUTHOR_EMAIL="john@example.com" git commit -qam "Add a line for an author with a name that isn't ASCII"
+  echo two >> file
+  GIT_AUTHOR_NAME="Doe, Jane (maintainer)" GIT_AUTHOR_DATE="2005-04-07 22:13:13 -0700" git commit -qam "A very long subject which has to be wrapped because it doesn't fit into a single line of the mail header
+
+The body has a paragraph.
+
+Signed-off-by: Jane Doe <jane@example.com>
+Change-Id: I1234"
+  echo three >> file
+  git commit -qam "[RFC] Subject with brackets and a naïve body
+
+Ünïcödé in the body requires a content-type."
+  echo four >> file
+  git commit -qam "Subject that =?looks?= encoded and contains : colons" -m "Re: not a subject"
+  git branch tip
+
+  git format-patch -q --stdout base..tip > mbox
+  git format-patch -q -o patches base..tip
+  ls patches > file-names
+  git log --reverse --format=%H base..tip > ids
+
+  git checkout -q --detach base
+  git am -q mbox
+  git log --reverse --format='%an%x00%ae%x00%ad%x00%B%x00' --date=raw base..HEAD > am-commits
+  git checkout -q tip
+)
@@ -0,0 +1,205 @@
+use bstr::{BStr, ByteSlice};
+use gix_apply::mailbox::{self, write};
+use gix_object::FindExt;
+
+fn fixture() -> std::path::PathBuf {
+    gix_testtools::scripted_fixture_read_only("make_patches.sh")
+        .expect("fixture is valid")
+        .join("mailbox")
+}
+
+fn commit_ids(root: &std::path::Path) -> crate::Result<Vec<gix_hash::ObjectId>> {
+    Ok(std::fs::read_to_string(root.join("ids"))?
+        .lines()
+        .map(|hex| gix_hash::ObjectId::from_hex(hex.as_bytes()))
+        .collect::<Result<_, _>>()?)
+}
+
+#[test]
+fn split_and_parse_like_git_am() -> crate::Result {
+    let root = fixture();
+    let mbox = std::fs::read(root.join("mbox"))?;
+    let expected = std::fs::read(root.join("am-commits"))?;
+    let expected: Vec<_> = expected.split_str("\0\n").filter(|c| !c.is_empty()).collect();
+
+    let mails: Vec<_> = mailbox::split(&mbox).collect();
+    assert_eq!(mails.len(), 4, "one mail per commit");
+    assert_eq!(mails.concat(), mbox, "no data is lost");
+
+    for ((mail, expected), id) in mails.iter().zip(expected).zip(commit_ids(&root)?) {
+        let mail = mailbox::parse(mail, Default::default())?;
+        let fields: Vec<_> = expected.splitn_str(4, "\0").collect();
+        assert_eq!(mail.id, Some(id), "the id is taken from the From line");
+        assert_eq!(mail.author.name, fields[0].as_bstr());
+        assert_eq!(mail.author.email, fields[1].as_bstr());
+        assert_eq!(
+            mail.author.time.to_bstring(),
+            fields[2].as_bstr(),
+            "the date is parsed with its offset"
+        );
+        assert_eq!(mail.message, fields[3].as_bstr());
+        assert!(mail.patch.starts_with(b"---\n"), "the patch starts with the separator");
+        assert_eq!(
+            gix_apply::patch::parse(&mail.patch, Default::default())?.len(),
+            1,
+            "the diffstat is ignored when parsing the patch"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn trailers_are_available_from_the_message() -> crate::Result {
+    let mbox = std::fs::read(fixture().join("mbox"))?;
+    let mail = mailbox::split(&mbox).nth(1).expect("second mail");
+    let mail = mailbox::parse(mail, Default::default())?;
+    let message = mail.message_ref();
+    let trailers: Vec<_> = message
+        .body()
+        .expect("has body")
+        .trailers()
+        .map(|trailer| (trailer.token.to_string(), trailer.value.to_string()))
+        .collect();
+    assert_eq!(
+        trailers,
+        [
+            ("Signed-off-by".into(), "Jane Doe <jane@example.com>".into()),
+            ("Change-Id".into(), "I1234".into())
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn subjects_can_be_kept() -> crate::Result {
+    let mbox = std::fs::read(fixture().join("mbox"))?;
+    let mail = mailbox::split(&mbox).nth(2).expect("third mail");
+    let mail = mailbox::parse(mail, mailbox::parse::Options { keep_subject: true })?;
+    assert_eq!(
+        mail.message.lines().next().expect("title").as_bstr(),
+        "[PATCH 3/4] [RFC] Subject with brackets and a naïve body"
+    );
+    Ok(())
+}
+
+#[test]
+fn in_body_headers_override_mail_headers() -> crate::Result {
+    let mail = mailbox::parse(
+        b"From: Sender <sender@example.com>
+Date: Sat, 1 Jan 2000 00:00:00 +0100
+Subject: [PATCH] =?ISO-8859-1?Q?Caf=E9?= =?UTF-8?B?IGF1?= lait
+Content-Transfer-Encoding: quoted-printable
+
+From: =?UTF-8?q?Ren=C3=A9?= <rene@example.com>
+Date: Thu, 7 Apr 2005 22:13:13 -0700
+
+A body line that is long enough to be broken into two lines with a soft =
+line break.
+--- a/file
++++ b/file
+",
+        Default::default(),
+    )?;
+    assert_eq!(mail.id, None, "there is no From line");
+    assert_eq!(mail.author.name, "René");
+    assert_eq!(mail.author.email, "rene@example.com");
+    assert_eq!(mail.author.time.to_bstring(), "1112937193 -0700");
+    assert_eq!(
+        mail.message,
+        "Café au lait\n\nA body line that is long enough to be broken into two lines with a soft line break.\n"
+    );
+    assert_eq!(mail.patch, "--- a/file\n+++ b/file\n");
+
+    let mail = mailbox::parse(
+        b"From: jane@example.com (Jane Doe)\nDate: Sat, 1 Jan 2000 00:00:00 +0100\n\nno subject\n",
+        Default::default(),
+    )?;
+    assert_eq!(mail.author.name, "Jane Doe");
+    assert_eq!(mail.author.email, "jane@example.com");
+    assert_eq!(mail.message, "no subject\n", "the empty subject is removed");
+
+    assert!(matches!(
+        mailbox::parse(b"Subject: no author\n\nbody\n", Default::default()),
+        Err(mailbox::parse::Error::MissingAuthor)
+    ));
+    Ok(())
+}
+
+#[test]
+fn headers_and_file_names_like_git_format_patch() -> crate::Result {
+    let root = fixture();
+    let odb = gix_odb::at(root.join(".git").join("objects"))?;
+    let mbox = std::fs::read(root.join("mbox"))?;
+    let file_names = std::fs::read_to_string(root.join("file-names"))?;
+    let ids = commit_ids(&root)?;
+
+    for (number, ((mail, id), expected_file_name)) in mailbox::split(&mbox)
+        .zip(&ids)
+        .zip(file_names.lines())
+        .enumerate()
+        .map(|(index, item)| (index + 1, item))
+    {
+        let mut buf = Vec::new();
+        let commit = odb.find_commit(id, &mut buf)?;
+        let subject = commit.message_summary();
+        let utf8_body = !commit.message.is_ascii();
+        let mut actual = Vec::new();
+        write::header(
+            &mut actual,
+            &write::Header {
+                id,
+                from: commit.author(),
+                subject_prefix: format!("PATCH {number}/{}", ids.len()).as_str().into(),
+                subject: subject.as_ref(),
+                utf8_body,
+            },
+        )?;
+        let header_end = mail.find(b"\n\n").expect("headers end with an empty line") + 2;
+        assert_eq!(actual.as_bstr(), mail[..header_end].as_bstr());
+        assert_eq!(write::file_name(number, subject.as_ref()), expected_file_name);
+    }
+    Ok(())
+}
+
+#[test]
+fn file_names_are_sanitized_and_truncated() {
+    for (subject, expected) in [
+        (
+            "..leading dots..and trailing --",
+            "0001-.leading-dots.and-trailing.patch",
+        ),
+        ("", "0001-.patch"),
+        (
+            "a subject that is way too long to fit into the limit of a file name",
+            "0001-a-subject-that-is-way-too-long-to-fit-into-the-limit.patch",
+        ),
+    ] {
+        assert_eq!(write::file_name(1, subject.into()), expected, "{subject:?}");
+    }
+    assert_eq!(write::file_name(0, "cover letter".into()), "0000-cover-letter.patch");
+}
+
+#[test]
+fn wrapped_text_breaks_at_whitespace() {
+    let mut out = Vec::new();
+    write::wrapped_text(&mut out, "one two three four five".into(), 2, 4, 13);
+    assert_eq!(out.as_bstr(), "  one two\n    three\n    four five");
+
+    let mut out = b"Subject: ".to_vec();
+    write::wrapped_text(&mut out, "first\nsecond".into(), -9, 1, 78);
+    assert_eq!(
+        out.as_bstr(),
+        "Subject: first second",
+        "newlines followed by alphanumerics are spaces"
+    );
+}
+
+#[test]
+fn parsing_a_mail_without_from_line() -> crate::Result {
+    let mail: &BStr = "From: a <a@b>\nDate: Sat, 1 Jan 2000 00:00:00 +0100\nSubject: s\n\n".into();
+    assert_eq!(mailbox::split(mail).count(), 1);
+    let mail = mailbox::parse(mail, Default::default())?;
+    assert_eq!(mail.message, "s\n");
+    assert_eq!(mail.patch, "");
+    Ok(())
+}
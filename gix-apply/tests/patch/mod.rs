@@ -0,0 +1,203 @@
+use gix_apply::patch::{parse, Body, Hunk, Line, LineKind, Operation, Patch};
+use gix_object::tree::EntryKind;
+
+fn fixture(name: &str) -> std::path::PathBuf {
+    gix_testtools::scripted_fixture_read_only("make_patches.sh")
+        .expect("fixture is valid")
+        .join(name)
+}
+
+#[test]
+fn git_diff_output_yields_one_patch_per_file() -> crate::Result {
+    let patches = crate::patches_at(&fixture("all-kinds"), "patch")?;
+    let summary: Vec<_> = patches
+        .iter()
+        .map(|patch| {
+            (
+                patch.operation,
+                patch.source_path().map(ToString::to_string),
+                patch.path().to_string(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (Operation::Modify, Some("binary".into()), "binary".into()),
+            (Operation::Delete, Some("deleted".into()), "deleted".into()),
+            (Operation::Add, None, "dir/added".into()),
+            (
+                Operation::Rename,
+                Some("renamed-with-change-from".into()),
+                "dir/renamed-with-change-to".into()
+            ),
+            (Operation::Modify, Some("link".into()), "link".into()),
+            (Operation::Modify, Some("mode-change".into()), "mode-change".into()),
+            (Operation::Modify, Some("modified".into()), "modified".into()),
+            (Operation::Add, None, "new-binary".into()),
+            (Operation::Modify, Some("no-newline".into()), "no-newline".into()),
+            (Operation::Rename, Some("renamed-from".into()), "renamed-to".into()),
+        ]
+    );
+
+    let by_path = |path: &str| patches.iter().find(|patch| patch.path() == path).expect("present");
+    let mode_change = by_path("mode-change");
+    assert_eq!(mode_change.old_mode, Some(EntryKind::Blob));
+    assert_eq!(mode_change.new_mode, Some(EntryKind::BlobExecutable));
+    assert_eq!(mode_change.body, Body::Empty);
+
+    let link = by_path("link");
+    assert_eq!(
+        link.old_mode,
+        Some(EntryKind::Link),
+        "the mode of the index line is used"
+    );
+    assert_eq!(
+        link.old_id.as_ref().map(ToString::to_string).as_deref(),
+        Some("d84012f")
+    );
+
+    let renamed = by_path("dir/renamed-with-change-to");
+    assert_eq!(renamed.similarity, Some(96));
+    assert!(matches!(&renamed.body, Body::Text(hunks) if hunks.len() == 1));
+    assert_eq!(by_path("renamed-to").body, Body::Empty, "pure renames have no content");
+
+    assert!(
+        matches!(&by_path("binary").body, Body::Binary { reverse: Some(_), .. }),
+        "git writes hunks for both directions"
+    );
+    assert!(
+        matches!(&by_path("new-binary").body, Body::Binary { forward, .. } if forward.data == b"new\0binary"),
+        "binary data is decoded and inflated"
+    );
+
+    let Body::Text(hunks) = &by_path("no-newline").body else {
+        unreachable!("text patch")
+    };
+    assert_eq!(
+        hunks[0].lines,
+        [
+            Line {
+                kind: LineKind::Context,
+                content: "a\n".into()
+            },
+            Line {
+                kind: LineKind::Removal,
+                content: "b".into()
+            },
+            Line {
+                kind: LineKind::Addition,
+                content: "c".into()
+            }
+        ],
+        "missing newlines at the end of the file are retained"
+    );
+    Ok(())
+}
+
+#[test]
+fn format_patch_output_yields_the_same_patches_as_git_diff() -> crate::Result {
+    let root = fixture("all-kinds");
+    assert_eq!(crate::patches_at(&root, "mail")?, crate::patches_at(&root, "patch")?);
+    Ok(())
+}
+
+#[test]
+fn hunks_can_be_written_back() -> crate::Result {
+    let root = fixture("all-kinds");
+    let patches = crate::patches_at(&root, "patch")?;
+    let modified = patches
+        .iter()
+        .find(|patch| patch.path() == "modified")
+        .expect("present");
+    let Body::Text(hunks) = &modified.body else {
+        unreachable!("text patch")
+    };
+    let mut out = Vec::new();
+    for hunk in hunks {
+        hunk.write_to(&mut out)?;
+    }
+    let patch = std::fs::read(root.join("patch"))?;
+    let start = patch.windows(out.len()).position(|window| window == out);
+    assert!(start.is_some(), "the hunks are written exactly as git wrote them");
+    Ok(())
+}
+
+#[test]
+fn reversing_twice_yields_the_original() -> crate::Result {
+    let patches = crate::patches_at(&fixture("all-kinds"), "patch")?;
+    for patch in patches {
+        assert_eq!(patch.reversed().reversed(), patch);
+    }
+    Ok(())
+}
+
+#[test]
+fn traditional_unified_diffs_are_supported() -> crate::Result {
+    let input = "--- a/dir/file.orig\t2024-01-01 00:00:00\n+++ b/dir/file\t2024-01-01 00:00:00\n@@ -1,2 +1,2 @@ section\n-old\n+new\n context\n";
+    let patches = parse(input.as_bytes(), Default::default())?;
+    assert_eq!(
+        patches,
+        [Patch {
+            operation: Operation::Modify,
+            old_path: Some("dir/file".into()),
+            new_path: Some("dir/file".into()),
+            old_mode: None,
+            new_mode: None,
+            old_id: None,
+            new_id: None,
+            similarity: None,
+            body: Body::Text(vec![Hunk {
+                old_start: 1,
+                old_lines: 2,
+                new_start: 1,
+                new_lines: 2,
+                section: "section".into(),
+                lines: vec![
+                    Line {
+                        kind: LineKind::Removal,
+                        content: "old\n".into()
+                    },
+                    Line {
+                        kind: LineKind::Addition,
+                        content: "new\n".into()
+                    },
+                    Line {
+                        kind: LineKind::Context,
+                        content: "context\n".into()
+                    },
+                ]
+            }]),
+        }]
+    );
+
+    let patches = parse(input.as_bytes(), gix_apply::patch::parse::Options { strip: 2 })?;
+    assert_eq!(patches[0].path(), "file", "leading path components can be stripped");
+    Ok(())
+}
+
+#[test]
+fn truncated_hunks_are_an_error() {
+    let err = parse(
+        b"diff --git a/f b/f\n--- a/f\n+++ b/f\n@@ -1,3 +1,3 @@\n-a\n+b\n",
+        Default::default(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, gix_apply::patch::parse::Error::TruncatedHunk { .. }));
+}
+
+#[test]
+fn encoded_binary_patches_can_be_parsed_back() -> crate::Result {
+    let old: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+    let new = b"new\0content".to_vec();
+    let mut input = b"diff --git a/file b/file\nindex 1111111..2222222 100644\n".to_vec();
+    gix_apply::binary::encode(&old, &new, &mut input)?;
+
+    let patches = parse(&input, Default::default())?;
+    let Body::Binary { forward, reverse } = &patches[0].body else {
+        unreachable!("a binary patch was written")
+    };
+    assert_eq!(forward.data, new);
+    assert_eq!(reverse.as_ref().expect("git-style reverse hunk").data, old);
+    Ok(())
+}
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+
+use gix_apply::{text, tree, Options};
+use gix_odb::Write;
+
+fn apply(
+    name: &str,
+    base: &str,
+    options: Options,
+) -> crate::Result<(tree::Outcome, HashMap<String, gix_hash::ObjectId>)> {
+    let (odb, trees) = crate::odb_and_trees(name)?;
+    let root = gix_testtools::scripted_fixture_read_only("make_patches.sh")?.join(name);
+    let patches = crate::patches_at(&root, "patch")?;
+    let outcome = tree::apply(
+        &trees[base],
+        &patches,
+        &odb,
+        |kind, data| odb.write_buf(kind, data),
+        crate::find_preimage(&odb),
+        options,
+    )?;
+    Ok((outcome, trees))
+}
+
+#[test]
+fn all_kinds_of_changes_produce_the_same_tree_as_git() -> crate::Result {
+    let (outcome, trees) = apply("all-kinds", "base", Options::default())?;
+    assert!(outcome.rejected.is_empty() && outcome.conflicts.is_empty());
+    assert_eq!(outcome.tree, trees["changed"]);
+    Ok(())
+}
+
+#[test]
+fn reversed_patches_undo_all_changes() -> crate::Result {
+    let (outcome, trees) = apply(
+        "all-kinds",
+        "changed",
+        Options {
+            reverse: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.tree, trees["base"]);
+    Ok(())
+}
+
+#[test]
+fn patches_fail_to_apply_twice() -> crate::Result {
+    let err = apply("all-kinds", "changed", Options::default()).unwrap_err();
+    assert!(
+        matches!(
+            err.downcast_ref::<tree::Error>(),
+            Some(tree::Error::Apply(
+                gix_apply::Error::HunksFailed { .. }
+                    | gix_apply::Error::AlreadyExists { .. }
+                    | gix_apply::Error::MissingSource { .. }
+                    | gix_apply::Error::BinaryPreimageMismatch { .. }
+            ))
+        ),
+        "{err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn hunks_are_found_at_an_offset() -> crate::Result {
+    let (outcome, trees) = apply("drifted", "offset", Options::default())?;
+    assert_eq!(outcome.tree, trees["offset-applied"]);
+    Ok(())
+}
+
+#[test]
+fn context_can_be_reduced_to_apply_with_fuzz() -> crate::Result {
+    let err = apply("drifted", "fuzz", Options::default()).unwrap_err();
+    assert!(
+        matches!(
+            err.downcast_ref::<tree::Error>(),
+            Some(tree::Error::Apply(gix_apply::Error::HunksFailed { hunks, total: 3, .. })) if hunks.len() == 2
+        ),
+        "{err:?}"
+    );
+
+    let (outcome, trees) = apply(
+        "drifted",
+        "fuzz",
+        Options {
+            text: text::Options {
+                min_context: Some(1),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.tree, trees["fuzz-applied"]);
+    Ok(())
+}
+
+#[test]
+fn whitespace_changes_can_be_ignored() -> crate::Result {
+    let (outcome, trees) = apply(
+        "drifted",
+        "whitespace",
+        Options {
+            text: text::Options {
+                ignore_whitespace: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.tree, trees["whitespace-applied"]);
+    Ok(())
+}
+
+#[test]
+fn hunks_that_dont_apply_can_be_rejected() -> crate::Result {
+    let (outcome, trees) = apply(
+        "drifted",
+        "reject",
+        Options {
+            reject: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.tree, trees["reject-applied"], "all other hunks were applied");
+    assert_eq!(outcome.rejected.len(), 1);
+
+    let mut rej = Vec::new();
+    outcome.rejected[0].write_to(&mut rej)?;
+    let root = gix_testtools::scripted_fixture_read_only("make_patches.sh")?.join("drifted");
+    assert_eq!(
+        rej,
+        std::fs::read(root.join("rejected-hunks"))?,
+        "rejected hunks are written like git does"
+    );
+    Ok(())
+}
+
+#[test]
+fn three_way_merges_use_the_preimage() -> crate::Result {
+    assert!(
+        apply("drifted", "three-way", Options::default()).is_err(),
+        "the changed context prevents the hunk from applying"
+    );
+    let (outcome, trees) = apply(
+        "drifted",
+        "three-way",
+        Options {
+            three_way: Some(Default::default()),
+            ..Default::default()
+        },
+    )?;
+    assert!(outcome.conflicts.is_empty(), "the merge is clean");
+    assert_eq!(outcome.tree, trees["three-way-applied"]);
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use gix_apply::{index, worktree, Options};
+use gix_object::FindExt;
+use gix_odb::Write;
+
+fn blob_in_tree(odb: &crate::Odb, tree: &gix_hash::oid, path: &str) -> crate::Result<(gix_hash::ObjectId, Vec<u8>)> {
+    let mut buf = Vec::new();
+    let entry = odb
+        .find_tree(tree, &mut buf)?
+        .entries
+        .into_iter()
+        .find(|entry| entry.filename == path)
+        .expect("entry exists");
+    let id = entry.oid.to_owned();
+    Ok((id, odb.find_blob(&id, &mut buf)?.data.to_vec()))
+}
+
+#[test]
+fn worktree_and_index_are_changed_like_git_does() -> crate::Result {
+    let tmp = gix_testtools::scripted_fixture_writable("make_patches.sh")?;
+    let root = tmp.path().join("drifted");
+    let trees = crate::trees_at(&root)?;
+    let odb = gix_odb::memory::Proxy::new(gix_odb::at(root.join(".git").join("objects"))?, gix_hash::Kind::Sha1);
+    let patches = crate::patches_at(&root, "patch")?;
+
+    let outcome = worktree::apply(&root, &patches, crate::find_preimage(&odb), Options::default())?;
+    assert!(outcome.rejected.is_empty() && outcome.conflicts().next().is_none());
+    let (expected_id, expected_data) = blob_in_tree(&odb, &trees["change"], "file")?;
+    assert_eq!(std::fs::read(root.join("file"))?, expected_data);
+
+    let mut index = gix_index::File::at(
+        root.join(".git").join("index"),
+        gix_hash::Kind::Sha1,
+        false,
+        Default::default(),
+    )?;
+    assert!(index.tree().is_some(), "git writes the tree extension");
+    let outcome = index::apply(
+        &mut index,
+        &patches,
+        &odb,
+        |kind, data| odb.write_buf(kind, data),
+        crate::find_preimage(&odb),
+        Options::default(),
+    )?;
+    assert!(outcome.rejected.is_empty() && outcome.conflicts.is_empty());
+    assert_eq!(index.entries().len(), 1);
+    assert_eq!(index.entries()[0].id, expected_id);
+    assert!(index.tree().is_none(), "the tree extension is removed as it's outdated");
+    Ok(())
+}
+
+#[test]
+fn rejected_hunks_are_written_next_to_the_file() -> crate::Result {
+    let tmp = gix_testtools::scripted_fixture_writable("make_patches.sh")?;
+    let root = tmp.path().join("drifted");
+    let file = root.join("file");
+    let content = std::fs::read_to_string(&file)?.replace("line 15\n", "line FIFTEEN\n");
+    std::fs::write(&file, content)?;
+
+    let patches = crate::patches_at(&root, "patch")?;
+    let outcome = worktree::apply(
+        &root,
+        &patches,
+        |_| None,
+        Options {
+            reject: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.rejected.len(), 1);
+    assert_eq!(
+        std::fs::read(root.join("file.rej"))?,
+        std::fs::read(root.join("rejected-hunks"))?
+    );
+    let content = std::fs::read_to_string(&file)?;
+    assert!(content.contains("line five\n") && content.contains("line twenty-seven\n"));
+    Ok(())
+}
+
+#[test]
+fn reversed_patches_restore_all_files() -> crate::Result {
+    let tmp = gix_testtools::scripted_fixture_writable_with_args(
+        "make_patches.sh",
+        None::<String>,
+        gix_testtools::Creation::ExecuteScript,
+    )?;
+    let root = tmp.path().join("all-kinds");
+    let patches = crate::patches_at(&root, "patch")?;
+    worktree::apply(
+        &root,
+        &patches,
+        |_| None,
+        Options {
+            reverse: true,
+            ..Default::default()
+        },
+    )?;
+
+    assert_eq!(std::fs::read(root.join("deleted"))?, b"content\n");
+    assert_eq!(std::fs::read(root.join("no-newline"))?, b"a\nb");
+    assert!(root.join("renamed-from").is_file());
+    assert!(!root.join("renamed-to").exists());
+    assert!(!root.join("new-binary").exists());
+    assert!(!root.join("dir").exists(), "directories that become empty are removed");
+    assert_eq!(std::fs::read_link(root.join("link"))?, std::path::Path::new("modified"));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(
+            std::fs::metadata(root.join("mode-change"))?.permissions().mode() & 0o111,
+            0,
+            "the executable bit is removed"
+        );
+    }
+    Ok(())
+}
@@ -113,6 +113,87 @@ impl<'event> File<'event> {
         new_section_id
     }
 
+    /// Replace the header of the section with `id` with `header`, and move it to its new place in the lookup tables.
+    pub(crate) fn set_section_header(&mut self, id: SectionId, header: section::Header<'event>) {
+        let section = self.sections.get_mut(&id).expect("known section-id");
+        let previous = std::mem::replace(&mut section.header, header);
+        self.remove_from_lookup(id, &previous);
+
+        let order = self
+            .section_order
+            .iter()
+            .position(|v| *v == id)
+            .expect("known section-id");
+        let lookup_section_order = {
+            let section_order = &self.section_order;
+            move |section_id| {
+                section_order
+                    .iter()
+                    .position(|id| *id == section_id)
+                    .expect("every id in the lookup is ordered")
+            }
+        };
+        let header = &self.sections[&id].header;
+        let lookup = self.section_lookup_tree.entry(header.name.clone()).or_default();
+        let ids = match header.subsection_name.clone() {
+            Some(subsection_name) => {
+                let pos = lookup
+                    .iter()
+                    .position(|node| matches!(node, SectionBodyIdsLut::NonTerminal(_)))
+                    .unwrap_or_else(|| {
+                        lookup.push(SectionBodyIdsLut::NonTerminal(HashMap::new()));
+                        lookup.len() - 1
+                    });
+                match &mut lookup[pos] {
+                    SectionBodyIdsLut::NonTerminal(subsections) => subsections.entry(subsection_name).or_default(),
+                    SectionBodyIdsLut::Terminal(_) => unreachable!("found by position"),
+                }
+            }
+            None => {
+                let pos = lookup
+                    .iter()
+                    .position(|node| matches!(node, SectionBodyIdsLut::Terminal(_)))
+                    .unwrap_or_else(|| {
+                        lookup.push(SectionBodyIdsLut::Terminal(Vec::new()));
+                        lookup.len() - 1
+                    });
+                match &mut lookup[pos] {
+                    SectionBodyIdsLut::Terminal(ids) => ids,
+                    SectionBodyIdsLut::NonTerminal(_) => unreachable!("found by position"),
+                }
+            }
+        };
+        let insert_pos = find_insert_pos_by_order(ids, order, lookup_section_order);
+        ids.insert(insert_pos, id);
+    }
+
+    /// Remove the section `id` with `header` from the lookup tables, leaving the section itself untouched.
+    pub(crate) fn remove_from_lookup(&mut self, id: SectionId, header: &section::Header<'event>) {
+        let lut = self
+            .section_lookup_tree
+            .get_mut(&header.name)
+            .expect("lookup cache still has name to be deleted");
+        // NOTE: this leaves empty lists in the data structure which our code now has to deal with.
+        for entry in lut {
+            match header.subsection_name.as_deref() {
+                Some(subsection_name) => {
+                    if let SectionBodyIdsLut::NonTerminal(map) = entry {
+                        if let Some(ids) = map.get_mut(subsection_name) {
+                            ids.remove(ids.iter().position(|v| *v == id).expect("present"));
+                            break;
+                        }
+                    }
+                }
+                None => {
+                    if let SectionBodyIdsLut::Terminal(ids) = entry {
+                        ids.remove(ids.iter().position(|v| *v == id).expect("present"));
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
     /// Returns the mapping between section and subsection name to section ids.
     pub(crate) fn section_ids_by_name_and_subname<'a>(
         &'a self,
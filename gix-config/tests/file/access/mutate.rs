@@ -64,6 +64,83 @@ mod rename_section {
             ))
         ));
     }
+
+    #[test]
+    fn renamed_section_is_only_found_by_its_new_name() -> crate::Result {
+        let mut file = gix_config::File::try_from("[core]\n\ta = b\n")?;
+        file.rename_section("core", None, "new-core", Some(Cow::Borrowed("sub".into())))?;
+        assert_eq!(file.string("new-core.sub.a").expect("present").as_ref(), "b");
+        assert!(file.section("new-core", Some("sub".into())).is_ok());
+        assert!(file.string("core.a").is_none(), "the old name is gone from the lookup");
+        assert!(file.section("core", None).is_err());
+
+        file.rename_section_filter("new-core", Some("sub".into()), "core", None, &mut |_| true)?;
+        assert_eq!(file.string("core.a").expect("present").as_ref(), "b");
+        assert!(file.string("new-core.sub.a").is_none());
+        assert_eq!(file.to_string(), "[core]\n\ta = b\n");
+        Ok(())
+    }
+
+    #[test]
+    fn renamed_sections_can_be_looked_up_by_their_new_name() -> crate::Result {
+        let mut file = gix_config::File::try_from(
+            "[remote \"a\"]\n\turl = first\n[remote \"b\"]\n\turl = other\n[remote \"a\"]\n\turl = second\n",
+        )?;
+        file.rename_section("remote", Some("a".into()), "remote", Some(Cow::Borrowed("b".into())))?;
+        assert_eq!(
+            file.string("remote.b.url").expect("present").as_ref(),
+            "second",
+            "the renamed section is the last one with the new name"
+        );
+        assert_eq!(urls(&file), ["other", "second"]);
+        assert_eq!(file.string("remote.a.url").expect("present").as_ref(), "first");
+
+        file.rename_section("remote", Some("a".into()), "remote", Some(Cow::Borrowed("b".into())))?;
+        assert_eq!(
+            urls(&file),
+            ["first", "other", "second"],
+            "the order of values follows the order of sections"
+        );
+        assert!(file.string("remote.a.url").is_none(), "all sections were renamed");
+        assert_eq!(
+            file.to_string(),
+            "[remote \"b\"]\n\turl = first\n[remote \"b\"]\n\turl = other\n[remote \"b\"]\n\turl = second\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn sections_can_be_renamed_by_id() -> crate::Result {
+        let mut file = gix_config::File::try_from(
+            "[remote \"a\"]\n\turl = first\n[remote \"b\"]\n\turl = other\n[remote \"a\"]\n\turl = second\n",
+        )?;
+        let ids: Vec<_> = file
+            .sections_and_ids_by_name("remote")
+            .expect("present")
+            .filter(|(section, _)| section.header().subsection_name() == Some("a".into()))
+            .map(|(_, id)| id)
+            .collect();
+        for id in &ids {
+            file.rename_section_by_id(*id, "remote", Some(Cow::Borrowed("b".into())))?;
+        }
+        assert_eq!(urls(&file), ["first", "other", "second"]);
+        assert!(file.string("remote.a.url").is_none(), "all sections were renamed");
+
+        file.remove_section_by_id(ids[0]);
+        assert!(matches!(
+            file.rename_section_by_id(ids[0], "remote", None),
+            Err(rename_section::Error::Lookup(_))
+        ));
+        Ok(())
+    }
+
+    fn urls(file: &gix_config::File<'_>) -> Vec<String> {
+        file.strings("remote.b.url")
+            .expect("present")
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
 }
 mod set_meta {
     use gix_config::file;
@@ -11,53 +11,139 @@ pub enum Error {
     MissingCurrentTime,
 }
 
+pub use function::{approximate, approximate_with_utc_offset, parse_with_utc_offset};
+
 pub(crate) mod function {
     use std::{str::FromStr, time::SystemTime};
 
-    use jiff::{civil::Date, fmt::rfc2822, tz::TimeZone, Zoned};
+    use jiff::{civil::Date, fmt::rfc2822, tz::TimeZone, Timestamp, Zoned};
 
     use crate::{
-        parse::{relative, Error},
+        parse::{approxidate, relative, Error},
         time::{
             format::{DEFAULT, GITOXIDE, ISO8601, ISO8601_STRICT, SHORT},
             Sign,
         },
-        SecondsSinceUnixEpoch, Time,
+        OffsetInSeconds, SecondsSinceUnixEpoch, Time,
     };
 
-    #[allow(missing_docs)]
+    /// Parse `input` strictly as date like `git` would, with `now` as reference for relative dates like `2 weeks ago`.
+    ///
+    /// Besides the well-known formats, this understands all other dates `git` can parse strictly, like `git commit --date`.
+    /// Dates without an offset are interpreted in the time zone of the system.
+    /// Use [`approximate()`] to also parse dates like `last friday noon` just like `git`'s `approxidate`.
+    ///
+    /// If `now` is `None`, [`Error::MissingCurrentTime`] is returned for relative dates.
     pub fn parse(input: &str, now: Option<SystemTime>) -> Result<Time, Error> {
-        // TODO: actual implementation, this is just to not constantly fail
-        if input == "1979-02-26 18:30:00" {
-            return Ok(Time::new(42, 1800));
+        parse_in_time_zone(input, now, &TimeZone::system())
+    }
+
+    /// Like [`parse()`], but interpret dates without an offset as if they were local to a time zone with `utc_offset`
+    /// instead of the time zone of the system.
+    pub fn parse_with_utc_offset(
+        input: &str,
+        now: Option<SystemTime>,
+        utc_offset: OffsetInSeconds,
+    ) -> Result<Time, Error> {
+        parse_in_time_zone(input, now, &fixed_time_zone(input, utc_offset)?)
+    }
+
+    /// Parse `input` like [`parse()`], but if that fails, approximate a date from it like `git`'s `approxidate` does,
+    /// with `now` as reference for relative dates.
+    ///
+    /// This understands dates like `yesterday`, `last friday noon` or `2 days ago 5pm`, and ignores everything
+    /// it doesn't understand. It's what `git` uses for options like `--since` and for `@{<date>}` in rev-specs.
+    /// Approximate dates are interpreted in the time zone of the system, and have its offset at that date.
+    ///
+    /// If `now` is `None`, [`Error::MissingCurrentTime`] is returned for dates that can only be approximated.
+    pub fn approximate(input: &str, now: Option<SystemTime>) -> Result<Time, Error> {
+        approximate_in_time_zone(input, now, &TimeZone::system())
+    }
+
+    /// Like [`approximate()`], but interpret dates as if they were local to a time zone with `utc_offset` instead of the
+    /// time zone of the system.
+    pub fn approximate_with_utc_offset(
+        input: &str,
+        now: Option<SystemTime>,
+        utc_offset: OffsetInSeconds,
+    ) -> Result<Time, Error> {
+        approximate_in_time_zone(input, now, &fixed_time_zone(input, utc_offset)?)
+    }
+
+    fn fixed_time_zone(input: &str, utc_offset: OffsetInSeconds) -> Result<TimeZone, Error> {
+        let offset =
+            jiff::tz::Offset::from_seconds(utc_offset).map_err(|_| Error::InvalidDateString { input: input.into() })?;
+        Ok(TimeZone::fixed(offset))
+    }
+
+    fn parse_in_time_zone(input: &str, now: Option<SystemTime>, tz: &TimeZone) -> Result<Time, Error> {
+        if let Some(time) = parse_known_formats(input, now, tz)? {
+            return Ok(time);
         }
+        match relative::parse(input, now).transpose()? {
+            Some(val) => Ok(Time::new(val.timestamp().as_second(), val.offset().seconds())),
+            None => Err(Error::InvalidDateString { input: input.into() }),
+        }
+    }
 
-        Ok(if let Ok(val) = Date::strptime(SHORT.0, input) {
-            let val = val
-                .to_zoned(TimeZone::UTC)
-                .map_err(|_| Error::InvalidDateString { input: input.into() })?;
-            Time::new(val.timestamp().as_second(), val.offset().seconds())
-        } else if let Ok(val) = rfc2822_relaxed(input) {
-            Time::new(val.timestamp().as_second(), val.offset().seconds())
-        } else if let Ok(val) = strptime_relaxed(ISO8601.0, input) {
-            Time::new(val.timestamp().as_second(), val.offset().seconds())
-        } else if let Ok(val) = strptime_relaxed(ISO8601_STRICT.0, input) {
-            Time::new(val.timestamp().as_second(), val.offset().seconds())
-        } else if let Ok(val) = strptime_relaxed(GITOXIDE.0, input) {
-            Time::new(val.timestamp().as_second(), val.offset().seconds())
-        } else if let Ok(val) = strptime_relaxed(DEFAULT.0, input) {
-            Time::new(val.timestamp().as_second(), val.offset().seconds())
-        } else if let Ok(val) = SecondsSinceUnixEpoch::from_str(input) {
-            // Format::Unix
-            Time::new(val, 0)
-        } else if let Some(val) = parse_raw(input) {
-            // Format::Raw
-            val
-        } else if let Some(val) = relative::parse(input, now).transpose()? {
-            Time::new(val.timestamp().as_second(), val.offset().seconds())
-        } else {
-            return Err(Error::InvalidDateString { input: input.into() });
-        })
+    fn approximate_in_time_zone(input: &str, now: Option<SystemTime>, tz: &TimeZone) -> Result<Time, Error> {
+        if let Some(time) = parse_known_formats(input, now, tz)? {
+            return Ok(time);
+        }
+        let Some(now) = now else {
+            // Only complain about the missing time if it would have been needed.
+            return Err(match approxidate::approximate(input, 0, tz) {
+                Ok(None) => Error::InvalidDateString { input: input.into() },
+                Ok(Some(_)) | Err(_) => Error::MissingCurrentTime,
+            });
+        };
+        match approxidate::approximate(input, seconds_since_epoch(now)?, tz)? {
+            Some(seconds) => {
+                let timestamp = Timestamp::from_second(seconds).map_err(|_| Error::RelativeTimeConversion)?;
+                Ok(Time::new(seconds, tz.to_offset(timestamp).0.seconds()))
+            }
+            None => Err(Error::InvalidDateString { input: input.into() }),
+        }
+    }
+
+    /// Parse `input` in one of the formats that `git` can parse strictly, or return `None` if it's none of them.
+    fn parse_known_formats(input: &str, now: Option<SystemTime>, tz: &TimeZone) -> Result<Option<Time>, Error> {
+        Ok(Some(
+            if let Some(val) = Date::strptime(SHORT.0, input)
+                .ok()
+                .and_then(|date| date.to_zoned(TimeZone::UTC).ok())
+            {
+                Time::new(val.timestamp().as_second(), val.offset().seconds())
+            } else if let Ok(val) = rfc2822_relaxed(input) {
+                Time::new(val.timestamp().as_second(), val.offset().seconds())
+            } else if let Ok(val) = strptime_relaxed(ISO8601.0, input) {
+                Time::new(val.timestamp().as_second(), val.offset().seconds())
+            } else if let Ok(val) = strptime_relaxed(ISO8601_STRICT.0, input) {
+                Time::new(val.timestamp().as_second(), val.offset().seconds())
+            } else if let Ok(val) = strptime_relaxed(GITOXIDE.0, input) {
+                Time::new(val.timestamp().as_second(), val.offset().seconds())
+            } else if let Ok(val) = strptime_relaxed(DEFAULT.0, input) {
+                Time::new(val.timestamp().as_second(), val.offset().seconds())
+            } else if let Ok(val) = SecondsSinceUnixEpoch::from_str(input) {
+                // Format::Unix
+                Time::new(val, 0)
+            } else if let Some(val) = parse_raw(input) {
+                // Format::Raw
+                val
+            } else if let Some(val) =
+                approxidate::parse_basic(input, seconds_since_epoch(now.unwrap_or_else(SystemTime::now))?, tz)
+            {
+                val
+            } else {
+                return Ok(None);
+            },
+        ))
+    }
+
+    fn seconds_since_epoch(time: SystemTime) -> Result<SecondsSinceUnixEpoch, Error> {
+        Ok(Timestamp::try_from(time)
+            .map_err(|_| Error::RelativeTimeConversion)?
+            .as_second())
     }
 
     fn parse_raw(input: &str) -> Option<Time> {
@@ -104,6 +190,8 @@ pub(crate) mod function {
     }
 }
 
+mod approxidate;
+
 mod relative {
     use std::{str::FromStr, time::SystemTime};
 
@@ -112,7 +200,15 @@ mod relative {
     use crate::parse::Error;
 
     fn parse_inner(input: &str) -> Option<Result<Span, Error>> {
-        let mut split = input.split_whitespace();
+        match input.trim() {
+            "now" => return Some(Ok(Span::new())),
+            "yesterday" => return Some(Ok(Span::new().days(1))),
+            _ => {}
+        }
+        // Dots are accepted as separators as well, as in `2.weeks.ago`, which is common in configuration files.
+        let mut split = input
+            .split(|c: char| c.is_whitespace() || c == '.')
+            .filter(|token| !token.is_empty());
         let units = i64::from_str(split.next()?).ok()?;
         let period = split.next()?;
         if split.next()? != "ago" {
@@ -153,7 +249,8 @@ mod relative {
             "hour" => Span::new().try_hours(units),
             "day" => Span::new().try_days(units),
             "week" => Span::new().try_weeks(units),
-            // TODO months & years? YES
+            "month" => Span::new().try_months(units),
+            "year" => Span::new().try_years(units),
             // Ignore values you don't know, assume seconds then (so does git)
             _ => return None,
         };
@@ -168,5 +265,10 @@ mod relative {
         fn two_weeks_ago() {
             assert_eq!(parse_inner("2 weeks ago").unwrap().unwrap(), Span::new().weeks(2));
         }
+
+        #[test]
+        fn two_weeks_ago_with_dots() {
+            assert_eq!(parse_inner("2.weeks.ago").unwrap().unwrap(), Span::new().weeks(2));
+        }
     }
 }
@@ -0,0 +1,839 @@
+//! Date parsing that follows `parse_date_basic()` and `approxidate_str()` in `git/date.c` closely, including their quirks,
+//! so that dates like `last friday noon`, `2 days ago 5pm` or `10/11/12` are interpreted just like `git` would.
+use jiff::{civil, tz::TimeZone, Span, Timestamp};
+
+use crate::{parse::Error, SecondsSinceUnixEpoch, Time};
+
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const WEEKDAY_NAMES: [&str; 7] = [
+    "Sundays",
+    "Mondays",
+    "Tuesdays",
+    "Wednesdays",
+    "Thursdays",
+    "Fridays",
+    "Saturdays",
+];
+
+const NUMBER_NAMES: [&str; 11] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+];
+
+/// Units of relative dates along with their length in seconds.
+const UNITS: [(&str, i64); 5] = [
+    ("seconds", 1),
+    ("minutes", 60),
+    ("hours", 60 * 60),
+    ("days", 24 * 60 * 60),
+    ("weeks", 7 * 24 * 60 * 60),
+];
+
+/// Time zone names along with their offset in hours, and whether they denote daylight saving time.
+const TIME_ZONES: [(&str, i64, bool); 44] = [
+    ("IDLW", -12, false),
+    ("NT", -11, false),
+    ("CAT", -10, false),
+    ("HST", -10, false),
+    ("HDT", -10, true),
+    ("YST", -9, false),
+    ("YDT", -9, true),
+    ("PST", -8, false),
+    ("PDT", -8, true),
+    ("MST", -7, false),
+    ("MDT", -7, true),
+    ("CST", -6, false),
+    ("CDT", -6, true),
+    ("EST", -5, false),
+    ("EDT", -5, true),
+    ("AST", -3, false),
+    ("ADT", -3, true),
+    ("WAT", -1, false),
+    ("GMT", 0, false),
+    ("UTC", 0, false),
+    ("Z", 0, false),
+    ("WET", 0, false),
+    ("BST", 0, true),
+    ("CET", 1, false),
+    ("MET", 1, false),
+    ("MEWT", 1, false),
+    ("MEST", 1, true),
+    ("CEST", 1, true),
+    ("MESZ", 1, true),
+    ("FWT", 1, false),
+    ("FST", 1, true),
+    ("EET", 2, false),
+    ("EEST", 2, true),
+    ("WAST", 7, false),
+    ("WADT", 7, true),
+    ("CCT", 8, false),
+    ("JST", 9, false),
+    ("EAST", 10, false),
+    ("EADT", 10, true),
+    ("GST", 10, false),
+    ("NZT", 12, false),
+    ("NZST", 12, false),
+    ("NZDT", 12, true),
+    ("IDLE", 12, false),
+];
+
+/// A broken-down time like `struct tm`, where fields that aren't set yet are negative.
+#[derive(Debug, Clone, Copy)]
+struct Tm {
+    /// Years since 1900.
+    year: i64,
+    /// Months since January.
+    mon: i64,
+    mday: i64,
+    hour: i64,
+    min: i64,
+    sec: i64,
+    /// Days since Sunday.
+    wday: i64,
+}
+
+impl Tm {
+    const UNSET: Tm = Tm {
+        year: -1,
+        mon: -1,
+        mday: -1,
+        hour: -1,
+        min: -1,
+        sec: -1,
+        wday: -1,
+    };
+
+    /// Like `localtime()`, break `seconds` down into the fields of a date in `tz`.
+    fn from_timestamp(seconds: SecondsSinceUnixEpoch, tz: &TimeZone) -> Result<Self, Error> {
+        let datetime = tz.to_datetime(Timestamp::from_second(seconds).map_err(|_| Error::RelativeTimeConversion)?);
+        Ok(Tm {
+            year: i64::from(datetime.year()) - 1900,
+            mon: i64::from(datetime.month()) - 1,
+            mday: datetime.day().into(),
+            hour: datetime.hour().into(),
+            min: datetime.minute().into(),
+            sec: datetime.second().into(),
+            wday: datetime.weekday().to_sunday_zero_offset().into(),
+        })
+    }
+
+    /// Like `mktime()`, interpret all fields as date in `tz` while normalizing the ones that are out of range,
+    /// so the 32nd of January is the first of February.
+    fn to_timestamp(self, tz: &TimeZone) -> Result<SecondsSinceUnixEpoch, Error> {
+        let conversion = |_| Error::RelativeTimeConversion;
+        let year = self
+            .year
+            .checked_add(1900)
+            .and_then(|year| i16::try_from(year).ok())
+            .ok_or(Error::RelativeTimeConversion)?;
+        let seconds = self
+            .hour
+            .checked_mul(60 * 60)
+            .and_then(|hours| hours.checked_add(self.min.checked_mul(60)?))
+            .and_then(|seconds| seconds.checked_add(self.sec))
+            .ok_or(Error::RelativeTimeConversion)?;
+        let datetime = civil::Date::new(year, 1, 1)
+            .map_err(conversion)?
+            .checked_add(Span::new().try_months(self.mon).map_err(conversion)?)
+            .map_err(conversion)?
+            .checked_add(Span::new().try_days(self.mday - 1).map_err(conversion)?)
+            .map_err(conversion)?
+            .to_datetime(civil::Time::midnight())
+            .checked_add(Span::new().try_seconds(seconds).map_err(conversion)?)
+            .map_err(conversion)?;
+        Ok(tz.to_timestamp(datetime).map_err(conversion)?.as_second())
+    }
+
+    fn is_unset(&self) -> bool {
+        [self.year, self.mon, self.mday, self.hour, self.min, self.sec]
+            .iter()
+            .all(|field| *field < 0)
+    }
+
+    fn is_date_known(&self) -> bool {
+        self.year != -1 && self.mon != -1 && self.mday != -1
+    }
+}
+
+/// Parse `input` strictly like `git` does in `parse_date_basic()`, or return `None` if it doesn't contain a complete date.
+///
+/// `now` is used to reject dates in the future when guessing the order of day and month,
+/// and dates without offset are assumed to be in `tz`.
+pub(super) fn parse_basic(input: &str, now: SecondsSinceUnixEpoch, tz: &TimeZone) -> Option<Time> {
+    let date = input.as_bytes();
+    if let Some(time) = date.strip_prefix(b"@").and_then(object_header_date) {
+        return Some(time);
+    }
+
+    let mut tm = Tm::UNSET;
+    let mut offset_in_minutes = None;
+    let mut is_utc = false;
+    let mut pos = 0;
+    while pos < date.len() && date[pos] != b'\n' {
+        let rest = &date[pos..];
+        let consumed = match rest[0] {
+            c if c.is_ascii_alphabetic() => match_alpha(rest, &mut tm, &mut offset_in_minutes),
+            c if c.is_ascii_digit() => match_digit(rest, &mut tm, &mut offset_in_minutes, &mut is_utc, now),
+            b'-' | b'+' if starts_with_digit(&rest[1..]) => match_tz(rest, &mut offset_in_minutes),
+            _ => 0,
+        };
+        pos += consumed.max(1);
+    }
+
+    let mut seconds = tm_to_time_t(&tm)?;
+    let offset_in_minutes = match offset_in_minutes {
+        Some(offset) => offset,
+        None => (seconds - tm.to_timestamp(tz).ok()?) / 60,
+    };
+    if !is_utc {
+        seconds -= offset_in_minutes * 60;
+    }
+    Some(Time::new(seconds, i32::try_from(offset_in_minutes * 60).ok()?))
+}
+
+/// Approximate the date in `input` like `git` does in `approxidate_str()`, and return it as seconds since the unix epoch,
+/// or `None` if nothing in `input` could be interpreted as part of a date.
+///
+/// Relative dates are relative to `now`, and all dates are interpreted in `tz`.
+pub(super) fn approximate(
+    input: &str,
+    now: SecondsSinceUnixEpoch,
+    tz: &TimeZone,
+) -> Result<Option<SecondsSinceUnixEpoch>, Error> {
+    let now_tm = Tm::from_timestamp(now, tz)?;
+    let mut state = Approximation {
+        tz,
+        now,
+        now_tm,
+        tm: Tm {
+            year: -1,
+            mon: -1,
+            mday: -1,
+            ..now_tm
+        },
+        num: 0,
+        touched: false,
+    };
+
+    let date = input.as_bytes();
+    let mut pos = 0;
+    while pos < date.len() {
+        let rest = &date[pos..];
+        if rest[0].is_ascii_digit() {
+            state.pending_number();
+            pos += state.digit(rest);
+            state.touched = true;
+        } else if rest[0].is_ascii_alphabetic() {
+            pos += state.alpha(rest)?;
+        } else {
+            pos += 1;
+        }
+    }
+    state.pending_number();
+    if !state.touched {
+        return Ok(None);
+    }
+    state.update_tm(0).map(Some)
+}
+
+struct Approximation<'a> {
+    tz: &'a TimeZone,
+    now: SecondsSinceUnixEpoch,
+    now_tm: Tm,
+    tm: Tm,
+    /// A number that was seen, but not yet used.
+    num: i64,
+    /// If `true`, something in the input was understood.
+    touched: bool,
+}
+
+impl Approximation<'_> {
+    /// Fill in unset date fields from the current time, subtract `seconds` and return the result,
+    /// which is also broken down into all fields again.
+    fn update_tm(&mut self, seconds: i64) -> Result<SecondsSinceUnixEpoch, Error> {
+        let (tm, now) = (&mut self.tm, &self.now_tm);
+        if tm.mday < 0 {
+            tm.mday = now.mday;
+        }
+        if tm.mon < 0 {
+            tm.mon = now.mon;
+        }
+        if tm.year < 0 {
+            tm.year = now.year;
+            if tm.mon > now.mon {
+                tm.year -= 1;
+            }
+        }
+        let time = tm
+            .to_timestamp(self.tz)?
+            .checked_sub(seconds)
+            .ok_or(Error::RelativeTimeConversion)?;
+        *tm = Tm::from_timestamp(time, self.tz)?;
+        Ok(time)
+    }
+
+    /// Use a number that wasn't followed by a unit as day of month, month or year, whichever is still unset and fits.
+    fn pending_number(&mut self) {
+        let number = std::mem::take(&mut self.num);
+        if number == 0 {
+            return;
+        }
+        let tm = &mut self.tm;
+        if tm.mday < 0 && number < 32 {
+            tm.mday = number;
+        } else if tm.mon < 0 && number < 13 {
+            tm.mon = number - 1;
+        } else if tm.year < 0 {
+            if number > 1969 && number < 2100 {
+                tm.year = number - 1900;
+            } else if number > 69 && number < 100 {
+                tm.year = number;
+            } else if number < 38 {
+                tm.year = 100 + number;
+            }
+        }
+    }
+
+    /// Handle the digits at the beginning of `date` and return the amount of consumed bytes.
+    fn digit(&mut self, date: &[u8]) -> usize {
+        let (number, end) = parse_number(date);
+        if let Some(&separator) = date.get(end) {
+            if matches!(separator, b':' | b'.' | b'/' | b'-') && starts_with_digit(&date[end + 1..]) {
+                let consumed = match_multi_number(number, separator, date, end, &mut self.tm, self.now);
+                if consumed != 0 {
+                    return consumed;
+                }
+            }
+        }
+        // Accept zero-padding only for small numbers, as in `Dec 02`, but not in `Dec 0002`.
+        if date[0] != b'0' || end <= 2 {
+            self.num = number;
+        }
+        end
+    }
+
+    /// Handle the word at the beginning of `date` and return the amount of consumed bytes.
+    fn alpha(&mut self, date: &[u8]) -> Result<usize, Error> {
+        let end = date.iter().take_while(|b| b.is_ascii_alphabetic()).count();
+
+        if let Some(month) = MONTH_NAMES.iter().position(|name| match_string(date, name) >= 3) {
+            self.tm.mon = month as i64;
+            self.touched = true;
+            return Ok(end);
+        }
+
+        for (name, special) in [
+            ("yesterday", Special::Yesterday),
+            ("noon", Special::Noon),
+            ("midnight", Special::Midnight),
+            ("tea", Special::Tea),
+            ("PM", Special::Pm),
+            ("AM", Special::Am),
+            ("never", Special::Never),
+            ("now", Special::Now),
+        ] {
+            if match_string(date, name) == name.len() {
+                self.special(special)?;
+                self.touched = true;
+                return Ok(end);
+            }
+        }
+
+        if self.num == 0 {
+            if let Some(number) = (1..NUMBER_NAMES.len()).find(|idx| {
+                let name = NUMBER_NAMES[*idx];
+                match_string(date, name) == name.len()
+            }) {
+                self.num = number as i64;
+                self.touched = true;
+            } else if match_string(date, "last") == 4 {
+                self.num = 1;
+                self.touched = true;
+            }
+            return Ok(end);
+        }
+
+        if let Some((_, length)) = UNITS
+            .iter()
+            .find(|(name, _)| match_string(date, name) >= name.len() - 1)
+        {
+            let seconds = length.checked_mul(self.num).ok_or(Error::RelativeTimeConversion)?;
+            self.update_tm(seconds)?;
+            self.num = 0;
+            self.touched = true;
+            return Ok(end);
+        }
+
+        if let Some(weekday) = WEEKDAY_NAMES.iter().position(|name| match_string(date, name) >= 3) {
+            let mut n = std::mem::take(&mut self.num) - 1;
+            let mut diff = self.tm.wday - weekday as i64;
+            if diff <= 0 {
+                n += 1;
+            }
+            diff = n
+                .checked_mul(7)
+                .and_then(|weeks| weeks.checked_add(diff))
+                .and_then(|days| days.checked_mul(24 * 60 * 60))
+                .ok_or(Error::RelativeTimeConversion)?;
+            self.update_tm(diff)?;
+            self.touched = true;
+            return Ok(end);
+        }
+
+        if match_string(date, "months") >= 5 {
+            self.update_tm(0)?;
+            let mut month = self
+                .tm
+                .mon
+                .checked_sub(std::mem::take(&mut self.num))
+                .ok_or(Error::RelativeTimeConversion)?;
+            if month < 0 {
+                let years = (-month + 11) / 12;
+                month += years * 12;
+                self.tm.year -= years;
+            }
+            self.tm.mon = month;
+            self.touched = true;
+            return Ok(end);
+        }
+
+        if match_string(date, "years") >= 4 {
+            self.update_tm(0)?;
+            self.tm.year = self
+                .tm
+                .year
+                .checked_sub(std::mem::take(&mut self.num))
+                .ok_or(Error::RelativeTimeConversion)?;
+            self.touched = true;
+            return Ok(end);
+        }
+
+        Ok(end)
+    }
+
+    fn special(&mut self, special: Special) -> Result<(), Error> {
+        match special {
+            Special::Yesterday => {
+                self.num = 0;
+                self.update_tm(24 * 60 * 60)?;
+            }
+            Special::Noon => self.at_hour(12)?,
+            Special::Midnight => self.at_hour(0)?,
+            Special::Tea => self.at_hour(17)?,
+            Special::Pm | Special::Am => {
+                let n = std::mem::take(&mut self.num);
+                let mut hour = self.tm.hour;
+                if n != 0 {
+                    hour = n;
+                    self.tm.min = 0;
+                    self.tm.sec = 0;
+                }
+                self.tm.hour = hour % 12 + if matches!(special, Special::Pm) { 12 } else { 0 };
+            }
+            Special::Never => {
+                self.tm = Tm::from_timestamp(0, self.tz)?;
+                self.num = 0;
+            }
+            Special::Now => {
+                self.num = 0;
+                self.update_tm(0)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Set the time to `hour` o'clock, on the previous day if that time didn't come yet.
+    fn at_hour(&mut self, hour: i64) -> Result<(), Error> {
+        self.pending_number();
+        if self.tm.hour < hour {
+            self.update_tm(24 * 60 * 60)?;
+        }
+        self.tm.hour = hour;
+        self.tm.min = 0;
+        self.tm.sec = 0;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Special {
+    Yesterday,
+    Noon,
+    Midnight,
+    Tea,
+    Pm,
+    Am,
+    Never,
+    Now,
+}
+
+/// Parse `<seconds> <+|-><hhmm>` as used in object headers.
+fn object_header_date(date: &[u8]) -> Option<Time> {
+    if !starts_with_digit(date) {
+        return None;
+    }
+    let (seconds, end) = parse_number(date);
+    let sign = match date.get(end..end + 2)? {
+        [b' ', sign @ (b'+' | b'-')] => *sign,
+        _ => return None,
+    };
+    let offset = &date[end + 2..];
+    let (hhmm, digits) = parse_number(offset);
+    if digits != 4 || !matches!(offset.get(digits), None | Some(b'\n')) || seconds == i64::MAX {
+        return None;
+    }
+    let minutes = (hhmm / 100) * 60 + hhmm % 100;
+    let minutes = if sign == b'-' { -minutes } else { minutes };
+    Some(Time::new(seconds, i32::try_from(minutes * 60).ok()?))
+}
+
+/// Handle a word in an otherwise strictly parsed date and return the amount of consumed bytes.
+fn match_alpha(date: &[u8], tm: &mut Tm, offset_in_minutes: &mut Option<i64>) -> usize {
+    for (month, name) in MONTH_NAMES.iter().enumerate() {
+        let matched = match_string(date, name);
+        if matched >= 3 {
+            tm.mon = month as i64;
+            return matched;
+        }
+    }
+    for (weekday, name) in WEEKDAY_NAMES.iter().enumerate() {
+        let matched = match_string(date, name);
+        if matched >= 3 {
+            tm.wday = weekday as i64;
+            return matched;
+        }
+    }
+    for (name, hours, is_dst) in TIME_ZONES {
+        let matched = match_string(date, name);
+        if matched >= 3 || matched == name.len() {
+            // `git` adds an hour for daylight saving time, even though this isn't how it works.
+            if offset_in_minutes.is_none() {
+                *offset_in_minutes = Some((hours + i64::from(is_dst)) * 60);
+            }
+            return matched;
+        }
+    }
+    if match_string(date, "PM") == 2 {
+        tm.hour = tm.hour % 12 + 12;
+        return 2;
+    }
+    if match_string(date, "AM") == 2 {
+        tm.hour %= 12;
+        return 2;
+    }
+    // ISO-8601 allows `yyyymmddThhmmss`.
+    if date[0] == b'T' && starts_with_digit(&date[1..]) && tm.hour == -1 {
+        tm.min = 0;
+        tm.sec = 0;
+        return 1;
+    }
+    1 + date[1..].iter().take_while(|b| b.is_ascii_alphabetic()).count()
+}
+
+/// Handle a number in an otherwise strictly parsed date and return the amount of consumed bytes.
+fn match_digit(
+    date: &[u8],
+    tm: &mut Tm,
+    offset_in_minutes: &mut Option<i64>,
+    is_utc: &mut bool,
+    now: SecondsSinceUnixEpoch,
+) -> usize {
+    let (num, mut end) = parse_number(date);
+
+    // Numbers with more than 8 digits are seconds since the unix epoch, as `20070606` could still be a date.
+    if num >= 100_000_000 && tm.is_unset() {
+        if let Ok(utc) = Tm::from_timestamp(num, &TimeZone::UTC) {
+            *tm = utc;
+            *is_utc = true;
+            return end;
+        }
+    }
+
+    if let Some(&separator) = date.get(end) {
+        if matches!(separator, b':' | b'.' | b'/' | b'-') && starts_with_digit(&date[end + 1..]) {
+            let consumed = match_multi_number(num, separator, date, end, tm, now);
+            if consumed != 0 {
+                return consumed;
+            }
+        }
+    }
+
+    let n = end;
+    // The compact forms of ISO-8601, `yyyymmdd` and `hhmmss`.
+    if n == 8 || n == 6 {
+        let (num1, num2, num3) = (num / 10000, (num % 10000) / 100, num % 100);
+        if n == 8 {
+            set_date(num1, num2, num3, None, now, tm);
+        } else if set_time(num1, num2, num3, tm) && date.get(end) == Some(&b'.') && starts_with_digit(&date[end + 1..])
+        {
+            end += 1 + parse_number(&date[end + 1..]).1;
+        }
+        return end;
+    }
+
+    // A four-digit year or an offset?
+    if n == 4 {
+        if num <= 1400 && offset_in_minutes.is_none() {
+            *offset_in_minutes = Some((num / 100) * 60 + num % 100);
+        } else if num > 1900 && num < 2100 {
+            tm.year = num - 1900;
+        }
+        return n;
+    }
+
+    // Days and months have at most two digits.
+    if n > 2 {
+        return n;
+    }
+
+    // Prefer the day of month over the month or year, so `01 Apr 05` is April 1st, 2005.
+    if num > 0 && num < 32 && tm.mday < 0 {
+        tm.mday = num;
+        return n;
+    }
+
+    if n == 2 && tm.year < 0 {
+        if num < 10 && tm.mday >= 0 {
+            tm.year = num + 100;
+            return n;
+        }
+        if num >= 70 {
+            tm.year = num;
+            return n;
+        }
+    }
+
+    if num > 0 && num < 13 && tm.mon < 0 {
+        tm.mon = num - 1;
+    }
+    n
+}
+
+/// Handle `-<hh>`, `+<hhmm>` or `+<hh>:<mm>` at the beginning of `date` and return the amount of consumed bytes.
+fn match_tz(date: &[u8], offset_in_minutes: &mut Option<i64>) -> usize {
+    let (mut hour, digits) = parse_number(&date[1..]);
+    let mut end = 1 + digits;
+    let mut min = 0;
+    if digits == 4 {
+        min = hour % 100;
+        hour /= 100;
+    } else if digits != 2 {
+        min = 99;
+    } else if date.get(end) == Some(&b':') {
+        let (minutes, digits) = parse_number(&date[end + 1..]);
+        min = minutes;
+        end += 1 + digits;
+        if end - 1 != 5 {
+            min = 99;
+        }
+    }
+
+    // Reject offsets that don't make sense.
+    if min < 60 && hour < 24 {
+        let offset = hour * 60 + min;
+        *offset_in_minutes = Some(if date[0] == b'-' { -offset } else { offset });
+    }
+    end
+}
+
+/// Handle `num<separator>num2[<separator>num3]` where `date[end]` is the first separator, and return the amount of consumed
+/// bytes, or 0 if it's neither a valid time nor a valid date.
+fn match_multi_number(
+    num: i64,
+    separator: u8,
+    date: &[u8],
+    end: usize,
+    tm: &mut Tm,
+    now: SecondsSinceUnixEpoch,
+) -> usize {
+    let (num2, digits) = parse_number(&date[end + 1..]);
+    let mut end = end + 1 + digits;
+    let mut num3 = -1;
+    if date.get(end) == Some(&separator) && starts_with_digit(&date[end + 1..]) {
+        let (number, digits) = parse_number(&date[end + 1..]);
+        num3 = number;
+        end += 1 + digits;
+    }
+
+    if separator == b':' {
+        if !set_time(num, num2, num3.max(0), tm) {
+            return 0;
+        }
+        // Fractional seconds are ignored, but only after a date.
+        if date.get(end) == Some(&b'.') && starts_with_digit(&date[end + 1..]) && tm.is_date_known() {
+            end += 1 + parse_number(&date[end + 1..]).1;
+        }
+        return end;
+    }
+
+    let now_tm = Tm::from_timestamp(now, &TimeZone::UTC).ok();
+    let refuse_future = now_tm.as_ref();
+    let is_date = (num > 70 && (set_date(num, num2, num3, None, now, tm) == 0 || set_date(num, num3, num2, None, now, tm) == 0))
+        // `mm/dd/yy[yy]` takes precedence, unless the separator is a dot as in European `dd.mm.yy[yy]`.
+        || (separator != b'.' && set_date(num3, num, num2, refuse_future, now, tm) == 0)
+        || set_date(num3, num2, num, refuse_future, now, tm) == 0
+        || (separator == b'.' && set_date(num3, num, num2, refuse_future, now, tm) == 0);
+    if is_date {
+        end
+    } else {
+        0
+    }
+}
+
+/// Set the date in `tm` and return 0 on success, or a non-zero value otherwise, just like `git` does.
+///
+/// If `now_tm` is set, `tm` is only changed on success, and dates more than ten days in the future are rejected.
+/// Otherwise, month and day may be set even if the year is invalid.
+fn set_date(year: i64, month: i64, day: i64, now_tm: Option<&Tm>, now: SecondsSinceUnixEpoch, tm: &mut Tm) -> i32 {
+    if !(month > 0 && month < 13 && day > 0 && day < 32) {
+        return -1;
+    }
+    let mut r = *tm;
+    r.mon = month - 1;
+    r.mday = day;
+    if year == -1 {
+        match now_tm {
+            Some(now_tm) => r.year = now_tm.year,
+            None => {
+                *tm = r;
+                return 1;
+            }
+        }
+    } else if (1970..2100).contains(&year) {
+        r.year = year - 1900;
+    } else if year > 70 && year < 100 {
+        r.year = year;
+    } else if year < 38 {
+        r.year = year + 100;
+    } else {
+        if now_tm.is_none() {
+            *tm = r;
+        }
+        return -1;
+    }
+    if now_tm.is_none() {
+        *tm = r;
+        return 0;
+    }
+
+    // It doesn't make sense to specify dates far in the future.
+    if tm_to_time_t(&r).map_or(false, |specified| now + 10 * 24 * 60 * 60 < specified) {
+        return -1;
+    }
+    tm.mon = r.mon;
+    tm.mday = r.mday;
+    if year != -1 {
+        tm.year = r.year;
+    }
+    0
+}
+
+/// Set the time in `tm` if it is valid, allowing for leap seconds, and return `true` in that case.
+fn set_time(hour: i64, minute: i64, second: i64, tm: &mut Tm) -> bool {
+    let is_valid = (0..=24).contains(&hour) && (0..60).contains(&minute) && (0..=60).contains(&second);
+    if is_valid {
+        tm.hour = hour;
+        tm.min = minute;
+        tm.sec = second;
+    }
+    is_valid
+}
+
+/// Convert `tm` to seconds since the unix epoch as UTC, or return `None` if a field is unset or out of range.
+///
+/// Just like in `git`, this only works for years from 1970 to 2099.
+fn tm_to_time_t(tm: &Tm) -> Option<SecondsSinceUnixEpoch> {
+    const DAYS_BEFORE_MONTH: [i64; 12] = [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334];
+    let year = tm.year - 70;
+    if !(0..=129).contains(&year) || !(0..=11).contains(&tm.mon) {
+        return None;
+    }
+    let mut day = tm.mday;
+    if tm.mon < 2 || (year + 2) % 4 != 0 {
+        day -= 1;
+    }
+    if tm.hour < 0 || tm.min < 0 || tm.sec < 0 {
+        return None;
+    }
+    Some(
+        (year * 365 + (year + 1) / 4 + DAYS_BEFORE_MONTH[tm.mon as usize] + day) * 24 * 60 * 60
+            + tm.hour * 60 * 60
+            + tm.min * 60
+            + tm.sec,
+    )
+}
+
+/// Return the amount of characters at the beginning of `date` that match `name` case-insensitively,
+/// or 0 if the word in `date` continues with a character that doesn't match.
+fn match_string(date: &[u8], name: &str) -> usize {
+    let name = name.as_bytes();
+    for (idx, c) in date.iter().enumerate() {
+        if name.get(idx).map_or(false, |expected| c.eq_ignore_ascii_case(expected)) {
+            continue;
+        }
+        return if c.is_ascii_alphanumeric() { 0 } else { idx };
+    }
+    date.len()
+}
+
+/// Parse the decimal number at the beginning of `date`, saturating on overflow, and return it along with its amount of digits.
+fn parse_number(date: &[u8]) -> (i64, usize) {
+    let digits = date.iter().take_while(|b| b.is_ascii_digit()).count();
+    let number = date[..digits].iter().fold(0i64, |number, digit| {
+        number.saturating_mul(10).saturating_add(i64::from(digit - b'0'))
+    });
+    (number, digits)
+}
+
+fn starts_with_digit(date: &[u8]) -> bool {
+    date.first().map_or(false, u8::is_ascii_digit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 24 * 60 * 60;
+
+    fn approximate_utc(input: &str, now: SecondsSinceUnixEpoch) -> Option<SecondsSinceUnixEpoch> {
+        approximate(input, now, &TimeZone::UTC).expect("valid")
+    }
+
+    #[test]
+    fn two_weeks_ago() {
+        assert_eq!(approximate_utc("2 weeks ago", 100 * DAY), Some(86 * DAY));
+    }
+
+    #[test]
+    fn two_weeks_ago_with_dots() {
+        assert_eq!(approximate_utc("2.weeks.ago", 100 * DAY), Some(86 * DAY));
+    }
+
+    #[test]
+    fn words_without_meaning_are_not_a_date() {
+        assert_eq!(approximate_utc("friday", 100 * DAY), None);
+        assert_eq!(approximate_utc("a week ago", 100 * DAY), None);
+    }
+
+    #[test]
+    fn match_string_stops_at_the_end_of_words() {
+        assert_eq!(match_string(b"fri noon", "Fridays"), 3);
+        assert_eq!(match_string(b"FRIDAY", "Fridays"), 6);
+        assert_eq!(match_string(b"fridge", "Fridays"), 0);
+        assert_eq!(match_string(b"now", "now"), 3);
+        assert_eq!(match_string(b"nowhere", "now"), 0);
+    }
+}
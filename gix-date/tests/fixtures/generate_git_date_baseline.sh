@@ -44,3 +44,93 @@ baseline '1660874655 +0800' 'RAW'
 
 # Note that we can't necessarily put 64bit dates here yet as `git` on the system might not yet support it.
 
+
+# approximate dates, relative to a fixed point in time (Sun Sep 9 01:46:40 2001 UTC) in time zones with fixed offsets
+NOW=1000000000
+
+function approxidate_baseline() {
+    local test_date=$1 # the date to test, parsed in each time zone
+
+    for tz_and_offset in "UTC 0" "UTC-2 7200" "UTC+5 -18000"; do
+        set -- $tz_and_offset
+        local tz=$1 offset=$2
+        GIT_TEST_DATE_NOW=$NOW TZ=$tz git -c section.key="$test_date" config --type=expiry-date section.key >/dev/null 2>&1 && status=0 || status=$?
+        {
+            echo "$test_date"
+            echo "$offset"
+            echo "$status"
+            if [ $status == 0 ]
+            then
+              GIT_TEST_DATE_NOW=$NOW TZ=$tz git -c section.key="$test_date" config --type=expiry-date section.key
+            else
+              echo "-1"
+            fi
+        } >> approxidate.git
+    done
+}
+
+# relative
+approxidate_baseline 'yesterday'
+approxidate_baseline '2 weeks ago'
+approxidate_baseline '2.weeks.ago'
+approxidate_baseline '3 days 2 hours ago'
+approxidate_baseline 'one week ago'
+approxidate_baseline 'ten minutes ago'
+approxidate_baseline '5.minutes.ago'
+approxidate_baseline '1 second ago'
+approxidate_baseline '1 month ago'
+approxidate_baseline '3 months ago'
+approxidate_baseline '14 months ago'
+approxidate_baseline '2 years ago'
+approxidate_baseline 'last year'
+approxidate_baseline '-4 week ago Z'
+# times of day
+approxidate_baseline 'noon'
+approxidate_baseline 'midnight'
+approxidate_baseline 'tea'
+approxidate_baseline 'yesterday noon'
+approxidate_baseline 'noon yesterday'
+approxidate_baseline '10am'
+approxidate_baseline '5pm'
+approxidate_baseline '12am'
+approxidate_baseline '12pm'
+approxidate_baseline '1:30pm'
+approxidate_baseline '08:30'
+approxidate_baseline '23:59:59'
+approxidate_baseline '10:00 yesterday'
+approxidate_baseline '2 days ago 5pm'
+# weekdays
+approxidate_baseline 'last friday'
+approxidate_baseline 'last friday noon'
+approxidate_baseline 'last sunday'
+approxidate_baseline 'last monday 5pm'
+approxidate_baseline '3 fridays ago'
+approxidate_baseline 'sat'
+# calendar dates
+approxidate_baseline 'december'
+approxidate_baseline 'Dec 02'
+approxidate_baseline 'may 5'
+approxidate_baseline '5 may'
+approxidate_baseline 'may 5 2020'
+approxidate_baseline 'Sep 4 10:00'
+approxidate_baseline '1.1.2020'
+approxidate_baseline '10/11/12'
+approxidate_baseline '12/25'
+approxidate_baseline '25.12.'
+approxidate_baseline '2001-09-15 12:00'
+# strictly parsed dates with and without offsets
+approxidate_baseline '2022-08-17 21:43:13'
+approxidate_baseline '2022-08-17T21:43:13Z'
+approxidate_baseline '2022-08-17 21:43:13.123 +0200'
+approxidate_baseline '2022.08.17 21:43:13 -05:30'
+approxidate_baseline '20220817T214313'
+approxidate_baseline 'Thu, 18 Aug 2022 12:45:06 GMT'
+approxidate_baseline '18 Aug 2022 12:45:06 PST'
+approxidate_baseline 'Aug 18 2022 12:45:06 CEST'
+approxidate_baseline 'Aug 18 12:45:06 2022'
+approxidate_baseline '@1234567890 +0130'
+approxidate_baseline '@1234567890'
+# not a date
+approxidate_baseline 'friday'
+approxidate_baseline 'a week ago'
+approxidate_baseline 'foo'
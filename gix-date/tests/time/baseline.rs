@@ -83,3 +83,68 @@ fn parse_compare_format() {
         }
     }
 }
+
+struct ApproxidateSample {
+    utc_offset: i32,
+    exit_code: usize,
+    seconds: SecondsSinceUnixEpoch,
+}
+
+/// The point in time relative to which the approximate dates in the baseline were parsed.
+const APPROXIDATE_NOW: u64 = 1_000_000_000;
+
+static APPROXIDATE_BASELINE: Lazy<Vec<(String, ApproxidateSample)>> = Lazy::new(|| {
+    (|| -> Result<_> {
+        let base = gix_testtools::scripted_fixture_read_only("generate_git_date_baseline.sh")?;
+        let file = std::fs::read(base.join("approxidate.git"))?;
+        let baseline = std::str::from_utf8(&file).expect("valid utf");
+        let mut samples = Vec::new();
+        let mut lines = baseline.lines();
+        while let Some(date_str) = lines.next() {
+            let utc_offset = lines.next().expect("four lines per baseline").parse()?;
+            let exit_code = lines.next().expect("four lines per baseline").parse()?;
+            let seconds = lines
+                .next()
+                .expect("four lines per baseline")
+                .parse()
+                .expect("valid epoch value");
+            samples.push((
+                date_str.into(),
+                ApproxidateSample {
+                    utc_offset,
+                    exit_code,
+                    seconds,
+                },
+            ));
+        }
+        Ok(samples)
+    })()
+    .expect("baseline format is well known and can always be parsed")
+});
+
+#[test]
+fn approxidate_compare() {
+    let now = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(APPROXIDATE_NOW);
+    for (
+        pattern,
+        ApproxidateSample {
+            utc_offset,
+            exit_code,
+            seconds,
+        },
+    ) in APPROXIDATE_BASELINE.iter()
+    {
+        let res = gix_date::parse::approximate_with_utc_offset(pattern, Some(now), *utc_offset);
+        assert_eq!(
+            res.is_ok(),
+            *exit_code == 0,
+            "{pattern:?} at UTC offset {utc_offset} disagrees with baseline: {res:?}"
+        );
+        if let Ok(t) = res {
+            assert_eq!(
+                t.seconds, *seconds,
+                "{pattern:?} at UTC offset {utc_offset} disagrees with baseline seconds since epoch"
+            );
+        }
+    }
+}
@@ -3,11 +3,11 @@ use std::time::SystemTime;
 use gix_date::{time::Sign, Time};
 
 #[test]
-fn special_time_is_ok_for_now() {
+fn dates_without_offset_are_local_to_the_given_time_zone() {
     assert_eq!(
-        gix_date::parse("1979-02-26 18:30:00", Some(SystemTime::now())).unwrap(),
+        gix_date::parse::parse_with_utc_offset("1979-02-26 18:30:00", Some(SystemTime::now()), 1800).unwrap(),
         Time {
-            seconds: 42,
+            seconds: 288900000,
             offset: 1800,
             sign: Sign::Plus,
         }
@@ -143,6 +143,53 @@ fn git_default() {
     );
 }
 
+#[test]
+fn approximate_dates_need_the_current_time() {
+    for input in ["yesterday", "last friday noon", "Dec 02"] {
+        assert!(
+            matches!(
+                gix_date::parse::approximate(input, None).unwrap_err(),
+                gix_date::parse::Error::MissingCurrentTime
+            ),
+            "{input}"
+        );
+    }
+}
+
+#[test]
+fn approximate_dates_are_not_parsed_strictly() {
+    for input in ["last friday noon", "Dec 02", "noon yesterday"] {
+        assert!(
+            matches!(
+                gix_date::parse(input, Some(SystemTime::now())).unwrap_err(),
+                gix_date::parse::Error::InvalidDateString { .. }
+            ),
+            "{input}"
+        );
+        assert!(
+            gix_date::parse::approximate(input, Some(SystemTime::now())).is_ok(),
+            "{input}"
+        );
+    }
+}
+
+#[test]
+fn approximate_dates_have_the_offset_of_their_time_zone() {
+    let now = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000_000);
+    for (input, utc_offset) in [("yesterday", 7200), ("last friday noon", -18000), ("2 days ago 5pm", 0)] {
+        let time = gix_date::parse::approximate_with_utc_offset(input, Some(now), utc_offset).unwrap();
+        assert_eq!(time.offset, utc_offset, "{input}");
+        assert_eq!(time.sign, if utc_offset < 0 { Sign::Minus } else { Sign::Plus });
+    }
+    assert_eq!(
+        gix_date::parse::approximate_with_utc_offset("Thu, 18 Aug 2022 12:45:06 +0800", Some(now), 7200)
+            .unwrap()
+            .offset,
+        28800,
+        "dates with offset keep it"
+    );
+}
+
 #[test]
 fn invalid_dates_can_be_produced_without_current_time() {
     assert!(matches!(
@@ -176,6 +223,26 @@ mod relative {
         assert_eq!(date.seconds, -1);
     }
 
+    #[test]
+    fn dots_as_separators_and_special_words() {
+        let now = std::time::UNIX_EPOCH + std::time::Duration::from_secs(400 * 24 * 60 * 60);
+        let days_since_epoch = |days: i64| days * 24 * 60 * 60;
+        for (input, expected) in [
+            ("2.weeks.ago", days_since_epoch(400 - 14)),
+            ("1.day.ago", days_since_epoch(399)),
+            ("yesterday", days_since_epoch(399)),
+            ("now", days_since_epoch(400)),
+            ("1 year ago", days_since_epoch(400 - 365)),
+        ] {
+            assert_eq!(gix_date::parse(input, Some(now)).unwrap().seconds, expected, "{input}");
+        }
+        assert_eq!(
+            gix_date::parse("2 months ago", Some(now)).unwrap().seconds,
+            days_since_epoch(400 - 31 - 31),
+            "months are calendar months, from February 5th 1971 back to December 5th 1970"
+        );
+    }
+
     #[test]
     fn various() {
         let now = SystemTime::now();
@@ -208,4 +275,11 @@ mod fuzz {
             let _ = gix_date::parse(input, Some(std::time::UNIX_EPOCH)).unwrap_err();
         }
     }
+
+    #[test]
+    fn approximated_like_git_does_without_panic() {
+        for input in ["-9999-1-1", "7	-𬞋", "5 ڜ-09", "-4 week ago Z"] {
+            let _ = gix_date::parse::approximate(input, Some(std::time::UNIX_EPOCH)).unwrap();
+        }
+    }
 }
@@ -11,6 +11,16 @@ pub mod pipeline;
 ///
 pub mod platform;
 
+/// Render diffs of lines in the unified format that `git diff` produces.
+pub mod unified_diff;
+pub use unified_diff::UnifiedDiff;
+
+/// Write patches with headers that describe the change of a file, similar to `git diff`.
+pub mod patch;
+
+/// Write diffstats that summarize the changes to files, similar to `git diff --stat`.
+pub mod stat;
+
 /// Information about the diff performed to detect similarity.
 #[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd)]
 pub struct DiffLineStats {
@@ -62,6 +72,11 @@ pub struct Driver {
     /// If `Some(false)`, it won't be considered binary, and the its data will not be sampled for the null-byte either.
     /// Leaving it to `None` means binary detection is automatic, and is based on the presence of the `0` byte in the first 8kB of the buffer.
     pub is_binary: Option<bool>,
+    /// Newline-separated regular expressions to find the lines that are shown in hunk headers, typically the name of the
+    /// function a hunk is in, as configured with `diff.<driver>.xfuncname`.
+    ///
+    /// Use [`unified_diff::Patterns::from_xfuncname()`] to compile them.
+    pub function_name_patterns: Option<BString>,
 }
 
 /// A conversion pipeline to take an object or path from what's stored in `git` to what can be diffed, while
@@ -0,0 +1,246 @@
+use std::{borrow::Cow, io::Write};
+
+use bstr::{BStr, BString, ByteVec};
+
+/// One side of a [`Change`], i.e. the old or the new version of a file.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Side<'a> {
+    /// The location of the file relative to the root of the repository.
+    pub path: &'a BStr,
+    /// The id of the file's content.
+    pub id: &'a gix_hash::oid,
+    /// The mode of the file.
+    pub mode: gix_object::tree::EntryMode,
+}
+
+/// A change to a file that is described by the headers of a patch.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Change<'a> {
+    /// The file was added.
+    Addition {
+        /// The new version of the file.
+        new: Side<'a>,
+    },
+    /// The file was deleted.
+    Deletion {
+        /// The old version of the file.
+        old: Side<'a>,
+    },
+    /// The file was changed in place, which may also be a change of its mode only.
+    Modification {
+        /// The old version of the file.
+        old: Side<'a>,
+        /// The new version of the file.
+        new: Side<'a>,
+    },
+    /// The file was renamed, or copied if `copy` is `true`, possibly with changes to its content or mode.
+    Rewrite {
+        /// The source of the rename or copy.
+        old: Side<'a>,
+        /// The destination of the rename or copy.
+        new: Side<'a>,
+        /// How similar the source and destination are in percent, from 0 to 100.
+        similarity: u8,
+        /// If `true`, the source still exists, and the destination is a copy of it.
+        copy: bool,
+    },
+}
+
+/// The content of a patch that follows its headers.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Content<'a> {
+    /// The hunks of a unified diff as produced by [`UnifiedDiff`](super::unified_diff::UnifiedDiff), which may be empty
+    /// if the content didn't change.
+    Hunks(&'a BStr),
+    /// One of the versions is binary and can't be diffed.
+    Binary,
+    /// One of the versions is binary, and this is the `GIT binary patch` including its first line to turn the old version
+    /// into the new one, as produced by `git diff --binary`.
+    BinaryPatch(&'a BStr),
+}
+
+/// Options for use in [`write()`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Options {
+    /// The amount of hexadecimal characters to abbreviate object ids to in `index` lines, similar to `git diff --abbrev=<n>`.
+    ///
+    /// Defaults to 7.
+    pub id_hex_len: usize,
+    /// The prefix for paths of the old version of a file, similar to `git diff --src-prefix`.
+    ///
+    /// Defaults to `a/`.
+    pub old_prefix: BString,
+    /// The prefix for paths of the new version of a file, similar to `git diff --dst-prefix`.
+    ///
+    /// Defaults to `b/`.
+    pub new_prefix: BString,
+    /// If `true`, bytes above `0x7f` in paths are quoted as octal escape sequences, just like `core.quotePath` does.
+    ///
+    /// Defaults to `true`.
+    pub quote_path: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            id_hex_len: 7,
+            old_prefix: "a/".into(),
+            new_prefix: "b/".into(),
+            quote_path: true,
+        }
+    }
+}
+
+impl<'a> Change<'a> {
+    /// Return the old version of the file, if there is one.
+    pub fn old_side(&self) -> Option<&Side<'a>> {
+        match self {
+            Change::Addition { .. } => None,
+            Change::Deletion { old } | Change::Modification { old, .. } | Change::Rewrite { old, .. } => Some(old),
+        }
+    }
+
+    /// Return the new version of the file, if there is one.
+    pub fn new_side(&self) -> Option<&Side<'a>> {
+        match self {
+            Change::Deletion { .. } => None,
+            Change::Addition { new } | Change::Modification { new, .. } | Change::Rewrite { new, .. } => Some(new),
+        }
+    }
+}
+
+/// Write the patch for `change` with its `content` to `out`, configured by `options`, just like `git diff` would.
+///
+/// Nothing is written if the change is a modification without any difference in content or mode.
+pub fn write(out: &mut dyn Write, change: &Change<'_>, content: Content<'_>, options: &Options) -> std::io::Result<()> {
+    let (old, new) = (change.old_side(), change.new_side());
+    let old_path = old.or(new).expect("at least one side is set").path;
+    let new_path = new.or(old).expect("at least one side is set").path;
+    let old_name = quote_path(options.old_prefix.as_ref(), old_path, options.quote_path);
+    let new_name = quote_path(options.new_prefix.as_ref(), new_path, options.quote_path);
+
+    let mut header = BString::default();
+    header.push_str(b"diff --git ");
+    header.push_str(old_name.as_ref());
+    header.push_byte(b' ');
+    header.push_str(new_name.as_ref());
+    header.push_byte(b'\n');
+
+    let mut must_show_header = true;
+    match (old, new) {
+        (None, Some(new)) => header.push_str(format!("new file mode {:06o}\n", new.mode.0)),
+        (Some(old), None) => header.push_str(format!("deleted file mode {:06o}\n", old.mode.0)),
+        (Some(old), Some(new)) => {
+            must_show_header = old.mode != new.mode;
+            if must_show_header {
+                header.push_str(format!("old mode {:06o}\nnew mode {:06o}\n", old.mode.0, new.mode.0));
+            }
+        }
+        (None, None) => unreachable!("a change always has at least one side"),
+    }
+    if let Change::Rewrite {
+        old,
+        new,
+        similarity,
+        copy,
+    } = change
+    {
+        must_show_header = true;
+        let operation = if *copy { "copy" } else { "rename" };
+        header.push_str(format!("similarity index {similarity}%\n{operation} from "));
+        header.push_str(quote_path("".into(), old.path, options.quote_path).as_ref());
+        header.push_str(format!("\n{operation} to "));
+        header.push_str(quote_path("".into(), new.path, options.quote_path).as_ref());
+        header.push_byte(b'\n');
+    }
+    let null_id = old.or(new).expect("at least one side is set").id.kind().null();
+    let (old_id, new_id) = (
+        old.map_or(null_id.as_ref(), |s| s.id),
+        new.map_or(null_id.as_ref(), |s| s.id),
+    );
+    if old_id != new_id {
+        header.push_str(format!(
+            "index {}..{}",
+            old_id.to_hex_with_len(options.id_hex_len),
+            new_id.to_hex_with_len(options.id_hex_len)
+        ));
+        if let (Some(old), Some(new)) = (old, new) {
+            if old.mode == new.mode {
+                header.push_str(format!(" {:06o}", old.mode.0));
+            }
+        }
+        header.push_byte(b'\n');
+    }
+
+    let old_label = old.map_or(Cow::Borrowed("/dev/null".into()), |_| old_name.clone());
+    let new_label = new.map_or(Cow::Borrowed("/dev/null".into()), |_| new_name.clone());
+    match content {
+        Content::Binary => {
+            out.write_all(&header)?;
+            out.write_all(b"Binary files ")?;
+            out.write_all(&old_label)?;
+            out.write_all(b" and ")?;
+            out.write_all(&new_label)?;
+            out.write_all(b" differ\n")?;
+        }
+        Content::BinaryPatch(data) => {
+            out.write_all(&header)?;
+            out.write_all(data)?;
+        }
+        Content::Hunks(hunks) if hunks.is_empty() => {
+            if must_show_header {
+                out.write_all(&header)?;
+            }
+        }
+        Content::Hunks(hunks) => {
+            out.write_all(&header)?;
+            for (marker, label, path) in [(b"---", old_label, old_path), (b"+++", new_label, new_path)] {
+                out.write_all(marker)?;
+                out.write_all(b" ")?;
+                out.write_all(&label)?;
+                if path.contains(&b' ') {
+                    out.write_all(b"\t")?;
+                }
+                out.write_all(b"\n")?;
+            }
+            out.write_all(hunks)?;
+        }
+    }
+    Ok(())
+}
+
+/// Join `prefix` and `path`, and quote the result if needed like `git` does, which is when it contains control
+/// characters, double quotes or backslashes, or bytes above `0x7f` if `quote_non_ascii` is `true`.
+pub(crate) fn quote_path<'a>(prefix: &BStr, path: &'a BStr, quote_non_ascii: bool) -> Cow<'a, BStr> {
+    let needs_quoting = |b: u8| b < 0x20 || b == b'"' || b == b'\\' || b == 0x7f || (quote_non_ascii && b > 0x7f);
+    if !prefix.iter().chain(path.iter()).any(|b| needs_quoting(*b)) {
+        return if prefix.is_empty() {
+            Cow::Borrowed(path)
+        } else {
+            let mut joined = BString::from(prefix);
+            joined.push_str(path);
+            Cow::Owned(joined)
+        };
+    }
+    let mut out = BString::from("\"");
+    for &b in prefix.iter().chain(path.iter()) {
+        if !needs_quoting(b) {
+            out.push_byte(b);
+            continue;
+        }
+        match b {
+            b'\x07' => out.push_str(br"\a"),
+            b'\x08' => out.push_str(br"\b"),
+            b'\t' => out.push_str(br"\t"),
+            b'\n' => out.push_str(br"\n"),
+            b'\x0b' => out.push_str(br"\v"),
+            b'\x0c' => out.push_str(br"\f"),
+            b'\r' => out.push_str(br"\r"),
+            b'"' => out.push_str(br#"\""#),
+            b'\\' => out.push_str(br"\\"),
+            _ => out.push_str(format!("\\{b:03o}")),
+        }
+    }
+    out.push_byte(b'"');
+    Cow::Owned(out)
+}
@@ -0,0 +1,305 @@
+use std::io::Write;
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+
+use super::patch::{quote_path, Change};
+
+/// The amount of changes to a file, as shown in a diffstat.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Counts {
+    /// The amount of lines that were added and removed in a text file.
+    Lines {
+        /// The amount of added lines.
+        added: u32,
+        /// The amount of removed lines.
+        removed: u32,
+    },
+    /// The size of the old and new version of a binary file in bytes, which is shown instead of a line count.
+    Binary {
+        /// The size of the old version, or 0 if there is none.
+        old_size: u64,
+        /// The size of the new version, or 0 if there is none.
+        new_size: u64,
+    },
+}
+
+/// Options for use in [`write()`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Options {
+    /// The maximum amount of columns to use for each line of the diffstat, similar to `git diff --stat=<width>`.
+    ///
+    /// Defaults to 80, and `git format-patch` uses 72.
+    pub width: usize,
+    /// If `true`, follow the diffstat with lines that summarize additions, deletions, renames, copies and
+    /// mode changes, similar to `git diff --summary`.
+    ///
+    /// Defaults to `true`.
+    pub summary: bool,
+    /// If `true`, bytes above `0x7f` in paths are quoted as octal escape sequences, just like `core.quotePath` does.
+    ///
+    /// Defaults to `true`.
+    pub quote_path: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            width: 80,
+            summary: true,
+            quote_path: true,
+        }
+    }
+}
+
+/// Write a diffstat for all `changes` and their `counts` in the given order to `out`, just like `git diff --stat --summary`
+/// would, including the line with the totals.
+///
+/// The graph of `+` and `-` characters is scaled to fit into [`Options::width`], and paths that are too long are shortened
+/// from the left.
+pub fn write(out: &mut dyn Write, changes: &[(Change<'_>, Counts)], options: &Options) -> std::io::Result<()> {
+    let names: Vec<BString> = changes
+        .iter()
+        .map(|(change, _)| match change {
+            Change::Rewrite { old, new, .. } => rename_name(old.path, new.path, options.quote_path),
+            _ => quote_path(
+                "".into(),
+                change.new_side().or(change.old_side()).expect("one side").path,
+                options.quote_path,
+            )
+            .into_owned(),
+        })
+        .collect();
+
+    let mut max_len = 0;
+    let mut max_change = 0u64;
+    let mut bin_width = 0;
+    let mut number_width = 0;
+    for ((_, counts), name) in changes.iter().zip(&names) {
+        max_len = max_len.max(display_width(name.as_ref()));
+        match *counts {
+            Counts::Binary { old_size, new_size } => {
+                bin_width = bin_width.max(14 + decimal_width(new_size) + decimal_width(old_size));
+                number_width = 3;
+            }
+            Counts::Lines { added, removed } => max_change = max_change.max(u64::from(added) + u64::from(removed)),
+        }
+    }
+
+    let width = options.width.max(16 + 6 + number_width);
+    let number_width = decimal_width(max_change).max(number_width);
+    let mut graph_width = if max_change as usize + 4 > bin_width {
+        max_change as usize
+    } else {
+        bin_width - 4
+    };
+    let mut name_width = max_len;
+    if name_width + number_width + 6 + graph_width > width {
+        let available = (width * 3 / 8).saturating_sub(number_width + 6);
+        if graph_width > available {
+            graph_width = available.max(6);
+        }
+        if name_width > width.saturating_sub(number_width + 6 + graph_width) {
+            name_width = width.saturating_sub(number_width + 6 + graph_width);
+        } else {
+            graph_width = width - number_width - 6 - name_width;
+        }
+    }
+
+    let (mut total_added, mut total_removed) = (0u64, 0u64);
+    for ((_, counts), name) in changes.iter().zip(&names) {
+        let (prefix, name) = shorten(name.as_ref(), name_width);
+        let padding = name_width.saturating_sub(prefix.len() + display_width(name));
+        write!(out, " {prefix}")?;
+        out.write_all(name)?;
+        write!(out, "{:padding$} | ", "")?;
+        match *counts {
+            Counts::Binary { old_size, new_size } => {
+                write!(out, "{:>number_width$}", "Bin")?;
+                if old_size != 0 || new_size != 0 {
+                    write!(out, " {old_size} -> {new_size} bytes")?;
+                }
+            }
+            Counts::Lines { added, removed } => {
+                let (added, removed) = (u64::from(added), u64::from(removed));
+                total_added += added;
+                total_removed += removed;
+                write!(out, "{:>number_width$}", added + removed)?;
+                if added + removed != 0 {
+                    out.write_all(b" ")?;
+                }
+                let (mut add, mut del) = (added, removed);
+                if graph_width as u64 <= max_change {
+                    let mut total = scale_linear(added + removed, graph_width as u64, max_change);
+                    if total < 2 && added != 0 && removed != 0 {
+                        total = 2;
+                    }
+                    if added < removed {
+                        add = scale_linear(added, graph_width as u64, max_change);
+                        del = total - add;
+                    } else {
+                        del = scale_linear(removed, graph_width as u64, max_change);
+                        add = total - del;
+                    }
+                }
+                out.write_all(&b"+".repeat(add as usize))?;
+                out.write_all(&b"-".repeat(del as usize))?;
+            }
+        }
+        out.write_all(b"\n")?;
+    }
+    write_totals(out, changes.len(), total_added, total_removed)?;
+
+    if options.summary {
+        for (change, _) in changes {
+            write_summary(out, change, options.quote_path)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_totals(out: &mut dyn Write, files: usize, added: u64, removed: u64) -> std::io::Result<()> {
+    let plural = |count: u64| if count == 1 { "" } else { "s" };
+    if files == 0 {
+        return out.write_all(b" 0 files changed\n");
+    }
+    write!(out, " {files} file{} changed", plural(files as u64))?;
+    if added != 0 || removed == 0 {
+        write!(out, ", {added} insertion{}(+)", plural(added))?;
+    }
+    if removed != 0 || added == 0 {
+        write!(out, ", {removed} deletion{}(-)", plural(removed))?;
+    }
+    out.write_all(b"\n")
+}
+
+fn write_summary(out: &mut dyn Write, change: &Change<'_>, quote_non_ascii: bool) -> std::io::Result<()> {
+    let quoted = |path| quote_path("".into(), path, quote_non_ascii).into_owned();
+    match change {
+        Change::Addition { new } => {
+            write!(out, " create mode {:06o} ", new.mode.0)?;
+            out.write_all(&quoted(new.path))?;
+            out.write_all(b"\n")
+        }
+        Change::Deletion { old } => {
+            write!(out, " delete mode {:06o} ", old.mode.0)?;
+            out.write_all(&quoted(old.path))?;
+            out.write_all(b"\n")
+        }
+        Change::Rewrite {
+            old,
+            new,
+            similarity,
+            copy,
+        } => {
+            write!(out, " {} ", if *copy { "copy" } else { "rename" })?;
+            out.write_all(&rename_name(old.path, new.path, quote_non_ascii))?;
+            writeln!(out, " ({similarity}%)")?;
+            if old.mode != new.mode {
+                writeln!(out, " mode change {:06o} => {:06o}", old.mode.0, new.mode.0)?;
+            }
+            Ok(())
+        }
+        Change::Modification { old, new } => {
+            if old.mode != new.mode {
+                write!(out, " mode change {:06o} => {:06o} ", old.mode.0, new.mode.0)?;
+                out.write_all(&quoted(new.path))?;
+                out.write_all(b"\n")?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Produce `old => new`, with the directories they have in common outside of braces like `dir/{old => new}`.
+fn rename_name(old: &BStr, new: &BStr, quote_non_ascii: bool) -> BString {
+    let (quoted_old, quoted_new) = (
+        quote_path("".into(), old, quote_non_ascii),
+        quote_path("".into(), new, quote_non_ascii),
+    );
+    let mut out = BString::default();
+    if quoted_old.as_ref() != old || quoted_new.as_ref() != new {
+        out.push_str(quoted_old.as_ref());
+        out.push_str(" => ");
+        out.push_str(quoted_new.as_ref());
+        return out;
+    }
+
+    let prefix_len = old
+        .iter()
+        .zip(new.iter())
+        .take_while(|(a, b)| a == b)
+        .enumerate()
+        .filter(|(_, (a, _))| **a == b'/')
+        .last()
+        .map_or(0, |(pos, _)| pos + 1);
+    // Both paths are conceptually terminated by a NUL byte that is always in common, and the search for a common suffix
+    // may reach one byte into the prefix to see its trailing slash.
+    let limit = prefix_len.saturating_sub(usize::from(prefix_len > 0));
+    let (mut old_pos, mut new_pos) = (old.len() as isize, new.len() as isize);
+    let mut suffix_len = 0;
+    let byte_at = |path: &BStr, pos: isize| path.get(pos as usize).copied().unwrap_or(0);
+    while limit as isize <= old_pos && limit as isize <= new_pos && byte_at(old, old_pos) == byte_at(new, new_pos) {
+        if byte_at(old, old_pos) == b'/' {
+            suffix_len = old.len() - old_pos as usize;
+        }
+        old_pos -= 1;
+        new_pos -= 1;
+    }
+
+    let old_mid = old.len().saturating_sub(prefix_len + suffix_len);
+    let new_mid = new.len().saturating_sub(prefix_len + suffix_len);
+    let has_braces = prefix_len + suffix_len > 0;
+    if has_braces {
+        out.push_str(&old[..prefix_len]);
+        out.push_byte(b'{');
+    }
+    out.push_str(&old[prefix_len..][..old_mid]);
+    out.push_str(" => ");
+    out.push_str(&new[prefix_len..][..new_mid]);
+    if has_braces {
+        out.push_byte(b'}');
+        out.push_str(&old[old.len() - suffix_len..]);
+    }
+    out
+}
+
+/// Return `("...", tail)` if `name` is wider than `width`, where `tail` is what fits of `name`, starting at its next slash if possible.
+fn shorten(name: &BStr, width: usize) -> (&'static str, &BStr) {
+    let mut len = display_width(name);
+    if len <= width {
+        return ("", name);
+    }
+    let available = width.saturating_sub(3);
+    let mut tail = name;
+    while len > available {
+        let Some((_, end, _)) = tail.char_indices().next() else {
+            break;
+        };
+        tail = tail[end..].as_bstr();
+        len -= 1;
+    }
+    ("...", tail.find_byte(b'/').map_or(tail, |pos| tail[pos..].as_bstr()))
+}
+
+fn display_width(name: &BStr) -> usize {
+    name.chars().count()
+}
+
+fn decimal_width(mut n: u64) -> usize {
+    let mut width = 1;
+    while n >= 10 {
+        n /= 10;
+        width += 1;
+    }
+    width
+}
+
+/// Scale `it` linearly so that `max_change` fits into `width`, making sure that at least one character is shown for
+/// non-zero values.
+fn scale_linear(it: u64, width: u64, max_change: u64) -> u64 {
+    if it == 0 {
+        0
+    } else {
+        1 + (it * (width - 1) / max_change)
+    }
+}
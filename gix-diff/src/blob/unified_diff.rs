@@ -0,0 +1,451 @@
+use std::{hash::Hash, ops::Range};
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+use imara_diff::{
+    intern::{InternedInput, Interner, Token},
+    Sink,
+};
+
+/// Options to control how a [`UnifiedDiff`] is rendered.
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// The amount of unchanged lines to show before and after each change, similar to `git diff -U<n>`.
+    ///
+    /// Defaults to 3.
+    pub context_lines: u32,
+    /// Merge two hunks if there are no more than this amount of unchanged lines between their context lines, similar to
+    /// `git diff --inter-hunk-context=<n>`.
+    ///
+    /// Defaults to 0, which still merges hunks whose context lines would touch or overlap.
+    pub inter_hunk_context: u32,
+    /// Determine what's shown after the line numbers in each hunk header.
+    pub function_name: FunctionName,
+    /// If set, render the removed and added lines of each hunk word by word, similar to `git diff --word-diff=<mode>`.
+    pub word_diff: Option<WordDiff>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            context_lines: 3,
+            inter_hunk_context: 0,
+            function_name: FunctionName::default(),
+            word_diff: None,
+        }
+    }
+}
+
+/// Determines the text shown after the line numbers of a hunk header, which is typically the name of the function
+/// the hunk is in.
+///
+/// It's the closest line before the hunk's first line that matches, and if there is none, the one of the previous hunk.
+#[derive(Debug, Clone, Default)]
+pub enum FunctionName {
+    /// Don't show anything after the line numbers.
+    None,
+    /// Use lines that start with a letter, `_` or `$`, which is what `git` does if there is no pattern configured.
+    #[default]
+    Default,
+    /// Use lines that match the given patterns, typically configured with `diff.<driver>.xfuncname`.
+    Patterns(Patterns),
+}
+
+/// A list of regular expressions to find lines for use in hunk headers, as configured with `diff.<driver>.xfuncname`.
+///
+/// The first pattern that matches a line decides, and if it's negated the line isn't used.
+/// If a pattern has a capture group, only the text of the first group is used instead of the whole match.
+#[derive(Debug, Clone)]
+pub struct Patterns(Vec<(bool, regex::bytes::Regex)>);
+
+/// The way changed lines are shown word by word.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum WordDiff {
+    /// Show removed words as `[-removed-]` and added words as `{+added+}`, like `git diff --word-diff=plain`.
+    Plain,
+    /// A line-based format for consumption by scripts, like `git diff --word-diff=porcelain`.
+    ///
+    /// Unchanged, removed and added words are placed on lines starting with ` `, `-` and `+` respectively, and
+    /// newlines of the input are represented by lines that only contain `~`.
+    Porcelain,
+}
+
+///
+pub mod patterns {
+    use bstr::BString;
+
+    /// The error returned by [`Patterns::from_xfuncname()`](super::Patterns::from_xfuncname()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The last pattern of '{patterns}' must not be negated")]
+        LastPatternNegated { patterns: BString },
+        #[error("Could not compile '{pattern}' to find function names with")]
+        Regex { pattern: BString, source: regex::Error },
+    }
+}
+
+impl Patterns {
+    /// Parse `value` as newline-separated list of extended regular expressions, each of which may be prefixed with `!`
+    /// to negate it, just like `git` does for `diff.<driver>.xfuncname`.
+    pub fn from_xfuncname(value: &BStr) -> Result<Self, patterns::Error> {
+        let patterns: Vec<_> = value.split_str("\n").collect();
+        let mut out = Vec::with_capacity(patterns.len());
+        for (index, pattern) in patterns.iter().enumerate() {
+            let (negated, pattern) = match pattern.strip_prefix(b"!") {
+                Some(pattern) => (true, pattern),
+                None => (false, *pattern),
+            };
+            if negated && index + 1 == patterns.len() {
+                return Err(patterns::Error::LastPatternNegated {
+                    patterns: value.to_owned(),
+                });
+            }
+            let regex = regex::bytes::RegexBuilder::new(&pattern.to_str_lossy())
+                .unicode(false)
+                .build()
+                .map_err(|err| patterns::Error::Regex {
+                    pattern: pattern.into(),
+                    source: err,
+                })?;
+            out.push((negated, regex));
+        }
+        Ok(Patterns(out))
+    }
+}
+
+impl FunctionName {
+    /// `git` truncates function names to this amount of bytes.
+    const MAX_LEN: usize = 80;
+
+    /// Return the function name found in `line` if it qualifies, with its trailing whitespace removed.
+    fn matches<'a>(&self, line: &'a [u8]) -> Option<&'a [u8]> {
+        let name = match self {
+            FunctionName::None => return None,
+            FunctionName::Default => {
+                let first = *line.first()?;
+                if !(first.is_ascii_alphabetic() || first == b'_' || first == b'$') {
+                    return None;
+                }
+                &line[..line.len().min(Self::MAX_LEN)]
+            }
+            FunctionName::Patterns(Patterns(patterns)) => {
+                let line = line
+                    .strip_suffix(b"\r\n")
+                    .or_else(|| line.strip_suffix(b"\n"))
+                    .unwrap_or(line);
+                let (negated, captures) = patterns
+                    .iter()
+                    .find_map(|(negated, regex)| regex.captures(line).map(|captures| (*negated, captures)))?;
+                if negated {
+                    return None;
+                }
+                let name = captures
+                    .get(1)
+                    .or_else(|| captures.get(0))
+                    .expect("the whole match is always present")
+                    .as_bytes();
+                &name[..name.len().min(Self::MAX_LEN)]
+            }
+        };
+        Some(name.trim_end_with(|c| c.is_ascii_whitespace() || c == '\x0b'))
+    }
+}
+
+/// A [`Sink`] to render the changes between two files in the unified format that `git diff` produces, without any file
+/// headers, which can be obtained with [`patch::write()`](super::patch::write()).
+///
+/// It needs tokens that include their line terminator, as produced by
+/// [`byte_lines_with_terminator()`](imara_diff::sources::byte_lines_with_terminator()), to be able to tell if the last
+/// line of a file has a newline.
+pub struct UnifiedDiff<'a, T: Hash + Eq> {
+    before: &'a [Token],
+    after: &'a [Token],
+    interner: &'a Interner<T>,
+    options: Options,
+
+    /// The changes of the hunk that is currently being collected, as `(before, after)` pairs.
+    changes: Vec<(Range<u32>, Range<u32>)>,
+    /// The most recently found function name for use in hunk headers.
+    function_name: BString,
+    /// The line before which we stopped looking for function names, or `None` if we didn't search yet.
+    function_name_search_limit: Option<u32>,
+    /// The text of removed lines that are yet to be shown word by word.
+    removed_text: BString,
+    /// The text of added lines that are yet to be shown word by word.
+    added_text: BString,
+
+    out: BString,
+}
+
+impl<'a, T> UnifiedDiff<'a, T>
+where
+    T: Hash + Eq + AsRef<[u8]>,
+{
+    /// Create a new instance to render the changes of `input` according to `options`.
+    pub fn new(input: &'a InternedInput<T>, options: Options) -> Self {
+        UnifiedDiff {
+            before: &input.before,
+            after: &input.after,
+            interner: &input.interner,
+            options,
+            changes: Vec::new(),
+            function_name: BString::default(),
+            function_name_search_limit: None,
+            removed_text: BString::default(),
+            added_text: BString::default(),
+            out: BString::default(),
+        }
+    }
+
+    fn line(&self, token: Token) -> &'a [u8] {
+        let interner: &'a Interner<T> = self.interner;
+        interner[token].as_ref()
+    }
+
+    /// Look for a function name for a hunk that starts at `hunk_start` in the old version of the file, and remember it
+    /// if one was found.
+    fn update_function_name(&mut self, hunk_start: u32) {
+        if matches!(self.options.function_name, FunctionName::None) {
+            return;
+        }
+        let limit = self.function_name_search_limit.unwrap_or(0);
+        for line in (limit..hunk_start).rev() {
+            let line = self.line(self.before[line as usize]);
+            if let Some(name) = self.options.function_name.matches(line) {
+                self.function_name = name.into();
+                break;
+            }
+        }
+        self.function_name_search_limit = Some(hunk_start);
+    }
+
+    fn flush(&mut self) {
+        let (Some((first_before, first_after)), Some((last_before, last_after))) =
+            (self.changes.first().cloned(), self.changes.last().cloned())
+        else {
+            return;
+        };
+        let leading_context = first_before.start.min(self.options.context_lines);
+        let before_start = first_before.start - leading_context;
+        let after_start = first_after.start - leading_context;
+        let trailing_context = (self.before.len() as u32 - last_before.end).min(self.options.context_lines);
+        let before_end = last_before.end + trailing_context;
+        let after_end = last_after.end + trailing_context;
+
+        self.update_function_name(before_start);
+        self.write_hunk_header(before_start..before_end, after_start..after_end);
+
+        let (before_tokens, after_tokens) = (self.before, self.after);
+        let mut pos = before_start;
+        for (before, after) in std::mem::take(&mut self.changes) {
+            self.write_context(pos..before.start);
+            for &token in &before_tokens[before.start as usize..before.end as usize] {
+                self.write_line(b'-', self.line(token));
+            }
+            for &token in &after_tokens[after.start as usize..after.end as usize] {
+                self.write_line(b'+', self.line(token));
+            }
+            pos = before.end;
+        }
+        self.write_context(pos..before_end);
+        self.flush_words();
+    }
+
+    fn write_hunk_header(&mut self, before: Range<u32>, after: Range<u32>) {
+        fn write_range(out: &mut BString, range: Range<u32>) {
+            let len = range.end - range.start;
+            let start = if len == 0 { range.start } else { range.start + 1 };
+            out.push_str(start.to_string());
+            if len != 1 {
+                out.push_str(format!(",{len}"));
+            }
+        }
+        self.out.push_str("@@ -");
+        write_range(&mut self.out, before);
+        self.out.push_str(" +");
+        write_range(&mut self.out, after);
+        self.out.push_str(" @@");
+        if !self.function_name.is_empty() {
+            self.out.push_byte(b' ');
+            self.out.push_str(&self.function_name);
+        }
+        self.out.push_byte(b'\n');
+    }
+
+    fn write_context(&mut self, lines: Range<u32>) {
+        let before = self.before;
+        for &token in &before[lines.start as usize..lines.end as usize] {
+            self.write_line(b' ', self.line(token));
+        }
+    }
+
+    fn write_line(&mut self, prefix: u8, line: &[u8]) {
+        match self.options.word_diff {
+            None => {
+                self.out.push_byte(prefix);
+                self.out.push_str(line);
+                if !line.ends_with(b"\n") {
+                    self.out.push_str("\n\\ No newline at end of file\n");
+                }
+            }
+            Some(mode) => {
+                // Like in `git`, a missing newline at the end of the file is ignored when showing words.
+                let text = match prefix {
+                    b'-' => &mut self.removed_text,
+                    b'+' => &mut self.added_text,
+                    _ => {
+                        self.flush_words();
+                        if mode == WordDiff::Porcelain {
+                            self.out.push_byte(prefix);
+                        }
+                        &mut self.out
+                    }
+                };
+                text.push_str(line);
+                if !line.ends_with(b"\n") {
+                    text.push_byte(b'\n');
+                }
+                if prefix == b' ' && mode == WordDiff::Porcelain {
+                    self.out.push_str("~\n");
+                }
+            }
+        }
+    }
+
+    /// Show the removed and added text collected so far word by word, with words separated by whitespace.
+    fn flush_words(&mut self) {
+        let Some(mode) = self.options.word_diff else {
+            return;
+        };
+        if self.removed_text.is_empty() && self.added_text.is_empty() {
+            return;
+        }
+        let removed = std::mem::take(&mut self.removed_text);
+        let added = std::mem::take(&mut self.added_text);
+        let out = &mut self.out;
+        if added.is_empty() {
+            write_words(out, mode, Style::Removed, &removed);
+            return;
+        }
+
+        let removed_words = words(&removed);
+        let added_words = words(&added);
+        let mut input = InternedInput::default();
+        input.update_before(removed_words.iter().map(|word| &removed[word.clone()]));
+        input.update_after(added_words.iter().map(|word| &added[word.clone()]));
+
+        let end_of_word_before =
+            |words: &[Range<usize>], index: u32| index.checked_sub(1).map_or(0, |index| words[index as usize].end);
+        let span = |words: &[Range<usize>], range: Range<u32>| {
+            if range.is_empty() {
+                let end = end_of_word_before(words, range.start);
+                end..end
+            } else {
+                words[range.start as usize].start..words[range.end as usize - 1].end
+            }
+        };
+        let mut pos = 0;
+        imara_diff::diff(
+            imara_diff::Algorithm::Myers,
+            &input,
+            |before: Range<u32>, after: Range<u32>| {
+                let removed_span = span(&removed_words, before);
+                let added_span = span(&added_words, after);
+                if pos != added_span.start {
+                    write_words(out, mode, Style::Unchanged, &added[pos..added_span.start]);
+                }
+                if !removed_span.is_empty() {
+                    write_words(out, mode, Style::Removed, &removed[removed_span]);
+                }
+                if !added_span.is_empty() {
+                    write_words(out, mode, Style::Added, &added[added_span.clone()]);
+                }
+                pos = added_span.end;
+            },
+        );
+        if pos != added.len() {
+            write_words(out, mode, Style::Unchanged, &added[pos..]);
+        }
+    }
+}
+
+impl<T> Sink for UnifiedDiff<'_, T>
+where
+    T: Hash + Eq + AsRef<[u8]>,
+{
+    type Out = BString;
+
+    fn process_change(&mut self, before: Range<u32>, after: Range<u32>) {
+        if let Some((last_before, _)) = self.changes.last() {
+            let max_unchanged_lines = 2 * self.options.context_lines + self.options.inter_hunk_context;
+            if before.start - last_before.end > max_unchanged_lines {
+                self.flush();
+            }
+        }
+        self.changes.push((before, after));
+    }
+
+    fn finish(mut self) -> Self::Out {
+        self.flush();
+        self.out
+    }
+}
+
+#[derive(Copy, Clone)]
+enum Style {
+    Unchanged,
+    Removed,
+    Added,
+}
+
+/// Return the ranges of all whitespace-separated words in `text`.
+fn words(text: &[u8]) -> Vec<Range<usize>> {
+    let mut out = Vec::new();
+    let mut start = None;
+    for (index, byte) in text.iter().enumerate() {
+        match (start, is_space(*byte)) {
+            (None, false) => start = Some(index),
+            (Some(word_start), true) => {
+                out.push(word_start..index);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(word_start) = start {
+        out.push(word_start..text.len());
+    }
+    out
+}
+
+fn is_space(byte: u8) -> bool {
+    byte.is_ascii_whitespace() || byte == b'\x0b'
+}
+
+/// Write `text` in the given `style`, with each of its lines marked individually.
+fn write_words(out: &mut BString, mode: WordDiff, style: Style, mut text: &[u8]) {
+    let (prefix, suffix, newline) = match (mode, style) {
+        (WordDiff::Plain, Style::Unchanged) => ("", "", "\n"),
+        (WordDiff::Plain, Style::Removed) => ("[-", "-]", "\n"),
+        (WordDiff::Plain, Style::Added) => ("{+", "+}", "\n"),
+        (WordDiff::Porcelain, Style::Unchanged) => (" ", "\n", "~\n"),
+        (WordDiff::Porcelain, Style::Removed) => ("-", "\n", "~\n"),
+        (WordDiff::Porcelain, Style::Added) => ("+", "\n", "~\n"),
+    };
+    while !text.is_empty() {
+        let line_end = text.find_byte(b'\n');
+        let line = &text[..line_end.unwrap_or(text.len())];
+        if !line.is_empty() {
+            out.push_str(prefix);
+            out.push_str(line);
+            out.push_str(suffix);
+        }
+        match line_end {
+            Some(pos) => {
+                out.push_str(newline);
+                text = &text[pos + 1..];
+            }
+            None => break,
+        }
+    }
+}
@@ -49,3 +49,7 @@ pub mod tree;
 ///
 #[cfg(feature = "blob")]
 pub mod blob;
+
+/// Pair the patches of two versions of a commit series and show how they differ, similar to `git range-diff`.
+#[cfg(feature = "blob")]
+pub mod range_diff;
@@ -0,0 +1,393 @@
+use std::{borrow::Borrow, io::Write};
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+
+use crate::blob::{
+    intern::InternedInput,
+    patch,
+    sources::byte_lines_with_terminator,
+    unified_diff::{FunctionName, Patterns},
+    Algorithm, UnifiedDiff,
+};
+
+/// The percentage of the size of a patch that it costs to consider it added or removed instead of pairing it with
+/// another patch, which is also the default of `git range-diff --creation-factor`.
+pub const DEFAULT_CREATION_FACTOR: u32 = 60;
+
+/// The cost of pairings that must not happen, the same value `git range-diff` uses.
+const COST_MAX: u32 = 1 << 16;
+
+/// The text of a commit as `git range-diff` compares it, with its author, message and the changes it makes to files.
+///
+/// The changes are rendered like `git log -p` would, but each file is introduced by a section header like
+/// ` ## path ##` instead of the usual patch headers, and hunk headers only contain the function name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Patch {
+    text: BString,
+    /// The position in `text` at which the section of the first file starts.
+    diff_start: Option<usize>,
+    /// The amount of lines in the sections of all files.
+    diff_lines: u32,
+}
+
+/// The way a [`Pair`] of patches differs.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Status {
+    /// Both patches are the same, shown as `=`.
+    Unchanged,
+    /// The patches differ, shown as `!`.
+    Changed,
+    /// The patch is only present in the old series, shown as `<`.
+    Removed,
+    /// The patch is only present in the new series, shown as `>`.
+    Added,
+}
+
+impl Status {
+    /// Return the character `git range-diff` uses to indicate this status.
+    pub fn as_char(&self) -> char {
+        match self {
+            Status::Unchanged => '=',
+            Status::Changed => '!',
+            Status::Removed => '<',
+            Status::Added => '>',
+        }
+    }
+}
+
+/// An entry of the pairing produced by [`pairs()`], referring to patches of the old and the new series by their index.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Pair {
+    /// The index of the patch in the old series, or `None` if the patch was added.
+    pub old: Option<usize>,
+    /// The index of the patch in the new series, or `None` if the patch was removed.
+    pub new: Option<usize>,
+    /// How both patches differ.
+    pub status: Status,
+}
+
+impl Patch {
+    /// Start a patch with the `author` of a commit, formatted like `Name <email>`, and its `message`.
+    ///
+    /// Use [`push_file()`](Self::push_file()) to add the changes the commit makes.
+    pub fn new(author: &BStr, message: &BStr) -> Self {
+        let mut text = BString::from(" ## Metadata ##\nAuthor: ");
+        text.push_str(author);
+        text.push_str("\n\n ## Commit message ##\n");
+        let lines = message.lines().skip_while(|line| line.trim().is_empty());
+        for line in lines {
+            let line = line.trim_end();
+            if !line.is_empty() {
+                text.push_str("    ");
+                text.push_str(line);
+            }
+            text.push_byte(b'\n');
+        }
+        Patch {
+            text,
+            diff_start: None,
+            diff_lines: 0,
+        }
+    }
+
+    /// Add the `change` of a file along with its `content`, which are the hunks produced by [`UnifiedDiff`] for
+    /// text files.
+    ///
+    /// Files should be added in the order in which `git diff` shows them.
+    pub fn push_file(&mut self, change: &patch::Change<'_>, content: patch::Content<'_>) {
+        let (old, new) = (change.old_side(), change.new_side());
+        self.text.push_byte(b'\n');
+        self.diff_start.get_or_insert(self.text.len());
+
+        self.text.push_str(" ## ");
+        match (change, old, new) {
+            (patch::Change::Addition { new }, _, _) => {
+                self.text.push_str(new.path);
+                self.text.push_str(" (new)");
+            }
+            (patch::Change::Deletion { old }, _, _) => {
+                self.text.push_str(old.path);
+                self.text.push_str(" (deleted)");
+            }
+            (
+                patch::Change::Rewrite {
+                    old, new, copy: false, ..
+                },
+                _,
+                _,
+            ) => {
+                self.text.push_str(old.path);
+                self.text.push_str(" => ");
+                self.text.push_str(new.path);
+            }
+            (_, _, Some(new)) => self.text.push_str(new.path),
+            (_, _, None) => unreachable!("only deletions lack a new side"),
+        }
+        if let (Some(old), Some(new)) = (old, new) {
+            if old.mode != new.mode {
+                self.text
+                    .push_str(format!(" (mode change {:06o} => {:06o})", old.mode.0, new.mode.0));
+            }
+        }
+        self.text.push_str(" ##\n");
+        self.diff_lines += 1;
+
+        match content {
+            patch::Content::Hunks(hunks) => {
+                let path = new.or(old).expect("at least one side is set").path;
+                for line in hunks.lines() {
+                    if let Some(header) = line.strip_prefix(b"@@ ") {
+                        self.text.push_str("@@");
+                        let function_name = header
+                            .find(b"@@")
+                            .map(|pos| &header[pos + 2..])
+                            .filter(|name| !name.is_empty());
+                        if let Some(function_name) = function_name {
+                            self.text.push_byte(b' ');
+                            self.text.push_str(path);
+                            self.text.push_byte(b':');
+                            self.text.push_str(function_name);
+                        }
+                    } else {
+                        if !matches!(line.first(), Some(b'+' | b'-' | b' ')) {
+                            self.text.push_byte(b' ');
+                        }
+                        self.text.push_str(line);
+                    }
+                    self.text.push_byte(b'\n');
+                    self.diff_lines += 1;
+                }
+            }
+            patch::Content::Binary | patch::Content::BinaryPatch(_) => {
+                self.text.push_str(" Binary files ");
+                self.text.push_str(old.map_or("/dev/null".into(), |side| side.path));
+                self.text.push_str(" and ");
+                self.text.push_str(new.map_or("/dev/null".into(), |side| side.path));
+                self.text.push_str(" differ\n");
+                self.diff_lines += 1;
+            }
+        }
+    }
+
+    /// Return the complete text of the patch.
+    pub fn as_bstr(&self) -> &BStr {
+        self.text.as_ref()
+    }
+
+    /// Return the sections of all changed files, or the complete text if no file was changed, which is what patches
+    /// are compared by to pair them.
+    pub fn diff(&self) -> &BStr {
+        self.text[self.diff_start.unwrap_or(0)..].as_bstr()
+    }
+}
+
+/// Pair the patches of the `old` series with the ones in the `new` series, and return the pairs in the order
+/// `git range-diff` shows them, i.e. in the order of the `new` series with removed patches shown as early as possible.
+///
+/// Patches with the same changes are paired first. All others are paired so that the size of the diffs between paired
+/// patches is minimal overall, where leaving a patch unpaired costs `creation_factor` percent of its own size.
+/// A higher `creation_factor` thus pairs more patches, see [`DEFAULT_CREATION_FACTOR`].
+pub fn pairs(old: &[impl Borrow<Patch>], new: &[impl Borrow<Patch>], creation_factor: u32) -> Vec<Pair> {
+    let old: Vec<&Patch> = old.iter().map(Borrow::borrow).collect();
+    let new: Vec<&Patch> = new.iter().map(Borrow::borrow).collect();
+    let mut old_to_new: Vec<Option<usize>> = vec![None; old.len()];
+    let mut new_to_old: Vec<Option<usize>> = vec![None; new.len()];
+    for (new_index, new_patch) in new.iter().enumerate() {
+        let exact_match = old
+            .iter()
+            .enumerate()
+            .find(|(old_index, old_patch)| old_to_new[*old_index].is_none() && old_patch.diff() == new_patch.diff());
+        if let Some((old_index, _)) = exact_match {
+            old_to_new[old_index] = Some(new_index);
+            new_to_old[new_index] = Some(old_index);
+        }
+    }
+
+    let n = old.len() + new.len();
+    let creation_cost = |patch: &Patch, paired: Option<usize>| match paired {
+        Some(_) => COST_MAX,
+        None => (patch.diff_lines.saturating_mul(creation_factor) / 100).min(COST_MAX),
+    };
+    let mut cost = vec![0; n * n];
+    for (old_index, old_patch) in old.iter().enumerate() {
+        let row = &mut cost[old_index * n..][..n];
+        for (new_index, new_patch) in new.iter().enumerate() {
+            row[new_index] = match (old_to_new[old_index], new_to_old[new_index]) {
+                (Some(paired), _) if paired == new_index => 0,
+                (None, None) => diff_size(old_patch.diff(), new_patch.diff()),
+                _ => COST_MAX,
+            };
+        }
+        row[new.len()..].fill(creation_cost(old_patch, old_to_new[old_index]));
+    }
+    for (new_index, new_patch) in new.iter().enumerate() {
+        let cost_to_create = creation_cost(new_patch, new_to_old[new_index]);
+        for old_index in old.len()..n {
+            cost[old_index * n + new_index] = cost_to_create;
+        }
+    }
+    for (old_index, new_index) in assignment(n, &cost).into_iter().enumerate().take(old.len()) {
+        if new_index < new.len() {
+            old_to_new[old_index] = Some(new_index);
+            new_to_old[new_index] = Some(old_index);
+        }
+    }
+
+    let mut out = Vec::with_capacity(n);
+    let mut shown = vec![false; old.len()];
+    let (mut old_index, mut new_index) = (0, 0);
+    while old_index < old.len() || new_index < new.len() {
+        while old_index < old.len() && shown[old_index] {
+            old_index += 1;
+        }
+        if old_index < old.len() && old_to_new[old_index].is_none() {
+            out.push(Pair {
+                old: Some(old_index),
+                new: None,
+                status: Status::Removed,
+            });
+            old_index += 1;
+            continue;
+        }
+        while new_index < new.len() && new_to_old[new_index].is_none() {
+            out.push(Pair {
+                old: None,
+                new: Some(new_index),
+                status: Status::Added,
+            });
+            new_index += 1;
+        }
+        if new_index < new.len() {
+            let paired = new_to_old[new_index].expect("unpaired patches were shown");
+            out.push(Pair {
+                old: Some(paired),
+                new: Some(new_index),
+                status: if old[paired].text == new[new_index].text {
+                    Status::Unchanged
+                } else {
+                    Status::Changed
+                },
+            });
+            shown[paired] = true;
+            new_index += 1;
+        }
+    }
+    out
+}
+
+/// Write the differences between the `old` and the `new` version of a patch to `out`, with each line indented by four
+/// spaces, like `git range-diff` shows them below a pair of changed patches.
+///
+/// Hunk headers only show the name of the section they are in, like `@@ Commit message` or `@@ file.rs: fn name()`.
+pub fn write_interdiff(out: &mut dyn Write, old: &Patch, new: &Patch) -> std::io::Result<()> {
+    let patterns = Patterns::from_xfuncname("^ ## (.*) ##$\n^.?@@ (.*)$".into()).expect("valid patterns");
+    let input = InternedInput::new(
+        byte_lines_with_terminator(old.text.as_ref()),
+        byte_lines_with_terminator(new.text.as_ref()),
+    );
+    let hunks = crate::blob::diff(
+        Algorithm::Myers,
+        &input,
+        UnifiedDiff::new(
+            &input,
+            crate::blob::unified_diff::Options {
+                function_name: FunctionName::Patterns(patterns),
+                ..Default::default()
+            },
+        ),
+    );
+    for line in hunks.lines_with_terminator() {
+        out.write_all(b"    ")?;
+        match line.strip_prefix(b"@@ -") {
+            Some(header) => {
+                out.write_all(b"@@")?;
+                let function_name = header.find(b"@@").map_or(&b"\n"[..], |pos| &header[pos + 2..]);
+                out.write_all(function_name)?;
+            }
+            None => out.write_all(line)?,
+        }
+    }
+    Ok(())
+}
+
+/// Return the amount of lines in the unified diff between `a` and `b`, including hunk headers.
+fn diff_size(a: &BStr, b: &BStr) -> u32 {
+    let input = InternedInput::new(byte_lines_with_terminator(a), byte_lines_with_terminator(b));
+    let hunks = crate::blob::diff(
+        Algorithm::Myers,
+        &input,
+        UnifiedDiff::new(
+            &input,
+            crate::blob::unified_diff::Options {
+                function_name: FunctionName::None,
+                ..Default::default()
+            },
+        ),
+    );
+    (hunks.lines().count() as u32).min(COST_MAX)
+}
+
+/// Solve the linear assignment problem for the `n * n` matrix `cost`, stored row by row, and return the column
+/// assigned to each row so that the sum of their costs is minimal.
+///
+/// This is the Hungarian algorithm, which runs in `O(n³)`.
+fn assignment(n: usize, cost: &[u32]) -> Vec<usize> {
+    // Rows and columns are numbered from 1, with 0 being a virtual column to start each augmentation from.
+    let mut row_potential = vec![0i64; n + 1];
+    let mut column_potential = vec![0i64; n + 1];
+    let mut row_of_column = vec![0usize; n + 1];
+    let mut previous_column = vec![0usize; n + 1];
+    for row in 1..=n {
+        row_of_column[0] = row;
+        let mut column = 0;
+        let mut min_slack = vec![i64::MAX; n + 1];
+        let mut used = vec![false; n + 1];
+        loop {
+            used[column] = true;
+            let current_row = row_of_column[column];
+            let mut delta = i64::MAX;
+            let mut next_column = 0;
+            for candidate in 1..=n {
+                if used[candidate] {
+                    continue;
+                }
+                let slack = i64::from(cost[(current_row - 1) * n + candidate - 1])
+                    - row_potential[current_row]
+                    - column_potential[candidate];
+                if slack < min_slack[candidate] {
+                    min_slack[candidate] = slack;
+                    previous_column[candidate] = column;
+                }
+                if min_slack[candidate] < delta {
+                    delta = min_slack[candidate];
+                    next_column = candidate;
+                }
+            }
+            for candidate in 0..=n {
+                if used[candidate] {
+                    row_potential[row_of_column[candidate]] += delta;
+                    column_potential[candidate] -= delta;
+                } else {
+                    min_slack[candidate] -= delta;
+                }
+            }
+            column = next_column;
+            if row_of_column[column] == 0 {
+                break;
+            }
+        }
+        while column != 0 {
+            let previous = previous_column[column];
+            row_of_column[column] = row_of_column[previous];
+            column = previous;
+        }
+    }
+
+    let mut out = vec![0; n];
+    for column in 1..=n {
+        if row_of_column[column] != 0 {
+            out[row_of_column[column] - 1] = column - 1;
+        }
+    }
+    out
+}
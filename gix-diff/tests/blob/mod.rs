@@ -1,2 +1,4 @@
 pub(crate) mod pipeline;
 mod platform;
+mod stat;
+mod unified_diff;
@@ -0,0 +1,153 @@
+use gix_diff::blob::{
+    patch,
+    stat::{self, Counts},
+    Algorithm,
+};
+use gix_object::bstr::{BString, ByteSlice};
+
+use super::unified_diff::with_raw_change;
+
+#[test]
+fn diffstats_match_git_baseline() -> crate::Result {
+    let root = gix_testtools::scripted_fixture_read_only_standalone("make_unified_diff_repo.sh")?;
+    let odb = gix_odb::at(root.join(".git/objects"))?;
+    let raw = std::fs::read(root.join("raw"))?;
+
+    let mut changes = Vec::new();
+    for line in raw.lines() {
+        with_raw_change(&odb, line.as_bstr(), |change, old_data, new_data| {
+            let counts = if old_data.contains(&0) || new_data.contains(&0) {
+                Counts::Binary {
+                    old_size: old_data.len() as u64,
+                    new_size: new_data.len() as u64,
+                }
+            } else {
+                let input = gix_diff::blob::intern::InternedInput::new(
+                    gix_diff::blob::sources::byte_lines_with_terminator(old_data),
+                    gix_diff::blob::sources::byte_lines_with_terminator(new_data),
+                );
+                let counter = gix_diff::blob::diff(Algorithm::Myers, &input, gix_diff::blob::sink::Counter::default());
+                Counts::Lines {
+                    added: counter.insertions,
+                    removed: counter.removals,
+                }
+            };
+            changes.push((owned(change), counts));
+            Ok(())
+        })?;
+    }
+    let changes: Vec<_> = changes
+        .iter()
+        .map(|(change, counts)| (change.as_change(), *counts))
+        .collect();
+
+    for (baseline, width) in [("stat-72.diff", 72), ("stat-36.diff", 36)] {
+        let mut actual = Vec::new();
+        stat::write(
+            &mut actual,
+            &changes,
+            &stat::Options {
+                width,
+                ..Default::default()
+            },
+        )?;
+        let expected = std::fs::read(root.join(baseline))?;
+        pretty_assertions::assert_eq!(actual.as_bstr(), expected.as_bstr(), "{baseline}");
+    }
+    Ok(())
+}
+
+#[test]
+fn renames_show_common_directories_once() -> crate::Result {
+    let id = gix_hash::ObjectId::null(gix_hash::Kind::Sha1);
+    let mode = gix_object::tree::EntryKind::Blob.into();
+    for (old, new, expected) in [
+        ("a/b/c/x/y/z", "a/b/d/x/y/z", " a/b/{c => d}/x/y/z | 0\n"),
+        ("old", "new", " old => new | 0\n"),
+        ("dir/old", "dir/new", " dir/{old => new} | 0\n"),
+        ("old/file", "new/file", " {old => new}/file | 0\n"),
+        ("dir/file", "file", " dir/file => file | 0\n"),
+    ] {
+        let change = patch::Change::Rewrite {
+            old: patch::Side {
+                path: old.into(),
+                id: &id,
+                mode,
+            },
+            new: patch::Side {
+                path: new.into(),
+                id: &id,
+                mode,
+            },
+            similarity: 100,
+            copy: false,
+        };
+        let mut out = Vec::new();
+        stat::write(
+            &mut out,
+            &[(change, Counts::Lines { added: 0, removed: 0 })],
+            &stat::Options {
+                summary: false,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(
+            out.lines().next().expect("one line").as_bstr(),
+            expected.trim_end_matches('\n'),
+            "{old} => {new}"
+        );
+    }
+    Ok(())
+}
+
+struct OwnedSide {
+    path: BString,
+    id: gix_hash::ObjectId,
+    mode: gix_object::tree::EntryMode,
+}
+
+struct OwnedChange {
+    old: Option<OwnedSide>,
+    new: Option<OwnedSide>,
+    rewrite: Option<(u8, bool)>,
+}
+
+fn owned(change: &patch::Change<'_>) -> OwnedChange {
+    let side = |side: &patch::Side<'_>| OwnedSide {
+        path: side.path.to_owned(),
+        id: side.id.to_owned(),
+        mode: side.mode,
+    };
+    OwnedChange {
+        old: change.old_side().map(side),
+        new: change.new_side().map(side),
+        rewrite: match change {
+            patch::Change::Rewrite { similarity, copy, .. } => Some((*similarity, *copy)),
+            _ => None,
+        },
+    }
+}
+
+impl OwnedChange {
+    fn as_change(&self) -> patch::Change<'_> {
+        fn side(side: &OwnedSide) -> patch::Side<'_> {
+            patch::Side {
+                path: side.path.as_ref(),
+                id: &side.id,
+                mode: side.mode,
+            }
+        }
+        match (self.old.as_ref().map(side), self.new.as_ref().map(side), self.rewrite) {
+            (Some(old), Some(new), Some((similarity, copy))) => patch::Change::Rewrite {
+                old,
+                new,
+                similarity,
+                copy,
+            },
+            (Some(old), Some(new), None) => patch::Change::Modification { old, new },
+            (None, Some(new), _) => patch::Change::Addition { new },
+            (Some(old), None, _) => patch::Change::Deletion { old },
+            (None, None, _) => unreachable!("at least one side"),
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use gix_diff::blob::{
+    patch,
+    unified_diff::{FunctionName, Options, Patterns, WordDiff},
+    Algorithm, UnifiedDiff,
+};
+use gix_object::{
+    bstr::{BStr, BString, ByteSlice},
+    FindExt,
+};
+
+#[test]
+fn patches_match_git_baseline() -> crate::Result {
+    let root = gix_testtools::scripted_fixture_read_only_standalone("make_unified_diff_repo.sh")?;
+    let odb = gix_odb::at(root.join(".git/objects"))?;
+    let raw = std::fs::read(root.join("raw"))?;
+
+    let with_prefixes = patch::Options {
+        id_hex_len: 12,
+        old_prefix: "old/".into(),
+        new_prefix: "new/".into(),
+        ..Default::default()
+    };
+    for (baseline, options, patch_options) in [
+        ("default.diff", Options::default(), patch::Options::default()),
+        (
+            "context-1.diff",
+            Options {
+                context_lines: 1,
+                ..Default::default()
+            },
+            patch::Options::default(),
+        ),
+        (
+            "context-1-inter-hunk-3.diff",
+            Options {
+                context_lines: 1,
+                inter_hunk_context: 3,
+                ..Default::default()
+            },
+            patch::Options::default(),
+        ),
+        (
+            "context-0.diff",
+            Options {
+                context_lines: 0,
+                ..Default::default()
+            },
+            patch::Options::default(),
+        ),
+        ("abbrev-12-prefixes.diff", Options::default(), with_prefixes),
+        (
+            "word-diff-plain.diff",
+            Options {
+                word_diff: Some(WordDiff::Plain),
+                ..Default::default()
+            },
+            patch::Options::default(),
+        ),
+        (
+            "word-diff-porcelain.diff",
+            Options {
+                word_diff: Some(WordDiff::Porcelain),
+                ..Default::default()
+            },
+            patch::Options::default(),
+        ),
+    ] {
+        let mut actual = Vec::new();
+        for line in raw.lines() {
+            write_patch(&mut actual, &odb, line.as_bstr(), &options, &patch_options)?;
+        }
+        let expected = std::fs::read(root.join(baseline))?;
+        pretty_assertions::assert_eq!(actual.as_bstr(), expected.as_bstr(), "{baseline}");
+    }
+    Ok(())
+}
+
+#[test]
+fn function_names_are_taken_from_the_closest_matching_line_or_the_previous_hunk() -> crate::Result {
+    let before = "a\nfn one\n1\n2\n3\n4\n5\n6\n7\n8\n  fn not-a-match\n9\n10\n11\n12\n";
+    let after = "a\nfn one\n1\n2\nX\n4\n5\n6\n7\n8\n  fn not-a-match\n9\n10\nY\n12\n";
+    let patterns = Patterns::from_xfuncname("^fn (.*)$".into())?;
+    for (function_name, expected) in [
+        (
+            FunctionName::Patterns(patterns),
+            "@@ -5 +5 @@ one\n-3\n+X\n@@ -14 +14 @@ one\n-11\n+Y\n",
+        ),
+        (
+            FunctionName::Default,
+            "@@ -5 +5 @@ fn one\n-3\n+X\n@@ -14 +14 @@ fn one\n-11\n+Y\n",
+        ),
+        (FunctionName::None, "@@ -5 +5 @@\n-3\n+X\n@@ -14 +14 @@\n-11\n+Y\n"),
+    ] {
+        let actual = unified_diff(
+            before,
+            after,
+            Options {
+                context_lines: 0,
+                function_name,
+                ..Default::default()
+            },
+        );
+        assert_eq!(actual, expected);
+    }
+    Ok(())
+}
+
+#[test]
+fn negated_patterns() -> crate::Result {
+    let patterns = Patterns::from_xfuncname("!^fn skip\n^fn".into())?;
+    let actual = unified_diff(
+        "fn one\nfn skip\n1\n",
+        "fn one\nfn skip\n2\n",
+        Options {
+            context_lines: 0,
+            function_name: FunctionName::Patterns(patterns),
+            ..Default::default()
+        },
+    );
+    assert_eq!(
+        actual, "@@ -3 +3 @@ fn\n-1\n+2\n",
+        "negated patterns reject lines, and without a capture group the whole match is used"
+    );
+
+    assert!(
+        Patterns::from_xfuncname("^fn\n!^fn skip".into()).is_err(),
+        "the last pattern must not be negated"
+    );
+    assert!(Patterns::from_xfuncname("(".into()).is_err(), "invalid patterns fail");
+    Ok(())
+}
+
+fn unified_diff(before: &str, after: &str, options: Options) -> BString {
+    let input = gix_diff::blob::intern::InternedInput::new(
+        gix_diff::blob::sources::byte_lines_with_terminator(before.as_bytes()),
+        gix_diff::blob::sources::byte_lines_with_terminator(after.as_bytes()),
+    );
+    gix_diff::blob::diff(Algorithm::Myers, &input, UnifiedDiff::new(&input, options))
+}
+
+/// Write the patch for a `line` of `git diff --raw --no-abbrev` output.
+fn write_patch(
+    out: &mut Vec<u8>,
+    odb: &gix_odb::Handle,
+    line: &BStr,
+    options: &Options,
+    patch_options: &patch::Options,
+) -> crate::Result {
+    with_raw_change(odb, line, |change, old_data, new_data| {
+        let hunks;
+        let content = if old_data.contains(&0) || new_data.contains(&0) {
+            patch::Content::Binary
+        } else {
+            let mut options = options.clone();
+            let path = change.new_side().or(change.old_side()).expect("one side").path;
+            if path.ends_with(b".rs") {
+                options.function_name =
+                    FunctionName::Patterns(Patterns::from_xfuncname("!^fn skipped\n^fn (.*)$".into())?);
+            }
+            let input = gix_diff::blob::intern::InternedInput::new(
+                gix_diff::blob::sources::byte_lines_with_terminator(old_data),
+                gix_diff::blob::sources::byte_lines_with_terminator(new_data),
+            );
+            hunks = gix_diff::blob::diff(Algorithm::Myers, &input, UnifiedDiff::new(&input, options));
+            patch::Content::Hunks(hunks.as_ref())
+        };
+        patch::write(out, change, content, patch_options)?;
+        Ok(())
+    })
+}
+
+/// Parse a `line` of `git diff --raw --no-abbrev` output and call `f` with the change and the data of both sides,
+/// which is empty if a side doesn't exist.
+pub(super) fn with_raw_change(
+    odb: &gix_odb::Handle,
+    line: &BStr,
+    mut f: impl FnMut(&patch::Change<'_>, &[u8], &[u8]) -> crate::Result,
+) -> crate::Result {
+    let (info, paths) = line.split_once_str("\t").expect("tab-separated paths");
+    let info: Vec<_> = info[1..].split_str(" ").collect();
+    let paths: Vec<_> = paths.split_str("\t").map(ByteSlice::as_bstr).collect();
+    let mode = |mode: &[u8]| {
+        gix_object::tree::EntryMode(u16::from_str_radix(mode.to_str().expect("ascii"), 8).expect("octal"))
+    };
+    let (old_id, new_id) = (
+        gix_hash::ObjectId::from_hex(info[2])?,
+        gix_hash::ObjectId::from_hex(info[3])?,
+    );
+    let old = patch::Side {
+        path: paths[0],
+        id: &old_id,
+        mode: mode(info[0]),
+    };
+    let new = patch::Side {
+        path: paths.last().copied().expect("at least one path"),
+        id: &new_id,
+        mode: mode(info[1]),
+    };
+    let status = info[4];
+    let change = match status[0] {
+        b'A' => patch::Change::Addition { new },
+        b'D' => patch::Change::Deletion { old },
+        b'M' => patch::Change::Modification { old, new },
+        b'R' | b'C' => patch::Change::Rewrite {
+            old,
+            new,
+            similarity: status[1..].to_str()?.parse()?,
+            copy: status[0] == b'C',
+        },
+        _ => unreachable!("unexpected status {status:?}"),
+    };
+
+    let (mut old_buf, mut new_buf) = (Vec::new(), Vec::new());
+    let old_data: &[u8] = if old_id.is_null() {
+        &[]
+    } else {
+        odb.find_blob(&old_id, &mut old_buf)?.data
+    };
+    let new_data: &[u8] = if new_id.is_null() {
+        &[]
+    } else {
+        odb.find_blob(&new_id, &mut new_buf)?.data
+    };
+    f(&change, old_data, new_data)
+}
+
+#[test]
+fn unusual_paths_are_quoted_and_exact_renames_have_no_content() -> crate::Result {
+    let id = gix_hash::ObjectId::from_hex(b"587be6b4c3f93f93c489c0111bba5596147a26cb")?;
+    let mode = gix_object::tree::EntryKind::Blob.into();
+    let change = patch::Change::Rewrite {
+        old: patch::Side {
+            path: "ä\"q".into(),
+            id: &id,
+            mode,
+        },
+        new: patch::Side {
+            path: "new name".into(),
+            id: &id,
+            mode,
+        },
+        similarity: 100,
+        copy: false,
+    };
+    let mut out = Vec::new();
+    patch::write(
+        &mut out,
+        &change,
+        patch::Content::Hunks("".into()),
+        &patch::Options::default(),
+    )?;
+    assert_eq!(
+        out.as_bstr(),
+        "diff --git \"a/\\303\\244\\\"q\" b/new name\nsimilarity index 100%\nrename from \"\\303\\244\\\"q\"\nrename to new name\n",
+        "this is what git produces"
+    );
+    Ok(())
+}
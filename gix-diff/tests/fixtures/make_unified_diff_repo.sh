@@ -0,0 +1,72 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git config diff.custom.xfuncname "$(printf '%s\n%s' '!^fn skipped' '^fn (.*)$')"
+echo '*.rs diff=custom' > .gitattributes
+
+for i in $(seq 1 40); do
+  if [ $((i % 10)) = 1 ]; then echo "section $i"; else echo "  item $i"; fi
+done > long.txt
+cat <<EOF >code.rs
+fn first() {
+  one
+  two
+  three
+  four
+}
+fn skipped() {
+  five
+  six
+  seven
+  eight
+}
+EOF
+printf 'a\nb' > no-newline.txt
+printf 'a\nb\n' > loses-newline.txt
+echo "#!/bin/sh" > mode.sh
+seq 1 5 > deleted.txt
+seq 1 10 > renamed-from.txt
+seq 20 30 > copied-from.txt
+printf 'bin\0ary\n' > binary.bin
+echo "content" > "with space.txt"
+cat <<EOF >words.txt
+the quick brown fox
+jumps over the lazy dog
+unchanged line
+and another one that is
+removed entirely
+EOF
+git add . && git commit -q -m "c1"
+
+sed -i 's/  item 3$/  item 3 changed/; s/  item 9$/  item 9 changed/; s/  item 25$/  item 25 changed/; s/  item 40$/  item 40 changed/' long.txt
+sed -i 's/  four/  FOUR/; s/  eight/  EIGHT/' code.rs
+printf 'a\nc' > no-newline.txt
+printf 'a\nb' > loses-newline.txt
+chmod +x mode.sh
+git rm -q deleted.txt
+git mv renamed-from.txt renamed-to.txt
+echo 11 >> renamed-to.txt
+cp copied-from.txt copied-to.txt
+echo 31 >> copied-to.txt
+printf 'bin\0ary changed\n' > binary.bin
+echo "changed" > "with space.txt"
+echo new > added.txt
+cat <<EOF >words.txt
+the slow brown fox
+  jumps over  the dog
+unchanged line
+and another one that was
+EOF
+git add . && git commit -q -m "c2"
+
+git diff --raw --no-abbrev -M --find-copies-harder HEAD~1 HEAD > raw
+git diff -M --find-copies-harder HEAD~1 HEAD > default.diff
+git diff -M --find-copies-harder -U1 HEAD~1 HEAD > context-1.diff
+git diff -M --find-copies-harder -U1 --inter-hunk-context=3 HEAD~1 HEAD > context-1-inter-hunk-3.diff
+git diff -M --find-copies-harder -U0 HEAD~1 HEAD > context-0.diff
+git diff -M --find-copies-harder --abbrev=12 --src-prefix=old/ --dst-prefix=new/ HEAD~1 HEAD > abbrev-12-prefixes.diff
+git diff -M --find-copies-harder --word-diff=plain HEAD~1 HEAD > word-diff-plain.diff
+git diff -M --find-copies-harder --word-diff=porcelain HEAD~1 HEAD > word-diff-porcelain.diff
+git diff -M --find-copies-harder --stat=72 --summary HEAD~1 HEAD > stat-72.diff
+git diff -M --find-copies-harder --stat=36 --summary HEAD~1 HEAD > stat-36.diff
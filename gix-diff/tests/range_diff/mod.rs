@@ -0,0 +1,167 @@
+use gix_diff::{
+    blob::patch,
+    range_diff::{pairs, write_interdiff, Pair, Patch, Status, DEFAULT_CREATION_FACTOR},
+};
+use gix_object::{
+    bstr::{BStr, ByteSlice},
+    tree::EntryKind,
+};
+
+fn patch(message: &str, path: &str, hunks: &str) -> Patch {
+    let id = gix_hash::Kind::Sha1.null();
+    let side = |path: &'static str| patch::Side {
+        path: path.into(),
+        id: &id,
+        mode: EntryKind::Blob.into(),
+    };
+    let path: &'static str = Box::leak(path.to_owned().into_boxed_str());
+    let mut out = Patch::new("A U Thor <author@example.com>".into(), message.into());
+    out.push_file(
+        &patch::Change::Modification {
+            old: side(path),
+            new: side(path),
+        },
+        patch::Content::Hunks(hunks.into()),
+    );
+    out
+}
+
+fn statuses(pairs: &[Pair]) -> Vec<(Option<usize>, Option<usize>, Status)> {
+    pairs.iter().map(|pair| (pair.old, pair.new, pair.status)).collect()
+}
+
+#[test]
+fn patch_text() {
+    let mut patch = Patch::new(
+        "A U Thor <author@example.com>".into(),
+        "\nsubject \n\nbody\n  \nmore\n".into(),
+    );
+    let id = gix_hash::Kind::Sha1.null();
+    let side = |path: &'static str, kind: EntryKind| patch::Side {
+        path: path.into(),
+        id: &id,
+        mode: kind.into(),
+    };
+    patch.push_file(
+        &patch::Change::Rewrite {
+            old: side("old", EntryKind::Blob),
+            new: side("new", EntryKind::BlobExecutable),
+            similarity: 90,
+            copy: false,
+        },
+        patch::Content::Hunks("@@ -1,2 +1,2 @@ fn main() {\n-a\n+b\n c\n\\ No newline at end of file\n".into()),
+    );
+    patch.push_file(
+        &patch::Change::Addition {
+            new: side("image", EntryKind::Blob),
+        },
+        patch::Content::Binary,
+    );
+    patch.push_file(
+        &patch::Change::Deletion {
+            old: side("gone", EntryKind::Blob),
+        },
+        patch::Content::Hunks("@@ -1 +0,0 @@\n-gone\n".into()),
+    );
+    assert_eq!(
+        patch.as_bstr(),
+        " ## Metadata ##
+Author: A U Thor <author@example.com>
+
+ ## Commit message ##
+    subject
+
+    body
+
+    more
+
+ ## old => new (mode change 100644 => 100755) ##
+@@ new: fn main() {
+-a
++b
+ c
+ \\ No newline at end of file
+
+ ## image (new) ##
+ Binary files /dev/null and image differ
+
+ ## gone (deleted) ##
+@@
+-gone
+"
+    );
+    assert!(
+        patch.diff().starts_with(b" ## old => new"),
+        "the diff starts with the first file"
+    );
+}
+
+#[test]
+fn exact_matches_are_paired_even_if_reordered() {
+    let first = patch("first", "a", "@@ -1 +1 @@\n-a\n+b\n");
+    let second = patch("second", "b", "@@ -1 +1 @@\n-c\n+d\n");
+    let reworded = patch("second, reworded", "b", "@@ -1 +1 @@\n-c\n+d\n");
+    assert_eq!(
+        statuses(&pairs(
+            &[&first, &second],
+            &[&reworded, &first],
+            DEFAULT_CREATION_FACTOR
+        )),
+        [
+            (Some(1), Some(0), Status::Changed),
+            (Some(0), Some(1), Status::Unchanged),
+        ],
+        "patches are shown in the order of the new series"
+    );
+}
+
+#[test]
+fn similar_patches_are_paired_depending_on_the_creation_factor() {
+    let lines: Vec<_> = (0..10).map(|n| format!("+line {n}")).collect();
+    let hunks = lines.join("\n") + "\n";
+    let old = patch("add lines", "file", &format!("@@ -0,0 +1,10 @@\n{hunks}"));
+    let new = patch(
+        "add lines",
+        "file",
+        &format!("@@ -0,0 +1,10 @@\n{}", hunks.replace("line 5", "LINE 5")),
+    );
+    let unrelated = patch("other", "other", "@@ -1 +1 @@\n-x\n+y\n");
+
+    assert_eq!(
+        statuses(&pairs(&[&old], &[&new, &unrelated], DEFAULT_CREATION_FACTOR)),
+        [(Some(0), Some(0), Status::Changed), (None, Some(1), Status::Added),]
+    );
+    assert_eq!(
+        statuses(&pairs(&[&old, &unrelated], &[&new], 0)),
+        [
+            (Some(0), None, Status::Removed),
+            (Some(1), None, Status::Removed),
+            (None, Some(0), Status::Added),
+        ],
+        "without creation cost, nothing is paired"
+    );
+    assert!(pairs(&[] as &[Patch], &[] as &[Patch], DEFAULT_CREATION_FACTOR).is_empty());
+}
+
+#[test]
+fn interdiff() -> std::io::Result<()> {
+    let old = patch("message", "file", "@@ -1,2 +1,2 @@ fn main() {\n a\n-b\n+c\n");
+    let new = patch("message", "file", "@@ -1,2 +1,2 @@ fn main() {\n a\n-b\n+d\n");
+    let mut out = Vec::new();
+    write_interdiff(&mut out, &old, &new)?;
+    assert_eq!(
+        out.as_bstr(),
+        "    @@ file
+     @@ file: fn main() {
+      a
+     -b
+    -+c
+    ++d
+"
+    );
+
+    out.clear();
+    write_interdiff(&mut out, &old, &old)?;
+    assert_eq!(out.as_bstr(), BStr::new(""), "nothing is written for equal patches");
+    Ok(())
+}
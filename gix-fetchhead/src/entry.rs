@@ -0,0 +1,94 @@
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+
+use crate::{Entry, Source};
+
+impl Source {
+    /// Classify the full reference `name` on the remote the way `git` does when writing `FETCH_HEAD`.
+    pub fn from_ref_name(name: &BStr) -> Self {
+        if name == "HEAD" {
+            Source::Head
+        } else if let Some(short) = name.strip_prefix(b"refs/heads/") {
+            Source::Branch(short.into())
+        } else if let Some(short) = name.strip_prefix(b"refs/tags/") {
+            Source::Tag(short.into())
+        } else if let Some(short) = name.strip_prefix(b"refs/remotes/") {
+            Source::RemoteTrackingBranch(short.into())
+        } else {
+            Source::Other(name.into())
+        }
+    }
+
+    /// Return the full name of the remote reference, or the hexadecimal object id if an object was fetched by id.
+    pub fn to_ref_name(&self) -> BString {
+        let (prefix, name) = match self {
+            Source::Head => return "HEAD".into(),
+            Source::Branch(name) => ("refs/heads/", name),
+            Source::Tag(name) => ("refs/tags/", name),
+            Source::RemoteTrackingBranch(name) => ("refs/remotes/", name),
+            Source::Other(name) => return name.clone(),
+        };
+        let mut out = BString::from(prefix);
+        out.push_str(name);
+        out
+    }
+
+    /// Write the note describing this source, which precedes the url.
+    fn write_note_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        let (kind, name) = match self {
+            Source::Head => return Ok(()),
+            Source::Branch(name) => ("branch ", name),
+            Source::Tag(name) => ("tag ", name),
+            Source::RemoteTrackingBranch(name) => ("remote-tracking branch ", name),
+            Source::Other(name) => ("", name),
+        };
+        out.write_all(kind.as_bytes())?;
+        out.write_all(b"'")?;
+        out.write_all(name)?;
+        out.write_all(b"' of ")
+    }
+}
+
+impl Entry {
+    /// Serialize this entry as a single line, including the trailing newline, to `out`.
+    ///
+    /// Newlines in the url are escaped as `\n`, just like `git` does.
+    pub fn write_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        self.id.write_hex_to(&mut *out)?;
+        out.write_all(b"\t")?;
+        if !self.for_merge {
+            out.write_all(b"not-for-merge")?;
+        }
+        out.write_all(b"\t")?;
+        self.source.write_note_to(out)?;
+        for (idx, part) in self.url.split_str("\n").enumerate() {
+            if idx != 0 {
+                out.write_all(b"\\n")?;
+            }
+            out.write_all(part)?;
+        }
+        out.write_all(b"\n")
+    }
+}
+
+/// Write all `entries` to `out` in the order `git` writes them, that is all entries marked for merge first,
+/// followed by all others, each in their original order.
+pub fn write<'a>(
+    entries: impl IntoIterator<Item = &'a Entry> + Clone,
+    out: &mut dyn std::io::Write,
+) -> std::io::Result<()> {
+    for for_merge in [true, false] {
+        for entry in entries.clone().into_iter().filter(|e| e.for_merge == for_merge) {
+            entry.write_to(out)?;
+        }
+    }
+    Ok(())
+}
+
+/// Strip trailing slashes and a `.git` suffix from `url`, the way `git` does before writing it into `FETCH_HEAD`.
+pub fn trim_url(url: &BStr) -> &BStr {
+    let url = url.trim_end_with(|c| c == '/').as_bstr();
+    match url.strip_suffix(b".git") {
+        Some(stripped) if !stripped.is_empty() => stripped.as_bstr(),
+        _ => url,
+    }
+}
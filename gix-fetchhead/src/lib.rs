@@ -1,2 +1,47 @@
-#![deny(rust_2018_idioms)]
+//! [Parse][parse()] and [write][write()] `FETCH_HEAD` files, which record the tips obtained by the last fetch operation
+//! along with the information which of them should be merged by a subsequent `pull`.
+#![deny(missing_docs, rust_2018_idioms)]
 #![forbid(unsafe_code)]
+
+use bstr::BString;
+
+///
+pub mod parse;
+
+/// Parse the given `buf` of bytes line by line into [entries][Entry].
+///
+/// Errors may occur per line, but it's up to the caller to stop iteration when one is encountered.
+pub fn parse(buf: &[u8]) -> parse::Lines<'_> {
+    parse::Lines::new(buf)
+}
+
+mod entry;
+pub use entry::{trim_url, write};
+
+/// The remote reference an [`Entry`] was fetched from, as it is described in `FETCH_HEAD`.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub enum Source {
+    /// The `HEAD` reference of the remote, which isn't mentioned by name.
+    Head,
+    /// A branch, with `refs/heads/` stripped off its name.
+    Branch(BString),
+    /// A tag, with `refs/tags/` stripped off its name.
+    Tag(BString),
+    /// A remote tracking branch of the remote, with `refs/remotes/` stripped off its name.
+    RemoteTrackingBranch(BString),
+    /// Any other reference by its full name, or the hexadecimal object id if an object was fetched by id.
+    Other(BString),
+}
+
+/// A line in a `FETCH_HEAD` file.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub struct Entry {
+    /// The object the remote reference pointed to when it was fetched.
+    pub id: gix_hash::ObjectId,
+    /// If `true`, the object is supposed to be merged into the current branch, otherwise it is marked as `not-for-merge`.
+    pub for_merge: bool,
+    /// The remote reference the object was obtained from.
+    pub source: Source,
+    /// The url of the remote the object was fetched from, typically [trimmed][trim_url()].
+    pub url: BString,
+}
@@ -0,0 +1,109 @@
+mod error {
+    use bstr::BString;
+
+    /// The error returned by [`parse()`][crate::parse()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Line {line_number} does not have three tab-separated fields: {line:?}")]
+        MissingField { line_number: usize, line: BString },
+        #[error("Line {line_number} does not start with a hexadecimal object id: {line:?}")]
+        ObjectId {
+            line_number: usize,
+            line: BString,
+            source: gix_hash::decode::Error,
+        },
+        #[error("Line {line_number} has an unknown merge marker {marker:?}, expected nothing or 'not-for-merge'")]
+        MergeMarker { line_number: usize, marker: BString },
+    }
+}
+
+use bstr::{BStr, ByteSlice};
+pub use error::Error;
+
+use crate::{Entry, Source};
+
+/// An iterator to parse `FETCH_HEAD` lines on-demand.
+pub struct Lines<'a> {
+    lines: bstr::Lines<'a>,
+    line_no: usize,
+}
+
+impl<'a> Lines<'a> {
+    pub(crate) fn new(input: &'a [u8]) -> Self {
+        Lines {
+            lines: input.as_bstr().lines(),
+            line_no: 0,
+        }
+    }
+}
+
+impl Iterator for Lines<'_> {
+    type Item = Result<Entry, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for line in self.lines.by_ref() {
+            self.line_no += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            return parse_line(line.as_bstr(), self.line_no).into();
+        }
+        None
+    }
+}
+
+fn parse_line(line: &BStr, line_number: usize) -> Result<Entry, Error> {
+    let mut fields = line.splitn_str(3, "\t");
+    let (id, marker, note) = match (fields.next(), fields.next(), fields.next()) {
+        (Some(id), Some(marker), Some(note)) => (id, marker, note.as_bstr()),
+        _ => {
+            return Err(Error::MissingField {
+                line_number,
+                line: line.into(),
+            })
+        }
+    };
+    let id = gix_hash::ObjectId::from_hex(id).map_err(|source| Error::ObjectId {
+        line_number,
+        line: line.into(),
+        source,
+    })?;
+    let for_merge = match marker {
+        b"" => true,
+        b"not-for-merge" => false,
+        _ => {
+            return Err(Error::MergeMarker {
+                line_number,
+                marker: marker.into(),
+            })
+        }
+    };
+    let (source, url) = parse_note(note);
+    Ok(Entry {
+        id,
+        for_merge,
+        source,
+        url: url.into(),
+    })
+}
+
+/// Split `note` into the description of the remote reference and the url of the remote.
+fn parse_note(note: &BStr) -> (Source, &BStr) {
+    type MakeSource = fn(bstr::BString) -> Source;
+    let kinds: [(&str, MakeSource); 4] = [
+        ("branch '", Source::Branch),
+        ("tag '", Source::Tag),
+        ("remote-tracking branch '", Source::RemoteTrackingBranch),
+        ("'", Source::Other),
+    ];
+    for (prefix, make_source) in kinds {
+        let Some(rest) = note.strip_prefix(prefix.as_bytes()) else {
+            continue;
+        };
+        if let Some(end) = rest.find("' of ") {
+            return (make_source(rest[..end].into()), rest[end + "' of ".len()..].as_bstr());
+        }
+    }
+    (Source::Head, note)
+}
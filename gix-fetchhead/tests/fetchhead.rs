@@ -0,0 +1,136 @@
+use gix_fetchhead::{parse, Entry, Source};
+use gix_hash::ObjectId;
+use gix_testtools::fixture_bytes;
+
+fn hex_to_id(hex: &str) -> ObjectId {
+    ObjectId::from_hex(hex.as_bytes()).expect("valid hex")
+}
+
+fn entry(hex: &str, for_merge: bool, source: Source, url: &str) -> Entry {
+    Entry {
+        id: hex_to_id(hex),
+        for_merge,
+        source,
+        url: url.into(),
+    }
+}
+
+#[test]
+fn a_typical_fetch_head() {
+    let input = fixture_bytes("typical.txt");
+    let actual = gix_fetchhead::parse(&input).map(Result::unwrap).collect::<Vec<_>>();
+    let url = "https://github.com/Byron/gitoxide";
+    assert_eq!(
+        actual,
+        vec![
+            entry(
+                "b22f680cb17fac2dd449956f642d9d9ade222f11",
+                true,
+                Source::Branch("main".into()),
+                url
+            ),
+            entry(
+                "b22f680cb17fac2dd449956f642d9d9ade222f11",
+                true,
+                Source::Head,
+                "https://example.com/other"
+            ),
+            entry(
+                "4b825dc642cb6eb9a060e54bf8d69288fbee4904",
+                false,
+                Source::Branch("feat/it's-complicated".into()),
+                url
+            ),
+            entry(
+                "5a8fd6f3a1a8e5a6c2f6b1d0c1f4b6b4a8c2f3e1",
+                false,
+                Source::Tag("v1.0".into()),
+                url
+            ),
+            entry(
+                "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391",
+                false,
+                Source::RemoteTrackingBranch("upstream/main".into()),
+                "../local"
+            ),
+            entry(
+                "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391",
+                false,
+                Source::Other("refs/pull/1/head".into()),
+                url
+            ),
+        ]
+    );
+}
+
+#[test]
+fn writing_round_trips_and_puts_entries_for_merge_first() -> Result<(), Box<dyn std::error::Error>> {
+    let input = fixture_bytes("typical.txt");
+    let mut entries = gix_fetchhead::parse(&input).collect::<Result<Vec<_>, _>>()?;
+
+    let mut out = Vec::new();
+    gix_fetchhead::write(&entries, &mut out)?;
+    assert_eq!(out, input, "the fixture is written in the same order as git would");
+
+    entries.reverse();
+    out.clear();
+    gix_fetchhead::write(&entries, &mut out)?;
+    let actual = gix_fetchhead::parse(&out).collect::<Result<Vec<_>, _>>()?;
+    assert!(actual[0].for_merge && actual[1].for_merge);
+    assert_eq!(actual[0].source, Source::Head, "the original order is kept otherwise");
+    assert!(actual[2..].iter().all(|e| !e.for_merge));
+    Ok(())
+}
+
+#[test]
+fn newlines_in_urls_are_escaped() -> std::io::Result<()> {
+    let mut out = Vec::new();
+    entry("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391", false, Source::Head, "a\nb").write_to(&mut out)?;
+    assert_eq!(out, b"e69de29bb2d1d6434b8b29ae775ad8c2e48c5391\tnot-for-merge\ta\\nb\n");
+    Ok(())
+}
+
+#[test]
+fn source_from_and_to_ref_name() {
+    for (name, expected) in [
+        ("HEAD", Source::Head),
+        ("refs/heads/main", Source::Branch("main".into())),
+        ("refs/tags/v1", Source::Tag("v1".into())),
+        (
+            "refs/remotes/origin/main",
+            Source::RemoteTrackingBranch("origin/main".into()),
+        ),
+        ("refs/pull/1/head", Source::Other("refs/pull/1/head".into())),
+    ] {
+        let source = Source::from_ref_name(name.into());
+        assert_eq!(source, expected);
+        assert_eq!(source.to_ref_name(), name, "round-trips");
+    }
+}
+
+#[test]
+fn trim_url() {
+    for (input, expected) in [
+        ("https://example.com/repo.git/", "https://example.com/repo"),
+        ("https://example.com/repo//", "https://example.com/repo"),
+        ("../repo", "../repo"),
+        (".git", ".git"),
+    ] {
+        assert_eq!(gix_fetchhead::trim_url(input.into()), expected, "{input}");
+    }
+}
+
+#[test]
+fn line_numbers_are_counted_correctly_in_errors() {
+    let input = fixture_bytes("invalid.txt");
+    let mut actual = gix_fetchhead::parse(&input).collect::<Vec<_>>().into_iter();
+    assert_eq!(actual.len(), 4);
+
+    assert!(actual.next().expect("four items").is_ok());
+    let err = actual.next().expect("three items left").unwrap_err();
+    assert!(matches!(err, parse::Error::ObjectId { line_number: 3, .. }));
+    let err = actual.next().expect("two items left").unwrap_err();
+    assert!(matches!(err, parse::Error::MissingField { line_number: 4, .. }));
+    let err = actual.next().expect("one item left").unwrap_err();
+    assert!(matches!(err, parse::Error::MergeMarker { line_number: 5, .. }));
+}
@@ -0,0 +1,172 @@
+use std::ops::Range;
+
+use bstr::BStr;
+use imara_diff::intern::{InternedInput, Token, TokenSource};
+
+/// The way conflicting hunks are presented in the merged output.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ConflictStyle {
+    /// Show the conflicting lines of *ours* and *theirs* only, like git's `merge.conflictStyle = merge`.
+    #[default]
+    Merge,
+    /// Also show the lines of the common ancestor between *ours* and *theirs*, like git's `merge.conflictStyle = diff3`.
+    Diff3,
+}
+
+/// The names to write next to conflict markers to identify each side of the merge.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Labels<'a> {
+    /// The name of the common ancestor, shown after `|||||||` when using [`ConflictStyle::Diff3`].
+    pub ancestor: Option<&'a BStr>,
+    /// The name of the current version, *ours*, shown after `<<<<<<<`.
+    pub current: Option<&'a BStr>,
+    /// The name of the other version, *theirs*, shown after `>>>>>>>`.
+    pub other: Option<&'a BStr>,
+}
+
+/// Options to control how blobs are merged.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// How to present conflicts.
+    pub conflict_style: ConflictStyle,
+    /// The amount of characters to use for each conflict marker, like `<<<<<<<`. Defaults to 7.
+    pub marker_size: usize,
+    /// The diff algorithm to use to learn about the changes of each side compared to the common ancestor.
+    pub algorithm: imara_diff::Algorithm,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            conflict_style: Default::default(),
+            marker_size: 7,
+            algorithm: imara_diff::Algorithm::Myers,
+        }
+    }
+}
+
+/// The result of a [merge()].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Resolution {
+    /// All changes could be merged without conflict.
+    Complete,
+    /// At least one hunk could not be merged automatically, and conflict markers were written instead.
+    Conflict,
+}
+
+/// Return `true` if `data` is considered binary, which is when it contains a null-byte in its first 8000 bytes,
+/// just like git determines it.
+pub fn is_binary(data: &[u8]) -> bool {
+    data.iter().take(8000).any(|b| *b == 0)
+}
+
+/// Perform a line-based three-way merge of `ours` and `theirs` which both derive from the common ancestor `base`,
+/// and write the result into `out`, which is cleared beforehand.
+///
+/// Changes made by only one side are taken as is, as are changes that are the same on both sides. If both sides changed
+/// the same or adjacent lines differently, a conflict is written using `labels` for the markers as configured in `options`.
+///
+/// Note that the inputs are treated as text, use [`is_binary()`] to detect binary files beforehand which can't be merged that way.
+pub fn merge(base: &[u8], ours: &[u8], theirs: &[u8], labels: Labels<'_>, options: Options, out: &mut Vec<u8>) -> Resolution {
+    out.clear();
+    let mut input = InternedInput::new(
+        imara_diff::sources::byte_lines_with_terminator(base),
+        imara_diff::sources::byte_lines_with_terminator(ours),
+    );
+    let ours_tokens = std::mem::take(&mut input.after);
+    input.update_after(imara_diff::sources::byte_lines_with_terminator(theirs).tokenize());
+    let theirs_tokens = std::mem::take(&mut input.after);
+
+    let mut hunks = Vec::new();
+    for (side, tokens) in [(Side::Ours, &ours_tokens), (Side::Theirs, &theirs_tokens)] {
+        imara_diff::diff_with_tokens(
+            options.algorithm,
+            &input.before,
+            tokens,
+            input.interner.num_tokens(),
+            |before: Range<u32>, after: Range<u32>| hunks.push(Hunk { before, after, side }),
+        );
+    }
+    hunks.sort_by_key(|h| (h.before.start, h.before.end));
+
+    let write_tokens = |tokens: &[Token], out: &mut Vec<u8>| {
+        for token in tokens {
+            out.extend_from_slice(input.interner[*token]);
+        }
+    };
+
+    let mut resolution = Resolution::Complete;
+    let mut base_cursor = 0;
+    let mut hunks = hunks.into_iter().peekable();
+    while let Some(first) = hunks.next() {
+        let mut group = vec![first];
+        let mut group_end = group[0].before.end;
+        while let Some(next) = hunks.next_if(|h| h.before.start <= group_end) {
+            group_end = group_end.max(next.before.end);
+            group.push(next);
+        }
+        let group_start = group[0].before.start;
+
+        write_tokens(&input.before[base_cursor..group_start as usize], out);
+        base_cursor = group_end as usize;
+
+        let ours = side_range(&group, Side::Ours, group_start, group_end).map(|r| &ours_tokens[r]);
+        let theirs = side_range(&group, Side::Theirs, group_start, group_end).map(|r| &theirs_tokens[r]);
+        match (ours, theirs) {
+            (Some(ours), None) => write_tokens(ours, out),
+            (None, Some(theirs)) => write_tokens(theirs, out),
+            (Some(ours), Some(theirs)) if ours == theirs => write_tokens(ours, out),
+            (Some(ours), Some(theirs)) => {
+                resolution = Resolution::Conflict;
+                write_marker(out, b'<', options.marker_size, labels.current);
+                write_tokens(ours, out);
+                if options.conflict_style == ConflictStyle::Diff3 {
+                    write_marker(out, b'|', options.marker_size, labels.ancestor);
+                    write_tokens(&input.before[group_start as usize..group_end as usize], out);
+                }
+                write_marker(out, b'=', options.marker_size, None);
+                write_tokens(theirs, out);
+                write_marker(out, b'>', options.marker_size, labels.other);
+            }
+            (None, None) => unreachable!("each group has at least one hunk"),
+        }
+    }
+    write_tokens(&input.before[base_cursor..], out);
+    resolution
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Side {
+    Ours,
+    Theirs,
+}
+
+#[derive(Debug, Clone)]
+struct Hunk {
+    before: Range<u32>,
+    after: Range<u32>,
+    side: Side,
+}
+
+/// Return the range of tokens on `side` that corresponds to the range of base tokens `group_start..group_end`,
+/// or `None` if `side` didn't change anything in that range.
+fn side_range(group: &[Hunk], side: Side, group_start: u32, group_end: u32) -> Option<Range<usize>> {
+    let mut hunks = group.iter().filter(|h| h.side == side);
+    let first = hunks.next()?;
+    let last = hunks.next_back().unwrap_or(first);
+    let start = first.after.start - (first.before.start - group_start);
+    let end = last.after.end + (group_end - last.before.end);
+    Some(start as usize..end as usize)
+}
+
+fn write_marker(out: &mut Vec<u8>, marker: u8, size: usize, label: Option<&BStr>) {
+    if !out.is_empty() && !out.ends_with(b"\n") {
+        out.push(b'\n');
+    }
+    out.extend(std::iter::repeat(marker).take(size));
+    if let Some(label) = label {
+        out.push(b' ');
+        out.extend_from_slice(label);
+    }
+    out.push(b'\n');
+}
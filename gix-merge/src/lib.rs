@@ -0,0 +1,10 @@
+//! Provide facilities to perform three-way merges of blobs and trees, the building block for higher-level
+//! operations like cherry-picks, reverts and merges of commits.
+#![deny(rust_2018_idioms, missing_docs)]
+#![forbid(unsafe_code)]
+
+///
+pub mod blob;
+
+///
+pub mod tree;
@@ -0,0 +1,316 @@
+use std::collections::BTreeMap;
+
+use bstr::{BStr, BString, ByteSlice};
+use gix_hash::{oid, ObjectId};
+use gix_object::{tree::EntryMode, FindExt};
+
+use crate::blob;
+
+/// The error returned by [merge()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindTree(#[from] gix_object::find::existing_iter::Error),
+    #[error(transparent)]
+    FindBlob(#[from] gix_object::find::existing_object::Error),
+    #[error(transparent)]
+    Diff(#[from] gix_diff::tree::changes::Error),
+    #[error(transparent)]
+    EditTree(#[from] gix_object::tree::editor::Error),
+    #[error("Could not write merged object")]
+    WriteObject(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+}
+
+/// Options for [merge()].
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The options to use when merging the content of blobs that were changed on both sides.
+    pub blob: blob::Options,
+}
+
+/// The way a conflict came to be, which also indicates what was written into the merged tree at its path.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ConflictKind {
+    /// Both sides modified the same lines of a file differently.
+    ///
+    /// The merged tree contains the file with conflict markers.
+    Content,
+    /// Both sides added a file at the same path with different content.
+    ///
+    /// The merged tree contains the file with conflict markers, merged as if both started out empty.
+    AddAdd,
+    /// One side modified the file while the other side deleted it.
+    ///
+    /// The merged tree contains the modified version.
+    ModifyDelete,
+    /// One side placed a non-directory entry where the other side has a directory, or the other way around.
+    ///
+    /// The merged tree contains the entry as seen from *ours*.
+    DirectoryFile,
+    /// Both sides changed the entry in ways that can't be merged line by line, for example when it's a binary file,
+    /// a symbolic link or a submodule, or when its type was changed.
+    ///
+    /// The merged tree contains the entry as seen from *ours*.
+    Unmergeable,
+}
+
+/// A path that couldn't be merged cleanly.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Conflict {
+    /// The path relative to the root of the merged tree, with `/` as separator.
+    pub path: BString,
+    /// The kind of conflict.
+    pub kind: ConflictKind,
+}
+
+/// The outcome of a [merge()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The id of the merged tree, which contains conflict markers or one side of the conflict if there were conflicts.
+    pub tree: ObjectId,
+    /// All paths that couldn't be merged cleanly, sorted by path.
+    pub conflicts: Vec<Conflict>,
+}
+
+impl Outcome {
+    /// Return `true` if there is at least one conflict, which means [the tree](Self::tree) should not be used
+    /// without human intervention.
+    pub fn has_conflicts(&self) -> bool {
+        !self.conflicts.is_empty()
+    }
+}
+
+/// Merge the changes that were made from the `base` tree to the `theirs` tree into the `ours` tree and return the merged tree
+/// along with all conflicts that were encountered.
+///
+/// `objects` is used to lookup trees and blobs, and `write` is called with the kind and the serialized data of each object
+/// that needs to be written to produce the merged tree, returning its id.
+/// `labels` are used to mark each side in conflicting hunks of blobs.
+///
+/// Note that renames aren't detected, they are seen as deletions and additions.
+/// Mode changes are merged alongside the content, so the executable bit changed on one side is kept even if the other side
+/// changed the content of the same file.
+pub fn merge<E>(
+    base: &oid,
+    ours: &oid,
+    theirs: &oid,
+    labels: blob::Labels<'_>,
+    objects: &impl FindExt,
+    mut write: impl FnMut(gix_object::Kind, &[u8]) -> Result<ObjectId, E>,
+    options: Options,
+) -> Result<Outcome, Error>
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+{
+    let mut state = gix_diff::tree::State::default();
+    let ours_changes = changes(base, ours, objects, &mut state)?;
+    let theirs_changes = changes(base, theirs, objects, &mut state)?;
+
+    let mut buf = Vec::new();
+    let ours_tree = if ours == ObjectId::empty_tree(ours.kind()) {
+        gix_object::Tree::empty()
+    } else {
+        objects.find_tree(ours, &mut buf)?.into()
+    };
+    let mut editor = gix_object::tree::Editor::new(ours_tree, objects, ours.kind());
+    let mut conflicts = Vec::new();
+    let (mut base_buf, mut ours_buf, mut theirs_buf, mut merged) = (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+    for (path, theirs_change) in &theirs_changes {
+        let components = path.split(|b| *b == b'/');
+        let Some(ours_change) = ours_changes.get(path) else {
+            match theirs_change.current {
+                Some(entry) => {
+                    if clashes_with_directory_or_file(path.as_ref(), &ours_changes) {
+                        conflicts.push(Conflict {
+                            path: path.clone(),
+                            kind: ConflictKind::DirectoryFile,
+                        });
+                    } else {
+                        editor.upsert(components, entry.mode.kind(), entry.id)?;
+                    }
+                }
+                None => {
+                    editor.remove(components)?;
+                }
+            }
+            continue;
+        };
+
+        match (ours_change.current, theirs_change.current) {
+            (ours, theirs) if ours == theirs => {}
+            (Some(_), None) => conflicts.push(Conflict {
+                path: path.clone(),
+                kind: ConflictKind::ModifyDelete,
+            }),
+            (None, Some(theirs)) => {
+                let kind = if clashes_with_directory_or_file(path.as_ref(), &ours_changes) {
+                    ConflictKind::DirectoryFile
+                } else {
+                    editor.upsert(components, theirs.mode.kind(), theirs.id)?;
+                    ConflictKind::ModifyDelete
+                };
+                conflicts.push(Conflict {
+                    path: path.clone(),
+                    kind,
+                });
+            }
+            (Some(ours), Some(theirs)) => {
+                let base = theirs_change.previous;
+                let is_text_merge_possible = ours.mode.is_blob()
+                    && theirs.mode.is_blob()
+                    && base.map_or(true, |base| base.mode.is_blob());
+                if !is_text_merge_possible {
+                    conflicts.push(Conflict {
+                        path: path.clone(),
+                        kind: ConflictKind::Unmergeable,
+                    });
+                    continue;
+                }
+                let base_data: &[u8] = match base {
+                    Some(base) => objects.find_blob(&base.id, &mut base_buf)?.data,
+                    None => &[],
+                };
+                let ours_data = objects.find_blob(&ours.id, &mut ours_buf)?.data;
+                let theirs_data = objects.find_blob(&theirs.id, &mut theirs_buf)?.data;
+                if blob::is_binary(base_data) || blob::is_binary(ours_data) || blob::is_binary(theirs_data) {
+                    conflicts.push(Conflict {
+                        path: path.clone(),
+                        kind: ConflictKind::Unmergeable,
+                    });
+                    continue;
+                }
+                let resolution = blob::merge(base_data, ours_data, theirs_data, labels, options.blob, &mut merged);
+                let id = write(gix_object::Kind::Blob, &merged).map_err(|err| Error::WriteObject(err.into()))?;
+                let mode = match base {
+                    Some(base) if base.mode == ours.mode => theirs.mode,
+                    _ => ours.mode,
+                };
+                editor.upsert(components, mode.kind(), id)?;
+                if resolution == blob::Resolution::Conflict {
+                    conflicts.push(Conflict {
+                        path: path.clone(),
+                        kind: if base.is_some() {
+                            ConflictKind::Content
+                        } else {
+                            ConflictKind::AddAdd
+                        },
+                    });
+                }
+            }
+            (None, None) => unreachable!("equal states are handled above"),
+        }
+    }
+
+    let tree = editor.write(|tree| {
+        buf.clear();
+        gix_object::WriteTo::write_to(tree, &mut buf).expect("write to memory works");
+        write(gix_object::Kind::Tree, &buf).map_err(|err| Error::WriteObject(err.into()))
+    })?;
+    Ok(Outcome { tree, conflicts })
+}
+
+/// The state of a non-tree entry in a tree.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Entry {
+    mode: EntryMode,
+    id: ObjectId,
+}
+
+/// The state of a path before and after a change, with `None` meaning it doesn't exist.
+#[derive(Debug, Copy, Clone)]
+struct Change {
+    previous: Option<Entry>,
+    current: Option<Entry>,
+}
+
+/// Return the changes of all non-tree entries from `from` to `to`, keyed by their path.
+fn changes(
+    from: &oid,
+    to: &oid,
+    objects: &impl FindExt,
+    state: &mut gix_diff::tree::State,
+) -> Result<BTreeMap<BString, Change>, Error> {
+    let (mut from_buf, mut to_buf) = (Vec::new(), Vec::new());
+    let mut recorder = gix_diff::tree::Recorder::default();
+    gix_diff::tree::Changes::from(tree_iter(from, objects, &mut from_buf)?).needed_to_obtain(
+        tree_iter(to, objects, &mut to_buf)?,
+        state,
+        objects,
+        &mut recorder,
+    )?;
+
+    use gix_diff::tree::recorder::Change::*;
+    Ok(recorder
+        .records
+        .into_iter()
+        .filter_map(|change| {
+            let (path, change) = match change {
+                Addition { entry_mode, oid, path } => (
+                    path,
+                    Change {
+                        previous: None,
+                        current: Some(Entry { mode: entry_mode, id: oid }),
+                    },
+                ),
+                Deletion { entry_mode, oid, path } => (
+                    path,
+                    Change {
+                        previous: Some(Entry { mode: entry_mode, id: oid }),
+                        current: None,
+                    },
+                ),
+                Modification {
+                    previous_entry_mode,
+                    previous_oid,
+                    entry_mode,
+                    oid,
+                    path,
+                } => (
+                    path,
+                    Change {
+                        previous: Some(Entry {
+                            mode: previous_entry_mode,
+                            id: previous_oid,
+                        }),
+                        current: Some(Entry { mode: entry_mode, id: oid }),
+                    },
+                ),
+            };
+            // Trees are represented by the entries they contain, and type-changes are seen as deletion and addition.
+            let previous = change.previous.filter(|e| e.mode.is_no_tree());
+            let current = change.current.filter(|e| e.mode.is_no_tree());
+            (previous.is_some() || current.is_some()).then_some((path, Change { previous, current }))
+        })
+        .collect())
+}
+
+fn tree_iter<'a>(
+    id: &oid,
+    objects: &impl FindExt,
+    buf: &'a mut Vec<u8>,
+) -> Result<gix_object::TreeRefIter<'a>, gix_object::find::existing_iter::Error> {
+    if id == ObjectId::empty_tree(id.kind()) {
+        Ok(gix_object::TreeRefIter::from_bytes(&[]))
+    } else {
+        objects.find_tree_iter(id, buf)
+    }
+}
+
+/// Return `true` if `path`, which is about to receive a non-tree entry, would be placed where `ours` has a non-tree
+/// entry in one of its parent directories, or if `ours` added entries underneath `path` as a directory.
+fn clashes_with_directory_or_file(path: &BStr, ours: &BTreeMap<BString, Change>) -> bool {
+    let has_file_in_parent_directory = path
+        .iter()
+        .enumerate()
+        .filter(|(_, b)| **b == b'/')
+        .any(|(end, _)| ours.get(path[..end].as_bstr()).map_or(false, |c| c.current.is_some()));
+    if has_file_in_parent_directory {
+        return true;
+    }
+    let mut directory = path.to_owned();
+    directory.push(b'/');
+    ours.range(directory.clone()..)
+        .take_while(|(p, _)| p.starts_with(&directory))
+        .any(|(_, c)| c.current.is_some())
+}
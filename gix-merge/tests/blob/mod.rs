@@ -0,0 +1,89 @@
+use gix_merge::blob::{merge, ConflictStyle, Labels, Options, Resolution};
+
+fn merged(base: &str, ours: &str, theirs: &str, options: Options) -> (String, Resolution) {
+    let mut out = Vec::new();
+    let labels = Labels {
+        ancestor: Some("base".into()),
+        current: Some("ours".into()),
+        other: Some("theirs".into()),
+    };
+    let resolution = merge(
+        base.as_bytes(),
+        ours.as_bytes(),
+        theirs.as_bytes(),
+        labels,
+        options,
+        &mut out,
+    );
+    (String::from_utf8(out).expect("valid UTF-8"), resolution)
+}
+
+#[test]
+fn changes_of_one_side_are_taken_as_is() {
+    let base = "1\n2\n3\n";
+    assert_eq!(
+        merged(base, "1\n2\n3\n", "1\ntwo\n3\n", Default::default()),
+        ("1\ntwo\n3\n".into(), Resolution::Complete)
+    );
+    assert_eq!(
+        merged(base, "0\n1\n2\n3\n", base, Default::default()),
+        ("0\n1\n2\n3\n".into(), Resolution::Complete)
+    );
+}
+
+#[test]
+fn non_overlapping_changes_of_both_sides_are_combined() {
+    assert_eq!(
+        merged("1\n2\n3\n4\n5\n", "one\n2\n3\n4\n5\n", "1\n2\n3\n4\nfive\n", Default::default()),
+        ("one\n2\n3\n4\nfive\n".into(), Resolution::Complete)
+    );
+    assert_eq!(
+        merged("1\n2\n3\n", "1\n3\n", "1\n3\n", Default::default()),
+        ("1\n3\n".into(), Resolution::Complete),
+        "identical changes on both sides are no conflict"
+    );
+}
+
+#[test]
+fn overlapping_changes_are_conflicts() {
+    assert_eq!(
+        merged("1\n2\n3\n", "1\nours\n3\n", "1\ntheirs\n3\n", Default::default()),
+        (
+            "1\n<<<<<<< ours\nours\n=======\ntheirs\n>>>>>>> theirs\n3\n".into(),
+            Resolution::Conflict
+        )
+    );
+}
+
+#[test]
+fn conflict_styles_and_marker_sizes() {
+    let options = Options {
+        conflict_style: ConflictStyle::Diff3,
+        marker_size: 3,
+        ..Default::default()
+    };
+    assert_eq!(
+        merged("1\n2\n3\n", "1\nours\n3\n", "1\ntheirs\n3\n", options),
+        (
+            "1\n<<< ours\nours\n||| base\n2\n===\ntheirs\n>>> theirs\n3\n".into(),
+            Resolution::Conflict
+        )
+    );
+}
+
+#[test]
+fn missing_trailing_newlines_do_not_corrupt_markers() {
+    assert_eq!(
+        merged("1", "ours", "theirs", Default::default()),
+        (
+            "<<<<<<< ours\nours\n=======\ntheirs\n>>>>>>> theirs\n".into(),
+            Resolution::Conflict
+        )
+    );
+}
+
+#[test]
+fn is_binary() {
+    assert!(gix_merge::blob::is_binary(b"a\0b"));
+    assert!(!gix_merge::blob::is_binary(b"text"));
+}
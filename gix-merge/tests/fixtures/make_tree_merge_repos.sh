@@ -0,0 +1,72 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function write_trees() {
+  {
+    for name in base ours theirs; do
+      echo "$name $(git rev-parse $name^{tree})"
+    done
+  } > trees
+}
+
+git init clean
+(cd clean
+  seq 1 10 > a
+  echo b > b
+  mkdir dir
+  echo c > dir/c
+  seq 1 3 > mode-ours
+  seq 1 3 > mode-theirs
+  git add . && git commit -qm base
+  git branch base
+
+  git checkout -qb ours
+  sed -i.bak 's/^1$/one/' a && rm a.bak
+  git rm -q b
+  echo new > ours-new
+  chmod +x mode-ours
+  echo ours >> mode-theirs
+  git add . && git commit -qm ours
+
+  git checkout -qb theirs base
+  sed -i.bak 's/^10$/ten/' a && rm a.bak
+  echo changed > dir/c
+  echo new > dir/theirs-new
+  chmod +x dir/theirs-new
+  echo theirs >> mode-ours
+  chmod +x mode-theirs
+  git add . && git commit -qm theirs
+
+  write_trees
+  echo "expected $(git merge-tree --write-tree ours theirs)" >> trees
+)
+
+git init conflicts
+(cd conflicts
+  seq 1 3 > content
+  echo original > modify-delete
+  echo original > delete-modify
+  printf 'binary\0' > binary
+  git add . && git commit -qm base
+  git branch base
+
+  git checkout -qb ours
+  sed -i.bak 's/^2$/ours/' content && rm content.bak
+  echo ours > modify-delete
+  git rm -q delete-modify
+  echo ours > add-add
+  echo ours > directory-file
+  printf 'ours\0' > binary
+  git add . && git commit -qm ours
+
+  git checkout -qb theirs base
+  sed -i.bak 's/^2$/theirs/' content && rm content.bak
+  git rm -q modify-delete
+  echo theirs > delete-modify
+  echo theirs > add-add
+  mkdir directory-file && echo theirs > directory-file/file
+  printf 'theirs\0' > binary
+  git add . && git commit -qm theirs
+
+  write_trees
+)
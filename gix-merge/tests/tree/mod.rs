@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use gix_merge::tree::{merge, Conflict, ConflictKind};
+use gix_object::FindExt;
+use gix_odb::Write;
+
+fn odb_and_trees(name: &str) -> crate::Result<(gix_odb::memory::Proxy<gix_odb::Handle>, HashMap<String, gix_hash::ObjectId>)> {
+    let root = gix_testtools::scripted_fixture_read_only("make_tree_merge_repos.sh")?.join(name);
+    let odb = gix_odb::memory::Proxy::new(gix_odb::at(root.join(".git").join("objects"))?, gix_hash::Kind::Sha1);
+    let trees = std::fs::read_to_string(root.join("trees"))?
+        .lines()
+        .map(|line| {
+            let (name, hex) = line.split_once(' ').expect("name and id");
+            (name.to_owned(), gix_hash::ObjectId::from_hex(hex.as_bytes()).expect("valid hex"))
+        })
+        .collect();
+    Ok((odb, trees))
+}
+
+fn merge_trees(
+    odb: &gix_odb::memory::Proxy<gix_odb::Handle>,
+    trees: &HashMap<String, gix_hash::ObjectId>,
+) -> crate::Result<gix_merge::tree::Outcome> {
+    Ok(merge(
+        &trees["base"],
+        &trees["ours"],
+        &trees["theirs"],
+        gix_merge::blob::Labels {
+            ancestor: None,
+            current: Some("ours".into()),
+            other: Some("theirs".into()),
+        },
+        odb,
+        |kind, data| odb.write_buf(kind, data),
+        Default::default(),
+    )?)
+}
+
+#[test]
+fn clean_merges_match_git() -> crate::Result {
+    let (odb, trees) = odb_and_trees("clean")?;
+    let outcome = merge_trees(&odb, &trees)?;
+    assert!(!outcome.has_conflicts());
+    assert_eq!(
+        outcome.tree, trees["expected"],
+        "the merged tree is exactly the same as the one produced by `git merge-tree`"
+    );
+    Ok(())
+}
+
+#[test]
+fn mode_changes_of_one_side_are_merged_with_content_changes_of_the_other() -> crate::Result {
+    let (odb, trees) = odb_and_trees("clean")?;
+    let outcome = merge_trees(&odb, &trees)?;
+    assert!(!outcome.has_conflicts());
+
+    let mut buf = Vec::new();
+    let tree = odb.find_tree(&outcome.tree, &mut buf)?.to_owned();
+    for (name, side) in [("mode-ours", "theirs"), ("mode-theirs", "ours")] {
+        let entry = tree
+            .entries
+            .iter()
+            .find(|e| e.filename == name)
+            .unwrap_or_else(|| panic!("{name} is present"));
+        assert!(entry.mode.is_executable(), "{name}: the mode change is kept");
+        let mut buf = Vec::new();
+        assert_eq!(
+            odb.find_blob(&entry.oid, &mut buf)?.data,
+            format!("1\n2\n3\n{side}\n").as_bytes(),
+            "{name}: the content change of {side} is kept"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn conflicts_are_recorded_and_resolved_in_favor_of_ours_or_with_markers() -> crate::Result {
+    let (odb, trees) = odb_and_trees("conflicts")?;
+    let outcome = merge_trees(&odb, &trees)?;
+    assert_eq!(
+        outcome.conflicts,
+        [
+            ("add-add", ConflictKind::AddAdd),
+            ("binary", ConflictKind::Unmergeable),
+            ("content", ConflictKind::Content),
+            ("delete-modify", ConflictKind::ModifyDelete),
+            ("directory-file/file", ConflictKind::DirectoryFile),
+            ("modify-delete", ConflictKind::ModifyDelete),
+        ]
+        .into_iter()
+        .map(|(path, kind)| Conflict {
+            path: path.into(),
+            kind
+        })
+        .collect::<Vec<_>>()
+    );
+
+    let mut buf = Vec::new();
+    let tree = odb.find_tree(&outcome.tree, &mut buf)?.to_owned();
+    let blob = |name: &str| -> crate::Result<String> {
+        let entry = tree
+            .entries
+            .iter()
+            .find(|e| e.filename == name)
+            .unwrap_or_else(|| panic!("{name} is present"));
+        let mut buf = Vec::new();
+        Ok(std::str::from_utf8(odb.find_blob(&entry.oid, &mut buf)?.data)?.to_owned())
+    };
+    assert_eq!(
+        blob("content")?,
+        "1\n<<<<<<< ours\nours\n=======\ntheirs\n>>>>>>> theirs\n3\n"
+    );
+    assert_eq!(
+        blob("add-add")?,
+        "<<<<<<< ours\nours\n=======\ntheirs\n>>>>>>> theirs\n"
+    );
+    assert_eq!(blob("modify-delete")?, "ours\n", "the modified side is kept");
+    assert_eq!(blob("delete-modify")?, "theirs\n", "the modified side is kept");
+    assert_eq!(blob("directory-file")?, "ours\n", "ours is kept");
+    assert_eq!(blob("binary")?, "ours\0", "ours is kept");
+    Ok(())
+}
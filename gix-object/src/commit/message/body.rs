@@ -1,44 +1,49 @@
-use std::ops::Deref;
-
-use winnow::{
-    combinator::{eof, rest, separated_pair, terminated},
-    error::{ErrorKind, ParserError},
-    prelude::*,
-    token::take_until,
-};
+use std::{borrow::Cow, ops::Deref};
 
 use crate::{
-    bstr::{BStr, ByteSlice},
-    commit::message::BodyRef,
+    bstr::{BStr, BString, ByteSlice},
+    commit::message::{trailer, BodyRef},
 };
 
 /// An iterator over trailers as parsed from a commit message body.
 ///
-/// lines with parsing failures will be skipped
+/// Lines in the trailer block that aren't trailers are skipped.
 pub struct Trailers<'a> {
-    pub(crate) cursor: &'a [u8],
+    pub(crate) lines: trailer::Lines<'a>,
+    pub(crate) separators: &'a BStr,
 }
 
 /// A trailer as parsed from the commit message body.
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TrailerRef<'a> {
-    /// The name of the trailer, like "Signed-off-by", up to the separator ": "
+    /// The name of the trailer, like "Signed-off-by", up to the separator, with whitespace trimmed.
     #[cfg_attr(feature = "serde", serde(borrow))]
     pub token: &'a BStr,
-    /// The value right after the separator ": ", with leading and trailing whitespace trimmed.
-    /// Note that multi-line values aren't currently supported.
+    /// The value right after the separator, with leading and trailing whitespace trimmed.
+    ///
+    /// Values can span multiple lines if the lines following the trailer start with whitespace, in which case these
+    /// lines are included verbatim. Use [`unfolded_value()`](Self::unfolded_value()) to obtain a single-line value.
     pub value: &'a BStr,
 }
 
-fn parse_single_line_trailer<'a, E: ParserError<&'a [u8]>>(i: &mut &'a [u8]) -> PResult<(&'a BStr, &'a BStr), E> {
-    *i = i.trim_end();
-    let (token, value) = separated_pair(take_until(1.., b":".as_ref()), b": ", rest).parse_next(i)?;
-
-    if token.trim_end().len() != token.len() || value.trim_start().len() != value.len() {
-        Err(winnow::error::ErrMode::from_error_kind(i, ErrorKind::Fail).cut())
-    } else {
-        Ok((token.as_bstr(), value.as_bstr()))
+impl<'a> TrailerRef<'a> {
+    /// Return the value with all continuation lines joined by a single space, similar to `git interpret-trailers --unfold`.
+    pub fn unfolded_value(&self) -> Cow<'a, BStr> {
+        if !self.value.contains(&b'\n') {
+            return self.value.into();
+        }
+        let mut out = BString::default();
+        let mut bytes = self.value.iter().copied().peekable();
+        while let Some(byte) = bytes.next() {
+            if byte == b'\n' {
+                while bytes.next_if(u8::is_ascii_whitespace).is_some() {}
+                out.push(b' ');
+            } else {
+                out.push(byte);
+            }
+        }
+        out.trim_with(|c| c.is_ascii_whitespace()).as_bstr().to_owned().into()
     }
 }
 
@@ -46,44 +51,38 @@ impl<'a> Iterator for Trailers<'a> {
     type Item = TrailerRef<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.cursor.is_empty() {
-            return None;
-        }
-        for mut line in self.cursor.lines_with_terminator() {
-            self.cursor = &self.cursor[line.len()..];
-            if let Some(trailer) = terminated(parse_single_line_trailer::<()>, eof)
-                .parse_next(&mut line)
-                .ok()
-                .map(|(token, value)| TrailerRef {
-                    token: token.trim().as_bstr(),
-                    value: value.trim().as_bstr(),
-                })
-            {
-                return Some(trailer);
-            }
-        }
-        None
+        let separators = self.separators;
+        self.lines
+            .find_map(|line| trailer::parse(line, separators).map(|(token, value)| TrailerRef { token, value }))
     }
 }
 
 impl<'a> BodyRef<'a> {
-    /// Parse `body` bytes into the trailer and the actual body.
+    /// Parse `body` bytes into the trailer and the actual body, using `:` as separator between trailer tokens and values.
     pub fn from_bytes(body: &'a [u8]) -> Self {
-        body.rfind(b"\n\n")
-            .map(|pos| (2, pos))
-            .or_else(|| body.rfind(b"\r\n\r\n").map(|pos| (4, pos)))
-            .and_then(|(sep_len, pos)| {
-                let trailer = &body[pos + sep_len..];
-                let body = &body[..pos];
-                Trailers { cursor: trailer }.next().map(|_| BodyRef {
-                    body_without_trailer: body.as_bstr(),
-                    start_of_trailer: trailer,
-                })
-            })
-            .unwrap_or_else(|| BodyRef {
+        Self::from_bytes_inner(body, ":".into(), &[])
+    }
+
+    /// Parse `body` bytes into the trailer and the actual body, using the separators and tokens configured in `options`.
+    pub fn from_bytes_with_options(body: &'a [u8], options: &'a trailer::Options) -> Self {
+        Self::from_bytes_inner(body, options.separators.as_ref(), &options.tokens)
+    }
+
+    fn from_bytes_inner(body: &'a [u8], separators: &'a BStr, tokens: &[trailer::Token]) -> Self {
+        let block = trailer::block_with(body, false, separators, tokens);
+        if block.is_empty() {
+            BodyRef {
                 body_without_trailer: body.as_bstr(),
                 start_of_trailer: &[],
-            })
+                separators,
+            }
+        } else {
+            BodyRef {
+                body_without_trailer: body[..block.start].trim_end_with(|c| c == '\n' || c == '\r').as_bstr(),
+                start_of_trailer: &body[block],
+                separators,
+            }
+        }
     }
 
     /// Returns the body with the trailers stripped.
@@ -96,7 +95,8 @@ impl<'a> BodyRef<'a> {
     /// Return an iterator over the trailers parsed from the last paragraph of the body. May be empty.
     pub fn trailers(&self) -> Trailers<'a> {
         Trailers {
-            cursor: self.start_of_trailer,
+            lines: trailer::Lines::new(self.start_of_trailer, self.separators),
+            separators: self.separators,
         }
     }
 }
@@ -118,36 +118,47 @@ impl<'a> Deref for BodyRef<'a> {
 mod test_parse_trailer {
     use super::*;
 
-    fn parse(input: &str) -> (&BStr, &BStr) {
-        parse_single_line_trailer::<()>.parse_peek(input.as_bytes()).unwrap().1
+    fn parse(input: &str) -> Option<(&BStr, &BStr)> {
+        trailer::parse(input.as_bytes(), ":".into())
     }
 
     #[test]
     fn simple_newline() {
-        assert_eq!(parse("foo: bar\n"), ("foo".into(), "bar".into()));
+        assert_eq!(parse("foo: bar\n"), Some(("foo".into(), "bar".into())));
     }
 
     #[test]
-    fn simple_non_ascii_no_newline() {
-        assert_eq!(parse("🤗: 🎉"), ("🤗".into(), "🎉".into()));
+    fn tokens_are_ascii_alphanumeric_with_dashes() {
+        assert_eq!(
+            parse("Signed-off-by2: 🎉"),
+            Some(("Signed-off-by2".into(), "🎉".into()))
+        );
+        assert_eq!(parse("🤗: 🎉"), None);
+        assert_eq!(parse("hello foo: bar there   \n"), None, "tokens can't contain spaces");
     }
 
     #[test]
-    fn with_lots_of_whitespace_newline() {
+    fn whitespace_around_separator_is_allowed() {
+        assert_eq!(parse("foo :bar"), Some(("foo".into(), "bar".into())));
+        assert_eq!(parse("foo\t:  bar"), Some(("foo".into(), "bar".into())));
         assert_eq!(
-            parse("hello foo: bar there   \n"),
-            ("hello foo".into(), "bar there".into())
+            parse(" foo: bar"),
+            None,
+            "leading whitespace indicates a continuation line"
         );
+        assert_eq!(parse(": bar"), None, "the token can't be empty");
     }
 
     #[test]
-    fn extra_whitespace_before_token_or_value_is_error() {
-        assert!(parse_single_line_trailer::<()>.parse_peek(b"foo : bar").is_err());
-        assert!(parse_single_line_trailer::<()>.parse_peek(b"foo:  bar").is_err());
+    fn simple_newline_windows() {
+        assert_eq!(parse("foo: bar\r\n"), Some(("foo".into(), "bar".into())));
     }
 
     #[test]
-    fn simple_newline_windows() {
-        assert_eq!(parse("foo: bar\r\n"), ("foo".into(), "bar".into()));
+    fn multi_line_values_can_be_unfolded() {
+        let (token, value) = parse("foo: bar\n  baz\n\tend\n").expect("valid");
+        let trailer = TrailerRef { token, value };
+        assert_eq!(trailer.value, "bar\n  baz\n\tend");
+        assert_eq!(trailer.unfolded_value().as_ref(), "bar baz end");
     }
 }
@@ -9,6 +9,8 @@ use crate::{
 ///
 pub mod body;
 mod decode;
+///
+pub mod trailer;
 
 impl<'a> CommitRef<'a> {
     /// Return exactly the same message as [`MessageRef::summary()`].
@@ -19,7 +21,7 @@ impl<'a> CommitRef<'a> {
     /// Return an iterator over message trailers as obtained from the last paragraph of the commit message.
     /// May be empty.
     pub fn message_trailers(&self) -> body::Trailers<'a> {
-        BodyRef::from_bytes(self.message).trailers()
+        self.message().trailers()
     }
 }
 
@@ -49,6 +51,18 @@ impl<'a> MessageRef<'a> {
     pub fn body(&self) -> Option<BodyRef<'a>> {
         self.body.map(|b| BodyRef::from_bytes(b))
     }
+
+    /// Return an iterator over the trailers in the last paragraph of the body, similar to `git interpret-trailers --parse`.
+    /// May be empty.
+    pub fn trailers(&self) -> body::Trailers<'a> {
+        BodyRef::from_bytes(self.body.unwrap_or_default()).trailers()
+    }
+
+    /// Like [`trailers()`](Self::trailers()), but uses the separators and tokens configured in `options`, typically
+    /// obtained from the `trailer` configuration section.
+    pub fn trailers_with_options(&self, options: &'a trailer::Options) -> body::Trailers<'a> {
+        BodyRef::from_bytes_with_options(self.body.unwrap_or_default(), options).trailers()
+    }
 }
 
 pub(crate) fn summary(message: &BStr) -> Cow<'_, BStr> {
@@ -90,11 +104,14 @@ pub(crate) fn summary(message: &BStr) -> Cow<'_, BStr> {
 /// A reference to a message body, further parsed to only contain the non-trailer parts.
 ///
 /// See [git-interpret-trailers](https://git-scm.com/docs/git-interpret-trailers) for more information
-/// on what constitutes trailers and not that this implementation is only good for typical sign-off footer or key-value parsing.
+/// on what constitutes trailers. Just like `git`, the trailer block is the last paragraph of the body if all of its lines
+/// are trailers or continuation lines, or if at least a quarter of them are trailers and one of them is a `Signed-off-by`
+/// trailer or uses a configured token.
 ///
 /// Note that we only parse trailers from the bottom of the body.
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
 pub struct BodyRef<'a> {
     body_without_trailer: &'a BStr,
     start_of_trailer: &'a [u8],
+    separators: &'a BStr,
 }
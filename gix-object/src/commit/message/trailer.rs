@@ -0,0 +1,508 @@
+use std::ops::Range;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    commit::message::body::TrailerRef,
+};
+
+/// Lines starting with these prefixes are always considered trailers, just like `git` does, which makes trailer blocks
+/// with non-trailer lines in them possible.
+const GIT_GENERATED_PREFIXES: &[&[u8]] = &[b"Signed-off-by: ", b"(cherry picked from commit "];
+
+/// The character introducing comment lines, which are ignored.
+const COMMENT_CHAR: u8 = b'#';
+
+/// Where to place a new trailer, as configured by `trailer.where` or `trailer.<token>.where`.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Placement {
+    /// Place the new trailer after the last existing trailer, or after the last trailer with the same token if
+    /// one exists.
+    #[default]
+    End,
+    /// Place the new trailer before the first existing trailer, or before the first trailer with the same token if
+    /// one exists.
+    Start,
+    /// Place the new trailer right after the last trailer with the same token, or after the last trailer if there is none.
+    After,
+    /// Place the new trailer right before the first trailer with the same token, or before the first trailer if there is none.
+    Before,
+}
+
+impl Placement {
+    fn is_after_or_end(&self) -> bool {
+        matches!(self, Placement::After | Placement::End)
+    }
+}
+
+/// What to do if a trailer with the same token already exists, as configured by `trailer.ifExists` or
+/// `trailer.<token>.ifExists`.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IfExists {
+    /// Add the new trailer unless its neighbor at the place it would be added has the same token and value.
+    #[default]
+    AddIfDifferentNeighbor,
+    /// Add the new trailer unless a trailer with the same token and value exists anywhere, which deduplicates trailers.
+    AddIfDifferent,
+    /// Always add the new trailer.
+    Add,
+    /// Replace an existing trailer with the same token with the new trailer.
+    Replace,
+    /// Keep existing trailers and don't add the new trailer.
+    DoNothing,
+}
+
+/// What to do if no trailer with the same token exists, as configured by `trailer.ifMissing` or
+/// `trailer.<token>.ifMissing`.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IfMissing {
+    /// Add the new trailer.
+    #[default]
+    Add,
+    /// Don't add the new trailer.
+    DoNothing,
+}
+
+/// The configuration of a trailer token, as configured in the `trailer.<name>` section.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Token {
+    /// The name of the subsection, like `sign` in `trailer.sign.key`, which can be used instead of the actual token.
+    pub name: BString,
+    /// The actual token, like `Signed-off-by`, to use in place of `name`, as configured by `trailer.<name>.key`.
+    pub key: Option<BString>,
+    /// Where to place trailers with this token, or `None` to use [`Options::placement`].
+    pub placement: Option<Placement>,
+    /// What to do if a trailer with this token exists, or `None` to use [`Options::if_exists`].
+    pub if_exists: Option<IfExists>,
+    /// What to do if no trailer with this token exists, or `None` to use [`Options::if_missing`].
+    pub if_missing: Option<IfMissing>,
+}
+
+impl Token {
+    /// Return `true` if `token` refers to this configured token, i.e. if it matches its name or key case-insensitively.
+    pub fn matches(&self, token: &BStr) -> bool {
+        !token.is_empty()
+            && (token.eq_ignore_ascii_case(&self.name)
+                || self.key.as_ref().map_or(false, |key| token.eq_ignore_ascii_case(key)))
+    }
+}
+
+/// Options to control how trailers are parsed and added, typically obtained from the `trailer` configuration section.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// The characters that separate tokens from values, as configured by `trailer.separators`.
+    ///
+    /// The first one is used when writing trailers. Defaults to `:`.
+    pub separators: BString,
+    /// Where to place new trailers by default, as configured by `trailer.where`.
+    pub placement: Placement,
+    /// What to do by default if a trailer with the same token exists, as configured by `trailer.ifExists`.
+    pub if_exists: IfExists,
+    /// What to do by default if no trailer with the same token exists, as configured by `trailer.ifMissing`.
+    pub if_missing: IfMissing,
+    /// The configuration of individual tokens.
+    ///
+    /// Configured tokens help to detect trailer blocks that also contain lines that aren't trailers, and they are
+    /// replaced with their [key](Token::key) if set when adding trailers.
+    pub tokens: Vec<Token>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            separators: ":".into(),
+            placement: Default::default(),
+            if_exists: Default::default(),
+            if_missing: Default::default(),
+            tokens: Vec::new(),
+        }
+    }
+}
+
+impl Options {
+    fn token(&self, token: &BStr) -> Option<&Token> {
+        self.tokens.iter().find(|t| t.matches(token))
+    }
+}
+
+/// Add `trailers` to the trailers of `message`, which is a full commit message including its title, similar to
+/// `git interpret-trailers --trailer <token>=<value>`, and return the new message.
+///
+/// Each new trailer is placed according to the [`Placement`] configured for its token in `options`, while [`IfExists`]
+/// and [`IfMissing`] determine if it's added at all, which allows to replace or to deduplicate trailers.
+/// New trailers are processed in order, so each of them sees the ones that were added before it.
+///
+/// Note that just like `git`, all existing trailers are rewritten with the first of the configured separators, and
+/// a trailer block is always separated by an empty line from the rest of the message.
+pub fn add<'a>(message: &[u8], trailers: impl IntoIterator<Item = TrailerRef<'a>>, options: &Options) -> BString {
+    let mut message = BString::from(message);
+    if !message.is_empty() && !message.ends_with(b"\n") {
+        message.push_byte(b'\n');
+    }
+    let block = block(&message, true, options);
+
+    let mut items: Vec<Item> = Lines::new(&message[block.clone()], options.separators.as_ref())
+        .filter(|line| line.first() != Some(&COMMENT_CHAR))
+        .map(|line| match parse(line, options.separators.as_ref()) {
+            Some((token, value)) => Item {
+                token: Some(canonical_token(token, options)),
+                value: value.into(),
+            },
+            None => Item {
+                token: None,
+                value: line.trim_end_with(|c| c == '\n').into(),
+            },
+        })
+        .collect();
+
+    for trailer in trailers {
+        let token = trim(trailer.token);
+        let config = options.token(token);
+        let new = New {
+            item: Item {
+                token: Some(canonical_token(token, options)),
+                value: trim(trailer.value).into(),
+            },
+            placement: config.and_then(|t| t.placement).unwrap_or(options.placement),
+            if_exists: config.and_then(|t| t.if_exists).unwrap_or(options.if_exists),
+            if_missing: config.and_then(|t| t.if_missing).unwrap_or(options.if_missing),
+        };
+        if !new.apply_if_exists(&mut items) {
+            new.apply_if_missing(&mut items);
+        }
+    }
+
+    let mut out = BString::from(&message[..block.start]);
+    if !ends_with_blank_line(&out) {
+        out.push_byte(b'\n');
+    }
+    let separator = options.separators.first().copied().unwrap_or(b':');
+    for item in &items {
+        match &item.token {
+            Some(token) => {
+                out.push_str(token);
+                if !token.last().map_or(false, |c| options.separators.contains(c)) {
+                    out.push_byte(separator);
+                    out.push_byte(b' ');
+                }
+                out.push_str(&item.value);
+            }
+            None => out.push_str(&item.value),
+        }
+        out.push_byte(b'\n');
+    }
+    out.push_str(&message[block.end..]);
+    out
+}
+
+/// An existing trailer line, or a line in the trailer block that isn't a trailer if `token` is `None`.
+#[derive(Debug, Clone)]
+struct Item {
+    token: Option<BString>,
+    value: BString,
+}
+
+impl Item {
+    fn same_token(&self, other: &Item) -> bool {
+        match (&self.token, &other.token) {
+            (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+            _ => false,
+        }
+    }
+
+    fn same_trailer(&self, other: &Item) -> bool {
+        self.same_token(other) && self.value.eq_ignore_ascii_case(&other.value)
+    }
+}
+
+/// A trailer to add, along with its configuration.
+struct New {
+    item: Item,
+    placement: Placement,
+    if_exists: IfExists,
+    if_missing: IfMissing,
+}
+
+impl New {
+    /// Apply this trailer to `items` if there is one with the same token, and return `true` in that case.
+    fn apply_if_exists(&self, items: &mut Vec<Item>) -> bool {
+        if items.is_empty() {
+            return false;
+        }
+        let backwards = self.placement.is_after_or_end();
+        let existing = if backwards {
+            items.iter().rposition(|item| item.same_token(&self.item))
+        } else {
+            items.iter().position(|item| item.same_token(&self.item))
+        };
+        let Some(existing) = existing else {
+            return false;
+        };
+        let neighbor = match self.placement {
+            Placement::After | Placement::Before => existing,
+            Placement::End => items.len() - 1,
+            Placement::Start => 0,
+        };
+        let insert_at = if backwards { neighbor + 1 } else { neighbor };
+        match self.if_exists {
+            IfExists::DoNothing => {}
+            IfExists::Replace => {
+                items.insert(insert_at, self.item.clone());
+                items.remove(if insert_at <= existing { existing + 1 } else { existing });
+            }
+            IfExists::Add => items.insert(insert_at, self.item.clone()),
+            IfExists::AddIfDifferent => {
+                if self.is_different(items, existing, true) {
+                    items.insert(insert_at, self.item.clone());
+                }
+            }
+            IfExists::AddIfDifferentNeighbor => {
+                if self.is_different(items, neighbor, false) {
+                    items.insert(insert_at, self.item.clone());
+                }
+            }
+        }
+        true
+    }
+
+    /// Return `true` if the item at `index` is different from this trailer, along with all items before it if placed
+    /// after or at the end, or all items after it otherwise if `check_all` is `true`.
+    fn is_different(&self, items: &[Item], index: usize, check_all: bool) -> bool {
+        if !check_all {
+            return !items[index].same_trailer(&self.item);
+        }
+        if self.placement.is_after_or_end() {
+            !items[..=index].iter().any(|item| item.same_trailer(&self.item))
+        } else {
+            !items[index..].iter().any(|item| item.same_trailer(&self.item))
+        }
+    }
+
+    fn apply_if_missing(&self, items: &mut Vec<Item>) {
+        match self.if_missing {
+            IfMissing::DoNothing => {}
+            IfMissing::Add => {
+                if self.placement.is_after_or_end() {
+                    items.push(self.item.clone());
+                } else {
+                    items.insert(0, self.item.clone());
+                }
+            }
+        }
+    }
+}
+
+/// Return the key configured for `token`, or `token` itself.
+fn canonical_token(token: &BStr, options: &Options) -> BString {
+    options
+        .token(token)
+        .and_then(|t| t.key.as_ref())
+        .map_or_else(|| token.to_owned(), ToOwned::to_owned)
+}
+
+/// Return the range of the trailer block in `text`, which is empty and at the end of the message without trailing
+/// comments and empty lines if there is no trailer block.
+///
+/// If `has_title` is `true`, the first paragraph of `text` is a title which can't contain trailers. Otherwise,
+/// `text` is a message body, and trailers may start on its first line.
+pub(crate) fn block(text: &[u8], has_title: bool, options: &Options) -> Range<usize> {
+    block_with(text, has_title, options.separators.as_ref(), &options.tokens)
+}
+
+pub(crate) fn block_with(text: &[u8], has_title: bool, separators: &BStr, tokens: &[Token]) -> Range<usize> {
+    let end = end_of_message(text);
+    let start = block_start(&text[..end], has_title, separators, tokens);
+    start..end
+}
+
+/// Return the end of the message without trailing comments, empty lines and `Conflicts:` blocks, which `git`
+/// used to add to messages of merge commits.
+fn end_of_message(text: &[u8]) -> usize {
+    let mut start_of_ignored = None;
+    let mut in_conflicts_block = false;
+    let mut pos = 0;
+    for line in text.lines_with_terminator() {
+        if line[0] == COMMENT_CHAR || line[0] == b'\n' {
+            start_of_ignored.get_or_insert(pos);
+        } else if line == b"Conflicts:\n" {
+            in_conflicts_block = true;
+            start_of_ignored.get_or_insert(pos);
+        } else if in_conflicts_block && line[0] == b'\t' {
+            // a path in the conflicts block
+        } else {
+            start_of_ignored = None;
+            in_conflicts_block = false;
+        }
+        pos += line.len();
+    }
+    start_of_ignored.unwrap_or(text.len())
+}
+
+/// Return the start of the trailer block in `text`, or its length if there is no trailer block.
+///
+/// A trailer block is a paragraph whose lines are all trailers, or which has at least one trailer line generated by
+/// `git` or configured in `tokens` while at least 25% of its lines are trailers.
+fn block_start(text: &[u8], has_title: bool, separators: &BStr, tokens: &[Token]) -> usize {
+    let mut end_of_title = 0;
+    if has_title {
+        for line in text.lines_with_terminator() {
+            if line[0] != COMMENT_CHAR && is_blank_line(line) {
+                break;
+            }
+            end_of_title += line.len();
+        }
+    }
+
+    let mut only_spaces = true;
+    let mut recognized_prefix = false;
+    let mut trailer_lines = 0;
+    let mut non_trailer_lines = 0;
+    let mut possible_continuation_lines = 0;
+    let mut line_end = text.len();
+    for line in text[end_of_title..].lines_with_terminator().rev() {
+        let line_start = line_end - line.len();
+        let next_line_start = line_end;
+        line_end = line_start;
+        if line[0] == COMMENT_CHAR {
+            non_trailer_lines += possible_continuation_lines;
+            possible_continuation_lines = 0;
+            continue;
+        }
+        if is_blank_line(line) {
+            if only_spaces {
+                continue;
+            }
+            return block_after_blank_line(
+                text.len(),
+                next_line_start,
+                recognized_prefix,
+                trailer_lines,
+                non_trailer_lines + possible_continuation_lines,
+            );
+        }
+        only_spaces = false;
+
+        if GIT_GENERATED_PREFIXES.iter().any(|prefix| line.starts_with(prefix)) {
+            trailer_lines += 1;
+            possible_continuation_lines = 0;
+            recognized_prefix = true;
+            continue;
+        }
+
+        match find_separator(line, separators) {
+            Some(separator_pos) if !line[0].is_ascii_whitespace() => {
+                trailer_lines += 1;
+                possible_continuation_lines = 0;
+                if !recognized_prefix {
+                    let token = trim(&line[..separator_pos]);
+                    recognized_prefix = tokens.iter().any(|t| t.matches(token));
+                }
+            }
+            _ if line[0].is_ascii_whitespace() => possible_continuation_lines += 1,
+            _ => {
+                non_trailer_lines += 1 + possible_continuation_lines;
+                possible_continuation_lines = 0;
+            }
+        }
+    }
+    if has_title || only_spaces {
+        text.len()
+    } else {
+        // The beginning of a body is like an empty line after the title.
+        block_after_blank_line(
+            text.len(),
+            end_of_title,
+            recognized_prefix,
+            trailer_lines,
+            non_trailer_lines + possible_continuation_lines,
+        )
+    }
+}
+
+/// Return `block_start` if the lines after a blank line form a trailer block, or `len` otherwise.
+fn block_after_blank_line(
+    len: usize,
+    block_start: usize,
+    recognized_prefix: bool,
+    trailer_lines: usize,
+    non_trailer_lines: usize,
+) -> usize {
+    if (recognized_prefix && trailer_lines * 3 >= non_trailer_lines) || (trailer_lines > 0 && non_trailer_lines == 0) {
+        block_start
+    } else {
+        len
+    }
+}
+
+fn trim(text: &[u8]) -> &BStr {
+    text.trim_with(|c| c.is_ascii_whitespace()).as_bstr()
+}
+
+fn is_blank_line(line: &[u8]) -> bool {
+    line.iter().all(u8::is_ascii_whitespace)
+}
+
+fn ends_with_blank_line(text: &[u8]) -> bool {
+    text.lines_with_terminator().next_back().map_or(false, is_blank_line)
+}
+
+/// Return the position of the separator in `line` if it starts with a token, which consists of alphanumeric characters
+/// and dashes, optionally followed by whitespace.
+pub(crate) fn find_separator(line: &[u8], separators: &BStr) -> Option<usize> {
+    let mut whitespace_found = false;
+    for (pos, &byte) in line.iter().enumerate() {
+        if separators.contains(&byte) {
+            return (pos > 0).then_some(pos);
+        }
+        if !whitespace_found && (byte.is_ascii_alphanumeric() || byte == b'-') {
+            continue;
+        }
+        if pos != 0 && (byte == b' ' || byte == b'\t') {
+            whitespace_found = true;
+            continue;
+        }
+        break;
+    }
+    None
+}
+
+/// Split a trailer `line`, which may include continuation lines, into its token and value, both with whitespace trimmed.
+pub(crate) fn parse<'a>(line: &'a [u8], separators: &BStr) -> Option<(&'a BStr, &'a BStr)> {
+    find_separator(line, separators).map(|pos| (trim(&line[..pos]), trim(&line[pos + 1..])))
+}
+
+/// An iterator over the lines of a trailer block, with continuation lines of trailers joined with the trailer line.
+pub(crate) struct Lines<'a> {
+    cursor: &'a [u8],
+    separators: &'a BStr,
+}
+
+impl<'a> Lines<'a> {
+    pub(crate) fn new(block: &'a [u8], separators: &'a BStr) -> Self {
+        Lines {
+            cursor: block,
+            separators,
+        }
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut lines = self.cursor.lines_with_terminator();
+        let first = lines.next()?;
+        let mut len = first.len();
+        if find_separator(first, self.separators).is_some() {
+            len += lines
+                .take_while(|line| line[0].is_ascii_whitespace())
+                .map(<[u8]>::len)
+                .sum::<usize>();
+        }
+        let (line, rest) = self.cursor.split_at(len);
+        self.cursor = rest;
+        Some(line)
+    }
+}
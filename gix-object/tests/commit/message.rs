@@ -133,7 +133,7 @@ fn title_with_windows_separator_and_empty_body() {
 
 mod body {
     use gix_object::commit::{
-        message::{body::TrailerRef, BodyRef},
+        message::{body::TrailerRef, trailer, BodyRef},
         MessageRef,
     };
 
@@ -197,19 +197,91 @@ mod body {
     fn two_trailers_with_broken_one_inbetween_after_a_few_paragraphs() {
         let input = "foo\nbar\n\nbar\n\nbaz\n\na: b\ncannot parse this\r\nc: d\n";
         let body = body(input);
+        assert_eq!(
+            body.as_ref(),
+            input,
+            "like git, a paragraph with non-trailer lines needs a git-generated or configured token to be a trailer block, \
+             so the trailers that were previously found here are now part of the body"
+        );
+        assert_eq!(body.trailers().count(), 0);
+    }
+
+    #[test]
+    fn two_trailers_with_broken_one_inbetween_and_a_git_generated_token() {
+        let input = "foo\nbar\n\nbar\n\nbaz\n\nSigned-off-by: b\ncannot parse this\r\nc: d\n";
+        let body = body(input);
         assert_eq!(body.as_ref(), "foo\nbar\n\nbar\n\nbaz");
         assert_eq!(
             body.trailers().collect::<Vec<_>>(),
             vec![
                 TrailerRef {
-                    token: "a".into(),
+                    token: "Signed-off-by".into(),
                     value: "b".into()
                 },
                 TrailerRef {
                     token: "c".into(),
                     value: "d".into()
                 }
-            ]
+            ],
+            "the line that isn't a trailer is skipped"
+        );
+    }
+
+    #[test]
+    fn broken_trailers_need_a_git_generated_or_configured_token() {
+        let input = "foo\n\na: b\ncannot parse this\nc: d\n";
+        assert_eq!(
+            body(input).as_ref(),
+            input,
+            "no line is recognized, so it's not a trailer block"
+        );
+
+        let options = trailer::Options {
+            tokens: vec![trailer::Token {
+                name: "c".into(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let body = BodyRef::from_bytes_with_options(input.as_bytes(), &options);
+        assert_eq!(body.as_ref(), "foo");
+        assert_eq!(body.trailers().count(), 2);
+    }
+
+    #[test]
+    fn trailers_may_start_right_after_the_title() {
+        let msg = MessageRef::from_bytes(b"title\n\nCloses: #42\n  and #43\n");
+        let trailers: Vec<_> = msg.trailers().collect();
+        assert_eq!(
+            trailers,
+            vec![TrailerRef {
+                token: "Closes".into(),
+                value: "#42\n  and #43".into()
+            }]
+        );
+        assert_eq!(trailers[0].unfolded_value().as_ref(), "#42 and #43");
+        assert_eq!(
+            MessageRef::from_bytes(b"fix: the title isn't a trailer")
+                .trailers()
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn separators_can_be_configured() {
+        let options = trailer::Options {
+            separators: "=".into(),
+            ..Default::default()
+        };
+        let msg = MessageRef::from_bytes(b"title\n\nbody\n\nTicket = ABC-1\n");
+        assert_eq!(msg.trailers().count(), 0);
+        assert_eq!(
+            msg.trailers_with_options(&options).collect::<Vec<_>>(),
+            vec![TrailerRef {
+                token: "Ticket".into(),
+                value: "ABC-1".into()
+            }]
         );
     }
 
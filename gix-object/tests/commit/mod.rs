@@ -187,3 +187,4 @@ fn invalid() {
 mod from_bytes;
 mod iter;
 mod message;
+mod trailer;
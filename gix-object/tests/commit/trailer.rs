@@ -0,0 +1,114 @@
+use std::fmt::Write;
+
+use gix_object::{
+    bstr::{BString, ByteSlice},
+    commit::{
+        message::{body::TrailerRef, trailer},
+        MessageRef,
+    },
+};
+
+#[test]
+fn parse_and_add_like_git_interpret_trailers() -> crate::Result {
+    let root = gix_testtools::scripted_fixture_read_only("make_trailers.sh")?;
+    let mut count = 0;
+    for entry in std::fs::read_dir(&root)? {
+        let dir = entry?.path();
+        let name = dir.file_name().expect("named").to_string_lossy().into_owned();
+        let read = |file: &str| std::fs::read(dir.join(file));
+        let input = read("input")?;
+        let options = options(&read("config")?.to_str_lossy());
+
+        let separator = options.separators[0] as char;
+        let mut actual = String::new();
+        for trailer in MessageRef::from_bytes(&input).trailers_with_options(&options) {
+            let token = options
+                .tokens
+                .iter()
+                .find(|t| t.matches(trailer.token))
+                .and_then(|t| t.key.as_ref())
+                .map_or(trailer.token, |key| key.as_ref());
+            writeln!(actual, "{token}{separator} {}", trailer.unfolded_value())?;
+        }
+        assert_eq!(actual.as_bytes().as_bstr(), read("parsed")?.as_bstr(), "{name}: parsed");
+
+        let trailers = read("trailers")?;
+        let trailers = trailers.lines().map(|line| {
+            let (token, value) = line.split_once_str("=").expect("token=value");
+            TrailerRef {
+                token: token.as_bstr(),
+                value: value.as_bstr(),
+            }
+        });
+        assert_eq!(
+            trailer::add(&input, trailers, &options),
+            BString::from(read("added")?),
+            "{name}: added"
+        );
+        count += 1;
+    }
+    assert_eq!(count, 26, "all baselines were checked");
+    Ok(())
+}
+
+/// Turn `trailer.*` configuration as passed to `git -c` into options.
+fn options(config: &str) -> trailer::Options {
+    let mut options = trailer::Options::default();
+    for line in config.lines() {
+        let (key, value) = line.split_once('=').expect("key=value");
+        let key = key.strip_prefix("trailer.").expect("only trailer configuration");
+        let (name, key) = match key.rsplit_once('.') {
+            Some((name, key)) => (Some(name), key.to_ascii_lowercase()),
+            None => (None, key.to_ascii_lowercase()),
+        };
+        let placement = || match value {
+            "end" => trailer::Placement::End,
+            "start" => trailer::Placement::Start,
+            "after" => trailer::Placement::After,
+            "before" => trailer::Placement::Before,
+            _ => unreachable!("invalid where: {value}"),
+        };
+        let if_exists = || match value {
+            "addIfDifferentNeighbor" => trailer::IfExists::AddIfDifferentNeighbor,
+            "addIfDifferent" => trailer::IfExists::AddIfDifferent,
+            "add" => trailer::IfExists::Add,
+            "replace" => trailer::IfExists::Replace,
+            "doNothing" => trailer::IfExists::DoNothing,
+            _ => unreachable!("invalid ifExists: {value}"),
+        };
+        let if_missing = || match value {
+            "add" => trailer::IfMissing::Add,
+            "doNothing" => trailer::IfMissing::DoNothing,
+            _ => unreachable!("invalid ifMissing: {value}"),
+        };
+        match name {
+            None => match key.as_str() {
+                "separators" => options.separators = value.into(),
+                "where" => options.placement = placement(),
+                "ifexists" => options.if_exists = if_exists(),
+                "ifmissing" => options.if_missing = if_missing(),
+                _ => unreachable!("unknown key: {key}"),
+            },
+            Some(name) => {
+                let token = match options.tokens.iter().position(|t| t.name == name) {
+                    Some(pos) => &mut options.tokens[pos],
+                    None => {
+                        options.tokens.push(trailer::Token {
+                            name: name.into(),
+                            ..Default::default()
+                        });
+                        options.tokens.last_mut().expect("just pushed")
+                    }
+                };
+                match key.as_str() {
+                    "key" => token.key = Some(value.into()),
+                    "where" => token.placement = Some(placement()),
+                    "ifexists" => token.if_exists = Some(if_exists()),
+                    "ifmissing" => token.if_missing = Some(if_missing()),
+                    _ => unreachable!("unknown key: {key}"),
+                }
+            }
+        }
+    }
+    options
+}
@@ -0,0 +1,56 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+# Create a directory `name` with the message read from stdin as `input`, along with the output of `git interpret-trailers`
+# for parsing it as `parsed` and for adding trailers to it as `added`.
+# Arguments starting with `trailer.` are configuration and written to `config`, all others are trailers to add in the
+# form `<token>=<value>` and written to `trailers`.
+function baseline() {
+  local name=${1:?First argument is the name of the baseline directory}
+  shift
+  mkdir "$name"
+  cat > "$name/input"
+  local config_args=() trailer_args=()
+  touch "$name/config" "$name/trailers"
+  for arg in "$@"; do
+    case $arg in
+      trailer.*)
+        config_args+=(-c "$arg")
+        echo "$arg" >> "$name/config"
+        ;;
+      *)
+        trailer_args+=(--trailer "$arg")
+        echo "$arg" >> "$name/trailers"
+        ;;
+    esac
+  done
+  git ${config_args[@]+"${config_args[@]}"} interpret-trailers --parse --no-divider < "$name/input" > "$name/parsed"
+  git ${config_args[@]+"${config_args[@]}"} interpret-trailers --no-divider ${trailer_args[@]+"${trailer_args[@]}"} < "$name/input" > "$name/added"
+}
+
+printf 'title\n\nbody\n\nSigned-off-by: A <a@example.com>\n' | baseline simple "Reviewed-by=B <b@example.com>"
+printf 'title\n\nbody text\n' | baseline no-trailers "Closes=#1"
+printf 'title\n' | baseline title-only "Closes=#1"
+printf 'title\n\nA: b' | baseline no-trailing-newline "C=d"
+printf 'title\n\nFixes: #1\n' | baseline right-after-title "Fixes=#1" "fixes=#2"
+printf 'title\n\nbody\n\nTicket: ABC-1\n  ABC-2\n\tABC-3\nCo-authored-by: X <x@example.com>\n' | baseline continuation-lines "Ticket=ABC-4"
+printf 'title\n\nbody\n\nSigned-off-by: A\nsome text\nmore text\n' | baseline git-generated-quarter "Signed-off-by=A"
+printf 'title\n\nbody\n\nSigned-off-by: A\nl1\nl2\nl3\nl4\n' | baseline below-a-quarter "Signed-off-by=A"
+printf 'title\n\nbody\n\n(cherry picked from commit abc)\nsome note\nKey: value\n' | baseline cherry-picked "Key=value"
+printf 'title\n\nbody\n\nTicket: 1\nnot a trailer\n' | baseline configured-token trailer.ticket.key=Ticket "ticket=2"
+printf 'title\n\nbody\n\nbroken: 1\nnot a trailer\n' | baseline not-a-trailer-block "a=b"
+printf 'title\n\nbody\n\nBug #42\nReviewed-by = x\n' | baseline separators "trailer.separators=#=" "Fixes=1"
+printf 'title\n\nbody\n\nkey token: value\n' | baseline token-with-space "a=b"
+printf 'title\n\nbody\n\nChange-Id: I1\nSigned-off-by: A\n' | baseline replace trailer.change-id.ifexists=replace "Change-Id=I2"
+printf 'title\n\nbody\n\nCo-authored-by: X\nSigned-off-by: A\n' | baseline add-if-different trailer.ifexists=addIfDifferent "Co-authored-by=x" "co-authored-by=Y"
+printf 'title\n\nbody\n\nCo-authored-by: X\nSigned-off-by: A\n' | baseline add-if-different-neighbor "Co-authored-by=X" "Signed-off-by=A" "Signed-off-by=B"
+printf 'title\n\nbody\n\nCo-authored-by: X\nSigned-off-by: A\n' | baseline add trailer.ifexists=add "Signed-off-by=A"
+printf 'title\n\nbody\n\nCo-authored-by: X\nSigned-off-by: A\n' | baseline do-nothing trailer.ifexists=doNothing "Signed-off-by=B" "Acked-by=C"
+printf 'title\n\nbody\n\nSigned-off-by: A\nAcked-by: C\nSigned-off-by: B\n' | baseline where-after trailer.where=after "Signed-off-by=D" "Tested-by=E"
+printf 'title\n\nbody\n\nSigned-off-by: A\nAcked-by: C\nAcked-by: B\n' | baseline where-before trailer.acked.key=Acked-by trailer.acked.where=before "acked=D" "Tested-by=E"
+printf 'title\n\nbody\n\nSigned-off-by: A\nAcked-by: C\n' | baseline where-start trailer.where=start "Acked-by=D" "Tested-by=E"
+printf 'title\n\nbody\n\nSigned-off-by: A\nAcked-by: C\n' | baseline replace-at-start trailer.where=start trailer.ifexists=replace "Acked-by=D"
+printf 'title\n\nbody\n\nSigned-off-by: A\n' | baseline if-missing trailer.ifmissing=doNothing trailer.closes.ifmissing=add "Fixes=1" "closes=2"
+printf 'title\n\nbody\n\nSigned-off-by: A\n\n# a comment\n\n' | baseline trailing-comments "Acked-by=B"
+printf 'Merge branch x\n\nSigned-off-by: A\n\nConflicts:\n\tfile\n\tother\n' | baseline conflicts-block "Acked-by=B"
+printf 'title\n\nbody\n\nSigned-off-by: A\n# comment in block\nAcked-by: B\n' | baseline comment-in-block "Tested-by=C"
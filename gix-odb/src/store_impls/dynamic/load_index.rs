@@ -69,6 +69,17 @@ impl super::Store {
         Ok(snapshot)
     }
 
+    /// Check the disk for new, changed or removed pack indices and update the store accordingly, which makes newly written
+    /// packs and multi-pack indices visible to all handles right away.
+    ///
+    /// Indices and packs that were removed from disk are unloaded, unless a handle [prevents that](super::Handle::prevent_pack_unload())
+    /// as it relies on stable pack ids. Then they remain usable by that handle, which is possible as open files and memory maps
+    /// remain valid even after their files were deleted on most platforms.
+    pub fn refresh(&self) -> Result<(), Error> {
+        self.consolidate_with_disk_state(false /* needs init */, false /*load one new index*/)
+            .map(|_| ())
+    }
+
     /// If `None` is returned, there is new indices and the caller should give up. This is a possibility even if it's allowed to refresh
     /// as here might be no change to pick up.
     pub(crate) fn load_one_index(
@@ -949,6 +949,44 @@ fn auto_refresh_with_and_without_id_stability() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn explicit_refresh_picks_up_new_and_removed_packs() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    std::fs::create_dir_all(tmp.path().join("objects/pack"))?;
+    gix_testtools::copy_recursively_into_existing_dir(
+        fixture_path_standalone("objects/pack"),
+        tmp.path().join("objects/pack"),
+    )?;
+    let pack_path = |name: &str| tmp.path().join("objects/pack").join(name);
+    let hidden = pack_path("pack-11fdfa9e156ab73caae3b6da867192221f2089c2");
+    std::fs::rename(hidden.with_extension("idx"), hidden.with_extension("idx.bak"))?;
+
+    let handle = gix_odb::at(tmp.path().join("objects"))?;
+    assert_eq!(handle.store_ref().structure()?.len(), 2 + 1, "two indices and one loose db");
+    let metrics = handle.store_ref().metrics();
+    assert_eq!(metrics.num_refreshes, 1);
+    assert_eq!(metrics.known_reachable_indices, 2);
+
+    std::fs::rename(hidden.with_extension("idx.bak"), hidden.with_extension("idx"))?;
+    let removed = pack_path("pack-c0438c19fb16422b6bbcce24387b3264416d485b");
+    std::fs::remove_file(removed.with_extension("idx"))?;
+    std::fs::remove_file(removed.with_extension("pack"))?;
+
+    handle.store_ref().refresh()?;
+    let metrics = handle.store_ref().metrics();
+    assert_eq!(metrics.num_refreshes, 2, "refreshes always look at the disk");
+    assert_eq!(
+        metrics.known_reachable_indices, 2,
+        "the new index is known, the removed one isn't reachable anymore"
+    );
+    assert_eq!(metrics.unreachable_indices, 0, "without stable handles, removed indices are unloaded");
+    assert!(
+        handle.exists(&hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0")),
+        "objects in the new pack can be found"
+    );
+    Ok(())
+}
+
 mod verify {
     use std::sync::atomic::AtomicBool;
 
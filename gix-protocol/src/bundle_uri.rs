@@ -0,0 +1,266 @@
+use bstr::{BStr, BString, ByteSlice};
+
+/// How the bundles of a [`List`] relate to each other.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Mode {
+    /// All bundles are needed to obtain the complete data, with later bundles possibly depending on earlier ones.
+    All,
+    /// Each bundle contains the complete data, so any of them is sufficient.
+    Any,
+}
+
+/// A hint about how bundles of a [`List`] can be used to download only what's new since the last time.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Heuristic {
+    /// Each bundle has a `creationToken`, with bundles with greater tokens depending on the ones with smaller tokens.
+    ///
+    /// Clients remember the greatest token they applied, and only download bundles with greater tokens when fetching again.
+    CreationToken,
+}
+
+/// A single bundle as advertised in a [`List`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bundle {
+    /// The identifier of the bundle, unique within its list.
+    pub id: BString,
+    /// The URI to download the bundle from, which may be relative to the location of the list.
+    ///
+    /// It may point to another list instead of a bundle.
+    pub uri: BString,
+    /// The token to order bundles by if the list uses the [creation token heuristic](Heuristic::CreationToken).
+    pub creation_token: Option<u64>,
+    /// The object filter used to create the bundle, if any.
+    pub filter: Option<BString>,
+}
+
+/// A list of bundles as advertised by a server via the `bundle-uri` command, or as downloaded from a URI.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct List {
+    /// The version of the list format, which is always `1`.
+    pub version: u32,
+    /// How bundles relate to each other.
+    pub mode: Mode,
+    /// The heuristic to use for incremental fetches, if any.
+    pub heuristic: Option<Heuristic>,
+    /// All bundles in the order they were listed.
+    pub bundles: Vec<Bundle>,
+}
+
+///
+pub mod decode {
+    use bstr::BString;
+
+    /// The error returned by [`List::from_key_value_pairs()`](super::List::from_key_value_pairs()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The bundle list lacks the 'bundle.{key}' key")]
+        MissingKey { key: &'static str },
+        #[error("The value {value:?} of key {key:?} is invalid")]
+        InvalidValue { key: BString, value: BString },
+        #[error("Bundle list version {version} is unsupported")]
+        UnsupportedVersion { version: u32 },
+        #[error("The bundle {id:?} doesn't have an URI")]
+        MissingUri { id: BString },
+        #[error("The line {line:?} isn't a key-value pair")]
+        MalformedLine { line: BString },
+    }
+}
+
+impl List {
+    /// Parse a list from `pairs` of keys and values, with keys like `bundle.version` or `bundle.<id>.uri`.
+    ///
+    /// Keys and values are expected as they appear in the response to the `bundle-uri` command, or as obtained by flattening
+    /// a git-configuration file with a bundle list. Unknown keys are ignored.
+    pub fn from_key_value_pairs<'a>(
+        pairs: impl IntoIterator<Item = (&'a BStr, &'a BStr)>,
+    ) -> Result<Self, decode::Error> {
+        let mut version = None;
+        let mut mode = None;
+        let mut heuristic = None;
+        let mut bundles = Vec::<Bundle>::new();
+        for (key, value) in pairs {
+            let invalid_value = || decode::Error::InvalidValue {
+                key: key.to_owned(),
+                value: value.to_owned(),
+            };
+            let Some(rest) = key
+                .split_once_str(".")
+                .filter(|(section, _)| section.eq_ignore_ascii_case(b"bundle"))
+                .map(|(_, rest)| rest.as_bstr())
+            else {
+                continue;
+            };
+            match rest.rsplit_once_str(".") {
+                None => {
+                    let name = rest.to_ascii_lowercase();
+                    match name.as_slice() {
+                        b"version" => {
+                            version = Some(
+                                value
+                                    .to_str()
+                                    .ok()
+                                    .and_then(|v| v.parse::<u32>().ok())
+                                    .ok_or_else(invalid_value)?,
+                            );
+                        }
+                        b"mode" => {
+                            mode = Some(match value.as_bytes() {
+                                b"all" => Mode::All,
+                                b"any" => Mode::Any,
+                                _ => return Err(invalid_value()),
+                            });
+                        }
+                        b"heuristic" => {
+                            heuristic = match value.as_bytes() {
+                                b"creationToken" => Some(Heuristic::CreationToken),
+                                // Like `git`, we ignore heuristics we don't know.
+                                _ => None,
+                            };
+                        }
+                        _ => {}
+                    }
+                }
+                Some((id, name)) => {
+                    let idx = match bundles.iter().position(|b| b.id == id) {
+                        Some(idx) => idx,
+                        None => {
+                            bundles.push(Bundle {
+                                id: id.into(),
+                                uri: BString::default(),
+                                creation_token: None,
+                                filter: None,
+                            });
+                            bundles.len() - 1
+                        }
+                    };
+                    let bundle = &mut bundles[idx];
+                    match name.to_ascii_lowercase().as_slice() {
+                        b"uri" => bundle.uri = value.to_owned(),
+                        b"creationtoken" => {
+                            bundle.creation_token = Some(
+                                value
+                                    .to_str()
+                                    .ok()
+                                    .and_then(|v| v.parse::<u64>().ok())
+                                    .ok_or_else(invalid_value)?,
+                            );
+                        }
+                        b"filter" => bundle.filter = Some(value.to_owned()),
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        let version = version.ok_or(decode::Error::MissingKey { key: "version" })?;
+        if version != 1 {
+            return Err(decode::Error::UnsupportedVersion { version });
+        }
+        if let Some(bundle) = bundles.iter().find(|b| b.uri.is_empty()) {
+            return Err(decode::Error::MissingUri { id: bundle.id.clone() });
+        }
+        Ok(List {
+            version,
+            mode: mode.ok_or(decode::Error::MissingKey { key: "mode" })?,
+            heuristic,
+            bundles,
+        })
+    }
+
+    /// Parse a list from `lines` in the form of `key=value`, as sent by the server in response to the `bundle-uri` command.
+    pub fn from_lines<'a>(lines: impl IntoIterator<Item = &'a BStr>) -> Result<Self, decode::Error> {
+        let pairs = lines
+            .into_iter()
+            .map(|line| {
+                let line = line.trim_end_with(|c| c == '\n').as_bstr();
+                line.split_once_str("=")
+                    .map(|(key, value)| (key.as_bstr(), value.as_bstr()))
+                    .ok_or_else(|| decode::Error::MalformedLine { line: line.to_owned() })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::from_key_value_pairs(pairs)
+    }
+}
+
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+mod error {
+    /// The error returned by [`bundle_uri()`][crate::bundle_uri()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+        #[error(transparent)]
+        Transport(#[from] gix_transport::client::Error),
+        #[error(transparent)]
+        Decode(#[from] super::decode::Error),
+    }
+
+    impl gix_transport::IsSpuriousError for Error {
+        fn is_spurious(&self) -> bool {
+            match self {
+                Error::Io(err) => err.is_spurious(),
+                Error::Transport(err) => err.is_spurious(),
+                _ => false,
+            }
+        }
+    }
+}
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+pub use error::Error;
+
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+pub(crate) mod function {
+    use bstr::{BString, ByteSlice};
+    use gix_features::progress::Progress;
+    use gix_transport::client::{Capabilities, Transport, TransportV2Ext};
+    use maybe_async::maybe_async;
+
+    use super::{Error, List};
+    use crate::{command::Feature, Command};
+
+    /// Invoke a `bundle-uri` V2 command on `transport`, which requires a prior handshake that yielded server `capabilities`,
+    /// and return the list of bundles the server advertises, or `None` if it doesn't know any or doesn't support the command.
+    /// `features` are sent along with the command and typically contain the `agent`. `progress` is used to provide feedback.
+    /// If `trace` is `true`, all packetlines received or sent will be passed to the facilities of the `gix-trace` crate.
+    #[maybe_async]
+    pub async fn bundle_uri(
+        mut transport: impl Transport,
+        capabilities: &Capabilities,
+        features: Vec<Feature>,
+        progress: &mut impl Progress,
+        trace: bool,
+    ) -> Result<Option<List>, Error> {
+        let _span = gix_features::trace::detail!("gix_protocol::bundle_uri()");
+        if !capabilities.contains(Command::BundleUri.as_str()) {
+            return Ok(None);
+        }
+        progress.step();
+        progress.set_name("list bundles".into());
+        let mut reader = transport
+            .invoke(
+                Command::BundleUri.as_str(),
+                features.into_iter(),
+                None::<std::iter::Empty<BString>>,
+                trace,
+            )
+            .await?;
+        let mut lines = Vec::<BString>::new();
+        let mut line = String::new();
+        while reader.readline_str(&mut line).await? != 0 {
+            lines.push(line.as_str().into());
+            line.clear();
+        }
+        if lines.is_empty() {
+            return Ok(None);
+        }
+        List::from_lines(lines.iter().map(|line| line.as_bstr()))
+            .map(Some)
+            .map_err(Into::into)
+    }
+}
@@ -12,6 +12,7 @@ impl Command {
         match self {
             Command::LsRefs => "ls-refs",
             Command::Fetch => "fetch",
+            Command::BundleUri => "bundle-uri",
         }
     }
 }
@@ -28,6 +29,7 @@ mod with_io {
         fn all_argument_prefixes(&self) -> &'static [&'static str] {
             match self {
                 Command::LsRefs => &["symrefs", "peel", "ref-prefix ", "unborn"],
+                Command::BundleUri => &[],
                 Command::Fetch => &[
                     "want ", // hex oid
                     "have ", // hex oid
@@ -58,7 +60,7 @@ mod with_io {
 
         fn all_features(&self, version: gix_transport::Protocol) -> &'static [&'static str] {
             match self {
-                Command::LsRefs => &[],
+                Command::LsRefs | Command::BundleUri => &[],
                 Command::Fetch => match version {
                     gix_transport::Protocol::V0 | gix_transport::Protocol::V1 => &[
                         "multi_ack",
@@ -108,6 +110,7 @@ mod with_io {
                     )
                     .collect(),
                 Command::LsRefs => vec![b"symrefs".as_bstr().to_owned(), b"peel".as_bstr().to_owned()],
+                Command::BundleUri => vec![],
             }
         }
 
@@ -130,6 +133,9 @@ mod with_io {
                                 "side-band" if has_sideband_64k => false,
                                 "multi_ack" if has_multi_ack_detailed => false,
                                 "no-progress" => false,
+                                // With it, the server sends the pack right after `ready` without waiting for `done`, which
+                                // stateful connections can't predict, and stateless ones would download only to discard it.
+                                "no-done" => false,
                                 feature => server_capabilities.contains(feature),
                             })
                             .map(|s| (s, None))
@@ -154,7 +160,7 @@ mod with_io {
                             .collect()
                     }
                 },
-                Command::LsRefs => vec![],
+                Command::LsRefs | Command::BundleUri => vec![],
             }
         }
         /// Panics if the given arguments and features don't match what's statically known. It's considered a bug in the delegate.
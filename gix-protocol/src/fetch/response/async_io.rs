@@ -4,7 +4,7 @@ use gix_transport::{client, Protocol};
 
 use crate::fetch::{
     response,
-    response::{Acknowledgement, ShallowUpdate, WantedRef},
+    response::{Acknowledgement, PackfileUri, ShallowUpdate, WantedRef},
     Response,
 };
 
@@ -35,6 +35,8 @@ impl Response {
     ///
     /// `client_expects_pack` is only relevant for V1 stateful connections, and if `false`, causes us to stop parsing when seeing `NAK`,
     /// and if `true` we will keep parsing until we get a pack as the client already signalled to the server that it's done.
+    /// If `false`, a status-less `ACK` as sent by servers without `multi_ack` support also stops parsing, and is followed by
+    /// [`Acknowledgement::Ready`] to indicate that the client should send `done` next.
     /// This way of doing things allows us to exploit knowledge about more recent versions of the protocol, which keeps code easier
     /// and more localized without having to support all the cruft that there is.
     ///
@@ -58,12 +60,8 @@ impl Response {
                     line.clear();
                     let peeked_line = match reader.peek_data_line().await {
                         Some(Ok(Ok(line))) => String::from_utf8_lossy(line),
-                        // This special case (hang/block forever) deals with a single NAK being a legitimate EOF sometimes
-                        // Note that this might block forever in stateful connections as there it's not really clear
-                        // if something will be following or not by just looking at the response. Instead you have to know
-                        // [a lot](https://github.com/git/git/blob/9e49351c3060e1fa6e0d2de64505b7becf157f28/fetch-pack.c#L583-L594)
-                        // to deal with this correctly.
-                        // For now this is acceptable, as V2 can be used as a workaround, which also is the default.
+                        // This special case deals with a single NAK being a legitimate EOF sometimes, as in stateless connections
+                        // the server hangs up after its response to a round without `done`.
                         Some(Err(err)) if err.kind() == io::ErrorKind::UnexpectedEof => break 'lines false,
                         Some(Err(err)) => return Err(err.into()),
                         Some(Ok(Err(err))) => return Err(err.into()),
@@ -88,11 +86,17 @@ impl Response {
                     if Response::parse_v1_ack_or_shallow_or_assume_pack(&mut acks, &mut shallows, &peeked_line) {
                         break 'lines true;
                     }
+                    let is_ack_without_status = Response::is_v1_ack_without_status(&peeked_line);
                     assert_ne!(
                         reader.readline_str(&mut line).await?,
                         0,
                         "consuming a peeked line works"
                     );
+                    if is_ack_without_status && !client_expects_pack {
+                        // Servers without `multi_ack` won't say anything else until we are done, so we have to be.
+                        acks.push(Acknowledgement::Ready);
+                        break 'lines false;
+                    }
                     // When the server sends ready, we know there is going to be a pack so no need to stop early.
                     saw_ready |= matches!(acks.last(), Some(Acknowledgement::Ready));
                     if let Some(Acknowledgement::Nak) = acks.last().filter(|_| !client_expects_pack || !saw_ready) {
@@ -106,6 +110,7 @@ impl Response {
                     acks,
                     shallows,
                     wanted_refs: vec![],
+                    packfile_uris: vec![],
                     has_pack,
                 })
             }
@@ -116,6 +121,7 @@ impl Response {
                 let mut acks = Vec::<Acknowledgement>::new();
                 let mut shallows = Vec::<ShallowUpdate>::new();
                 let mut wanted_refs = Vec::<WantedRef>::new();
+                let mut packfile_uris = Vec::<PackfileUri>::new();
                 let has_pack = 'section: loop {
                     line.clear();
                     if reader.readline_str(&mut line).await? == 0 {
@@ -141,6 +147,11 @@ impl Response {
                                 break 'section false;
                             }
                         }
+                        "packfile-uris" => {
+                            if parse_v2_section(&mut line, reader, &mut packfile_uris, PackfileUri::from_line).await? {
+                                break 'section false;
+                            }
+                        }
                         "packfile" => {
                             // what follows is the packfile itself, which can be read with a sideband enabled reader
                             break 'section true;
@@ -152,6 +163,7 @@ impl Response {
                     acks,
                     shallows,
                     wanted_refs,
+                    packfile_uris,
                     has_pack,
                 })
             }
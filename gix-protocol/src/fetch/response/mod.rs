@@ -79,6 +79,16 @@ pub struct WantedRef {
     pub path: BString,
 }
 
+/// A packfile-uri line received from the server, indicating a pack that has to be downloaded in addition to the one sent inline.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PackfileUri {
+    /// The hash of the pack to download, which is expected to match the checksum of the downloaded pack.
+    pub pack_hash: gix_hash::ObjectId,
+    /// The URI to download the pack from.
+    pub uri: BString,
+}
+
 impl ShallowUpdate {
     /// Parse a `ShallowUpdate` from a `line` as received to the server.
     pub fn from_line(line: &str) -> Result<ShallowUpdate, Error> {
@@ -113,7 +123,8 @@ impl Acknowledgement {
                     };
                     if let Some(description) = description {
                         match description {
-                            "common" => {}
+                            // `continue` is what servers supporting only `multi_ack` send for both, common and ready commits.
+                            "common" | "continue" => {}
                             "ready" => return Ok(Acknowledgement::Ready),
                             _ => return Err(Error::UnknownLineType { line: line.to_owned() }),
                         }
@@ -148,12 +159,30 @@ impl WantedRef {
     }
 }
 
+impl PackfileUri {
+    /// Parse a `PackfileUri` from a `line` as received from the server.
+    pub fn from_line(line: &str) -> Result<PackfileUri, Error> {
+        match line.trim_end().split_once(' ') {
+            Some((pack_hash, uri)) if !uri.is_empty() => {
+                let pack_hash = gix_hash::ObjectId::from_hex(pack_hash.as_bytes())
+                    .map_err(|_| Error::UnknownLineType { line: line.to_owned() })?;
+                Ok(PackfileUri {
+                    pack_hash,
+                    uri: uri.into(),
+                })
+            }
+            _ => Err(Error::UnknownLineType { line: line.to_owned() }),
+        }
+    }
+}
+
 /// A representation of a complete fetch response
 #[derive(Debug)]
 pub struct Response {
     acks: Vec<Acknowledgement>,
     shallows: Vec<ShallowUpdate>,
     wanted_refs: Vec<WantedRef>,
+    packfile_uris: Vec<PackfileUri>,
     has_pack: bool,
 }
 
@@ -172,12 +201,8 @@ impl Response {
         match version {
             Protocol::V0 | Protocol::V1 => {
                 let has = |name: &str| features.iter().any(|f| f.0 == name);
-                // Let's focus on V2 standards, and simply not support old servers to keep our code simpler
-                if !has("multi_ack_detailed") {
-                    return Err(Error::MissingServerCapability {
-                        feature: "multi_ack_detailed",
-                    });
-                }
+                // Note that `multi_ack_detailed` isn't required as we can also negotiate with servers that only support
+                // `multi_ack` or none of these, which only affects how many acknowledgements they send.
                 // It's easy to NOT do sideband for us, but then again, everyone supports it.
                 // CORRECTION: If side-band is off, it would send the packfile without packet line encoding,
                 // which is nothing we ever want to deal with (despite it being more efficient). In V2, this
@@ -207,6 +232,14 @@ impl Response {
     pub fn wanted_refs(&self) -> &[WantedRef] {
         &self.wanted_refs
     }
+
+    /// Return all packfile-uris [parsed previously][Response::from_line_reader()].
+    ///
+    /// The packs they refer to must be downloaded and added to the object database, as the pack sent by the server
+    /// doesn't contain their objects.
+    pub fn packfile_uris(&self) -> &[PackfileUri] {
+        &self.packfile_uris
+    }
 }
 
 #[cfg(any(feature = "async-client", feature = "blocking-client"))]
@@ -236,6 +269,14 @@ impl Response {
         };
         false
     }
+
+    /// Return `true` if `line` is an `ACK` without status, which is what servers that don't support `multi_ack` send when
+    /// finding the first common commit. After that, nothing else is sent until the client is done.
+    fn is_v1_ack_without_status(line: &str) -> bool {
+        line.trim_end()
+            .strip_prefix("ACK ")
+            .map_or(false, |id| !id.is_empty() && !id.contains(' '))
+    }
 }
 
 #[cfg(feature = "async-client")]
@@ -17,6 +17,8 @@ pub enum Command {
     LsRefs,
     /// Fetch a pack.
     Fetch,
+    /// Obtain a list of bundles to download before fetching.
+    BundleUri,
 }
 pub mod command;
 
@@ -58,6 +60,11 @@ pub mod ls_refs;
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
 pub use ls_refs::function::ls_refs;
 
+///
+pub mod bundle_uri;
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+pub use bundle_uri::function::bundle_uri;
+
 mod util;
 pub use util::agent;
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
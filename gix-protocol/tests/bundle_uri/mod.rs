@@ -0,0 +1,129 @@
+mod list {
+    use gix_protocol::bundle_uri::{decode, Heuristic, List, Mode};
+
+    #[test]
+    fn from_lines_with_creation_tokens() -> crate::Result {
+        let list = List::from_lines(
+            [
+                "bundle.version=1",
+                "bundle.mode=all",
+                "bundle.heuristic=creationToken",
+                "bundle.one.uri=https://example.com/one.bundle",
+                "bundle.one.creationToken=1",
+                "Bundle.two.URI=two.bundle\n",
+                "bundle.two.creationtoken=2",
+                "bundle.two.filter=blob:none",
+                "bundle.one.unknown=ignored",
+            ]
+            .map(Into::into),
+        )?;
+        assert_eq!(list.version, 1);
+        assert_eq!(list.mode, Mode::All);
+        assert_eq!(list.heuristic, Some(Heuristic::CreationToken));
+        assert_eq!(
+            list.bundles
+                .iter()
+                .map(|b| (
+                    b.id.to_string(),
+                    b.uri.to_string(),
+                    b.creation_token,
+                    b.filter.as_ref().map(ToString::to_string)
+                ))
+                .collect::<Vec<_>>(),
+            [
+                ("one".into(), "https://example.com/one.bundle".into(), Some(1), None),
+                ("two".into(), "two.bundle".into(), Some(2), Some("blob:none".into()))
+            ],
+            "keys are case-insensitive, and unknown keys are ignored"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_heuristics_are_ignored() -> crate::Result {
+        let list = List::from_lines(["bundle.version=1", "bundle.mode=any", "bundle.heuristic=future"].map(Into::into))?;
+        assert_eq!(list.mode, Mode::Any);
+        assert_eq!(list.heuristic, None);
+        assert!(list.bundles.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_lists() {
+        for (lines, expected) in [
+            (&["bundle.mode=all"][..], "The bundle list lacks the 'bundle.version' key"),
+            (&["bundle.version=1"], "The bundle list lacks the 'bundle.mode' key"),
+            (&["bundle.version=2", "bundle.mode=all"], "Bundle list version 2 is unsupported"),
+            (
+                &["bundle.version=1", "bundle.mode=some"],
+                "The value \"some\" of key \"bundle.mode\" is invalid",
+            ),
+            (
+                &["bundle.version=1", "bundle.mode=all", "bundle.b.creationToken=1"],
+                "The bundle \"b\" doesn't have an URI",
+            ),
+            (&["bundle.version"], "The line \"bundle.version\" isn't a key-value pair"),
+        ] {
+            let err: decode::Error = List::from_lines(lines.iter().map(|l| (*l).into())).unwrap_err();
+            assert_eq!(err.to_string(), expected);
+        }
+    }
+}
+
+mod command {
+    use bstr::ByteSlice;
+    use gix_features::progress;
+    use gix_protocol::bundle_uri::Mode;
+    use gix_transport::{client::Capabilities, Protocol};
+
+    use crate::fetch::transport;
+
+    #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+    async fn list_from_server() -> crate::Result {
+        let capabilities = Capabilities::from_lines("version 2\nbundle-uri\n".into())?;
+        let mut out = Vec::new();
+        let mut transport = transport(
+            &mut out,
+            "v2/bundle-uri.response",
+            Protocol::V2,
+            gix_transport::client::git::ConnectMode::Daemon,
+        );
+        let list = gix_protocol::bundle_uri(
+            &mut transport,
+            &capabilities,
+            vec![("agent", Some("agent".into()))],
+            &mut progress::Discard,
+            false,
+        )
+        .await?
+        .expect("the server advertises bundles");
+        assert_eq!(list.mode, Mode::All);
+        assert_eq!(
+            list.bundles.iter().map(|b| b.uri.to_string()).collect::<Vec<_>>(),
+            ["https://example.com/one.bundle", "two.bundle"]
+        );
+        drop(transport);
+        assert_eq!(
+            out.as_bstr(),
+            "0017command=bundle-uri\n0010agent=agent\n0000",
+            "without arguments, there is no delimiter"
+        );
+        Ok(())
+    }
+
+    #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+    async fn nothing_is_sent_if_the_server_does_not_support_it() -> crate::Result {
+        let capabilities = Capabilities::from_lines("version 2\nls-refs\n".into())?;
+        let mut out = Vec::new();
+        let transport = transport(
+            &mut out,
+            "v2/bundle-uri.response",
+            Protocol::V2,
+            gix_transport::client::git::ConnectMode::Daemon,
+        );
+        let list = gix_protocol::bundle_uri(transport, &capabilities, Vec::new(), &mut progress::Discard, false).await?;
+        assert!(list.is_none());
+        assert!(out.is_empty());
+        Ok(())
+    }
+}
@@ -138,6 +138,49 @@ mod v1 {
             assert_eq!(bytes_read, 9703, "should be able to read the whole pack");
             Ok(())
         }
+
+        #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+        async fn fetch_acks_with_multi_ack_without_pack() -> crate::Result {
+            let mut provider = mock_reader("v1/fetch-multi-ack-no-pack.response");
+            let r =
+                fetch::Response::from_line_reader(Protocol::V1, &mut provider.as_read_without_sidebands(), false, true)
+                    .await?;
+            assert_eq!(
+                r.acknowledgements(),
+                &[
+                    Acknowledgement::Common(id("47ee0b7fe4f3a7d776c78794873e6467e1c47e59")),
+                    Acknowledgement::Common(id("3f02c0ad360d96e8dbba92f97b42ebbaa4319db1")),
+                    Acknowledgement::Nak,
+                ],
+                "`continue` is how servers with `multi_ack` acknowledge common commits"
+            );
+            assert!(!r.has_pack());
+            Ok(())
+        }
+
+        #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+        async fn fetch_ack_without_multi_ack_stops_negotiation() -> crate::Result {
+            let mut provider = mock_reader("v1/fetch-without-multi-ack.response");
+            let mut reader = provider.as_read_without_sidebands();
+            let r = fetch::Response::from_line_reader(Protocol::V1, &mut reader, false, true).await?;
+            assert_eq!(
+                r.acknowledgements(),
+                &[
+                    Acknowledgement::Common(id("f22743895a3024bb0c958335981439f1fa747d57")),
+                    Acknowledgement::Ready,
+                ],
+                "the server won't say anything more until it receives `done`, so we must not wait for a NAK"
+            );
+            assert!(!r.has_pack(), "the pack isn't read as we don't expect it yet");
+
+            let r = fetch::Response::from_line_reader(Protocol::V1, &mut reader, true, true).await?;
+            assert!(r.acknowledgements().is_empty(), "nothing is acknowledged after `done`");
+            assert!(r.has_pack());
+            let mut buf = Vec::new();
+            let bytes_read = reader.read_to_end(&mut buf).await?;
+            assert_eq!(bytes_read, 9703, "should be able to read the whole pack");
+            Ok(())
+        }
     }
 
     mod arguments {
@@ -209,7 +252,7 @@ mod v2 {
         use gix_packetline::read::ProgressAction;
         use gix_protocol::fetch::{
             self,
-            response::{Acknowledgement, ShallowUpdate},
+            response::{Acknowledgement, PackfileUri, ShallowUpdate},
         };
         use gix_transport::Protocol;
 
@@ -373,6 +416,29 @@ mod v2 {
             assert_eq!(bytes_read, 5360, "should be able to read the whole pack");
             Ok(())
         }
+
+        #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+        async fn fetch_packfile_uris_and_pack() -> crate::Result {
+            let mut provider = mock_reader("v2/fetch-packfile-uris.response");
+            let mut reader = provider.as_read_without_sidebands();
+            let r = fetch::Response::from_line_reader(Protocol::V2, &mut reader, true, true).await?;
+            assert_eq!(
+                r.packfile_uris(),
+                &[PackfileUri {
+                    pack_hash: id("3f02c0ad360d96e8dbba92f97b42ebbaa4319db1"),
+                    uri: "https://cdn.example.com/packs/pack-3f02c0ad.pack".into(),
+                }]
+            );
+            assert!(r.has_pack(), "the inline pack follows the packfile-uris");
+            let mut buf = Vec::new();
+            reader.set_progress_handler(Some(Box::new(|a: bool, b: &[u8]| {
+                gix_protocol::RemoteProgress::translate_to_progress(a, b, &mut gix_features::progress::Discard);
+                ProgressAction::Continue
+            }) as gix_transport::client::HandleProgress));
+            let bytes_read = reader.read_to_end(&mut buf).await?;
+            assert_eq!(bytes_read, 5360, "should be able to read the whole pack");
+            Ok(())
+        }
     }
 
     mod arguments {
@@ -401,6 +467,10 @@ mod v2 {
                 !args.can_use_ref_in_want(),
                 "V2 only feature, and we initialize capabilities with V1 for convenience"
             );
+            assert!(
+                !args.can_use_packfile_uris(),
+                "V2 only feature, and we initialize capabilities with V1 for convenience"
+            );
             assert!(args.is_empty());
 
             args.shallow(id("97c5a932b3940a09683e924ef6a92b31a6f7c6de"));
@@ -425,5 +495,31 @@ mod v2 {
 0032have bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb\n0009done\n0000");
             Ok(())
         }
+
+        #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+        async fn packfile_uris() -> crate::Result {
+            let caps = Capabilities::from_lines("version 2\nfetch=shallow packfile-uris\n".into())?;
+            let mut args = fetch::Arguments::new(
+                Protocol::V2,
+                Command::Fetch.default_features(Protocol::V2, &caps),
+                false,
+            );
+            assert!(args.can_use_packfile_uris());
+            args.packfile_uris("https,http");
+            args.want(id("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"));
+
+            let mut out = Vec::new();
+            let mut transport = transport(
+                &mut out,
+                "v1/clone.response",
+                Protocol::V2,
+                gix_transport::client::git::ConnectMode::Daemon,
+            );
+
+            let _response = args.send(&mut transport, true).await?;
+            drop(_response);
+            assert_eq!(out.as_slice().as_bstr(), "0012command=fetch\n0001000ethin-pack\n000eofs-delta\n001dpackfile-uris https,http\n0032want aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\n0009done\n0000");
+            Ok(())
+        }
     }
 }
@@ -230,6 +230,130 @@ pub mod create_or_update {
     use crate::FullNameRef;
 }
 
+///
+pub mod expire {
+    use std::io::Write;
+
+    use gix_object::bstr::ByteSlice;
+
+    use crate::{
+        store_impl::{file, file::log::LineRef},
+        FullNameRef,
+    };
+
+    impl file::Store {
+        /// Rewrite the reflog of the reference `name` so that it only retains the entries for which `is_expired` returns `false`,
+        /// and return the amount of entries that were removed, similar to `git reflog expire`.
+        ///
+        /// The reference and its reflog are locked according to `lock_mode` while the reflog is rewritten, which prevents
+        /// concurrent updates to the reference from appending to the reflog in the meantime.
+        /// Entries that can't be parsed are retained, and nothing happens if there is no reflog.
+        pub fn reflog_expire<'a, Name, E>(
+            &self,
+            name: Name,
+            lock_mode: gix_lock::acquire::Fail,
+            mut is_expired: impl FnMut(&LineRef<'_>) -> bool,
+        ) -> Result<usize, Error>
+        where
+            Name: TryInto<&'a FullNameRef, Error = E>,
+            crate::name::Error: From<E>,
+        {
+            let name: &FullNameRef = name.try_into().map_err(|err| Error::RefnameValidation(err.into()))?;
+            self.rewrite_reflog(name, lock_mode, |_index_from_newest, line| {
+                line.map_or(false, |line| is_expired(&line))
+            })
+        }
+
+        /// Remove the entry at `index_from_newest` from the reflog of the reference `name`, and return `true` if it existed,
+        /// similar to `git reflog delete <name>@{<index_from_newest>}`.
+        ///
+        /// The newest entry is at index 0, just like in `<name>@{0}`, and all other entries are retained as they are.
+        /// Locking happens just like in [`reflog_expire()`](Self::reflog_expire()).
+        pub fn reflog_delete_entry<'a, Name, E>(
+            &self,
+            name: Name,
+            index_from_newest: usize,
+            lock_mode: gix_lock::acquire::Fail,
+        ) -> Result<bool, Error>
+        where
+            Name: TryInto<&'a FullNameRef, Error = E>,
+            crate::name::Error: From<E>,
+        {
+            let name: &FullNameRef = name.try_into().map_err(|err| Error::RefnameValidation(err.into()))?;
+            let num_removed = self.rewrite_reflog(name, lock_mode, |index, _line| index == index_from_newest)?;
+            Ok(num_removed != 0)
+        }
+
+        /// Rewrite the reflog of `name` without the lines for which `is_removed(index_from_newest, parsed_line)` returns `true`,
+        /// and return the amount of removed lines.
+        fn rewrite_reflog(
+            &self,
+            name: &FullNameRef,
+            lock_mode: gix_lock::acquire::Fail,
+            mut is_removed: impl FnMut(usize, Option<LineRef<'_>>) -> bool,
+        ) -> Result<usize, Error> {
+            let reflog_path = self.reflog_path(name);
+            if !reflog_path.is_file() {
+                return Ok(0);
+            }
+            let (base, relative_path) = self.reference_path_with_base(name);
+            let _reference_lock = gix_lock::Marker::acquire_to_hold_resource(
+                base.join(relative_path.as_ref()),
+                lock_mode,
+                Some(base.into_owned()),
+            )?;
+            let mut lock = gix_lock::File::acquire_to_update_resource(&reflog_path, lock_mode, None)?;
+            let lines = match std::fs::read(&reflog_path) {
+                Ok(lines) => lines,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+                Err(err) => {
+                    return Err(Error::Read {
+                        source: err,
+                        path: reflog_path,
+                    })
+                }
+            };
+
+            let num_lines = lines.lines_with_terminator().count();
+            let mut num_removed = 0;
+            for (index_from_oldest, line) in lines.lines_with_terminator().enumerate() {
+                let parsed = LineRef::from_bytes(line.trim_end_with(|c| c == '\n' || c == '\r')).ok();
+                if is_removed(num_lines - 1 - index_from_oldest, parsed) {
+                    num_removed += 1;
+                } else {
+                    lock.write_all(line).map_err(Error::Write)?;
+                }
+            }
+            if num_removed == 0 {
+                return Ok(0);
+            }
+            lock.commit()?;
+            Ok(num_removed)
+        }
+    }
+
+    mod error {
+        use std::path::PathBuf;
+
+        /// The error returned by [`crate::file::Store::reflog_expire()`] and [`crate::file::Store::reflog_delete_entry()`].
+        #[derive(Debug, thiserror::Error)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            #[error("The reflog name or path is not a valid ref name")]
+            RefnameValidation(#[from] crate::name::Error),
+            #[error(transparent)]
+            LockAcquire(#[from] gix_lock::acquire::Error),
+            #[error("The reflog at {path:?} could not be read")]
+            Read { source: std::io::Error, path: PathBuf },
+            #[error("The expired reflog could not be written")]
+            Write(#[source] std::io::Error),
+            #[error(transparent)]
+            Commit(#[from] gix_lock::commit::Error<gix_lock::File>),
+        }
+    }
+    pub use error::Error;
+}
+
 mod error {
     /// The error returned by [`crate::file::Store::reflog_iter()`].
     #[derive(Debug, thiserror::Error)]
@@ -67,3 +67,93 @@ mod iter_rev {
         Ok(())
     }
 }
+
+mod expire {
+    use gix_lock::acquire::Fail;
+
+    use crate::file::store_writable;
+
+    #[test]
+    fn removes_matching_entries_and_keeps_the_rest() -> crate::Result {
+        let (_tmp, store) = store_writable("make_repo_for_reflog.sh")?;
+        let mut buf = Vec::new();
+        let before: Vec<_> = store
+            .reflog_iter("HEAD", &mut buf)?
+            .expect("exists")
+            .map(|line| line.map(|line| line.to_owned()))
+            .collect::<Result<_, _>>()?;
+        assert_eq!(before.len(), 5);
+
+        let mut seen = 0;
+        let num_expired = store.reflog_expire("HEAD", Fail::Immediately, |_line| {
+            seen += 1;
+            seen <= 2
+        })?;
+        assert_eq!(num_expired, 2, "the two oldest entries are gone");
+        let after: Vec<_> = store
+            .reflog_iter("HEAD", &mut buf)?
+            .expect("still exists")
+            .map(|line| line.map(|line| line.to_owned()))
+            .collect::<Result<_, _>>()?;
+        assert_eq!(after, before[2..], "the order of the remaining entries is retained");
+        assert!(
+            !store.git_dir().join("HEAD.lock").exists() && !store.git_dir().join("logs/HEAD.lock").exists(),
+            "locks are released"
+        );
+
+        assert_eq!(
+            store.reflog_expire("HEAD", Fail::Immediately, |_line| false)?,
+            0,
+            "nothing to do"
+        );
+        assert_eq!(
+            store.reflog_expire("refs/heads/does-not-exist", Fail::Immediately, |_line| true)?,
+            0,
+            "non-existing reflogs are ignored"
+        );
+        assert_eq!(
+            store.reflog_expire("refs/heads/main", Fail::Immediately, |_line| true)?,
+            5
+        );
+        assert_eq!(
+            store.reflog_iter("refs/heads/main", &mut buf)?.expect("exists").count(),
+            0,
+            "it's possible to remove all entries"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn delete_entry_by_index_from_newest() -> crate::Result {
+        let (_tmp, store) = store_writable("make_repo_for_reflog.sh")?;
+        let mut buf = Vec::new();
+        let mut entries = |store: &gix_ref::file::Store| -> crate::Result<Vec<_>> {
+            Ok(store
+                .reflog_iter("HEAD", &mut buf)?
+                .expect("exists")
+                .map(|line| line.map(|line| line.to_owned()))
+                .collect::<Result<_, _>>()?)
+        };
+        let before = entries(&store)?;
+
+        assert!(
+            store.reflog_delete_entry("HEAD", 0, Fail::Immediately)?,
+            "HEAD@{{0}} is the newest entry"
+        );
+        assert_eq!(entries(&store)?, before[..4]);
+
+        assert!(
+            store.reflog_delete_entry("HEAD", 3, Fail::Immediately)?,
+            "HEAD@{{3}} is now the oldest entry"
+        );
+        assert_eq!(entries(&store)?, before[1..4]);
+
+        assert!(
+            !store.reflog_delete_entry("HEAD", 3, Fail::Immediately)?,
+            "entries out of range are not found"
+        );
+        assert!(!store.reflog_delete_entry("refs/heads/does-not-exist", 0, Fail::Immediately)?);
+        assert_eq!(entries(&store)?.len(), 3, "nothing else changed");
+        Ok(())
+    }
+}
@@ -0,0 +1,114 @@
+use gix_hash::ObjectId;
+use gix_revwalk::graph;
+
+use crate::Graph;
+
+bitflags::bitflags! {
+    /// The flags used in the graph of a [`Cache`] to remember which commits contain the current target commit.
+    #[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+    pub struct Flags: u8 {
+        /// The commit contains the target commit, i.e. the target is reachable from it.
+        const CONTAINS = 1 << 0;
+        /// The commit doesn't contain the target commit.
+        const DOES_NOT_CONTAIN = 1 << 1;
+    }
+}
+
+/// The error returned by [`Cache::contains()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A commit could not be inserted into the graph")]
+    InsertCommit(#[from] gix_revwalk::graph::get_or_insert_default::Error),
+}
+
+/// A utility to answer if a commit is contained in (i.e. reachable from) one or more tips, similar to
+/// `git for-each-ref --contains`.
+///
+/// It keeps the structure of all commits it has seen in memory and remembers which of them contain the last target commit,
+/// so that querying many tips for the same target only traverses shared history once.
+/// Querying another target reuses the known commit structure, which avoids decoding commits again.
+pub struct Cache<'find, 'cache> {
+    graph: Graph<'find, 'cache, graph::Commit<Flags>>,
+    target: Option<ObjectId>,
+}
+
+impl<'find, 'cache> Cache<'find, 'cache> {
+    /// Create a new instance to traverse `graph`, which should be backed by a commit-graph to allow
+    /// stopping the traversal early based on generation numbers.
+    pub fn new(graph: Graph<'find, 'cache, graph::Commit<Flags>>) -> Self {
+        Cache { graph, target: None }
+    }
+
+    /// Return `true` if `commit` is reachable from `tip`, which is also the case if both are the same.
+    ///
+    /// If `tip` or `commit` don't exist or aren't commits, `false` is returned, as is the case if `commit` is beyond
+    /// the boundary of a shallow repository.
+    pub fn contains(&mut self, tip: ObjectId, commit: ObjectId) -> Result<bool, Error> {
+        if tip == commit {
+            return Ok(self.graph.get_or_insert_commit(commit, |_| {})?.is_some());
+        }
+        if self.target != Some(commit) {
+            self.graph.clear_commit_data(|flags| *flags = Flags::empty());
+            self.target = Some(commit);
+        }
+        let Some(target) = self.graph.get_or_insert_commit(commit, |_| {})? else {
+            return Ok(false);
+        };
+        target.data = Flags::CONTAINS;
+        let cutoff = target.generation;
+
+        let mut stack = vec![tip];
+        while let Some(id) = stack.last().copied() {
+            let Some(commit) = self.graph.get_or_insert_commit(id, |_| {})? else {
+                stack.pop();
+                continue;
+            };
+            if !commit.data.is_empty() {
+                stack.pop();
+                continue;
+            }
+            // A commit with a generation that isn't higher than the one of the target can't contain it.
+            if let (Some(cutoff), Some(generation)) = (cutoff, commit.generation) {
+                if generation <= cutoff {
+                    commit.data = Flags::DOES_NOT_CONTAIN;
+                    stack.pop();
+                    continue;
+                }
+            }
+
+            let parents = commit.parents.clone();
+            let stack_len = stack.len();
+            let mut state = Flags::DOES_NOT_CONTAIN;
+            for parent_id in parents {
+                match self.graph.get_or_insert_commit(parent_id, |_| {})? {
+                    // Parents outside a shallow boundary don't exist.
+                    None => {}
+                    Some(parent) if parent.data.contains(Flags::CONTAINS) => {
+                        state = Flags::CONTAINS;
+                        stack.truncate(stack_len);
+                        break;
+                    }
+                    Some(parent) if parent.data.is_empty() => {
+                        state = Flags::empty();
+                        stack.push(parent_id);
+                    }
+                    Some(_) => {}
+                }
+            }
+            if !state.is_empty() {
+                self.graph.get_mut(&id).expect("present").data = state;
+                stack.pop();
+            }
+        }
+        Ok(self
+            .graph
+            .get(&tip)
+            .map_or(false, |tip| tip.data.contains(Flags::CONTAINS)))
+    }
+
+    /// Return the graph with all commit information gathered so far.
+    pub fn into_graph(self) -> Graph<'find, 'cache, graph::Commit<Flags>> {
+        self.graph
+    }
+}
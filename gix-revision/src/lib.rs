@@ -8,6 +8,10 @@
 #![cfg_attr(all(doc, feature = "document-features"), feature(doc_cfg, doc_auto_cfg))]
 #![deny(missing_docs, rust_2018_idioms, unsafe_code)]
 
+///
+#[cfg(feature = "contains")]
+pub mod contains;
+
 ///
 #[cfg(feature = "describe")]
 pub mod describe;
@@ -20,6 +24,12 @@ pub mod merge_base;
 #[cfg(feature = "merge_base")]
 pub use merge_base::function::merge_base;
 
+///
+#[cfg(feature = "name_rev")]
+pub mod name_rev;
+#[cfg(feature = "name_rev")]
+pub use name_rev::function::name_rev;
+
 ///
 pub mod spec;
 pub use gix_revwalk::{graph, Graph, PriorityQueue};
@@ -0,0 +1,277 @@
+use std::borrow::Cow;
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+use gix_date::SecondsSinceUnixEpoch;
+
+/// The extra distance charged for stepping onto a non-first parent, which makes names along the first-parent chain
+/// preferable. It's the same value that `git name-rev` uses.
+pub const MERGE_TRAVERSAL_WEIGHT: u32 = 65535;
+
+/// The amount of seconds to subtract from the oldest commit to name when using commit dates as cutoff, to allow for clock skew.
+pub const CUTOFF_DATE_SLOP: SecondsSinceUnixEpoch = 86400;
+
+/// A reference tip from which names are propagated to all commits in its past.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Tip<'name> {
+    /// The commit the reference ultimately points to, after peeling tags.
+    pub id: gix_hash::ObjectId,
+    /// The name to use for the tip, like `v1.0` or `main`.
+    pub name: Cow<'name, BStr>,
+    /// If `true`, the reference is a tag, and names derived from tags are preferred over all others.
+    pub from_tag: bool,
+    /// The date of the first annotated tag on the way to the commit, or the committer date of the commit
+    /// if the reference points to it directly.
+    ///
+    /// Older tips are preferred if everything else is equal.
+    pub tagger_date: SecondsSinceUnixEpoch,
+    /// If `true`, the reference points to an annotated tag that had to be peeled to get to the commit,
+    /// which will be visible as `^0` suffix if the commit is named by this tip directly.
+    pub peeled: bool,
+}
+
+/// The name of a single commit, as stored in the graph passed to [`name_rev()`](function::name_rev()).
+///
+/// Use [`Outcome::format()`] to turn it into a string like `v1.0~2^2~1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Name {
+    /// The index into [`Outcome::tip_names`] of the name this commit is relative to.
+    pub tip: usize,
+    /// The `tagger_date` of the original [`Tip`].
+    pub tagger_date: SecondsSinceUnixEpoch,
+    /// The amount of first-parent hops between the commit and the tip name, visible as `~<generation>`.
+    pub generation: u32,
+    /// The total amount of hops to the original tip, where each hop to a non-first parent is
+    /// charged with [`MERGE_TRAVERSAL_WEIGHT`].
+    pub distance: u32,
+    /// If `true`, the original tip is a tag.
+    pub from_tag: bool,
+}
+
+impl Name {
+    /// Return `true` if `self` should replace the `existing` name.
+    fn is_better_than(&self, existing: &Name) -> bool {
+        // Among tags, prefer the older one even if it is further away.
+        if self.from_tag && existing.from_tag {
+            return existing.tagger_date > self.tagger_date
+                || (existing.tagger_date == self.tagger_date && existing.distance > self.distance);
+        }
+        if self.from_tag != existing.from_tag {
+            return self.from_tag;
+        }
+        if existing.distance != self.distance {
+            return existing.distance > self.distance;
+        }
+        existing.tagger_date > self.tagger_date
+    }
+}
+
+/// The outcome of [`name_rev()`](function::name_rev()), needed to turn the [names](Name) stored in the graph into strings.
+#[derive(Debug, Clone, Default)]
+pub struct Outcome<'name> {
+    /// All names that commits can be relative to, which includes the names of tips along with the names
+    /// of merge-parents that were derived from them.
+    pub tip_names: Vec<Cow<'name, BStr>>,
+    /// The amount of commits we traversed.
+    pub commits_seen: u32,
+}
+
+impl<'name> Outcome<'name> {
+    /// Produce a string like `v1.0~2^2~1` for the given `name` as the `git name-rev --name-only` would.
+    pub fn format(&self, name: &Name) -> BString {
+        let tip = self.tip_names[name.tip].as_ref();
+        if name.generation == 0 {
+            return tip.to_owned();
+        }
+        let mut out: BString = strip_peel_suffix(tip).into();
+        out.push_str(format!("~{}", name.generation));
+        out
+    }
+}
+
+/// The options for use in [`name_rev()`](function::name_rev()).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Options {
+    /// If `false`, the default, don't walk into the past of commits that can't contain any of the commits to name,
+    /// which is determined by generation numbers of the commit-graph if available, or by the commit date
+    /// with a slop of [`CUTOFF_DATE_SLOP`].
+    ///
+    /// If `true`, name all commits reachable from all tips.
+    pub all: bool,
+}
+
+/// The error returned by the [`name_rev()`](function::name_rev()) function.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Find(#[from] gix_object::find::existing_iter::Error),
+    #[error("A commit could not be decoded during traversal")]
+    Decode(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    IterParents(#[from] gix_revwalk::graph::commit::iter_parents::Error),
+}
+
+fn strip_peel_suffix(name: &BStr) -> &BStr {
+    name.strip_suffix(b"^0").unwrap_or(name).as_bstr()
+}
+
+pub(crate) mod function {
+    use bstr::BString;
+    use gix_date::SecondsSinceUnixEpoch;
+    use gix_hash::ObjectId;
+    use gix_revwalk::graph::{Generation, LazyCommit};
+
+    use super::{strip_peel_suffix, Error, Name, Options, Outcome, Tip, CUTOFF_DATE_SLOP, MERGE_TRAVERSAL_WEIGHT};
+    use crate::Graph;
+
+    /// Propagate the names of all `tips` to the commits in their past while tracking them in `graph`,
+    /// using the same rules as `git name-rev`, and return the information needed to [format](Outcome::format()) them.
+    ///
+    /// Names derived from tags are preferred, where older tags win even if they are further away.
+    /// Otherwise, names that are fewer hops away are preferred, followed by older tips.
+    /// After the call, the [`Name`] of each commit to name can be obtained from `graph` if it is reachable from any tip.
+    ///
+    /// Unless [`Options::all`] is set, the traversal stops at commits that can't contain any of the `commits`
+    /// to name, which is known precisely if generation numbers from a commit-graph are available.
+    /// Otherwise, the oldest commit date is used as cutoff.
+    pub fn name_rev<'name>(
+        commits: &[ObjectId],
+        tips: impl IntoIterator<Item = Tip<'name>>,
+        graph: &mut Graph<'_, '_, Name>,
+        Options { all }: Options,
+    ) -> Result<Outcome<'name>, Error> {
+        let _span = gix_trace::coarse!("gix_revision::name_rev()", commits = commits.len(), all);
+        let cutoff = if all {
+            Cutoff::default()
+        } else {
+            Cutoff::from_commits(commits, graph)?
+        };
+
+        let mut tips: Vec<_> = tips.into_iter().collect();
+        // Tags first, then older tips first, as these end up being preferred if everything else is equal.
+        tips.sort_by(|a, b| {
+            b.from_tag
+                .cmp(&a.from_tag)
+                .then_with(|| a.tagger_date.cmp(&b.tagger_date))
+        });
+
+        graph.clear();
+        let mut out = Outcome::default();
+        let mut stack = Vec::new();
+        let mut parents = Vec::new();
+        let mut parents_to_queue = Vec::new();
+        for tip in tips {
+            match graph.try_lookup(&tip.id)? {
+                Some(commit) if !cutoff.excludes(&commit)? => {}
+                _ => continue,
+            }
+            let name = Name {
+                tip: out.tip_names.len(),
+                tagger_date: tip.tagger_date,
+                generation: 0,
+                distance: 0,
+                from_tag: tip.from_tag,
+            };
+            if !graph
+                .get(&tip.id)
+                .map_or(true, |existing| name.is_better_than(existing))
+            {
+                continue;
+            }
+            out.tip_names.push(if tip.peeled {
+                let mut peeled = tip.name.into_owned();
+                peeled.extend_from_slice(b"^0");
+                peeled.into()
+            } else {
+                tip.name
+            });
+            graph.insert(tip.id, name);
+
+            stack.push(tip.id);
+            while let Some(id) = stack.pop() {
+                out.commits_seen += 1;
+                let name = graph[&id];
+                parents.clear();
+                for parent_id in graph.lookup(&id)?.iter_parents() {
+                    parents.push(parent_id?);
+                }
+
+                for (parent_number, parent_id) in parents.iter().enumerate().map(|(idx, id)| (idx + 1, *id)) {
+                    match graph.try_lookup(&parent_id)? {
+                        Some(parent) if !cutoff.excludes(&parent)? => {}
+                        _ => continue,
+                    }
+                    let mut parent_name = if parent_number > 1 {
+                        Name {
+                            generation: 0,
+                            distance: name.distance.saturating_add(MERGE_TRAVERSAL_WEIGHT),
+                            ..name
+                        }
+                    } else {
+                        Name {
+                            generation: name.generation + 1,
+                            distance: name.distance.saturating_add(1),
+                            ..name
+                        }
+                    };
+                    if !graph
+                        .get(&parent_id)
+                        .map_or(true, |existing| parent_name.is_better_than(existing))
+                    {
+                        continue;
+                    }
+                    if parent_number > 1 {
+                        let tip = strip_peel_suffix(out.tip_names[name.tip].as_ref());
+                        let merge_parent_name = if name.generation > 0 {
+                            format!("{tip}~{}^{parent_number}", name.generation)
+                        } else {
+                            format!("{tip}^{parent_number}")
+                        };
+                        parent_name.tip = out.tip_names.len();
+                        out.tip_names.push(BString::from(merge_parent_name).into());
+                    }
+                    graph.insert(parent_id, parent_name);
+                    parents_to_queue.push(parent_id);
+                }
+                // The first parent must be processed first.
+                stack.extend(parents_to_queue.drain(..).rev());
+            }
+        }
+        Ok(out)
+    }
+
+    /// Determines which commits are too old to contain any of the commits to name.
+    #[derive(Default)]
+    struct Cutoff {
+        generation: Option<Generation>,
+        date: Option<SecondsSinceUnixEpoch>,
+    }
+
+    impl Cutoff {
+        fn from_commits(commits: &[ObjectId], graph: &mut Graph<'_, '_, Name>) -> Result<Self, Error> {
+            let mut out = Cutoff::default();
+            for id in commits {
+                let Some(commit) = graph.try_lookup(id)? else {
+                    continue;
+                };
+                let (generation, date) = commit.generation_and_timestamp()?;
+                out.date = Some(out.date.map_or(date, |cutoff| cutoff.min(date)));
+                if let Some(generation) = generation {
+                    out.generation = Some(out.generation.map_or(generation, |cutoff| cutoff.min(generation)));
+                }
+            }
+            out.date = out.date.map(|date| date.saturating_sub(CUTOFF_DATE_SLOP));
+            Ok(out)
+        }
+
+        fn excludes(&self, commit: &LazyCommit<'_, '_>) -> Result<bool, Error> {
+            Ok(match self.generation {
+                Some(cutoff) => cutoff > 0 && commit.generation().map_or(false, |generation| generation < cutoff),
+                None => match self.date {
+                    Some(cutoff) => commit.committer_timestamp()? < cutoff,
+                    None => false,
+                },
+            })
+        }
+    }
+}
@@ -450,7 +450,7 @@ where
                         source: None,
                     })
                     .and_then(|date| {
-                        gix_date::parse(date, Some(SystemTime::now())).map_err(|err| Error::Time {
+                        gix_date::parse::approximate(date, Some(SystemTime::now())).map_err(|err| Error::Time {
                             input: nav.into(),
                             source: err.into(),
                         })
@@ -0,0 +1,65 @@
+use std::path::Path;
+
+use gix_hash::ObjectId;
+use gix_object::bstr::{BString, ByteSlice};
+use gix_revision::contains::Cache;
+
+use crate::hex_to_id;
+
+#[test]
+fn baseline() -> crate::Result {
+    let root = gix_testtools::scripted_fixture_read_only("make_name_rev_repo.sh")?;
+    let odb = gix_odb::at(root.join(".git/objects"))?;
+    let tips = tips(&root)?;
+    let expectations = std::fs::read(root.join("contains.baseline"))?;
+
+    for use_commitgraph in [false, true] {
+        let commitgraph = use_commitgraph
+            .then(|| gix_commitgraph::Graph::from_info_dir(&odb.store_ref().path().join("info")).unwrap());
+        let mut cache = Cache::new(gix_revision::Graph::new(&odb, commitgraph.as_ref()));
+        let mut count = 0;
+        for line in expectations.lines() {
+            let mut tokens = line.split_str(" ");
+            let commit = hex_to_id(tokens.next().expect("commit").to_str()?);
+            let expected: Vec<_> = tokens.map(ByteSlice::as_bstr).collect();
+            let mut actual = Vec::new();
+            for (refname, tip) in &tips {
+                if cache.contains(*tip, commit)? {
+                    actual.push(refname.as_bstr());
+                }
+            }
+            assert_eq!(actual, expected, "{commit}, commitgraph = {use_commitgraph}");
+            count += 1;
+        }
+        assert_eq!(count, 16, "all commits were checked");
+    }
+    Ok(())
+}
+
+#[test]
+fn missing_commits_are_not_contained() -> crate::Result {
+    let root = gix_testtools::scripted_fixture_read_only("make_name_rev_repo.sh")?;
+    let odb = gix_odb::at(root.join(".git/objects"))?;
+    let mut cache = Cache::new(gix_revision::Graph::new(&odb, None));
+    let missing = hex_to_id("0000000000000000000000000000000000000001");
+    let main = tips(&root)?
+        .into_iter()
+        .find_map(|(refname, id)| (refname == "refs/heads/main").then_some(id))
+        .expect("main is present");
+    assert!(!cache.contains(main, missing)?);
+    assert!(!cache.contains(missing, main)?);
+    assert!(!cache.contains(missing, missing)?, "the commit has to exist");
+    assert!(cache.contains(main, main)?, "an existing commit contains itself");
+    Ok(())
+}
+
+/// Read all reference names along with the commit they point to from the `tips` file written by `git for-each-ref`.
+fn tips(root: &Path) -> crate::Result<Vec<(BString, ObjectId)>> {
+    let mut out = Vec::new();
+    for line in std::fs::read(root.join("tips"))?.lines() {
+        let fields: Vec<_> = line.split_str(" ").collect();
+        let peeled_id = if fields[2].is_empty() { fields[3] } else { fields[2] };
+        out.push((fields[0].as_bstr().to_owned(), hex_to_id(peeled_id.to_str()?)));
+    }
+    Ok(out)
+}
@@ -0,0 +1,72 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function tick () {
+  if test -z "${tick+set}"; then
+    tick=1112911993
+  else
+    tick=$((tick + 60))
+  fi
+  GIT_COMMITTER_DATE="$tick -0700"
+  GIT_AUTHOR_DATE="$tick -0700"
+  export GIT_COMMITTER_DATE GIT_AUTHOR_DATE
+}
+
+function commit () {
+  tick
+  git commit -q --allow-empty -m "$1"
+}
+
+git init -q
+git config merge.ff false
+
+git checkout -q -b main
+commit c1
+git tag -m "annotated v0.1" v0.1
+commit c2
+commit c3
+tick
+git tag v0.2-lightweight
+
+git checkout -q -b feature
+commit f1
+commit f2
+tick
+git tag -m "annotated feature" feature-done
+commit f3
+
+git checkout -q -b topic main
+commit t1
+commit t2
+
+git checkout -q main
+commit c4
+tick
+git merge -q topic -m "merge topic"
+commit c5
+tick
+git merge -q feature -m "merge feature"
+tick
+git tag -m "annotated v1.0" v1.0
+commit c6
+commit c7
+tick
+git tag -m "annotated v1.1" v1.1
+commit c8
+
+git checkout -q -b unmerged main~3
+commit u1
+
+git commit-graph write --no-progress --reachable
+git repack -adq
+
+git for-each-ref --format='%(refname) %(objecttype) %(*objectname) %(objectname) %(taggerdate:unix) %(committerdate:unix)' > tips
+for commit in $(git rev-list --all); do
+  echo "$commit $(git name-rev --tags --name-only --no-undefined --always "$commit")"
+done > name-rev-tags.baseline
+for commit in $(git rev-list --all); do
+  echo "$commit $(git name-rev --name-only --no-undefined --always "$commit")"
+done > name-rev-all.baseline
+for commit in $(git rev-list --all); do
+  echo "$commit" $(git for-each-ref --format='%(refname)' --contains "$commit")
+done > contains.baseline
@@ -0,0 +1,137 @@
+use std::{borrow::Cow, path::Path};
+
+use gix_date::SecondsSinceUnixEpoch;
+use gix_hash::ObjectId;
+use gix_object::bstr::{BStr, ByteSlice};
+use gix_revision::{name_rev, name_rev::Tip};
+
+use crate::hex_to_id;
+
+#[test]
+fn tags_only_baseline() -> crate::Result {
+    assert_baseline("name-rev-tags.baseline", |refname| {
+        refname
+            .strip_prefix(b"refs/tags/")
+            .map(|name| name.as_bstr().to_owned())
+    })
+}
+
+#[test]
+fn all_refs_baseline() -> crate::Result {
+    assert_baseline("name-rev-all.baseline", |refname| {
+        Some(
+            refname
+                .strip_prefix(b"refs/heads/")
+                .or_else(|| refname.strip_prefix(b"refs/"))
+                .unwrap_or(refname)
+                .as_bstr()
+                .to_owned(),
+        )
+    })
+}
+
+#[test]
+fn unreachable_commits_have_no_name_and_all_disables_the_generation_cutoff() -> crate::Result {
+    let root = gix_testtools::scripted_fixture_read_only("make_name_rev_repo.sh")?;
+    let odb = gix_odb::at(root.join(".git/objects"))?;
+    let cache = gix_commitgraph::Graph::from_info_dir(&odb.store_ref().path().join("info"))?;
+    let all_tips = tips(&root, |refname| Some(refname.to_owned()))?;
+    let peeled_id = |refname: &str| {
+        all_tips
+            .iter()
+            .find(|tip| tip.name.as_ref() == refname)
+            .expect("present")
+            .id
+    };
+    let (unmerged, newest_tagged) = (peeled_id("refs/heads/unmerged"), peeled_id("refs/tags/v1.1"));
+    let tips = tips(&root, |refname| {
+        refname
+            .strip_prefix(b"refs/tags/")
+            .map(|name| name.as_bstr().to_owned())
+    })?;
+
+    let mut graph = gix_revision::Graph::new(&odb, Some(&cache));
+    let outcome = name_rev(&[newest_tagged], tips.clone(), &mut graph, Default::default())?;
+    assert!(
+        graph.get(&unmerged).is_none(),
+        "the unmerged branch isn't reachable from any tag"
+    );
+    assert_eq!(
+        outcome.format(graph.get(&newest_tagged).expect("named")),
+        "v1.1^0",
+        "tips that point to annotated tags are marked as peeled"
+    );
+    let commits_seen_with_cutoff = outcome.commits_seen;
+    assert_eq!(
+        commits_seen_with_cutoff, 1,
+        "only the tip itself is traversed as all of its ancestors have a lower generation than the commit to name"
+    );
+
+    let outcome = name_rev(&[newest_tagged], tips, &mut graph, name_rev::Options { all: true })?;
+    assert!(
+        outcome.commits_seen > commits_seen_with_cutoff,
+        "without cutoff, everything reachable is traversed"
+    );
+    assert_eq!(outcome.format(graph.get(&newest_tagged).expect("named")), "v1.1^0");
+    Ok(())
+}
+
+fn assert_baseline(
+    baseline: &str,
+    mut name_from_refname: impl FnMut(&BStr) -> Option<gix_object::bstr::BString>,
+) -> crate::Result {
+    let root = gix_testtools::scripted_fixture_read_only("make_name_rev_repo.sh")?;
+    let odb = gix_odb::at(root.join(".git/objects"))?;
+    let tips = tips(&root, &mut name_from_refname)?;
+    let expectations = std::fs::read(root.join(baseline))?;
+    let mut count = 0;
+    for line in expectations.lines() {
+        let (id, expected) = line.split_once_str(" ").expect("id and name");
+        let id = hex_to_id(id.to_str()?);
+        for use_commitgraph in [false, true] {
+            let cache = use_commitgraph
+                .then(|| gix_commitgraph::Graph::from_info_dir(&odb.store_ref().path().join("info")).unwrap());
+            let mut graph = gix_revision::Graph::new(&odb, cache.as_ref());
+            let outcome = name_rev(&[id], tips.clone(), &mut graph, Default::default())?;
+            let actual = graph.get(&id).map(|name| outcome.format(name)).unwrap_or_else(|| {
+                // `git name-rev --always` falls back to the abbreviated id.
+                id.to_hex_with_len(7).to_string().into()
+            });
+            assert_eq!(actual, expected.as_bstr(), "{id}, commitgraph = {use_commitgraph}");
+        }
+        count += 1;
+    }
+    assert_eq!(count, 16, "all commits are named");
+    Ok(())
+}
+
+/// Parse the `tips` file as written by `git for-each-ref` into tips, using `name_from_refname` to select and name them.
+fn tips(
+    root: &Path,
+    mut name_from_refname: impl FnMut(&BStr) -> Option<gix_object::bstr::BString>,
+) -> crate::Result<Vec<Tip<'static>>> {
+    let mut out = Vec::new();
+    for line in std::fs::read(root.join("tips"))?.lines() {
+        let fields: Vec<_> = line.split_str(" ").collect();
+        let [refname, kind, peeled_id, id, tagger_date, committer_date] = fields[..] else {
+            unreachable!("6 fields per line")
+        };
+        let Some(name) = name_from_refname(refname.as_bstr()) else {
+            continue;
+        };
+        let peeled = kind == b"tag";
+        let (id, date): (ObjectId, &[u8]) = if peeled {
+            (hex_to_id(peeled_id.to_str()?), tagger_date)
+        } else {
+            (hex_to_id(id.to_str()?), committer_date)
+        };
+        out.push(Tip {
+            id,
+            name: Cow::Owned(name),
+            from_tag: refname.starts_with(b"refs/tags/"),
+            tagger_date: date.to_str()?.parse::<SecondsSinceUnixEpoch>()?,
+            peeled,
+        });
+    }
+    Ok(out)
+}
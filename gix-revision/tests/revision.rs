@@ -1,7 +1,11 @@
+#[cfg(feature = "contains")]
+mod contains;
 #[cfg(feature = "describe")]
 mod describe;
 #[cfg(feature = "merge_base")]
 mod merge_base;
+#[cfg(feature = "name_rev")]
+mod name_rev;
 mod spec;
 
 pub use gix_testtools::Result;
@@ -35,7 +35,7 @@ fn reflog_by_entry_for_current_branch() {
 
 #[test]
 fn reflog_by_date_for_current_branch() {
-    let rec = parse("@{1979-02-26 18:30:00}");
+    let rec = parse("@{1979-02-26 18:30:00 +0030}");
 
     assert!(rec.kind.is_none());
     assert_eq!(rec.find_ref[0], None,);
@@ -43,10 +43,33 @@ fn reflog_by_date_for_current_branch() {
         rec.prefix[0], None,
         "neither ref nor prefixes are set, straight to navigation"
     );
-    assert_eq!(rec.current_branch_reflog_entry[0], Some("42 +0030".to_string()));
+    assert_eq!(rec.current_branch_reflog_entry[0], Some("288900000 +0030".to_string()));
     assert_eq!(rec.calls, 1);
 }
 
+#[test]
+fn reflog_by_relative_date() {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("after epoch")
+        .as_secs() as i64;
+    for (spec, seconds_ago) in [
+        ("@{yesterday}", 24 * 60 * 60),
+        ("@{1.day.ago}", 24 * 60 * 60),
+        ("main@{2.weeks.ago}", 14 * 24 * 60 * 60),
+        ("main@{2 weeks ago}", 14 * 24 * 60 * 60),
+    ] {
+        let rec = parse(spec);
+        let time = rec.current_branch_reflog_entry[0].as_ref().expect("date was parsed");
+        let seconds: i64 = time.split(' ').next().expect("seconds first").parse().expect("valid");
+        assert!(
+            (now - seconds - seconds_ago).abs() < 60,
+            "{spec}: {time} should be about {seconds_ago}s ago"
+        );
+        assert_eq!(rec.calls, if spec.starts_with('@') { 1 } else { 2 });
+    }
+}
+
 #[test]
 fn reflog_by_date_with_date_parse_failure() {
     let err = try_parse("@{foo}").unwrap_err();
@@ -56,9 +79,9 @@ fn reflog_by_date_with_date_parse_failure() {
 #[test]
 fn reflog_by_date_for_hash_is_invalid() {
     for (spec, full_name) in [
-        ("1234@{1979-02-26 18:30:00}", "1234"),
-        ("abcd-dirty@{1979-02-26 18:30:00}", "abcd-dirty"),
-        ("v1.2.3-0-g1234@{1979-02-26 18:30:00}", "v1.2.3-0-g1234"),
+        ("1234@{1979-02-26 18:30:00 +0030}", "1234"),
+        ("abcd-dirty@{1979-02-26 18:30:00 +0030}", "abcd-dirty"),
+        ("v1.2.3-0-g1234@{1979-02-26 18:30:00 +0030}", "v1.2.3-0-g1234"),
     ] {
         let err = try_parse(spec).unwrap_err();
         assert!(matches!(err, spec::parse::Error::ReflogLookupNeedsRefName {name} if name == full_name));
@@ -68,10 +91,10 @@ fn reflog_by_date_for_hash_is_invalid() {
 #[test]
 fn reflog_by_date_for_given_ref_name() {
     for (spec, expected_ref) in [
-        ("main@{1979-02-26 18:30:00}", "main"),
-        ("refs/heads/other@{1979-02-26 18:30:00}", "refs/heads/other"),
+        ("main@{1979-02-26 18:30:00 +0030}", "main"),
+        ("refs/heads/other@{1979-02-26 18:30:00 +0030}", "refs/heads/other"),
         (
-            "refs/worktree/feature/a@{1979-02-26 18:30:00}",
+            "refs/worktree/feature/a@{1979-02-26 18:30:00 +0030}",
             "refs/worktree/feature/a",
         ),
     ] {
@@ -80,7 +103,7 @@ fn reflog_by_date_for_given_ref_name() {
         assert!(rec.kind.is_none());
         assert_eq!(rec.get_ref(0), expected_ref);
         assert_eq!(rec.prefix[0], None,);
-        assert_eq!(rec.current_branch_reflog_entry[0], Some("42 +0030".to_string()));
+        assert_eq!(rec.current_branch_reflog_entry[0], Some("288900000 +0030".to_string()));
         assert_eq!(rec.calls, 2, "first the ref, then the reflog entry");
     }
 }
@@ -0,0 +1,413 @@
+//! Changes between a tree, represented as index, and the index, i.e. what `git diff --cached` shows.
+mod types;
+pub use types::{Change, Context, Error, Options, Outcome};
+
+pub(super) mod function {
+    use crate::is_dir_to_mode;
+    use crate::tree_index::{Change, Context, Error, Options, Outcome};
+    use bstr::{BStr, BString, ByteVec};
+    use gix_diff::tree::visit::Action;
+    use gix_object::FindExt;
+    use std::borrow::Cow;
+    use std::cmp::Ordering;
+    use std::collections::VecDeque;
+
+    /// Compare the entries of `lhs`, typically an index created from the tree of `HEAD`, to the ones in `rhs`,
+    /// typically the current index, and call `cb` with each [`Change`] as if `lhs` was changed into `rhs`.
+    ///
+    /// * `lhs`
+    ///     - the index state to compare with, usually created with [`State::from_tree()`](gix_index::State::from_tree()).
+    /// * `rhs`
+    ///     - the index state to compare to, usually the index of the repository.
+    /// * `cb`
+    ///     - called with each change in order of their paths, and it may return [`Action::Cancel`] to stop the operation early.
+    /// * `objects`
+    ///     - A way to obtain trees to expand sparse directories, and blobs for similarity checks.
+    /// * `ctx`
+    ///     - Additional information to limit the comparison and track rewrites.
+    /// * `options`
+    ///     - Configure the comparison.
+    ///
+    /// Conflicting entries, i.e. those with a stage other than 0, as well as entries that are merely intended to be added,
+    /// are ignored along with their counterpart on the other side. The index-to-worktree comparison is the one to report them.
+    ///
+    /// ### Sparse Indices
+    ///
+    /// Sparse directory entries that are present in both states with the same tree are skipped entirely, which
+    /// is the common case as changes typically happen within the sparse cone. Otherwise, they are expanded into their
+    /// contents so these can be compared individually.
+    pub fn tree_index<'index, Find>(
+        lhs: &'index gix_index::State,
+        rhs: &'index gix_index::State,
+        mut cb: impl FnMut(Change<'index>) -> Action,
+        objects: Find,
+        mut ctx: Context<'_>,
+        options: Options,
+    ) -> Result<Outcome, Error>
+    where
+        Find: gix_object::Find + gix_object::FindHeader,
+    {
+        let _span = gix_features::trace::coarse!("gix_status::tree_index()");
+        let mut out = Outcome::default();
+        let mut lhs_cursor = Cursor::new(lhs, rhs, ctx.pathspec.common_prefix());
+        let mut rhs_cursor = Cursor::new(rhs, lhs, ctx.pathspec.common_prefix());
+        let mut tracker = options.rewrites.map(gix_diff::rewrites::Tracker::<Change<'index>>::new);
+        let mut changes_for_sorting = Vec::new();
+
+        let mut emit = |change: Change<'index>, changes_for_sorting: &mut Vec<Change<'index>>| -> Action {
+            match tracker.as_mut() {
+                Some(tracker) => {
+                    let location = change.location().to_owned();
+                    if let Some(change) = tracker.try_push_change(change, location.as_ref()) {
+                        changes_for_sorting.push(change);
+                    }
+                    Action::Continue
+                }
+                None => cb(change),
+            }
+        };
+
+        let (mut lhs_item, mut rhs_item) = (None, None);
+        loop {
+            if ctx.should_interrupt.load(std::sync::atomic::Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            if lhs_item.is_none() {
+                lhs_item = lhs_cursor.next(&mut ctx, &objects, &mut out);
+            }
+            if rhs_item.is_none() {
+                rhs_item = rhs_cursor.next(&mut ctx, &objects, &mut out);
+            }
+            let change = match (lhs_item.take(), rhs_item.take()) {
+                (None, None) => break,
+                (Some(lhs), None) => {
+                    if lhs.is_sparse_directory() {
+                        lhs_cursor.expand(lhs, &objects, &mut out)?;
+                        continue;
+                    }
+                    lhs.into_deletion()
+                }
+                (None, Some(rhs)) => {
+                    if rhs.is_sparse_directory() {
+                        rhs_cursor.expand(rhs, &objects, &mut out)?;
+                        continue;
+                    }
+                    rhs.into_addition()
+                }
+                (Some(lhs), Some(rhs)) => match lhs.location.cmp(&rhs.location) {
+                    Ordering::Less => {
+                        rhs_item = Some(rhs);
+                        if lhs.is_sparse_directory() {
+                            lhs_cursor.expand(lhs, &objects, &mut out)?;
+                            continue;
+                        }
+                        lhs.into_deletion()
+                    }
+                    Ordering::Greater => {
+                        lhs_item = Some(lhs);
+                        if rhs.is_sparse_directory() {
+                            rhs_cursor.expand(rhs, &objects, &mut out)?;
+                            continue;
+                        }
+                        rhs.into_addition()
+                    }
+                    Ordering::Equal => {
+                        if lhs.entry_mode == rhs.entry_mode && lhs.id == rhs.id {
+                            continue;
+                        }
+                        match (lhs.is_sparse_directory(), rhs.is_sparse_directory()) {
+                            (false, false) => Change::Modification {
+                                location: rhs.location,
+                                previous_index: lhs.index,
+                                previous_entry_mode: lhs.entry_mode,
+                                previous_id: lhs.id,
+                                index: rhs.index,
+                                entry_mode: rhs.entry_mode,
+                                id: rhs.id,
+                            },
+                            (lhs_is_sparse, rhs_is_sparse) => {
+                                if lhs_is_sparse {
+                                    lhs_cursor.expand(lhs, &objects, &mut out)?;
+                                } else {
+                                    lhs_item = Some(lhs);
+                                }
+                                if rhs_is_sparse {
+                                    rhs_cursor.expand(rhs, &objects, &mut out)?;
+                                } else {
+                                    rhs_item = Some(rhs);
+                                }
+                                continue;
+                            }
+                        }
+                    }
+                },
+            };
+            if emit(change, &mut changes_for_sorting).cancelled() {
+                return Ok(out);
+            }
+        }
+
+        if let Some(mut tracker) = tracker {
+            let rewrites = tracker.emit(
+                |dest, source| {
+                    let change = match source {
+                        None => dest.change,
+                        Some(source) => {
+                            let (source_index, source_entry_mode) = match source.change {
+                                Change::Deletion { index, entry_mode, .. }
+                                | Change::Modification {
+                                    previous_index: index,
+                                    previous_entry_mode: entry_mode,
+                                    ..
+                                } => (*index, *entry_mode),
+                                Change::Addition { .. } | Change::Rewrite { .. } => {
+                                    unreachable!("BUG: only deletions and modifications can be sources")
+                                }
+                            };
+                            let Change::Addition {
+                                location,
+                                index,
+                                entry_mode,
+                                id,
+                            } = dest.change
+                            else {
+                                unreachable!("BUG: only additions can be destinations")
+                            };
+                            Change::Rewrite {
+                                source_location: Cow::Owned(source.location.to_owned()),
+                                source_index,
+                                source_entry_mode,
+                                source_id: source.id,
+                                location,
+                                index,
+                                entry_mode,
+                                id,
+                                diff: source.diff,
+                                copy: source.kind == gix_diff::rewrites::tracker::visit::SourceKind::Copy,
+                            }
+                        }
+                    };
+                    changes_for_sorting.push(change);
+                    Action::Continue
+                },
+                &mut ctx.resource_cache,
+                &objects,
+                |push| {
+                    for (index, entry) in lhs.entries().iter().enumerate() {
+                        if entry.stage_raw() != 0 || entry.mode.is_sparse() {
+                            continue;
+                        }
+                        let location = entry.path(lhs);
+                        push(
+                            Change::Modification {
+                                location: Cow::Borrowed(location),
+                                previous_index: index,
+                                previous_entry_mode: entry.mode,
+                                previous_id: entry.id,
+                                index,
+                                entry_mode: entry.mode,
+                                id: entry.id,
+                            },
+                            location,
+                        );
+                    }
+                    Ok::<_, std::convert::Infallible>(())
+                },
+            )?;
+            out.rewrites = Some(rewrites);
+        }
+
+        changes_for_sorting.sort_by(|a, b| a.location().cmp(b.location()));
+        for change in changes_for_sorting {
+            if cb(change).cancelled() {
+                break;
+            }
+        }
+        Ok(out)
+    }
+
+    /// An entry of either side, which may be borrowed from the index or created from the tree of a sparse directory.
+    struct Item<'index> {
+        location: Cow<'index, BStr>,
+        index: usize,
+        entry_mode: gix_index::entry::Mode,
+        id: gix_hash::ObjectId,
+    }
+
+    impl<'index> Item<'index> {
+        fn is_sparse_directory(&self) -> bool {
+            self.entry_mode.is_sparse()
+        }
+
+        fn into_addition(self) -> Change<'index> {
+            Change::Addition {
+                location: self.location,
+                index: self.index,
+                entry_mode: self.entry_mode,
+                id: self.id,
+            }
+        }
+
+        fn into_deletion(self) -> Change<'index> {
+            Change::Deletion {
+                location: self.location,
+                index: self.index,
+                entry_mode: self.entry_mode,
+                id: self.id,
+            }
+        }
+    }
+
+    /// Iterate the entries of an index in order, along with the contents of sparse directories that were expanded.
+    struct Cursor<'index> {
+        state: &'index gix_index::State,
+        /// The state of the other side, to be able to skip entries that are skipped there.
+        other: &'index gix_index::State,
+        other_has_skipped_entries: bool,
+        entries: std::ops::Range<usize>,
+        /// Items obtained from expanding a sparse directory, which sort before the next entry in `entries`.
+        expanded: VecDeque<Item<'index>>,
+    }
+
+    impl<'index> Cursor<'index> {
+        fn new(state: &'index gix_index::State, other: &'index gix_index::State, common_prefix: &BStr) -> Self {
+            // Sparse directories may contain paths with the common prefix, but aren't prefixed by it themselves.
+            let entries = if state.is_sparse() {
+                None
+            } else {
+                state.prefixed_entries_range(common_prefix)
+            }
+            .unwrap_or(0..state.entries().len());
+            Cursor {
+                state,
+                other,
+                other_has_skipped_entries: other.entries().iter().any(is_skipped_by_flags),
+                entries,
+                expanded: VecDeque::new(),
+            }
+        }
+
+        /// Return the next item which isn't excluded by flags or the pathspec. Sparse directories are always returned.
+        fn next<Find>(&mut self, ctx: &mut Context<'_>, objects: &Find, out: &mut Outcome) -> Option<Item<'index>>
+        where
+            Find: gix_object::Find,
+        {
+            loop {
+                let item = match self.expanded.pop_front() {
+                    Some(item) => item,
+                    None => {
+                        let index = self.entries.next()?;
+                        let entry = &self.state.entries()[index];
+                        if is_skipped_by_flags(entry) || self.is_skipped_in_other(entry.path(self.state)) {
+                            out.entries_skipped_by_entry_flags += 1;
+                            continue;
+                        }
+                        Item {
+                            location: Cow::Borrowed(entry.path(self.state)),
+                            index,
+                            entry_mode: entry.mode,
+                            id: entry.id,
+                        }
+                    }
+                };
+                if item.is_sparse_directory() {
+                    return Some(item);
+                }
+
+                let attr_stack = &mut ctx.resource_cache.attr_stack;
+                let is_excluded = ctx
+                    .pathspec
+                    .pattern_matching_relative_path(
+                        item.location.as_ref(),
+                        Some(item.entry_mode.is_submodule()),
+                        &mut |relative_path, case, is_dir, out| {
+                            attr_stack
+                                .set_case(case)
+                                .at_entry(relative_path, Some(is_dir_to_mode(is_dir)), objects)
+                                .map_or(false, |platform| platform.matching_attributes(out))
+                        },
+                    )
+                    .map_or(true, |m| m.is_excluded());
+                if is_excluded {
+                    out.entries_skipped_by_pathspec += 1;
+                    continue;
+                }
+                return Some(item);
+            }
+        }
+
+        /// Return `true` if the entry at `path` in the other state is ignored due to its flags, which
+        /// means it has to be ignored here as well.
+        fn is_skipped_in_other(&self, path: &BStr) -> bool {
+            self.other_has_skipped_entries
+                && self.other.entry_range(path).map_or(false, |range| {
+                    self.other.entries()[range].iter().any(is_skipped_by_flags)
+                })
+        }
+
+        /// Replace the sparse directory `item` with its contents, recursively.
+        fn expand<Find>(&mut self, item: Item<'index>, objects: &Find, out: &mut Outcome) -> Result<(), Error>
+        where
+            Find: gix_object::Find,
+        {
+            out.sparse_directories_expanded += 1;
+            let mut items = Vec::new();
+            let mut buf = Vec::new();
+            let mut prefix: BString = item.location.as_ref().into();
+            if prefix.last() == Some(&b'/') {
+                prefix.pop();
+            }
+            expand_tree(&item.id, &mut prefix, item.index, objects, &mut buf, &mut items).map_err(|source| {
+                Error::ExpandSparseDirectory {
+                    rela_path: item.location.into_owned(),
+                    source,
+                }
+            })?;
+            for item in items.into_iter().rev() {
+                self.expanded.push_front(item);
+            }
+            Ok(())
+        }
+    }
+
+    /// Add all non-tree entries of `tree` to `out`, depth-first, which is in the same order as the one of the index.
+    fn expand_tree<'index, Find>(
+        tree: &gix_hash::oid,
+        prefix: &mut BString,
+        index: usize,
+        objects: &Find,
+        buf: &mut Vec<u8>,
+        out: &mut Vec<Item<'index>>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>>
+    where
+        Find: gix_object::Find,
+    {
+        let entries = objects
+            .find_tree_iter(tree, buf)?
+            .map(|entry| entry.map(|entry| (entry.mode, BString::from(entry.filename), entry.oid.to_owned())))
+            .collect::<Result<Vec<_>, _>>()?;
+        for (mode, filename, id) in entries {
+            let prefix_len = prefix.len();
+            if !prefix.is_empty() {
+                prefix.push_byte(b'/');
+            }
+            prefix.push_str(&filename);
+            if mode.is_tree() {
+                expand_tree(&id, prefix, index, objects, buf, out)?;
+            } else {
+                out.push(Item {
+                    location: Cow::Owned(prefix.clone()),
+                    index,
+                    entry_mode: mode.into(),
+                    id,
+                });
+            }
+            prefix.truncate(prefix_len);
+        }
+        Ok(())
+    }
+
+    fn is_skipped_by_flags(entry: &gix_index::Entry) -> bool {
+        entry.stage_raw() != 0 || entry.flags.contains(gix_index::entry::Flags::INTENT_TO_ADD)
+    }
+}
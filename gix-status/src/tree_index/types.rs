@@ -0,0 +1,257 @@
+use bstr::BStr;
+use std::borrow::Cow;
+use std::sync::atomic::AtomicBool;
+
+/// The error returned by [`tree_index()`](crate::tree_index()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The operation was interrupted")]
+    Interrupted,
+    #[error("Could not read the tree of the sparse directory at '{rela_path}'")]
+    ExpandSparseDirectory {
+        rela_path: bstr::BString,
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[error(transparent)]
+    RewriteTracker(#[from] gix_diff::rewrites::tracker::emit::Error),
+}
+
+/// Options for use in [`tree_index()`](crate::tree_index()).
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct Options {
+    /// If `Some(_)`, additions and deletions will be matched up to find renames and copies, which are then
+    /// provided as [`Change::Rewrite`].
+    ///
+    /// Note that this requires all changes to be collected first, so they are emitted once the comparison is complete,
+    /// sorted by their (destination) path.
+    pub rewrites: Option<gix_diff::Rewrites>,
+}
+
+/// The context for [`tree_index()`](crate::tree_index()).
+pub struct Context<'a> {
+    /// The pathspec to limit the amount of paths that are compared. Can be empty to allow all paths.
+    ///
+    /// Note that these are expected to have a [common_prefix()](gix_pathspec::Search::common_prefix()) according
+    /// to the prefix of the repository to efficiently limit the scope of the paths we process.
+    pub pathspec: gix_pathspec::Search,
+    /// A cache of blobs to diff for similarity checks during rewrite tracking.
+    ///
+    /// Its attribute stack is also used to match pathspecs with attributes, so it should be configured to read
+    /// `.gitattributes` from the index.
+    pub resource_cache: gix_diff::blob::Platform,
+    /// A flag to query to learn if cancellation is requested.
+    pub should_interrupt: &'a AtomicBool,
+}
+
+/// Provide additional information collected during the runtime of [`tree_index()`](crate::tree_index()).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Outcome {
+    /// The amount of entries that were skipped due to exclusion by *pathspecs*.
+    pub entries_skipped_by_pathspec: usize,
+    /// The amount of entries that were skipped as they are conflicting or merely intended to be added.
+    pub entries_skipped_by_entry_flags: usize,
+    /// The amount of sparse directory entries that had to be expanded into their contents as they differed.
+    pub sparse_directories_expanded: usize,
+    /// The result of the rewrite operation, if [rewrites were configured](Options::rewrites).
+    pub rewrites: Option<gix_diff::rewrites::Outcome>,
+}
+
+/// A change between the entries of two index states, typically an index created from the `HEAD` tree, the *left-hand side*,
+/// and the actual index, the *right-hand side*.
+///
+/// Each `index` field refers to the position of the entry in [`entries()`](gix_index::State::entries()) of the respective side.
+/// If the entry was obtained from a sparse directory, it refers to the sparse directory entry instead.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change<'index> {
+    /// An entry exists only on the right-hand side.
+    Addition {
+        /// The repository-relative path of the entry.
+        location: Cow<'index, BStr>,
+        /// The position of the entry in the right-hand side.
+        index: usize,
+        /// The mode of the added entry.
+        entry_mode: gix_index::entry::Mode,
+        /// The object id of the added entry.
+        id: gix_hash::ObjectId,
+    },
+    /// An entry exists only on the left-hand side.
+    Deletion {
+        /// The repository-relative path of the entry.
+        location: Cow<'index, BStr>,
+        /// The position of the entry in the left-hand side.
+        index: usize,
+        /// The mode of the deleted entry.
+        entry_mode: gix_index::entry::Mode,
+        /// The object id of the deleted entry.
+        id: gix_hash::ObjectId,
+    },
+    /// An entry exists on both sides, but its mode or id differs.
+    Modification {
+        /// The repository-relative path of the entry.
+        location: Cow<'index, BStr>,
+        /// The position of the entry in the left-hand side.
+        previous_index: usize,
+        /// The mode of the entry on the left-hand side.
+        previous_entry_mode: gix_index::entry::Mode,
+        /// The object id of the entry on the left-hand side.
+        previous_id: gix_hash::ObjectId,
+        /// The position of the entry in the right-hand side.
+        index: usize,
+        /// The mode of the entry on the right-hand side.
+        entry_mode: gix_index::entry::Mode,
+        /// The object id of the entry on the right-hand side.
+        id: gix_hash::ObjectId,
+    },
+    /// An entry of the left-hand side was renamed or copied to a new location on the right-hand side,
+    /// possibly with modifications.
+    Rewrite {
+        /// The repository-relative path of the source of the rewrite.
+        source_location: Cow<'index, BStr>,
+        /// The position of the source in the left-hand side.
+        source_index: usize,
+        /// The mode of the source.
+        source_entry_mode: gix_index::entry::Mode,
+        /// The object id of the source.
+        source_id: gix_hash::ObjectId,
+        /// The repository-relative path of the destination of the rewrite.
+        location: Cow<'index, BStr>,
+        /// The position of the destination in the right-hand side.
+        index: usize,
+        /// The mode of the destination.
+        entry_mode: gix_index::entry::Mode,
+        /// The object id of the destination.
+        id: gix_hash::ObjectId,
+        /// It's `None` if `source_id` is equal to `id`, as identity made an actual diff computation unnecessary.
+        /// Otherwise, it's `Some(stats)` to indicate how similar both entries were.
+        diff: Option<gix_diff::blob::DiffLineStats>,
+        /// If true, this rewrite is created by copy, and `source_location` still exists.
+        /// Otherwise, it's a rename.
+        copy: bool,
+    },
+}
+
+/// Access
+impl<'index> Change<'index> {
+    /// Return the repository-relative path of the entry on the right-hand side, or of the left-hand side for deletions.
+    pub fn location(&self) -> &BStr {
+        match self {
+            Change::Addition { location, .. }
+            | Change::Deletion { location, .. }
+            | Change::Modification { location, .. }
+            | Change::Rewrite { location, .. } => location.as_ref(),
+        }
+    }
+
+    /// Return the repository-relative path of the source of a rewrite, or `None` if this isn't a rewrite.
+    pub fn source_location(&self) -> Option<&BStr> {
+        match self {
+            Change::Rewrite { source_location, .. } => Some(source_location.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// Return an owned version of this instance, which doesn't borrow from the index states anymore.
+    pub fn into_owned(self) -> Change<'static> {
+        match self {
+            Change::Addition {
+                location,
+                index,
+                entry_mode,
+                id,
+            } => Change::Addition {
+                location: Cow::Owned(location.into_owned()),
+                index,
+                entry_mode,
+                id,
+            },
+            Change::Deletion {
+                location,
+                index,
+                entry_mode,
+                id,
+            } => Change::Deletion {
+                location: Cow::Owned(location.into_owned()),
+                index,
+                entry_mode,
+                id,
+            },
+            Change::Modification {
+                location,
+                previous_index,
+                previous_entry_mode,
+                previous_id,
+                index,
+                entry_mode,
+                id,
+            } => Change::Modification {
+                location: Cow::Owned(location.into_owned()),
+                previous_index,
+                previous_entry_mode,
+                previous_id,
+                index,
+                entry_mode,
+                id,
+            },
+            Change::Rewrite {
+                source_location,
+                source_index,
+                source_entry_mode,
+                source_id,
+                location,
+                index,
+                entry_mode,
+                id,
+                diff,
+                copy,
+            } => Change::Rewrite {
+                source_location: Cow::Owned(source_location.into_owned()),
+                source_index,
+                source_entry_mode,
+                source_id,
+                location: Cow::Owned(location.into_owned()),
+                index,
+                entry_mode,
+                id,
+                diff,
+                copy,
+            },
+        }
+    }
+}
+
+impl gix_diff::rewrites::tracker::Change for Change<'_> {
+    fn id(&self) -> &gix_hash::oid {
+        match self {
+            Change::Addition { id, .. }
+            | Change::Deletion { id, .. }
+            | Change::Modification { id, .. }
+            | Change::Rewrite { id, .. } => id,
+        }
+    }
+
+    fn kind(&self) -> gix_diff::rewrites::tracker::ChangeKind {
+        use gix_diff::rewrites::tracker::ChangeKind;
+        match self {
+            Change::Addition { .. } => ChangeKind::Addition,
+            Change::Deletion { .. } => ChangeKind::Deletion,
+            Change::Modification { .. } => ChangeKind::Modification,
+            Change::Rewrite { .. } => unreachable!("BUG: rewrites are never given to the tracker"),
+        }
+    }
+
+    fn entry_mode(&self) -> gix_object::tree::EntryMode {
+        match self {
+            Change::Addition { entry_mode, .. }
+            | Change::Deletion { entry_mode, .. }
+            | Change::Modification { entry_mode, .. }
+            | Change::Rewrite { entry_mode, .. } => entry_mode
+                .to_tree_entry_mode()
+                .unwrap_or(gix_object::tree::EntryKind::Blob.into()),
+        }
+    }
+
+    fn id_and_entry_mode(&self) -> (&gix_hash::oid, gix_object::tree::EntryMode) {
+        (self.id(), self.entry_mode())
+    }
+}
@@ -0,0 +1,53 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q staged-changes
+(cd staged-changes
+  echo "content" > modified
+  echo "content" > deleted
+  echo "content" > mode-change
+  seq 1 10 > renamed-from
+  seq 20 30 > renamed-with-change-from
+  mkdir dir
+  echo "other content" > dir/unchanged
+  echo "content" > dir/modified
+
+  git add -A
+  git commit -q -m "Commit"
+
+  echo "change" >> modified
+  echo "change" >> dir/modified
+  git rm -q deleted
+  chmod +x mode-change
+  git mv renamed-from renamed-to
+  git mv renamed-with-change-from renamed-with-change-to
+  echo 31 >> renamed-with-change-to
+  echo "new" > added
+  git add -A
+  echo "intended" > intent-to-add
+  git add -N intent-to-add
+
+  git diff --cached --name-status --no-renames HEAD > baseline-no-renames
+  git diff --cached --name-status -M HEAD > baseline-renames
+)
+
+git init -q sparse
+(cd sparse
+  mkdir in-cone out-of-cone
+  echo "content" > in-cone/file
+  echo "content" > out-of-cone/file
+  echo "content" > out-of-cone/other-file
+  echo "content" > top-level
+  git add -A
+  git commit -q -m "c1"
+
+  echo "change" >> in-cone/file
+  echo "change" >> out-of-cone/file
+  git commit -q -am "c2"
+
+  git config index.sparse true
+  git sparse-checkout set --cone in-cone
+  git reset -q --soft HEAD~1
+
+  git diff --cached --name-status --no-renames HEAD > baseline-no-renames
+)
@@ -0,0 +1,144 @@
+use crate::status::fixture_path;
+use bstr::ByteSlice;
+use gix_diff::blob::pipeline::WorktreeRoots;
+use gix_diff::tree::visit::Action;
+use gix_status::tree_index::{Change, Context, Options, Outcome};
+
+#[test]
+fn staged_changes_match_git() {
+    let (actual, out) = fixture_filtered("staged-changes", &[], None);
+    assert_eq!(actual, baseline("staged-changes", "baseline-no-renames"));
+    assert_eq!(
+        out,
+        Outcome {
+            entries_skipped_by_pathspec: 0,
+            entries_skipped_by_entry_flags: 1,
+            sparse_directories_expanded: 0,
+            rewrites: None,
+        },
+        "the intent-to-add entry is skipped"
+    );
+}
+
+#[test]
+fn staged_changes_with_renames_match_git() {
+    let (actual, out) = fixture_filtered("staged-changes", &[], Some(Default::default()));
+    assert_eq!(actual, baseline("staged-changes", "baseline-renames"));
+    assert_eq!(
+        out.rewrites.expect("configured").num_similarity_checks,
+        7,
+        "deletions and additions are compared with each other, after exact renames were paired up"
+    );
+}
+
+#[test]
+fn pathspecs_limit_the_comparison() {
+    let (actual, out) = fixture_filtered("staged-changes", &["dir/"], None);
+    assert_eq!(actual, ["M\tdir/modified"]);
+    assert_eq!(
+        out.entries_skipped_by_pathspec, 0,
+        "the common prefix narrows down entries"
+    );
+
+    let (actual, out) = fixture_filtered("staged-changes", &["*modified", ":!dir"], None);
+    assert_eq!(actual, ["M\tmodified"]);
+    assert_eq!(
+        out.entries_skipped_by_pathspec, 12,
+        "all but one entry on each side are excluded"
+    );
+}
+
+#[test]
+fn sparse_directories_are_skipped_if_unchanged_and_expanded_otherwise() {
+    let (actual, out) = fixture_filtered("sparse", &[], None);
+    assert_eq!(actual, baseline("sparse", "baseline-no-renames"));
+    assert_eq!(
+        out.sparse_directories_expanded, 1,
+        "only the changed sparse directory is expanded"
+    );
+}
+
+fn baseline(subdir: &str, name: &str) -> Vec<String> {
+    let baseline = std::fs::read(fixture_path("status_tree_index.sh").join(subdir).join(name)).unwrap();
+    baseline
+        .lines()
+        .map(|line| {
+            let line = line.to_str().unwrap();
+            match line.strip_prefix('R') {
+                // Our similarity scores don't match the ones of git.
+                Some(rest) => format!("R{}", &rest[rest.find('\t').unwrap()..]),
+                None => line.to_owned(),
+            }
+        })
+        .collect()
+}
+
+fn fixture_filtered(subdir: &str, pathspecs: &[&str], rewrites: Option<gix_diff::Rewrites>) -> (Vec<String>, Outcome) {
+    let worktree = fixture_path("status_tree_index.sh").join(subdir);
+    let git_dir = worktree.join(".git");
+    let index = gix_index::File::at(git_dir.join("index"), gix_hash::Kind::Sha1, false, Default::default()).unwrap();
+    let objects = gix_odb::at(git_dir.join("objects")).unwrap().into_arc().unwrap();
+    let head_tree = {
+        use gix_object::FindExt;
+        let head_ref = std::fs::read(git_dir.join("HEAD")).unwrap();
+        let head_ref = head_ref.trim().strip_prefix(b"ref: ").expect("symbolic HEAD");
+        let head = std::fs::read(git_dir.join(head_ref.to_path().unwrap())).unwrap();
+        let head = gix_hash::ObjectId::from_hex(head.trim()).unwrap();
+        objects.find_commit(&head, &mut Vec::new()).unwrap().tree()
+    };
+    let head_index = gix_index::State::from_tree(&head_tree, &objects, Default::default()).unwrap();
+
+    let search = gix_pathspec::Search::from_specs(
+        crate::status::index_as_worktree::to_pathspecs(pathspecs),
+        None,
+        std::path::Path::new(""),
+    )
+    .expect("valid specs can be normalized");
+    let stack = gix_worktree::Stack::from_state_and_ignore_case(
+        worktree.clone(),
+        false,
+        gix_worktree::stack::State::AttributesStack(Default::default()),
+        &index,
+        index.path_backing(),
+    );
+    let resource_cache = gix_diff::blob::Platform::new(
+        Default::default(),
+        gix_diff::blob::Pipeline::new(
+            WorktreeRoots::default(),
+            gix_filter::Pipeline::new(Default::default(), Default::default()),
+            vec![],
+            Default::default(),
+        ),
+        gix_diff::blob::pipeline::Mode::ToGit,
+        stack,
+    );
+
+    let mut actual = Vec::new();
+    let out = gix_status::tree_index(
+        &head_index,
+        &index,
+        |change| {
+            actual.push(match change {
+                Change::Addition { location, .. } => format!("A\t{location}"),
+                Change::Deletion { location, .. } => format!("D\t{location}"),
+                Change::Modification { location, .. } => format!("M\t{location}"),
+                Change::Rewrite {
+                    source_location,
+                    location,
+                    copy,
+                    ..
+                } => format!("{}\t{source_location}\t{location}", if copy { "C" } else { "R" }),
+            });
+            Action::Continue
+        },
+        objects,
+        Context {
+            pathspec: search,
+            resource_cache,
+            should_interrupt: &Default::default(),
+        },
+        Options { rewrites },
+    )
+    .unwrap();
+    (actual, out)
+}
@@ -7,7 +7,10 @@ pub(crate) mod function {
     /// A general purpose connector connecting to a repository identified by the given `url`.
     ///
     /// This includes connections to
-    /// [git daemons][crate::client::git::connect()] only at the moment.
+    /// [local repositories][crate::client::file::connect()],
+    /// [git daemons][crate::client::git::Connection::new_tcp()],
+    /// [repositories over ssh][crate::client::native_ssh::connect()] if the `native-ssh` feature is enabled,
+    /// and if compiled in connections to [git repositories over https][crate::client::http::connect()].
     ///
     /// Use `options` to further control specifics of the transport resulting from the connection.
     pub async fn connect<Url, E>(
@@ -20,6 +23,18 @@ pub(crate) mod function {
     {
         let mut url = url.try_into().map_err(gix_url::parse::Error::from)?;
         Ok(match url.scheme {
+            gix_url::Scheme::File => {
+                if url.user().is_some() || url.password().is_some() || url.host().is_some() || url.port.is_some() {
+                    return Err(Error::UnsupportedUrlTokens {
+                        url: url.to_bstring(),
+                        scheme: url.scheme,
+                    });
+                }
+                Box::new(
+                    crate::client::file::connect(url.path, options.version, options.trace)
+                        .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?,
+                )
+            }
             gix_url::Scheme::Git => {
                 if url.user().is_some() {
                     return Err(Error::UnsupportedUrlTokens {
@@ -40,6 +55,22 @@ pub(crate) mod function {
                     .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?,
                 )
             }
+            #[cfg(not(feature = "async-http-client-reqwest"))]
+            gix_url::Scheme::Https | gix_url::Scheme::Http => return Err(Error::CompiledWithoutHttp(url.scheme)),
+            #[cfg(feature = "async-http-client-reqwest")]
+            gix_url::Scheme::Https | gix_url::Scheme::Http => {
+                Box::new(crate::client::http::connect(url, options.version, options.trace))
+            }
+            #[cfg(feature = "native-ssh")]
+            gix_url::Scheme::Ssh => Box::new(
+                crate::client::native_ssh::connect(
+                    url,
+                    options.version,
+                    options.native_ssh.unwrap_or_default(),
+                    options.trace,
+                )
+                .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?,
+            ),
             scheme => return Err(Error::UnsupportedScheme(scheme)),
         })
     }
@@ -0,0 +1,144 @@
+use std::{
+    any::Any,
+    borrow::Cow,
+    error::Error,
+    process::{self, Stdio},
+};
+
+use async_trait::async_trait;
+use blocking::Unblock;
+use bstr::{BStr, BString, ByteSlice};
+
+use crate::{
+    client::{
+        self, git, non_io_types::file::ENV_VARS_TO_REMOVE, MessageKind, RequestWriter, SetServiceResponse, WriteMode,
+    },
+    Protocol, Service,
+};
+
+/// A utility to spawn a `git` process on the local machine to actually transmit data.
+///
+/// The standard input and output of the process are driven on a thread-pool, so the transport works with any async runtime.
+/// It can only be instantiated using the local [`connect()`].
+pub struct SpawnProcessOnDemand {
+    desired_version: Protocol,
+    url: gix_url::Url,
+    path: BString,
+    /// The environment variables to set in the invoked command.
+    envs: Vec<(&'static str, String)>,
+    connection: Option<git::Connection<Unblock<process::ChildStdout>, Unblock<process::ChildStdin>>>,
+    child: Option<process::Child>,
+    trace: bool,
+}
+
+impl SpawnProcessOnDemand {
+    fn new_local(path: BString, version: Protocol, trace: bool) -> SpawnProcessOnDemand {
+        SpawnProcessOnDemand {
+            url: gix_url::Url::from_parts(gix_url::Scheme::File, None, None, None, None, path.clone(), true)
+                .expect("valid url"),
+            path,
+            envs: if version != Protocol::V1 {
+                vec![("GIT_PROTOCOL", format!("version={}", version as usize))]
+            } else {
+                Vec::new()
+            },
+            child: None,
+            connection: None,
+            desired_version: version,
+            trace,
+        }
+    }
+}
+
+impl client::TransportWithoutIO for SpawnProcessOnDemand {
+    fn request(
+        &mut self,
+        write_mode: WriteMode,
+        on_into_read: MessageKind,
+        trace: bool,
+    ) -> Result<RequestWriter<'_>, client::Error> {
+        self.connection
+            .as_mut()
+            .expect("handshake() to have been called first")
+            .request(write_mode, on_into_read, trace)
+    }
+
+    fn to_url(&self) -> Cow<'_, BStr> {
+        Cow::Owned(self.url.to_bstring())
+    }
+
+    fn connection_persists_across_multiple_requests(&self) -> bool {
+        true
+    }
+
+    fn configure(&mut self, _config: &dyn Any) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
+        Ok(())
+    }
+}
+
+impl Drop for SpawnProcessOnDemand {
+    fn drop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            // The child process may still be running at this point if the operation failed or was interrupted,
+            // so kill it before waiting.
+            child.kill().ok();
+            child.wait().ok();
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl client::Transport for SpawnProcessOnDemand {
+    async fn handshake<'a>(
+        &mut self,
+        service: Service,
+        extra_parameters: &'a [(&'a str, Option<&'a str>)],
+    ) -> Result<SetServiceResponse<'_>, client::Error> {
+        if self.path.trim().first() == Some(&b'-') {
+            return Err(client::Error::AmbiguousPath {
+                path: self.path.clone(),
+            });
+        }
+        let mut cmd = gix_command::prepare(service.as_str()).stderr(Stdio::null());
+        cmd.stdin = Stdio::piped();
+        cmd.stdout = Stdio::piped();
+        cmd.args.push(self.path.to_os_str_lossy().into_owned());
+
+        let mut cmd = std::process::Command::from(cmd);
+        for env_to_remove in ENV_VARS_TO_REMOVE {
+            cmd.env_remove(env_to_remove);
+        }
+        cmd.envs(std::mem::take(&mut self.envs));
+
+        gix_features::trace::debug!(command = ?cmd, "gix_transport::SpawnProcessOnDemand");
+        let mut child = cmd.spawn().map_err(|err| client::Error::InvokeProgram {
+            source: err,
+            command: service.as_str().into(),
+        })?;
+        self.connection = Some(git::Connection::new_for_spawned_process(
+            Unblock::new(child.stdout.take().expect("stdout configured")),
+            Unblock::new(child.stdin.take().expect("stdin configured")),
+            self.desired_version,
+            self.path.clone(),
+            self.trace,
+        ));
+        self.child = Some(child);
+        self.connection
+            .as_mut()
+            .expect("connection to be there right after setting it")
+            .handshake(service, extra_parameters)
+            .await
+    }
+}
+
+/// Connect to a locally readable repository at `path` using the given `desired_version`.
+/// If `trace` is `true`, all packetlines received or sent will be passed to the facilities of the `gix-trace` crate.
+///
+/// This will spawn a `git` process locally.
+pub fn connect(
+    path: impl Into<BString>,
+    desired_version: Protocol,
+    trace: bool,
+) -> Result<SpawnProcessOnDemand, std::convert::Infallible> {
+    Ok(SpawnProcessOnDemand::new_local(path.into(), desired_version, trace))
+}
@@ -0,0 +1,32 @@
+use std::sync::{atomic::AtomicBool, Arc, Mutex};
+
+/// An implementation for HTTP requests via the async client of `reqwest`.
+pub struct Remote {
+    /// The client to perform requests with, created on first use.
+    client: Option<reqwest::Client>,
+    /// If `true`, the client may follow redirects for the next request.
+    allow_redirects: Arc<AtomicBool>,
+    /// How to handle redirects for the next request, as initialized from the configuration of the first request.
+    follow: Option<crate::client::http::options::FollowRedirects>,
+    /// The base url to use instead of the one passed by the caller, after a redirect was followed.
+    redirected_base_url: Arc<Mutex<Option<String>>>,
+    /// A mechanism for configuring the remote.
+    config: crate::client::http::Options,
+}
+
+/// A function to configure a single request prior to sending it, support most complex configuration beyond what's possible with
+/// basic `git` http configuration.
+pub type ConfigureRequestFn = dyn FnMut(&mut reqwest::Request) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>>
+    + Send
+    + Sync
+    + 'static;
+
+/// Options to configure the reqwest HTTP handler.
+#[derive(Default)]
+pub struct Options {
+    /// A function to configure the request that is about to be made.
+    pub configure_request: Option<Box<ConfigureRequestFn>>,
+}
+
+///
+pub mod remote;
@@ -0,0 +1,473 @@
+use std::{
+    any::Any,
+    future::Future,
+    io,
+    pin::Pin,
+    str::FromStr,
+    sync::{atomic, Arc, Mutex},
+    task::{ready, Context, Poll},
+};
+
+use async_trait::async_trait;
+use futures_lite::{Stream, StreamExt};
+
+use crate::client::http::{self, options::FollowRedirects, redirect, reqwest::Remote, PostBodyDataKind};
+
+/// The error returned by the 'remote' helper, a purely internal construct to perform http requests.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Reqwest(#[from] reqwest::Error),
+    #[error("Request configuration failed")]
+    ConfigureRequest(#[from] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    Redirect(#[from] redirect::Error),
+}
+
+impl crate::IsSpuriousError for Error {
+    fn is_spurious(&self) -> bool {
+        match self {
+            Error::Reqwest(err) => {
+                err.is_timeout() || err.is_connect() || err.status().map_or(false, |status| status.is_server_error())
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Default for Remote {
+    fn default() -> Self {
+        Remote {
+            client: None,
+            allow_redirects: Arc::new(atomic::AtomicBool::new(false)),
+            follow: None,
+            redirected_base_url: Default::default(),
+            config: http::Options::default(),
+        }
+    }
+}
+
+/// utilities
+impl Remote {
+    fn client(&mut self) -> Result<reqwest::Client, http::Error> {
+        if let Some(client) = &self.client {
+            return Ok(client.clone());
+        }
+        let allow_redirects = self.allow_redirects.clone();
+        let client = reqwest::ClientBuilder::new()
+            .connect_timeout(std::time::Duration::from_secs(20))
+            .http1_title_case_headers()
+            .redirect(reqwest::redirect::Policy::custom(move |attempt| {
+                if allow_redirects.load(atomic::Ordering::Relaxed) {
+                    let curr_url = attempt.url();
+                    let prev_urls = attempt.previous();
+
+                    match prev_urls.first() {
+                        Some(prev_url) if prev_url.host_str() != curr_url.host_str() => {
+                            // git does not want to be redirected to a different host.
+                            attempt.stop()
+                        }
+                        _ => {
+                            // emulate default git behaviour which relies on curl default behaviour apparently.
+                            const CURL_DEFAULT_REDIRS: usize = 50;
+                            if prev_urls.len() >= CURL_DEFAULT_REDIRS {
+                                attempt.error("too many redirects")
+                            } else {
+                                attempt.follow()
+                            }
+                        }
+                    }
+                } else {
+                    attempt.stop()
+                }
+            }))
+            .build()
+            .map_err(|err| http::Error::InitHttpClient {
+                source: Box::new(Error::from(err)),
+            })?;
+        Ok(self.client.insert(client).clone())
+    }
+
+    fn make_request(
+        &mut self,
+        url: &str,
+        base_url: &str,
+        headers: impl IntoIterator<Item = impl AsRef<str>>,
+        upload_body_kind: Option<PostBodyDataKind>,
+    ) -> Result<http::PostResponse<Headers, Body, PostBody>, http::Error> {
+        let mut header_map = reqwest::header::HeaderMap::new();
+        for header_line in headers {
+            let header_line = header_line.as_ref();
+            let colon_pos = header_line
+                .find(':')
+                .expect("header line must contain a colon to separate key and value");
+            let header_name = &header_line[..colon_pos];
+            let value = &header_line[colon_pos + 1..];
+
+            match reqwest::header::HeaderName::from_str(header_name)
+                .ok()
+                .zip(reqwest::header::HeaderValue::try_from(value.trim()).ok())
+            {
+                Some((key, val)) => header_map.insert(key, val),
+                None => continue,
+            };
+        }
+
+        let client = self.client()?;
+        let effective_url = redirect::swap_tails(
+            self.redirected_base_url
+                .lock()
+                .expect("no panic while holding the lock")
+                .as_deref(),
+            base_url,
+            url.to_owned(),
+        );
+        let builder = if upload_body_kind.is_some() {
+            client.post(&effective_url)
+        } else {
+            client.get(&effective_url)
+        }
+        .headers(header_map);
+
+        let follow = self.follow.get_or_insert(self.config.follow_redirects);
+        self.allow_redirects.store(
+            matches!(follow, FollowRedirects::Initial | FollowRedirects::All),
+            atomic::Ordering::Relaxed,
+        );
+        if *follow == FollowRedirects::Initial {
+            *follow = FollowRedirects::None;
+        }
+
+        let shared = Arc::new(Mutex::new(Shared {
+            post_body: upload_body_kind.map(|_| Vec::with_capacity(512)),
+            state: State::Pending(Box::new(Request {
+                client,
+                builder,
+                url: url.to_owned(),
+                base_url: base_url.to_owned(),
+                effective_url,
+                redirected_base_url: self.redirected_base_url.clone(),
+                backend: self.config.backend.clone(),
+            })),
+        }));
+        Ok(http::PostResponse {
+            post_body: PostBody { shared: shared.clone() },
+            headers: Headers {
+                shared: shared.clone(),
+                buf: None,
+                pos: 0,
+            },
+            body: Body {
+                shared,
+                stream: None,
+                buf: Vec::new(),
+                pos: 0,
+            },
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl http::Http for Remote {
+    type Headers = Headers;
+    type ResponseBody = Body;
+    type PostBody = PostBody;
+
+    async fn get(
+        &mut self,
+        url: &str,
+        base_url: &str,
+        headers: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> Result<http::GetResponse<Self::Headers, Self::ResponseBody>, http::Error> {
+        self.make_request(url, base_url, headers, None).map(Into::into)
+    }
+
+    fn post(
+        &mut self,
+        url: &str,
+        base_url: &str,
+        headers: impl IntoIterator<Item = impl AsRef<str>>,
+        post_body_kind: PostBodyDataKind,
+    ) -> Result<http::PostResponse<Self::Headers, Self::ResponseBody, Self::PostBody>, http::Error> {
+        self.make_request(url, base_url, headers, Some(post_body_kind))
+    }
+
+    fn configure(&mut self, config: &dyn Any) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+        if let Some(config) = config.downcast_ref::<http::Options>() {
+            self.config = config.clone();
+        }
+        Ok(())
+    }
+}
+
+/// A request that wasn't sent yet.
+struct Request {
+    client: reqwest::Client,
+    builder: reqwest::RequestBuilder,
+    url: String,
+    base_url: String,
+    effective_url: String,
+    redirected_base_url: Arc<Mutex<Option<String>>>,
+    backend: Option<Arc<Mutex<dyn Any + Send + Sync + 'static>>>,
+}
+
+impl Request {
+    /// Send the request along with `body`, and return the response along with its headers, one `name:value` pair per line.
+    async fn send(self, body: Option<Vec<u8>>) -> io::Result<(Vec<u8>, reqwest::Response)> {
+        fn other(err: impl Into<Error>) -> io::Error {
+            io::Error::new(io::ErrorKind::Other, err.into())
+        }
+        let Request {
+            client,
+            mut builder,
+            url,
+            base_url,
+            effective_url,
+            redirected_base_url,
+            backend,
+        } = self;
+        if let Some(body) = body {
+            builder = builder.body(body);
+        }
+        let mut req = builder.build().map_err(other)?;
+        if let Some(ref mut request_options) = backend.as_ref().and_then(|backend| backend.lock().ok()) {
+            if let Some(options) = request_options.downcast_mut::<super::Options>() {
+                if let Some(configure_request) = &mut options.configure_request {
+                    configure_request(&mut req).map_err(other)?;
+                }
+            }
+        }
+
+        let res = match client.execute(req).await.and_then(reqwest::Response::error_for_status) {
+            Ok(res) => res,
+            Err(err) => {
+                return Err(match err.status() {
+                    Some(status) => {
+                        let kind = if status == reqwest::StatusCode::UNAUTHORIZED {
+                            io::ErrorKind::PermissionDenied
+                        } else if status == reqwest::StatusCode::NOT_FOUND {
+                            io::ErrorKind::NotFound
+                        } else if status.is_server_error() {
+                            io::ErrorKind::ConnectionAborted
+                        } else {
+                            io::ErrorKind::Other
+                        };
+                        io::Error::new(kind, format!("Received HTTP status {}", status.as_str()))
+                    }
+                    None => other(err),
+                });
+            }
+        };
+
+        let actual_url = res.url().as_str();
+        if actual_url != effective_url.as_str() {
+            let base_url = redirect::base_url(actual_url, &base_url, url).map_err(other)?;
+            *redirected_base_url.lock().expect("no panic while holding the lock") = Some(base_url);
+        }
+
+        let mut headers = Vec::new();
+        for (name, value) in res.headers() {
+            headers.extend_from_slice(name.as_str().as_bytes());
+            headers.push(b':');
+            headers.extend_from_slice(value.as_bytes());
+            headers.push(b'\n');
+        }
+        Ok((headers, res))
+    }
+}
+
+type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+type ChunkStream = Pin<Box<dyn Stream<Item = io::Result<Vec<u8>>> + Send>>;
+
+enum State {
+    /// The request wasn't sent yet as the body to post may still be written.
+    Pending(Box<Request>),
+    /// The request was sent and we are waiting for the response.
+    Sending(BoxFuture<io::Result<(Vec<u8>, reqwest::Response)>>),
+    /// The response was received, and its parts are taken by their respective readers.
+    Received {
+        headers: Option<Vec<u8>>,
+        response: Option<reqwest::Response>,
+    },
+    /// The request failed, with the error handed to the first reader who asks for it.
+    Failed {
+        kind: io::ErrorKind,
+        message: String,
+        err: Option<io::Error>,
+    },
+}
+
+/// The state shared between the handles of a single request.
+struct Shared {
+    /// The body to post, if this is a `POST` request.
+    post_body: Option<Vec<u8>>,
+    state: State,
+}
+
+impl Shared {
+    /// Send the request if needed and wait for the response.
+    fn poll_response(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        loop {
+            match &mut self.state {
+                State::Pending(_) => {
+                    let State::Pending(request) = std::mem::replace(
+                        &mut self.state,
+                        State::Received {
+                            headers: None,
+                            response: None,
+                        },
+                    ) else {
+                        unreachable!("checked above")
+                    };
+                    self.state = State::Sending(Box::pin(request.send(self.post_body.take())));
+                }
+                State::Sending(fut) => {
+                    self.state = match ready!(fut.as_mut().poll(cx)) {
+                        Ok((headers, response)) => State::Received {
+                            headers: Some(headers),
+                            response: Some(response),
+                        },
+                        Err(err) => State::Failed {
+                            kind: err.kind(),
+                            message: err.to_string(),
+                            err: Some(err),
+                        },
+                    }
+                }
+                State::Received { .. } => return Poll::Ready(Ok(())),
+                State::Failed { kind, message, err } => {
+                    return Poll::Ready(Err(err
+                        .take()
+                        .unwrap_or_else(|| io::Error::new(*kind, message.clone()))))
+                }
+            }
+        }
+    }
+}
+
+fn lock(shared: &Mutex<Shared>) -> std::sync::MutexGuard<'_, Shared> {
+    shared.lock().expect("no panic while holding the lock")
+}
+
+/// The body to post to the server, which is held in memory until the request is sent.
+pub struct PostBody {
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl futures_io::AsyncWrite for PostBody {
+    fn poll_write(self: Pin<&mut Self>, _cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let shared = &mut *lock(&self.shared);
+        match (&mut shared.post_body, &shared.state) {
+            (Some(body), State::Pending(_)) => {
+                body.extend_from_slice(buf);
+                Poll::Ready(Ok(buf.len()))
+            }
+            _ => Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "Cannot write to the body of a request that was already sent",
+            ))),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// The headers of a response, one `name:value` pair per line, which sends the request when read for the first time.
+pub struct Headers {
+    shared: Arc<Mutex<Shared>>,
+    buf: Option<Vec<u8>>,
+    pos: usize,
+}
+
+impl futures_io::AsyncRead for Headers {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        let available = ready!(futures_io::AsyncBufRead::poll_fill_buf(self.as_mut(), cx))?;
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        futures_io::AsyncBufRead::consume(self, len);
+        Poll::Ready(Ok(len))
+    }
+}
+
+impl futures_io::AsyncBufRead for Headers {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+        if this.buf.is_none() {
+            let mut shared = lock(&this.shared);
+            ready!(shared.poll_response(cx))?;
+            this.buf = Some(match &mut shared.state {
+                State::Received { headers, .. } => headers.take().unwrap_or_default(),
+                _ => unreachable!("response was received"),
+            });
+        }
+        Poll::Ready(Ok(&this.buf.as_deref().expect("set above")[this.pos..]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.get_mut().pos += amt;
+    }
+}
+
+/// The body of a response, which sends the request when read for the first time.
+pub struct Body {
+    shared: Arc<Mutex<Shared>>,
+    stream: Option<ChunkStream>,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl futures_io::AsyncRead for Body {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        let available = ready!(futures_io::AsyncBufRead::poll_fill_buf(self.as_mut(), cx))?;
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        futures_io::AsyncBufRead::consume(self, len);
+        Poll::Ready(Ok(len))
+    }
+}
+
+impl futures_io::AsyncBufRead for Body {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+        if this.stream.is_none() {
+            let mut shared = lock(&this.shared);
+            ready!(shared.poll_response(cx))?;
+            let response = match &mut shared.state {
+                State::Received { response, .. } => response.take(),
+                _ => unreachable!("response was received"),
+            };
+            this.stream = Some(match response {
+                Some(response) => Box::pin(futures_lite::stream::unfold(Some(response), |response| async move {
+                    let mut response = response?;
+                    match response.chunk().await {
+                        Ok(Some(chunk)) => Some((Ok(chunk.into()), Some(response))),
+                        Ok(None) => None,
+                        Err(err) => Some((Err(io::Error::new(io::ErrorKind::Other, Error::from(err))), None)),
+                    }
+                })),
+                None => Box::pin(futures_lite::stream::empty()),
+            });
+        }
+        while this.pos == this.buf.len() {
+            match ready!(this.stream.as_mut().expect("set above").poll_next(cx)) {
+                Some(chunk) => {
+                    this.buf = chunk?;
+                    this.pos = 0;
+                }
+                None => break,
+            }
+        }
+        Poll::Ready(Ok(&this.buf[this.pos..]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.get_mut().pos += amt;
+    }
+}
@@ -1,3 +1,9 @@
+///
+pub mod file;
+///
+#[cfg(feature = "async-http-client")]
+pub mod http;
+
 mod bufread_ext;
 pub use bufread_ext::{ExtendedBufRead, HandleProgress, ReadlineBufRead};
 
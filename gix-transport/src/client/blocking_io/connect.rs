@@ -32,6 +32,16 @@ pub(crate) mod function {
                         .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?,
                 )
             }
+            #[cfg(feature = "native-ssh")]
+            gix_url::Scheme::Ssh if options.native_ssh.is_some() => Box::new({
+                crate::client::native_ssh::connect(
+                    url,
+                    options.version,
+                    options.native_ssh.expect("checked in guard"),
+                    options.trace,
+                )
+                .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?
+            }),
             gix_url::Scheme::Ssh => Box::new({
                 crate::client::blocking_io::ssh::connect(url, options.version, options.ssh, options.trace)
                     .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?
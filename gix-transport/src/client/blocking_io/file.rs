@@ -10,30 +10,13 @@ use std::{
 use bstr::{io::BufReadExt, BStr, BString, ByteSlice};
 
 use crate::{
-    client::{self, git, ssh, MessageKind, RequestWriter, SetServiceResponse, WriteMode},
+    client::{
+        self, git, non_io_types::file::ENV_VARS_TO_REMOVE, ssh, MessageKind, RequestWriter, SetServiceResponse,
+        WriteMode,
+    },
     Protocol, Service,
 };
 
-// from https://github.com/git/git/blob/20de7e7e4f4e9ae52e6cc7cfaa6469f186ddb0fa/environment.c#L115:L115
-const ENV_VARS_TO_REMOVE: &[&str] = &[
-    "GIT_ALTERNATE_OBJECT_DIRECTORIES",
-    "GIT_CONFIG",
-    "GIT_CONFIG_PARAMETERS",
-    "GIT_OBJECT_DIRECTORY",
-    "GIT_DIR",
-    "GIT_WORK_TREE",
-    "GIT_IMPLICIT_WORK_TREE",
-    "GIT_GRAFT_FILE",
-    "GIT_INDEX_FILE",
-    "GIT_NO_REPLACE_OBJECTS",
-    "GIT_REPLACE_REF_BASE",
-    "GIT_PREFIX",
-    "GIT_INTERNAL_SUPER_PREFIX",
-    "GIT_SHALLOW_FILE",
-    "GIT_COMMON_DIR",
-    "GIT_CONFIG_COUNT",
-];
-
 /// A utility to spawn a helper process to actually transmit data, possibly over `ssh`.
 ///
 /// It can only be instantiated using the local [`connect()`] or [ssh connect][crate::client::ssh::connect()].
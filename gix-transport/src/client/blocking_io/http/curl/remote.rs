@@ -9,13 +9,11 @@ use std::{
 use curl::easy::{Auth, Easy2};
 use gix_features::io::pipe;
 
-use crate::client::{
-    blocking_io::http::{self, curl::Error, redirect},
-    http::{
-        curl::curl_is_spurious,
-        options::{FollowRedirects, HttpVersion, ProxyAuthMethod, SslVersion},
-        traits::PostBodyDataKind,
-    },
+use crate::client::http::{
+    self,
+    curl::{curl_is_spurious, Error},
+    options::{FollowRedirects, HttpVersion, ProxyAuthMethod, SslVersion},
+    redirect, PostBodyDataKind,
 };
 
 enum StreamOrBuffer {
@@ -92,6 +90,8 @@ impl curl::easy::Handler for Handler {
                         .send(Err(io::Error::new(
                             if status == 401 {
                                 io::ErrorKind::PermissionDenied
+                            } else if status == 404 {
+                                io::ErrorKind::NotFound
                             } else if (500..600).contains(&status) {
                                 io::ErrorKind::ConnectionAborted
                             } else {
@@ -2,21 +2,19 @@ use std::{
     any::Any,
     borrow::Cow,
     io::{BufRead, Read},
-    path::PathBuf,
-    sync::{Arc, Mutex},
 };
 
 use base64::Engine;
-use bstr::BStr;
+use bstr::{BStr, ByteSlice};
 use gix_packetline::PacketLineRef;
-pub use traits::{Error, GetResponse, Http, PostBodyDataKind, PostResponse};
+pub use traits::Http;
 
 use crate::{
     client::{
         self,
         blocking_io::bufread_ext::ReadlineBufRead,
         capabilities,
-        http::options::{HttpVersion, SslVersionRangeInclusive},
+        http::{Error, GetResponse, PostResponse},
         Capabilities, ExtendedBufRead, HandleProgress, MessageKind, RequestWriter,
     },
     Protocol, Service,
@@ -38,180 +36,6 @@ pub mod reqwest;
 
 mod traits;
 
-///
-pub mod options {
-    /// A function to authenticate a URL.
-    pub type AuthenticateFn =
-        dyn FnMut(gix_credentials::helper::Action) -> gix_credentials::protocol::Result + Send + Sync;
-
-    /// Possible settings for the `http.followRedirects` configuration option.
-    #[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
-    pub enum FollowRedirects {
-        /// Follow only the first redirect request, most suitable for typical git requests.
-        #[default]
-        Initial,
-        /// Follow all redirect requests from the server unconditionally
-        All,
-        /// Follow no redirect request.
-        None,
-    }
-
-    /// The way to configure a proxy for authentication if a username is present in the configured proxy.
-    #[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
-    pub enum ProxyAuthMethod {
-        /// Automatically pick a suitable authentication method.
-        #[default]
-        AnyAuth,
-        ///HTTP basic authentication.
-        Basic,
-        /// Http digest authentication to prevent a password to be passed in clear text.
-        Digest,
-        /// GSS negotiate authentication.
-        Negotiate,
-        /// NTLM authentication
-        Ntlm,
-    }
-
-    /// Available SSL version numbers.
-    #[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd)]
-    #[allow(missing_docs)]
-    pub enum SslVersion {
-        /// The implementation default, which is unknown to this layer of abstraction.
-        Default,
-        TlsV1,
-        SslV2,
-        SslV3,
-        TlsV1_0,
-        TlsV1_1,
-        TlsV1_2,
-        TlsV1_3,
-    }
-
-    /// Available HTTP version numbers.
-    #[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd)]
-    #[allow(missing_docs)]
-    pub enum HttpVersion {
-        /// Equivalent to HTTP/1.1
-        V1_1,
-        /// Equivalent to HTTP/2
-        V2,
-    }
-
-    /// The desired range of acceptable SSL versions, or the single version to allow if both are set to the same value.
-    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
-    pub struct SslVersionRangeInclusive {
-        /// The smallest allowed ssl version to use.
-        pub min: SslVersion,
-        /// The highest allowed ssl version to use.
-        pub max: SslVersion,
-    }
-
-    impl SslVersionRangeInclusive {
-        /// Return `min` and `max` fields in the right order so `min` is smaller or equal to `max`.
-        pub fn min_max(&self) -> (SslVersion, SslVersion) {
-            if self.min > self.max {
-                (self.max, self.min)
-            } else {
-                (self.min, self.max)
-            }
-        }
-    }
-}
-
-/// Options to configure http requests.
-// TODO: testing most of these fields requires a lot of effort, unless special flags to introspect ongoing requests are added.
-#[derive(Clone)]
-pub struct Options {
-    /// Headers to be added to every request.
-    /// They are applied unconditionally and are expected to be valid as they occur in an HTTP request, like `header: value`, without newlines.
-    ///
-    /// Refers to `http.extraHeader` multi-var.
-    pub extra_headers: Vec<String>,
-    /// How to handle redirects.
-    ///
-    /// Refers to `http.followRedirects`.
-    pub follow_redirects: options::FollowRedirects,
-    /// Used in conjunction with `low_speed_time_seconds`, any non-0 value signals the amount of bytes per second at least to avoid
-    /// aborting the connection.
-    ///
-    /// Refers to `http.lowSpeedLimit`.
-    pub low_speed_limit_bytes_per_second: u32,
-    /// Used in conjunction with `low_speed_bytes_per_second`, any non-0 value signals the amount seconds the minimal amount
-    /// of bytes per second isn't reached.
-    ///
-    /// Refers to `http.lowSpeedTime`.
-    pub low_speed_time_seconds: u64,
-    /// A curl-style proxy declaration of the form `[protocol://][user[:password]@]proxyhost[:port]`.
-    ///
-    /// Note that an empty string means the proxy is disabled entirely.
-    /// Refers to `http.proxy`.
-    pub proxy: Option<String>,
-    /// The comma-separated list of hosts to not send through the `proxy`, or `*` to entirely disable all proxying.
-    pub no_proxy: Option<String>,
-    /// The way to authenticate against the proxy if the `proxy` field contains a username.
-    ///
-    /// Refers to `http.proxyAuthMethod`.
-    pub proxy_auth_method: options::ProxyAuthMethod,
-    /// If authentication is needed for the proxy as its URL contains a username, this method must be set to provide a password
-    /// for it before making the request, and to store it if the connection succeeds.
-    pub proxy_authenticate: Option<(
-        gix_credentials::helper::Action,
-        Arc<std::sync::Mutex<options::AuthenticateFn>>,
-    )>,
-    /// The `HTTP` `USER_AGENT` string presented to an `HTTP` server, notably not the user agent present to the `git` server.
-    ///
-    /// If not overridden, it defaults to the user agent provided by `curl`, which is a deviation from how `git` handles this.
-    /// Thus it's expected from the callers to set it to their application, or use higher-level crates which make it easy to do this
-    /// more correctly.
-    ///
-    /// Using the correct user-agent might affect how the server treats the request.
-    ///
-    /// Refers to `http.userAgent`.
-    pub user_agent: Option<String>,
-    /// The amount of time we wait until aborting a connection attempt.
-    ///
-    /// If `None`, this typically defaults to 2 minutes to 5 minutes.
-    /// Refers to `gitoxide.http.connectTimeout`.
-    pub connect_timeout: Option<std::time::Duration>,
-    /// If enabled, emit additional information about connections and possibly the data received or written.
-    pub verbose: bool,
-    /// If set, use this path to point to a file with CA certificates to verify peers.
-    pub ssl_ca_info: Option<PathBuf>,
-    /// The SSL version or version range to use, or `None` to let the TLS backend determine which versions are acceptable.
-    pub ssl_version: Option<SslVersionRangeInclusive>,
-    /// Controls whether to perform SSL identity verification or not. Turning this off is not recommended and can lead to
-    /// various security risks. An example where this may be needed is when an internal git server uses a self-signed
-    /// certificate and the user accepts the associated security risks.
-    pub ssl_verify: bool,
-    /// The HTTP version to enforce. If unset, it is implementation defined.
-    pub http_version: Option<HttpVersion>,
-    /// Backend specific options, if available.
-    pub backend: Option<Arc<Mutex<dyn Any + Send + Sync + 'static>>>,
-}
-
-impl Default for Options {
-    fn default() -> Self {
-        Options {
-            extra_headers: vec![],
-            follow_redirects: Default::default(),
-            low_speed_limit_bytes_per_second: 0,
-            low_speed_time_seconds: 0,
-            proxy: None,
-            no_proxy: None,
-            proxy_auth_method: Default::default(),
-            proxy_authenticate: None,
-            user_agent: None,
-            connect_timeout: None,
-            verbose: false,
-            ssl_ca_info: None,
-            ssl_version: None,
-            ssl_verify: true,
-            http_version: None,
-            backend: None,
-        }
-    }
-}
-
 /// The actual http client implementation, using curl
 #[cfg(feature = "http-client-curl")]
 pub type Impl = curl::Curl;
@@ -228,6 +52,8 @@ pub struct Transport<H: Http> {
     http: H,
     service: Option<Service>,
     line_provider: Option<gix_packetline::StreamingPeekableIter<H::ResponseBody>>,
+    /// The refs of a server that only supports the 'dumb' protocol, re-encoded as V1 advertisement.
+    dumb_refs: Option<gix_packetline::StreamingPeekableIter<std::io::Cursor<Vec<u8>>>>,
     identity: Option<gix_sec::identity::Account>,
     trace: bool,
 }
@@ -252,6 +78,7 @@ impl<H: Http> Transport<H> {
             service: None,
             http,
             line_provider: None,
+            dumb_refs: None,
             identity,
             trace,
         }
@@ -278,22 +105,71 @@ impl Transport<Impl> {
 
 impl<H: Http> Transport<H> {
     fn check_content_type(service: Service, kind: &str, headers: <H as Http>::Headers) -> Result<(), client::Error> {
-        let wanted_content_type = format!("application/x-{}-{}", service.as_str(), kind);
-        if !headers.lines().collect::<Result<Vec<_>, _>>()?.iter().any(|l| {
-            let mut tokens = l.split(':');
-            tokens.next().zip(tokens.next()).map_or(false, |(name, value)| {
-                name.eq_ignore_ascii_case("content-type") && value.trim() == wanted_content_type
-            })
-        }) {
-            return Err(client::Error::Http(Error::Detail {
-                description: format!(
-                    "Didn't find '{wanted_content_type}' header to indicate 'smart' protocol, and 'dumb' protocol is not supported."
-                ),
-            }));
+        let headers = headers.lines().collect::<Result<Vec<_>, _>>()?;
+        if !has_content_type(service, kind, &headers) {
+            return Err(missing_content_type(service, kind));
         }
         Ok(())
     }
 
+    /// Turn the `info/refs` file in `body` of a server without support for the 'smart' protocol into a V1 advertisement
+    /// that includes `HEAD`, to be able to fetch from it using [`DumbHttp`][client::DumbHttp].
+    fn handshake_dumb(
+        &mut self,
+        service: Service,
+        mut body: H::ResponseBody,
+    ) -> Result<client::SetServiceResponse<'_>, client::Error> {
+        let mut info_refs = Vec::new();
+        body.read_to_end(&mut info_refs)?;
+        let refs = ByteSlice::lines(info_refs.as_slice())
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                line.split_once_str("\t")
+                    .filter(|(hex, _)| is_hex_object_id(hex))
+                    .ok_or_else(|| missing_content_type(service, "advertisement"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut head_target = None;
+        if let Some(mut head) = client::DumbHttp::get_file(self, "HEAD")? {
+            let mut buf = Vec::new();
+            head.read_to_end(&mut buf)?;
+            head_target = buf.trim_end().strip_prefix(b"ref: ").map(|target| target.as_bstr().to_owned());
+        }
+        let head = head_target
+            .as_ref()
+            .and_then(|target| refs.iter().find(|(_, name)| name == target))
+            .map(|(hex, _)| (*hex, b"HEAD".as_slice()));
+
+        let mut advertisement = Vec::new();
+        for (hex, name) in head.into_iter().chain(refs.iter().copied()) {
+            let mut line = hex.to_vec();
+            line.push(b' ');
+            line.extend_from_slice(name);
+            line.push(b'\n');
+            gix_packetline::encode::data_to_write(&line, &mut advertisement)?;
+        }
+        gix_packetline::encode::flush_to_write(&mut advertisement)?;
+
+        let capabilities = Capabilities::from_v1_list(
+            head.and(head_target)
+                .map(|target| format!("symref=HEAD:{target}").into())
+                .unwrap_or_default(),
+        );
+        self.actual_version = Protocol::V1;
+        self.service = Some(service);
+        let refs = self.dumb_refs.insert(gix_packetline::StreamingPeekableIter::new(
+            std::io::Cursor::new(advertisement),
+            &[PacketLineRef::Flush],
+            self.trace,
+        ));
+        Ok(client::SetServiceResponse {
+            actual_protocol: Protocol::V1,
+            capabilities,
+            refs: Some(Box::new(refs.as_read())),
+        })
+    }
+
     #[allow(clippy::unnecessary_wraps, unknown_lints)]
     fn add_basic_auth_if_present(&self, headers: &mut Vec<Cow<'_, str>>) -> Result<(), client::Error> {
         if let Some(gix_sec::identity::Account { username, password }) = &self.identity {
@@ -312,6 +188,57 @@ impl<H: Http> Transport<H> {
     }
 }
 
+fn has_content_type(service: Service, kind: &str, headers: &[String]) -> bool {
+    let wanted_content_type = format!("application/x-{}-{}", service.as_str(), kind);
+    headers.iter().any(|l| {
+        let mut tokens = l.split(':');
+        tokens.next().zip(tokens.next()).map_or(false, |(name, value)| {
+            name.eq_ignore_ascii_case("content-type") && value.trim() == wanted_content_type
+        })
+    })
+}
+
+fn missing_content_type(service: Service, kind: &str) -> client::Error {
+    client::Error::Http(Error::Detail {
+        description: format!(
+            "Didn't find 'application/x-{}-{kind}' header to indicate 'smart' protocol, and the response isn't usable with the 'dumb' protocol either.",
+            service.as_str()
+        ),
+    })
+}
+
+fn is_hex_object_id(hex: &[u8]) -> bool {
+    matches!(hex.len(), 40 | 64) && hex.iter().all(u8::is_ascii_hexdigit)
+}
+
+/// Resolve `path` against the repository URL in `base` like a relative link, so `..` may leave the repository
+/// and a leading `/` starts at the root of the server. Complete URLs are returned as is.
+fn resolve_url(base: &str, path: &str) -> String {
+    if path.contains("://") {
+        return path.to_owned();
+    }
+    let path_start = base
+        .find("://")
+        .and_then(|scheme_end| base[scheme_end + 3..].find('/').map(|pos| scheme_end + 3 + pos))
+        .unwrap_or(base.len());
+    let (origin, base_path) = base.split_at(path_start);
+    let mut segments: Vec<_> = if path.starts_with('/') {
+        Vec::new()
+    } else {
+        base_path.split('/').filter(|s| !s.is_empty()).collect()
+    };
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    format!("{origin}/{}", segments.join("/"))
+}
+
 fn append_url(base: &str, suffix: &str) -> String {
     let mut buf = base.to_owned();
     if base.as_bytes().last() != Some(&b'/') {
@@ -334,6 +261,11 @@ impl<H: Http> client::TransportWithoutIO for Transport<H> {
         trace: bool,
     ) -> Result<RequestWriter<'_>, client::Error> {
         let service = self.service.expect("handshake() must have been called first");
+        if self.dumb_refs.is_some() {
+            return Err(client::Error::Http(Error::Detail {
+                description: "The server only supports the 'dumb' protocol which doesn't allow requests".into(),
+            }));
+        }
         let url = append_url(&self.url, service.as_str());
         let static_headers = &[
             Cow::Borrowed(self.user_agent_header),
@@ -425,7 +357,14 @@ impl<H: Http> client::Transport for Transport<H> {
         let GetResponse { headers, body } =
             self.http
                 .get(url.as_ref(), &self.url, static_headers.iter().chain(&dynamic_headers))?;
-        <Transport<H>>::check_content_type(service, "advertisement", headers)?;
+        let headers = headers.lines().collect::<Result<Vec<_>, _>>()?;
+        self.dumb_refs = None;
+        if !has_content_type(service, "advertisement", &headers) {
+            if service != Service::UploadPack {
+                return Err(missing_content_type(service, "advertisement"));
+            }
+            return self.handshake_dumb(service, body);
+        }
 
         let line_reader = self.line_provider.get_or_insert_with(|| {
             gix_packetline::StreamingPeekableIter::new(body, &[PacketLineRef::Flush], self.trace)
@@ -466,6 +405,28 @@ impl<H: Http> client::Transport for Transport<H> {
             refs,
         })
     }
+
+    fn dumb_http(&mut self) -> Option<&mut dyn client::DumbHttp> {
+        if self.dumb_refs.is_some() {
+            Some(self)
+        } else {
+            None
+        }
+    }
+}
+
+impl<H: Http> client::DumbHttp for Transport<H> {
+    fn get_file(&mut self, path: &str) -> Result<Option<Box<dyn BufRead + '_>>, client::Error> {
+        let url = resolve_url(&self.url, path);
+        let mut headers = vec![Cow::Borrowed(self.user_agent_header)];
+        self.add_basic_auth_if_present(&mut headers)?;
+        let GetResponse { headers, body } = self.http.get(&url, &self.url, &headers)?;
+        match headers.lines().collect::<Result<Vec<_>, _>>() {
+            Ok(_) => Ok(Some(Box::new(body))),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
 }
 
 struct HeadersThenBody<H: Http, B: Unpin> {
@@ -550,7 +511,3 @@ pub fn connect_http<H: Http>(http: H, url: gix_url::Url, desired_version: Protoc
 pub fn connect(url: gix_url::Url, desired_version: Protocol, trace: bool) -> Transport<Impl> {
     Transport::new(url, desired_version, trace)
 }
-
-///
-#[cfg(any(feature = "http-client-curl", feature = "http-client-reqwest"))]
-pub mod redirect;
@@ -7,7 +7,7 @@ use std::{
 
 use gix_features::io::pipe;
 
-use crate::client::http::{self, options::FollowRedirects, redirect, reqwest::Remote, traits::PostBodyDataKind};
+use crate::client::http::{self, options::FollowRedirects, redirect, reqwest::Remote, PostBodyDataKind};
 
 /// The error returned by the 'remote' helper, a purely internal construct to perform http requests.
 #[derive(Debug, thiserror::Error)]
@@ -145,6 +145,8 @@ impl Default for Remote {
                             Some(status) => {
                                 let kind = if status == reqwest::StatusCode::UNAUTHORIZED {
                                     std::io::ErrorKind::PermissionDenied
+                                } else if status == reqwest::StatusCode::NOT_FOUND {
+                                    std::io::ErrorKind::NotFound
                                 } else if status.is_server_error() {
                                     std::io::ErrorKind::ConnectionAborted
                                 } else {
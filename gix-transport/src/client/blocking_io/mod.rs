@@ -17,4 +17,4 @@ pub use request::RequestWriter;
 pub mod ssh;
 
 mod traits;
-pub use traits::{SetServiceResponse, Transport, TransportV2Ext};
+pub use traits::{DumbHttp, SetServiceResponse, Transport, TransportV2Ext};
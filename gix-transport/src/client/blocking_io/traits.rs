@@ -39,6 +39,24 @@ pub trait Transport: TransportWithoutIO {
         service: Service,
         extra_parameters: &'a [(&'a str, Option<&'a str>)],
     ) -> Result<SetServiceResponse<'_>, Error>;
+
+    /// Return access to the files of the remote repository if the [handshake][Transport::handshake()] revealed that
+    /// the server only supports the 'dumb' HTTP protocol, or `None` otherwise, which is the default.
+    ///
+    /// With such a server, [requests][TransportWithoutIO::request()] aren't possible, and objects have to be
+    /// obtained by downloading them one by one or as whole packs.
+    fn dumb_http(&mut self) -> Option<&mut dyn DumbHttp> {
+        None
+    }
+}
+
+/// Access to the files of a repository that is served by a plain web server, as used by the 'dumb' HTTP protocol.
+pub trait DumbHttp {
+    /// Download the file at `path`, which is relative to the URL of the repository, or return `None` if it doesn't exist.
+    ///
+    /// `path` may also be an absolute path on the same server, or a complete URL.
+    /// **Note that** the returned reader must be exhausted before making the next request.
+    fn get_file(&mut self, path: &str) -> Result<Option<Box<dyn std::io::BufRead + '_>>, Error>;
 }
 
 // Would be nice if the box implementation could auto-forward to all implemented traits.
@@ -50,6 +68,10 @@ impl<T: Transport + ?Sized> Transport for Box<T> {
     ) -> Result<SetServiceResponse<'_>, Error> {
         self.deref_mut().handshake(service, extra_parameters)
     }
+
+    fn dumb_http(&mut self) -> Option<&mut dyn DumbHttp> {
+        self.deref_mut().dumb_http()
+    }
 }
 
 impl<T: Transport + ?Sized> Transport for &mut T {
@@ -60,6 +82,10 @@ impl<T: Transport + ?Sized> Transport for &mut T {
     ) -> Result<SetServiceResponse<'_>, Error> {
         self.deref_mut().handshake(service, extra_parameters)
     }
+
+    fn dumb_http(&mut self) -> Option<&mut dyn DumbHttp> {
+        self.deref_mut().dumb_http()
+    }
 }
 
 /// An extension trait to add more methods to everything implementing [`Transport`].
@@ -126,6 +126,12 @@ impl Capabilities {
         })
     }
 
+    /// Create capabilities from the space-separated list in `data`, as it would follow the first ref in a V1 advertisement.
+    #[cfg(feature = "http-client")]
+    pub(crate) fn from_v1_list(data: BString) -> Capabilities {
+        Capabilities { data, value_sep: b' ' }
+    }
+
     /// Returns true of the given `feature` is mentioned in this list of capabilities.
     pub fn contains(&self, feature: &str) -> bool {
         self.capability(feature).is_some()
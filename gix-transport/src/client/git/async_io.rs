@@ -117,6 +117,23 @@ where
             mode,
         }
     }
+    pub(crate) fn new_for_spawned_process(
+        reader: R,
+        writer: W,
+        desired_version: Protocol,
+        repository_path: impl Into<BString>,
+        trace: bool,
+    ) -> Self {
+        Self::new(
+            reader,
+            writer,
+            desired_version,
+            repository_path,
+            None::<(&str, _)>,
+            git::ConnectMode::Process,
+            trace,
+        )
+    }
 }
 
 #[cfg(feature = "async-std")]
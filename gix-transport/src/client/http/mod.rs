@@ -0,0 +1,200 @@
+//! Types shared by the blocking and async implementations of the smart HTTP transport, along with the transport itself
+//! as selected by the `http-client` or `async-http-client` feature.
+use std::{
+    any::Any,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use options::{HttpVersion, SslVersionRangeInclusive};
+
+///
+pub mod options {
+    /// A function to authenticate a URL.
+    pub type AuthenticateFn =
+        dyn FnMut(gix_credentials::helper::Action) -> gix_credentials::protocol::Result + Send + Sync;
+
+    /// Possible settings for the `http.followRedirects` configuration option.
+    #[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+    pub enum FollowRedirects {
+        /// Follow only the first redirect request, most suitable for typical git requests.
+        #[default]
+        Initial,
+        /// Follow all redirect requests from the server unconditionally
+        All,
+        /// Follow no redirect request.
+        None,
+    }
+
+    /// The way to configure a proxy for authentication if a username is present in the configured proxy.
+    #[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+    pub enum ProxyAuthMethod {
+        /// Automatically pick a suitable authentication method.
+        #[default]
+        AnyAuth,
+        ///HTTP basic authentication.
+        Basic,
+        /// Http digest authentication to prevent a password to be passed in clear text.
+        Digest,
+        /// GSS negotiate authentication.
+        Negotiate,
+        /// NTLM authentication
+        Ntlm,
+    }
+
+    /// Available SSL version numbers.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd)]
+    #[allow(missing_docs)]
+    pub enum SslVersion {
+        /// The implementation default, which is unknown to this layer of abstraction.
+        Default,
+        TlsV1,
+        SslV2,
+        SslV3,
+        TlsV1_0,
+        TlsV1_1,
+        TlsV1_2,
+        TlsV1_3,
+    }
+
+    /// Available HTTP version numbers.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd)]
+    #[allow(missing_docs)]
+    pub enum HttpVersion {
+        /// Equivalent to HTTP/1.1
+        V1_1,
+        /// Equivalent to HTTP/2
+        V2,
+    }
+
+    /// The desired range of acceptable SSL versions, or the single version to allow if both are set to the same value.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    pub struct SslVersionRangeInclusive {
+        /// The smallest allowed ssl version to use.
+        pub min: SslVersion,
+        /// The highest allowed ssl version to use.
+        pub max: SslVersion,
+    }
+
+    impl SslVersionRangeInclusive {
+        /// Return `min` and `max` fields in the right order so `min` is smaller or equal to `max`.
+        pub fn min_max(&self) -> (SslVersion, SslVersion) {
+            if self.min > self.max {
+                (self.max, self.min)
+            } else {
+                (self.min, self.max)
+            }
+        }
+    }
+}
+
+/// Options to configure http requests.
+// TODO: testing most of these fields requires a lot of effort, unless special flags to introspect ongoing requests are added.
+#[derive(Clone)]
+pub struct Options {
+    /// Headers to be added to every request.
+    /// They are applied unconditionally and are expected to be valid as they occur in an HTTP request, like `header: value`, without newlines.
+    ///
+    /// Refers to `http.extraHeader` multi-var.
+    pub extra_headers: Vec<String>,
+    /// How to handle redirects.
+    ///
+    /// Refers to `http.followRedirects`.
+    pub follow_redirects: options::FollowRedirects,
+    /// Used in conjunction with `low_speed_time_seconds`, any non-0 value signals the amount of bytes per second at least to avoid
+    /// aborting the connection.
+    ///
+    /// Refers to `http.lowSpeedLimit`.
+    pub low_speed_limit_bytes_per_second: u32,
+    /// Used in conjunction with `low_speed_bytes_per_second`, any non-0 value signals the amount seconds the minimal amount
+    /// of bytes per second isn't reached.
+    ///
+    /// Refers to `http.lowSpeedTime`.
+    pub low_speed_time_seconds: u64,
+    /// A curl-style proxy declaration of the form `[protocol://][user[:password]@]proxyhost[:port]`.
+    ///
+    /// Note that an empty string means the proxy is disabled entirely.
+    /// Refers to `http.proxy`.
+    pub proxy: Option<String>,
+    /// The comma-separated list of hosts to not send through the `proxy`, or `*` to entirely disable all proxying.
+    pub no_proxy: Option<String>,
+    /// The way to authenticate against the proxy if the `proxy` field contains a username.
+    ///
+    /// Refers to `http.proxyAuthMethod`.
+    pub proxy_auth_method: options::ProxyAuthMethod,
+    /// If authentication is needed for the proxy as its URL contains a username, this method must be set to provide a password
+    /// for it before making the request, and to store it if the connection succeeds.
+    pub proxy_authenticate: Option<(
+        gix_credentials::helper::Action,
+        Arc<std::sync::Mutex<options::AuthenticateFn>>,
+    )>,
+    /// The `HTTP` `USER_AGENT` string presented to an `HTTP` server, notably not the user agent present to the `git` server.
+    ///
+    /// If not overridden, it defaults to the user agent provided by `curl`, which is a deviation from how `git` handles this.
+    /// Thus it's expected from the callers to set it to their application, or use higher-level crates which make it easy to do this
+    /// more correctly.
+    ///
+    /// Using the correct user-agent might affect how the server treats the request.
+    ///
+    /// Refers to `http.userAgent`.
+    pub user_agent: Option<String>,
+    /// The amount of time we wait until aborting a connection attempt.
+    ///
+    /// If `None`, this typically defaults to 2 minutes to 5 minutes.
+    /// Refers to `gitoxide.http.connectTimeout`.
+    pub connect_timeout: Option<std::time::Duration>,
+    /// If enabled, emit additional information about connections and possibly the data received or written.
+    pub verbose: bool,
+    /// If set, use this path to point to a file with CA certificates to verify peers.
+    pub ssl_ca_info: Option<PathBuf>,
+    /// The SSL version or version range to use, or `None` to let the TLS backend determine which versions are acceptable.
+    pub ssl_version: Option<SslVersionRangeInclusive>,
+    /// Controls whether to perform SSL identity verification or not. Turning this off is not recommended and can lead to
+    /// various security risks. An example where this may be needed is when an internal git server uses a self-signed
+    /// certificate and the user accepts the associated security risks.
+    pub ssl_verify: bool,
+    /// The HTTP version to enforce. If unset, it is implementation defined.
+    pub http_version: Option<HttpVersion>,
+    /// Backend specific options, if available.
+    pub backend: Option<Arc<Mutex<dyn Any + Send + Sync + 'static>>>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            extra_headers: vec![],
+            follow_redirects: Default::default(),
+            low_speed_limit_bytes_per_second: 0,
+            low_speed_time_seconds: 0,
+            proxy: None,
+            no_proxy: None,
+            proxy_auth_method: Default::default(),
+            proxy_authenticate: None,
+            user_agent: None,
+            connect_timeout: None,
+            verbose: false,
+            ssl_ca_info: None,
+            ssl_version: None,
+            ssl_verify: true,
+            http_version: None,
+            backend: None,
+        }
+    }
+}
+
+mod types;
+pub use types::{Error, GetResponse, PostBodyDataKind, PostResponse};
+
+///
+#[cfg(any(
+    feature = "http-client-curl",
+    feature = "http-client-reqwest",
+    feature = "async-http-client-reqwest"
+))]
+pub mod redirect;
+
+#[cfg(feature = "http-client")]
+pub use super::blocking_io::http::*;
+
+#[cfg(feature = "async-http-client")]
+pub use super::async_io::http::*;
@@ -0,0 +1,88 @@
+use crate::client::WriteMode;
+
+/// The error used by the [`Http`](crate::client::http::Http) trait.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not initialize the http client")]
+    InitHttpClient {
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[error("{description}")]
+    Detail { description: String },
+    #[error("An IO error occurred while uploading the body of a POST request")]
+    PostBody(#[from] std::io::Error),
+}
+
+impl crate::IsSpuriousError for Error {
+    fn is_spurious(&self) -> bool {
+        match self {
+            Error::PostBody(err) => err.is_spurious(),
+            #[cfg(any(
+                feature = "http-client-reqwest",
+                feature = "http-client-curl",
+                feature = "async-http-client-reqwest"
+            ))]
+            Error::InitHttpClient { source } => {
+                #[cfg(feature = "http-client-curl")]
+                if let Some(err) = source.downcast_ref::<crate::client::http::curl::Error>() {
+                    return err.is_spurious();
+                };
+                #[cfg(any(feature = "http-client-reqwest", feature = "async-http-client-reqwest"))]
+                if let Some(err) = source.downcast_ref::<crate::client::http::reqwest::remote::Error>() {
+                    return err.is_spurious();
+                };
+                false
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The return value of [`Http::get()`](crate::client::http::Http::get()).
+pub struct GetResponse<H, B> {
+    /// The response headers.
+    pub headers: H,
+    /// The response body.
+    pub body: B,
+}
+
+/// The return value of [`Http::post()`](crate::client::http::Http::post()).
+pub struct PostResponse<H, B, PB> {
+    /// The body to post to the server as part of the request.
+    ///
+    /// **Note**: Implementations should drop the handle to avoid deadlocks.
+    pub post_body: PB,
+    /// The headers of the post response.
+    pub headers: H,
+    /// The body of the post response.
+    pub body: B,
+}
+
+/// Whether or not the post body is expected to fit into memory or not.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum PostBodyDataKind {
+    /// We know how much data we are sending and think it will fit into memory. This allows to collect it into a buffer
+    /// and send it with `Content-Length: <body-len>`.
+    BoundedAndFitsIntoMemory,
+    /// We don't know how much data we will send and assume it won't fit into memory. This enables streaming mode.
+    Unbounded,
+}
+
+impl From<WriteMode> for PostBodyDataKind {
+    fn from(m: WriteMode) -> Self {
+        match m {
+            WriteMode::Binary => PostBodyDataKind::Unbounded,
+            WriteMode::OneLfTerminatedLinePerWriteCall => PostBodyDataKind::BoundedAndFitsIntoMemory,
+        }
+    }
+}
+
+impl<A, B, C> From<PostResponse<A, B, C>> for GetResponse<A, B> {
+    fn from(v: PostResponse<A, B, C>) -> Self {
+        GetResponse {
+            headers: v.headers,
+            body: v.body,
+        }
+    }
+}
@@ -2,7 +2,7 @@
 mod async_io;
 #[cfg(feature = "async-client")]
 pub use async_io::{
-    connect, ExtendedBufRead, HandleProgress, ReadlineBufRead, RequestWriter, SetServiceResponse, Transport,
+    connect, file, ExtendedBufRead, HandleProgress, ReadlineBufRead, RequestWriter, SetServiceResponse, Transport,
     TransportV2Ext,
 };
 
@@ -11,11 +11,9 @@ pub use traits::TransportWithoutIO;
 
 #[cfg(feature = "blocking-client")]
 mod blocking_io;
-#[cfg(feature = "http-client")]
-pub use blocking_io::http;
 #[cfg(feature = "blocking-client")]
 pub use blocking_io::{
-    connect, file, ssh, ExtendedBufRead, HandleProgress, ReadlineBufRead, RequestWriter, SetServiceResponse, Transport,
+    connect, file, ssh, DumbHttp, ExtendedBufRead, HandleProgress, ReadlineBufRead, RequestWriter, SetServiceResponse, Transport,
     TransportV2Ext,
 };
 #[cfg(feature = "blocking-client")]
@@ -34,3 +32,10 @@ pub use non_io_types::{Error, MessageKind, WriteMode};
 ///
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
 pub mod git;
+
+#[cfg(any(feature = "http-client", feature = "async-http-client"))]
+pub mod http;
+
+///
+#[cfg(all(feature = "native-ssh", any(feature = "blocking-client", feature = "async-client")))]
+pub mod native_ssh;
@@ -0,0 +1,61 @@
+use async_trait::async_trait;
+use blocking::Unblock;
+use bstr::ByteSlice;
+
+use crate::{
+    client::{
+        self, git,
+        native_ssh::{session, Transport},
+        SetServiceResponse,
+    },
+    Service,
+};
+
+pub(crate) type ChannelReader = Unblock<session::ChannelReader>;
+pub(crate) type ChannelWriter = Unblock<session::ChannelWriter>;
+
+#[async_trait(?Send)]
+impl client::Transport for Transport {
+    async fn handshake<'a>(
+        &mut self,
+        service: Service,
+        extra_parameters: &'a [(&'a str, Option<&'a str>)],
+    ) -> Result<SetServiceResponse<'_>, client::Error> {
+        if self.path.trim().first() == Some(&b'-') {
+            return Err(client::Error::AmbiguousPath {
+                path: self.path.clone(),
+            });
+        }
+        self.connection = None;
+        if let Some(session) = self.session.take() {
+            session.close();
+        }
+
+        // The session blocks while waiting for its runtime, so it's used on the thread-pool, just like the channel.
+        let (url, path, options, identity, desired_version) = (
+            self.url.clone(),
+            self.path.clone(),
+            self.options.clone(),
+            self.identity.clone(),
+            self.desired_version,
+        );
+        let (session, reader, writer) = blocking::unblock(move || {
+            Transport::establish(&url, &path, &options, identity.as_ref(), service, desired_version)
+        })
+        .await
+        .map_err(super::into_client_error)?;
+        self.connection = Some(git::Connection::new_for_spawned_process(
+            Unblock::new(reader),
+            Unblock::new(writer),
+            self.desired_version,
+            self.path.clone(),
+            self.trace,
+        ));
+        self.session = Some(session);
+        self.connection
+            .as_mut()
+            .expect("connection to be there right after setting it")
+            .handshake(service, extra_parameters)
+            .await
+    }
+}
@@ -0,0 +1,154 @@
+//! User authentication with public keys of an agent or key files, and with passwords.
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use russh::{
+    client::{AuthResult, Handle},
+    keys::{agent::client::AgentClient, PrivateKeyWithHashAlg, PublicKey},
+    MethodKind,
+};
+
+use crate::client::native_ssh::{
+    session::{Session, Verifier},
+    Error, Options,
+};
+
+/// Authenticate as `user` on `host` with the keys of the agent and the key files in `options`, and with `password` if
+/// it is given and all keys were rejected.
+pub(crate) fn authenticate(
+    session: &mut Session,
+    user: &str,
+    host: &str,
+    options: &Options,
+    password: Option<&str>,
+) -> Result<(), Error> {
+    let Session { runtime, handle } = session;
+    runtime.block_on(async {
+        // This tries the `none` method, which may already authenticate us.
+        let methods = match handle.authenticate_none(user).await? {
+            AuthResult::Success => return Ok(()),
+            AuthResult::Failure { remaining_methods, .. } => remaining_methods,
+        };
+        let is_allowed = |method| methods.contains(&method);
+
+        if is_allowed(MethodKind::PublicKey) {
+            let files: Vec<_> = identity_files(options)
+                .into_iter()
+                .filter(|path| path.is_file())
+                .collect();
+            if !options.disable_agent && agent(handle, user, options.identities_only.then_some(files.as_slice())).await
+            {
+                return Ok(());
+            }
+            for path in &files {
+                let Ok(key) = russh::keys::load_secret_key(path, None) else {
+                    continue;
+                };
+                let hash_alg = handle.best_supported_rsa_hash().await?.flatten();
+                let key = PrivateKeyWithHashAlg::new(Arc::new(key), hash_alg);
+                if handle.authenticate_publickey(user, key).await?.success() {
+                    return Ok(());
+                }
+            }
+        }
+
+        if let Some(password) = password.filter(|_| is_allowed(MethodKind::Password)) {
+            if handle.authenticate_password(user, password).await?.success() {
+                return Ok(());
+            }
+        }
+
+        // Failures are also reported if the connection was lost, which isn't a matter of permissions.
+        if handle.is_closed() {
+            return Err(russh::Error::Disconnect.into());
+        }
+        Err(Error::PermissionDenied {
+            user: user.to_owned(),
+            host: host.to_owned(),
+            password_allowed: is_allowed(MethodKind::Password),
+            methods: methods.iter().map(<&str>::from).collect::<Vec<_>>().join(","),
+        })
+    })
+}
+
+/// Try to authenticate as `user` with each key of the agent, if there is one, and return `true` on success.
+///
+/// If `only_files` is set, only the keys whose public key is stored next to one of these private key files are used.
+async fn agent(handle: &mut Handle<Verifier>, user: &str, only_files: Option<&[PathBuf]>) -> bool {
+    let Some(mut agent) = connect_agent().await else {
+        return false;
+    };
+    let Ok(identities) = agent.request_identities().await else {
+        return false;
+    };
+    let allowed_keys: Option<Vec<_>> =
+        only_files.map(|files| files.iter().filter_map(|path| public_key_of(path)).collect());
+    for key in identities {
+        if !allowed_keys.as_ref().map_or(true, |keys| {
+            keys.iter()
+                .any(|allowed: &PublicKey| allowed.key_data() == key.key_data())
+        }) {
+            continue;
+        }
+        let hash_alg = if key.algorithm().is_rsa() {
+            handle.best_supported_rsa_hash().await.ok().flatten().flatten()
+        } else {
+            None
+        };
+        if matches!(
+            handle.authenticate_publickey_with(user, key, hash_alg, &mut agent).await,
+            Ok(res) if res.success()
+        ) {
+            return true;
+        }
+    }
+    false
+}
+
+type Agent = AgentClient<Box<dyn russh::keys::agent::client::AgentStream + Send + Unpin + 'static>>;
+
+/// Connect to the agent whose socket is in the `SSH_AUTH_SOCK` environment variable.
+#[cfg(unix)]
+async fn connect_agent() -> Option<Agent> {
+    AgentClient::connect_env().await.ok().map(AgentClient::dynamic)
+}
+
+/// Connect to the agent whose named pipe is in the `SSH_AUTH_SOCK` environment variable, or to the one of OpenSSH,
+/// falling back to Pageant.
+#[cfg(windows)]
+async fn connect_agent() -> Option<Agent> {
+    let pipe = std::env::var_os("SSH_AUTH_SOCK").unwrap_or_else(|| r"\\.\pipe\openssh-ssh-agent".into());
+    Some(match AgentClient::connect_named_pipe(pipe).await {
+        Ok(agent) => agent.dynamic(),
+        Err(_) => AgentClient::connect_pageant().await.dynamic(),
+    })
+}
+
+#[cfg(not(any(unix, windows)))]
+async fn connect_agent() -> Option<Agent> {
+    None
+}
+
+/// Read the public key stored next to the private key at `path`.
+fn public_key_of(path: &Path) -> Option<PublicKey> {
+    let mut public_key_path = path.as_os_str().to_owned();
+    public_key_path.push(".pub");
+    russh::keys::load_public_key(public_key_path).ok()
+}
+
+/// The private key files to use, in order.
+fn identity_files(options: &Options) -> Vec<PathBuf> {
+    if !options.identity_files.is_empty() {
+        return options.identity_files.clone();
+    }
+    gix_path::env::home_dir()
+        .map(|home| {
+            ["id_ed25519", "id_ecdsa", "id_rsa"]
+                .iter()
+                .map(|name| home.join(".ssh").join(name))
+                .collect()
+        })
+        .unwrap_or_default()
+}
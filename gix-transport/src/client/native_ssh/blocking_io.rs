@@ -0,0 +1,53 @@
+use bstr::ByteSlice;
+
+use crate::{
+    client::{
+        self, git,
+        native_ssh::{session, Transport},
+        SetServiceResponse,
+    },
+    Service,
+};
+
+pub(crate) type ChannelReader = session::ChannelReader;
+pub(crate) type ChannelWriter = session::ChannelWriter;
+
+impl client::Transport for Transport {
+    fn handshake<'a>(
+        &mut self,
+        service: Service,
+        extra_parameters: &'a [(&'a str, Option<&'a str>)],
+    ) -> Result<SetServiceResponse<'_>, client::Error> {
+        if self.path.trim().first() == Some(&b'-') {
+            return Err(client::Error::AmbiguousPath {
+                path: self.path.clone(),
+            });
+        }
+        self.connection = None;
+        if let Some(session) = self.session.take() {
+            session.close();
+        }
+
+        let (session, reader, writer) = Transport::establish(
+            &self.url,
+            &self.path,
+            &self.options,
+            self.identity.as_ref(),
+            service,
+            self.desired_version,
+        )
+        .map_err(super::into_client_error)?;
+        self.connection = Some(git::Connection::new_for_spawned_process(
+            reader,
+            writer,
+            self.desired_version,
+            self.path.clone(),
+            self.trace,
+        ));
+        self.session = Some(session);
+        self.connection
+            .as_mut()
+            .expect("connection to be there right after setting it")
+            .handshake(service, extra_parameters)
+    }
+}
@@ -0,0 +1,92 @@
+//! Verification of host keys with `known_hosts` files in the format of OpenSSH, which are parsed and matched by `russh`.
+use std::{io::Write, path::PathBuf};
+
+use russh::keys::{known_hosts::check_known_hosts_path, HashAlg, PublicKey};
+
+use crate::client::native_ssh::{Error, Options, StrictHostKeyChecking};
+
+/// Check if `key` is the known host key of `host` on `port`, possibly adding it to the first `known_hosts` file
+/// depending on the policy in `options`.
+///
+/// Only entries with keys of the same type as `key` are considered, so keys of other types are neither known nor changed.
+pub(crate) fn verify(key: &PublicKey, host: &str, port: u16, options: &Options) -> Result<(), Error> {
+    let algorithm = key.algorithm().as_str().to_owned();
+    let name = if port == 22 {
+        host.to_owned()
+    } else {
+        format!("[{host}]:{port}")
+    };
+
+    let mut changed_in = None;
+    for path in files(options) {
+        if !path.is_file() {
+            continue;
+        }
+        match check_known_hosts_path(host, port, key, &path) {
+            Ok(true) => return Ok(()),
+            Ok(false) => {}
+            Err(russh::keys::Error::KeyChanged { .. }) => {
+                changed_in.get_or_insert(path);
+            }
+            Err(source) => return Err(Error::KnownHosts { path, source }),
+        }
+    }
+
+    let fingerprint = || key.fingerprint(HashAlg::Sha256).to_string();
+    match changed_in {
+        Some(path) if options.strict_host_key_checking != StrictHostKeyChecking::No => Err(Error::HostKeyMismatch {
+            host: name,
+            algorithm,
+            fingerprint: fingerprint(),
+            path,
+        }),
+        Some(_) => Ok(()),
+        None if options.strict_host_key_checking == StrictHostKeyChecking::Yes => Err(Error::UnknownHostKey {
+            host: name,
+            algorithm,
+            fingerprint: fingerprint(),
+        }),
+        None => add(key, &name, options),
+    }
+}
+
+/// Append the entry for `key` of `host` to the first `known_hosts` file.
+fn add(key: &PublicKey, host: &str, options: &Options) -> Result<(), Error> {
+    let Some(path) = files(options).into_iter().next() else {
+        return Ok(());
+    };
+    let append = || -> std::io::Result<()> {
+        let key = key
+            .to_openssh()
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(&path)?;
+        writeln!(file, "{host} {key}")
+    };
+    append().map_err(|source| Error::KnownHostsUpdate {
+        host: host.to_owned(),
+        path: path.clone(),
+        source,
+    })
+}
+
+/// The `known_hosts` files to use, with the ones of the user first.
+fn files(options: &Options) -> Vec<PathBuf> {
+    if !options.known_hosts_files.is_empty() {
+        return options.known_hosts_files.clone();
+    }
+    let mut files: Vec<_> = gix_path::env::home_dir()
+        .map(|home| {
+            ["known_hosts", "known_hosts2"]
+                .iter()
+                .map(|name| home.join(".ssh").join(name))
+                .collect()
+        })
+        .unwrap_or_default();
+    if cfg!(unix) {
+        files.extend(["/etc/ssh/ssh_known_hosts", "/etc/ssh/ssh_known_hosts2"].map(PathBuf::from));
+    }
+    files
+}
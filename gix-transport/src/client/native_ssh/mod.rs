@@ -0,0 +1,297 @@
+//! A built-in SSH client to connect to `ssh://` URLs without the need for an `ssh` program.
+//!
+//! The SSH protocol is implemented in Rust by the [`russh`](https://docs.rs/russh) crate, so it's the algorithms
+//! supported by it that are available.
+//! Users authenticate with public keys from an ssh-agent or from key files, or with a password obtained through
+//! [`set_identity()`][crate::client::TransportWithoutIO::set_identity()].
+//! The keys of servers are verified against `known_hosts` files.
+//!
+//! The connection is established lazily during the [handshake][crate::client::Transport::handshake()], just like
+//! the `ssh` program would be spawned lazily, and each handshake uses a new connection.
+//!
+//! Each connection is driven by its own `tokio` runtime with a single thread, so it works independently of the
+//! runtime of the caller, if there is one. The async transport reads and writes on the thread-pool of the `blocking`
+//! crate, just like it does for the `file` transport.
+use std::{path::PathBuf, time::Duration};
+
+use bstr::BString;
+
+use crate::Protocol;
+
+mod auth;
+mod known_hosts;
+mod session;
+
+#[cfg(feature = "async-client")]
+mod async_io;
+#[cfg(feature = "async-client")]
+use async_io as io;
+#[cfg(feature = "blocking-client")]
+mod blocking_io;
+#[cfg(feature = "blocking-client")]
+use blocking_io as io;
+
+/// How to handle servers whose host key isn't known yet, similar to the `StrictHostKeyChecking` option of `ssh`.
+///
+/// Note that a host key that differs from a known one is always rejected unless this is [`No`][Self::No].
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum StrictHostKeyChecking {
+    /// Refuse to connect to hosts whose key isn't known.
+    #[default]
+    Yes,
+    /// Add the keys of hosts not seen before to the first `known_hosts` file, but refuse to connect if a known key changed.
+    AcceptNew,
+    /// Add the keys of hosts not seen before and connect to hosts with changed keys as well.
+    ///
+    /// This makes connections vulnerable to man-in-the-middle attacks.
+    No,
+}
+
+/// Options to configure the built-in SSH client, which correspond to the `ssh` options one would otherwise pass through
+/// `core.sshCommand`.
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    /// The name of the user to log in as if the URL doesn't contain one, like `-l` or `User`.
+    ///
+    /// If unset, the name of the current user is used.
+    pub user: Option<String>,
+    /// The port to connect to if the URL doesn't contain one, like `-p` or `Port`.
+    ///
+    /// If unset, `22` is used.
+    pub port: Option<u16>,
+    /// The private key files to authenticate with, in order, like `-i` or `IdentityFile`.
+    ///
+    /// If empty, `~/.ssh/id_ed25519`, `~/.ssh/id_ecdsa` and `~/.ssh/id_rsa` are tried.
+    /// Note that keys protected by a passphrase can only be used if they are also held by the ssh-agent.
+    pub identity_files: Vec<PathBuf>,
+    /// If `true`, only use the keys in `identity_files`, even if the ssh-agent holds other keys, like `IdentitiesOnly`.
+    ///
+    /// Keys of the agent are identified by the public key files next to the `identity_files`, with `.pub` appended.
+    pub identities_only: bool,
+    /// If `true`, don't use an ssh-agent at all.
+    ///
+    /// Otherwise, the agent is found through the `SSH_AUTH_SOCK` environment variable, and on Windows it can also be
+    /// Pageant or the OpenSSH agent.
+    pub disable_agent: bool,
+    /// The files with the public keys of known hosts, like `UserKnownHostsFile`.
+    ///
+    /// If empty, `~/.ssh/known_hosts` and `~/.ssh/known_hosts2` are used, along with `/etc/ssh/ssh_known_hosts` and
+    /// `/etc/ssh/ssh_known_hosts2` on Unix.
+    /// Keys of new hosts are added to the first of these files.
+    /// Host names may be hashed, but unlike with `ssh`, patterns and markers like `@revoked` aren't supported.
+    pub known_hosts_files: Vec<PathBuf>,
+    /// How to handle hosts whose key isn't known yet.
+    pub strict_host_key_checking: StrictHostKeyChecking,
+    /// The time after which to give up connecting to the server, like `ConnectTimeout`.
+    ///
+    /// If unset, the system default applies.
+    pub connect_timeout: Option<Duration>,
+}
+
+/// The error returned when connecting with the built-in SSH client.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The scheme in \"{}\" is not usable for an ssh connection", .0.to_bstring())]
+    UnsupportedScheme(Box<gix_url::Url>),
+    #[error("Could not connect to {host}:{port}")]
+    Connect {
+        host: String,
+        port: u16,
+        source: std::io::Error,
+    },
+    #[error("An IO error occurred when talking to the SSH server")]
+    Io(#[from] std::io::Error),
+    #[error("The SSH session with the server failed")]
+    Session(#[from] russh::Error),
+    #[error("The {algorithm} host key of '{host}' with fingerprint {fingerprint} isn't known")]
+    UnknownHostKey {
+        host: String,
+        algorithm: String,
+        fingerprint: String,
+    },
+    #[error("The {algorithm} host key of '{host}' with fingerprint {fingerprint} doesn't match the one in '{}' - the server may be impersonated", path.display())]
+    HostKeyMismatch {
+        host: String,
+        algorithm: String,
+        fingerprint: String,
+        path: PathBuf,
+    },
+    #[error("Could not read the known hosts in '{}'", path.display())]
+    KnownHosts { path: PathBuf, source: russh::keys::Error },
+    #[error("Could not add the host key of '{host}' to '{}'", path.display())]
+    KnownHostsUpdate {
+        host: String,
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Permission denied for user '{user}' on '{host}' (methods allowed by the server: {methods})")]
+    PermissionDenied {
+        user: String,
+        host: String,
+        methods: String,
+        /// If `true`, password authentication could be attempted after setting an identity.
+        password_allowed: bool,
+    },
+    #[error("The server refused to execute {command:?}")]
+    Exec { command: BString },
+}
+
+impl crate::IsSpuriousError for Error {
+    fn is_spurious(&self) -> bool {
+        match self {
+            Error::Connect { source, .. } | Error::Io(source) => source.is_spurious(),
+            _ => false,
+        }
+    }
+}
+
+/// A transport to a repository on a server reachable via SSH, using the built-in SSH client.
+///
+/// It can only be instantiated using [`connect()`].
+pub struct Transport {
+    url: gix_url::Url,
+    path: BString,
+    desired_version: Protocol,
+    options: Options,
+    identity: Option<crate::client::Account>,
+    session: Option<session::Session>,
+    connection: Option<Connection>,
+    trace: bool,
+}
+
+type Connection = crate::client::git::Connection<io::ChannelReader, io::ChannelWriter>;
+
+impl Drop for Transport {
+    fn drop(&mut self) {
+        self.connection.take();
+        if let Some(session) = self.session.take() {
+            session.close();
+        }
+    }
+}
+
+impl Transport {
+    /// Connect to the server, authenticate and run `service` in a session, returning the session along with the
+    /// channel to communicate with the service.
+    fn establish(
+        url: &gix_url::Url,
+        path: &BString,
+        options: &Options,
+        identity: Option<&crate::client::Account>,
+        service: crate::Service,
+        desired_version: Protocol,
+    ) -> Result<(session::Session, session::ChannelReader, session::ChannelWriter), Error> {
+        let host = url.host().expect("validated in connect()");
+        let port = url.port.or(options.port).unwrap_or(22);
+        let user = url
+            .user()
+            .map(ToOwned::to_owned)
+            .or_else(|| identity.map(|account| account.username.clone()))
+            .or_else(|| options.user.clone())
+            .or_else(current_user)
+            .unwrap_or_else(|| "git".into());
+
+        let mut session = session::Session::connect(host, port, options)?;
+        auth::authenticate(
+            &mut session,
+            &user,
+            host,
+            options,
+            identity.map(|account| account.password.as_str()),
+        )?;
+
+        let mut command = BString::from(service.as_str());
+        command.push(b' ');
+        command.extend_from_slice(&gix_quote::single(path.as_ref()));
+        let env = (desired_version != Protocol::V1)
+            .then(|| ("GIT_PROTOCOL", format!("version={}", desired_version as usize)));
+        let (reader, writer) = session.exec(command, env)?;
+        Ok((session, reader, writer))
+    }
+}
+
+fn current_user() -> Option<String> {
+    ["USER", "USERNAME", "LOGNAME"]
+        .iter()
+        .find_map(|name| std::env::var(name).ok().filter(|value| !value.is_empty()))
+}
+
+mod transport_without_io {
+    use std::{any::Any, borrow::Cow, error::Error};
+
+    use bstr::BStr;
+
+    use crate::client::{self, native_ssh::Transport, MessageKind, RequestWriter, WriteMode};
+
+    impl client::TransportWithoutIO for Transport {
+        fn set_identity(&mut self, identity: client::Account) -> Result<(), client::Error> {
+            self.identity = Some(identity);
+            Ok(())
+        }
+
+        fn request(
+            &mut self,
+            write_mode: WriteMode,
+            on_into_read: MessageKind,
+            trace: bool,
+        ) -> Result<RequestWriter<'_>, client::Error> {
+            self.connection
+                .as_mut()
+                .expect("handshake() to have been called first")
+                .request(write_mode, on_into_read, trace)
+        }
+
+        fn to_url(&self) -> Cow<'_, BStr> {
+            Cow::Owned(self.url.to_bstring())
+        }
+
+        fn connection_persists_across_multiple_requests(&self) -> bool {
+            true
+        }
+
+        fn configure(&mut self, _config: &dyn Any) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
+            Ok(())
+        }
+    }
+}
+
+/// Turn `err` into an error that allows higher layers to obtain credentials if the server would accept a password.
+fn into_client_error(err: Error) -> crate::client::Error {
+    match err {
+        Error::PermissionDenied {
+            password_allowed: true, ..
+        } => std::io::Error::new(std::io::ErrorKind::PermissionDenied, err).into(),
+        err => err.into(),
+    }
+}
+
+/// Prepare a connection to the repository identified by `url` on an SSH server using the built-in SSH client.
+///
+/// The connection is established when performing the handshake, using `options` to control authentication and the
+/// verification of the server.
+///
+/// The `desired_version` is the preferred protocol version when establishing the connection, but note that it can be
+/// downgraded by servers not supporting it.
+/// If `trace` is `true`, all packetlines received or sent will be passed to the facilities of the `gix-trace` crate.
+pub fn connect(
+    url: gix_url::Url,
+    desired_version: Protocol,
+    options: Options,
+    trace: bool,
+) -> Result<Transport, Error> {
+    if url.scheme != gix_url::Scheme::Ssh || url.host().is_none() {
+        return Err(Error::UnsupportedScheme(Box::new(url)));
+    }
+    let path = gix_url::expand_path::for_shell(url.path.clone());
+    Ok(Transport {
+        url,
+        path,
+        desired_version,
+        options,
+        identity: None,
+        session: None,
+        connection: None,
+        trace,
+    })
+}
@@ -0,0 +1,238 @@
+//! Establishing sessions with `russh` and running commands in them.
+use std::{
+    io::{self, Read, Write},
+    sync::Arc,
+};
+
+use bstr::{BString, ByteSlice};
+use russh::{client, ChannelMsg, Disconnect};
+
+use crate::client::native_ssh::{known_hosts, Error, Options};
+
+/// A session with a server whose host key was verified.
+pub(crate) struct Session {
+    /// The runtime driving the connection, shared with the reader and writer of the channel.
+    pub runtime: Arc<Runtime>,
+    pub handle: client::Handle<Verifier>,
+}
+
+impl Session {
+    /// Connect to `host` on `port` and verify its host key according to `options`.
+    pub fn connect(host: &str, port: u16, options: &Options) -> Result<Self, Error> {
+        let runtime = Arc::new(Runtime::new()?);
+        let verifier = Verifier {
+            host: host.to_owned(),
+            port,
+            options: options.clone(),
+        };
+        let handle = runtime.block_on(async {
+            let connect = tokio::net::TcpStream::connect((host, port));
+            let stream = match options.connect_timeout {
+                Some(timeout) => tokio::time::timeout(timeout, connect)
+                    .await
+                    .unwrap_or_else(|_| Err(io::ErrorKind::TimedOut.into())),
+                None => connect.await,
+            }
+            .map_err(|source| Error::Connect {
+                host: host.to_owned(),
+                port,
+                source,
+            })?;
+            stream.set_nodelay(true)?;
+            client::connect_stream(Arc::new(client::Config::default()), stream, verifier).await
+        })?;
+        Ok(Session { runtime, handle })
+    }
+
+    /// Open a session channel to execute `command` with `env` set in its environment, if possible.
+    pub fn exec(&self, command: BString, env: Option<(&str, String)>) -> Result<(ChannelReader, ChannelWriter), Error> {
+        let channel = self.runtime.block_on(async {
+            let channel = self.handle.channel_open_session().await?;
+            if let Some((name, value)) = env {
+                // Like `ssh -o SendEnv`, we don't fail if servers don't accept the variable, as `sshd` only does if it's
+                // configured to.
+                channel.set_env(false, name, value).await?;
+            }
+            channel.exec(true, command.to_vec()).await?;
+            Ok::<_, Error>(channel)
+        })?;
+        let (read, write) = channel.split();
+        let mut reader = ChannelReader {
+            runtime: self.runtime.clone(),
+            channel: read,
+            command,
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+            exit: None,
+            is_closed: false,
+        };
+        if !reader.wait_for_reply() {
+            return Err(Error::Exec {
+                command: reader.command,
+            });
+        }
+        let writer = ChannelWriter {
+            runtime: self.runtime.clone(),
+            channel: write,
+        };
+        Ok((reader, writer))
+    }
+
+    /// Disconnect from the server without waiting for it, which also unblocks pending reads.
+    pub fn close(self) {
+        let handle = self.handle;
+        self.runtime.spawn(async move {
+            handle.disconnect(Disconnect::ByApplication, "", "").await.ok();
+        });
+    }
+}
+
+/// Checks the host key of the server as part of the key exchange.
+pub(crate) struct Verifier {
+    host: String,
+    port: u16,
+    options: Options,
+}
+
+impl client::Handler for Verifier {
+    type Error = Error;
+
+    async fn check_server_key(&mut self, key: &russh::keys::PublicKey) -> Result<bool, Self::Error> {
+        known_hosts::verify(key, &self.host, self.port, &self.options)?;
+        Ok(true)
+    }
+}
+
+/// A `tokio` runtime with a single thread, which shuts down without waiting for its tasks so it can be dropped
+/// anywhere, even within another runtime.
+pub(crate) struct Runtime(Option<tokio::runtime::Runtime>);
+
+impl Runtime {
+    fn new() -> io::Result<Self> {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .map(|runtime| Runtime(Some(runtime)))
+    }
+}
+
+impl std::ops::Deref for Runtime {
+    type Target = tokio::runtime::Runtime;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.as_ref().expect("only taken when dropped")
+    }
+}
+
+impl Drop for Runtime {
+    fn drop(&mut self) {
+        if let Some(runtime) = self.0.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+/// How the command running in a channel exited.
+enum Exit {
+    Status(u32),
+    Signal(String),
+}
+
+/// Reads the standard output of the command running in a channel.
+pub(crate) struct ChannelReader {
+    runtime: Arc<Runtime>,
+    channel: russh::ChannelReadHalf,
+    command: BString,
+    /// Output of the command that wasn't read yet.
+    stdout: Vec<u8>,
+    /// The error output of the command, to describe why it failed.
+    stderr: Vec<u8>,
+    exit: Option<Exit>,
+    /// If `true`, the server closed the channel and all output was received.
+    is_closed: bool,
+}
+
+impl ChannelReader {
+    /// Receive the next message of the server, and keep the data it carries.
+    ///
+    /// Returns `Some(success)` if the message was a reply to a request.
+    fn receive(&mut self) -> Option<bool> {
+        match self.runtime.block_on(self.channel.wait()) {
+            Some(ChannelMsg::Data { data }) => self.stdout.extend_from_slice(&data),
+            Some(ChannelMsg::ExtendedData { data, ext: 1 }) => self.stderr.extend_from_slice(&data),
+            Some(ChannelMsg::ExitStatus { exit_status }) => self.exit = Some(Exit::Status(exit_status)),
+            Some(ChannelMsg::ExitSignal { signal_name, .. }) => {
+                self.exit = Some(Exit::Signal(format!("{signal_name:?}")));
+            }
+            Some(ChannelMsg::Success) => return Some(true),
+            Some(ChannelMsg::Failure) => return Some(false),
+            Some(ChannelMsg::Close) | None => self.is_closed = true,
+            Some(_) => {}
+        }
+        None
+    }
+
+    /// Wait for the server to reply to the request to execute the command, and return `true` if it succeeded.
+    fn wait_for_reply(&mut self) -> bool {
+        while !self.is_closed {
+            if let Some(success) = self.receive() {
+                return success;
+            }
+        }
+        false
+    }
+
+    /// Return an error if the command didn't succeed, describing it along with its error output.
+    fn exit_error(&mut self) -> Option<io::Error> {
+        let reason = match self.exit.take()? {
+            Exit::Status(0) => return None,
+            Exit::Status(status) => format!("failed with exit status {status}"),
+            Exit::Signal(signal) => format!("was killed by signal {signal}"),
+        };
+        let stderr = self.stderr.trim();
+        let message = if stderr.is_empty() {
+            format!("{:?} {reason}", self.command)
+        } else {
+            format!("{:?} {reason}: {}", self.command, stderr.as_bstr())
+        };
+        Some(io::Error::new(io::ErrorKind::Other, message))
+    }
+}
+
+impl Read for ChannelReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.stdout.is_empty() && !self.is_closed && !buf.is_empty() {
+            self.receive();
+        }
+        if self.stdout.is_empty() && !buf.is_empty() {
+            if let Some(err) = self.exit_error() {
+                return Err(err);
+            }
+        }
+        let len = self.stdout.len().min(buf.len());
+        buf[..len].copy_from_slice(&self.stdout[..len]);
+        self.stdout.drain(..len);
+        Ok(len)
+    }
+}
+
+/// Writes to the standard input of the command running in a channel.
+pub(crate) struct ChannelWriter {
+    runtime: Arc<Runtime>,
+    channel: russh::ChannelWriteHalf<client::Msg>,
+}
+
+impl Write for ChannelWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.runtime
+            .block_on(self.channel.data(buf))
+            .map_err(|err| io::Error::new(io::ErrorKind::BrokenPipe, err))?;
+        Ok(buf.len())
+    }
+
+    /// Writes are sent right away, so there is nothing to flush.
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -30,6 +30,29 @@ pub enum MessageKind {
     Text(&'static [u8]),
 }
 
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+pub(crate) mod file {
+    // from https://github.com/git/git/blob/20de7e7e4f4e9ae52e6cc7cfaa6469f186ddb0fa/environment.c#L115:L115
+    pub const ENV_VARS_TO_REMOVE: &[&str] = &[
+        "GIT_ALTERNATE_OBJECT_DIRECTORIES",
+        "GIT_CONFIG",
+        "GIT_CONFIG_PARAMETERS",
+        "GIT_OBJECT_DIRECTORY",
+        "GIT_DIR",
+        "GIT_WORK_TREE",
+        "GIT_IMPLICIT_WORK_TREE",
+        "GIT_GRAFT_FILE",
+        "GIT_INDEX_FILE",
+        "GIT_NO_REPLACE_OBJECTS",
+        "GIT_REPLACE_REF_BASE",
+        "GIT_PREFIX",
+        "GIT_INTERNAL_SUPER_PREFIX",
+        "GIT_SHALLOW_FILE",
+        "GIT_COMMON_DIR",
+        "GIT_CONFIG_COUNT",
+    ];
+}
+
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
 pub(crate) mod connect {
     /// Options for connecting to a remote.
@@ -40,6 +63,12 @@ pub(crate) mod connect {
         #[cfg(feature = "blocking-client")]
         /// Options to use if the scheme of the URL is `ssh`.
         pub ssh: crate::client::ssh::connect::Options,
+        /// If set, connect to `ssh://` URLs with the [built-in SSH client][crate::client::native_ssh] configured by these
+        /// options instead of invoking an `ssh` program.
+        ///
+        /// With the `async-client`, the built-in SSH client is always used, with default options if this isn't set.
+        #[cfg(feature = "native-ssh")]
+        pub native_ssh: Option<crate::client::native_ssh::Options>,
         /// If `true`, all packetlines received or sent will be passed to the facilities of the `gix-trace` crate.
         pub trace: bool,
     }
@@ -61,11 +90,17 @@ pub(crate) mod connect {
         },
         #[error("The '{0}' protocol is currently unsupported")]
         UnsupportedScheme(gix_url::Scheme),
-        #[cfg(not(any(feature = "http-client-curl", feature = "http-client-reqwest")))]
+        #[cfg(all(
+            feature = "blocking-client",
+            not(any(feature = "http-client-curl", feature = "http-client-reqwest"))
+        ))]
         #[error(
             "'{0}' is not compiled in. Compile with the 'http-client-curl' or 'http-client-reqwest' cargo feature"
         )]
         CompiledWithoutHttp(gix_url::Scheme),
+        #[cfg(all(feature = "async-client", not(feature = "async-http-client-reqwest")))]
+        #[error("'{0}' is not compiled in. Compile with the 'async-http-client-reqwest' cargo feature")]
+        CompiledWithoutHttp(gix_url::Scheme),
     }
 
     // TODO: maybe fix this workaround: want `IsSpuriousError`  in `Connection(…)`
@@ -80,6 +115,10 @@ pub(crate) mod connect {
                     if let Some(err) = err.downcast_ref::<crate::client::Error>() {
                         return err.is_spurious();
                     }
+                    #[cfg(feature = "native-ssh")]
+                    if let Some(err) = err.downcast_ref::<crate::client::native_ssh::Error>() {
+                        return err.is_spurious();
+                    }
                     false
                 }
                 _ => false,
@@ -94,16 +133,16 @@ mod error {
     use bstr::BString;
 
     use crate::client::capabilities;
-    #[cfg(feature = "http-client")]
+    #[cfg(any(feature = "http-client", feature = "async-http-client"))]
     use crate::client::http;
     #[cfg(feature = "blocking-client")]
     use crate::client::ssh;
 
-    #[cfg(feature = "http-client")]
+    #[cfg(any(feature = "http-client", feature = "async-http-client"))]
     type HttpError = http::Error;
     #[cfg(feature = "blocking-client")]
     type SshInvocationError = ssh::invocation::Error;
-    #[cfg(not(feature = "http-client"))]
+    #[cfg(not(any(feature = "http-client", feature = "async-http-client")))]
     type HttpError = std::convert::Infallible;
     #[cfg(not(feature = "blocking-client"))]
     type SshInvocationError = std::convert::Infallible;
@@ -142,6 +181,9 @@ mod error {
         SshInvocation(SshInvocationError),
         #[error("The repository path '{path}' could be mistaken for a command-line argument")]
         AmbiguousPath { path: BString },
+        #[cfg(feature = "native-ssh")]
+        #[error(transparent)]
+        NativeSsh(#[from] crate::client::native_ssh::Error),
     }
 
     impl crate::IsSpuriousError for Error {
@@ -149,6 +191,8 @@ mod error {
             match self {
                 Error::Io(err) => err.is_spurious(),
                 Error::Http(err) => err.is_spurious(),
+                #[cfg(feature = "native-ssh")]
+                Error::NativeSsh(err) => err.is_spurious(),
                 _ => false,
             }
         }
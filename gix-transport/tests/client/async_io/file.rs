@@ -0,0 +1,98 @@
+use futures_lite::{AsyncBufReadExt, StreamExt};
+use gix_transport::{
+    client::{self, Transport, TransportWithoutIO},
+    Protocol, Service,
+};
+
+fn repo_path() -> crate::Result<std::path::PathBuf> {
+    gix_testtools::scripted_fixture_read_only("make_repo.sh").map_err(|err| err as crate::Error)
+}
+
+fn ref_names(lines: Vec<String>) -> Vec<String> {
+    lines
+        .into_iter()
+        .map(|line| line.split(' ').nth(1).expect("name").to_owned())
+        .collect()
+}
+
+#[async_std::test]
+async fn handshake_v1_lists_refs() -> crate::Result {
+    let mut c = client::file::connect(repo_path()?.to_str().expect("valid UTF-8"), Protocol::V1, false)?;
+    assert!(
+        c.connection_persists_across_multiple_requests(),
+        "the spawned process stays alive across requests"
+    );
+
+    let mut res = c.handshake(Service::UploadPack, &[]).await?;
+    assert_eq!(res.actual_protocol, Protocol::V1);
+    assert!(res.capabilities.contains("multi_ack_detailed"));
+    let lines = res
+        .refs
+        .as_mut()
+        .expect("v1 protocol provides refs")
+        .lines()
+        .try_collect::<_, _, Vec<_>>()
+        .await?;
+    assert_eq!(ref_names(lines), ["HEAD", "refs/heads/main", "refs/tags/v1"]);
+    Ok(())
+}
+
+#[async_std::test]
+#[cfg(feature = "async-std")]
+async fn handshake_v2_and_ls_refs() -> crate::Result {
+    use gix_transport::client::TransportV2Ext;
+
+    let mut c = gix_transport::connect(
+        format!("file://{}", repo_path()?.canonicalize()?.display()).as_str(),
+        gix_transport::connect::Options {
+            version: Protocol::V2,
+            ..Default::default()
+        },
+    )
+    .await?;
+    let res = c.handshake(Service::UploadPack, &[]).await?;
+    assert_eq!(res.actual_protocol, Protocol::V2);
+    assert!(res.refs.is_none(), "refs are only returned in V1");
+    assert!(res.capabilities.contains("ls-refs"));
+    drop(res);
+
+    let lines = c
+        .invoke(
+            "ls-refs",
+            Vec::<(_, Option<&str>)>::new().into_iter(),
+            Some(vec!["symrefs".into()].into_iter()),
+            false,
+        )
+        .await?
+        .lines()
+        .try_collect::<_, _, Vec<_>>()
+        .await?;
+    assert_eq!(ref_names(lines.clone()), ["HEAD", "refs/heads/main", "refs/tags/v1"]);
+    assert!(lines[0].ends_with("symref-target:refs/heads/main"));
+    Ok(())
+}
+
+#[async_std::test]
+async fn ambiguous_paths_are_rejected() -> crate::Result {
+    let mut c = client::file::connect("-looks-like-an-argument", Protocol::V2, false)?;
+    let err = c
+        .handshake(Service::UploadPack, &[])
+        .await
+        .err()
+        .expect("path is rejected");
+    assert!(matches!(err, client::Error::AmbiguousPath { .. }));
+    Ok(())
+}
+
+#[async_std::test]
+#[cfg(feature = "async-std")]
+async fn connect_rejects_url_tokens_for_files() {
+    let err = gix_transport::connect("file://user@host/path", Default::default())
+        .await
+        .err()
+        .expect("user and host aren't supported");
+    assert!(matches!(
+        err,
+        gix_transport::connect::Error::UnsupportedUrlTokens { .. }
+    ));
+}
@@ -0,0 +1,3 @@
+mod file;
+#[cfg(feature = "async-http-client-reqwest")]
+mod http;
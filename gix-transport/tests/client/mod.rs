@@ -1,4 +1,8 @@
+#[cfg(feature = "async-client")]
+mod async_io;
 #[cfg(feature = "blocking-client")]
 mod blocking_io;
 mod capabilities;
 mod git;
+#[cfg(feature = "native-ssh")]
+mod native_ssh;
@@ -0,0 +1,439 @@
+#[cfg(feature = "blocking-client")]
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+#[cfg(feature = "async-client")]
+use futures_lite::{AsyncBufReadExt, StreamExt};
+use gix_testtools::tempfile;
+use gix_transport::{
+    client::{
+        self,
+        native_ssh::{self, Error, Options, StrictHostKeyChecking},
+        Transport, TransportV2Ext,
+    },
+    Protocol, Service,
+};
+use hmac::Mac;
+use russh::keys::{ssh_key::LineEnding, PrivateKey, PublicKey};
+
+use crate::Result;
+
+mod server;
+
+fn repo_path() -> Result<PathBuf> {
+    Ok(gix_testtools::scripted_fixture_read_only("make_repo.sh")
+        .map_err(|err| err.to_string())?
+        .canonicalize()?)
+}
+
+struct Fixture {
+    dir: tempfile::TempDir,
+    port: u16,
+    log: server::Log,
+    host_key: PublicKey,
+    client_key: PrivateKey,
+}
+
+impl Fixture {
+    fn new(configure: impl FnOnce(&mut server::Config)) -> Result<Self> {
+        let client_key = server::ed25519_key();
+        let mut config = server::Config {
+            host_key: server::ed25519_key(),
+            authorized_keys: vec![client_key.public_key().clone()],
+            password: None,
+            window: 64 * 1024,
+        };
+        configure(&mut config);
+        let host_key = config.host_key.public_key().clone();
+        let (port, log) = server::spawn(config);
+        let fixture = Fixture {
+            dir: tempfile::tempdir()?,
+            port,
+            log,
+            host_key,
+            client_key,
+        };
+        fixture
+            .client_key
+            .write_openssh_file(&fixture.key_path(), LineEnding::LF)?;
+        Ok(fixture)
+    }
+
+    fn key_path(&self) -> PathBuf {
+        self.dir.path().join("id_ed25519")
+    }
+
+    fn known_hosts_path(&self) -> PathBuf {
+        self.dir.path().join("ssh").join("known_hosts")
+    }
+
+    fn host(&self) -> String {
+        format!("[127.0.0.1]:{}", self.port)
+    }
+
+    /// Write `line` as only line into the `known_hosts` file.
+    fn known_hosts(&self, line: &str) -> Result {
+        std::fs::create_dir_all(self.known_hosts_path().parent().expect("in directory"))?;
+        std::fs::write(self.known_hosts_path(), format!("{line}\n"))?;
+        Ok(())
+    }
+
+    /// Add the host key of the server to the `known_hosts` file.
+    fn trust_host(&self) -> Result {
+        self.known_hosts(&format!("{} {}", self.host(), self.host_key.to_openssh()?))
+    }
+
+    fn options(&self) -> Options {
+        Options {
+            identity_files: vec![self.key_path()],
+            disable_agent: true,
+            known_hosts_files: vec![self.known_hosts_path()],
+            ..Default::default()
+        }
+    }
+
+    fn connect(&self, repo: &Path, version: Protocol, options: Options) -> Result<native_ssh::Transport> {
+        let url = gix_url::parse(
+            format!("ssh://git@127.0.0.1:{}{}", self.port, repo.display())
+                .as_str()
+                .into(),
+        )?;
+        Ok(native_ssh::connect(url, version, options, false)?)
+    }
+
+    fn log(&self) -> Vec<String> {
+        self.log.lock().unwrap().clone()
+    }
+}
+
+fn ref_names(lines: Vec<String>) -> Vec<String> {
+    lines
+        .into_iter()
+        .map(|line| line.split(' ').nth(1).expect("name").to_owned())
+        .collect()
+}
+
+fn native_ssh_error(err: client::Error) -> Error {
+    match err {
+        client::Error::NativeSsh(err) => err,
+        err => panic!("expected a native ssh error, got {err:?}"),
+    }
+}
+
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn handshake_v2_and_ls_refs_with_key_file() -> Result {
+    let fixture = Fixture::new(|config| config.window = 16)?;
+    fixture.trust_host()?;
+    let repo = repo_path()?;
+    let mut c = fixture.connect(&repo, Protocol::V2, fixture.options())?;
+
+    let res = c.handshake(Service::UploadPack, &[]).await?;
+    assert_eq!(res.actual_protocol, Protocol::V2);
+    assert!(res.capabilities.contains("ls-refs"));
+    drop(res);
+
+    {
+        let reader = c
+            .invoke(
+                "ls-refs",
+                Vec::<(_, Option<&str>)>::new().into_iter(),
+                Some(vec!["symrefs".into(), "peel".into()].into_iter()),
+                false,
+            )
+            .await?;
+        let mut lines = Vec::new();
+        let mut stream = reader.lines();
+        #[allow(clippy::while_let_on_iterator)] // needed in async version of test
+        while let Some(line) = stream.next().await {
+            lines.push(line?);
+        }
+        assert_eq!(ref_names(lines), ["HEAD", "refs/heads/main", "refs/tags/v1"]);
+    }
+    drop(c);
+
+    let log = fixture.log();
+    assert_eq!(
+        &log[..3],
+        [
+            "auth publickey git".to_owned(),
+            "env GIT_PROTOCOL=version=2".into(),
+            format!("exec git-upload-pack '{}'", repo.display()),
+        ]
+    );
+    Ok(())
+}
+
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn handshake_v1_lists_refs() -> Result {
+    let fixture = Fixture::new(|_| {})?;
+    fixture.trust_host()?;
+    let mut c = fixture.connect(&repo_path()?, Protocol::V1, fixture.options())?;
+
+    let mut res = c.handshake(Service::UploadPack, &[]).await?;
+    assert_eq!(res.actual_protocol, Protocol::V1);
+    let mut lines = Vec::new();
+    let mut stream = res.refs.as_mut().expect("v1 protocol provides refs").lines();
+    #[allow(clippy::while_let_on_iterator)] // needed in async version of test
+    while let Some(line) = stream.next().await {
+        lines.push(line?);
+    }
+    assert_eq!(ref_names(lines), ["HEAD", "refs/heads/main", "refs/tags/v1"]);
+    assert!(
+        !fixture.log().iter().any(|event| event.starts_with("env ")),
+        "V1 is the default and needs no environment variable"
+    );
+    Ok(())
+}
+
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn failing_commands_report_their_error_output() -> Result {
+    let fixture = Fixture::new(|_| {})?;
+    fixture.trust_host()?;
+    let mut c = fixture.connect(Path::new("/does/not/exist"), Protocol::V2, fixture.options())?;
+
+    let err = c
+        .handshake(Service::UploadPack, &[])
+        .await
+        .err()
+        .expect("the repository doesn't exist");
+    let client::Error::Io(err) = err else {
+        panic!("expected an IO error, got {err:?}")
+    };
+    let message = err.to_string();
+    assert!(
+        message.contains("failed with exit status 128") && message.contains("/does/not/exist"),
+        "{message}"
+    );
+    Ok(())
+}
+
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn unknown_hosts_are_rejected_by_default() -> Result {
+    let fixture = Fixture::new(|_| {})?;
+    let mut c = fixture.connect(&repo_path()?, Protocol::V2, fixture.options())?;
+
+    let err = native_ssh_error(
+        c.handshake(Service::UploadPack, &[])
+            .await
+            .err()
+            .expect("host is unknown"),
+    );
+    assert!(
+        matches!(&err, Error::UnknownHostKey { host, algorithm, .. } if *host == fixture.host() && algorithm == "ssh-ed25519"),
+        "{err:?}"
+    );
+    assert!(!fixture.known_hosts_path().exists(), "nothing is written");
+    Ok(())
+}
+
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn unknown_hosts_are_remembered_if_new_ones_are_accepted() -> Result {
+    let fixture = Fixture::new(|_| {})?;
+    let repo = repo_path()?;
+    let mut c = fixture.connect(
+        &repo,
+        Protocol::V2,
+        Options {
+            strict_host_key_checking: StrictHostKeyChecking::AcceptNew,
+            ..fixture.options()
+        },
+    )?;
+    c.handshake(Service::UploadPack, &[]).await?;
+
+    assert_eq!(
+        std::fs::read_to_string(fixture.known_hosts_path())?,
+        format!("{} {}\n", fixture.host(), fixture.host_key.to_openssh()?),
+        "the directory is created and the key is added without comment"
+    );
+
+    let mut c = fixture.connect(&repo, Protocol::V2, fixture.options())?;
+    c.handshake(Service::UploadPack, &[]).await?;
+    Ok(())
+}
+
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn changed_host_keys_are_rejected_even_if_new_ones_are_accepted() -> Result {
+    let fixture = Fixture::new(|_| {})?;
+    let other_key = server::ed25519_key();
+    fixture.known_hosts(&format!("{} {}", fixture.host(), other_key.public_key().to_openssh()?))?;
+    let mut c = fixture.connect(
+        &repo_path()?,
+        Protocol::V2,
+        Options {
+            strict_host_key_checking: StrictHostKeyChecking::AcceptNew,
+            ..fixture.options()
+        },
+    )?;
+
+    let err = native_ssh_error(c.handshake(Service::UploadPack, &[]).await.err().expect("key changed"));
+    assert!(
+        matches!(&err, Error::HostKeyMismatch { path, .. } if *path == fixture.known_hosts_path()),
+        "{err:?}"
+    );
+    Ok(())
+}
+
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn hashed_host_names_are_matched() -> Result {
+    let fixture = Fixture::new(|_| {})?;
+    let salt = b"0123456789abcdefghij";
+    let mut mac = hmac::Hmac::<sha1::Sha1>::new_from_slice(salt)?;
+    mac.update(fixture.host().as_bytes());
+    let hash = mac.finalize().into_bytes();
+    let engine = base64::engine::general_purpose::STANDARD;
+    fixture.known_hosts(&format!(
+        "|1|{}|{} {}",
+        engine.encode(salt),
+        engine.encode(hash),
+        fixture.host_key.to_openssh()?
+    ))?;
+    fixture
+        .connect(&repo_path()?, Protocol::V2, fixture.options())?
+        .handshake(Service::UploadPack, &[])
+        .await?;
+    Ok(())
+}
+
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn keys_of_other_types_are_neither_known_nor_changed() -> Result {
+    let fixture = Fixture::new(|_| {})?;
+    fixture.known_hosts(&format!(
+        "{} ecdsa-sha2-nistp256 AAAAE2VjZHNhLXNoYTItbmlzdHAyNTYAAAAIbmlzdHAyNTYAAABBBEmKSENjQEezOmxkZMy7opKgwFB9nkt5YRrYMjNuG5N87uRgg6CLrbo5wAdT/y6v0mKV0U2w0WZ2YB/++Tpockg=",
+        fixture.host()
+    ))?;
+    let err = native_ssh_error(
+        fixture
+            .connect(&repo_path()?, Protocol::V2, fixture.options())?
+            .handshake(Service::UploadPack, &[])
+            .await
+            .err()
+            .expect("host is unknown"),
+    );
+    assert!(matches!(err, Error::UnknownHostKey { .. }), "{err:?}");
+    Ok(())
+}
+
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn passwords_can_be_provided_as_identity_after_keys_are_refused() -> Result {
+    let fixture = Fixture::new(|config| {
+        config.authorized_keys.clear();
+        config.password = Some("secret");
+    })?;
+    fixture.trust_host()?;
+    let mut c = fixture.connect(&repo_path()?, Protocol::V2, fixture.options())?;
+
+    let err = c
+        .handshake(Service::UploadPack, &[])
+        .await
+        .err()
+        .expect("the key isn't authorized");
+    assert!(
+        matches!(&err, client::Error::Io(err) if err.kind() == std::io::ErrorKind::PermissionDenied),
+        "this is how the higher layers know to obtain credentials, got {err:?}"
+    );
+
+    client::TransportWithoutIO::set_identity(
+        &mut c,
+        client::Account {
+            username: "alice".into(),
+            password: "secret".into(),
+        },
+    )?;
+    c.handshake(Service::UploadPack, &[]).await?;
+    assert_eq!(
+        fixture
+            .log()
+            .iter()
+            .filter(|event| event.starts_with("auth "))
+            .collect::<Vec<_>>(),
+        ["auth password git"],
+        "the user in the URL takes precedence"
+    );
+    Ok(())
+}
+
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn permission_is_denied_without_acceptable_keys() -> Result {
+    let fixture = Fixture::new(|config| config.authorized_keys.clear())?;
+    fixture.trust_host()?;
+    let mut c = fixture.connect(&repo_path()?, Protocol::V2, fixture.options())?;
+
+    let err = native_ssh_error(
+        c.handshake(Service::UploadPack, &[])
+            .await
+            .err()
+            .expect("not authorized"),
+    );
+    assert!(
+        matches!(
+            &err,
+            Error::PermissionDenied {
+                password_allowed: false,
+                methods,
+                user,
+                ..
+            } if methods == "publickey" && user == "git"
+        ),
+        "{err:?}"
+    );
+    Ok(())
+}
+
+#[cfg(unix)]
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn keys_of_the_agent_are_used() -> Result {
+    let fixture = Fixture::new(|_| {})?;
+    fixture.trust_host()?;
+    let socket = fixture.dir.path().join("agent.sock");
+    agent::spawn(&socket, fixture.client_key.clone())?;
+    // No other test uses the agent, so they aren't affected by this.
+    std::env::set_var("SSH_AUTH_SOCK", &socket);
+    let mut c = fixture.connect(
+        &repo_path()?,
+        Protocol::V2,
+        Options {
+            identity_files: vec![fixture.dir.path().join("missing")],
+            disable_agent: false,
+            ..fixture.options()
+        },
+    )?;
+
+    c.handshake(Service::UploadPack, &[]).await?;
+    assert_eq!(fixture.log()[0], "auth publickey git");
+    Ok(())
+}
+
+/// An ssh-agent holding a single key.
+#[cfg(unix)]
+mod agent {
+    use std::path::Path;
+
+    use russh::keys::{agent, PrivateKey};
+
+    pub fn spawn(socket: &Path, key: PrivateKey) -> std::io::Result<()> {
+        let listener = std::os::unix::net::UnixListener::bind(socket)?;
+        listener.set_nonblocking(true)?;
+        let socket = socket.to_owned();
+        let (added_tx, added_rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            super::server::runtime().block_on(async move {
+                let listener = tokio::net::UnixListener::from_std(listener).expect("valid listener");
+                let connections = Box::pin(futures_lite::stream::unfold(listener, |listener| async move {
+                    let connection = listener.accept().await.map(|(stream, _addr)| stream);
+                    Some((connection, listener))
+                }));
+                tokio::spawn(async move {
+                    let mut client = agent::client::AgentClient::connect_uds(socket).await?;
+                    client.add_identity(&key, &[]).await?;
+                    added_tx.send(()).ok();
+                    Ok::<_, russh::keys::Error>(())
+                });
+                agent::server::serve(connections, ()).await
+            })
+        });
+        added_rx
+            .recv_timeout(std::time::Duration::from_secs(10))
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))
+    }
+}
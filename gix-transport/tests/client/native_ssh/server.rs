@@ -0,0 +1,243 @@
+//! An SSH server standing in for `sshd`, which runs `git upload-pack` for the commands it's asked to execute.
+use std::{
+    process::Stdio,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use russh::{
+    keys::{ssh_key::rand_core::OsRng, Algorithm, PrivateKey, PublicKey},
+    server::{self, Auth, Msg, Session},
+    Channel, ChannelId, CryptoVec, MethodKind, MethodSet,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    process::{ChildStdin, Command},
+};
+
+pub struct Config {
+    pub host_key: PrivateKey,
+    pub authorized_keys: Vec<PublicKey>,
+    pub password: Option<&'static str>,
+    /// The window the client gets to send data to us, which is small to make it wait for adjustments.
+    pub window: u32,
+}
+
+/// A record of what the server was asked to do, one line per event.
+pub type Log = Arc<Mutex<Vec<String>>>;
+
+/// Start serving connections according to `config` on a local port, and return it along with the event log.
+pub fn spawn(config: Config) -> (u16, Log) {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("can bind to a local port");
+    listener.set_nonblocking(true).expect("can be configured");
+    let port = listener.local_addr().expect("bound").port();
+    let log = Log::default();
+    let mut methods = MethodSet::from(&[MethodKind::PublicKey][..]);
+    if config.password.is_some() {
+        methods.push(MethodKind::Password);
+    }
+    let server_config = Arc::new(server::Config {
+        methods,
+        auth_rejection_time: Duration::ZERO,
+        auth_rejection_time_initial: Some(Duration::ZERO),
+        keys: vec![config.host_key.clone()],
+        window_size: config.window,
+        ..Default::default()
+    });
+    let mut server = Server {
+        config: Arc::new(config),
+        log: log.clone(),
+    };
+    std::thread::spawn(move || {
+        runtime().block_on(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).expect("valid listener");
+            server::Server::run_on_socket(&mut server, server_config, &listener).await
+        })
+    });
+    (port, log)
+}
+
+/// Generate a new ed25519 key.
+pub fn ed25519_key() -> PrivateKey {
+    PrivateKey::random(&mut OsRng, Algorithm::Ed25519).expect("can generate keys")
+}
+
+/// A runtime for servers and agents running on their own thread.
+pub fn runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .expect("can create runtime")
+}
+
+struct Server {
+    config: Arc<Config>,
+    log: Log,
+}
+
+impl server::Server for Server {
+    type Handler = Connection;
+
+    fn new_client(&mut self, _peer_addr: Option<std::net::SocketAddr>) -> Self::Handler {
+        Connection {
+            config: self.config.clone(),
+            log: self.log.clone(),
+            git_protocol: None,
+            stdin: None,
+        }
+    }
+}
+
+struct Connection {
+    config: Arc<Config>,
+    log: Log,
+    git_protocol: Option<String>,
+    stdin: Option<ChildStdin>,
+}
+
+impl Connection {
+    fn log(&self, event: String) {
+        self.log.lock().unwrap().push(event);
+    }
+
+    fn is_authorized(&self, key: &PublicKey) -> bool {
+        self.config
+            .authorized_keys
+            .iter()
+            .any(|authorized| authorized.key_data() == key.key_data())
+    }
+
+    fn accept(&self, method: &str, user: &str) -> Auth {
+        self.log(format!("auth {method} {user}"));
+        Auth::Accept
+    }
+}
+
+type Error = Box<dyn std::error::Error + Send + Sync>;
+
+impl server::Handler for Connection {
+    type Error = russh::Error;
+
+    async fn auth_publickey_offered(&mut self, _user: &str, key: &PublicKey) -> Result<Auth, Self::Error> {
+        Ok(if self.is_authorized(key) {
+            Auth::Accept
+        } else {
+            Auth::reject()
+        })
+    }
+
+    /// Called once the signature made with `key` was verified.
+    async fn auth_publickey(&mut self, user: &str, key: &PublicKey) -> Result<Auth, Self::Error> {
+        Ok(if self.is_authorized(key) {
+            self.accept("publickey", user)
+        } else {
+            Auth::reject()
+        })
+    }
+
+    async fn auth_password(&mut self, user: &str, password: &str) -> Result<Auth, Self::Error> {
+        Ok(if self.config.password == Some(password) {
+            self.accept("password", user)
+        } else {
+            Auth::reject()
+        })
+    }
+
+    async fn channel_open_session(
+        &mut self,
+        _channel: Channel<Msg>,
+        _session: &mut Session,
+    ) -> Result<bool, Self::Error> {
+        Ok(true)
+    }
+
+    async fn env_request(
+        &mut self,
+        _channel: ChannelId,
+        name: &str,
+        value: &str,
+        _session: &mut Session,
+    ) -> Result<(), Self::Error> {
+        self.log(format!("env {name}={value}"));
+        self.git_protocol = (name == "GIT_PROTOCOL").then(|| value.to_owned());
+        Ok(())
+    }
+
+    async fn exec_request(
+        &mut self,
+        channel: ChannelId,
+        data: &[u8],
+        session: &mut Session,
+    ) -> Result<(), Self::Error> {
+        let command = String::from_utf8_lossy(data).into_owned();
+        self.log(format!("exec {command}"));
+        match self.spawn(&command, channel, session) {
+            Ok(()) => session.channel_success(channel)?,
+            Err(err) => {
+                self.log(format!("error {err}"));
+                session.channel_failure(channel)?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn data(&mut self, _channel: ChannelId, data: &[u8], _session: &mut Session) -> Result<(), Self::Error> {
+        if let Some(stdin) = self.stdin.as_mut() {
+            stdin.write_all(data).await?;
+        }
+        Ok(())
+    }
+
+    async fn channel_eof(&mut self, _channel: ChannelId, _session: &mut Session) -> Result<(), Self::Error> {
+        self.stdin = None;
+        Ok(())
+    }
+}
+
+impl Connection {
+    /// Run the git program in `command`, with its output sent to the client through `channel`.
+    fn spawn(&mut self, command: &str, channel: ChannelId, session: &mut Session) -> Result<(), Error> {
+        let (program, path) = command.split_once(' ').ok_or("command without arguments")?;
+        let path = path
+            .strip_prefix('\'')
+            .and_then(|path| path.strip_suffix('\''))
+            .ok_or("path isn't quoted")?;
+        let mut cmd = Command::new("git");
+        cmd.arg(program.strip_prefix("git-").ok_or("not a git command")?)
+            .arg(path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if let Some(value) = self.git_protocol.take() {
+            cmd.env("GIT_PROTOCOL", value);
+        }
+        let mut child = cmd.spawn()?;
+        self.stdin = child.stdin.take();
+
+        let handle = session.handle();
+        tokio::spawn(async move {
+            let mut stdout = child.stdout.take().expect("piped");
+            let mut buf = [0; 8192];
+            loop {
+                let len = stdout.read(&mut buf).await?;
+                if len == 0 {
+                    break;
+                }
+                if handle.data(channel, CryptoVec::from_slice(&buf[..len])).await.is_err() {
+                    return Ok(());
+                }
+            }
+            let mut stderr = Vec::new();
+            child.stderr.take().expect("piped").read_to_end(&mut stderr).await?;
+            if !stderr.is_empty() {
+                handle.extended_data(channel, 1, CryptoVec::from(stderr)).await.ok();
+            }
+            let status = child.wait().await?.code().unwrap_or(255) as u32;
+            handle.exit_status_request(channel, status).await.ok();
+            handle.eof(channel).await.ok();
+            handle.close(channel).await.ok();
+            Ok::<_, std::io::Error>(())
+        });
+        Ok(())
+    }
+}
@@ -157,9 +157,10 @@ where
             let commit = find(w.commit_graph.as_ref(), &w.find, id, &mut w.buf)?;
             let (_, time) = gen_and_commit_time(commit)?;
             let parent_ids = w.collect_all_parents(id)?.into_iter().map(|e| e.0).collect();
+            let queue_time = w.queue_time(id, time)?;
 
             w.topo_queue.push(
-                time,
+                queue_time,
                 Info {
                     id: *id,
                     parent_ids,
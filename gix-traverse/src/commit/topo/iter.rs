@@ -1,6 +1,7 @@
 use crate::commit::topo::{Error, Sorting, WalkFlags};
 use crate::commit::{find, Either, Info, Parents, Topo};
 use gix_hash::{oid, ObjectId};
+use gix_object::FindExt;
 use gix_revwalk::PriorityQueue;
 use smallvec::SmallVec;
 
@@ -13,6 +14,7 @@ pub(in crate::commit) type GenAndCommitTime = (u32, i64);
 #[derive(Debug)]
 pub(in crate::commit) enum Queue {
     Date(PriorityQueue<i64, Info>),
+    AuthorDate(PriorityQueue<i64, Info>),
     Topo(Vec<(i64, Info)>),
 }
 
@@ -20,20 +22,22 @@ impl Queue {
     pub(super) fn new(s: Sorting) -> Self {
         match s {
             Sorting::DateOrder => Self::Date(PriorityQueue::new()),
+            Sorting::AuthorDateOrder => Self::AuthorDate(PriorityQueue::new()),
             Sorting::TopoOrder => Self::Topo(vec![]),
         }
     }
 
-    pub(super) fn push(&mut self, commit_time: i64, info: Info) {
+    /// Push `info` with `time` as priority, which is the author time for [`Queue::AuthorDate`] and the commit time otherwise.
+    pub(super) fn push(&mut self, time: i64, info: Info) {
         match self {
-            Self::Date(q) => q.insert(commit_time, info),
-            Self::Topo(q) => q.push((commit_time, info)),
+            Self::Date(q) | Self::AuthorDate(q) => q.insert(time, info),
+            Self::Topo(q) => q.push((time, info)),
         }
     }
 
     fn pop(&mut self) -> Option<Info> {
         match self {
-            Self::Date(q) => q.pop().map(|(_, info)| info),
+            Self::Date(q) | Self::AuthorDate(q) => q.pop().map(|(_, info)| info),
             Self::Topo(q) => q.pop().map(|(_, info)| info),
         }
     }
@@ -129,8 +133,9 @@ where
             }
 
             let parent_ids = self.collect_all_parents(&pid)?.into_iter().map(|e| e.0).collect();
+            let time = self.queue_time(&pid, parent_commit_time)?;
             self.topo_queue.push(
-                parent_commit_time,
+                time,
                 Info {
                     id: pid,
                     parent_ids,
@@ -142,6 +147,14 @@ where
         Ok(())
     }
 
+    /// Return the time to queue the commit with `id` and `commit_time` with, which is its author time if the queue is sorted by it.
+    pub(super) fn queue_time(&mut self, id: &oid, commit_time: i64) -> Result<i64, Error> {
+        Ok(match self.topo_queue {
+            Queue::AuthorDate(_) => self.find.find_commit_iter(id, &mut self.buf)?.author()?.time.seconds,
+            Queue::Date(_) | Queue::Topo(_) => commit_time,
+        })
+    }
+
     fn process_parents(&mut self, id: &oid, parents: &[(ObjectId, GenAndCommitTime)]) -> Result<(), Error> {
         let state = self.states.get_mut(id).ok_or(Error::MissingStateUnexpected)?;
         if state.contains(WalkFlags::Added) {
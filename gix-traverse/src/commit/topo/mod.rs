@@ -63,6 +63,12 @@ pub enum Sorting {
     /// In the *sample history* the order would be `8, 6, 5, 3, 7, 4, 2, 1`.
     /// This is equivalent to `git rev-list --topo-order`.
     TopoOrder,
+    /// Show no parents before all of its children are shown, but otherwise show
+    /// commits in the author timestamp order.
+    ///
+    /// Note that author timestamps aren't stored in commit-graphs, so each commit is read from the object database.
+    /// This is equivalent to `git rev-list --author-date-order`.
+    AuthorDateOrder,
 }
 
 mod init;
@@ -0,0 +1,188 @@
+pub use gix_apply::*;
+
+use crate::bstr::BString;
+
+/// Determine what [Repository::apply_patches()](crate::Repository::apply_patches()) changes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Location {
+    /// Change only the files in the worktree, similar to `git apply`.
+    Worktree,
+    /// Change only the index, similar to `git apply --cached`.
+    Index,
+    /// Change the files in the worktree and update the index accordingly, similar to `git apply --index`.
+    ///
+    /// The patches are applied to the files in the worktree, which are assumed to match the index.
+    WorktreeAndIndex,
+}
+
+/// The outcome of [Repository::apply_patches()](crate::Repository::apply_patches()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// All hunks that couldn't be applied, if [`Options::reject`] is enabled.
+    ///
+    /// When applying to the worktree, they have also been written into `<path>.rej` files.
+    pub rejected: Vec<Rejected>,
+    /// All paths that were merged with conflicts, if [`Options::three_way`] is enabled.
+    pub conflicts: Vec<BString>,
+}
+
+///
+pub mod format_patch {
+    use crate::bstr::BString;
+
+    /// The error returned by [Repository::format_patch()](crate::Repository::format_patch()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Walk(#[from] crate::revision::walk::Error),
+        #[error(transparent)]
+        WalkCommits(#[from] crate::revision::walk::iter::Error),
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        CommitTree(#[from] crate::object::commit::Error),
+        #[error(transparent)]
+        Rewrites(#[from] crate::diff::new_rewrites::Error),
+        #[error(transparent)]
+        DiffTrees(#[from] crate::object::tree::diff::for_each::Error),
+        #[error("Committer identity is not configured, but it's needed for the cover letter")]
+        CommitterMissing,
+        #[error(transparent)]
+        Time(#[from] crate::config::time::Error),
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+
+    /// Options for [Repository::format_patch()](crate::Repository::format_patch()).
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Options {
+        /// The text in brackets before the subject of each mail, similar to `git format-patch --subject-prefix`.
+        ///
+        /// Defaults to `PATCH`.
+        pub subject_prefix: BString,
+        /// If `Some(true)`, number the patches like `[PATCH 1/2]`, or don't if `Some(false)`, similar to
+        /// `git format-patch --numbered` and `--no-numbered`.
+        ///
+        /// If `None`, the default, patches are numbered if there is more than one, or if there is a cover letter.
+        pub numbered: Option<bool>,
+        /// The number of the first patch, similar to `git format-patch --start-number`.
+        ///
+        /// Defaults to 1.
+        pub start_number: usize,
+        /// If `true`, write a cover letter with a shortlog and a diffstat of the whole series before the patches,
+        /// similar to `git format-patch --cover-letter`.
+        pub cover_letter: bool,
+        /// If `true`, the default, write a diffstat between the commit message and the patch.
+        pub stat: bool,
+        /// The signature to write after a `-- ` line at the end of each mail, similar to `git format-patch --signature`,
+        /// or `None` to write no signature.
+        ///
+        /// Defaults to the name and version of this crate.
+        pub signature: Option<BString>,
+    }
+
+    impl Default for Options {
+        fn default() -> Self {
+            Options {
+                subject_prefix: "PATCH".into(),
+                numbered: None,
+                start_number: 1,
+                cover_letter: false,
+                stat: true,
+                signature: Some(format!("gix {}", env!("CARGO_PKG_VERSION")).into()),
+            }
+        }
+    }
+
+    /// A mail as produced by [Repository::format_patch()](crate::Repository::format_patch()).
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Email {
+        /// The commit the patch in the mail was created from, or `None` if this is the cover letter.
+        pub commit: Option<gix_hash::ObjectId>,
+        /// The name of the file `git format-patch` would write the mail to, like `0001-fix-the-bug.patch`.
+        pub file_name: String,
+        /// The complete mail, starting with its `From <id> Mon Sep 17 00:00:00 2001` line.
+        ///
+        /// The data of all mails can be concatenated to produce a mailbox.
+        pub data: BString,
+    }
+}
+
+///
+pub mod apply_mailbox {
+    use crate::bstr::BString;
+
+    /// The error returned by [Repository::apply_mailbox()](crate::Repository::apply_mailbox()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not parse mail {index}")]
+        ParseMail {
+            index: usize,
+            source: gix_apply::mailbox::parse::Error,
+        },
+        #[error("Could not parse the patch in mail {index}: {subject}")]
+        ParsePatch {
+            index: usize,
+            subject: BString,
+            source: gix_apply::patch::parse::Error,
+        },
+        #[error("The patch in mail {index} does not apply: {subject}")]
+        Apply {
+            index: usize,
+            subject: BString,
+            source: gix_apply::tree::Error,
+        },
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        CommitTree(#[from] crate::object::commit::Error),
+        #[error("Committer identity is not configured")]
+        CommitterMissing,
+        #[error(transparent)]
+        Time(#[from] crate::config::time::Error),
+        #[error(transparent)]
+        WriteObject(#[from] crate::object::write::Error),
+    }
+
+    /// Options for [Repository::apply_mailbox()](crate::Repository::apply_mailbox()).
+    #[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+    pub struct Options {
+        /// Options to control how mails are parsed.
+        pub mail: gix_apply::mailbox::parse::Options,
+        /// Options to control how the patches in the mails are parsed.
+        pub patch: gix_apply::patch::parse::Options,
+        /// Options to control how the patches are applied.
+        pub apply: gix_apply::Options,
+        /// If `true`, add a `Signed-off-by` trailer with the committer to each commit message,
+        /// similar to `git am --signoff`.
+        pub signoff: bool,
+    }
+
+    /// The outcome of [Repository::apply_mailbox()](crate::Repository::apply_mailbox()).
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[allow(clippy::large_enum_variant)]
+    pub enum Outcome {
+        /// All patches were applied and a commit was created for each of them, in order.
+        Applied {
+            /// The ids of all created commits, the last of which is the new tip.
+            commits: Vec<gix_hash::ObjectId>,
+        },
+        /// The patch in a mail left conflicts or rejected hunks, and no commit was created for it.
+        Conflict {
+            /// The ids of the commits that were created for the mails before the one that didn't apply cleanly.
+            commits: Vec<gix_hash::ObjectId>,
+            /// The 0-based index of the mail whose patch didn't apply cleanly.
+            index: usize,
+            /// The parsed mail, which can be used to create the commit once the conflicts are resolved.
+            mail: gix_apply::mailbox::Mail,
+            /// The tree with the patch applied as far as possible, along with conflicting paths and rejected hunks.
+            outcome: gix_apply::tree::Outcome,
+        },
+    }
+}
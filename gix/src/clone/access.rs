@@ -30,6 +30,8 @@ impl PrepareFetch {
     }
 
     /// Make this clone a shallow one with the respective choice of shallow-ness.
+    ///
+    /// Unless the remote is configured otherwise, only tags pointing into the received history will be fetched then.
     pub fn with_shallow(mut self, shallow: crate::remote::fetch::Shallow) -> Self {
         self.shallow = shallow;
         self
@@ -37,6 +37,12 @@ pub enum Error {
     },
     #[error("Failed to update HEAD with values from remote")]
     HeadUpdate(#[from] crate::reference::edit::Error),
+    #[cfg(any(
+        feature = "blocking-http-transport-reqwest",
+        feature = "blocking-http-transport-curl"
+    ))]
+    #[error("Could not learn if bundles advertised by the remote should be used")]
+    BundleUriConfig(#[from] crate::config::boolean::Error),
     #[error("The remote didn't have any ref that matched '{}'", wanted.as_ref().as_bstr())]
     RefNameMissing { wanted: gix_ref::PartialName },
     #[error("The remote has {} refs for '{}', try to use a specific name: {}", candidates.len(), wanted.as_ref().as_bstr(), candidates.iter().filter_map(|n| n.to_str().ok()).collect::<Vec<_>>().join(", "))]
@@ -118,7 +124,13 @@ impl PrepareFetch {
         if let Some(f) = self.configure_remote.as_mut() {
             remote = f(remote).map_err(Error::RemoteConfiguration)?;
         } else {
-            clone_fetch_tags = remote::fetch::Tags::All.into();
+            // Like `git`, shallow clones only follow tags pointing into the history they receive.
+            clone_fetch_tags = if self.shallow == remote::fetch::Shallow::NoChange {
+                remote::fetch::Tags::All
+            } else {
+                remote::fetch::Tags::Included
+            }
+            .into();
         }
 
         let config = util::write_remote_to_local_config_file(&mut remote, remote_name.clone())?;
@@ -198,8 +210,29 @@ impl PrepareFetch {
             b.insert_str(0, "clone: from ");
             b
         };
+        #[cfg(any(
+            feature = "blocking-http-transport-reqwest",
+            feature = "blocking-http-transport-curl"
+        ))]
+        let pending_pack = {
+            use crate::config::{cache::util::ApplyLeniency, tree::Transfer};
+            let bundles_from_remote = repo
+                .config
+                .resolved
+                .boolean(Transfer::BUNDLE_URI)
+                .map(|value| Transfer::BUNDLE_URI.enrich_error(value))
+                .transpose()
+                .with_leniency(repo.config.lenient_config)?
+                .unwrap_or(false);
+            let pending_pack = pending_pack.with_bundles_from_remote(bundles_from_remote);
+            match self.bundle_uri.clone() {
+                Some(uri) => pending_pack.with_bundle_uri(uri),
+                None => pending_pack,
+            }
+        };
         let outcome = pending_pack
             .with_write_packed_refs_only(true)
+            .with_write_fetch_head(false)
             .with_reflog_message(RefLogMessage::Override {
                 message: reflog_message.clone(),
             })
@@ -208,6 +241,13 @@ impl PrepareFetch {
             .await?;
 
         util::append_config_to_repo_config(repo, config);
+        #[cfg(any(
+            feature = "blocking-http-transport-reqwest",
+            feature = "blocking-http-transport-curl"
+        ))]
+        if !outcome.bundles.is_empty() {
+            util::reload_bundle_config(repo)?;
+        }
         util::update_head(
             repo,
             &outcome.ref_map,
@@ -47,6 +47,7 @@ fn write_to_local_config(config: &gix_config::File<'static>, mode: WriteMode) ->
     let mut local_config = std::fs::OpenOptions::new()
         .create(false)
         .write(matches!(mode, WriteMode::Overwrite))
+        .truncate(matches!(mode, WriteMode::Overwrite))
         .append(matches!(mode, WriteMode::Append))
         .open(config.meta().path.as_deref().expect("local config with path set"))?;
     local_config.write_all(config.detect_newline_style())?;
@@ -58,6 +59,32 @@ pub fn append_config_to_repo_config(repo: &mut Repository, config: gix_config::F
     repo_config.append(config);
 }
 
+/// Applying bundles may have stored values in the `fetch` section of the local configuration file, which are added
+/// to the in-memory configuration of `repo` here so they aren't lost when it's written back.
+#[cfg(any(
+    feature = "blocking-http-transport-reqwest",
+    feature = "blocking-http-transport-curl"
+))]
+pub fn reload_bundle_config(repo: &mut Repository) -> Result<(), Error> {
+    use crate::config::tree::{Fetch, Key};
+
+    let meta = local_config_meta(repo);
+    let on_disk = gix_config::File::from_path_no_includes(
+        meta.path.clone().expect("local config with path set"),
+        gix_config::Source::Local,
+    )?;
+    let mut config = gix_config::File::new(meta);
+    for key in [&Fetch::BUNDLE_URI as &dyn Key, &Fetch::BUNDLE_CREATION_TOKEN] {
+        if let Ok(value) = on_disk.raw_value_by("fetch", None, key.name()) {
+            config
+                .set_raw_value_by("fetch", None, key.name(), value.as_ref())
+                .expect("valid at compile time");
+        }
+    }
+    append_config_to_repo_config(repo, config);
+    Ok(())
+}
+
 /// HEAD cannot be written by means of refspec by design, so we have to do it manually here. Also create the pointed-to ref
 /// if we have to, as it might not have been naturally included in the ref-specs.
 /// Lastly, use `ref_name` if it was provided instead, and let `HEAD` point to it.
@@ -37,6 +37,12 @@ pub struct PrepareFetch {
     /// The name of the reference to fetch. If `None`, the reference pointed to by `HEAD` will be checked out.
     #[cfg_attr(not(feature = "blocking-network-client"), allow(dead_code))]
     ref_name: Option<gix_ref::PartialName>,
+    /// The location of a bundle or bundle list to bootstrap the clone with.
+    #[cfg(any(
+        feature = "blocking-http-transport-reqwest",
+        feature = "blocking-http-transport-curl"
+    ))]
+    bundle_uri: Option<BString>,
 }
 
 /// The error returned by [`PrepareFetch::new()`].
@@ -126,6 +132,11 @@ impl PrepareFetch {
             configure_connection: None,
             shallow: remote::fetch::Shallow::NoChange,
             ref_name: None,
+            #[cfg(any(
+                feature = "blocking-http-transport-reqwest",
+                feature = "blocking-http-transport-curl"
+            ))]
+            bundle_uri: None,
         })
     }
 }
@@ -170,6 +181,18 @@ mod access_feat {
             self.fetch_options = opts;
             self
         }
+
+        /// Download the bundle or bundle list at `uri` and apply it before fetching the remainder from the remote.
+        ///
+        /// See [`Prepare::with_bundle_uri()`](crate::remote::fetch::Prepare::with_bundle_uri()) for details.
+        #[cfg(any(
+            feature = "blocking-http-transport-reqwest",
+            feature = "blocking-http-transport-curl"
+        ))]
+        pub fn with_bundle_uri(mut self, uri: impl Into<crate::bstr::BString>) -> Self {
+            self.bundle_uri = Some(uri.into());
+            self
+        }
     }
 }
 
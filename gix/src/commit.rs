@@ -30,7 +30,11 @@ pub mod describe {
     use gix_hash::ObjectId;
     use gix_hashtable::HashMap;
 
-    use crate::{bstr::BStr, ext::ObjectIdExt, Repository};
+    use crate::{
+        bstr::{BStr, BString, ByteSlice},
+        ext::ObjectIdExt,
+        Repository,
+    };
 
     /// The result of [`try_resolve()`][Platform::try_resolve()].
     pub struct Resolution<'repo> {
@@ -57,14 +61,35 @@ pub mod describe {
         pub fn format_with_dirty_suffix(
             self,
             dirty_suffix: impl Into<Option<String>>,
+        ) -> Result<gix_revision::describe::Format<'static>, Error> {
+            self.format_with_dirty_or_broken_suffix(dirty_suffix, None)
+        }
+
+        /// Like [`format_with_dirty_suffix()`](Self::format_with_dirty_suffix()), but if `broken_suffix` is `Some(suffix)`,
+        /// failing to determine the dirty-state of the repository isn't an error, but appends `suffix` instead, similar to
+        /// `git describe --broken`.
+        ///
+        /// Just like in `git`, setting `broken_suffix` implies checking for a dirty repository, using `dirty` as suffix
+        /// if no `dirty_suffix` is set.
+        #[cfg(feature = "status")]
+        pub fn format_with_dirty_or_broken_suffix(
+            self,
+            dirty_suffix: impl Into<Option<String>>,
+            broken_suffix: impl Into<Option<String>>,
         ) -> Result<gix_revision::describe::Format<'static>, Error> {
             let prefix = self.id.shorten()?;
-            let mut dirty_suffix = dirty_suffix.into();
-            if dirty_suffix.is_some() && !self.id.repo.is_dirty()? {
-                dirty_suffix.take();
-            }
+            let (dirty_suffix, broken_suffix) = (dirty_suffix.into(), broken_suffix.into());
+            let suffix = if dirty_suffix.is_none() && broken_suffix.is_none() {
+                None
+            } else {
+                match self.id.repo.is_dirty() {
+                    Ok(true) => Some(dirty_suffix.unwrap_or_else(|| "dirty".into())),
+                    Ok(false) => None,
+                    Err(err) => Some(broken_suffix.ok_or(err)?),
+                }
+            };
             let mut format = self.outcome.into_format(prefix.hex_len());
-            format.dirty_suffix = dirty_suffix;
+            format.dirty_suffix = suffix;
             Ok(format)
         }
     }
@@ -77,6 +102,8 @@ pub mod describe {
         OpenCache(#[from] crate::repository::commit_graph_if_enabled::Error),
         #[error(transparent)]
         Describe(#[from] gix_revision::describe::Error),
+        #[error(transparent)]
+        NameRev(#[from] gix_revision::name_rev::Error),
         #[error("Could not produce an unambiguous shortened id for formatting.")]
         ShortId(#[from] crate::id::shorten::Error),
         #[error(transparent)]
@@ -100,8 +127,43 @@ pub mod describe {
         AllRefs,
     }
 
+    /// Patterns to restrict the references that contribute to names.
+    struct Filter<'a> {
+        matching: &'a [BString],
+        excluding: &'a [BString],
+    }
+
+    impl Filter<'_> {
+        /// Return `true` if the reference named `name` may be used, matching patterns against its name without the
+        /// `refs/tags/`, `refs/heads/` or `refs/remotes/` prefix, just like `git describe --match|--exclude` would.
+        /// Other references are only used if there are no patterns.
+        fn is_selected(&self, name: &gix_ref::FullNameRef) -> bool {
+            let name = name.as_bstr();
+            let name_to_match = match name.strip_prefix(b"refs/tags/") {
+                Some(tag) => tag,
+                None if self.matching.is_empty() && self.excluding.is_empty() => return true,
+                None => match name
+                    .strip_prefix(b"refs/heads/")
+                    .or_else(|| name.strip_prefix(b"refs/remotes/"))
+                {
+                    Some(name) => name,
+                    None => return false,
+                },
+            }
+            .as_bstr();
+            let matches = |pattern: &BString| {
+                gix_glob::wildmatch(pattern.as_bstr(), name_to_match, gix_glob::wildmatch::Mode::empty())
+            };
+            !self.excluding.iter().any(matches) && (self.matching.is_empty() || self.matching.iter().any(matches))
+        }
+    }
+
     impl SelectRef {
-        fn names(&self, repo: &Repository) -> Result<HashMap<ObjectId, Cow<'static, BStr>>, Error> {
+        fn names(
+            &self,
+            repo: &Repository,
+            filter: &Filter<'_>,
+        ) -> Result<HashMap<ObjectId, Cow<'static, BStr>>, Error> {
             let platform = repo.references()?;
 
             Ok(match self {
@@ -112,6 +174,7 @@ pub mod describe {
                         _ => unreachable!(),
                     }
                     .filter_map(Result::ok)
+                    .filter(|r| filter.is_selected(r.name()))
                     .filter_map(|mut r: crate::Reference<'_>| {
                         let target_id = r.target().try_id().map(ToOwned::to_owned);
                         let peeled_id = r.peel_to_id_in_place().ok()?;
@@ -147,6 +210,7 @@ pub mod describe {
                     let mut peeled_commits_and_tag_date: Vec<_> = platform
                         .tags()?
                         .filter_map(Result::ok)
+                        .filter(|r| filter.is_selected(r.name()))
                         .filter_map(|r: crate::Reference<'_>| {
                             // TODO: we assume direct refs for tags, which is the common case, but it doesn't have to be
                             //       so rather follow symrefs till the first object and then peel tags after the first object was found.
@@ -168,6 +232,25 @@ pub mod describe {
                 }
             })
         }
+
+        /// Return the tips to propagate names from for `git describe --contains`, which always uses lightweight tags as well
+        /// as it is implemented by `git name-rev --tags`.
+        fn tips(
+            &self,
+            repo: &Repository,
+            filter: &Filter<'_>,
+        ) -> Result<Vec<gix_revision::name_rev::Tip<'static>>, Error> {
+            let platform = repo.references()?;
+            let refs = match self {
+                SelectRef::AllRefs => platform.all()?,
+                SelectRef::AllTags | SelectRef::AnnotatedTags => platform.tags()?,
+            };
+            Ok(crate::revision::name_rev::tips(
+                repo,
+                refs.filter_map(Result::ok).filter(|r| filter.is_selected(r.name())),
+                |name| Cow::from(name.shorten().to_owned()),
+            ))
+        }
     }
 
     /// A support type to allow configuring a `git describe` operation
@@ -178,6 +261,13 @@ pub mod describe {
         pub(crate) first_parent: bool,
         pub(crate) id_as_fallback: bool,
         pub(crate) max_candidates: usize,
+        pub(crate) contains: bool,
+        pub(crate) matching: Vec<BString>,
+        pub(crate) excluding: Vec<BString>,
+        #[cfg(feature = "status")]
+        pub(crate) dirty_suffix: Option<String>,
+        #[cfg(feature = "status")]
+        pub(crate) broken_suffix: Option<String>,
     }
 
     impl<'repo> Platform<'repo> {
@@ -205,10 +295,70 @@ pub mod describe {
             self
         }
 
+        /// If true, find the reference that *contains* the commit instead of the one that is contained in it, producing
+        /// names like `v1.0~2^2` just like `git describe --contains`.
+        ///
+        /// As in `git`, lightweight tags are always considered when naming by tags, and the traversal stops early at
+        /// commits that can't contain the commit to describe, which is most effective if a commit-graph with
+        /// generation numbers is available. [`Self::traverse_first_parent()`] and [`Self::max_candidates()`] are ignored,
+        /// and produced names shouldn't be displayed in [long](gix_revision::describe::Format::long()) form.
+        pub fn contains(mut self, contains: bool) -> Self {
+            self.contains = contains;
+            self
+        }
+
+        /// Only consider references whose name matches any of the given glob `patterns`, like `git describe --match`.
+        ///
+        /// Patterns are matched against tag names without the `refs/tags/` prefix, and when [all references](SelectRef::AllRefs)
+        /// are used, against branch names without `refs/heads/` or `refs/remotes/` as well. No other references are
+        /// considered if patterns are set.
+        pub fn matching(mut self, patterns: impl IntoIterator<Item = impl Into<BString>>) -> Self {
+            self.matching = patterns.into_iter().map(Into::into).collect();
+            self
+        }
+
+        /// Do not consider references whose name matches any of the given glob `patterns`, like `git describe --exclude`.
+        ///
+        /// See [`Self::matching()`] for details on how references are matched.
+        pub fn excluding(mut self, patterns: impl IntoIterator<Item = impl Into<BString>>) -> Self {
+            self.excluding = patterns.into_iter().map(Into::into).collect();
+            self
+        }
+
+        /// If `Some(suffix)`, append `suffix` to the formatted name if the repository is dirty, like `git describe --dirty`.
+        ///
+        /// Note that this only makes sense when describing the `HEAD` commit, and that the check can be expensive.
+        /// It has no effect if [`Self::contains()`] is set.
+        #[cfg(feature = "status")]
+        pub fn dirty_suffix(mut self, suffix: impl Into<Option<String>>) -> Self {
+            self.dirty_suffix = suffix.into();
+            self
+        }
+
+        /// If `Some(suffix)`, append `suffix` to the formatted name if it can't be determined if the repository is dirty,
+        /// instead of failing, like `git describe --broken`. This implies checking for a dirty repository
+        /// with the [`dirty_suffix`](Self::dirty_suffix()) defaulting to `dirty`.
+        ///
+        /// It has no effect if [`Self::contains()`] is set.
+        #[cfg(feature = "status")]
+        pub fn broken_suffix(mut self, suffix: impl Into<Option<String>>) -> Self {
+            self.broken_suffix = suffix.into();
+            self
+        }
+
         /// Try to find a name for the configured commit id using all prior configuration, returning `Some(describe::Format)`
         /// if one was found, or `None` if that wasn't the case.
         pub fn try_format(&self) -> Result<Option<gix_revision::describe::Format<'static>>, Error> {
-            self.try_resolve()?.map(Resolution::format).transpose()
+            let Some(resolution) = self.try_resolve()? else {
+                return Ok(None);
+            };
+            #[cfg(feature = "status")]
+            if !self.contains {
+                return resolution
+                    .format_with_dirty_or_broken_suffix(self.dirty_suffix.clone(), self.broken_suffix.clone())
+                    .map(Some);
+            }
+            resolution.format().map(Some)
         }
 
         /// Try to find a name for the configured commit id using all prior configuration, returning `Some(Outcome)`
@@ -224,12 +374,19 @@ pub mod describe {
             &self,
             cache: Option<&'_ gix_commitgraph::Graph>,
         ) -> Result<Option<Resolution<'repo>>, Error> {
+            let filter = Filter {
+                matching: &self.matching,
+                excluding: &self.excluding,
+            };
+            if self.contains {
+                return self.try_resolve_contains(cache, &filter);
+            }
             let mut graph = self.repo.revision_graph(cache);
             let outcome = gix_revision::describe(
                 &self.id,
                 &mut graph,
                 gix_revision::describe::Options {
-                    name_by_oid: self.select.names(self.repo)?,
+                    name_by_oid: self.select.names(self.repo, &filter)?,
                     fallback_to_oid: self.id_as_fallback,
                     first_parent: self.first_parent,
                     max_candidates: self.max_candidates,
@@ -242,6 +399,34 @@ pub mod describe {
             }))
         }
 
+        fn try_resolve_contains(
+            &self,
+            cache: Option<&'_ gix_commitgraph::Graph>,
+            filter: &Filter<'_>,
+        ) -> Result<Option<Resolution<'repo>>, Error> {
+            let mut graph = self.repo.revision_graph(cache);
+            let outcome = gix_revision::name_rev(
+                &[self.id],
+                self.select.tips(self.repo, filter)?,
+                &mut graph,
+                Default::default(),
+            )?;
+            let name = graph.get(&self.id).map(|name| Cow::Owned(outcome.format(name)));
+            if name.is_none() && !self.id_as_fallback {
+                return Ok(None);
+            }
+            Ok(Some(Resolution {
+                outcome: gix_revision::describe::Outcome {
+                    name,
+                    id: self.id,
+                    depth: 0,
+                    name_by_oid: Default::default(),
+                    commits_seen: outcome.commits_seen,
+                },
+                id: self.id.attach(self.repo),
+            }))
+        }
+
         /// Like [`Self::try_resolve_with_cache()`], but obtains the commitgraph-cache internally for a single use.
         ///
         /// # Performance
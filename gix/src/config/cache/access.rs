@@ -79,6 +79,9 @@ impl Cache {
             if let Some(textconv) = section.value(config::tree::Diff::DRIVER_TEXTCONV.name) {
                 driver.binary_to_text_command = textconv.into_owned().into();
             }
+            if let Some(patterns) = section.value(config::tree::Diff::DRIVER_XFUNCNAME.name) {
+                driver.function_name_patterns = patterns.into_owned().into();
+            }
             if let Some(algorithm) = section.value("algorithm") {
                 driver.algorithm = config::tree::Diff::DRIVER_ALGORITHM
                     .try_into_algorithm(algorithm)
@@ -251,6 +254,71 @@ impl Cache {
         })
     }
 
+    pub(crate) fn trailer_options(
+        &self,
+    ) -> Result<gix_object::commit::message::trailer::Options, config::trailer_options::Error> {
+        use crate::config::tree::Trailer;
+        use gix_object::commit::message::trailer;
+
+        let mut options = trailer::Options::default();
+        for section in self
+            .resolved
+            .sections_by_name("trailer")
+            .into_iter()
+            .flatten()
+            .filter(|s| (self.filter_config_section)(s.meta()))
+        {
+            let placement = |key: &'static config::tree::trailer::Where| {
+                self.apply_leniency(section.value(key.name).map(|v| key.try_into_placement(v)))
+            };
+            let if_exists = |key: &'static config::tree::trailer::IfExists| {
+                self.apply_leniency(section.value(key.name).map(|v| key.try_into_if_exists(v)))
+            };
+            let if_missing = |key: &'static config::tree::trailer::IfMissing| {
+                self.apply_leniency(section.value(key.name).map(|v| key.try_into_if_missing(v)))
+            };
+            let Some(name) = section.header().subsection_name() else {
+                if let Some(separators) = section.value(Trailer::SEPARATORS.name).filter(|v| !v.is_empty()) {
+                    options.separators = separators.into_owned();
+                }
+                if let Some(value) = placement(&Trailer::WHERE)? {
+                    options.placement = value;
+                }
+                if let Some(value) = if_exists(&Trailer::IF_EXISTS)? {
+                    options.if_exists = value;
+                }
+                if let Some(value) = if_missing(&Trailer::IF_MISSING)? {
+                    options.if_missing = value;
+                }
+                continue;
+            };
+
+            let token = match options.tokens.iter().position(|t| t.name == name) {
+                Some(pos) => &mut options.tokens[pos],
+                None => {
+                    options.tokens.push(trailer::Token {
+                        name: name.to_owned(),
+                        ..Default::default()
+                    });
+                    options.tokens.last_mut().expect("just pushed")
+                }
+            };
+            if let Some(key) = section.value(Trailer::TOKEN_KEY.name) {
+                token.key = Some(key.into_owned());
+            }
+            if let Some(value) = placement(&Trailer::TOKEN_WHERE)? {
+                token.placement = Some(value);
+            }
+            if let Some(value) = if_exists(&Trailer::TOKEN_IF_EXISTS)? {
+                token.if_exists = Some(value);
+            }
+            if let Some(value) = if_missing(&Trailer::TOKEN_IF_MISSING)? {
+                token.if_missing = Some(value);
+            }
+        }
+        Ok(options)
+    }
+
     #[cfg(any(feature = "index", feature = "tree-editor"))]
     pub(crate) fn protect_options(&self) -> Result<gix_validate::path::component::Options, config::boolean::Error> {
         const IS_WINDOWS: bool = cfg!(windows);
@@ -455,7 +523,11 @@ impl Cache {
     /// if it actually wants to use the home directory - we don't want to fail prematurely.
     #[cfg(any(
         feature = "blocking-http-transport-reqwest",
-        feature = "blocking-http-transport-curl"
+        feature = "blocking-http-transport-curl",
+        all(
+            feature = "native-ssh",
+            any(feature = "blocking-network-client", feature = "async-network-client")
+        )
     ))]
     pub(crate) fn home_dir(&self) -> Option<PathBuf> {
         home_dir(self.environment)
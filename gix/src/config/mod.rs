@@ -265,6 +265,30 @@ pub mod ssh_connect_options {
     pub struct Error(#[from] super::key::GenericErrorWithValue);
 }
 
+///
+#[cfg(all(
+    feature = "native-ssh",
+    any(feature = "blocking-network-client", feature = "async-network-client")
+))]
+pub mod native_ssh_connect_options {
+    /// The error produced when obtaining configuration for the built-in ssh client.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ConfigValue(#[from] super::key::GenericErrorWithValue),
+        #[error(transparent)]
+        Boolean(#[from] super::boolean::Error),
+        #[error(transparent)]
+        Duration(#[from] super::duration::Error),
+        #[error("Could not interpolate path at key {key:?}")]
+        InterpolatePath {
+            source: gix_config::path::interpolate::Error,
+            key: String,
+        },
+    }
+}
+
 ///
 pub mod key {
     use crate::bstr::BString;
@@ -417,6 +441,12 @@ pub mod remote {
     }
 }
 
+///
+pub mod trailer_options {
+    /// The error produced when obtaining [trailer options](gix_object::commit::message::trailer::Options).
+    pub type Error = super::key::GenericErrorWithValue;
+}
+
 ///
 pub mod time {
     /// The error produced when failing to parse time from configuration.
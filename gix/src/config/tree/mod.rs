@@ -37,6 +37,8 @@ pub(crate) mod root {
         pub const EXTENSIONS: sections::Extensions = sections::Extensions;
         /// The `fetch` section.
         pub const FETCH: sections::Fetch = sections::Fetch;
+        /// The `gc` section.
+        pub const GC: sections::Gc = sections::Gc;
         /// The `gitoxide` section.
         pub const GITOXIDE: sections::Gitoxide = sections::Gitoxide;
         /// The `http` section.
@@ -51,6 +53,9 @@ pub(crate) mod root {
         pub const PACK: sections::Pack = sections::Pack;
         /// The `protocol` section.
         pub const PROTOCOL: sections::Protocol = sections::Protocol;
+        /// The `pull` section.
+        #[cfg(feature = "pull")]
+        pub const PULL: sections::Pull = sections::Pull;
         /// The `push` section.
         pub const PUSH: sections::Push = sections::Push;
         /// The `remote` section.
@@ -62,6 +67,10 @@ pub(crate) mod root {
         /// The `status` section.
         #[cfg(feature = "status")]
         pub const STATUS: sections::Status = sections::Status;
+        /// The `trailer` section.
+        pub const TRAILER: sections::Trailer = sections::Trailer;
+        /// The `transfer` section.
+        pub const TRANSFER: sections::Transfer = sections::Transfer;
         /// The `user` section.
         pub const USER: sections::User = sections::User;
         /// The `url` section.
@@ -81,6 +90,7 @@ pub(crate) mod root {
                 &Self::DIFF,
                 &Self::EXTENSIONS,
                 &Self::FETCH,
+                &Self::GC,
                 &Self::GITOXIDE,
                 &Self::HTTP,
                 &Self::INDEX,
@@ -88,12 +98,16 @@ pub(crate) mod root {
                 &Self::MAILMAP,
                 &Self::PACK,
                 &Self::PROTOCOL,
+                #[cfg(feature = "pull")]
+                &Self::PULL,
                 &Self::PUSH,
                 &Self::REMOTE,
                 &Self::SAFE,
                 &Self::SSH,
                 #[cfg(feature = "status")]
                 &Self::STATUS,
+                &Self::TRAILER,
+                &Self::TRANSFER,
                 &Self::USER,
                 &Self::URL,
             ]
@@ -103,12 +117,14 @@ pub(crate) mod root {
 
 mod sections;
 pub use sections::{
-    branch, checkout, core, credential, extensions, fetch, gitoxide, http, index, protocol, push, remote, ssh, Author,
-    Branch, Checkout, Clone, Committer, Core, Credential, Extensions, Fetch, Gitoxide, Http, Index, Init, Mailmap,
-    Pack, Protocol, Push, Remote, Safe, Ssh, Url, User,
+    branch, checkout, core, credential, extensions, fetch, gc, gitoxide, http, index, protocol, push, remote, ssh,
+    trailer, Author, Branch, Checkout, Clone, Committer, Core, Credential, Extensions, Fetch, Gc, Gitoxide, Http, Index,
+    Init, Mailmap, Pack, Protocol, Push, Remote, Safe, Ssh, Trailer, Transfer, Url, User,
 };
 #[cfg(feature = "blob-diff")]
 pub use sections::{diff, Diff};
+#[cfg(feature = "pull")]
+pub use sections::{pull, Pull};
 #[cfg(feature = "status")]
 pub use sections::{status, Status};
 
@@ -10,6 +10,14 @@ impl Branch {
     pub const PUSH_REMOTE: keys::RemoteName =
         keys::RemoteName::new_remote_name("pushRemote", &crate::config::Tree::BRANCH)
             .with_subsection_requirement(NAME_PARAMETER);
+    /// The `branch.<name>.rebase` key, which overrides `pull.rebase` for the branch.
+    #[cfg(feature = "pull")]
+    pub const REBASE: crate::config::tree::pull::Rebase = crate::config::tree::pull::Rebase::new_with_validate(
+        "rebase",
+        &crate::config::Tree::BRANCH,
+        crate::config::tree::pull::validate::Rebase,
+    )
+    .with_subsection_requirement(NAME_PARAMETER);
     /// The `branch.<name>.remote` key.
     pub const REMOTE: keys::RemoteName = keys::RemoteName::new_remote_name("remote", &crate::config::Tree::BRANCH)
         .with_subsection_requirement(NAME_PARAMETER);
@@ -21,7 +29,13 @@ impl Section for Branch {
     }
 
     fn keys(&self) -> &[&dyn Key] {
-        &[&Self::MERGE, &Self::PUSH_REMOTE, &Self::REMOTE]
+        &[
+            &Self::MERGE,
+            &Self::PUSH_REMOTE,
+            #[cfg(feature = "pull")]
+            &Self::REBASE,
+            &Self::REMOTE,
+        ]
     }
 }
 
@@ -14,6 +14,17 @@ impl Fetch {
     #[cfg(feature = "attributes")]
     pub const RECURSE_SUBMODULES: RecurseSubmodules =
         RecurseSubmodules::new_with_validate("recurseSubmodules", &config::Tree::FETCH, validate::RecurseSubmodules);
+    /// The `fetch.uriProtocols` key.
+    pub const URI_PROTOCOLS: keys::String = keys::String::new_string("uriProtocols", &config::Tree::FETCH);
+    /// The `fetch.bundleURI` key.
+    pub const BUNDLE_URI: keys::String = keys::String::new_string("bundleURI", &config::Tree::FETCH);
+    /// The `fetch.bundleCreationToken` key.
+    pub const BUNDLE_CREATION_TOKEN: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("bundleCreationToken", &config::Tree::FETCH);
+    /// The `fetch.prune` key.
+    pub const PRUNE: keys::Boolean = keys::Boolean::new_boolean("prune", &config::Tree::FETCH);
+    /// The `fetch.pruneTags` key.
+    pub const PRUNE_TAGS: keys::Boolean = keys::Boolean::new_boolean("pruneTags", &config::Tree::FETCH);
 }
 
 impl Section for Fetch {
@@ -26,6 +37,11 @@ impl Section for Fetch {
             &Self::NEGOTIATION_ALGORITHM,
             #[cfg(feature = "attributes")]
             &Self::RECURSE_SUBMODULES,
+            &Self::URI_PROTOCOLS,
+            &Self::BUNDLE_URI,
+            &Self::BUNDLE_CREATION_TOKEN,
+            &Self::PRUNE,
+            &Self::PRUNE_TAGS,
         ]
     }
 }
@@ -0,0 +1,129 @@
+use crate::{
+    config,
+    config::tree::{keys, Gc, Key, Section, SubSectionRequirement},
+};
+
+impl Gc {
+    /// The `gc.packRefs` key.
+    pub const PACK_REFS: PackRefs = PackRefs::new_with_validate("packRefs", &config::Tree::GC, validate::PackRefs);
+    /// The `gc.pruneExpire` key.
+    pub const PRUNE_EXPIRE: Expiry = Expiry::new_with_validate("pruneExpire", &config::Tree::GC, validate::Expiry);
+    /// The `gc.reflogExpire` key.
+    pub const REFLOG_EXPIRE: Expiry = Expiry::new_with_validate("reflogExpire", &config::Tree::GC, validate::Expiry);
+    /// The `gc.reflogExpireUnreachable` key.
+    pub const REFLOG_EXPIRE_UNREACHABLE: Expiry =
+        Expiry::new_with_validate("reflogExpireUnreachable", &config::Tree::GC, validate::Expiry);
+    /// The `gc.<pattern>.reflogExpire` key, which overrides `gc.reflogExpire` for references matching `<pattern>`.
+    pub const PATTERN_REFLOG_EXPIRE: Expiry =
+        Expiry::new_with_validate("reflogExpire", &config::Tree::GC, validate::Expiry)
+            .with_subsection_requirement(Some(SubSectionRequirement::Parameter("pattern")));
+    /// The `gc.<pattern>.reflogExpireUnreachable` key, which overrides `gc.reflogExpireUnreachable` for references matching `<pattern>`.
+    pub const PATTERN_REFLOG_EXPIRE_UNREACHABLE: Expiry =
+        Expiry::new_with_validate("reflogExpireUnreachable", &config::Tree::GC, validate::Expiry)
+            .with_subsection_requirement(Some(SubSectionRequirement::Parameter("pattern")));
+}
+
+impl Section for Gc {
+    fn name(&self) -> &str {
+        "gc"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[
+            &Self::PACK_REFS,
+            &Self::PRUNE_EXPIRE,
+            &Self::REFLOG_EXPIRE,
+            &Self::REFLOG_EXPIRE_UNREACHABLE,
+            &Self::PATTERN_REFLOG_EXPIRE,
+            &Self::PATTERN_REFLOG_EXPIRE_UNREACHABLE,
+        ]
+    }
+}
+
+/// The `gc.packRefs` key.
+pub type PackRefs = keys::Any<validate::PackRefs>;
+
+/// Keys like `gc.pruneExpire` that specify a date before which something expires.
+pub type Expiry = keys::Any<validate::Expiry>;
+
+mod pack_refs {
+    use std::borrow::Cow;
+
+    use crate::{bstr::BStr, config::tree::sections::gc::PackRefs};
+
+    impl PackRefs {
+        /// Return `true` if references should be packed in a repository that is bare if `is_bare` is `true`,
+        /// as `notbare` only packs references in repositories with a worktree.
+        pub fn try_into_pack_refs(
+            &'static self,
+            value: Cow<'_, BStr>,
+            is_bare: bool,
+        ) -> Result<bool, crate::config::key::GenericErrorWithValue> {
+            if value.as_ref() == "notbare" {
+                return Ok(!is_bare);
+            }
+            gix_config::Boolean::try_from(value.as_ref())
+                .map(|b| b.0)
+                .map_err(|err| {
+                    crate::config::key::GenericErrorWithValue::from_value(self, value.into_owned()).with_source(err)
+                })
+        }
+    }
+}
+
+mod expiry {
+    use std::{borrow::Cow, time::SystemTime};
+
+    use crate::{
+        bstr::{BStr, ByteSlice},
+        config::tree::sections::gc::Expiry,
+    };
+
+    impl Expiry {
+        /// Convert `value` into the date before which items expire, with `now` as reference for relative dates,
+        /// or `None` if items never expire.
+        ///
+        /// `never` and `false` mean items never expire, while `now` and `all` make all items expire.
+        pub fn try_into_expiry(
+            &'static self,
+            value: Cow<'_, BStr>,
+            now: SystemTime,
+        ) -> Result<Option<gix_date::Time>, crate::config::key::GenericErrorWithValue<gix_date::parse::Error>> {
+            let time = match value.as_ref().as_bytes() {
+                b"never" | b"false" => return Ok(None),
+                b"now" | b"all" => gix_date::Time::new(
+                    now.duration_since(SystemTime::UNIX_EPOCH)
+                        .map_or(0, |d| d.as_secs() as gix_date::SecondsSinceUnixEpoch),
+                    0,
+                ),
+                _ => {
+                    let err =
+                        || crate::config::key::GenericErrorWithValue::from_value(self, value.clone().into_owned());
+                    let input = value.to_str().map_err(|_| err())?;
+                    gix_date::parse::approximate(input, Some(now)).map_err(|source| err().with_source(source))?
+                }
+            };
+            Ok(Some(time))
+        }
+    }
+}
+
+mod validate {
+    use crate::{bstr::BStr, config::tree::keys};
+
+    pub struct PackRefs;
+    impl keys::Validate for PackRefs {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            crate::config::tree::Gc::PACK_REFS.try_into_pack_refs(value.into(), false)?;
+            Ok(())
+        }
+    }
+
+    pub struct Expiry;
+    impl keys::Validate for Expiry {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            crate::config::tree::Gc::PRUNE_EXPIRE.try_into_expiry(value.into(), std::time::SystemTime::now())?;
+            Ok(())
+        }
+    }
+}
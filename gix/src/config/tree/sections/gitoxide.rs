@@ -350,6 +350,69 @@ mod subsections {
             keys::Executable::new_executable("commandWithoutShellFallback", &Gitoxide::SSH)
                 .with_environment_override("GIT_SSH")
                 .with_note("is always executed without shell and treated as fallback");
+        /// The `gitoxide.ssh.native` key.
+        pub const NATIVE: keys::Boolean = keys::Boolean::new_boolean("native", &Gitoxide::SSH)
+            .with_note("entirely new, use the built-in ssh client instead of spawning an `ssh` program. Requires the `native-ssh` feature");
+        /// The `gitoxide.ssh.identityFile` key.
+        pub const IDENTITY_FILE: keys::Path = keys::Path::new_path("identityFile", &Gitoxide::SSH)
+            .with_note("like `IdentityFile` of `ssh`, and may be specified multiple times to try each key in order");
+        /// The `gitoxide.ssh.identitiesOnly` key.
+        pub const IDENTITIES_ONLY: keys::Boolean = keys::Boolean::new_boolean("identitiesOnly", &Gitoxide::SSH)
+            .with_note("like `IdentitiesOnly` of `ssh`, to only use the keys of `gitoxide.ssh.identityFile` even if the agent holds others");
+        /// The `gitoxide.ssh.identityAgent` key.
+        pub const IDENTITY_AGENT: keys::Path = keys::Path::new_path("identityAgent", &Gitoxide::SSH).with_note(
+            "like `IdentityAgent` of `ssh`, but only `none` is supported to disable the use of an agent, which is otherwise found through `SSH_AUTH_SOCK`",
+        );
+        /// The `gitoxide.ssh.userKnownHostsFile` key.
+        pub const USER_KNOWN_HOSTS_FILE: keys::Path = keys::Path::new_path("userKnownHostsFile", &Gitoxide::SSH)
+            .with_note("like `UserKnownHostsFile` of `ssh`, and may be specified multiple times");
+        /// The `gitoxide.ssh.strictHostKeyChecking` key.
+        pub const STRICT_HOST_KEY_CHECKING: StrictHostKeyChecking = StrictHostKeyChecking::new_with_validate(
+            "strictHostKeyChecking",
+            &Gitoxide::SSH,
+            super::validate::StrictHostKeyChecking,
+        )
+        .with_note("like `StrictHostKeyChecking` of `ssh`, with `yes` (the default), `accept-new` and `no`");
+        /// The `gitoxide.ssh.connectTimeout` key.
+        pub const CONNECT_TIMEOUT: keys::DurationInMilliseconds =
+            keys::DurationInMilliseconds::new_duration("connectTimeout", &Gitoxide::SSH)
+                .with_note("like `ConnectTimeout` of `ssh`, but in milliseconds");
+    }
+
+    /// The `gitoxide.ssh.strictHostKeyChecking` key.
+    pub type StrictHostKeyChecking = keys::Any<super::validate::StrictHostKeyChecking>;
+
+    #[cfg(all(
+        feature = "native-ssh",
+        any(feature = "blocking-network-client", feature = "async-network-client")
+    ))]
+    mod strict_host_key_checking {
+        use std::borrow::Cow;
+
+        use super::StrictHostKeyChecking;
+        use crate::{
+            bstr::{BStr, ByteSlice},
+            config,
+        };
+
+        impl StrictHostKeyChecking {
+            /// Convert `value` into the policy for handling unknown and changed host keys.
+            pub fn try_into_strict_host_key_checking(
+                &'static self,
+                value: Cow<'_, BStr>,
+            ) -> Result<
+                gix_protocol::transport::client::native_ssh::StrictHostKeyChecking,
+                config::key::GenericErrorWithValue,
+            > {
+                use gix_protocol::transport::client::native_ssh::StrictHostKeyChecking as Policy;
+                Ok(match value.as_bytes() {
+                    b"yes" => Policy::Yes,
+                    b"accept-new" => Policy::AcceptNew,
+                    b"no" | b"off" => Policy::No,
+                    _ => return Err(config::key::GenericErrorWithValue::from_value(self, value.into_owned())),
+                })
+            }
+        }
     }
 
     impl Section for Ssh {
@@ -358,7 +421,16 @@ mod subsections {
         }
 
         fn keys(&self) -> &[&dyn Key] {
-            &[&Self::COMMAND_WITHOUT_SHELL_FALLBACK]
+            &[
+                &Self::COMMAND_WITHOUT_SHELL_FALLBACK,
+                &Self::NATIVE,
+                &Self::IDENTITY_FILE,
+                &Self::IDENTITIES_ONLY,
+                &Self::IDENTITY_AGENT,
+                &Self::USER_KNOWN_HOSTS_FILE,
+                &Self::STRICT_HOST_KEY_CHECKING,
+                &Self::CONNECT_TIMEOUT,
+            ]
         }
 
         fn parent(&self) -> Option<&dyn Section> {
@@ -537,7 +609,10 @@ pub use subsections::{Allow, Author, Commit, Committer, Core, Credentials, Http,
 pub mod validate {
     use std::error::Error;
 
-    use crate::{bstr::BStr, config::tree::keys::Validate};
+    use crate::{
+        bstr::{BStr, ByteSlice},
+        config::tree::keys::Validate,
+    };
 
     pub struct ProtocolFromUser;
     impl Validate for ProtocolFromUser {
@@ -549,6 +624,16 @@ pub mod validate {
         }
     }
 
+    pub struct StrictHostKeyChecking;
+    impl Validate for StrictHostKeyChecking {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
+            if !matches!(value.as_bytes(), b"yes" | b"accept-new" | b"no" | b"off") {
+                return Err("strictHostKeyChecking is either 'yes', 'accept-new' or 'no'".into());
+            }
+            Ok(())
+        }
+    }
+
     pub struct RefsNamespace;
     impl Validate for RefsNamespace {
         fn validate(&self, value: &BStr) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
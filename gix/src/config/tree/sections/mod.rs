@@ -52,6 +52,11 @@ pub mod extensions;
 pub struct Fetch;
 pub mod fetch;
 
+/// The `gc` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Gc;
+pub mod gc;
+
 /// The `gitoxide` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Gitoxide;
@@ -91,6 +96,13 @@ pub mod protocol;
 pub struct Push;
 pub mod push;
 
+/// The `pull` top-level section.
+#[derive(Copy, Clone, Default)]
+#[cfg(feature = "pull")]
+pub struct Pull;
+#[cfg(feature = "pull")]
+pub mod pull;
+
 /// The `remote` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Remote;
@@ -113,6 +125,16 @@ pub struct Status;
 #[cfg(feature = "status")]
 pub mod status;
 
+/// The `trailer` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Trailer;
+pub mod trailer;
+
+/// The `transfer` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Transfer;
+mod transfer;
+
 /// The `user` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct User;
@@ -0,0 +1,108 @@
+use crate::{
+    config,
+    config::tree::{keys, Key, Pull, Section},
+};
+
+impl Pull {
+    /// The `pull.rebase` key.
+    pub const REBASE: Rebase = Rebase::new_with_validate("rebase", &config::Tree::PULL, validate::Rebase);
+    /// The `pull.ff` key.
+    pub const FF: FastForward = FastForward::new_with_validate("ff", &config::Tree::PULL, validate::FastForward);
+}
+
+impl Section for Pull {
+    fn name(&self) -> &str {
+        "pull"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::REBASE, &Self::FF]
+    }
+}
+
+/// The `pull.rebase` and `branch.<name>.rebase` key type.
+pub type Rebase = keys::Any<validate::Rebase>;
+
+/// The `pull.ff` key type.
+pub type FastForward = keys::Any<validate::FastForward>;
+
+mod rebase {
+    use std::borrow::Cow;
+
+    use crate::{
+        bstr::{BStr, ByteSlice},
+        config,
+        config::tree::pull::Rebase,
+        pull,
+    };
+
+    impl Rebase {
+        /// Try to interpret `value` as `pull.rebase` or `branch.<name>.rebase`, which is a boolean or
+        /// one of `merges` or `interactive`, or their abbreviations.
+        pub fn try_into_rebase(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<pull::Rebase, config::key::GenericErrorWithValue> {
+            Ok(match value.as_ref().as_bytes() {
+                b"merges" | b"m" => pull::Rebase::Merges,
+                b"interactive" | b"i" => pull::Rebase::Interactive,
+                _ => match gix_config::Boolean::try_from(value.as_ref()) {
+                    Ok(gix_config::Boolean(true)) => pull::Rebase::True,
+                    Ok(gix_config::Boolean(false)) => pull::Rebase::False,
+                    Err(_) => return Err(config::key::GenericErrorWithValue::from_value(self, value.into_owned())),
+                },
+            })
+        }
+    }
+}
+
+mod fast_forward {
+    use std::borrow::Cow;
+
+    use crate::{
+        bstr::{BStr, ByteSlice},
+        config,
+        config::tree::pull::FastForward,
+        pull,
+    };
+
+    impl FastForward {
+        /// Try to interpret `value` as `pull.ff`, which is a boolean or `only`.
+        pub fn try_into_fast_forward(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<pull::FastForward, config::key::GenericErrorWithValue> {
+            Ok(match value.as_ref().as_bytes() {
+                b"only" => pull::FastForward::Only,
+                _ => match gix_config::Boolean::try_from(value.as_ref()) {
+                    Ok(gix_config::Boolean(true)) => pull::FastForward::Allow,
+                    Ok(gix_config::Boolean(false)) => pull::FastForward::Never,
+                    Err(_) => return Err(config::key::GenericErrorWithValue::from_value(self, value.into_owned())),
+                },
+            })
+        }
+    }
+}
+
+///
+pub mod validate {
+    use std::borrow::Cow;
+
+    use crate::{bstr::BStr, config::tree::keys};
+
+    pub struct Rebase;
+    impl keys::Validate for Rebase {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Pull::REBASE.try_into_rebase(Cow::Borrowed(value))?;
+            Ok(())
+        }
+    }
+
+    pub struct FastForward;
+    impl keys::Validate for FastForward {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Pull::FF.try_into_fast_forward(Cow::Borrowed(value))?;
+            Ok(())
+        }
+    }
+}
@@ -31,6 +31,12 @@ impl Remote {
         http::ProxyAuthMethod::new_proxy_auth_method("proxyAuthMethod", &config::Tree::REMOTE)
             .with_subsection_requirement(NAME_PARAMETER)
             .with_deviation("implemented like git, but never actually tried");
+    /// The `remote.<name>.prune` key.
+    pub const PRUNE: keys::Boolean =
+        keys::Boolean::new_boolean("prune", &config::Tree::REMOTE).with_subsection_requirement(NAME_PARAMETER);
+    /// The `remote.<name>.pruneTags` key.
+    pub const PRUNE_TAGS: keys::Boolean =
+        keys::Boolean::new_boolean("pruneTags", &config::Tree::REMOTE).with_subsection_requirement(NAME_PARAMETER);
 }
 
 impl Section for Remote {
@@ -48,6 +54,8 @@ impl Section for Remote {
             &Self::PUSH,
             &Self::PROXY,
             &Self::PROXY_AUTH_METHOD,
+            &Self::PRUNE,
+            &Self::PRUNE_TAGS,
         ]
     }
 }
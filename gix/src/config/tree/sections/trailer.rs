@@ -0,0 +1,149 @@
+use crate::{
+    config,
+    config::tree::{keys, Key, Section, SubSectionRequirement, Trailer},
+};
+
+impl Trailer {
+    /// The `trailer.separators` key.
+    pub const SEPARATORS: keys::String = keys::String::new_string("separators", &config::Tree::TRAILER);
+    /// The `trailer.where` key.
+    pub const WHERE: Where = Where::new_with_validate("where", &config::Tree::TRAILER, validate::Where);
+    /// The `trailer.ifExists` key.
+    pub const IF_EXISTS: IfExists = IfExists::new_with_validate("ifExists", &config::Tree::TRAILER, validate::IfExists);
+    /// The `trailer.ifMissing` key.
+    pub const IF_MISSING: IfMissing =
+        IfMissing::new_with_validate("ifMissing", &config::Tree::TRAILER, validate::IfMissing);
+
+    /// The `trailer.<token>.key` key.
+    pub const TOKEN_KEY: keys::String = keys::String::new_string("key", &config::Tree::TRAILER)
+        .with_subsection_requirement(Some(SubSectionRequirement::Parameter("token")));
+    /// The `trailer.<token>.where` key.
+    pub const TOKEN_WHERE: Where = Where::new_with_validate("where", &config::Tree::TRAILER, validate::Where)
+        .with_subsection_requirement(Some(SubSectionRequirement::Parameter("token")));
+    /// The `trailer.<token>.ifExists` key.
+    pub const TOKEN_IF_EXISTS: IfExists =
+        IfExists::new_with_validate("ifExists", &config::Tree::TRAILER, validate::IfExists)
+            .with_subsection_requirement(Some(SubSectionRequirement::Parameter("token")));
+    /// The `trailer.<token>.ifMissing` key.
+    pub const TOKEN_IF_MISSING: IfMissing =
+        IfMissing::new_with_validate("ifMissing", &config::Tree::TRAILER, validate::IfMissing)
+            .with_subsection_requirement(Some(SubSectionRequirement::Parameter("token")));
+}
+
+impl Section for Trailer {
+    fn name(&self) -> &str {
+        "trailer"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[
+            &Self::SEPARATORS,
+            &Self::WHERE,
+            &Self::IF_EXISTS,
+            &Self::IF_MISSING,
+            &Self::TOKEN_KEY,
+            &Self::TOKEN_WHERE,
+            &Self::TOKEN_IF_EXISTS,
+            &Self::TOKEN_IF_MISSING,
+        ]
+    }
+}
+
+/// The `trailer.where` and `trailer.<token>.where` keys.
+pub type Where = keys::Any<validate::Where>;
+
+/// The `trailer.ifExists` and `trailer.<token>.ifExists` keys.
+pub type IfExists = keys::Any<validate::IfExists>;
+
+/// The `trailer.ifMissing` and `trailer.<token>.ifMissing` keys.
+pub type IfMissing = keys::Any<validate::IfMissing>;
+
+mod values {
+    use std::borrow::Cow;
+
+    use gix_object::commit::message::trailer;
+
+    use crate::{
+        bstr::BStr,
+        config,
+        config::tree::trailer::{IfExists, IfMissing, Where},
+    };
+
+    impl Where {
+        /// Convert `value` into the placement of new trailers, case-insensitively.
+        pub fn try_into_placement(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<trailer::Placement, config::key::GenericErrorWithValue> {
+            Ok(match value.to_ascii_lowercase().as_slice() {
+                b"end" => trailer::Placement::End,
+                b"start" => trailer::Placement::Start,
+                b"after" => trailer::Placement::After,
+                b"before" => trailer::Placement::Before,
+                _ => return Err(config::key::GenericErrorWithValue::from_value(self, value.into_owned())),
+            })
+        }
+    }
+
+    impl IfExists {
+        /// Convert `value` into what to do if a trailer with the same token exists, case-insensitively.
+        pub fn try_into_if_exists(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<trailer::IfExists, config::key::GenericErrorWithValue> {
+            Ok(match value.to_ascii_lowercase().as_slice() {
+                b"addifdifferentneighbor" => trailer::IfExists::AddIfDifferentNeighbor,
+                b"addifdifferent" => trailer::IfExists::AddIfDifferent,
+                b"add" => trailer::IfExists::Add,
+                b"replace" => trailer::IfExists::Replace,
+                b"donothing" => trailer::IfExists::DoNothing,
+                _ => return Err(config::key::GenericErrorWithValue::from_value(self, value.into_owned())),
+            })
+        }
+    }
+
+    impl IfMissing {
+        /// Convert `value` into what to do if no trailer with the same token exists, case-insensitively.
+        pub fn try_into_if_missing(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<trailer::IfMissing, config::key::GenericErrorWithValue> {
+            Ok(match value.to_ascii_lowercase().as_slice() {
+                b"add" => trailer::IfMissing::Add,
+                b"donothing" => trailer::IfMissing::DoNothing,
+                _ => return Err(config::key::GenericErrorWithValue::from_value(self, value.into_owned())),
+            })
+        }
+    }
+}
+
+mod validate {
+    use crate::{
+        bstr::BStr,
+        config::tree::{keys, Trailer},
+    };
+
+    pub struct Where;
+    impl keys::Validate for Where {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            Trailer::WHERE.try_into_placement(value.into())?;
+            Ok(())
+        }
+    }
+
+    pub struct IfExists;
+    impl keys::Validate for IfExists {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            Trailer::IF_EXISTS.try_into_if_exists(value.into())?;
+            Ok(())
+        }
+    }
+
+    pub struct IfMissing;
+    impl keys::Validate for IfMissing {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            Trailer::IF_MISSING.try_into_if_missing(value.into())?;
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,168 @@
+//! Remove unreachable objects and consolidate loose objects, references and packs, similar to `git gc`.
+use std::path::PathBuf;
+
+use crate::bstr::{BStr, BString};
+
+/// The error returned by [Repository::gc()](crate::Repository::gc()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Another garbage collection appears to be running as '{}' was written recently, containing '{content}'", path.display())]
+    AlreadyRunning { path: PathBuf, content: String },
+    #[error(transparent)]
+    LockPidFile(#[from] gix_lock::acquire::Error),
+    #[error("Could not write the pid file at '{}'", path.display())]
+    WritePidFile { path: PathBuf, source: std::io::Error },
+    #[error(transparent)]
+    CommitPidFile(#[from] gix_lock::commit::Error<gix_lock::File>),
+    #[error("Could not list linked worktrees")]
+    ListWorktrees(#[source] std::io::Error),
+    #[error(transparent)]
+    OpenWorktree(#[from] crate::open::Error),
+    #[error(transparent)]
+    LockTimeout(#[from] crate::config::lock_timeout::Error),
+    #[error(transparent)]
+    OpenPackedRefs(#[from] gix_ref::packed::buffer::open::Error),
+    #[error("Could not iterate references")]
+    IterReferences(#[source] std::io::Error),
+    #[error(transparent)]
+    ReadReference(#[from] gix_ref::file::iter::loose_then_packed::Error),
+    #[error(transparent)]
+    FindReference(#[from] gix_ref::file::find::Error),
+    #[error(transparent)]
+    PackReferences(#[from] gix_ref::file::transaction::prepare::Error),
+    #[error(transparent)]
+    CommitReferences(#[from] gix_ref::file::transaction::commit::Error),
+    #[error(transparent)]
+    ReadReflog(#[from] gix_ref::file::log::Error),
+    #[error(transparent)]
+    ExpireReflog(#[from] gix_ref::file::log::expire::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    FindObject(#[from] gix_object::find::Error),
+    #[error(transparent)]
+    DecodeObject(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    IterLooseObjects(#[from] gix_odb::loose::iter::Error),
+    #[error(transparent)]
+    OpenPackIndex(#[from] gix_pack::index::init::Error),
+    #[error("Could not remove the loose object at '{}'", path.display())]
+    RemoveLooseObject { path: PathBuf, source: std::io::Error },
+    #[error(transparent)]
+    Repack(#[from] crate::repack::Error),
+    #[error(transparent)]
+    RefreshStore(#[from] gix_odb::store::load_index::Error),
+    #[error("Interrupted")]
+    Interrupted,
+}
+
+/// Options for use in [Repository::gc()](crate::Repository::gc()).
+///
+/// Use [Repository::gc_options()](crate::Repository::gc_options()) to obtain options that are configured like `git gc` would be.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// Unreachable loose objects that were last modified before this date are removed, or `None` to never remove them.
+    ///
+    /// This is the grace period that protects objects that were just written by concurrent writers which didn't have a
+    /// chance yet to make them reachable, and thus shouldn't be too close to the present.
+    /// It's configured with `gc.pruneExpire`.
+    pub prune_expiry: Option<gix_date::Time>,
+    /// Determines which reflog entries are removed, or `None` to keep all of them.
+    pub reflog_expiry: Option<ReflogExpiry>,
+    /// If `true`, move all loose references into the `packed-refs` file, similar to `git pack-refs --all`.
+    ///
+    /// It's configured with `gc.packRefs`.
+    pub pack_refs: bool,
+    /// How to consolidate packs after packing loose objects, or `None` to not consolidate them at all.
+    pub repack: Option<crate::repack::Mode>,
+    /// If `true`, run even if another garbage collection appears to be running according to a recent `gc.pid` file.
+    /// Without it, a `gc.pid` file is ignored if the process it names doesn't exist anymore on this host.
+    pub force: bool,
+    /// The amount of threads to use at most, or `None` to use all logical cores.
+    pub thread_limit: Option<usize>,
+}
+
+/// Determines which reflog entries are removed by [Repository::gc()](crate::Repository::gc()), similar to `git reflog expire`.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct ReflogExpiry {
+    /// Entries that were created before this date are removed, or `None` to keep them.
+    ///
+    /// It's configured with `gc.reflogExpire`.
+    pub expire: Option<gix_date::Time>,
+    /// Entries that were created before this date are removed if the commits they refer to aren't reachable from the tip of
+    /// their reference anymore, or `None` to keep them.
+    ///
+    /// For `HEAD`, which changes between branches, commits are considered reachable if any reference can reach them.
+    /// It's configured with `gc.reflogExpireUnreachable`.
+    pub expire_unreachable: Option<gix_date::Time>,
+    /// Dates for references whose full name matches a pattern, which take precedence over the ones above.
+    pub patterns: Vec<ReflogExpiryPattern>,
+}
+
+/// Reflog expiry dates that apply to all references whose name matches a pattern.
+///
+/// It's configured with `gc.<pattern>.reflogExpire` and `gc.<pattern>.reflogExpireUnreachable`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReflogExpiryPattern {
+    /// A wildmatch pattern like `refs/remotes/*` to match full reference names with, where `*` also matches slashes.
+    pub pattern: BString,
+    /// Like [`ReflogExpiry::expire`], but `None` if it is unset as well.
+    pub expire: Option<gix_date::Time>,
+    /// Like [`ReflogExpiry::expire_unreachable`], but `None` if it is unset as well.
+    pub expire_unreachable: Option<gix_date::Time>,
+}
+
+impl ReflogExpiry {
+    /// Return the dates `(expire, expire_unreachable)` that apply to the reflog of the reference with `full_name`.
+    ///
+    /// The first pattern that matches wins, and if none matches the default dates are used.
+    /// Just like in `git`, `refs/stash` never expires unless a pattern matches it.
+    pub fn dates_for(&self, full_name: &BStr) -> (Option<gix_date::Time>, Option<gix_date::Time>) {
+        if let Some(pattern) = self
+            .patterns
+            .iter()
+            .find(|p| gix_glob::wildmatch(p.pattern.as_ref(), full_name, gix_glob::wildmatch::Mode::empty()))
+        {
+            return (pattern.expire, pattern.expire_unreachable);
+        }
+        if full_name == "refs/stash" {
+            return (None, None);
+        }
+        (self.expire, self.expire_unreachable)
+    }
+}
+
+/// The outcome of [Repository::gc()](crate::Repository::gc()).
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The amount of loose references that were moved into the `packed-refs` file.
+    pub packed_refs: usize,
+    /// The amount of reflog entries that were removed across all reflogs.
+    pub expired_reflog_entries: usize,
+    /// The amount of objects that were found to be reachable.
+    pub num_reachable_objects: usize,
+    /// The path to the index of the pack that received all reachable loose objects, or `None` if there were none.
+    pub loose_objects_index_path: Option<PathBuf>,
+    /// The amount of reachable loose objects that were packed and removed.
+    pub packed_loose_objects: usize,
+    /// The amount of loose objects that were removed as they were already present in a pack.
+    pub redundant_loose_objects: usize,
+    /// The amount of unreachable loose objects that were removed as they were older than the prune expiry date.
+    pub pruned_loose_objects: usize,
+    /// The outcome of consolidating packs, if it was configured.
+    pub repack: Option<crate::repack::Outcome>,
+}
+
+///
+pub mod options {
+    /// The error returned by [Repository::gc_options()](crate::Repository::gc_options()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Expiry(#[from] crate::config::key::GenericErrorWithValue<gix_date::parse::Error>),
+        #[error(transparent)]
+        PackRefs(#[from] crate::config::key::GenericErrorWithValue),
+    }
+}
@@ -110,6 +110,7 @@ pub use gix_features::{
     progress::{Count, DynNestedProgress, NestedProgress, Progress},
     threading,
 };
+pub use gix_fetchhead as fetchhead;
 pub use gix_fs as fs;
 pub use gix_glob as glob;
 pub use gix_hash as hash;
@@ -181,12 +182,16 @@ pub mod commit;
 ///
 #[cfg(feature = "dirwalk")]
 pub mod dirwalk;
+#[cfg(feature = "gc")]
+pub mod gc;
 pub mod head;
 pub mod id;
 pub mod object;
 #[cfg(feature = "attributes")]
 pub mod pathspec;
 pub mod reference;
+#[cfg(feature = "repack")]
+pub mod repack;
 pub mod repository;
 #[cfg(feature = "attributes")]
 pub mod submodule;
@@ -202,6 +207,22 @@ pub mod push;
 ///
 pub mod diff;
 
+///
+#[cfg(feature = "merge")]
+pub mod merge;
+
+///
+#[cfg(feature = "apply")]
+pub mod apply;
+
+///
+#[cfg(feature = "pull")]
+pub mod pull;
+
+///
+#[cfg(feature = "apply")]
+pub mod range_diff;
+
 /// See [`ThreadSafeRepository::discover()`], but returns a [`Repository`] instead.
 ///
 /// # Note
@@ -0,0 +1,97 @@
+pub use gix_merge::*;
+
+///
+pub mod cherry_pick {
+    use crate::bstr::BString;
+
+    /// The error returned by [Repository::cherry_pick()](crate::Repository::cherry_pick()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error("Commit {commit} is a merge, but no mainline was specified")]
+        MainlineMissing { commit: gix_hash::ObjectId },
+        #[error("A mainline was specified, but commit {commit} is not a merge")]
+        MainlineForNonMerge { commit: gix_hash::ObjectId },
+        #[error("Commit {commit} has no parent number {mainline}")]
+        MainlineOutOfRange { commit: gix_hash::ObjectId, mainline: usize },
+        #[error(transparent)]
+        MergeTrees(#[from] gix_merge::tree::Error),
+        #[error("Committer identity is not configured")]
+        CommitterMissing,
+        #[error("Author identity is not configured")]
+        AuthorMissing,
+        #[error(transparent)]
+        Time(#[from] crate::config::time::Error),
+        #[error(transparent)]
+        WriteObject(#[from] crate::object::write::Error),
+        #[error("Could not lock merge state file for writing")]
+        LockState(#[from] gix_lock::acquire::Error),
+        #[error("Could not write merge state file at '{}'", path.display())]
+        WriteState { path: std::path::PathBuf, source: std::io::Error },
+        #[error("Could not commit merge state file")]
+        CommitState(#[from] gix_lock::commit::Error<gix_lock::File>),
+    }
+
+    /// Options for [Repository::cherry_pick()](crate::Repository::cherry_pick()) and [Repository::revert()](crate::Repository::revert()).
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    pub struct Options {
+        /// When picking a merge commit, the 1-based number of the parent to consider the mainline, whose changes
+        /// to the commit are applied. It's an error to pick a merge commit without it, or other commits with it,
+        /// similar to `git cherry-pick -m`.
+        pub mainline: Option<usize>,
+        /// If `true`, append a `(cherry picked from commit <id>)` line to the message of the picked commit,
+        /// similar to `git cherry-pick -x`. It has no effect when reverting.
+        pub record_origin: bool,
+        /// Options to control how trees are merged.
+        pub tree_merge: gix_merge::tree::Options,
+    }
+
+    impl Default for Options {
+        fn default() -> Self {
+            Options {
+                mainline: None,
+                record_origin: true,
+                tree_merge: Default::default(),
+            }
+        }
+    }
+
+    /// The outcome of [Repository::cherry_pick()](crate::Repository::cherry_pick()) and [Repository::revert()](crate::Repository::revert()).
+    #[derive(Debug, Clone)]
+    pub enum Outcome<'repo> {
+        /// The changes could be applied without conflict and a new commit was written to the object database.
+        ///
+        /// No reference was changed, and the worktree and index were left untouched.
+        Commit(crate::Id<'repo>),
+        /// The changes could not be applied without conflict.
+        ///
+        /// The merge state, like `CHERRY_PICK_HEAD` or `REVERT_HEAD`, as well as `MERGE_MSG` were written to the repository
+        /// so that the operation can be concluded by hand.
+        Conflict {
+            /// The merged tree with conflict markers along with all conflicting paths.
+            merge: gix_merge::tree::Outcome,
+            /// The message to use when concluding the operation, also written to `MERGE_MSG`, but without the
+            /// list of conflicting paths.
+            message: BString,
+        },
+    }
+}
+
+///
+pub mod revert {
+    pub use super::cherry_pick::{Options, Outcome};
+
+    /// The error returned by [Repository::revert()](crate::Repository::revert()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        HeadCommit(#[from] crate::reference::head_commit::Error),
+        #[error(transparent)]
+        Apply(#[from] super::cherry_pick::Error),
+    }
+}
@@ -154,6 +154,13 @@ impl<'repo> Commit<'repo> {
             first_parent: false,
             id_as_fallback: false,
             max_candidates: 10,
+            contains: false,
+            matching: Vec::new(),
+            excluding: Vec::new(),
+            #[cfg(feature = "status")]
+            dirty_suffix: None,
+            #[cfg(feature = "status")]
+            broken_suffix: None,
         }
     }
 
@@ -0,0 +1,207 @@
+use gix_hash::ObjectId;
+
+/// All possible values of `pull.rebase` and `branch.<name>.rebase`.
+#[derive(Default, Copy, Clone, PartialOrd, PartialEq, Ord, Eq, Hash, Debug)]
+pub enum Rebase {
+    /// Merge the upstream branch into the current branch.
+    #[default]
+    False,
+    /// Rebase the commits of the current branch onto the upstream branch.
+    True,
+    /// Rebase the commits of the current branch onto the upstream branch while recreating local merge commits,
+    /// similar to `git rebase --rebase-merges`.
+    Merges,
+    /// Rebase interactively.
+    Interactive,
+}
+
+/// All possible values of `pull.ff`.
+#[derive(Default, Copy, Clone, PartialOrd, PartialEq, Ord, Eq, Hash, Debug)]
+pub enum FastForward {
+    /// Fast-forward if possible, and create a merge commit otherwise.
+    #[default]
+    Allow,
+    /// Always create a merge commit, even if fast-forwarding would be possible, similar to `git pull --no-ff`.
+    Never,
+    /// Only fast-forward, and fail otherwise, similar to `git pull --ff-only`.
+    Only,
+}
+
+/// Options for [Repository::integrate_fetch_head()](crate::Repository::integrate_fetch_head()) and
+/// [Repository::pull()](crate::Repository::pull()).
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// Whether to merge or to rebase, similar to `git pull --rebase` and `git pull --no-rebase`.
+    ///
+    /// If `None`, it's determined by `branch.<name>.rebase` and `pull.rebase`.
+    pub rebase: Option<Rebase>,
+    /// How to handle fast-forwards when merging, similar to `git pull --ff`, `--no-ff` and `--ff-only`.
+    ///
+    /// If `None`, it's determined by `pull.ff`.
+    pub fast_forward: Option<FastForward>,
+    /// Options to control how trees are merged.
+    pub tree_merge: gix_merge::tree::Options,
+}
+
+/// The way the fetched commit was integrated into the current branch by
+/// [Repository::integrate_fetch_head()](crate::Repository::integrate_fetch_head()).
+#[derive(Debug, Clone)]
+pub enum Integration {
+    /// The fetched commit is already contained in the current branch, and nothing was changed.
+    UpToDate {
+        /// The commit `HEAD` points to.
+        head: ObjectId,
+    },
+    /// `HEAD` was moved to the fetched commit.
+    FastForward {
+        /// The commit `HEAD` pointed to before, or `None` if `HEAD` was unborn.
+        previous: Option<ObjectId>,
+        /// The fetched commit that `HEAD` points to now.
+        new: ObjectId,
+    },
+    /// A merge commit was created and `HEAD` points to it.
+    Merge {
+        /// The commit `HEAD` pointed to before, which is the first parent of `commit`.
+        previous: ObjectId,
+        /// The newly created merge commit.
+        commit: ObjectId,
+    },
+    /// The local commits were replayed on top of the fetched commit and `HEAD` points to the last of them.
+    Rebase {
+        /// The commit `HEAD` pointed to before.
+        previous: ObjectId,
+        /// The fetched commit the local commits were replayed onto.
+        onto: ObjectId,
+        /// The newly created commits in order, which is empty if all local commits were already contained upstream.
+        commits: Vec<ObjectId>,
+    },
+    /// The changes couldn't be integrated without conflict.
+    ///
+    /// ### Deviation
+    ///
+    /// Unlike `git`, no reference, index entry or file is changed in this case, and no merge or rebase state is written,
+    /// so conflicts have to be resolved by other means.
+    Conflict {
+        /// The commit whose changes couldn't be applied when rebasing, or `None` when merging.
+        picking: Option<ObjectId>,
+        /// The merged tree with conflict markers along with all conflicting paths.
+        merge: gix_merge::tree::Outcome,
+    },
+}
+
+/// The outcome of [Repository::pull()](crate::Repository::pull()).
+#[cfg(feature = "blocking-network-client")]
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The outcome of the fetch operation.
+    pub fetch: crate::remote::fetch::Outcome,
+    /// How the fetched commit was integrated into the current branch.
+    pub integration: Integration,
+}
+
+///
+pub mod integrate {
+    use crate::bstr::BString;
+
+    /// The error returned by [Repository::integrate_fetch_head()](crate::Repository::integrate_fetch_head()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FetchHead(#[from] crate::repository::fetch_head::Error),
+        #[error("There is no candidate for merging among the refs that were just fetched")]
+        NothingToMerge,
+        #[error("Merging more than one fetched commit at once isn't supported")]
+        MultipleCommitsToMerge,
+        #[error("Cannot pull into a bare repository")]
+        BareRepository,
+        #[error(transparent)]
+        Head(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        PeelToCommit(#[from] crate::object::peel::to_kind::Error),
+        #[error(transparent)]
+        CommitTree(#[from] crate::object::commit::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        Config(#[from] crate::config::key::GenericErrorWithValue),
+        #[error("Rebasing with '{mode:?}' isn't supported")]
+        UnsupportedRebase { mode: super::Rebase },
+        #[error(transparent)]
+        MergeBase(#[from] crate::repository::merge_base::Error),
+        #[error("Not possible to fast-forward, aborting")]
+        NotPossibleToFastForward,
+        #[error("Need to specify how to reconcile divergent branches, by configuring 'pull.rebase' or 'pull.ff'")]
+        DivergentBranches,
+        #[error("Cannot rebase merge commit {commit} as recreating merges isn't supported")]
+        MergeCommitToRebase { commit: gix_hash::ObjectId },
+        #[error(transparent)]
+        MergeTrees(#[from] gix_merge::tree::Error),
+        #[error("Committer identity is not configured")]
+        CommitterMissing,
+        #[error("Author identity is not configured")]
+        AuthorMissing,
+        #[error(transparent)]
+        Time(#[from] crate::config::time::Error),
+        #[error(transparent)]
+        WriteObject(#[from] crate::object::write::Error),
+        #[error(transparent)]
+        IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        Status(#[from] crate::status::Error),
+        #[error(transparent)]
+        StatusIter(#[from] crate::status::iter::Error),
+        #[error(transparent)]
+        StatusItem(#[from] crate::status::index_worktree::Error),
+        #[error("Your local changes to the following files would be overwritten: {}", paths.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+        LocalChanges { paths: Vec<BString> },
+        #[error("The following untracked working tree files would be overwritten: {}", paths.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+        UntrackedFiles { paths: Vec<BString> },
+        #[error("Cannot pull with rebase as the index or the worktree contain uncommitted changes: {}", paths.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+        UncommittedChanges { paths: Vec<BString> },
+        #[error("Could not delete '{}'", path.display())]
+        RemoveFile {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error(transparent)]
+        CheckoutOptions(#[from] crate::config::checkout_options::Error),
+        #[error("Could not prepare the object database for checkout")]
+        ObjectDatabase(#[from] std::io::Error),
+        #[error(transparent)]
+        Checkout(#[from] gix_worktree_state::checkout::Error),
+        #[error(transparent)]
+        WriteIndex(#[from] gix_index::file::write::Error),
+        #[error(transparent)]
+        EditReferences(#[from] crate::reference::edit::Error),
+    }
+}
+
+/// The error returned by [Repository::pull()](crate::Repository::pull()).
+#[cfg(feature = "blocking-network-client")]
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Head(#[from] crate::reference::find::existing::Error),
+    #[error("You are not currently on a branch")]
+    DetachedHead,
+    #[error("There is no tracking information for the current branch '{branch}'")]
+    NoUpstream { branch: crate::bstr::BString },
+    #[error(transparent)]
+    FindRemote(#[from] crate::remote::find::existing::Error),
+    #[error(transparent)]
+    Connect(#[from] crate::remote::connect::Error),
+    #[error(transparent)]
+    PrepareFetch(#[from] crate::remote::fetch::prepare::Error),
+    #[error(transparent)]
+    Fetch(#[from] crate::remote::fetch::Error),
+    #[error(transparent)]
+    Integrate(#[from] integrate::Error),
+}
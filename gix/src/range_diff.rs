@@ -0,0 +1,104 @@
+pub use gix_diff::range_diff::*;
+use gix_hash::ObjectId;
+
+use crate::bstr::BString;
+
+/// The error returned by [Repository::range_diff()](crate::Repository::range_diff()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Walk(#[from] crate::revision::walk::Error),
+    #[error(transparent)]
+    WalkCommits(#[from] crate::revision::walk::iter::Error),
+    #[error(transparent)]
+    FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    CommitTree(#[from] crate::object::commit::Error),
+    #[error(transparent)]
+    Rewrites(#[from] crate::diff::new_rewrites::Error),
+    #[error(transparent)]
+    DiffTrees(#[from] crate::object::tree::diff::for_each::Error),
+    #[error(transparent)]
+    ShortId(#[from] crate::id::shorten::Error),
+}
+
+/// Options for [Repository::range_diff()](crate::Repository::range_diff()).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// The percentage of the size of a patch that it costs to leave it unpaired, similar to `git range-diff --creation-factor`.
+    ///
+    /// Higher values pair more patches, and it defaults to [`DEFAULT_CREATION_FACTOR`].
+    pub creation_factor: u32,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            creation_factor: DEFAULT_CREATION_FACTOR,
+        }
+    }
+}
+
+/// A commit of one of the series compared by [Repository::range_diff()](crate::Repository::range_diff()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commit {
+    /// The id of the commit.
+    pub id: ObjectId,
+    /// The id of the commit, shortened as configured by `core.abbrev`.
+    pub short_id: gix_hash::Prefix,
+    /// The first paragraph of the commit message, as single line.
+    pub subject: BString,
+    /// The patch of the commit that is used to compare it with commits of the other series.
+    pub patch: Patch,
+}
+
+/// The outcome of [Repository::range_diff()](crate::Repository::range_diff()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The commits of the old series, from the oldest to the newest.
+    pub old: Vec<Commit>,
+    /// The commits of the new series, from the oldest to the newest.
+    pub new: Vec<Commit>,
+    /// The commits of both series paired with each other, with indices into [`old`](Self::old) and [`new`](Self::new),
+    /// in the order in which they should be shown.
+    pub pairs: Vec<Pair>,
+}
+
+impl Outcome {
+    /// Write all pairs to `out` like `git range-diff --no-color` would, with a line like `1:  1a2b3c4 ! 1:  4d5e6f7 subject`
+    /// for each pair, followed by the differences between both patches if they changed.
+    pub fn write_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        let width = (1 + self.old.len().max(self.new.len())).to_string().len();
+        let dashes = self
+            .old
+            .first()
+            .or(self.new.first())
+            .map_or(String::new(), |commit| "-".repeat(commit.short_id.hex_len()));
+        let side = |commit: Option<&Commit>, index: Option<usize>| match (commit, index) {
+            (Some(commit), Some(index)) => format!("{:>width$}:  {}", index + 1, commit.short_id),
+            _ => format!("{:>width$}:  {dashes}", "-"),
+        };
+        for pair in &self.pairs {
+            let old = pair.old.map(|index| &self.old[index]);
+            let new = pair.new.map(|index| &self.new[index]);
+            write!(
+                out,
+                "{} {} {} ",
+                side(old, pair.old),
+                pair.status.as_char(),
+                side(new, pair.new)
+            )?;
+            out.write_all(&old.or(new).expect("at least one side is set").subject)?;
+            out.write_all(b"\n")?;
+            if let (Some(old), Some(new), Status::Changed) = (old, new, pair.status) {
+                write_interdiff(out, &old.patch, &new.patch)?;
+            }
+        }
+        Ok(())
+    }
+}
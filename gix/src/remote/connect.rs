@@ -15,6 +15,9 @@ mod error {
     pub enum Error {
         #[error("Could not obtain options for connecting via ssh")]
         SshOptions(#[from] config::ssh_connect_options::Error),
+        #[cfg(feature = "native-ssh")]
+        #[error("Could not obtain options for connecting with the built-in ssh client")]
+        NativeSshOptions(#[from] config::native_ssh_connect_options::Error),
         #[error("Could not obtain the current directory")]
         CurrentDir(#[from] std::io::Error),
         #[error("Could not access remote repository at \"{}\"", directory.display())]
@@ -83,7 +86,7 @@ impl<'repo> Remote<'repo> {
         direction: crate::remote::Direction,
     ) -> Result<Connection<'_, 'repo, Box<dyn Transport + Send>>, Error> {
         let (url, version) = self.sanitized_url_and_version(direction)?;
-        #[cfg(feature = "blocking-network-client")]
+        #[cfg(any(feature = "blocking-network-client", feature = "native-ssh"))]
         let scheme_is_ssh = url.scheme == gix_url::Scheme::Ssh;
         let transport = gix_protocol::transport::connect(
             url,
@@ -94,6 +97,11 @@ impl<'repo> Remote<'repo> {
                     .then(|| self.repo.ssh_connect_options())
                     .transpose()?
                     .unwrap_or_default(),
+                #[cfg(feature = "native-ssh")]
+                native_ssh: scheme_is_ssh
+                    .then(|| self.repo.native_ssh_connect_options())
+                    .transpose()?
+                    .flatten(),
                 trace: self.repo.config.trace_packet(),
             },
         )
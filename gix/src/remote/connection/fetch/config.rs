@@ -1,6 +1,11 @@
 use super::Error;
 use crate::{
-    config::{cache::util::ApplyLeniency, tree::Pack},
+    bstr::BStr,
+    config,
+    config::{
+        cache::util::ApplyLeniency,
+        tree::{Fetch, Key, Pack, Remote},
+    },
     Repository,
 };
 
@@ -24,3 +29,31 @@ pub fn pack_index_version(repo: &Repository) -> Result<gix_pack::index::Version,
         .with_leniency(repo.options.lenient_config)?
         .unwrap_or(gix_pack::index::Version::V2))
 }
+
+/// Return `(prune, prune_tags)` as configured in `remote.<remote_name>.prune(Tags)`, falling back to `fetch.prune(Tags)`.
+pub fn prune(repo: &Repository, remote_name: Option<&BStr>) -> Result<(bool, bool), config::boolean::Error> {
+    let mut filter = repo.filter_config_section();
+    let mut boolean = |remote_key: &'static config::tree::keys::Boolean,
+                       fetch_key: &'static config::tree::keys::Boolean| {
+        remote_name
+            .and_then(|name| {
+                repo.config
+                    .resolved
+                    .boolean_filter_by("remote", Some(name), remote_key.name(), &mut filter)
+                    .map(|value| remote_key.enrich_error(value))
+            })
+            .or_else(|| {
+                repo.config
+                    .resolved
+                    .boolean_filter(fetch_key.logical_name().as_str(), &mut filter)
+                    .map(|value| fetch_key.enrich_error(value))
+            })
+            .transpose()
+            .with_leniency(repo.options.lenient_config)
+            .map(Option::unwrap_or_default)
+    };
+    Ok((
+        boolean(&Remote::PRUNE, &Fetch::PRUNE)?,
+        boolean(&Remote::PRUNE_TAGS, &Fetch::PRUNE_TAGS)?,
+    ))
+}
@@ -0,0 +1,411 @@
+//! Fetch objects from remotes that serve a repository as static files over HTTP, also known as the 'dumb' protocol.
+//!
+//! As the server can't help with negotiation, we walk the object graph ourselves starting at the objects we want,
+//! and download each object that is missing locally, either as loose object or as part of the pack that contains it.
+//! Objects that already exist locally are assumed to be complete, i.e. everything they reference exists as well.
+use std::{
+    collections::HashSet,
+    io::{Read, Write},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use gix_features::{
+    progress::{Count, DynNestedProgress, Progress},
+    zlib,
+};
+use gix_hash::ObjectId;
+use gix_object::ObjectRef;
+use gix_protocol::transport::client::DumbHttp;
+
+use crate::{bstr::ByteSlice, remote::fetch::outcome, Repository};
+
+/// The error returned when fetching objects using the 'dumb' HTTP protocol.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Client(#[from] gix_protocol::transport::client::Error),
+    #[error("Could not read '{path}' from the remote")]
+    Read { path: String, source: std::io::Error },
+    #[error("The loose object {id} on the remote could not be decompressed or has an invalid header")]
+    CorruptLooseObject { id: ObjectId },
+    #[error("The loose object {expected} on the remote actually hashes to {actual}")]
+    HashMismatch { expected: ObjectId, actual: ObjectId },
+    #[error("Could not write object {id} into the object database")]
+    WriteObject {
+        id: ObjectId,
+        source: crate::object::write::Error,
+    },
+    #[error("Could not store the pack index of '{name}' in a temporary file")]
+    TempIndex { name: String, source: std::io::Error },
+    #[error("Could not open the pack index of '{name}'")]
+    OpenIndex {
+        name: String,
+        source: gix_pack::index::init::Error,
+    },
+    #[error(transparent)]
+    WritePack(#[from] gix_pack::bundle::write::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error("Could not decode object {id}")]
+    Decode {
+        id: ObjectId,
+        source: gix_object::decode::Error,
+    },
+    #[error("Object {id} is neither available as loose object nor in any pack of the remote or its alternates")]
+    ObjectNotFound { id: ObjectId },
+    #[error("Interrupted")]
+    Interrupted,
+}
+
+enum Task {
+    /// Obtain the object with the given id if it's missing, and visit all objects it references.
+    Visit(ObjectId),
+    /// Write a loose object after all objects it references were obtained.
+    Write {
+        id: ObjectId,
+        kind: gix_object::Kind,
+        data: Vec<u8>,
+    },
+}
+
+/// A location on the remote to obtain objects from, the `objects` directory of the remote or one of its alternates.
+struct Base {
+    /// The path to the `objects` directory, relative to the URL of the remote.
+    path: String,
+    /// All packs on the remote which haven't been downloaded yet, or `None` if these weren't listed yet.
+    packs: Option<Vec<RemotePack>>,
+}
+
+struct RemotePack {
+    /// The name of the pack without extension, like `pack-<hash>`.
+    name: String,
+    /// All objects in the pack, sorted for lookup.
+    ids: Vec<ObjectId>,
+}
+
+/// Walk the object graph on the remote and download all objects that are missing locally.
+pub(crate) struct Walk<'a> {
+    repo: &'a Repository,
+    remote: &'a mut dyn DumbHttp,
+    bases: Vec<Base>,
+    /// `true` if alternates of the remote were already added to `bases`.
+    alternates_read: bool,
+    follow_alternates: bool,
+    /// Objects we have seen during the walk, to visit each object only once.
+    seen: HashSet<ObjectId>,
+    /// Objects that were received as part of a pack, which have to be traversed even though they exist locally.
+    received: HashSet<ObjectId>,
+    options: gix_pack::bundle::write::Options,
+    progress: &'a mut dyn DynNestedProgress,
+    objects: gix_features::progress::BoxedDynNestedProgress,
+    should_interrupt: &'a AtomicBool,
+    out: outcome::Download,
+}
+
+impl<'a> Walk<'a> {
+    /// Prepare fetching from `remote` into `repo`, writing packs with `options`.
+    ///
+    /// Alternates of the remote are only followed if `http.followRedirects` is `true`, just like `git` does.
+    pub(crate) fn new(
+        repo: &'a Repository,
+        remote: &'a mut dyn DumbHttp,
+        options: gix_pack::bundle::write::Options,
+        progress: &'a mut dyn DynNestedProgress,
+        should_interrupt: &'a AtomicBool,
+    ) -> Self {
+        let follow_alternates = repo
+            .config
+            .resolved
+            .boolean_filter("http.followRedirects", &mut repo.filter_config_section())
+            .and_then(Result::ok)
+            .unwrap_or(false);
+        let mut objects = progress.add_child("fetching objects".into());
+        objects.init(None, gix_features::progress::count("objects"));
+        Walk {
+            repo,
+            remote,
+            bases: vec![Base {
+                path: "objects".into(),
+                packs: None,
+            }],
+            alternates_read: false,
+            follow_alternates,
+            seen: HashSet::new(),
+            received: HashSet::new(),
+            options,
+            progress,
+            objects,
+            should_interrupt,
+            out: Default::default(),
+        }
+    }
+
+    /// Download all objects reachable from `tips` that are missing locally.
+    ///
+    /// Loose objects are only written once all objects they reference are present, so an interrupted fetch
+    /// never leaves objects behind that would be mistaken as complete.
+    pub(crate) fn fetch(&mut self, tips: impl IntoIterator<Item = ObjectId>) -> Result<(), Error> {
+        let mut stack: Vec<_> = tips.into_iter().map(Task::Visit).collect();
+        while let Some(task) = stack.pop() {
+            let id = match task {
+                Task::Visit(id) => id,
+                Task::Write { id, kind, data } => {
+                    if !self.repo.has_object(id) {
+                        self.repo
+                            .write_object_inner(&data, kind)
+                            .map_err(|source| Error::WriteObject { id, source })?;
+                        self.out.loose_objects += 1;
+                    }
+                    continue;
+                }
+            };
+            if !self.seen.insert(id) {
+                continue;
+            }
+            if self.should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            let is_received = self.received.contains(&id);
+            if !is_received && self.repo.has_object(id) {
+                continue;
+            }
+            self.objects.inc();
+
+            let loose = if is_received { None } else { self.download(&id)? };
+            let is_loose = loose.is_some();
+            let (kind, data) = match loose {
+                Some(loose) => loose,
+                None => {
+                    let object = self.repo.find_object(id)?.detach();
+                    (object.kind, object.data)
+                }
+            };
+            let num_tasks = stack.len();
+            match ObjectRef::from_bytes(kind, &data).map_err(|source| Error::Decode { id, source })? {
+                ObjectRef::Commit(commit) => {
+                    stack.push(Task::Visit(commit.tree()));
+                    stack.extend(commit.parents().map(Task::Visit));
+                }
+                ObjectRef::Tree(tree) => stack.extend(
+                    tree.entries
+                        .iter()
+                        .filter(|entry| !entry.mode.is_commit())
+                        .map(|entry| Task::Visit(entry.oid.to_owned())),
+                ),
+                ObjectRef::Tag(tag) => stack.push(Task::Visit(tag.target())),
+                ObjectRef::Blob(_) => {}
+            }
+            if is_loose {
+                stack.insert(num_tasks, Task::Write { id, kind, data });
+            }
+        }
+        Ok(())
+    }
+
+    /// Return what was downloaded so far.
+    pub(crate) fn into_outcome(self) -> outcome::Download {
+        self.out
+    }
+
+    /// Obtain `id` from the remote, and return its kind and data if it was received as loose object, which is yet to be written.
+    /// Otherwise, it was received as part of a pack and is now available in the object database.
+    fn download(&mut self, id: &gix_hash::oid) -> Result<Option<(gix_object::Kind, Vec<u8>)>, Error> {
+        let mut base_idx = 0;
+        loop {
+            while base_idx < self.bases.len() {
+                if let Some(loose) = self.download_loose(base_idx, id)? {
+                    return Ok(Some(loose));
+                }
+                if self.download_pack_containing(base_idx, id)? {
+                    return Ok(None);
+                }
+                base_idx += 1;
+            }
+            if !self.follow_alternates || self.alternates_read {
+                return Err(Error::ObjectNotFound { id: id.to_owned() });
+            }
+            self.read_alternates()?;
+        }
+    }
+
+    fn download_loose(
+        &mut self,
+        base_idx: usize,
+        id: &gix_hash::oid,
+    ) -> Result<Option<(gix_object::Kind, Vec<u8>)>, Error> {
+        let hex = id.to_hex().to_string();
+        let path = format!("{}/{}/{}", self.bases[base_idx].path, &hex[..2], &hex[2..]);
+        let Some(compressed) = self.read_file(&path)? else {
+            return Ok(None);
+        };
+        let corrupt = || Error::CorruptLooseObject { id: id.to_owned() };
+        let inflated = inflate(&compressed).ok_or_else(corrupt)?;
+        let (kind, size, header_size) = gix_object::decode::loose_header(&inflated).map_err(|_| corrupt())?;
+        let data = &inflated[header_size..];
+        if data.len() as u64 != size {
+            return Err(corrupt());
+        }
+        let actual = gix_object::compute_hash(self.repo.object_hash(), kind, data);
+        if actual != id {
+            return Err(Error::HashMismatch {
+                expected: id.to_owned(),
+                actual,
+            });
+        }
+        Ok(Some((kind, data.to_owned())))
+    }
+
+    /// Return `true` if a pack containing `id` was found in the base at `base_idx` and was downloaded.
+    fn download_pack_containing(&mut self, base_idx: usize, id: &gix_hash::oid) -> Result<bool, Error> {
+        if self.bases[base_idx].packs.is_none() {
+            let packs = self.list_packs(base_idx)?;
+            self.bases[base_idx].packs = Some(packs);
+        }
+        let base = &mut self.bases[base_idx];
+        let packs = base.packs.as_mut().expect("set above");
+        let Some(pack_idx) = packs
+            .iter()
+            .position(|p| p.ids.binary_search_by(|i| i.as_ref().cmp(id)).is_ok())
+        else {
+            return Ok(false);
+        };
+        let pack = packs.remove(pack_idx);
+        let path = format!("{}/pack/{}.pack", base.path, pack.name);
+
+        let mut rd = self
+            .remote
+            .get_file(&path)?
+            .ok_or_else(|| Error::ObjectNotFound { id: id.to_owned() })?;
+        let bundle = gix_pack::Bundle::write_to_directory(
+            &mut rd,
+            Some(&self.repo.objects.store_ref().path().join("pack")),
+            self.progress,
+            self.should_interrupt,
+            None::<gix_object::find::Never>,
+            self.options.clone(),
+        )?;
+        // Assure the stream is fully consumed, even if the pack was followed by extra bytes.
+        std::io::copy(&mut rd, &mut std::io::sink()).map_err(|source| Error::Read { path, source })?;
+        drop(rd);
+
+        self.received.extend(pack.ids);
+        self.out.packs.push(bundle);
+        Ok(true)
+    }
+
+    /// Read `objects/info/packs` of the base at `base_idx` and download the index of each pack to learn which objects it contains.
+    fn list_packs(&mut self, base_idx: usize) -> Result<Vec<RemotePack>, Error> {
+        let base_path = self.bases[base_idx].path.clone();
+        let Some(info) = self.read_file(&format!("{base_path}/info/packs"))? else {
+            return Ok(Vec::new());
+        };
+        let mut packs = Vec::new();
+        for name in ByteSlice::lines(info.as_slice())
+            .filter_map(|line| line.strip_prefix(b"P "))
+            .filter_map(|name| name.to_str().ok()?.trim().strip_suffix(".pack"))
+        {
+            if self.should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            let Some(index) = self.read_file(&format!("{base_path}/pack/{name}.idx"))? else {
+                continue;
+            };
+            let temp_index_err = |source| Error::TempIndex {
+                name: name.to_owned(),
+                source,
+            };
+            let mut file = gix_tempfile::new(
+                self.repo.objects.store_ref().path().join("pack"),
+                gix_tempfile::ContainingDirectory::Exists,
+                gix_tempfile::AutoRemove::Tempfile,
+            )
+            .map_err(temp_index_err)?;
+            let index_path = file
+                .with_mut(|f| f.write_all(&index).map(|_| f.path().to_owned()))
+                .and_then(std::convert::identity)
+                .map_err(temp_index_err)?;
+            let ids = gix_pack::index::File::at(&index_path, self.repo.object_hash())
+                .map_err(|source| Error::OpenIndex {
+                    name: name.to_owned(),
+                    source,
+                })?
+                .iter()
+                .map(|entry| entry.oid)
+                .collect();
+            packs.push(RemotePack {
+                name: name.to_owned(),
+                ids,
+            });
+        }
+        Ok(packs)
+    }
+
+    /// Add the alternates of the remote as additional bases, preferring `http-alternates` over `alternates`.
+    ///
+    /// Only `http-alternates` may contain URLs, lines in `alternates` are paths relative to the `objects` directory or
+    /// absolute paths on the server.
+    fn read_alternates(&mut self) -> Result<(), Error> {
+        self.alternates_read = true;
+        let (content, allow_urls) = match self.read_file("objects/info/http-alternates")? {
+            Some(content) => (content, true),
+            None => match self.read_file("objects/info/alternates")? {
+                Some(content) => (content, false),
+                None => return Ok(()),
+            },
+        };
+        for line in ByteSlice::lines(content.as_slice())
+            .filter_map(|line| line.to_str().ok())
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        {
+            let is_url = line.contains("://");
+            if is_url && !allow_urls {
+                continue;
+            }
+            let path = if is_url || line.starts_with('/') {
+                line.trim_end_matches('/').to_owned()
+            } else {
+                format!("objects/{}", line.trim_end_matches('/'))
+            };
+            if self.bases.iter().all(|base| base.path != path) {
+                self.bases.push(Base { path, packs: None });
+            }
+        }
+        Ok(())
+    }
+
+    /// Read the file at `path` into memory, or return `None` if it doesn't exist on the remote.
+    fn read_file(&mut self, path: &str) -> Result<Option<Vec<u8>>, Error> {
+        let Some(mut rd) = self.remote.get_file(path)? else {
+            return Ok(None);
+        };
+        let mut buf = Vec::new();
+        rd.read_to_end(&mut buf).map_err(|source| Error::Read {
+            path: path.to_owned(),
+            source,
+        })?;
+        Ok(Some(buf))
+    }
+}
+
+/// Decompress the entire zlib stream in `input`, or return `None` if it is invalid or incomplete.
+fn inflate(input: &[u8]) -> Option<Vec<u8>> {
+    let mut inflate = zlib::Inflate::default();
+    let mut out = vec![0; input.len() * 2 + 64];
+    let (mut read, mut written) = (0, 0);
+    loop {
+        let (status, consumed_in, consumed_out) = inflate.once(&input[read..], &mut out[written..]).ok()?;
+        read += consumed_in;
+        written += consumed_out;
+        match status {
+            zlib::Status::StreamEnd => {
+                out.truncate(written);
+                return Some(out);
+            }
+            _ if written == out.len() => out.resize(out.len() * 2, 0),
+            _ if consumed_in == 0 && consumed_out == 0 => return None,
+            _ => {}
+        }
+    }
+}
@@ -21,8 +21,19 @@ pub enum Error {
     Client(#[from] gix_protocol::transport::client::Error),
     #[error(transparent)]
     WritePack(#[from] gix_pack::bundle::write::Error),
+    #[cfg(feature = "blocking-network-client")]
+    #[error(transparent)]
+    Dumb(#[from] super::dumb::Error),
+    #[cfg(any(
+        feature = "blocking-http-transport-reqwest",
+        feature = "blocking-http-transport-curl"
+    ))]
+    #[error(transparent)]
+    Uri(#[from] super::uri::Error),
     #[error(transparent)]
     UpdateRefs(#[from] super::refs::update::Error),
+    #[error(transparent)]
+    WriteFetchHead(#[from] super::fetch_head::Error),
     #[error("Failed to remove .keep file at \"{}\"", path.display())]
     RemovePackKeepFile {
         path: std::path::PathBuf,
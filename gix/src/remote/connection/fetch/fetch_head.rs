@@ -0,0 +1,96 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    bstr::BStr,
+    remote,
+    remote::fetch::{RefMap, Source, SpecIndex},
+    Repository,
+};
+
+/// The error returned when writing `FETCH_HEAD` after fetching.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not determine the current branch to learn which tips should be merged")]
+    HeadName(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    MergeRefName(#[from] crate::repository::branch_remote_ref_name::Error),
+    #[error("Could not write FETCH_HEAD file at \"{}\"", path.display())]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// Write `FETCH_HEAD` with an entry for each tip of `ref_map` whose object is present in `repo` after fetching from `remote`.
+///
+/// Like `git`, tips are marked for merge if they match `branch.<name>.merge` of the current branch if it is tracking `remote`,
+/// or otherwise if they are matched by the first refspec if it isn't a glob pattern.
+/// All tips fetched from an unnamed remote, i.e. by url, are for merge as their refspecs can't be configured.
+pub(crate) fn write(repo: &Repository, remote: &crate::Remote<'_>, ref_map: &RefMap) -> Result<(), Error> {
+    let merge_ref = match (repo.head_name()?, remote.name()) {
+        (Some(head), Some(remote_name)) => {
+            let tracks_remote = repo
+                .branch_remote_name(head.shorten(), remote::Direction::Fetch)
+                .map_or(false, |name| name.as_bstr() == remote_name.as_bstr());
+            if tracks_remote {
+                repo.branch_remote_ref_name(head.as_ref(), remote::Direction::Fetch)
+                    .transpose()?
+                    .map(|name| name.as_bstr().to_owned())
+            } else {
+                None
+            }
+        }
+        _ => None,
+    };
+    let first_spec_is_exact = remote.refspecs(remote::Direction::Fetch).first().map_or(false, |spec| {
+        spec.to_ref().source().map_or(false, |source| !source.contains(&b'*'))
+    });
+
+    let url = remote.url(remote::Direction::Fetch).map_or_else(
+        || "foreign".into(),
+        |url| {
+            let mut url = url.clone();
+            url.set_user(None);
+            url.set_password(None);
+            gix_fetchhead::trim_url(url.to_bstring().as_ref()).to_owned()
+        },
+    );
+
+    let mut seen = BTreeSet::<&BStr>::new();
+    let mut entries = Vec::new();
+    for mapping in &ref_map.mappings {
+        let Some(id) = mapping.remote.as_id().filter(|id| repo.has_object(id)) else {
+            continue;
+        };
+        let source = match &mapping.remote {
+            Source::Ref(r) => {
+                let name = r.unpack().0;
+                if !seen.insert(name) {
+                    continue;
+                }
+                gix_fetchhead::Source::from_ref_name(name)
+            }
+            Source::ObjectId(id) => gix_fetchhead::Source::Other(id.to_hex().to_string().into()),
+        };
+        let for_merge = match mapping.spec_index {
+            SpecIndex::ExplicitInRemote(idx) => match &merge_ref {
+                Some(merge_ref) => mapping.remote.as_name() == Some(merge_ref.as_ref()),
+                None if remote.name().is_none() => true,
+                None => idx == 0 && first_spec_is_exact,
+            },
+            SpecIndex::Implicit(_) => false,
+        };
+        entries.push(gix_fetchhead::Entry {
+            id: id.to_owned(),
+            for_merge,
+            source,
+            url: url.clone(),
+        });
+    }
+
+    let mut buf = Vec::new();
+    gix_fetchhead::write(&entries, &mut buf).expect("writing to memory never fails");
+    let path = repo.fetch_head_file();
+    std::fs::write(&path, buf).map_err(|source| Error::Io { path, source })
+}
@@ -62,6 +62,15 @@ pub enum Status {
         negotiate: outcome::Negotiate,
         /// Information collected while writing the pack and its index.
         write_pack_bundle: gix_pack::bundle::write::Outcome,
+        /// Packs that were downloaded from URIs announced by the remote via `packfile-uris`, to complement the pack it sent.
+        packfile_uris: Vec<outcome::PackfileUri>,
+        /// Information collected while updating references.
+        update_refs: refs::update::Outcome,
+    },
+    /// The remote only serves static files, and at least one missing object was downloaded using the 'dumb' protocol.
+    Downloaded {
+        /// Information about the downloaded loose objects and packs.
+        download: outcome::Download,
         /// Information collected while updating references.
         update_refs: refs::update::Outcome,
     },
@@ -74,6 +83,8 @@ pub struct Outcome {
     pub ref_map: RefMap,
     /// The status of the operation to indicate what happened.
     pub status: Status,
+    /// Bundles that were applied before negotiating with the remote, in the order they were applied.
+    pub bundles: Vec<outcome::Bundle>,
 }
 
 /// Additional types related to the outcome of a fetch operation.
@@ -114,6 +125,35 @@ pub mod outcome {
             pub previous_response_had_at_least_one_in_common: bool,
         }
     }
+
+    /// Information about objects downloaded from a remote using the 'dumb' HTTP protocol.
+    #[derive(Default, Debug, Clone)]
+    pub struct Download {
+        /// The amount of loose objects that were downloaded and written into the object database.
+        pub loose_objects: usize,
+        /// Information about each pack that was downloaded in full as it contained at least one object we needed.
+        pub packs: Vec<gix_pack::bundle::write::Outcome>,
+    }
+
+    /// A pack that was downloaded from a URI as announced by the remote via `packfile-uris`.
+    #[derive(Debug, Clone)]
+    pub struct PackfileUri {
+        /// The URI the pack was downloaded from.
+        pub uri: crate::bstr::BString,
+        /// Information collected while writing the pack and its index.
+        pub write_pack_bundle: gix_pack::bundle::write::Outcome,
+    }
+
+    /// A bundle that was downloaded and applied before negotiating with the remote.
+    #[derive(Debug, Clone)]
+    pub struct Bundle {
+        /// The URI the bundle was downloaded from.
+        pub uri: crate::bstr::BString,
+        /// The creation token of the bundle if it was listed with one, for use with the `creationToken` heuristic.
+        pub creation_token: Option<u64>,
+        /// Information collected while writing the pack of the bundle, or `None` if all of its objects were present already.
+        pub write_pack_bundle: Option<gix_pack::bundle::write::Outcome>,
+    }
 }
 
 /// The progress ids used in during various steps of the fetch operation.
@@ -147,6 +187,8 @@ pub mod prepare {
         MissingRefSpecs,
         #[error(transparent)]
         RefMap(#[from] crate::remote::ref_map::Error),
+        #[error("Could not obtain configuration to learn if refs should be pruned")]
+        PruneConfig(#[from] crate::config::boolean::Error),
     }
 
     impl gix_protocol::transport::IsSpuriousError for Error {
@@ -175,16 +217,34 @@ where
     /// should the fetch not be performed. Furthermore, there the code doing the fetch is inherently blocking and it's not offloaded to a thread,
     /// making this call block the executor.
     /// It's best to unblock it by placing it into its own thread or offload it should usage in an async context be truly required.
+    ///
+    /// ### Configuration
+    ///
+    /// - `remote.<name>.prune` or `fetch.prune` determine if local refs are pruned if they don't exist on the remote anymore,
+    ///   which can be overridden with [`Prepare::with_prune()`].
+    /// - `remote.<name>.pruneTags` or `fetch.pruneTags` add the `refs/tags/*:refs/tags/*` refspec to the `extra_refspecs`
+    ///   of `options` if pruning is enabled, so local tags that don't exist on the remote anymore are pruned as well.
     #[allow(clippy::result_large_err)]
     #[gix_protocol::maybe_async::maybe_async]
     pub async fn prepare_fetch(
         mut self,
         progress: impl Progress,
-        options: ref_map::Options,
+        mut options: ref_map::Options,
     ) -> Result<Prepare<'remote, 'repo, T>, prepare::Error> {
         if self.remote.refspecs(remote::Direction::Fetch).is_empty() && options.extra_refspecs.is_empty() {
             return Err(prepare::Error::MissingRefSpecs);
         }
+        let (prune, prune_tags) =
+            config::prune(self.remote.repo, self.remote.name().map(crate::remote::Name::as_bstr))?;
+        if prune && prune_tags {
+            let tag_spec = remote::fetch::Tags::All
+                .to_refspec()
+                .expect("always present for all tags")
+                .to_owned();
+            if !options.extra_refspecs.contains(&tag_spec) {
+                options.extra_refspecs.push(tag_spec);
+            }
+        }
         let ref_map = self.ref_map_inner(progress, options).await?;
         Ok(Prepare {
             con: Some(self),
@@ -193,6 +253,19 @@ where
             reflog_message: None,
             write_packed_refs: WritePackedRefs::Never,
             shallow: Default::default(),
+            prune,
+            prune_tags,
+            write_fetch_head: true,
+            #[cfg(any(
+                feature = "blocking-http-transport-reqwest",
+                feature = "blocking-http-transport-curl"
+            ))]
+            bundle_uri: None,
+            #[cfg(any(
+                feature = "blocking-http-transport-reqwest",
+                feature = "blocking-http-transport-curl"
+            ))]
+            bundles_from_remote: false,
         })
     }
 }
@@ -208,10 +281,21 @@ where
 }
 
 mod config;
+///
+#[cfg(feature = "blocking-network-client")]
+pub mod dumb;
+///
+pub mod fetch_head;
 mod receive_pack;
 ///
 #[path = "update_refs/mod.rs"]
 pub mod refs;
+///
+#[cfg(any(
+    feature = "blocking-http-transport-reqwest",
+    feature = "blocking-http-transport-curl"
+))]
+pub mod uri;
 
 /// A structure to hold the result of the handshake with the remote and configure the upcoming fetch operation.
 pub struct Prepare<'remote, 'repo, T>
@@ -224,6 +308,19 @@ where
     reflog_message: Option<RefLogMessage>,
     write_packed_refs: WritePackedRefs,
     shallow: remote::fetch::Shallow,
+    prune: bool,
+    prune_tags: bool,
+    write_fetch_head: bool,
+    #[cfg(any(
+        feature = "blocking-http-transport-reqwest",
+        feature = "blocking-http-transport-curl"
+    ))]
+    bundle_uri: Option<BString>,
+    #[cfg(any(
+        feature = "blocking-http-transport-reqwest",
+        feature = "blocking-http-transport-curl"
+    ))]
+    bundles_from_remote: bool,
 }
 
 /// Builder
@@ -265,6 +362,49 @@ where
         self.shallow = shallow;
         self
     }
+
+    /// If `enabled`, delete all local refs that are the destination of a refspec, but whose source doesn't exist on the remote anymore.
+    /// This happens in the same transaction that updates all other refs.
+    ///
+    /// This overrides the value of `remote.<name>.prune` and `fetch.prune`. Note that to prune tags when they are only
+    /// followed automatically, `refs/tags/*:refs/tags/*` needs to be an extra refspec, as is done when `fetch.pruneTags` is set.
+    pub fn with_prune(mut self, enabled: bool) -> Self {
+        self.prune = enabled;
+        self
+    }
+
+    /// If `enabled` (the default), write all fetched tips to `FETCH_HEAD` along with the information which of them are to be merged,
+    /// similar to `git fetch --[no-]write-fetch-head`.
+    pub fn with_write_fetch_head(mut self, enabled: bool) -> Self {
+        self.write_fetch_head = enabled;
+        self
+    }
+
+    /// Download the bundle or bundle list at `uri` and apply it before negotiating with the remote, which then only has to
+    /// send what the bundles didn't contain.
+    ///
+    /// If not set, the bundle list configured in `fetch.bundleURI` is used.
+    /// If `uri` refers to a list using the `creationToken` heuristic, it's stored in `fetch.bundleURI` so later fetches
+    /// can download only the bundles that are new since.
+    #[cfg(any(
+        feature = "blocking-http-transport-reqwest",
+        feature = "blocking-http-transport-curl"
+    ))]
+    pub fn with_bundle_uri(mut self, uri: impl Into<BString>) -> Self {
+        self.bundle_uri = Some(uri.into());
+        self
+    }
+
+    /// If `enabled`, obtain the bundle list advertised by the remote and apply it before negotiating, unless a bundle URI
+    /// is set explicitly or configured.
+    #[cfg(any(
+        feature = "blocking-http-transport-reqwest",
+        feature = "blocking-http-transport-curl"
+    ))]
+    pub(crate) fn with_bundles_from_remote(mut self, enabled: bool) -> Self {
+        self.bundles_from_remote = enabled;
+        self
+    }
 }
 
 impl<'remote, 'repo, T> Drop for Prepare<'remote, 'repo, T>
@@ -255,6 +255,64 @@ pub(crate) fn add_wants(
     }
 }
 
+/// Update all remote refs in `ref_map` which were requested by name with `want-ref` to point to the object the server
+/// reported in its `wanted-refs` section, as the ref may have moved since it was listed.
+///
+/// Doing so assures we update local refs to exactly what was sent in the pack.
+pub(crate) fn apply_wanted_refs(
+    repo: &crate::Repository,
+    ref_map: &mut fetch::RefMap,
+    wanted_refs: &[gix_protocol::fetch::response::WantedRef],
+) {
+    use gix_protocol::handshake::Ref;
+    if wanted_refs.is_empty() {
+        return;
+    }
+    let peeled_target_of = |id: gix_hash::ObjectId| {
+        repo.try_find_object(id)
+            .ok()
+            .flatten()
+            .filter(|obj| obj.kind == gix_object::Kind::Tag)
+            .and_then(|tag| tag.peel_tags_to_end().ok())
+            .map(|obj| obj.id)
+    };
+    let refs = ref_map
+        .remote_refs
+        .iter_mut()
+        .chain(ref_map.mappings.iter_mut().filter_map(|m| match &mut m.remote {
+            fetch::Source::Ref(r) => Some(r),
+            fetch::Source::ObjectId(_) => None,
+        }));
+    for r in refs {
+        let Some(wanted) = wanted_refs.iter().find(|w| w.path == r.unpack().0) else {
+            continue;
+        };
+        let id = wanted.id;
+        let peeled = peeled_target_of(id);
+        match r {
+            Ref::Direct { full_ref_name, .. } | Ref::Peeled { full_ref_name, .. } => {
+                let full_ref_name = std::mem::take(full_ref_name);
+                *r = match peeled {
+                    Some(object) => Ref::Peeled {
+                        full_ref_name,
+                        tag: id,
+                        object,
+                    },
+                    None => Ref::Direct {
+                        full_ref_name,
+                        object: id,
+                    },
+                };
+            }
+            Ref::Symbolic { tag, object, .. } => {
+                *tag = peeled.map(|_| id);
+                *object = peeled.unwrap_or(id);
+            }
+            Ref::Unborn { .. } => {}
+        }
+    }
+}
+
 /// Remove all commits that are more recent than the cut-off, which is the commit time of the oldest common commit we have with the server.
 fn mark_recent_complete_commits(
     queue: &mut Queue,
@@ -328,7 +386,8 @@ fn mark_alternate_complete(
 
 /// Negotiate the nth `round` with `negotiator` sending `haves_to_send` after possibly making the known common commits
 /// as sent by the remote known to `negotiator` using `previous_response` if this isn't the first round.
-/// All `haves` are added to `arguments` accordingly.
+/// All `haves` are added to `arguments` accordingly, unless the remote signalled that it is ready to send a pack,
+/// in which case no more haves are sent to end the negotiation.
 /// Returns the amount of haves actually sent.
 pub(crate) fn one_round(
     negotiator: &mut dyn gix_negotiate::Negotiator,
@@ -339,6 +398,7 @@ pub(crate) fn one_round(
     mut common: Option<&mut Vec<gix_hash::ObjectId>>,
 ) -> Result<(usize, bool), Error> {
     let mut seen_ack = false;
+    let mut remote_is_ready = false;
     if let Some(response) = previous_response {
         use gix_protocol::fetch::response::Acknowledgement;
         for ack in response.acknowledgements() {
@@ -353,6 +413,8 @@ pub(crate) fn one_round(
                 Acknowledgement::Ready => {
                     // NOTE: In git, there is some logic dealing with whether to expect a DELIM or FLUSH package,
                     //       but we handle this with peeking.
+                    // In V1, the remote won't acknowledge anything anymore, and may not even respond to more haves.
+                    remote_is_ready = true;
                 }
                 Acknowledgement::Nak => {}
             }
@@ -368,6 +430,7 @@ pub(crate) fn one_round(
     }
 
     let mut haves_sent = 0;
+    let haves_to_send = if remote_is_ready { 0 } else { haves_to_send };
     for have_id in (0..haves_to_send).map_while(|_| negotiator.next_have(graph)) {
         arguments.have(have_id?);
         haves_sent += 1;
@@ -100,11 +100,38 @@ where
             });
         }
 
+        let repo = con.remote.repo;
+        if self.ref_map.object_hash != repo.object_hash() {
+            return Err(Error::IncompatibleObjectHash {
+                local: repo.object_hash(),
+                remote: self.ref_map.object_hash,
+            });
+        }
+
+        #[cfg(any(
+            feature = "blocking-http-transport-reqwest",
+            feature = "blocking-http-transport-curl"
+        ))]
+        let bundles = if matches!(self.dry_run, fetch::DryRun::No) {
+            self.apply_bundles(&mut con, progress, should_interrupt)?
+        } else {
+            Vec::new()
+        };
+        #[cfg(not(any(
+            feature = "blocking-http-transport-reqwest",
+            feature = "blocking-http-transport-curl"
+        )))]
+        let bundles = Vec::new();
+
+        #[cfg(feature = "blocking-network-client")]
+        if con.transport.dumb_http().is_some() {
+            return self.receive_dumb(con, bundles, progress, should_interrupt);
+        }
+
         let handshake = &self.ref_map.handshake;
         let protocol_version = handshake.server_protocol_version;
 
         let fetch = gix_protocol::Command::Fetch;
-        let repo = con.remote.repo;
         let fetch_features = {
             let mut f = fetch.default_features(protocol_version, &handshake.capabilities);
             f.push(repo.config.user_agent_tuple());
@@ -113,26 +140,28 @@ where
 
         gix_protocol::fetch::Response::check_required_features(protocol_version, &fetch_features)?;
         let sideband_all = fetch_features.iter().any(|(n, _)| *n == "sideband-all");
+        let tag_fetch_features = fetch_features.clone();
         let mut arguments = gix_protocol::fetch::Arguments::new(protocol_version, fetch_features, con.trace);
         if matches!(con.remote.fetch_tags, fetch::Tags::Included) {
-            if !arguments.can_use_include_tag() {
+            if arguments.can_use_include_tag() {
+                arguments.use_include_tag();
+            } else if !matches!(protocol_version, gix_protocol::transport::Protocol::V2) {
                 return Err(Error::MissingServerFeature {
                     feature: "include-tag",
-                    description:
-                        // NOTE: if this is an issue, we could probably do what's proposed here.
-                        "To make this work we would have to implement another pass to fetch attached tags separately",
+                    description: "Attached tags are only fetched in a separate pass when using protocol V2",
                 });
             }
-            arguments.use_include_tag();
         }
-        let (shallow_commits, mut shallow_lock) = add_shallow_args(&mut arguments, &self.shallow, repo)?;
-
-        if self.ref_map.object_hash != repo.object_hash() {
-            return Err(Error::IncompatibleObjectHash {
-                local: repo.object_hash(),
-                remote: self.ref_map.object_hash,
-            });
+        #[cfg(any(
+            feature = "blocking-http-transport-reqwest",
+            feature = "blocking-http-transport-curl"
+        ))]
+        if arguments.can_use_packfile_uris() {
+            if let Some(protocols) = packfile_uri_protocols(repo) {
+                arguments.packfile_uris(&protocols);
+            }
         }
+        let (shallow_commits, mut shallow_lock) = add_shallow_args(&mut arguments, &self.shallow, repo)?;
 
         let negotiate_span = gix_trace::detail!(
             "negotiate",
@@ -165,13 +194,19 @@ where
             &self.shallow,
             negotiate::make_refmapping_ignore_predicate(con.remote.fetch_tags, &self.ref_map),
         )?;
+        // Tags that weren't sent along with the pack are fetched separately, with a connection that supports multiple requests.
+        let backfill_tags = matches!(protocol_version, gix_protocol::transport::Protocol::V2)
+            && matches!(con.remote.fetch_tags, fetch::Tags::Included)
+            && matches!(self.dry_run, fetch::DryRun::No);
         let mut previous_response = None::<gix_protocol::fetch::Response>;
-        let (mut write_pack_bundle, negotiate) = match &action {
+        let (mut write_pack_bundle, mut packfile_uris, negotiate) = match &action {
             negotiate::Action::NoChange | negotiate::Action::SkipToRefUpdate => {
-                gix_protocol::indicate_end_of_interaction(&mut con.transport, con.trace)
-                    .await
-                    .ok();
-                (None, None)
+                if !backfill_tags {
+                    gix_protocol::indicate_end_of_interaction(&mut con.transport, con.trace)
+                        .await
+                        .ok();
+                }
+                (None, Vec::new(), None)
             }
             negotiate::Action::MustNegotiate {
                 remote_ref_target_known,
@@ -191,7 +226,7 @@ where
                 let mut seen_ack = false;
                 let mut in_vain = 0;
                 let mut common = is_stateless.then(Vec::new);
-                let mut reader = 'negotiation: loop {
+                let reader = 'negotiation: loop {
                     let _round = gix_trace::detail!("negotiate round", round = rounds.len() + 1);
                     progress.step();
                     progress.set_name(format!("negotiate (round {})", rounds.len() + 1));
@@ -216,6 +251,7 @@ where
                                 haves_to_send,
                                 previous_response_had_at_least_one_in_common: ack_seen,
                             });
+                            // Sending fewer haves than possible, like when the remote is ready, ends the negotiation.
                             let is_done = haves_sent != haves_to_send || (seen_ack && in_vain >= 256);
                             haves_to_send = gix_negotiate::window_size(is_stateless, Some(haves_to_send));
                             is_done
@@ -275,60 +311,76 @@ where
                     object_hash: con.remote.repo.object_hash(),
                 };
 
-                let write_pack_bundle = if matches!(self.dry_run, fetch::DryRun::No) {
-                    #[cfg(not(feature = "async-network-client"))]
-                    let mut rd = reader;
-                    #[cfg(feature = "async-network-client")]
-                    let mut rd = gix_protocol::futures_lite::io::BlockOn::new(reader);
-                    let res = gix_pack::Bundle::write_to_directory(
-                        &mut rd,
-                        Some(&repo.objects.store_ref().path().join("pack")),
-                        progress,
-                        should_interrupt,
-                        Some(Box::new({
-                            let repo = repo.clone();
-                            repo.objects
-                        })),
-                        options,
-                    )?;
-                    // Assure the final flush packet is consumed.
-                    #[cfg(feature = "async-network-client")]
-                    let has_read_to_end = { rd.get_ref().stopped_at().is_some() };
-                    #[cfg(not(feature = "async-network-client"))]
-                    let has_read_to_end = { rd.stopped_at().is_some() };
-                    if !has_read_to_end {
-                        std::io::copy(&mut rd, &mut std::io::sink()).map_err(Error::ReadRemainingBytes)?;
-                    }
-                    #[cfg(feature = "async-network-client")]
-                    {
-                        reader = rd.into_inner();
+                #[cfg_attr(
+                    not(any(
+                        feature = "blocking-http-transport-reqwest",
+                        feature = "blocking-http-transport-curl"
+                    )),
+                    allow(unused_mut)
+                )]
+                let mut packfile_uris = Vec::<outcome::PackfileUri>::new();
+                #[cfg(any(
+                    feature = "blocking-http-transport-reqwest",
+                    feature = "blocking-http-transport-curl"
+                ))]
+                if matches!(self.dry_run, fetch::DryRun::No) {
+                    // The inline pack may use objects in these packs as delta bases, so they are needed first.
+                    for packfile_uri in previous_response.packfile_uris() {
+                        progress.step();
+                        packfile_uris.push(fetch::uri::download_pack(
+                            repo,
+                            con.remote.name().map(remote::Name::as_bstr),
+                            packfile_uri.uri.as_ref(),
+                            &packfile_uri.pack_hash,
+                            con.trace,
+                            progress,
+                            should_interrupt,
+                            options.clone(),
+                        )?);
                     }
+                }
 
-                    #[cfg(not(feature = "async-network-client"))]
-                    {
-                        reader = rd;
-                    }
-                    Some(res)
+                let write_pack_bundle = if matches!(self.dry_run, fetch::DryRun::No) {
+                    Some(write_pack(repo, reader, progress, should_interrupt, options)?)
                 } else {
+                    drop(reader);
                     None
                 };
-                drop(reader);
+                negotiate::apply_wanted_refs(repo, &mut self.ref_map, previous_response.wanted_refs());
 
-                if matches!(protocol_version, gix_protocol::transport::Protocol::V2) {
+                if matches!(protocol_version, gix_protocol::transport::Protocol::V2) && !backfill_tags {
                     gix_protocol::indicate_end_of_interaction(&mut con.transport, con.trace)
                         .await
                         .ok();
                 }
 
                 if let Some(shallow_lock) = shallow_lock {
-                    if !previous_response.shallow_updates().is_empty() {
-                        crate::shallow::write(shallow_lock, shallow_commits, previous_response.shallow_updates())?;
+                    if !previous_response.shallow_updates().is_empty() && matches!(self.dry_run, fetch::DryRun::No) {
+                        crate::shallow::write(
+                            shallow_lock,
+                            shallow_commits,
+                            previous_response.shallow_updates(),
+                            &repo.objects,
+                        )?;
                     }
                 }
-                (write_pack_bundle, Some(outcome::Negotiate { graph, rounds }))
+                (
+                    write_pack_bundle,
+                    packfile_uris,
+                    Some(outcome::Negotiate { graph, rounds }),
+                )
             }
         };
 
+        let mut tags_pack = None;
+        if backfill_tags {
+            let res = fetch_missing_tags(&mut con, &self.ref_map, tag_fetch_features, progress, should_interrupt).await;
+            gix_protocol::indicate_end_of_interaction(&mut con.transport, con.trace)
+                .await
+                .ok();
+            tags_pack = res?;
+        }
+
         let update_refs = refs::update(
             repo,
             self.reflog_message
@@ -340,21 +392,32 @@ where
             con.remote.fetch_tags,
             self.dry_run,
             self.write_packed_refs,
+            &self.refs_to_prune(con.remote)?,
         )?;
+        if matches!(self.dry_run, fetch::DryRun::No) && self.write_fetch_head {
+            fetch::fetch_head::write(repo, con.remote, &self.ref_map)?;
+        }
 
         if let Some(bundle) = write_pack_bundle.as_mut() {
             if !update_refs.edits.is_empty() || bundle.index.num_objects == 0 {
-                if let Some(path) = bundle.keep_path.take() {
-                    std::fs::remove_file(&path).map_err(|err| Error::RemovePackKeepFile { path, source: err })?;
+                for bundle in std::iter::once(bundle).chain(packfile_uris.iter_mut().map(|p| &mut p.write_pack_bundle))
+                {
+                    if let Some(path) = bundle.keep_path.take() {
+                        std::fs::remove_file(&path).map_err(|err| Error::RemovePackKeepFile { path, source: err })?;
+                    }
                 }
             }
         }
+        if let Some(path) = tags_pack.and_then(|mut bundle| bundle.keep_path.take()) {
+            std::fs::remove_file(&path).map_err(|err| Error::RemovePackKeepFile { path, source: err })?;
+        }
 
         let out = Outcome {
             ref_map: std::mem::take(&mut self.ref_map),
             status: match write_pack_bundle {
                 Some(write_pack_bundle) => Status::Change {
                     write_pack_bundle,
+                    packfile_uris,
                     update_refs,
                     negotiate: negotiate.expect("if we have a pack, we always negotiated it"),
                 },
@@ -364,11 +427,228 @@ where
                     update_refs,
                 },
             },
+            bundles,
         };
         Ok(out)
     }
 }
 
+#[cfg(feature = "blocking-network-client")]
+impl<'remote, 'repo, T> Prepare<'remote, 'repo, T>
+where
+    T: Transport,
+{
+    /// Download all missing objects from a remote that only serves static files, which can't negotiate a pack for us.
+    fn receive_dumb(
+        mut self,
+        mut con: remote::Connection<'remote, 'repo, T>,
+        bundles: Vec<outcome::Bundle>,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error> {
+        let repo = con.remote.repo;
+        if self.shallow != Shallow::NoChange || repo.is_shallow() {
+            return Err(Error::MissingServerFeature {
+                feature: "shallow",
+                description: "the 'dumb' protocol can only transfer complete histories",
+            });
+        }
+
+        let mut wants = Vec::new();
+        let mut included_tags = Vec::new();
+        let mapping_is_ignored = negotiate::make_refmapping_ignore_predicate(con.remote.fetch_tags, &self.ref_map);
+        for mapping in &self.ref_map.mappings {
+            if mapping_is_ignored(mapping) {
+                // Implicitly included tags are only fetched if the object they point to is present after fetching.
+                if let fetch::Source::Ref(r) = &mapping.remote {
+                    if let (_, Some(tag), Some(peeled)) = r.unpack() {
+                        included_tags.push((tag.to_owned(), peeled.to_owned()));
+                    }
+                }
+            } else if let Some(id) = mapping.remote.as_id() {
+                wants.push(id.to_owned());
+            }
+        }
+        drop(mapping_is_ignored);
+        wants.retain(|id| !repo.has_object(id));
+        included_tags.retain(|(tag, _)| !repo.has_object(tag));
+
+        let mut download =
+            if matches!(self.dry_run, fetch::DryRun::No) && !(wants.is_empty() && included_tags.is_empty()) {
+                progress.step();
+                progress.set_name("fetching objects from 'dumb' remote".into());
+                let options = gix_pack::bundle::write::Options {
+                    thread_limit: config::index_threads(repo)?,
+                    index_version: config::pack_index_version(repo)?,
+                    iteration_mode: gix_pack::data::input::Mode::Verify,
+                    object_hash: repo.object_hash(),
+                };
+                let remote = con.transport.dumb_http().expect("checked by caller");
+                let mut walk = fetch::dumb::Walk::new(repo, remote, options, progress, should_interrupt);
+                walk.fetch(wants)?;
+                walk.fetch(
+                    included_tags
+                        .into_iter()
+                        .filter_map(|(tag, peeled)| repo.has_object(peeled).then_some(tag)),
+                )?;
+                Some(walk.into_outcome()).filter(|d| d.loose_objects != 0 || !d.packs.is_empty())
+            } else {
+                None
+            };
+
+        let update_refs = refs::update(
+            repo,
+            self.reflog_message
+                .take()
+                .unwrap_or_else(|| RefLogMessage::Prefixed { action: "fetch".into() }),
+            &self.ref_map.mappings,
+            con.remote.refspecs(remote::Direction::Fetch),
+            &self.ref_map.extra_refspecs,
+            con.remote.fetch_tags,
+            self.dry_run,
+            self.write_packed_refs,
+            &self.refs_to_prune(con.remote)?,
+        )?;
+        if matches!(self.dry_run, fetch::DryRun::No) && self.write_fetch_head {
+            fetch::fetch_head::write(repo, con.remote, &self.ref_map)?;
+        }
+
+        if let Some(download) = download.as_mut() {
+            for bundle in &mut download.packs {
+                if !update_refs.edits.is_empty() || bundle.index.num_objects == 0 {
+                    if let Some(path) = bundle.keep_path.take() {
+                        std::fs::remove_file(&path).map_err(|err| Error::RemovePackKeepFile { path, source: err })?;
+                    }
+                }
+            }
+        }
+
+        Ok(Outcome {
+            ref_map: std::mem::take(&mut self.ref_map),
+            status: match download {
+                Some(download) => Status::Downloaded { download, update_refs },
+                None => Status::NoPackReceived {
+                    dry_run: matches!(self.dry_run, fetch::DryRun::Yes),
+                    negotiate: None,
+                    update_refs,
+                },
+            },
+            bundles,
+        })
+    }
+}
+
+impl<'remote, 'repo, T> Prepare<'remote, 'repo, T>
+where
+    T: Transport,
+{
+    /// Return the local refs which don't exist on the remote anymore if pruning is enabled.
+    ///
+    /// Tags that are only followed automatically are never pruned, unless pruning of tags was requested.
+    fn refs_to_prune(&self, remote: &crate::Remote<'_>) -> Result<Vec<gix_ref::FullName>, Error> {
+        if !self.prune {
+            return Ok(Vec::new());
+        }
+        let followed_tags_spec = matches!(remote.fetch_tags, fetch::Tags::Included)
+            .then(|| remote.fetch_tags.to_refspec())
+            .flatten()
+            .filter(|_| !self.prune_tags);
+        let specs = remote
+            .refspecs(remote::Direction::Fetch)
+            .iter()
+            .chain(&self.ref_map.extra_refspecs)
+            .map(gix_refspec::RefSpec::to_ref)
+            .filter(move |spec| Some(*spec) != followed_tags_spec);
+        Ok(refs::prune_candidates(remote.repo, specs, &self.ref_map.remote_refs)?)
+    }
+}
+
+#[cfg(any(
+    feature = "blocking-http-transport-reqwest",
+    feature = "blocking-http-transport-curl"
+))]
+impl<'remote, 'repo, T> Prepare<'remote, 'repo, T>
+where
+    T: Transport,
+{
+    /// Apply the bundles at the explicitly set bundle URI, the one configured in `fetch.bundleURI`, or the ones advertised by
+    /// the remote if enabled, in that order.
+    fn apply_bundles(
+        &self,
+        con: &mut remote::Connection<'remote, 'repo, T>,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Vec<outcome::Bundle>, Error> {
+        use fetch::uri::Source;
+
+        let repo = con.remote.repo;
+        let source = match self.bundle_uri.clone() {
+            Some(uri) => Some(Source::Uri { uri, remember: true }),
+            None => match repo.config.resolved.string(Fetch::BUNDLE_URI) {
+                Some(uri) => Some(Source::Uri {
+                    uri: uri.into_owned(),
+                    remember: false,
+                }),
+                None if self.bundles_from_remote && con.transport.dumb_http().is_none() => gix_protocol::bundle_uri(
+                    &mut con.transport,
+                    &self.ref_map.handshake.capabilities,
+                    vec![repo.config.user_agent_tuple()],
+                    &mut &mut *progress,
+                    con.trace,
+                )
+                .map_err(fetch::uri::Error::from)?
+                .map(|list| Source::List {
+                    list,
+                    base: con
+                        .remote
+                        .url(remote::Direction::Fetch)
+                        .map(gix_url::Url::to_bstring)
+                        .unwrap_or_default(),
+                }),
+                None => None,
+            },
+        };
+        let Some(source) = source else {
+            return Ok(Vec::new());
+        };
+        progress.step();
+        progress.set_name("applying bundles".into());
+        let options = gix_pack::bundle::write::Options {
+            thread_limit: config::index_threads(repo)?,
+            index_version: config::pack_index_version(repo)?,
+            iteration_mode: gix_pack::data::input::Mode::Verify,
+            object_hash: repo.object_hash(),
+        };
+        Ok(fetch::uri::Bootstrap {
+            repo,
+            remote_name: con.remote.name().map(remote::Name::as_bstr),
+            trace: con.trace,
+            options,
+            progress,
+            should_interrupt,
+        }
+        .apply(source)?)
+    }
+}
+
+/// Return the protocols allowed for downloading packs via `packfile-uris` as configured in `fetch.uriProtocols`,
+/// limited to those we can download from, or `None` if there is none.
+#[cfg(any(
+    feature = "blocking-http-transport-reqwest",
+    feature = "blocking-http-transport-curl"
+))]
+fn packfile_uri_protocols(repo: &Repository) -> Option<String> {
+    use crate::bstr::ByteSlice;
+    let protocols = repo.config.resolved.string(Fetch::URI_PROTOCOLS)?;
+    let protocols: Vec<_> = protocols
+        .split(|b| *b == b',')
+        .map(|p| p.trim_with(|c| c.is_ascii_whitespace()))
+        .filter(|p| matches!(*p, b"http" | b"https"))
+        .filter_map(|p| std::str::from_utf8(p).ok())
+        .collect();
+    (!protocols.is_empty()).then(|| protocols.join(","))
+}
+
 fn acquire_shallow_lock(repo: &Repository) -> Result<gix_lock::File, Error> {
     gix_lock::File::acquire_to_update_resource(repo.shallow_file(), gix_lock::acquire::Fail::Immediately, None)
         .map_err(Into::into)
@@ -420,6 +700,103 @@ fn add_shallow_args(
     Ok((shallow_commits, shallow_lock))
 }
 
+/// Fetch all annotated tags that are followed automatically, but are missing locally even though the object they point to is present.
+///
+/// This happens if the remote didn't include them into the pack as the object they point to was already present, for instance because
+/// it was obtained without tags before. It's also what `git` does after fetching.
+#[gix_protocol::maybe_async::maybe_async]
+async fn fetch_missing_tags<T>(
+    con: &mut remote::Connection<'_, '_, T>,
+    ref_map: &fetch::RefMap,
+    features: Vec<(&'static str, Option<std::borrow::Cow<'static, str>>)>,
+    progress: &mut dyn crate::DynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> Result<Option<gix_pack::bundle::write::Outcome>, Error>
+where
+    T: Transport,
+{
+    let repo = con.remote.repo;
+    let protocol_version = ref_map.handshake.server_protocol_version;
+    let sideband_all = features.iter().any(|(n, _)| *n == "sideband-all");
+    let mut arguments = gix_protocol::fetch::Arguments::new(protocol_version, features, con.trace);
+    let mapping_is_ignored = negotiate::make_refmapping_ignore_predicate(con.remote.fetch_tags, ref_map);
+    for mapping in ref_map.mappings.iter().filter(|m| mapping_is_ignored(m)) {
+        if let fetch::Source::Ref(r) = &mapping.remote {
+            if let (_, Some(tag), Some(peeled)) = r.unpack() {
+                if !repo.has_object(tag) && repo.has_object(peeled) {
+                    arguments.want(tag);
+                    arguments.have(peeled);
+                }
+            }
+        }
+    }
+    if arguments.is_empty() {
+        return Ok(None);
+    }
+    if arguments.can_use_shallow() {
+        if let Some(shallow_commits) = repo.shallow_commits()? {
+            for commit in shallow_commits.iter() {
+                arguments.shallow(commit);
+            }
+        }
+    }
+
+    progress.step();
+    progress.set_name("fetching missing tags".into());
+    let mut reader = arguments.send(&mut con.transport, true).await?;
+    if sideband_all {
+        setup_remote_progress(progress, &mut reader, should_interrupt);
+    }
+    let response = gix_protocol::fetch::Response::from_line_reader(protocol_version, &mut reader, true, false).await?;
+    if !response.has_pack() {
+        return Ok(None);
+    }
+    if !sideband_all {
+        setup_remote_progress(progress, &mut reader, should_interrupt);
+    }
+    let options = gix_pack::bundle::write::Options {
+        thread_limit: config::index_threads(repo)?,
+        index_version: config::pack_index_version(repo)?,
+        iteration_mode: gix_pack::data::input::Mode::Verify,
+        object_hash: repo.object_hash(),
+    };
+    write_pack(repo, reader, progress, should_interrupt, options).map(Some)
+}
+
+/// Write the pack sent by the remote via `reader` into the object database of `repo`, consuming all remaining bytes of the stream.
+fn write_pack<'a>(
+    repo: &Repository,
+    reader: Box<dyn gix_protocol::transport::client::ExtendedBufRead<'a> + Unpin + 'a>,
+    progress: &mut dyn crate::DynNestedProgress,
+    should_interrupt: &AtomicBool,
+    options: gix_pack::bundle::write::Options,
+) -> Result<gix_pack::bundle::write::Outcome, Error> {
+    #[cfg(not(feature = "async-network-client"))]
+    let mut rd = reader;
+    #[cfg(feature = "async-network-client")]
+    let mut rd = gix_protocol::futures_lite::io::BlockOn::new(reader);
+    let res = gix_pack::Bundle::write_to_directory(
+        &mut rd,
+        Some(&repo.objects.store_ref().path().join("pack")),
+        progress,
+        should_interrupt,
+        Some(Box::new({
+            let repo = repo.clone();
+            repo.objects
+        })),
+        options,
+    )?;
+    // Assure the final flush packet is consumed.
+    #[cfg(feature = "async-network-client")]
+    let has_read_to_end = { rd.get_ref().stopped_at().is_some() };
+    #[cfg(not(feature = "async-network-client"))]
+    let has_read_to_end = { rd.stopped_at().is_some() };
+    if !has_read_to_end {
+        std::io::copy(&mut rd, &mut std::io::sink()).map_err(Error::ReadRemainingBytes)?;
+    }
+    Ok(res)
+}
+
 fn setup_remote_progress<'a>(
     progress: &mut dyn crate::DynNestedProgress,
     reader: &mut Box<dyn gix_protocol::transport::client::ExtendedBufRead<'a> + Unpin + 'a>,
@@ -59,6 +59,8 @@ impl From<Mode> for Update {
 /// * …existing refs would not become 'unborn', i.e. point to a reference that doesn't exist and won't be created due to ref-specs
 ///
 /// With these safeguards in place, one can handle each naturally and implement mirrors or bare repos easily.
+///
+/// All local references in `prune` are deleted in the same transaction, unless they are checked out.
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn update(
     repo: &Repository,
@@ -69,6 +71,7 @@ pub(crate) fn update(
     fetch_tags: fetch::Tags,
     dry_run: fetch::DryRun,
     write_packed_refs: fetch::WritePackedRefs,
+    prune: &[gix_ref::FullName],
 ) -> Result<update::Outcome, update::Error> {
     let _span = gix_trace::detail!("update_refs()", mappings = mappings.len());
     let mut edits = Vec::new();
@@ -315,6 +318,26 @@ pub(crate) fn update(
         }
     }
 
+    if !prune.is_empty() {
+        let checked_out_branches = worktree_branches(repo)?;
+        for name in prune {
+            if checked_out_branches.contains_key(name) {
+                continue;
+            }
+            let Some(existing) = repo.try_find_reference(name.as_ref())? else {
+                continue;
+            };
+            edits.push(RefEdit {
+                change: Change::Delete {
+                    expected: PreviousValue::MustExistAndMatch(existing.target().into_owned()),
+                    log: RefLog::AndReference,
+                },
+                name: name.to_owned(),
+                deref: false,
+            });
+        }
+    }
+
     let edits = match dry_run {
         fetch::DryRun::No => {
             let _span = gix_trace::detail!("apply", edits = edits.len());
@@ -342,6 +365,72 @@ pub(crate) fn update(
     Ok(update::Outcome { edits, updates })
 }
 
+/// Return the names of all local references that are the destination of one of `refspecs`, but whose source
+/// isn't among the `remote_refs` anymore.
+///
+/// Like `git`, symbolic references are never pruned.
+pub(crate) fn prune_candidates<'a>(
+    repo: &Repository,
+    refspecs: impl IntoIterator<Item = gix_refspec::RefSpecRef<'a>> + Clone,
+    remote_refs: &[gix_protocol::handshake::Ref],
+) -> Result<Vec<gix_ref::FullName>, update::Error> {
+    // Reverse all refspecs to map local references back to the name they would have on the remote.
+    let reversed: Vec<_> = refspecs
+        .clone()
+        .into_iter()
+        .filter_map(|spec| {
+            let reversed = format!("{}:{}", spec.destination()?, spec.source()?);
+            gix_refspec::parse(reversed.as_str().into(), gix_refspec::parse::Operation::Fetch)
+                .ok()
+                .map(|spec| spec.to_owned())
+        })
+        .collect();
+    if reversed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut local_refs = Vec::new();
+    let platform = repo.references()?;
+    for r in platform.all()? {
+        let r = r?;
+        if let Some(id) = r.target().try_id() {
+            local_refs.push((r.name().to_owned(), id.to_owned()));
+        }
+    }
+    let group = gix_refspec::MatchGroup::from_fetch_specs(reversed.iter().map(gix_refspec::RefSpec::to_ref));
+    let matches = group.match_remotes(local_refs.iter().map(|(name, id)| gix_refspec::match_group::Item {
+        full_ref_name: name.as_bstr(),
+        target: id,
+        object: None,
+    }));
+
+    let null = repo.object_hash().null();
+    let mut out = Vec::new();
+    for mapping in matches.mappings {
+        let (Some(local_index), Some(remote_name)) = (mapping.item_index, mapping.rhs) else {
+            continue;
+        };
+        if remote_refs.iter().any(|r| r.unpack().0 == remote_name.as_ref()) {
+            continue;
+        }
+        let local_name = &local_refs[local_index].0;
+        // Only prune if the remote name would map to our local ref, which also respects negative refspecs.
+        let is_destination = gix_refspec::MatchGroup::from_fetch_specs(refspecs.clone())
+            .match_remotes(std::iter::once(gix_refspec::match_group::Item {
+                full_ref_name: remote_name.as_ref(),
+                target: &null,
+                object: None,
+            }))
+            .mappings
+            .iter()
+            .any(|m| m.rhs.as_deref() == Some(local_name.as_bstr()));
+        if is_destination && !out.contains(local_name) {
+            out.push(local_name.clone());
+        }
+    }
+    Ok(out)
+}
+
 /// Figure out if target of `edit` points to a reference that doesn't exist in `repo` and won't exist as it's not in any of `edits`.
 /// If so, return true.
 fn update_needs_adjustment_as_edits_symbolic_target_is_missing(
@@ -153,6 +153,7 @@ mod update {
                 fetch::Tags::None,
                 reflog_message.map_or(fetch::DryRun::No, |_| fetch::DryRun::Yes),
                 fetch::WritePackedRefs::Never,
+                &[],
             )
             .unwrap();
 
@@ -217,6 +218,7 @@ mod update {
                 fetch::Tags::None,
                 fetch::DryRun::Yes,
                 fetch::WritePackedRefs::Never,
+                &[],
             )?;
 
             assert_eq!(
@@ -249,6 +251,7 @@ mod update {
             fetch::Tags::None,
             fetch::DryRun::Yes,
             fetch::WritePackedRefs::Never,
+            &[],
         )?;
         assert_eq!(
             out.updates,
@@ -276,6 +279,7 @@ mod update {
             fetch::Tags::None,
             fetch::DryRun::Yes,
             fetch::WritePackedRefs::Never,
+            &[],
         )?;
         assert_eq!(
             out.updates,
@@ -316,6 +320,7 @@ mod update {
             fetch::Tags::None,
             fetch::DryRun::Yes,
             fetch::WritePackedRefs::Never,
+            &[],
         )?;
         assert_eq!(
             out.updates,
@@ -367,6 +372,7 @@ mod update {
             fetch::Tags::None,
             fetch::DryRun::Yes,
             fetch::WritePackedRefs::Never,
+            &[],
         )?;
         assert_eq!(
             out.updates,
@@ -415,6 +421,7 @@ mod update {
             fetch::Tags::None,
             fetch::DryRun::Yes,
             fetch::WritePackedRefs::Never,
+            &[],
         )?;
         assert_eq!(
             out.updates,
@@ -445,6 +452,7 @@ mod update {
             fetch::Tags::None,
             fetch::DryRun::Yes,
             fetch::WritePackedRefs::Never,
+            &[],
         )?;
         assert_eq!(
             out.updates,
@@ -589,6 +597,7 @@ mod update {
                 fetch::Tags::None,
                 fetch::DryRun::Yes,
                 fetch::WritePackedRefs::Never,
+                &[],
             )
             .unwrap();
 
@@ -621,6 +630,7 @@ mod update {
             fetch::Tags::None,
             fetch::DryRun::Yes,
             fetch::WritePackedRefs::Never,
+            &[],
         )
         .unwrap();
 
@@ -666,6 +676,7 @@ mod update {
             fetch::Tags::None,
             fetch::DryRun::Yes,
             fetch::WritePackedRefs::Never,
+            &[],
         )
         .unwrap();
 
@@ -693,6 +704,7 @@ mod update {
             fetch::Tags::None,
             fetch::DryRun::Yes,
             fetch::WritePackedRefs::Never,
+            &[],
         )
         .unwrap();
 
@@ -744,6 +756,7 @@ mod update {
             fetch::Tags::None,
             fetch::DryRun::Yes,
             fetch::WritePackedRefs::Never,
+            &[],
         )
         .unwrap();
 
@@ -794,6 +807,7 @@ mod update {
             fetch::Tags::None,
             fetch::DryRun::Yes,
             fetch::WritePackedRefs::Never,
+            &[],
         )
         .unwrap();
 
@@ -829,6 +843,7 @@ mod update {
             fetch::Tags::None,
             fetch::DryRun::No,
             fetch::WritePackedRefs::Never,
+            &[],
         )
         .unwrap();
 
@@ -852,6 +867,7 @@ mod update {
             fetch::Tags::None,
             fetch::DryRun::No,
             fetch::WritePackedRefs::Never,
+            &[],
         )
         .unwrap();
 
@@ -886,6 +902,7 @@ mod update {
             fetch::Tags::None,
             fetch::DryRun::No,
             fetch::WritePackedRefs::Never,
+            &[],
         )
         .unwrap();
 
@@ -23,6 +23,12 @@ mod error {
         PeelToId(#[from] crate::reference::peel::Error),
         #[error("Failed to follow a symbolic reference to assure worktree isn't affected")]
         FollowSymref(#[from] gix_ref::file::find::existing::Error),
+        #[error(transparent)]
+        InitRefsIterator(#[from] crate::reference::iter::init::Error),
+        #[error(transparent)]
+        InitRefsIteratorPlatform(#[from] crate::reference::iter::Error),
+        #[error(transparent)]
+        ObtainRefDuringIteration(#[from] Box<dyn std::error::Error + Send + Sync + 'static>),
     }
 }
 
@@ -32,6 +38,9 @@ pub use error::Error;
 #[derive(Debug, Clone)]
 pub struct Outcome {
     /// All edits that were performed to update local refs.
+    ///
+    /// Deletions of local refs that were pruned as they don't exist on the remote anymore are placed after all other edits,
+    /// and they don't correspond to any of the `updates`.
     pub edits: Vec<gix_ref::transaction::RefEdit>,
     /// Each update provides more information about what happened to the corresponding mapping.
     /// Use [`iter_mapping_updates()`][Self::iter_mapping_updates()] to recombine the update information with ref-edits and their
@@ -123,6 +132,14 @@ pub enum TypeChange {
 }
 
 impl Outcome {
+    /// Return the names of all local references that were deleted as they don't exist on the remote anymore.
+    pub fn iter_pruned(&self) -> impl Iterator<Item = &gix_ref::FullNameRef> {
+        self.edits
+            .iter()
+            .filter(|edit| matches!(edit.change, gix_ref::transaction::Change::Delete { .. }))
+            .map(|edit| edit.name.as_ref())
+    }
+
     /// Produce an iterator over all information used to produce the this outcome, ref-update by ref-update, using the `mappings`
     /// used when producing the ref update.
     ///
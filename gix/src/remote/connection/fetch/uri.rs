@@ -0,0 +1,565 @@
+//! Download packs and bundles from URIs to complement or bootstrap a fetch.
+//!
+//! Servers may announce packs to download in addition to the one they send via `packfile-uris`, and may advertise
+//! lists of bundles via `bundle-uri` that can be applied before negotiating, so that only what's missing has to be fetched.
+//! Bundle lists may also be obtained from URIs configured by the user with `fetch.bundleURI`.
+use std::{
+    io::{BufRead, Read},
+    sync::atomic::AtomicBool,
+};
+
+use gix_features::progress::DynNestedProgress;
+use gix_hash::ObjectId;
+use gix_protocol::{
+    bundle_uri::{Heuristic, List, Mode},
+    transport::client::{http, DumbHttp, TransportWithoutIO},
+};
+use gix_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    Target,
+};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    config::{
+        cache::util::ApplyLeniency,
+        tree::{Fetch, Key},
+    },
+    remote::fetch::outcome,
+    Repository,
+};
+
+/// The maximum amount of bundle lists that may refer to each other, as used by `git`.
+const MAX_LIST_DEPTH: usize = 4;
+
+/// The error returned when downloading packs or bundles from URIs.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Only http:// and https:// URIs are supported, got {uri:?}")]
+    UnsupportedScheme { uri: BString },
+    #[error(transparent)]
+    Url(#[from] gix_url::parse::Error),
+    #[error(transparent)]
+    GatherTransportConfig(#[from] crate::config::transport::Error),
+    #[error("Could not configure the transport to download {uri:?}")]
+    ConfigureTransport {
+        uri: BString,
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[error(transparent)]
+    Client(#[from] gix_protocol::transport::client::Error),
+    #[error("Nothing could be downloaded from {uri:?}")]
+    NotFound { uri: BString },
+    #[error("Could not read the data downloaded from {uri:?}")]
+    Read { uri: BString, source: std::io::Error },
+    #[error(transparent)]
+    WritePack(#[from] gix_pack::bundle::write::Error),
+    #[error("The pack downloaded from {uri:?} has checksum {actual}, but the remote announced {expected}")]
+    PackHashMismatch {
+        uri: BString,
+        expected: ObjectId,
+        actual: ObjectId,
+    },
+    #[error("The bundle downloaded from {uri:?} is malformed: {message}")]
+    MalformedBundle { uri: BString, message: &'static str },
+    #[error("The bundle downloaded from {uri:?} uses the {actual} object format, but the repository uses {expected}")]
+    ObjectFormatMismatch {
+        uri: BString,
+        expected: gix_hash::Kind,
+        actual: BString,
+    },
+    #[error("The bundle list downloaded from {uri:?} could not be parsed")]
+    ParseList {
+        uri: BString,
+        source: gix_config::file::init::Error,
+    },
+    #[error(transparent)]
+    DecodeList(#[from] gix_protocol::bundle_uri::decode::Error),
+    #[error(
+        "Bundle lists may only refer to other lists up to a depth of {MAX_LIST_DEPTH}, which was exceeded by {uri:?}"
+    )]
+    ListTooDeep { uri: BString },
+    #[error("Could not obtain the bundle list from the remote")]
+    BundleUriCommand(#[from] gix_protocol::bundle_uri::Error),
+    #[error("Could not create references to the objects of a bundle")]
+    EditReferences(#[from] crate::reference::edit::Error),
+    #[error("Failed to remove .keep file at \"{}\"", path.display())]
+    RemovePackKeepFile {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Could not make the objects of applied bundles visible")]
+    RefreshObjects(#[from] gix_odb::store::load_index::Error),
+    #[error(transparent)]
+    CreationToken(#[from] crate::config::unsigned_integer::Error),
+    #[error("Could not load the repository-local configuration to remember the bundle list")]
+    LoadConfig(#[from] gix_config::file::init::from_paths::Error),
+    #[error(transparent)]
+    SetConfigValue(#[from] gix_config::file::set_raw_value::Error),
+    #[error("Could not lock the repository-local configuration file for writing")]
+    LockConfig(#[from] gix_lock::acquire::Error),
+    #[error("Could not write the repository-local configuration file")]
+    WriteConfig(#[from] gix_lock::commit::Error<gix_lock::File>),
+    #[error("Could not write the repository-local configuration file")]
+    WriteConfigIo(#[source] std::io::Error),
+}
+
+/// A connection to download a single file from an HTTP(S) URL.
+struct Download {
+    transport: http::Transport<http::Impl>,
+    uri: BString,
+}
+
+impl Download {
+    /// Prepare downloading `uri`, which is configured like the transport to the remote named `remote_name` would be.
+    fn new(repo: &Repository, remote_name: Option<&BStr>, uri: &BStr, trace: bool) -> Result<Self, Error> {
+        let url = gix_url::parse(uri)?;
+        if !matches!(url.scheme, gix_url::Scheme::Http | gix_url::Scheme::Https) {
+            return Err(Error::UnsupportedScheme { uri: uri.to_owned() });
+        }
+        let mut transport = http::connect(url, gix_protocol::transport::Protocol::V2, trace);
+        if let Some(options) = repo.transport_options(uri, remote_name)? {
+            transport
+                .configure(&*options)
+                .map_err(|source| Error::ConfigureTransport {
+                    uri: uri.to_owned(),
+                    source,
+                })?;
+        }
+        Ok(Download {
+            transport,
+            uri: uri.to_owned(),
+        })
+    }
+
+    /// Start the download, or return `None` if there was nothing at our URI.
+    fn open(&mut self) -> Result<Option<Box<dyn BufRead + '_>>, Error> {
+        let uri = self.uri.to_str_lossy().into_owned();
+        Ok(DumbHttp::get_file(&mut self.transport, &uri)?)
+    }
+}
+
+/// Download the pack at `uri` as announced by the remote via `packfile-uris`, write it to the object database of `repo`
+/// and assure its checksum matches `pack_hash`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn download_pack(
+    repo: &Repository,
+    remote_name: Option<&BStr>,
+    uri: &BStr,
+    pack_hash: &gix_hash::oid,
+    trace: bool,
+    progress: &mut dyn DynNestedProgress,
+    should_interrupt: &AtomicBool,
+    options: gix_pack::bundle::write::Options,
+) -> Result<outcome::PackfileUri, Error> {
+    let mut download = Download::new(repo, remote_name, uri, trace)?;
+    let mut rd = download
+        .open()?
+        .ok_or_else(|| Error::NotFound { uri: uri.to_owned() })?;
+    progress.set_name(format!("downloading {uri}"));
+    let write_pack_bundle = gix_pack::Bundle::write_to_directory(
+        &mut rd,
+        Some(&repo.objects.store_ref().path().join("pack")),
+        progress,
+        should_interrupt,
+        Some(repo.objects.clone()),
+        options,
+    )?;
+    std::io::copy(&mut rd, &mut std::io::sink()).map_err(|source| Error::Read {
+        uri: uri.to_owned(),
+        source,
+    })?;
+    if write_pack_bundle.index.data_hash != pack_hash {
+        return Err(Error::PackHashMismatch {
+            uri: uri.to_owned(),
+            expected: pack_hash.to_owned(),
+            actual: write_pack_bundle.index.data_hash,
+        });
+    }
+    Ok(outcome::PackfileUri {
+        uri: uri.to_owned(),
+        write_pack_bundle,
+    })
+}
+
+/// Where to obtain bundles from.
+pub(crate) enum Source {
+    /// Download a bundle or a bundle list from `uri`.
+    Uri {
+        /// The location of the bundle or bundle list.
+        uri: BString,
+        /// If `true`, store `uri` in the repository configuration if it's a list that supports incremental fetches.
+        remember: bool,
+    },
+    /// Use a `list` advertised by the remote at `base`, which is used to resolve relative URIs.
+    List { list: List, base: BString },
+}
+
+/// Apply bundles to `repo` before fetching from a remote to reduce what has to be negotiated and sent.
+pub(crate) struct Bootstrap<'a> {
+    pub repo: &'a Repository,
+    pub remote_name: Option<&'a BStr>,
+    pub trace: bool,
+    pub options: gix_pack::bundle::write::Options,
+    pub progress: &'a mut dyn DynNestedProgress,
+    pub should_interrupt: &'a AtomicBool,
+}
+
+/// The result of trying to apply a bundle.
+enum Applied {
+    /// The bundle, or at least one bundle of the list it pointed to, was applied.
+    Yes,
+    /// The bundle couldn't be used, maybe because objects it depends on are missing.
+    No,
+}
+
+impl Bootstrap<'_> {
+    /// Obtain bundles from `source` and apply them, returning information about each applied bundle.
+    ///
+    /// Bundles that can't be applied are skipped, as a fetch will obtain their objects anyway.
+    pub(crate) fn apply(mut self, source: Source) -> Result<Vec<outcome::Bundle>, Error> {
+        let mut out = Vec::new();
+        match source {
+            Source::Uri { uri, remember } => {
+                if let Some(list) = self.download(uri.as_ref(), None, &mut out)? {
+                    let uses_creation_token = list.heuristic == Some(Heuristic::CreationToken);
+                    self.apply_list(list, uri.as_ref(), 1, &mut out)?;
+                    if remember && uses_creation_token {
+                        self.persist(&Fetch::BUNDLE_URI, uri.as_ref())?;
+                    }
+                }
+            }
+            Source::List { list, base } => {
+                self.apply_list(list, base.as_ref(), 1, &mut out)?;
+            }
+        }
+        if !out.is_empty() {
+            self.repo.objects.store_ref().refresh()?;
+        }
+        Ok(out)
+    }
+
+    fn apply_list(
+        &mut self,
+        list: List,
+        base: &BStr,
+        depth: usize,
+        out: &mut Vec<outcome::Bundle>,
+    ) -> Result<Applied, Error> {
+        if list.heuristic == Some(Heuristic::CreationToken) {
+            let stored_token = self
+                .repo
+                .config
+                .resolved
+                .integer(Fetch::BUNDLE_CREATION_TOKEN)
+                .map(|token| Fetch::BUNDLE_CREATION_TOKEN.try_into_u64(token))
+                .transpose()
+                .with_leniency(self.repo.config.lenient_config)?
+                .unwrap_or(0);
+            let mut bundles: Vec<_> = list
+                .bundles
+                .into_iter()
+                .filter_map(|b| b.creation_token.filter(|token| *token > stored_token).map(|t| (t, b)))
+                .collect();
+            bundles.sort_by_key(|(token, _)| *token);
+
+            // Newer bundles build on older ones, so once one can't be applied, none of the newer ones can be either.
+            let mut applied_token = None;
+            for (token, bundle) in bundles {
+                let uri = resolve(base, bundle.uri.as_ref());
+                match self.apply_uri(uri.as_ref(), Some(token), depth, out)? {
+                    Applied::Yes => applied_token = Some(token),
+                    Applied::No => break,
+                }
+            }
+            return Ok(match applied_token {
+                Some(token) => {
+                    self.persist(&Fetch::BUNDLE_CREATION_TOKEN, token.to_string().as_str().into())?;
+                    Applied::Yes
+                }
+                None => Applied::No,
+            });
+        }
+
+        let mut pending: Vec<_> = list
+            .bundles
+            .into_iter()
+            .map(|bundle| resolve(base, bundle.uri.as_ref()))
+            .collect();
+        match list.mode {
+            Mode::Any => {
+                for uri in pending {
+                    if let Applied::Yes = self.apply_uri(uri.as_ref(), None, depth, out)? {
+                        return Ok(Applied::Yes);
+                    }
+                }
+                Ok(Applied::No)
+            }
+            Mode::All => {
+                // Bundles may depend on each other in any order, so retry those that couldn't be applied as long as we make progress.
+                let mut applied_any = false;
+                loop {
+                    let num_pending = pending.len();
+                    let mut remaining = Vec::new();
+                    for uri in pending {
+                        match self.apply_uri(uri.as_ref(), None, depth, out)? {
+                            Applied::Yes => applied_any = true,
+                            Applied::No => remaining.push(uri),
+                        }
+                    }
+                    if remaining.is_empty() || remaining.len() == num_pending {
+                        break;
+                    }
+                    pending = remaining;
+                }
+                Ok(if applied_any { Applied::Yes } else { Applied::No })
+            }
+        }
+    }
+
+    /// Apply the bundle at `uri`, or the bundles of the list at `uri`.
+    fn apply_uri(
+        &mut self,
+        uri: &BStr,
+        creation_token: Option<u64>,
+        depth: usize,
+        out: &mut Vec<outcome::Bundle>,
+    ) -> Result<Applied, Error> {
+        let num_applied = out.len();
+        match self.download(uri, creation_token, out)? {
+            Some(list) => {
+                if depth >= MAX_LIST_DEPTH {
+                    return Err(Error::ListTooDeep { uri: uri.to_owned() });
+                }
+                self.apply_list(list, uri, depth + 1, out)
+            }
+            None => Ok(if out.len() > num_applied {
+                Applied::Yes
+            } else {
+                Applied::No
+            }),
+        }
+    }
+
+    /// Download `uri` and apply it if it's a bundle, or return it as list otherwise.
+    /// Applied bundles are added to `out`, which is left unchanged if the bundle couldn't be applied.
+    fn download(
+        &mut self,
+        uri: &BStr,
+        creation_token: Option<u64>,
+        out: &mut Vec<outcome::Bundle>,
+    ) -> Result<Option<List>, Error> {
+        let read_err = |source| Error::Read {
+            uri: uri.to_owned(),
+            source,
+        };
+        let mut download = Download::new(self.repo, self.remote_name, uri, self.trace)?;
+        self.progress.set_name(format!("downloading {uri}"));
+        let Some(mut rd) = download.open()? else {
+            // A bundle that isn't there can't be applied, but the subsequent fetch will make up for it.
+            return Ok(None);
+        };
+        let mut first_line = Vec::new();
+        rd.read_until(b'\n', &mut first_line).map_err(read_err)?;
+        let version = match first_line.as_slice() {
+            b"# v2 git bundle\n" => 2,
+            b"# v3 git bundle\n" => 3,
+            _ => {
+                let mut data = first_line;
+                rd.read_to_end(&mut data).map_err(read_err)?;
+                let config = gix_config::File::from_bytes_no_includes(
+                    &data,
+                    gix_config::file::Metadata::api(),
+                    Default::default(),
+                )
+                .map_err(|source| Error::ParseList {
+                    uri: uri.to_owned(),
+                    source,
+                })?;
+                let mut pairs = Vec::<(BString, BString)>::new();
+                for section in config.sections() {
+                    let header = section.header();
+                    let mut prefix = BString::from(header.name());
+                    if let Some(subsection) = header.subsection_name() {
+                        prefix.push(b'.');
+                        prefix.extend_from_slice(subsection);
+                    }
+                    for name in section.value_names() {
+                        let Some(value) = section.value(name.as_ref()) else {
+                            continue;
+                        };
+                        let mut key = prefix.clone();
+                        key.push(b'.');
+                        key.extend_from_slice(name.as_ref().as_bytes());
+                        pairs.push((key, value.into_owned()));
+                    }
+                }
+                return Ok(Some(List::from_key_value_pairs(
+                    pairs.iter().map(|(key, value)| (key.as_bstr(), value.as_bstr())),
+                )?));
+            }
+        };
+
+        let header = Header::read(&mut rd, version, uri, self.repo.object_hash())?;
+        if header.filtered {
+            // Partial clones aren't supported, and we don't want to pretend we have objects we don't have.
+            return Ok(None);
+        }
+        if !header.prerequisites.iter().all(|id| self.repo.has_object(id)) {
+            return Ok(None);
+        }
+        let write_pack_bundle = if header.refs.iter().all(|(id, _)| self.repo.has_object(id)) {
+            None
+        } else {
+            let bundle = gix_pack::Bundle::write_to_directory(
+                &mut rd,
+                Some(&self.repo.objects.store_ref().path().join("pack")),
+                self.progress,
+                self.should_interrupt,
+                Some(self.repo.objects.clone()),
+                self.options.clone(),
+            )?;
+            std::io::copy(&mut rd, &mut std::io::sink()).map_err(read_err)?;
+            Some(bundle)
+        };
+        drop(rd);
+
+        let edits: Vec<_> = header
+            .refs
+            .iter()
+            .filter_map(|(id, name)| {
+                let branch = name.strip_prefix(b"refs/heads/")?;
+                let mut name = BString::from("refs/bundles/");
+                name.extend_from_slice(branch);
+                Some(RefEdit {
+                    change: Change::Update {
+                        log: LogChange {
+                            mode: RefLog::AndReference,
+                            force_create_reflog: false,
+                            message: "fetched bundle".into(),
+                        },
+                        expected: PreviousValue::Any,
+                        new: Target::Object(*id),
+                    },
+                    name: name.try_into().ok()?,
+                    deref: false,
+                })
+            })
+            .collect();
+        self.repo.edit_references(edits)?;
+
+        let mut write_pack_bundle = write_pack_bundle;
+        if let Some(path) = write_pack_bundle.as_mut().and_then(|b| b.keep_path.take()) {
+            std::fs::remove_file(&path).map_err(|source| Error::RemovePackKeepFile { path, source })?;
+        }
+        out.push(outcome::Bundle {
+            uri: uri.to_owned(),
+            creation_token,
+            write_pack_bundle,
+        });
+        Ok(None)
+    }
+
+    /// Write `value` for `key` to the repository-local configuration file, to be picked up by subsequent fetches.
+    fn persist(&self, key: &'static dyn Key, value: &BStr) -> Result<(), Error> {
+        let path = self.repo.common_dir().join("config");
+        let mut config = gix_config::File::from_path_no_includes(path.clone(), gix_config::Source::Local)?;
+        config.set_raw_value_by(key.section().name(), None, key.name(), value)?;
+        let mut lock = gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None)?;
+        config.write_to(&mut lock).map_err(Error::WriteConfigIo)?;
+        lock.commit()?;
+        Ok(())
+    }
+}
+
+/// The header of a bundle file, which precedes the pack.
+struct Header {
+    /// Objects that must exist for the pack to be complete.
+    prerequisites: Vec<ObjectId>,
+    /// The references contained in the bundle along with the objects they point to.
+    refs: Vec<(ObjectId, BString)>,
+    /// If `true`, the bundle was created with an object filter and is incomplete.
+    filtered: bool,
+}
+
+impl Header {
+    /// Read the header of a bundle of the given `version` from `rd`, whose signature line was already consumed.
+    fn read(rd: &mut dyn BufRead, version: u8, uri: &BStr, object_hash: gix_hash::Kind) -> Result<Self, Error> {
+        let malformed = |message| Error::MalformedBundle {
+            uri: uri.to_owned(),
+            message,
+        };
+        let mut out = Header {
+            prerequisites: Vec::new(),
+            refs: Vec::new(),
+            filtered: false,
+        };
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            if rd.read_until(b'\n', &mut line).map_err(|source| Error::Read {
+                uri: uri.to_owned(),
+                source,
+            })? == 0
+            {
+                return Err(malformed("the header ended unexpectedly"));
+            }
+            let line = line.strip_suffix(b"\n").unwrap_or(&line);
+            if line.is_empty() {
+                break;
+            }
+            if let Some(capability) = line.strip_prefix(b"@").filter(|_| version == 3) {
+                match capability.split_once_str("=") {
+                    Some((b"object-format", format)) => {
+                        if format != object_hash.to_string().as_bytes() {
+                            return Err(Error::ObjectFormatMismatch {
+                                uri: uri.to_owned(),
+                                expected: object_hash,
+                                actual: format.into(),
+                            });
+                        }
+                    }
+                    Some((b"filter", _)) => out.filtered = true,
+                    _ => return Err(malformed("it uses an unknown capability")),
+                }
+            } else if let Some(prerequisite) = line.strip_prefix(b"-") {
+                let hex = prerequisite.split_str(" ").next().unwrap_or_default();
+                out.prerequisites
+                    .push(ObjectId::from_hex(hex).map_err(|_| malformed("a prerequisite isn't a valid object id"))?);
+            } else {
+                let (hex, name) = line
+                    .split_once_str(" ")
+                    .ok_or_else(|| malformed("a reference line lacks the reference name"))?;
+                out.refs.push((
+                    ObjectId::from_hex(hex).map_err(|_| malformed("a reference doesn't point to a valid object id"))?,
+                    name.into(),
+                ));
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Resolve `uri` relative to the location of the list at `base` unless it's absolute already.
+fn resolve(base: &BStr, uri: &BStr) -> BString {
+    if uri.contains_str("://") {
+        return uri.to_owned();
+    }
+    let path_start = base
+        .find("://")
+        .and_then(|scheme_end| base[scheme_end + 3..].find_byte(b'/').map(|pos| scheme_end + 3 + pos))
+        .unwrap_or(base.len());
+    let mut out = if uri.starts_with(b"/") {
+        BString::from(&base[..path_start])
+    } else {
+        let dir_end = base[path_start..]
+            .rfind_byte(b'/')
+            .map_or(base.len(), |pos| path_start + pos);
+        let mut dir = BString::from(&base[..dir_end]);
+        dir.push(b'/');
+        dir
+    };
+    out.extend_from_slice(uri);
+    out
+}
@@ -7,14 +7,18 @@ pub mod negotiate {
     pub use super::super::connection::fetch::negotiate::Error;
     #[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
     pub(crate) use super::super::connection::fetch::negotiate::{
-        add_wants, make_refmapping_ignore_predicate, mark_complete_and_common_ref, one_round, Action,
+        add_wants, apply_wanted_refs, make_refmapping_ignore_predicate, mark_complete_and_common_ref, one_round, Action,
     };
 }
 
 #[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
 pub use super::connection::fetch::{
-    outcome, prepare, refs, Error, Outcome, Prepare, ProgressId, RefLogMessage, Status,
+    fetch_head, outcome, prepare, refs, Error, Outcome, Prepare, ProgressId, RefLogMessage, Status,
 };
+#[cfg(feature = "blocking-network-client")]
+pub use super::connection::fetch::dumb;
+#[cfg(any(feature = "blocking-http-transport-reqwest", feature = "blocking-http-transport-curl"))]
+pub use super::connection::fetch::uri;
 
 /// If `Yes`, don't really make changes but do as much as possible to get an idea of what would be done.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -0,0 +1,163 @@
+//! Errors for adding, renaming and removing remotes, and for changing their configuration,
+//! with all changes persisted in the repository-local configuration file.
+
+///
+pub mod persist {
+    /// The error returned when writing changes to remotes to the repository-local configuration file.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not load the repository-local configuration file")]
+        LoadConfig(#[from] gix_config::file::init::from_paths::Error),
+        #[error("Could not lock the repository-local configuration file for writing")]
+        LockConfig(#[from] gix_lock::acquire::Error),
+        #[error("Could not write the repository-local configuration file")]
+        WriteConfig(#[from] gix_lock::commit::Error<gix_lock::File>),
+        #[error("Could not write the repository-local configuration file")]
+        WriteConfigIo(#[source] std::io::Error),
+        #[error("Could not apply the changed configuration to the repository")]
+        ApplyConfig(#[from] crate::config::Error),
+    }
+}
+
+///
+pub mod add {
+    use crate::bstr::BString;
+
+    /// The error returned by [Repository::remote_add()](crate::Repository::remote_add()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Name(#[from] crate::remote::name::Error),
+        #[error("The remote named {name:?} already exists")]
+        Exists { name: BString },
+        #[error(transparent)]
+        Init(#[from] crate::remote::init::Error),
+        #[error(transparent)]
+        RefSpec(#[from] gix_refspec::parse::Error),
+        #[error(transparent)]
+        Save(#[from] crate::remote::save::AsError),
+        #[error(transparent)]
+        Persist(#[from] super::persist::Error),
+        #[error(transparent)]
+        Find(#[from] crate::remote::find::existing::Error),
+    }
+}
+
+///
+pub mod rename {
+    use crate::bstr::BString;
+
+    /// The error returned by [Repository::remote_rename()](crate::Repository::remote_rename()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Name(#[from] crate::remote::name::Error),
+        #[error("The remote named {name:?} isn't configured in the repository-local configuration file")]
+        NotFound { name: BString },
+        #[error("The remote named {name:?} already exists")]
+        Exists { name: BString },
+        #[error("Could not rename the configuration of the remote")]
+        RenameSection(#[from] gix_config::file::rename_section::Error),
+        #[error(transparent)]
+        Persist(#[from] super::persist::Error),
+        #[error("The remote tracking branch can't be moved as its new name is invalid")]
+        RefName(#[source] gix_validate::reference::name::Error),
+        #[error(transparent)]
+        OpenReferences(#[from] crate::reference::iter::Error),
+        #[error(transparent)]
+        InitReferenceIter(#[from] crate::reference::iter::init::Error),
+        #[error("Could not read a reference")]
+        IterReferences(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error(transparent)]
+        EditReferences(#[from] crate::reference::edit::Error),
+    }
+}
+
+///
+pub mod remove {
+    use crate::bstr::BString;
+
+    /// The error returned by [Repository::remote_remove()](crate::Repository::remote_remove()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The remote named {name:?} isn't configured in the repository-local configuration file")]
+        NotFound { name: BString },
+        #[error("The fetch ref-spec {spec:?} of the remote to remove was invalid")]
+        RefSpec {
+            spec: BString,
+            source: gix_refspec::parse::Error,
+        },
+        #[error(transparent)]
+        Persist(#[from] super::persist::Error),
+        #[error(transparent)]
+        OpenReferences(#[from] crate::reference::iter::Error),
+        #[error(transparent)]
+        InitReferenceIter(#[from] crate::reference::iter::init::Error),
+        #[error("Could not read a reference")]
+        IterReferences(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error(transparent)]
+        EditReferences(#[from] crate::reference::edit::Error),
+    }
+}
+
+///
+pub mod set_url {
+    use crate::bstr::BString;
+
+    /// The error returned by [Repository::remote_set_url()](crate::Repository::remote_set_url()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The remote named {name:?} isn't configured in the repository-local configuration file")]
+        NotFound { name: BString },
+        #[error(transparent)]
+        Url(#[from] gix_url::parse::Error),
+        #[error(transparent)]
+        Persist(#[from] super::persist::Error),
+    }
+}
+
+///
+pub mod set_head {
+    use crate::bstr::BString;
+
+    /// The error returned by [Repository::remote_set_head()](crate::Repository::remote_set_head()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The remote named {name:?} isn't configured")]
+        NotFound { name: BString },
+        #[error(transparent)]
+        Name(#[from] gix_validate::reference::name::Error),
+        #[error("The remote tracking branch {name:?} doesn't exist")]
+        TrackingBranchNotFound { name: BString },
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::Error),
+        #[error(transparent)]
+        EditReference(#[from] crate::reference::edit::Error),
+    }
+}
+
+///
+pub mod set_branches {
+    use crate::bstr::BString;
+
+    /// The error returned by [Repository::remote_set_branches()](crate::Repository::remote_set_branches()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The remote named {name:?} isn't configured in the repository-local configuration file")]
+        NotFound { name: BString },
+        #[error("The branch {branch:?} can't be tracked as it doesn't yield a valid ref-spec")]
+        RefSpec {
+            branch: BString,
+            source: gix_refspec::parse::Error,
+        },
+        #[error(transparent)]
+        Persist(#[from] super::persist::Error),
+    }
+}
@@ -61,6 +61,8 @@ pub use connection::{ref_map, AuthenticateFn, Connection};
 ///
 pub mod save;
 
+pub mod manage;
+
 mod access;
 ///
 pub mod url;
@@ -0,0 +1,97 @@
+//! Consolidate packs in the object database to keep the amount of packs low, similar to `git repack`.
+use std::path::PathBuf;
+
+/// The error returned by [Repository::repack()](crate::Repository::repack()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The geometric factor must be at least 2, got {factor}")]
+    InvalidGeometricFactor { factor: u32 },
+    #[error("Could not list packs in '{}'", path.display())]
+    ListPacks { path: PathBuf, source: std::io::Error },
+    #[error(transparent)]
+    OpenIndex(#[from] gix_pack::index::init::Error),
+    #[error("Could not obtain a thread-safe handle to the object database")]
+    ConvertStore(#[source] std::io::Error),
+    #[error("Could not create a temporary file to write the consolidated pack to")]
+    CreateTempfile(#[source] std::io::Error),
+    #[error(transparent)]
+    WritePack(#[from] gix_pack::data::output::bytes::Error<gix_pack::data::output::entry::iter_from_counts::Error>),
+    #[error("Could not read back the consolidated pack to index it")]
+    ReadPack(#[source] std::io::Error),
+    #[error(transparent)]
+    WriteBundle(#[from] gix_pack::bundle::write::Error),
+    #[error("Could not remove the keep-file at '{}'", path.display())]
+    RemoveKeepFile { path: PathBuf, source: std::io::Error },
+    #[error(transparent)]
+    LockMultiIndex(#[from] gix_lock::acquire::Error),
+    #[error(transparent)]
+    WriteMultiIndex(#[from] gix_pack::multi_index::write::Error),
+    #[error(transparent)]
+    CommitMultiIndex(#[from] gix_lock::commit::Error<gix_lock::File>),
+    #[error("Could not flush the multi-pack index")]
+    FlushMultiIndex(#[source] std::io::Error),
+    #[error(transparent)]
+    RefreshStore(#[from] gix_odb::store::load_index::Error),
+    #[error("Interrupted")]
+    Interrupted,
+}
+
+/// The way packs are selected for consolidation.
+///
+/// Packs that are marked with a `.keep` file are never selected.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Mode {
+    /// Consolidate all packs into a single pack, similar to `git repack -a -d`, but without packing loose objects.
+    All,
+    /// Consolidate the smallest packs so that the remaining packs form a geometric progression, where each pack
+    /// contains at least `factor` times as many objects as the next smaller one, similar to `git repack --geometric=<factor>`.
+    ///
+    /// This keeps the amount of packs logarithmic in the amount of objects while rewriting big packs only rarely.
+    Geometric {
+        /// The factor by which each pack has to be larger than the next smaller one, which must be at least 2.
+        factor: u32,
+    },
+    /// Consolidate the oldest packs that are smaller than the given amount of bytes until their total size reaches it,
+    /// similar to `git multi-pack-index repack --batch-size=<size>`, which is used for incremental maintenance.
+    ///
+    /// Nothing is done if the selected packs aren't large enough together, as consolidating them wouldn't be worth it yet.
+    BatchSize(u64),
+}
+
+impl Default for Mode {
+    fn default() -> Self {
+        Mode::Geometric { factor: 2 }
+    }
+}
+
+/// Options for use in [Repository::repack()](crate::Repository::repack()).
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// How to select the packs to consolidate.
+    pub mode: Mode,
+    /// If `true`, write a multi-pack index for all packs after consolidating them even if there was none before,
+    /// similar to `git repack --write-midx`.
+    ///
+    /// Note that an existing multi-pack index is always updated to avoid it referring to packs that were removed.
+    pub write_multi_pack_index: bool,
+    /// The amount of threads to use at most, or `None` to use all logical cores.
+    pub thread_limit: Option<usize>,
+}
+
+/// The outcome of [Repository::repack()](crate::Repository::repack()).
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The path to the index of the newly written pack which contains all objects of the consolidated packs,
+    /// or `None` if there was nothing to consolidate.
+    pub index_path: Option<PathBuf>,
+    /// The amount of objects in the newly written pack.
+    pub num_objects: u32,
+    /// The paths to the indices of all packs that were consolidated into the new pack.
+    pub consolidated_packs: Vec<PathBuf>,
+    /// The paths to the indices of consolidated packs that couldn't be removed, for instance because they were still in use
+    /// on platforms that don't allow removing open files. They are redundant and can be removed at a later time.
+    pub retained_packs: Vec<PathBuf>,
+    /// The path to the multi-pack index if it was written.
+    pub multi_pack_index_path: Option<PathBuf>,
+}
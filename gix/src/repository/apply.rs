@@ -0,0 +1,83 @@
+use gix_hash::ObjectId;
+
+use crate::{
+    apply::{Location, Options, Outcome},
+    bstr::BStr,
+    repository::apply_patches,
+};
+
+/// Applying patches
+impl crate::Repository {
+    /// Apply `patches` as obtained by [`gix::apply::patch::parse()`](crate::apply::patch::parse()) to the worktree,
+    /// the index, or both depending on `location`, similar to `git apply`.
+    ///
+    /// If [three-way merges](Options::three_way) are enabled, the preimages of the patches are looked up in the object
+    /// database by the blob ids of their `index` lines. Changes to the index are written back to disk.
+    pub fn apply_patches(
+        &self,
+        patches: &[crate::apply::patch::Patch],
+        location: Location,
+        options: Options,
+    ) -> Result<Outcome, apply_patches::Error> {
+        let find_preimage = |hex: &BStr| self.find_preimage(hex);
+        let write = |kind, data: &[u8]| self.write_object_inner(data, kind).map(crate::Id::detach);
+        match location {
+            Location::Worktree => {
+                let root = self.work_dir().ok_or(apply_patches::Error::MissingWorktree)?;
+                let outcome = gix_apply::worktree::apply(root, patches, find_preimage, options)?;
+                Ok(Outcome {
+                    conflicts: outcome.conflicts().map(ToOwned::to_owned).collect(),
+                    rejected: outcome.rejected,
+                })
+            }
+            Location::Index => {
+                let mut index = self.open_index()?;
+                let outcome =
+                    gix_apply::index::apply(&mut index, patches, &self.objects, write, find_preimage, options)?;
+                index.write(Default::default())?;
+                Ok(Outcome {
+                    rejected: outcome.rejected,
+                    conflicts: outcome.conflicts,
+                })
+            }
+            Location::WorktreeAndIndex => {
+                let root = self.work_dir().ok_or(apply_patches::Error::MissingWorktree)?;
+                let mut index = self.open_index()?;
+                let outcome = gix_apply::worktree::apply(root, patches, find_preimage, options)?;
+                gix_apply::index::apply_changes(&mut index, &outcome.changes, write)?;
+                index.write(Default::default())?;
+                Ok(Outcome {
+                    conflicts: outcome.conflicts().map(ToOwned::to_owned).collect(),
+                    rejected: outcome.rejected,
+                })
+            }
+        }
+    }
+
+    /// Apply `patches` to `tree` and write the resulting tree to the object database, without touching the worktree or
+    /// the index.
+    ///
+    /// Files with conflicts are written with conflict markers. See [`apply_patches()`](Self::apply_patches()) for details.
+    pub fn apply_patches_to_tree(
+        &self,
+        tree: impl AsRef<gix_hash::oid>,
+        patches: &[crate::apply::patch::Patch],
+        options: Options,
+    ) -> Result<gix_apply::tree::Outcome, gix_apply::tree::Error> {
+        gix_apply::tree::apply(
+            tree.as_ref(),
+            patches,
+            &self.objects,
+            |kind, data| self.write_object_inner(data, kind).map(crate::Id::detach),
+            |hex| self.find_preimage(hex),
+            options,
+        )
+    }
+
+    /// Find the blob whose id starts with `hex`, if it's unambiguous.
+    fn find_preimage(&self, hex: &BStr) -> Option<Vec<u8>> {
+        let prefix = gix_hash::Prefix::from_hex(std::str::from_utf8(hex).ok()?).ok()?;
+        let id: ObjectId = self.objects.lookup_prefix(prefix, None).ok()??.ok()?;
+        Some(self.find_blob(id).ok()?.take_data())
+    }
+}
@@ -37,6 +37,16 @@ impl crate::Repository {
         self.config.stat_options()
     }
 
+    /// Return options for parsing and adding commit message trailers, as configured in the `trailer` section.
+    ///
+    /// Use them with [`MessageRef::trailers_with_options()`](gix_object::commit::MessageRef::trailers_with_options())
+    /// and [`trailer::add()`](gix_object::commit::message::trailer::add()).
+    pub fn trailer_options(
+        &self,
+    ) -> Result<gix_object::commit::message::trailer::Options, config::trailer_options::Error> {
+        self.config.trailer_options()
+    }
+
     /// The options used to open the repository.
     pub fn open_options(&self) -> &crate::open::Options {
         &self.options
@@ -74,6 +84,105 @@ impl crate::Repository {
         Ok(opts)
     }
 
+    /// Obtain options for use when connecting via `ssh` with the built-in client.
+    ///
+    /// Return `None` if the built-in client wasn't enabled with `gitoxide.ssh.native`, while an async client is always
+    /// built-in, so `Some` is returned unconditionally.
+    #[cfg(all(
+        feature = "native-ssh",
+        any(feature = "blocking-network-client", feature = "async-network-client")
+    ))]
+    pub fn native_ssh_connect_options(
+        &self,
+    ) -> Result<Option<gix_protocol::transport::client::native_ssh::Options>, config::native_ssh_connect_options::Error>
+    {
+        use crate::config::{
+            cache::{interpolate_context, util::ApplyLeniency},
+            native_ssh_connect_options::Error,
+            tree::{gitoxide::Ssh, Key},
+        };
+
+        let config = &self.config.resolved;
+        let lenient = self.options.lenient_config;
+        let mut trusted = self.filter_config_section();
+        let mut boolean = |key: &'static config::tree::keys::Boolean| -> Result<Option<bool>, Error> {
+            Ok(config
+                .boolean_filter(key, &mut trusted)
+                .map(|value| key.enrich_error(value))
+                .transpose()
+                .with_leniency(lenient)?)
+        };
+
+        let is_native = boolean(&Ssh::NATIVE)?.unwrap_or(cfg!(feature = "async-network-client"));
+        if !is_native {
+            return Ok(None);
+        }
+        let identities_only = boolean(&Ssh::IDENTITIES_ONLY)?.unwrap_or_default();
+
+        let (install_dir, home_dir) = (self.install_dir().ok(), self.config.home_dir());
+        let ctx = interpolate_context(install_dir.as_deref(), home_dir.as_deref());
+        let mut trusted = self.filter_config_section();
+        let mut paths = |key: &'static config::tree::keys::Path| -> Result<Vec<std::path::PathBuf>, Error> {
+            let mut out = Vec::new();
+            for path in config.strings_filter(key, &mut trusted).unwrap_or_default() {
+                if let Some(path) = gix_config::Path::from(path)
+                    .interpolate(ctx)
+                    .map(std::borrow::Cow::into_owned)
+                    .map(Some)
+                    .with_leniency(lenient)
+                    .map_err(|source| Error::InterpolatePath {
+                        source,
+                        key: key.logical_name(),
+                    })?
+                {
+                    out.push(path);
+                }
+            }
+            Ok(out)
+        };
+        let identity_files = paths(&Ssh::IDENTITY_FILE)?;
+        let known_hosts_files = paths(&Ssh::USER_KNOWN_HOSTS_FILE)?;
+
+        let mut trusted = self.filter_config_section();
+        let disable_agent = config
+            .string_filter(Ssh::IDENTITY_AGENT, &mut trusted)
+            .map(|value| {
+                if value.as_ref() == "none" {
+                    Ok(true)
+                } else {
+                    Err(config::key::GenericErrorWithValue::from_value(
+                        &Ssh::IDENTITY_AGENT,
+                        value.into_owned(),
+                    ))
+                }
+            })
+            .transpose()
+            .with_leniency(lenient)?
+            .unwrap_or_default();
+        let strict_host_key_checking = config
+            .string_filter(Ssh::STRICT_HOST_KEY_CHECKING, &mut trusted)
+            .map(|value| Ssh::STRICT_HOST_KEY_CHECKING.try_into_strict_host_key_checking(value))
+            .transpose()
+            .with_leniency(lenient)?
+            .unwrap_or_default();
+        let connect_timeout = config
+            .integer_filter(Ssh::CONNECT_TIMEOUT, &mut trusted)
+            .map(|value| Ssh::CONNECT_TIMEOUT.try_into_duration(value))
+            .transpose()
+            .with_leniency(lenient)?;
+
+        Ok(Some(gix_protocol::transport::client::native_ssh::Options {
+            user: None,
+            port: None,
+            identity_files,
+            identities_only,
+            disable_agent,
+            known_hosts_files,
+            strict_host_key_checking,
+            connect_timeout,
+        }))
+    }
+
     /// Return the context to be passed to any spawned program that is supposed to interact with the repository, like
     /// hooks or filters.
     #[cfg(feature = "attributes")]
@@ -0,0 +1,35 @@
+use std::path::PathBuf;
+
+use crate::Repository;
+
+/// The error returned by [Repository::fetch_head()](crate::Repository::fetch_head()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not read FETCH_HEAD file")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Parse(#[from] gix_fetchhead::parse::Error),
+}
+
+impl Repository {
+    /// Return the path to the `FETCH_HEAD` file, which records the tips obtained by the last fetch operation.
+    ///
+    /// Note that like in `git`, each worktree has its own `FETCH_HEAD`.
+    pub fn fetch_head_file(&self) -> PathBuf {
+        self.git_dir().join("FETCH_HEAD")
+    }
+
+    /// Return all entries of the `FETCH_HEAD` file in the order they were written by the last fetch operation,
+    /// or `None` if no such file exists.
+    ///
+    /// Entries that are marked for merge always come first.
+    pub fn fetch_head(&self) -> Result<Option<Vec<gix_fetchhead::Entry>>, Error> {
+        let buf = match std::fs::read(self.fetch_head_file()) {
+            Ok(buf) => buf,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        Ok(Some(gix_fetchhead::parse(&buf).collect::<Result<_, _>>()?))
+    }
+}
@@ -0,0 +1,578 @@
+use std::{
+    borrow::Cow,
+    collections::BTreeSet,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, SystemTime},
+};
+
+use gix_features::progress::{Count, DynNestedProgress, Progress};
+use gix_hash::ObjectId;
+use gix_object::Find;
+use gix_ref::{
+    file::transaction::PackedRefs,
+    transaction::{Change, LogChange, PreviousValue, RefEdit},
+    Target,
+};
+
+use crate::{
+    config::tree::Gc,
+    gc::{options, Error, Options, Outcome, ReflogExpiry, ReflogExpiryPattern},
+    Repository,
+};
+
+/// A `gc.pid` file younger than this is assumed to belong to a garbage collection that is still running.
+const PID_FILE_VALIDITY: Duration = Duration::from_secs(12 * 60 * 60);
+
+/// Garbage collection
+impl Repository {
+    /// Return options for [`gc()`](Self::gc()) as configured, using the same defaults as `git gc`.
+    pub fn gc_options(&self) -> Result<Options, options::Error> {
+        let config = &self.config.resolved;
+        let now = SystemTime::now();
+        let expiry = |key: &'static crate::config::tree::gc::Expiry, default: &'static str| {
+            let value = config.string(key).unwrap_or_else(|| Cow::Borrowed(default.into()));
+            key.try_into_expiry(value, now)
+        };
+        let mut patterns = Vec::<ReflogExpiryPattern>::new();
+        for section in config
+            .sections_by_name("gc")
+            .into_iter()
+            .flatten()
+            .filter(|s| (self.filter_config_section())(s.meta()))
+        {
+            let Some(pattern) = section.header().subsection_name().filter(|n| !n.is_empty()) else {
+                continue;
+            };
+            let entry = match patterns.iter_mut().find(|p| p.pattern == pattern) {
+                Some(existing) => existing,
+                None => {
+                    patterns.push(ReflogExpiryPattern {
+                        pattern: pattern.into(),
+                        expire: None,
+                        expire_unreachable: None,
+                    });
+                    patterns.last_mut().expect("just pushed")
+                }
+            };
+            if let Some(value) = section.value(Gc::PATTERN_REFLOG_EXPIRE.name) {
+                entry.expire = Gc::PATTERN_REFLOG_EXPIRE.try_into_expiry(value, now)?;
+            }
+            if let Some(value) = section.value(Gc::PATTERN_REFLOG_EXPIRE_UNREACHABLE.name) {
+                entry.expire_unreachable = Gc::PATTERN_REFLOG_EXPIRE_UNREACHABLE.try_into_expiry(value, now)?;
+            }
+        }
+        Ok(Options {
+            prune_expiry: expiry(&Gc::PRUNE_EXPIRE, "2.weeks.ago")?,
+            reflog_expiry: Some(ReflogExpiry {
+                expire: expiry(&Gc::REFLOG_EXPIRE, "90.days.ago")?,
+                expire_unreachable: expiry(&Gc::REFLOG_EXPIRE_UNREACHABLE, "30.days.ago")?,
+                patterns,
+            }),
+            pack_refs: config
+                .string(&Gc::PACK_REFS)
+                .map(|value| Gc::PACK_REFS.try_into_pack_refs(value, self.is_bare()))
+                .transpose()?
+                .unwrap_or(true),
+            repack: Some(Default::default()),
+            force: false,
+            thread_limit: None,
+        })
+    }
+
+    /// Remove unreachable loose objects and consolidate references and objects as configured in `options`, similar to `git gc`.
+    ///
+    /// The following steps are performed in order:
+    ///
+    /// * Move loose references into the `packed-refs` file if enabled.
+    /// * Remove reflog entries older than the reflog expiry dates, see [`ReflogExpiry`].
+    /// * Find all objects reachable from references, reflogs and the indices of all worktrees, including linked ones.
+    /// * Write all reachable loose objects into a new pack, and remove loose objects that are present in a pack.
+    /// * Remove unreachable loose objects that were last modified before the prune expiry date.
+    /// * Consolidate packs with [`repack()`](Self::repack()).
+    ///
+    /// To be safe against concurrent writers, loose objects are only ever removed if they are present in a pack, or if they
+    /// are unreachable and older than the prune expiry date. The latter is a grace period which gives writers the chance to make
+    /// the objects they write reachable, so it should not be too close to the present.
+    /// Unreachable objects in packs are retained.
+    ///
+    /// Only one garbage collection may run at a time, which is assured with the `gc.pid` file, also used by `git gc`.
+    /// If it was written less than 12 hours ago, the operation is aborted unless forced in `options`.
+    /// `progress` is used to report progress, and `should_interrupt` is polled to abort the operation.
+    pub fn gc(
+        &self,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::gc()");
+        let _pid_file = PidFile::acquire(self.common_dir(), options.force)?;
+
+        let mut repos = vec![if self.git_dir() == self.common_dir() {
+            self.clone()
+        } else {
+            self.main_repo()?
+        }];
+        for worktree in self.worktrees().map_err(Error::ListWorktrees)? {
+            repos.push(worktree.into_repo_with_possibly_inaccessible_worktree()?);
+        }
+
+        let mut outcome = Outcome::default();
+        if options.pack_refs {
+            outcome.packed_refs = pack_refs(&repos[0])?;
+        }
+        if let Some(expiry) = &options.reflog_expiry {
+            for (idx, repo) in repos.iter().enumerate() {
+                outcome.expired_reflog_entries += expire_reflogs(repo, idx != 0, expiry, should_interrupt)?;
+            }
+        }
+
+        let reachable = self.reachable_objects(&repos, progress, should_interrupt)?;
+        outcome.num_reachable_objects = reachable.len();
+        self.pack_and_prune_loose_objects(&reachable, progress, should_interrupt, &options, &mut outcome)?;
+
+        if let Some(mode) = options.repack {
+            outcome.repack = Some(self.repack(
+                progress,
+                should_interrupt,
+                crate::repack::Options {
+                    mode,
+                    write_multi_pack_index: false,
+                    thread_limit: options.thread_limit,
+                },
+            )?);
+        }
+        Ok(outcome)
+    }
+
+    /// Return the ids of all objects reachable from references, reflogs and indices of all `repos`.
+    ///
+    /// Objects that are missing are skipped, as is the case with parents of shallow commits.
+    fn reachable_objects(
+        &self,
+        repos: &[Repository],
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<gix_hashtable::HashSet<ObjectId>, Error> {
+        let mut seen = gix_hashtable::HashSet::default();
+        let mut tips = Vec::new();
+        let mut buf = Vec::new();
+        for repo in repos {
+            let mut names = Vec::new();
+            for reference in repo.refs.iter()?.all().map_err(Error::IterReferences)? {
+                let reference = reference?;
+                if let Target::Object(id) = reference.target {
+                    tips.push(id);
+                }
+                names.push(reference.name);
+            }
+            if let Some(head) = repo.refs.try_find("HEAD")? {
+                if let Target::Object(id) = head.target {
+                    tips.push(id);
+                }
+                names.push(head.name);
+            }
+            for name in names {
+                if let Some(log) = repo.refs.reflog_iter(name.as_ref(), &mut buf)? {
+                    for line in log.filter_map(Result::ok) {
+                        tips.extend(
+                            [line.previous_oid(), line.new_oid()]
+                                .into_iter()
+                                .filter(|id| !id.is_null()),
+                        );
+                    }
+                }
+            }
+
+            if repo.index_path().is_file() {
+                let index = repo.open_index()?;
+                for entry in index.entries() {
+                    if entry.mode != gix_index::entry::Mode::COMMIT {
+                        seen.insert(entry.id);
+                    }
+                }
+                let mut trees: Vec<_> = index.tree().into_iter().collect();
+                while let Some(tree) = trees.pop() {
+                    if tree.num_entries.is_some() {
+                        tips.push(tree.id);
+                    }
+                    trees.extend(&tree.children);
+                }
+            }
+        }
+
+        let mut objects = self.objects.clone();
+        objects.ignore_replacements = true;
+        let mut traversal = progress.add_child("find reachable objects".into());
+        traversal.init(None, gix_features::progress::count("objects"));
+        while let Some(id) = tips.pop() {
+            if !seen.insert(id) {
+                continue;
+            }
+            traversal.inc();
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            let Some(data) = objects.try_find(&id, &mut buf)? else {
+                continue;
+            };
+            match data.decode()? {
+                gix_object::ObjectRef::Commit(commit) => {
+                    tips.push(commit.tree());
+                    tips.extend(commit.parents());
+                }
+                gix_object::ObjectRef::Tree(tree) => {
+                    for entry in tree.entries {
+                        if entry.mode.is_tree() {
+                            tips.push(entry.oid.to_owned());
+                        } else if !entry.mode.is_commit() {
+                            // There is no need to look at blobs, as they don't refer to other objects.
+                            seen.insert(entry.oid.to_owned());
+                        }
+                    }
+                }
+                gix_object::ObjectRef::Tag(tag) => tips.push(tag.target()),
+                gix_object::ObjectRef::Blob(_) => {}
+            }
+        }
+        Ok(seen)
+    }
+
+    /// Write all `reachable` loose objects into a new pack, and remove all loose objects that are packed or that are unreachable
+    /// and expired.
+    fn pack_and_prune_loose_objects(
+        &self,
+        reachable: &gix_hashtable::HashSet<ObjectId>,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: &Options,
+        outcome: &mut Outcome,
+    ) -> Result<(), Error> {
+        let object_hash = self.object_hash();
+        let objects_dir = self.objects.store_ref().path();
+        let pack_dir = objects_dir.join("pack");
+        let loose = gix_odb::loose::Store::at(objects_dir, object_hash);
+        let indices = pack_indices(&pack_dir, object_hash)?;
+        let is_packed = |id: &ObjectId| indices.iter().any(|index| index.lookup(id).is_some());
+
+        let mut to_pack = Vec::new();
+        let mut redundant = Vec::new();
+        let mut expired = Vec::new();
+        for id in loose.iter() {
+            let id = id?;
+            if is_packed(&id) {
+                redundant.push(id);
+            } else if reachable.contains(&id) {
+                to_pack.push(id);
+            } else if let Some(expiry) = options.prune_expiry {
+                let is_expired = std::fs::metadata(loose.object_path(&id))
+                    .and_then(|md| md.modified())
+                    .ok()
+                    .and_then(|mtime| mtime.duration_since(SystemTime::UNIX_EPOCH).ok())
+                    .map_or(false, |mtime| {
+                        mtime.as_secs() as gix_date::SecondsSinceUnixEpoch <= expiry.seconds
+                    });
+                if is_expired {
+                    expired.push(id);
+                }
+            }
+        }
+
+        if !to_pack.is_empty() {
+            let bundle = self.write_pack(
+                to_pack.clone(),
+                &pack_dir,
+                progress,
+                should_interrupt,
+                options.thread_limit,
+            )?;
+            outcome.loose_objects_index_path = bundle.index_path;
+            self.objects.store_ref().refresh()?;
+        }
+        if should_interrupt.load(Ordering::Relaxed) {
+            return Err(Error::Interrupted);
+        }
+
+        let mut directories = BTreeSet::new();
+        for (ids, count) in [
+            (to_pack, &mut outcome.packed_loose_objects),
+            (redundant, &mut outcome.redundant_loose_objects),
+            (expired, &mut outcome.pruned_loose_objects),
+        ] {
+            for id in ids {
+                let path = loose.object_path(&id);
+                match std::fs::remove_file(&path) {
+                    Ok(()) => *count += 1,
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(err) => return Err(Error::RemoveLooseObject { path, source: err }),
+                }
+                directories.extend(path.parent().map(ToOwned::to_owned));
+            }
+        }
+        for directory in directories {
+            // It's fine if this fails as the directory isn't empty, maybe because a new object was just written into it.
+            std::fs::remove_dir(directory).ok();
+        }
+        Ok(())
+    }
+}
+
+/// Move all loose references of `repo` that point to an object into the `packed-refs` file, and return their amount.
+fn pack_refs(repo: &Repository) -> Result<usize, Error> {
+    let mut edits = Vec::new();
+    for reference in repo.refs.loose_iter().map_err(Error::IterReferences)? {
+        let reference = reference?;
+        let is_worktree_private = reference
+            .name
+            .category()
+            .map_or(true, |category| category.is_worktree_private());
+        if is_worktree_private || !matches!(reference.target, Target::Object(_)) {
+            continue;
+        }
+        edits.push(RefEdit {
+            change: Change::Update {
+                log: LogChange::default(),
+                expected: PreviousValue::MustExistAndMatch(reference.target.clone()),
+                new: reference.target,
+            },
+            name: reference.name,
+            deref: false,
+        });
+    }
+    if edits.is_empty() {
+        return Ok(0);
+    }
+    let (file_lock_fail, packed_refs_lock_fail) = repo.config.lock_timeout()?;
+    let edits = repo
+        .refs
+        .transaction()
+        .packed_refs(PackedRefs::DeletionsAndNonSymbolicUpdatesRemoveLooseSourceReference(
+            Box::new(&repo.objects),
+        ))
+        .prepare(edits, file_lock_fail, packed_refs_lock_fail)?
+        .commit(None)?;
+    Ok(edits.len())
+}
+
+/// Remove all entries that expired according to `expiry` from the reflogs of `repo`, or only from the reflogs that are
+/// private to it if it `is_linked_worktree`, and return the amount of removed entries.
+fn expire_reflogs(
+    repo: &Repository,
+    is_linked_worktree: bool,
+    expiry: &ReflogExpiry,
+    should_interrupt: &AtomicBool,
+) -> Result<usize, Error> {
+    let mut refs: Vec<(gix_ref::FullName, Option<ObjectId>)> = Vec::new();
+    for reference in repo.refs.iter()?.all().map_err(Error::IterReferences)? {
+        let reference = reference?;
+        refs.push((reference.name, reference.target.try_id().map(ToOwned::to_owned)));
+    }
+    let head_id = repo
+        .refs
+        .try_find("HEAD")?
+        .and_then(|head| head.target.try_id().map(ToOwned::to_owned));
+    // All references and HEAD itself are tips for HEAD, as its entries may refer to any branch.
+    let head_tips: Vec<_> = refs.iter().filter_map(|(_, id)| *id).chain(head_id).collect();
+
+    let mut names = vec![("HEAD".try_into().expect("valid"), head_tips)];
+    names.extend(
+        refs.into_iter()
+            .filter(|(name, _)| {
+                !is_linked_worktree || name.category().map_or(false, |category| category.is_worktree_private())
+            })
+            .map(|(name, id)| (name, id.into_iter().collect())),
+    );
+
+    let (lock_fail, _) = repo.config.lock_timeout()?;
+    let mut buf = Vec::new();
+    let mut num_expired = 0;
+    for (name, tips) in names {
+        let (expire, expire_unreachable) = expiry.dates_for(name.as_bstr());
+        let expire = expire.map(|time| time.seconds);
+        let expire_unreachable = expire_unreachable
+            .map(|time| time.seconds)
+            .filter(|unreachable| expire.map_or(true, |expire| *unreachable > expire));
+
+        // Only compute reachability if there are entries that can only expire if they are unreachable.
+        let mut reachable = None;
+        if let Some(expire_unreachable) = expire_unreachable {
+            let needs_reachability = repo
+                .refs
+                .reflog_iter(name.as_ref(), &mut buf)?
+                .map_or(false, |mut log| {
+                    log.any(|line| {
+                        line.map_or(false, |line| {
+                            let time = line.signature.time.seconds;
+                            time < expire_unreachable && expire.map_or(true, |expire| time >= expire)
+                        })
+                    })
+                });
+            if needs_reachability {
+                reachable = Some(reachable_commits(repo, tips, should_interrupt)?);
+            }
+        }
+
+        num_expired += repo.refs.reflog_expire(name.as_ref(), lock_fail, |line| {
+            let time = line.signature.time.seconds;
+            if expire.map_or(false, |expire| time < expire) {
+                return true;
+            }
+            match (expire_unreachable, &reachable) {
+                (Some(expire_unreachable), Some(reachable)) if time < expire_unreachable => {
+                    [line.previous_oid(), line.new_oid()]
+                        .into_iter()
+                        .any(|id| !id.is_null() && !reachable.contains(&id))
+                }
+                _ => false,
+            }
+        })?;
+    }
+    Ok(num_expired)
+}
+
+/// Return all commits of `repo` that are reachable from `tips`, peeling annotated tags along the way.
+///
+/// Missing objects are skipped, as is the case with parents of shallow commits.
+fn reachable_commits(
+    repo: &Repository,
+    mut tips: Vec<ObjectId>,
+    should_interrupt: &AtomicBool,
+) -> Result<gix_hashtable::HashSet<ObjectId>, Error> {
+    let mut seen = gix_hashtable::HashSet::default();
+    let mut buf = Vec::new();
+    while let Some(id) = tips.pop() {
+        if !seen.insert(id) {
+            continue;
+        }
+        if should_interrupt.load(Ordering::Relaxed) {
+            return Err(Error::Interrupted);
+        }
+        let Some(data) = repo.objects.try_find(&id, &mut buf)? else {
+            continue;
+        };
+        match data.decode()? {
+            gix_object::ObjectRef::Commit(commit) => tips.extend(commit.parents()),
+            gix_object::ObjectRef::Tag(tag) => tips.push(tag.target()),
+            gix_object::ObjectRef::Tree(_) | gix_object::ObjectRef::Blob(_) => {}
+        }
+    }
+    Ok(seen)
+}
+
+/// Return the indices of all packs in `pack_dir` that have a data file.
+fn pack_indices(pack_dir: &Path, object_hash: gix_hash::Kind) -> Result<Vec<gix_pack::index::File>, Error> {
+    let entries = match std::fs::read_dir(pack_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(Error::Repack(crate::repack::Error::ListPacks {
+                path: pack_dir.to_owned(),
+                source: err,
+            }))
+        }
+    };
+    let mut indices = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|err| {
+                Error::Repack(crate::repack::Error::ListPacks {
+                    path: pack_dir.to_owned(),
+                    source: err,
+                })
+            })?
+            .path();
+        if path.extension() == Some("idx".as_ref()) && path.with_extension("pack").is_file() {
+            indices.push(gix_pack::index::File::at(&path, object_hash)?);
+        }
+    }
+    Ok(indices)
+}
+
+/// The `gc.pid` file which is removed when dropped.
+struct PidFile(PathBuf);
+
+impl PidFile {
+    fn acquire(common_dir: &Path, force: bool) -> Result<Self, Error> {
+        let path = common_dir.join("gc.pid");
+        // The pid file is only validated while holding the lock so concurrent runs can't both consider it stale.
+        let mut lock = gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None)?;
+        let hostname = hostname();
+        if !force {
+            if let Some(content) = running_gc(&path, &hostname) {
+                return Err(Error::AlreadyRunning { path, content });
+            }
+        }
+        // Like `git`, write the process id and the hostname so the file can be recognized by other implementations.
+        writeln!(lock, "{} {}", std::process::id(), hostname).map_err(|err| Error::WritePidFile {
+            path: path.clone(),
+            source: err,
+        })?;
+        lock.commit()?;
+        Ok(PidFile(path))
+    }
+}
+
+/// Return the content of the pid file at `path` if it was written recently by a garbage collection that may still be
+/// running, like `git` does it.
+///
+/// As processes on other hosts can't be checked, they are assumed to be running. On this host, the pid file is
+/// stale if its process doesn't exist anymore.
+fn running_gc(path: &Path, hostname: &str) -> Option<String> {
+    let is_recent = std::fs::metadata(path)
+        .and_then(|md| md.modified())
+        .ok()
+        .and_then(|mtime| mtime.elapsed().ok())
+        .map_or(false, |age| age < PID_FILE_VALIDITY);
+    if !is_recent {
+        return None;
+    }
+    let content = std::fs::read_to_string(path).ok()?.trim().to_owned();
+    let mut tokens = content.split_whitespace();
+    let pid: u32 = tokens.next()?.parse().ok()?;
+    let locking_host = tokens.next()?;
+    (locking_host != hostname || is_running(pid)).then_some(content)
+}
+
+/// The name of this host as written to the pid file.
+fn hostname() -> String {
+    #[cfg(unix)]
+    {
+        let mut buf = [0u8; 256];
+        // SAFETY: the buffer is valid for writes of its length, and `gethostname()` doesn't write past it.
+        #[allow(unsafe_code)]
+        let res = unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) };
+        if res == 0 {
+            let len = buf.iter().position(|b| *b == 0).unwrap_or(buf.len());
+            if let Ok(name) = std::str::from_utf8(&buf[..len]) {
+                return name.to_owned();
+            }
+        }
+    }
+    std::env::var("COMPUTERNAME").unwrap_or_else(|_| "unknown".into())
+}
+
+/// Return `true` if the process with `pid` exists. If that can't be determined, it's assumed to exist.
+fn is_running(pid: u32) -> bool {
+    #[cfg(unix)]
+    {
+        let Ok(pid) = libc::pid_t::try_from(pid) else {
+            return true;
+        };
+        // SAFETY: signal 0 only checks if the process exists and if we may signal it, without sending anything.
+        #[allow(unsafe_code)]
+        let res = unsafe { libc::kill(pid, 0) };
+        res == 0 || std::io::Error::last_os_error().raw_os_error() != Some(libc::ESRCH)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = pid;
+        true
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        std::fs::remove_file(&self.0).ok();
+    }
+}
@@ -0,0 +1,495 @@
+use std::collections::{BTreeMap, HashSet};
+
+use gix_diff::blob::{patch, stat};
+use gix_hash::ObjectId;
+
+use crate::{
+    apply::{apply_mailbox, format_patch},
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    object::tree::diff::{change::Event, Action},
+    Tree,
+};
+
+/// The width of diffstats and shortlogs in mails.
+const MAIL_WIDTH: usize = 72;
+
+/// Creating and applying patches sent by mail
+impl crate::Repository {
+    /// Create a mail for each commit reachable from `until` but not from `since`, from the oldest to the newest,
+    /// similar to `git format-patch <since>..<until>`. Merge commits are skipped.
+    ///
+    /// Each mail contains the author, message and diff of its commit, with renames detected as configured by
+    /// `diff.renames`. If enabled in `options`, a cover letter precedes them, from the configured committer and
+    /// with placeholders for its subject and text.
+    pub fn format_patch(
+        &self,
+        since: impl Into<ObjectId>,
+        until: impl Into<ObjectId>,
+        options: &format_patch::Options,
+    ) -> Result<Vec<format_patch::Email>, format_patch::Error> {
+        let commits = self.commits_in_range::<format_patch::Error>(since.into(), until.into())?;
+        let Some(&tip) = commits.last() else {
+            return Ok(Vec::new());
+        };
+
+        let first_number = options.start_number.max(1);
+        let total = first_number - 1 + commits.len();
+        let numbered = options.numbered.unwrap_or(commits.len() > 1 || options.cover_letter);
+        let subject_prefix = |number: usize| {
+            let mut prefix = options.subject_prefix.clone();
+            if numbered {
+                if !prefix.is_empty() {
+                    prefix.push_byte(b' ');
+                }
+                prefix.push_str(format!("{number}/{total}"));
+            }
+            prefix
+        };
+
+        let mut emails = Vec::new();
+        let mut shortlog = BTreeMap::<BString, Vec<BString>>::new();
+        let mut has_non_ascii_commit = false;
+        let mut base_tree = None;
+        for (number, id) in (first_number..).zip(&commits) {
+            let commit = self.find_commit(*id)?;
+            let commit_ref = commit.decode()?;
+            has_non_ascii_commit |= !commit.data.is_ascii();
+            let subject = commit_ref.message_summary();
+            shortlog
+                .entry(commit_ref.author.name.to_owned())
+                .or_default()
+                .push(subject.clone().into_owned());
+
+            let old_tree = match commit_ref.parents().next() {
+                Some(parent) => self.find_commit(parent)?.tree()?,
+                None => self.empty_tree(),
+            };
+            base_tree.get_or_insert(old_tree.id);
+
+            let mut data = Vec::new();
+            gix_apply::mailbox::write::header(
+                &mut data,
+                &gix_apply::mailbox::write::Header {
+                    id,
+                    from: commit_ref.author,
+                    subject_prefix: subject_prefix(number).as_ref(),
+                    subject: subject.as_ref(),
+                    utf8_body: !commit_ref.message.is_ascii(),
+                },
+            )?;
+            let body = mail_body(commit_ref.message);
+            if !body.is_empty() {
+                data.extend_from_slice(body);
+                data.push(b'\n');
+            }
+            let changes = self.tree_changes::<format_patch::Error>(&old_tree, &commit.tree()?)?;
+            if options.stat {
+                data.extend_from_slice(b"---\n");
+                write_stat(&mut data, &changes)?;
+            }
+            data.push(b'\n');
+            for change in &changes {
+                change.write_patch(&mut data)?;
+            }
+            write_signature(&mut data, options.signature.as_ref());
+
+            emails.push(format_patch::Email {
+                commit: Some(*id),
+                file_name: gix_apply::mailbox::write::file_name(number, subject.as_ref()),
+                data: data.into(),
+            });
+        }
+
+        if options.cover_letter {
+            let committer = self.committer().ok_or(format_patch::Error::CommitterMissing)??;
+            let mut data = Vec::new();
+            gix_apply::mailbox::write::header(
+                &mut data,
+                &gix_apply::mailbox::write::Header {
+                    id: &tip,
+                    from: committer,
+                    subject_prefix: subject_prefix(first_number - 1).as_ref(),
+                    subject: "*** SUBJECT HERE ***".into(),
+                    utf8_body: has_non_ascii_commit,
+                },
+            )?;
+            data.extend_from_slice(b"*** BLURB HERE ***\n\n");
+            for (name, subjects) in &shortlog {
+                data.extend_from_slice(name);
+                data.extend_from_slice(format!(" ({}):\n", subjects.len()).as_bytes());
+                for subject in subjects {
+                    gix_apply::mailbox::write::wrapped_text(&mut data, subject.as_ref(), 2, 4, MAIL_WIDTH);
+                    data.push(b'\n');
+                }
+                data.push(b'\n');
+            }
+            let base_tree = self
+                .find_object(base_tree.expect("there is at least one commit"))?
+                .into_tree();
+            let tip_tree = self.find_commit(tip)?.tree()?;
+            write_stat(
+                &mut data,
+                &self.tree_changes::<format_patch::Error>(&base_tree, &tip_tree)?,
+            )?;
+            data.push(b'\n');
+            write_signature(&mut data, options.signature.as_ref());
+
+            emails.insert(
+                0,
+                format_patch::Email {
+                    commit: None,
+                    file_name: gix_apply::mailbox::write::file_name(first_number - 1, "cover letter".into()),
+                    data: data.into(),
+                },
+            );
+        }
+        Ok(emails)
+    }
+
+    /// Apply the patches in all mails of the mailbox `mbox` one after another on top of the commit `onto`, creating a
+    /// commit for each of them with the author and message of its mail, similar to `git am`.
+    ///
+    /// The commits are only written to the object database, and no reference is updated, nor are the index or the worktree
+    /// touched, which makes this usable in bare repositories.
+    ///
+    /// If a patch can only be applied with conflicts or rejected hunks, no commit is created for it and the
+    /// partially applied tree is returned along with all commits created so far.
+    pub fn apply_mailbox(
+        &self,
+        mbox: &[u8],
+        onto: impl Into<ObjectId>,
+        options: apply_mailbox::Options,
+    ) -> Result<apply_mailbox::Outcome, apply_mailbox::Error> {
+        let mut parent = onto.into();
+        let mut tree = self.find_commit(parent)?.tree()?.id;
+        let mut commits = Vec::new();
+        for (index, mail) in gix_apply::mailbox::split(mbox).enumerate() {
+            let mail = gix_apply::mailbox::parse(mail, options.mail)
+                .map_err(|err| apply_mailbox::Error::ParseMail { index, source: err })?;
+            let subject = || mail.message_ref().summary().into_owned();
+            let patches = gix_apply::patch::parse(&mail.patch, options.patch).map_err(|err| {
+                apply_mailbox::Error::ParsePatch {
+                    index,
+                    subject: subject(),
+                    source: err,
+                }
+            })?;
+            let outcome = self
+                .apply_patches_to_tree(tree, &patches, options.apply)
+                .map_err(|err| apply_mailbox::Error::Apply {
+                    index,
+                    subject: subject(),
+                    source: err,
+                })?;
+            if !outcome.conflicts.is_empty() || !outcome.rejected.is_empty() {
+                return Ok(apply_mailbox::Outcome::Conflict {
+                    commits,
+                    index,
+                    mail,
+                    outcome,
+                });
+            }
+
+            let committer = self.committer().ok_or(apply_mailbox::Error::CommitterMissing)??;
+            let mut message = mail.message;
+            if options.signoff {
+                append_signoff(&mut message, committer);
+            }
+            let commit = gix_object::Commit {
+                tree: outcome.tree,
+                parents: Some(parent).into_iter().collect(),
+                author: mail.author,
+                committer: committer.into(),
+                encoding: None,
+                message,
+                extra_headers: Default::default(),
+            };
+            parent = self.write_object(&commit)?.detach();
+            tree = outcome.tree;
+            commits.push(parent);
+        }
+        Ok(apply_mailbox::Outcome::Applied { commits })
+    }
+
+    /// Return all commits reachable from `until` but not from `since` that aren't merge commits, from the oldest to the newest.
+    pub(super) fn commits_in_range<E>(&self, since: ObjectId, until: ObjectId) -> Result<Vec<ObjectId>, E>
+    where
+        E: From<crate::revision::walk::Error> + From<crate::revision::walk::iter::Error>,
+    {
+        let hidden = self
+            .rev_walk([since])
+            .all()?
+            .map(|info| info.map(|info| info.id))
+            .collect::<Result<HashSet<_>, _>>()?;
+        let mut commits = Vec::new();
+        for info in self.rev_walk([until]).selected(move |id| !hidden.contains(id))? {
+            let info = info?;
+            if info.parent_ids.len() <= 1 {
+                commits.push(info.id);
+            }
+        }
+        commits.reverse();
+        Ok(commits)
+    }
+
+    /// Return all changes to files between `old` and `new`, sorted by path.
+    pub(super) fn tree_changes<E>(&self, old: &Tree<'_>, new: &Tree<'_>) -> Result<Vec<FileChange>, E>
+    where
+        E: From<crate::diff::new_rewrites::Error>
+            + From<crate::object::tree::diff::for_each::Error>
+            + From<crate::object::find::existing::Error>,
+    {
+        let mut changes = Vec::new();
+        old.changes()?.track_path().for_each_to_obtain_tree(new, |change| {
+            let side = |path: &BStr, id: crate::Id<'_>, mode| Side {
+                path: path.to_owned(),
+                id: id.detach(),
+                mode,
+            };
+            let location = change.location;
+            let (old, new, rewrite) = match change.event {
+                Event::Addition { entry_mode, id } if !entry_mode.is_tree() => {
+                    (None, Some(side(location, id, entry_mode)), None)
+                }
+                Event::Deletion { entry_mode, id } if !entry_mode.is_tree() => {
+                    (Some(side(location, id, entry_mode)), None, None)
+                }
+                Event::Modification {
+                    previous_entry_mode,
+                    previous_id,
+                    entry_mode,
+                    id,
+                } if !previous_entry_mode.is_tree() && !entry_mode.is_tree() => (
+                    Some(side(location, previous_id, previous_entry_mode)),
+                    Some(side(location, id, entry_mode)),
+                    None,
+                ),
+                Event::Rewrite {
+                    source_location,
+                    source_entry_mode,
+                    source_id,
+                    diff,
+                    entry_mode,
+                    id,
+                    copy,
+                } => (
+                    Some(side(source_location, source_id, source_entry_mode)),
+                    Some(side(location, id, entry_mode)),
+                    Some((diff.map_or(100, |diff| (diff.similarity * 100.0) as u8), copy)),
+                ),
+                _ => return Ok::<_, std::convert::Infallible>(Action::Continue),
+            };
+            changes.push((old, new, rewrite));
+            Ok(Action::Continue)
+        })?;
+
+        let mut out = Vec::with_capacity(changes.len());
+        for (old, new, rewrite) in changes {
+            let data = |side: &Option<Side>| -> Result<Vec<u8>, E> {
+                Ok(match side {
+                    Some(side) if side.mode.is_commit() => format!("Subproject commit {}\n", side.id).into_bytes(),
+                    Some(side) => self.find_object(side.id)?.detach().data,
+                    None => Vec::new(),
+                })
+            };
+            let (old_data, new_data) = (data(&old)?, data(&new)?);
+            out.push(FileChange::new(old, new, rewrite, &old_data, &new_data));
+        }
+        out.sort_by(|a, b| a.path().cmp(b.path()));
+        Ok(out)
+    }
+}
+
+struct Side {
+    path: BString,
+    id: ObjectId,
+    mode: gix_object::tree::EntryMode,
+}
+
+/// A change to a file with everything needed to write it as patch and diffstat.
+pub(super) struct FileChange {
+    old: Option<Side>,
+    new: Option<Side>,
+    /// The similarity in percent and whether it's a copy if the file was renamed or copied.
+    rewrite: Option<(u8, bool)>,
+    counts: stat::Counts,
+    /// The hunks of a text diff, or the `GIT binary patch` if one of the sides is binary.
+    pub(super) content: BString,
+    pub(super) is_binary: bool,
+}
+
+impl FileChange {
+    fn new(
+        old: Option<Side>,
+        new: Option<Side>,
+        rewrite: Option<(u8, bool)>,
+        old_data: &[u8],
+        new_data: &[u8],
+    ) -> Self {
+        let is_binary = |data: &[u8]| data[..data.len().min(8000)].contains(&0);
+        let (counts, content, is_binary) = if old_data == new_data {
+            (stat::Counts::Lines { added: 0, removed: 0 }, BString::default(), false)
+        } else if is_binary(old_data) || is_binary(new_data) {
+            let mut content = Vec::new();
+            gix_apply::binary::encode(old_data, new_data, &mut content).expect("writing to a vec never fails");
+            (
+                stat::Counts::Binary {
+                    old_size: old_data.len() as u64,
+                    new_size: new_data.len() as u64,
+                },
+                content.into(),
+                true,
+            )
+        } else {
+            let input = gix_diff::blob::intern::InternedInput::new(
+                gix_diff::blob::sources::byte_lines_with_terminator(old_data),
+                gix_diff::blob::sources::byte_lines_with_terminator(new_data),
+            );
+            let diff = gix_diff::blob::diff(
+                gix_diff::blob::Algorithm::Myers,
+                &input,
+                gix_diff::blob::sink::Counter::new(gix_diff::blob::UnifiedDiff::new(&input, Default::default())),
+            );
+            (
+                stat::Counts::Lines {
+                    added: diff.insertions,
+                    removed: diff.removals,
+                },
+                diff.wrapped,
+                false,
+            )
+        };
+        FileChange {
+            old,
+            new,
+            rewrite,
+            counts,
+            content,
+            is_binary,
+        }
+    }
+
+    fn path(&self) -> &BStr {
+        self.new
+            .as_ref()
+            .or(self.old.as_ref())
+            .expect("at least one side")
+            .path
+            .as_ref()
+    }
+
+    pub(super) fn as_change(&self) -> patch::Change<'_> {
+        fn side(side: &Side) -> patch::Side<'_> {
+            patch::Side {
+                path: side.path.as_ref(),
+                id: &side.id,
+                mode: side.mode,
+            }
+        }
+        match (self.old.as_ref().map(side), self.new.as_ref().map(side), self.rewrite) {
+            (Some(old), Some(new), Some((similarity, copy))) => patch::Change::Rewrite {
+                old,
+                new,
+                similarity,
+                copy,
+            },
+            (Some(old), Some(new), None) => patch::Change::Modification { old, new },
+            (None, Some(new), _) => patch::Change::Addition { new },
+            (Some(old), None, _) => patch::Change::Deletion { old },
+            (None, None, _) => unreachable!("at least one side is set"),
+        }
+    }
+
+    fn write_patch(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        let change = self.as_change();
+        if self.is_binary {
+            let options = patch::Options {
+                id_hex_len: self.path_id_hex_len(),
+                ..Default::default()
+            };
+            patch::write(
+                out,
+                &change,
+                patch::Content::BinaryPatch(self.content.as_ref()),
+                &options,
+            )
+        } else {
+            patch::write(
+                out,
+                &change,
+                patch::Content::Hunks(self.content.as_ref()),
+                &Default::default(),
+            )
+        }
+    }
+
+    /// Binary patches use full object ids in their `index` line.
+    fn path_id_hex_len(&self) -> usize {
+        self.old
+            .as_ref()
+            .or(self.new.as_ref())
+            .expect("at least one side")
+            .id
+            .kind()
+            .len_in_hex()
+    }
+}
+
+fn write_stat(out: &mut Vec<u8>, changes: &[FileChange]) -> std::io::Result<()> {
+    let changes: Vec<_> = changes
+        .iter()
+        .map(|change| (change.as_change(), change.counts))
+        .collect();
+    stat::write(
+        out,
+        &changes,
+        &stat::Options {
+            width: MAIL_WIDTH,
+            ..Default::default()
+        },
+    )
+}
+
+fn write_signature(out: &mut Vec<u8>, signature: Option<&BString>) {
+    let Some(signature) = signature.filter(|signature| !signature.is_empty()) else {
+        return;
+    };
+    out.extend_from_slice(b"-- \n");
+    out.extend_from_slice(signature);
+    if !signature.ends_with(b"\n") {
+        out.push(b'\n');
+    }
+    out.push(b'\n');
+}
+
+/// Return the part of `message` after its title paragraph, without leading empty lines and trailing whitespace.
+fn mail_body(message: &BStr) -> &[u8] {
+    let mut lines = message.lines_with_terminator().peekable();
+    while lines.next_if(|line| line.trim().is_empty()).is_some() {}
+    while lines.next_if(|line| !line.trim().is_empty()).is_some() {}
+    while lines.next_if(|line| line.trim().is_empty()).is_some() {}
+    let consumed = message.len() - lines.map(<[u8]>::len).sum::<usize>();
+    message[consumed..].trim_end()
+}
+
+/// Add a `Signed-off-by` trailer for `committer` to `message` unless it's already the last trailer.
+fn append_signoff(message: &mut BString, committer: gix_actor::SignatureRef<'_>) {
+    let mut signoff = BString::from("Signed-off-by: ");
+    signoff.push_str(committer.name);
+    signoff.push_str(" <");
+    signoff.push_str(committer.email);
+    signoff.push_str(">");
+
+    let has_trailers = gix_object::commit::MessageRef::from_bytes(message)
+        .body()
+        .map_or(false, |body| body.trailers().next().is_some());
+    if has_trailers && message.lines().next_back() == Some(signoff.as_slice()) {
+        return;
+    }
+    while message.ends_with(b"\n") {
+        message.pop();
+    }
+    message.push_str(if has_trailers { "\n" } else { "\n\n" });
+    message.push_str(&signoff);
+    message.push_byte(b'\n');
+}
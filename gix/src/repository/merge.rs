@@ -0,0 +1,253 @@
+use gix_hash::ObjectId;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    ext::ObjectIdExt,
+    merge::{cherry_pick, revert},
+    Commit,
+};
+
+/// Merging of trees and commits
+impl crate::Repository {
+    /// Merge the changes from the `base` tree to the `theirs` tree into the `ours` tree and return the merged tree, which is written
+    /// to the object database along with all conflicting paths.
+    ///
+    /// `labels` are used to annotate conflict markers, which are written into blobs that couldn't be merged cleanly.
+    pub fn merge_trees(
+        &self,
+        base: impl AsRef<gix_hash::oid>,
+        ours: impl AsRef<gix_hash::oid>,
+        theirs: impl AsRef<gix_hash::oid>,
+        labels: gix_merge::blob::Labels<'_>,
+        options: gix_merge::tree::Options,
+    ) -> Result<gix_merge::tree::Outcome, gix_merge::tree::Error> {
+        gix_merge::tree::merge(
+            base.as_ref(),
+            ours.as_ref(),
+            theirs.as_ref(),
+            labels,
+            &self.objects,
+            |kind, data| self.write_object_inner(data, kind).map(crate::Id::detach),
+            options,
+        )
+    }
+
+    /// Apply the changes introduced by `commit` on top of the commit `onto`, similar to `git cherry-pick`, and write
+    /// the resulting commit to the object database.
+    ///
+    /// The new commit keeps the author and message of `commit`, has the configured committer and `onto` as its only parent.
+    /// No reference is updated, and neither the index nor the worktree are touched, which makes this usable in bare repositories.
+    ///
+    /// If the changes can't be applied cleanly, `CHERRY_PICK_HEAD` and `MERGE_MSG` are written to the repository and the merged
+    /// tree with conflict markers is returned instead.
+    pub fn cherry_pick(
+        &self,
+        commit: impl Into<ObjectId>,
+        onto: impl Into<ObjectId>,
+        options: cherry_pick::Options,
+    ) -> Result<cherry_pick::Outcome<'_>, cherry_pick::Error> {
+        let commit = self.find_commit(commit)?;
+        let onto = self.find_commit(onto)?;
+        let parent = mainline_parent(&commit, options.mainline)?;
+
+        let commit_ref = commit.decode()?;
+        let title = commit_title(&commit, &commit_ref.message_summary());
+        let mut message = BString::from(commit_ref.message);
+        if options.record_origin {
+            append_origin_line(&mut message, commit.id);
+        }
+
+        let ours_label = onto.id().shorten_or_id().to_string();
+        let base_label = format!("parent of {title}");
+        self.apply_commit_changes(
+            ApplyChanges {
+                base_tree: self.commit_tree_id(parent)?,
+                ours: &onto,
+                theirs_tree: commit.tree_id()?.detach(),
+                labels: gix_merge::blob::Labels {
+                    ancestor: Some(base_label.as_str().into()),
+                    current: Some(ours_label.as_str().into()),
+                    other: Some(title.as_str().into()),
+                },
+                author: Some(commit_ref.author),
+                message,
+                state_file: "CHERRY_PICK_HEAD",
+                commit_id: commit.id,
+            },
+            options,
+        )
+    }
+
+    /// Undo the changes introduced by `commit` on top of the commit that `HEAD` points to, similar to `git revert`, and write
+    /// the resulting commit to the object database.
+    ///
+    /// The new commit has the configured author and committer, a message referring to the reverted commit, and the `HEAD` commit
+    /// as its only parent.
+    /// No reference is updated, and neither the index nor the worktree are touched, which makes this usable in bare repositories.
+    ///
+    /// If the changes can't be undone cleanly, `REVERT_HEAD` and `MERGE_MSG` are written to the repository and the merged
+    /// tree with conflict markers is returned instead.
+    pub fn revert(
+        &self,
+        commit: impl Into<ObjectId>,
+        options: revert::Options,
+    ) -> Result<revert::Outcome<'_>, revert::Error> {
+        let onto = self.head_commit()?;
+        let commit = self.find_commit(commit).map_err(cherry_pick::Error::from)?;
+        let parent = mainline_parent(&commit, options.mainline)?;
+
+        let commit_ref = commit.decode().map_err(cherry_pick::Error::from)?;
+        let title = commit_title(&commit, &commit_ref.message_summary());
+        let mut message = BString::from(format!(
+            "Revert \"{}\"\n\nThis reverts commit {}",
+            commit_ref.message_summary(),
+            commit.id
+        ));
+        match (parent, options.mainline) {
+            (Some(parent), Some(_)) => message.push_str(format!(", reversing\nchanges made to {parent}.\n")),
+            _ => message.push_str(".\n"),
+        }
+
+        let parent_label = format!("parent of {title}");
+        Ok(self.apply_commit_changes(
+            ApplyChanges {
+                base_tree: commit.tree_id().map_err(cherry_pick::Error::from)?.detach(),
+                ours: &onto,
+                theirs_tree: self.commit_tree_id(parent)?,
+                labels: gix_merge::blob::Labels {
+                    ancestor: Some(title.as_str().into()),
+                    current: Some("HEAD".into()),
+                    other: Some(parent_label.as_str().into()),
+                },
+                author: None,
+                message,
+                state_file: "REVERT_HEAD",
+                commit_id: commit.id,
+            },
+            options,
+        )?)
+    }
+}
+
+struct ApplyChanges<'a, 'repo> {
+    /// The tree that is the common ancestor of `ours` and `theirs_tree`.
+    base_tree: ObjectId,
+    /// The commit to apply the changes to, which will be the parent of the new commit.
+    ours: &'a Commit<'repo>,
+    /// The tree containing the changes to apply.
+    theirs_tree: ObjectId,
+    labels: gix_merge::blob::Labels<'a>,
+    /// The author to use for the new commit, or `None` to use the configured one.
+    author: Option<gix_actor::SignatureRef<'a>>,
+    message: BString,
+    /// The name of the file to write into the `.git` directory in case of conflicts.
+    state_file: &'static str,
+    /// The commit to write into `state_file`.
+    commit_id: ObjectId,
+}
+
+impl crate::Repository {
+    fn apply_commit_changes<'repo>(
+        &'repo self,
+        ApplyChanges {
+            base_tree,
+            ours,
+            theirs_tree,
+            labels,
+            author,
+            message,
+            state_file,
+            commit_id,
+        }: ApplyChanges<'_, 'repo>,
+        options: cherry_pick::Options,
+    ) -> Result<cherry_pick::Outcome<'repo>, cherry_pick::Error> {
+        let merge = self.merge_trees(base_tree, ours.tree_id()?, theirs_tree, labels, options.tree_merge)?;
+
+        if merge.has_conflicts() {
+            let mut merge_msg = message.clone();
+            merge_msg.push_str("\n# Conflicts:\n");
+            for conflict in &merge.conflicts {
+                merge_msg.push_str("#\t");
+                merge_msg.push_str(&conflict.path);
+                merge_msg.push_byte(b'\n');
+            }
+            self.write_state_file(state_file, format!("{commit_id}\n").as_bytes())?;
+            self.write_state_file("MERGE_MSG", &merge_msg)?;
+            return Ok(cherry_pick::Outcome::Conflict { merge, message });
+        }
+
+        let committer = self.committer().ok_or(cherry_pick::Error::CommitterMissing)??;
+        let author = match author {
+            Some(author) => author,
+            None => self.author().ok_or(cherry_pick::Error::AuthorMissing)??,
+        };
+        let commit = gix_object::Commit {
+            tree: merge.tree,
+            parents: Some(ours.id).into_iter().collect(),
+            author: author.into(),
+            committer: committer.into(),
+            encoding: None,
+            message,
+            extra_headers: Default::default(),
+        };
+        Ok(cherry_pick::Outcome::Commit(self.write_object(&commit)?))
+    }
+
+    /// Return the tree of the commit with `id`, or the empty tree if there is no such commit.
+    fn commit_tree_id(&self, id: Option<ObjectId>) -> Result<ObjectId, cherry_pick::Error> {
+        Ok(match id {
+            Some(id) => self.find_commit(id)?.tree_id()?.detach(),
+            None => ObjectId::empty_tree(self.object_hash()),
+        })
+    }
+
+    fn write_state_file(&self, name: &str, content: &[u8]) -> Result<(), cherry_pick::Error> {
+        use std::io::Write;
+        let path = self.path().join(name);
+        let mut lock = gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None)?;
+        lock.write_all(content)
+            .map_err(|source| cherry_pick::Error::WriteState { path, source })?;
+        lock.commit()?;
+        Ok(())
+    }
+}
+
+/// Return the parent of `commit` whose changes to `commit` should be applied, or `None` if it's a root commit.
+fn mainline_parent(commit: &Commit<'_>, mainline: Option<usize>) -> Result<Option<ObjectId>, cherry_pick::Error> {
+    let parents: Vec<_> = commit.parent_ids().map(crate::Id::detach).collect();
+    match mainline {
+        None if parents.len() > 1 => Err(cherry_pick::Error::MainlineMissing { commit: commit.id }),
+        None => Ok(parents.first().copied()),
+        Some(_) if parents.len() < 2 => Err(cherry_pick::Error::MainlineForNonMerge { commit: commit.id }),
+        Some(mainline) => parents
+            .get(mainline.wrapping_sub(1))
+            .copied()
+            .map(Some)
+            .ok_or(cherry_pick::Error::MainlineOutOfRange {
+                commit: commit.id,
+                mainline,
+            }),
+    }
+}
+
+/// Produce a title like `abcdef1 (subject)` to identify `commit` in conflict markers, similar to git.
+fn commit_title(commit: &Commit<'_>, summary: &BStr) -> String {
+    format!("{} ({summary})", commit.id.attach(commit.repo).shorten_or_id())
+}
+
+/// Append the line that notes where a cherry-picked commit originated from, with an empty line
+/// in between unless the message already ends with a block of trailers, just like `git`.
+fn append_origin_line(message: &mut BString, id: ObjectId) {
+    let trimmed_len = message.trim_end().len();
+    message.truncate(trimmed_len);
+    message.push_byte(b'\n');
+    let parsed = gix_object::commit::MessageRef::from_bytes(message);
+    let ends_with_trailers = parsed
+        .body
+        .zip(parsed.body())
+        .map_or(false, |(raw, body)| body.without_trailer().len() != raw.len());
+    if !ends_with_trailers {
+        message.push_byte(b'\n');
+    }
+    message.push_str(format!("(cherry picked from commit {id})\n"));
+}
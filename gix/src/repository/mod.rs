@@ -36,6 +36,8 @@ impl crate::Repository {
     }
 }
 
+#[cfg(feature = "apply")]
+mod apply;
 #[cfg(any(feature = "attributes", feature = "excludes"))]
 pub mod attributes;
 mod cache;
@@ -47,8 +49,12 @@ pub mod diff;
 #[cfg(feature = "dirwalk")]
 mod dirwalk;
 ///
+pub mod fetch_head;
+///
 #[cfg(feature = "attributes")]
 pub mod filter;
+#[cfg(feature = "gc")]
+mod gc;
 mod graph;
 pub(crate) mod identity;
 mod impls;
@@ -57,14 +63,25 @@ mod index;
 pub(crate) mod init;
 mod kind;
 mod location;
+#[cfg(feature = "apply")]
+mod mailbox;
+#[cfg(feature = "pull")]
+mod pull;
 #[cfg(feature = "mailmap")]
 mod mailmap;
+#[cfg(feature = "merge")]
+mod merge;
 mod object;
 #[cfg(feature = "attributes")]
 mod pathspec;
+#[cfg(feature = "apply")]
+mod range_diff;
 mod reference;
 mod remote;
+#[cfg(feature = "repack")]
+mod repack;
 mod revision;
+mod server_info;
 mod shallow;
 mod state;
 #[cfg(feature = "attributes")]
@@ -105,6 +122,26 @@ pub mod merge_base {
     }
 }
 
+///
+#[cfg(feature = "revision")]
+pub mod refs_containing {
+    /// The error returned by [Repository::refs_containing()](crate::Repository::refs_containing()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        OpenCache(#[from] crate::repository::commit_graph_if_enabled::Error),
+        #[error(transparent)]
+        Contains(#[from] gix_revision::contains::Error),
+        #[error(transparent)]
+        RefIterInit(#[from] crate::reference::iter::init::Error),
+        #[error(transparent)]
+        RefIter(#[from] crate::reference::iter::Error),
+        #[error(transparent)]
+        Iter(Box<dyn std::error::Error + Send + Sync + 'static>),
+    }
+}
+
 ///
 #[cfg(feature = "revision")]
 pub mod merge_base_with_graph {
@@ -152,6 +189,26 @@ pub mod index_from_tree {
     }
 }
 
+///
+#[cfg(feature = "apply")]
+pub mod apply_patches {
+    /// The error returned by [Repository::apply_patches()](crate::Repository::apply_patches()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Patches can't be applied to the worktree of a bare repository")]
+        MissingWorktree,
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        ApplyToIndex(#[from] gix_apply::index::Error),
+        #[error(transparent)]
+        ApplyToWorktree(#[from] gix_apply::worktree::Error),
+        #[error(transparent)]
+        WriteIndex(#[from] gix_index::file::write::Error),
+    }
+}
+
 ///
 pub mod branch_remote_ref_name {
 
@@ -184,6 +241,39 @@ pub mod branch_remote_tracking_ref_name {
     }
 }
 
+///
+pub mod update_server_info {
+    /// The error returned by [Repository::update_server_info()](crate::Repository::update_server_info()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        OpenReferences(#[from] crate::reference::iter::Error),
+        #[error(transparent)]
+        InitReferenceIter(#[from] crate::reference::iter::init::Error),
+        #[error("Could not read a reference")]
+        IterReferences(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error(transparent)]
+        FollowReference(#[from] crate::reference::follow::to_object::Error),
+        #[error(transparent)]
+        PeelReference(#[from] crate::reference::peel::Error),
+        #[error("Could not list packs in \"{}\"", path.display())]
+        ListPacks {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error(transparent)]
+        Lock(#[from] gix_lock::acquire::Error),
+        #[error("Could not write \"{}\"", path.display())]
+        Write {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error("Could not move the updated file into place")]
+        Commit(#[source] std::io::Error),
+    }
+}
+
 ///
 #[cfg(feature = "attributes")]
 pub mod pathspec_defaults_ignore_case {
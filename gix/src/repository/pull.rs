@@ -0,0 +1,587 @@
+use std::{collections::BTreeSet, sync::atomic::AtomicBool};
+
+use gix_hash::ObjectId;
+use gix_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    FullNameRef, Target,
+};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    config::{
+        cache::util::ApplyLeniencyDefault,
+        tree::{Branch, Pull},
+    },
+    pull::{integrate, FastForward, Integration, Options, Rebase},
+};
+
+/// The action to prefix reflog messages with, just like `git pull` does.
+const REFLOG_ACTION: &str = "pull";
+
+/// Pulling
+impl crate::Repository {
+    /// Integrate the commit marked for merge in `FETCH_HEAD`, as written by the last fetch, into the current branch
+    /// by fast-forwarding, merging or rebasing, similar to what `git pull` does after fetching.
+    ///
+    /// The index and the files in the worktree are updated to match the new `HEAD` commit, but only if none of the
+    /// files that are changed have local modifications, and no untracked file would be overwritten.
+    /// Changes staged for other files are kept. Like in `git`, rebasing requires that neither the index nor tracked
+    /// files have any changes at all.
+    /// `should_interrupt` is checked while files are written.
+    ///
+    /// Like in `git`, `ORIG_HEAD` is set to the previous `HEAD` commit, and reflog messages
+    /// are `pull: Fast-forward` and `pull: Merge made by the 'ort' strategy.` respectively.
+    /// When rebasing, the branch is updated with `pull (finish): <branch> onto <commit>`.
+    ///
+    /// ### Configuration
+    ///
+    /// - `branch.<name>.rebase` and `pull.rebase` determine whether to rebase instead of merging,
+    ///   unless [`Options::rebase`] is set.
+    /// - `pull.ff` determines how fast-forwards are handled, unless [`Options::fast_forward`] is set.
+    ///
+    /// If neither is configured and the branches have diverged, an error is returned, just like `git` does.
+    ///
+    /// ### Deviation
+    ///
+    /// - Rebasing local merge commits fails, and rebasing with `merges` or `interactive` isn't supported.
+    /// - Merging more than one commit at once isn't supported.
+    /// - If there are conflicts, nothing is changed and [`Integration::Conflict`] is returned instead, so no merge or
+    ///   rebase state like `MERGE_HEAD` is written, and neither the index nor the worktree contain the conflicts.
+    /// - When rebasing, `HEAD` isn't detached and all references are updated at once, so there are no
+    ///   `pull (start)`, `pull (pick)` and `pull (finish): returning to <branch>` reflog entries.
+    pub fn integrate_fetch_head(
+        &self,
+        should_interrupt: &AtomicBool,
+        options: Options,
+    ) -> Result<Integration, integrate::Error> {
+        let entries = self.fetch_head()?.unwrap_or_default();
+        let mut to_merge = entries.iter().filter(|entry| entry.for_merge);
+        let entry = match (to_merge.next(), to_merge.next()) {
+            (None, _) => return Err(integrate::Error::NothingToMerge),
+            (Some(_), Some(_)) => return Err(integrate::Error::MultipleCommitsToMerge),
+            (Some(entry), None) => entry,
+        };
+        if self.work_dir().is_none() {
+            return Err(integrate::Error::BareRepository);
+        }
+
+        let theirs = self
+            .find_object(entry.id)?
+            .peel_to_kind(gix_object::Kind::Commit)?
+            .into_commit();
+        let theirs_tree = theirs.tree_id()?.detach();
+        let head = self.head()?;
+        let head_name = head.referent_name().map(ToOwned::to_owned);
+        let Some(ours) = head.id().map(crate::Id::detach) else {
+            self.update_worktree_and_index(ObjectId::empty_tree(self.object_hash()), theirs_tree, should_interrupt)?;
+            self.update_head(None, theirs.id, "initial pull".into())?;
+            return Ok(Integration::FastForward {
+                previous: None,
+                new: theirs.id,
+            });
+        };
+
+        let rebase = match options.rebase {
+            Some(rebase) => Some(rebase),
+            None => self.pull_rebase(head_name.as_ref().map(AsRef::as_ref))?,
+        };
+        let fast_forward = match options.fast_forward {
+            Some(fast_forward) => Some(fast_forward),
+            None => self
+                .config
+                .resolved
+                .string(Pull::FF)
+                .map(|value| {
+                    Pull::FF
+                        .try_into_fast_forward(value)
+                        .with_lenient_default(self.config.lenient_config)
+                })
+                .transpose()?,
+        };
+
+        let base = self.merge_base(ours, theirs.id)?.detach();
+        if base == theirs.id {
+            return Ok(Integration::UpToDate { head: ours });
+        }
+        let ours_tree = self.find_commit(ours)?.tree_id()?.detach();
+        let can_fast_forward = base == ours;
+        if !can_fast_forward {
+            if fast_forward == Some(FastForward::Only) {
+                return Err(integrate::Error::NotPossibleToFastForward);
+            }
+            if fast_forward.is_none() && rebase.is_none() {
+                return Err(integrate::Error::DivergentBranches);
+            }
+        }
+        // `--ff-only` takes precedence over rebasing, just like fast-forwarding does.
+        let rebase = if fast_forward == Some(FastForward::Only) {
+            Rebase::False
+        } else {
+            rebase.unwrap_or_default()
+        };
+        if rebase != Rebase::False {
+            self.assure_no_uncommitted_changes(ours_tree)?;
+        }
+        if can_fast_forward && (rebase != Rebase::False || fast_forward != Some(FastForward::Never)) {
+            self.update_worktree_and_index(ours_tree, theirs_tree, should_interrupt)?;
+            self.update_head(Some(ours), theirs.id, format!("{REFLOG_ACTION}: Fast-forward").into())?;
+            return Ok(Integration::FastForward {
+                previous: Some(ours),
+                new: theirs.id,
+            });
+        }
+
+        match rebase {
+            Rebase::False => {
+                let theirs_label = entry.id.to_hex().to_string();
+                let merge = self.merge_trees(
+                    self.find_commit(base)?.tree_id()?.detach(),
+                    ours_tree,
+                    theirs_tree,
+                    gix_merge::blob::Labels {
+                        ancestor: None,
+                        current: Some("HEAD".into()),
+                        other: Some(theirs_label.as_str().into()),
+                    },
+                    options.tree_merge,
+                )?;
+                if merge.has_conflicts() {
+                    return Ok(Integration::Conflict { picking: None, merge });
+                }
+                let commit = self.write_commit_with_parents(
+                    merge.tree,
+                    [ours, theirs.id],
+                    None,
+                    merge_message(entry, head_name.as_ref().map(AsRef::as_ref)),
+                )?;
+                self.update_worktree_and_index(ours_tree, merge.tree, should_interrupt)?;
+                self.update_head(
+                    Some(ours),
+                    commit,
+                    format!("{REFLOG_ACTION}: Merge made by the 'ort' strategy.").into(),
+                )?;
+                Ok(Integration::Merge { previous: ours, commit })
+            }
+            Rebase::True => {
+                // The commits to pick form a line as merges aren't supported, so following the first parent
+                // reaches the merge-base without having to traverse possibly missing commits of shallow repositories.
+                let mut to_pick = Vec::new();
+                let mut id = ours;
+                while id != base {
+                    let commit = self.find_commit(id)?;
+                    let mut parents = commit.parent_ids();
+                    let parent = parents.next();
+                    if parents.next().is_some() {
+                        return Err(integrate::Error::MergeCommitToRebase { commit: id });
+                    }
+                    to_pick.push(id);
+                    match parent {
+                        Some(parent) => id = parent.detach(),
+                        None => break,
+                    }
+                }
+                to_pick.reverse();
+
+                let mut onto = theirs.id;
+                let mut onto_tree = theirs_tree;
+                let mut commits = Vec::new();
+                for id in to_pick {
+                    let commit = self.find_commit(id)?;
+                    let commit_ref = commit.decode()?;
+                    let commit_tree = commit.tree_id()?.detach();
+                    let parent_tree = match commit.parent_ids().next() {
+                        Some(parent) => self.find_commit(parent)?.tree_id()?.detach(),
+                        None => ObjectId::empty_tree(self.object_hash()),
+                    };
+                    let summary = commit_ref.message_summary();
+                    let merge = self.merge_trees(
+                        parent_tree,
+                        onto_tree,
+                        commit_tree,
+                        gix_merge::blob::Labels {
+                            ancestor: Some("parent of picked commit".into()),
+                            current: Some("HEAD".into()),
+                            other: Some(summary.as_ref()),
+                        },
+                        options.tree_merge,
+                    )?;
+                    if merge.has_conflicts() {
+                        return Ok(Integration::Conflict {
+                            picking: Some(id),
+                            merge,
+                        });
+                    }
+                    // Like `git rebase`, drop commits whose changes are already upstream.
+                    if merge.tree == onto_tree && parent_tree != commit_tree {
+                        continue;
+                    }
+                    onto = self.write_commit_with_parents(
+                        merge.tree,
+                        [onto],
+                        Some(commit_ref.author),
+                        commit_ref.message.into(),
+                    )?;
+                    onto_tree = merge.tree;
+                    commits.push(onto);
+                }
+
+                self.update_worktree_and_index(ours_tree, onto_tree, should_interrupt)?;
+                let branch = head_name.as_ref().map_or("HEAD".into(), gix_ref::FullName::as_bstr);
+                self.update_head(
+                    Some(ours),
+                    onto,
+                    format!("{REFLOG_ACTION} (finish): {branch} onto {}", theirs.id).into(),
+                )?;
+                Ok(Integration::Rebase {
+                    previous: ours,
+                    onto: theirs.id,
+                    commits,
+                })
+            }
+            mode @ (Rebase::Merges | Rebase::Interactive) => Err(integrate::Error::UnsupportedRebase { mode }),
+        }
+    }
+
+    /// Fetch from the remote configured in `branch.<name>.remote` for the current branch and integrate the reference
+    /// configured in `branch.<name>.merge` into it, similar to `git pull`.
+    ///
+    /// Reflog messages of remote tracking references updated by the fetch are prefixed with `pull` instead of `fetch`.
+    /// See [`integrate_fetch_head()`](Self::integrate_fetch_head()) for how the fetched commit is integrated
+    /// according to `options`.
+    #[cfg(feature = "blocking-network-client")]
+    pub fn pull<P>(
+        &self,
+        mut progress: P,
+        should_interrupt: &AtomicBool,
+        options: Options,
+    ) -> Result<crate::pull::Outcome, crate::pull::Error>
+    where
+        P: gix_features::progress::NestedProgress,
+        P::SubProgress: 'static,
+    {
+        use crate::remote;
+
+        let head = self.head()?;
+        let branch = head.referent_name().ok_or(crate::pull::Error::DetachedHead)?;
+        let no_upstream = || crate::pull::Error::NoUpstream {
+            branch: branch.shorten().to_owned(),
+        };
+        if self.branch_remote_ref_name(branch, remote::Direction::Fetch).is_none() {
+            return Err(no_upstream());
+        }
+        let remote = self
+            .branch_remote(branch.shorten(), remote::Direction::Fetch)
+            .ok_or_else(no_upstream)??;
+        let fetch = remote
+            .connect(remote::Direction::Fetch)?
+            .prepare_fetch(&mut progress, Default::default())?
+            .with_reflog_message(remote::fetch::RefLogMessage::Prefixed {
+                action: REFLOG_ACTION.into(),
+            })
+            .receive(progress, should_interrupt)?;
+        let integration = self.integrate_fetch_head(should_interrupt, options)?;
+        Ok(crate::pull::Outcome { fetch, integration })
+    }
+
+    /// Obtain the configured rebase mode for `branch`, preferring `branch.<name>.rebase` over `pull.rebase`.
+    fn pull_rebase(&self, branch: Option<&FullNameRef>) -> Result<Option<Rebase>, integrate::Error> {
+        let config = &self.config.resolved;
+        let branch_value =
+            branch.and_then(|name| config.string_by("branch", Some(name.shorten()), Branch::REBASE.name));
+        Ok(match branch_value {
+            Some(value) => Some(
+                Branch::REBASE
+                    .try_into_rebase(value)
+                    .with_lenient_default(self.config.lenient_config)?,
+            ),
+            None => config
+                .string(Pull::REBASE)
+                .map(|value| {
+                    Pull::REBASE
+                        .try_into_rebase(value)
+                        .with_lenient_default(self.config.lenient_config)
+                })
+                .transpose()?,
+        })
+    }
+
+    fn write_commit_with_parents(
+        &self,
+        tree: ObjectId,
+        parents: impl IntoIterator<Item = ObjectId>,
+        author: Option<gix_actor::SignatureRef<'_>>,
+        message: BString,
+    ) -> Result<ObjectId, integrate::Error> {
+        let committer = self.committer().ok_or(integrate::Error::CommitterMissing)??;
+        let author = match author {
+            Some(author) => author,
+            None => self.author().ok_or(integrate::Error::AuthorMissing)??,
+        };
+        let commit = gix_object::Commit {
+            tree,
+            parents: parents.into_iter().collect(),
+            author: author.into(),
+            committer: committer.into(),
+            encoding: None,
+            message,
+            extra_headers: Default::default(),
+        };
+        Ok(self.write_object(&commit)?.detach())
+    }
+
+    /// Point `HEAD`, or the branch it refers to, to `new` and set `ORIG_HEAD` to `previous`, if there is one.
+    fn update_head(&self, previous: Option<ObjectId>, new: ObjectId, message: BString) -> Result<(), integrate::Error> {
+        let mut edits = vec![RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message,
+                },
+                expected: match previous {
+                    Some(previous) => PreviousValue::MustExistAndMatch(Target::Object(previous)),
+                    None => PreviousValue::MustNotExist,
+                },
+                new: Target::Object(new),
+            },
+            name: "HEAD".try_into().expect("valid"),
+            deref: true,
+        }];
+        edits.extend(previous.map(orig_head_edit));
+        self.edit_references(edits)?;
+        Ok(())
+    }
+
+    /// Change the index and the files in the worktree from matching `old_tree` to matching `new_tree`, touching only
+    /// the files that differ between both trees.
+    ///
+    /// Fail without changing anything if one of these files has local changes, or if an untracked file is in the way.
+    fn update_worktree_and_index(
+        &self,
+        old_tree: ObjectId,
+        new_tree: ObjectId,
+        should_interrupt: &AtomicBool,
+    ) -> Result<(), integrate::Error> {
+        let work_dir = self.work_dir().ok_or(integrate::Error::BareRepository)?;
+        let old = self.index_from_tree(&old_tree)?;
+        let new = self.index_from_tree(&new_tree)?;
+
+        let (mut changed, mut removed) = (BTreeSet::<BString>::new(), Vec::<BString>::new());
+        for entry in old.entries() {
+            let path = entry.path(&old);
+            match new.entry_by_path_and_stage(path, gix_index::entry::Stage::Unconflicted) {
+                Some(new_entry) if new_entry.id == entry.id && new_entry.mode == entry.mode => {}
+                Some(_) => {
+                    changed.insert(path.to_owned());
+                }
+                None => removed.push(path.to_owned()),
+            }
+        }
+        let mut added = BTreeSet::<BString>::new();
+        for entry in new.entries() {
+            let path = entry.path(&new);
+            if old
+                .entry_by_path_and_stage(path, gix_index::entry::Stage::Unconflicted)
+                .is_none()
+            {
+                added.insert(path.to_owned());
+            }
+        }
+        if changed.is_empty() && removed.is_empty() && added.is_empty() {
+            return Ok(());
+        }
+
+        self.assure_changes_do_not_overwrite_local_state(old_tree, &changed, &removed, &added)?;
+
+        for rela_path in &removed {
+            let path = work_dir.join(gix_path::from_bstr(rela_path.as_bstr()));
+            match std::fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(source) => return Err(integrate::Error::RemoveFile { path, source }),
+            }
+            let mut dir = path.parent();
+            while let Some(parent) = dir.filter(|dir| *dir != work_dir && dir.starts_with(work_dir)) {
+                if std::fs::remove_dir(parent).is_err() {
+                    break;
+                }
+                dir = parent.parent();
+            }
+        }
+
+        let mut to_checkout = gix_index::State::new(self.object_hash());
+        for entry in new.entries() {
+            let path = entry.path(&new);
+            if changed.contains(path) || added.contains(path) {
+                to_checkout.dangerously_push_entry(entry.stat, entry.id, entry.flags, entry.mode, path);
+            }
+        }
+        let mut opts = self.config.checkout_options(
+            self,
+            gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
+        )?;
+        opts.overwrite_existing = true;
+        gix_worktree_state::checkout(
+            &mut to_checkout,
+            work_dir,
+            self.objects.clone().into_arc()?,
+            &gix_features::progress::Discard,
+            &gix_features::progress::Discard,
+            should_interrupt,
+            opts,
+        )?;
+
+        // Staged changes to touched paths were ruled out above, so all other entries can be kept as they are.
+        let mut index = gix_index::File::clone(&**self.index_or_empty()?);
+        index.remove_entries(|_, path, _| {
+            changed.contains(path) || added.contains(path) || removed.iter().any(|p| p.as_bstr() == path)
+        });
+        for entry in to_checkout.entries() {
+            index.dangerously_push_entry(entry.stat, entry.id, entry.flags, entry.mode, entry.path(&to_checkout));
+        }
+        index.sort_entries();
+        index.remove_tree();
+        index.write(Default::default())?;
+        Ok(())
+    }
+
+    /// Fail if the index or tracked files in the worktree have any changes compared to `head_tree`, as rebasing
+    /// requires, ignoring submodules just like `git`.
+    fn assure_no_uncommitted_changes(&self, head_tree: ObjectId) -> Result<(), integrate::Error> {
+        let mut paths = Vec::new();
+        for item in self
+            .status(gix_features::progress::Discard)?
+            .head_tree(head_tree)
+            .untracked_files(crate::status::UntrackedFiles::None)
+            .index_worktree_rewrites(None)
+            .tree_index_track_renames(None)
+            .index_worktree_submodules(crate::status::Submodule::Given {
+                ignore: crate::submodule::config::Ignore::All,
+                check_dirty: false,
+            })
+            .into_iter(None)?
+        {
+            let item = item?;
+            let is_change = match &item {
+                crate::status::Item::TreeIndex(_) => true,
+                crate::status::Item::IndexWorktree(item) => !matches!(
+                    item.summary(),
+                    None | Some(gix_status::index_as_worktree_with_renames::Summary::Added)
+                ),
+            };
+            if is_change {
+                paths.push(item.location().to_owned());
+            }
+        }
+        if !paths.is_empty() {
+            paths.sort();
+            paths.dedup();
+            return Err(integrate::Error::UncommittedChanges { paths });
+        }
+        Ok(())
+    }
+
+    /// Fail if the `changed` or `removed` paths are modified in the index or the worktree compared to `head_tree`,
+    /// or if `added` paths exist as untracked files.
+    fn assure_changes_do_not_overwrite_local_state(
+        &self,
+        head_tree: ObjectId,
+        changed: &BTreeSet<BString>,
+        removed: &[BString],
+        added: &BTreeSet<BString>,
+    ) -> Result<(), integrate::Error> {
+        let is_tracked_change = |path: &BStr| changed.contains(path) || removed.iter().any(|p| p.as_bstr() == path);
+        let (mut local_changes, mut untracked) = (Vec::new(), Vec::new());
+        for item in self
+            .status(gix_features::progress::Discard)?
+            .head_tree(head_tree)
+            .untracked_files(crate::status::UntrackedFiles::Files)
+            .index_worktree_rewrites(None)
+            .tree_index_track_renames(None)
+            .index_worktree_submodules(crate::status::Submodule::Given {
+                ignore: crate::submodule::config::Ignore::All,
+                check_dirty: false,
+            })
+            .into_iter(None)?
+        {
+            let item = item?;
+            let path = item.location();
+            match &item {
+                crate::status::Item::TreeIndex(_) => {
+                    if is_tracked_change(path) || added.contains(path) {
+                        local_changes.push(path.to_owned());
+                    }
+                }
+                crate::status::Item::IndexWorktree(item) => match item.summary() {
+                    Some(gix_status::index_as_worktree_with_renames::Summary::Added) if added.contains(path) => {
+                        untracked.push(path.to_owned());
+                    }
+                    Some(gix_status::index_as_worktree_with_renames::Summary::Added) => {}
+                    Some(_) if is_tracked_change(path) => local_changes.push(path.to_owned()),
+                    _ => {}
+                },
+            }
+        }
+        if !local_changes.is_empty() {
+            local_changes.sort();
+            local_changes.dedup();
+            return Err(integrate::Error::LocalChanges { paths: local_changes });
+        }
+        if !untracked.is_empty() {
+            untracked.sort();
+            return Err(integrate::Error::UntrackedFiles { paths: untracked });
+        }
+        Ok(())
+    }
+}
+
+/// Set `ORIG_HEAD` to `previous`, without writing a reflog just like `git`.
+fn orig_head_edit(previous: ObjectId) -> RefEdit {
+    RefEdit {
+        change: Change::Update {
+            log: LogChange {
+                mode: RefLog::AndReference,
+                force_create_reflog: false,
+                message: Default::default(),
+            },
+            expected: PreviousValue::Any,
+            new: Target::Object(previous),
+        },
+        name: "ORIG_HEAD".try_into().expect("valid"),
+        deref: false,
+    }
+}
+
+/// Produce the message of a merge commit for `entry` like `git fmt-merge-msg` does, e.g. `Merge branch 'main' of <url>`,
+/// which mentions the current `branch` unless it's `main` or `master`.
+fn merge_message(entry: &gix_fetchhead::Entry, branch: Option<&FullNameRef>) -> BString {
+    use gix_fetchhead::Source;
+    let mut message = BString::from("Merge ");
+    let (kind, name) = match &entry.source {
+        Source::Head => ("", None),
+        Source::Branch(name) => ("branch ", Some(name)),
+        Source::Tag(name) => ("tag ", Some(name)),
+        Source::RemoteTrackingBranch(name) => ("remote-tracking branch ", Some(name)),
+        Source::Other(name) => ("", Some(name)),
+    };
+    match name {
+        Some(name) => {
+            message.push_str(kind);
+            message.push_str(format!("'{name}'"));
+            if entry.url != "." {
+                message.push_str(" of ");
+                message.push_str(&entry.url);
+            }
+        }
+        None => message.push_str(&entry.url),
+    }
+    if let Some(branch) = branch
+        .map(FullNameRef::shorten)
+        .filter(|name| *name != "main" && *name != "master")
+    {
+        message.push_str(" into ");
+        message.push_str(branch);
+    }
+    message.push_byte(b'\n');
+    message
+}
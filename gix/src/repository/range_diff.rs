@@ -0,0 +1,73 @@
+use std::ops::Range;
+
+use gix_diff::blob::patch;
+use gix_hash::ObjectId;
+
+use crate::{
+    bstr::{BString, ByteVec},
+    range_diff,
+};
+
+/// Comparing versions of a commit series
+impl crate::Repository {
+    /// Compare the commits in the `old` range with the ones in the `new` range, similar to
+    /// `git range-diff <old.start>..<old.end> <new.start>..<new.end>`, to see how a series of commits changed after
+    /// it was rewritten, for instance by an interactive rebase. Merge commits are skipped.
+    ///
+    /// Each commit is turned into a [patch](range_diff::Patch) with its author, message and diff, with renames detected
+    /// as configured by `diff.renames`. Patches of both series are then paired so that the differences between them
+    /// are minimal, and patches that changed too much to be paired are considered removed or added, as controlled by
+    /// [`creation_factor`](range_diff::Options::creation_factor).
+    ///
+    /// To compare the commits of two branches since they diverged, like `git range-diff <a>...<b>` does, use
+    /// [`merge_base()`](Self::merge_base()) as start of both ranges.
+    pub fn range_diff(
+        &self,
+        old: Range<ObjectId>,
+        new: Range<ObjectId>,
+        options: range_diff::Options,
+    ) -> Result<range_diff::Outcome, range_diff::Error> {
+        let old = self.range_diff_commits(old)?;
+        let new = self.range_diff_commits(new)?;
+        let pairs = range_diff::pairs(
+            &old.iter().map(|commit| &commit.patch).collect::<Vec<_>>(),
+            &new.iter().map(|commit| &commit.patch).collect::<Vec<_>>(),
+            options.creation_factor,
+        );
+        Ok(range_diff::Outcome { old, new, pairs })
+    }
+
+    fn range_diff_commits(&self, range: Range<ObjectId>) -> Result<Vec<range_diff::Commit>, range_diff::Error> {
+        let mut out = Vec::new();
+        for id in self.commits_in_range::<range_diff::Error>(range.start, range.end)? {
+            let commit = self.find_commit(id)?;
+            let commit_ref = commit.decode()?;
+            let old_tree = match commit_ref.parents().next() {
+                Some(parent) => self.find_commit(parent)?.tree()?,
+                None => self.empty_tree(),
+            };
+
+            let mut author = BString::from(commit_ref.author.name);
+            author.push_str(" <");
+            author.push_str(commit_ref.author.email);
+            author.push_byte(b'>');
+            let mut patch = range_diff::Patch::new(author.as_ref(), commit_ref.message);
+            for change in self.tree_changes::<range_diff::Error>(&old_tree, &commit.tree()?)? {
+                let content = if change.is_binary {
+                    patch::Content::Binary
+                } else {
+                    patch::Content::Hunks(change.content.as_ref())
+                };
+                patch.push_file(&change.as_change(), content);
+            }
+
+            out.push(range_diff::Commit {
+                id,
+                short_id: commit.id().shorten()?,
+                subject: commit_ref.message_summary().into_owned(),
+                patch,
+            });
+        }
+        Ok(out)
+    }
+}
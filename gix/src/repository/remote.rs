@@ -1,5 +1,17 @@
 #![allow(clippy::result_large_err)]
-use crate::{bstr::BStr, config, remote, remote::find, Remote};
+use std::{borrow::Cow, path::Path};
+
+use gix_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    FullName, Target,
+};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    config, remote,
+    remote::{find, manage},
+    Remote,
+};
 
 impl crate::Repository {
     /// Create a new remote available at the given `url`.
@@ -225,3 +237,520 @@ impl crate::Repository {
         }
     }
 }
+
+/// Remote management, with all changes written to the repository-local configuration file.
+///
+/// The configuration of this instance is updated to match the written file, but other instances of the same repository
+/// aren't affected.
+impl crate::Repository {
+    /// Add a new remote named `name` that fetches from `url`, and write it to the repository-local configuration file,
+    /// similar to `git remote add <name> <url>`, returning it as configured now.
+    ///
+    /// The new remote fetches all branches into `refs/remotes/<name>/*` along with the tags they include.
+    /// Use [`remote_set_branches()`](Self::remote_set_branches()) to fetch only some branches
+    /// and [`remote_set_head()`](Self::remote_set_head()) to set its default branch.
+    pub fn remote_add<Url, E>(&mut self, name: impl Into<BString>, url: Url) -> Result<Remote<'_>, manage::add::Error>
+    where
+        Url: TryInto<gix_url::Url, Error = E>,
+        gix_url::parse::Error: From<E>,
+    {
+        let name = remote::name::validated(name)?;
+        if self.has_remote_section(name.as_ref(), false) {
+            return Err(manage::add::Error::Exists { name });
+        }
+        let mut config = gix_config::File::new(
+            gix_config::file::Metadata::from(gix_config::Source::Local).at(self.common_dir().join("config")),
+        );
+        let mut remote = self.remote_at_without_url_rewrite(url)?.with_refspecs(
+            Some(format!("+refs/heads/*:refs/remotes/{name}/*").as_str()),
+            remote::Direction::Fetch,
+        )?;
+        remote.save_as_to(name.clone(), &mut config)?;
+        let mut local = self.lock_local_config()?;
+        local.config.append(config);
+        self.write_local_config(local)?;
+        Ok(self.find_remote(name.as_bstr())?)
+    }
+
+    /// Rename the remote `name` to `new_name` similar to `git remote rename`, which moves all of its
+    /// remote tracking branches from `refs/remotes/<name>/` to `refs/remotes/<new_name>/`.
+    ///
+    /// In the repository-local configuration file, this renames all `remote.<name>` sections, adjusts fetch ref-specs that
+    /// write into `refs/remotes/<name>/`, and updates `branch.<branch>.remote`, `branch.<branch>.pushRemote` and
+    /// `remote.pushDefault` if they refer to the renamed remote.
+    ///
+    /// ### Deviation
+    ///
+    /// Unlike `git`, the reflogs of the moved references are started anew instead of being moved along with them.
+    pub fn remote_rename(&mut self, name: &BStr, new_name: impl Into<BString>) -> Result<(), manage::rename::Error> {
+        let new_name = remote::name::validated(new_name)?;
+        if !self.has_remote_section(name, true) {
+            return Err(manage::rename::Error::NotFound { name: name.into() });
+        }
+        if self.has_remote_section(new_name.as_ref(), false) {
+            return Err(manage::rename::Error::Exists { name: new_name });
+        }
+
+        let old_prefix: BString = format!("refs/remotes/{name}/").into();
+        let new_prefix: BString = format!("refs/remotes/{new_name}/").into();
+        let mut local = self.lock_local_config()?;
+        let config = &mut local.config;
+        for id in remote_section_ids(config, name) {
+            config.rename_section_by_id(id, "remote", Some(Cow::Owned(new_name.clone())))?;
+        }
+
+        let old_destination = [b":".as_slice(), old_prefix.as_slice()].concat();
+        let new_destination = [b":".as_slice(), new_prefix.as_slice()].concat();
+        for id in remote_section_ids(config, new_name.as_ref()) {
+            let mut section = config.section_mut_by_id(id).expect("just listed");
+            let specs: Vec<BString> = section
+                .values(config::tree::Remote::FETCH.name)
+                .into_iter()
+                .map(Cow::into_owned)
+                .collect();
+            if !specs.iter().any(|spec| spec.contains_str(&old_destination)) {
+                continue;
+            }
+            while section.remove(config::tree::Remote::FETCH.name).is_some() {}
+            for spec in specs {
+                section.push(
+                    value_name(config::tree::Remote::FETCH.name),
+                    Some(spec.replace(&old_destination, &new_destination).as_bstr()),
+                );
+            }
+        }
+        replace_remote_references(config, name, |section, key| {
+            section.set(value_name(key), new_name.as_ref());
+        });
+
+        let mut edits = Vec::new();
+        for reference in self.references()?.prefixed(gix_path::from_bstr(old_prefix.as_bstr()))? {
+            let reference = reference.map_err(manage::rename::Error::IterReferences)?.detach();
+            let new_ref_name: FullName = {
+                let mut new_ref_name = new_prefix.clone();
+                new_ref_name.extend_from_slice(&reference.name.as_bstr()[old_prefix.len()..]);
+                new_ref_name.try_into().map_err(manage::rename::Error::RefName)?
+            };
+            let new_target = match &reference.target {
+                Target::Symbolic(target) if target.as_bstr().starts_with(old_prefix.as_slice()) => {
+                    let mut new_target = new_prefix.clone();
+                    new_target.extend_from_slice(&target.as_bstr()[old_prefix.len()..]);
+                    Target::Symbolic(new_target.try_into().map_err(manage::rename::Error::RefName)?)
+                }
+                target => target.clone(),
+            };
+            edits.push(RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: format!(
+                            "remote: renamed {} to {}",
+                            reference.name.as_bstr(),
+                            new_ref_name.as_bstr()
+                        )
+                        .into(),
+                    },
+                    expected: PreviousValue::MustNotExist,
+                    new: new_target,
+                },
+                name: new_ref_name,
+                deref: false,
+            });
+            edits.push(RefEdit {
+                change: Change::Delete {
+                    expected: PreviousValue::MustExistAndMatch(reference.target),
+                    log: RefLog::AndReference,
+                },
+                name: reference.name,
+                deref: false,
+            });
+        }
+        // The configuration file stays locked while references are changed, and is only written if that succeeded.
+        self.edit_references(edits)?;
+        self.write_local_config(local)?;
+        Ok(())
+    }
+
+    /// Remove the remote `name` similar to `git remote remove`, deleting all of its remote tracking branches
+    /// in `refs/remotes/`, that is all references its fetch ref-specs write to.
+    /// Just like `git`, references that the fetch ref-specs of other remotes write to are kept.
+    ///
+    /// In the repository-local configuration file, this removes all `remote.<name>` sections along with
+    /// `branch.<branch>.remote`, `branch.<branch>.merge`, `branch.<branch>.pushRemote` and `remote.pushDefault`
+    /// if they refer to the removed remote.
+    pub fn remote_remove(&mut self, name: &BStr) -> Result<(), manage::remove::Error> {
+        if !self.has_remote_section(name, true) {
+            return Err(manage::remove::Error::NotFound { name: name.into() });
+        }
+        let destinations = self.fetch_destinations(name)?;
+        let mut other_destinations = Vec::new();
+        for other in self.remote_names() {
+            if other.as_ref() != name {
+                other_destinations.extend(self.fetch_destinations(other.as_ref())?);
+            }
+        }
+
+        let mut local = self.lock_local_config()?;
+        let config = &mut local.config;
+        for id in remote_section_ids(config, name) {
+            config.remove_section_by_id(id);
+        }
+        let changed = replace_remote_references(config, name, |section, key| {
+            while section.remove(key).is_some() {}
+            if key == config::tree::Branch::REMOTE.name {
+                while section.remove(config::tree::Branch::MERGE.name).is_some() {}
+            }
+        });
+        for id in changed {
+            if config
+                .section_mut_by_id(id)
+                .map_or(false, |section| section.num_values() == 0)
+            {
+                config.remove_section_by_id(id);
+            }
+        }
+
+        let is_written_by = |destinations: &[BString], name: &BStr| {
+            destinations
+                .iter()
+                .any(|destination| matches_destination(destination.as_ref(), name))
+        };
+        let mut edits = Vec::new();
+        for reference in self.references()?.prefixed("refs/remotes/")? {
+            let reference = reference.map_err(manage::remove::Error::IterReferences)?.detach();
+            let ref_name = reference.name.as_bstr();
+            if is_written_by(&destinations, ref_name) && !is_written_by(&other_destinations, ref_name) {
+                edits.push(RefEdit {
+                    change: Change::Delete {
+                        expected: PreviousValue::MustExistAndMatch(reference.target),
+                        log: RefLog::AndReference,
+                    },
+                    name: reference.name,
+                    deref: false,
+                });
+            }
+        }
+        // The configuration file stays locked while references are changed, and is only written if that succeeded.
+        self.edit_references(edits)?;
+        self.write_local_config(local)?;
+        Ok(())
+    }
+
+    /// Set the `url` of the remote `name` for use in `direction`, similar to `git remote set-url [--push] <name> <url>`.
+    ///
+    /// This sets `remote.<name>.url` for [fetching](remote::Direction::Fetch) and `remote.<name>.pushUrl`
+    /// for [pushing](remote::Direction::Push) in the repository-local configuration file.
+    ///
+    /// ### Deviation
+    ///
+    /// If multiple URLs are configured, all of them are replaced by `url` whereas `git` only replaces the first one.
+    pub fn remote_set_url<Url, E>(
+        &mut self,
+        name: &BStr,
+        url: Url,
+        direction: remote::Direction,
+    ) -> Result<(), manage::set_url::Error>
+    where
+        Url: TryInto<gix_url::Url, Error = E>,
+        gix_url::parse::Error: From<E>,
+    {
+        if !self.has_remote_section(name, true) {
+            return Err(manage::set_url::Error::NotFound { name: name.into() });
+        }
+        let url = url.try_into().map_err(gix_url::parse::Error::from)?.to_bstring();
+        let key = match direction {
+            remote::Direction::Fetch => config::tree::Remote::URL.name,
+            // Written like `git` and `Remote::save_to()` do.
+            remote::Direction::Push => "pushurl",
+        };
+        let mut local = self.lock_local_config()?;
+        let config = &mut local.config;
+        {
+            let ids = remote_section_ids(config, name);
+            let mut is_set = false;
+            for id in &ids {
+                let mut section = config.section_mut_by_id(*id).expect("just listed");
+                let num_values = section.values(key).len();
+                if num_values == 0 {
+                    continue;
+                }
+                if is_set {
+                    while section.remove(key).is_some() {}
+                } else {
+                    // Keep the first value in place.
+                    for _ in 1..num_values {
+                        section.remove(key);
+                    }
+                    section.set(value_name(key), url.as_ref());
+                    is_set = true;
+                }
+            }
+            if let Some(id) = ids.last().filter(|_| !is_set) {
+                config
+                    .section_mut_by_id(*id)
+                    .expect("just listed")
+                    .push(value_name(key), Some(url.as_ref()));
+            }
+        }
+        self.write_local_config(local)?;
+        Ok(())
+    }
+
+    /// Let `refs/remotes/<name>/HEAD` point to the remote tracking branch of `branch`, or delete it if `branch` is `None`,
+    /// similar to `git remote set-head <name> <branch>` and `git remote set-head --delete <name>` respectively.
+    ///
+    /// This defines the default branch of the remote as seen locally, and fails if the remote tracking branch doesn't exist.
+    pub fn remote_set_head(&mut self, name: &BStr, branch: Option<&BStr>) -> Result<(), manage::set_head::Error> {
+        if !self.has_remote_section(name, false) {
+            return Err(manage::set_head::Error::NotFound { name: name.into() });
+        }
+        let head: FullName = BString::from(format!("refs/remotes/{name}/HEAD")).try_into()?;
+        match branch {
+            Some(branch) => {
+                let target: FullName = BString::from(format!("refs/remotes/{name}/{branch}")).try_into()?;
+                if self.try_find_reference(target.as_ref())?.is_none() {
+                    return Err(manage::set_head::Error::TrackingBranchNotFound {
+                        name: target.into_inner(),
+                    });
+                }
+                self.edit_reference(RefEdit {
+                    change: Change::Update {
+                        log: LogChange {
+                            mode: RefLog::AndReference,
+                            force_create_reflog: false,
+                            message: "remote set-head".into(),
+                        },
+                        expected: PreviousValue::Any,
+                        new: Target::Symbolic(target),
+                    },
+                    name: head,
+                    deref: false,
+                })?;
+            }
+            None => {
+                if let Some(reference) = self.try_find_reference(head.as_ref())? {
+                    self.edit_reference(RefEdit {
+                        change: Change::Delete {
+                            expected: PreviousValue::MustExistAndMatch(reference.inner.target.clone()),
+                            log: RefLog::AndReference,
+                        },
+                        name: head,
+                        deref: false,
+                    })?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Configure the remote `name` to only fetch the given `branches` into its remote tracking branches,
+    /// similar to `git remote set-branches [--add] <name> <branch>…`.
+    ///
+    /// Each branch may also be a glob like `feature/*`. If `append` is `true`, the `branches` are tracked in addition to
+    /// the ones that are already tracked, otherwise all previously configured fetch ref-specs are replaced.
+    pub fn remote_set_branches(
+        &mut self,
+        name: &BStr,
+        branches: impl IntoIterator<Item = impl AsRef<BStr>>,
+        append: bool,
+    ) -> Result<(), manage::set_branches::Error> {
+        if !self.has_remote_section(name, true) {
+            return Err(manage::set_branches::Error::NotFound { name: name.into() });
+        }
+        let specs = branches
+            .into_iter()
+            .map(|branch| {
+                let branch = branch.as_ref();
+                let spec: BString = format!("+refs/heads/{branch}:refs/remotes/{name}/{branch}").into();
+                gix_refspec::parse(spec.as_ref(), gix_refspec::parse::Operation::Fetch)
+                    .map(|_| spec.clone())
+                    .map_err(|source| manage::set_branches::Error::RefSpec {
+                        branch: branch.into(),
+                        source,
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let key = config::tree::Remote::FETCH.name;
+        let mut local = self.lock_local_config()?;
+        let config = &mut local.config;
+        {
+            let ids = remote_section_ids(config, name);
+            if !append {
+                for id in &ids {
+                    let mut section = config.section_mut_by_id(*id).expect("just listed");
+                    while section.remove(key).is_some() {}
+                }
+            }
+            if let Some(id) = ids.last() {
+                let mut section = config.section_mut_by_id(*id).expect("just listed");
+                for spec in &specs {
+                    section.push(value_name(key), Some(spec.as_ref()));
+                }
+            }
+        }
+        self.write_local_config(local)?;
+        Ok(())
+    }
+
+    /// Return `true` if a `remote.<name>` section exists, or if `local_only` is `true`, if it exists in the
+    /// repository-local configuration file.
+    fn has_remote_section(&self, name: &BStr, local_only: bool) -> bool {
+        let local_config = self.common_dir().join("config");
+        self.config
+            .resolved
+            .sections_by_name("remote")
+            .into_iter()
+            .flatten()
+            .any(|section| {
+                section.header().subsection_name() == Some(name)
+                    && (!local_only || is_local_config_file(section.meta(), &local_config))
+            })
+    }
+
+    /// Return the destinations of all fetch ref-specs of the remote `name`.
+    fn fetch_destinations(&self, name: &BStr) -> Result<Vec<BString>, manage::remove::Error> {
+        let mut filter = self.filter_config_section();
+        self.config
+            .resolved
+            .strings_filter(
+                format!("remote.{name}.{}", config::tree::Remote::FETCH.name),
+                &mut filter,
+            )
+            .unwrap_or_default()
+            .into_iter()
+            .map(|spec| {
+                gix_refspec::parse(spec.as_ref(), gix_refspec::parse::Operation::Fetch)
+                    .map(|spec| spec.destination().map(ToOwned::to_owned))
+                    .map_err(|source| manage::remove::Error::RefSpec {
+                        spec: spec.as_ref().into(),
+                        source,
+                    })
+            })
+            .filter_map(Result::transpose)
+            .collect()
+    }
+
+    /// Lock the repository-local configuration file and load it for editing.
+    fn lock_local_config(&self) -> Result<LocalConfig, manage::persist::Error> {
+        let path = self.common_dir().join("config");
+        let lock = gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None)?;
+        let config = gix_config::File::from_path_no_includes(path, gix_config::Source::Local)?;
+        Ok(LocalConfig { lock, config })
+    }
+
+    /// Write `local` back to the repository-local configuration file, and replace the sections of this file in our
+    /// configuration with the ones that were written.
+    fn write_local_config(
+        &mut self,
+        LocalConfig { mut lock, config }: LocalConfig,
+    ) -> Result<(), manage::persist::Error> {
+        config
+            .write_to(&mut lock)
+            .map_err(manage::persist::Error::WriteConfigIo)?;
+        lock.commit()?;
+
+        let path = self.common_dir().join("config");
+        let mut written = Some(config);
+        let mut snapshot = self.config_snapshot_mut();
+        let mut resolved = gix_config::File::new(snapshot.meta_owned());
+        for section in snapshot.sections() {
+            let meta = section.meta();
+            if meta.source > gix_config::Source::Local {
+                if let Some(written) = written.take() {
+                    resolved.append(written);
+                }
+            }
+            if !is_local_config_file(meta, &path) {
+                resolved.push_section(section.clone());
+            }
+        }
+        if let Some(written) = written {
+            resolved.append(written);
+        }
+        *snapshot = resolved;
+        snapshot.commit()?;
+        Ok(())
+    }
+}
+
+/// The repository-local configuration file, which stays locked until it is written back or dropped.
+struct LocalConfig {
+    lock: gix_lock::File,
+    config: gix_config::File<'static>,
+}
+
+fn is_local_config_file(meta: &gix_config::file::Metadata, path: &Path) -> bool {
+    meta.source == gix_config::Source::Local && meta.path.as_deref() == Some(path)
+}
+
+fn value_name(name: &'static str) -> gix_config::parse::section::ValueName<'static> {
+    name.try_into().expect("valid")
+}
+
+/// Return the ids of all `remote.<name>` sections in `config`, in order.
+fn remote_section_ids(config: &gix_config::File<'static>, name: &BStr) -> Vec<gix_config::file::SectionId> {
+    config
+        .sections_and_ids_by_name("remote")
+        .map(|sections| {
+            sections
+                .filter(|(section, _)| section.header().subsection_name() == Some(name))
+                .map(|(_, id)| id)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Call `change(section, key)` for each `branch.<branch>.remote`, `branch.<branch>.pushRemote` and `remote.pushDefault`
+/// whose value is the remote `name`, and return the ids of all changed sections.
+fn replace_remote_references(
+    config: &mut gix_config::File<'static>,
+    name: &BStr,
+    mut change: impl FnMut(&mut gix_config::file::SectionMut<'_, 'static>, &'static str),
+) -> Vec<gix_config::file::SectionId> {
+    let mut changed = Vec::new();
+    for (section_name, keys) in [
+        (
+            "branch",
+            &[
+                config::tree::Branch::REMOTE.name,
+                config::tree::Branch::PUSH_REMOTE.name,
+            ][..],
+        ),
+        ("remote", &[config::tree::Remote::PUSH_DEFAULT.name][..]),
+    ] {
+        let ids: Vec<_> = config
+            .sections_and_ids_by_name(section_name)
+            .map(|sections| {
+                sections
+                    .filter(|(section, _)| (section_name == "branch") == section.header().subsection_name().is_some())
+                    .map(|(_, id)| id)
+                    .collect()
+            })
+            .unwrap_or_default();
+        for id in ids {
+            let mut section = config.section_mut_by_id(id).expect("just listed");
+            for key in keys {
+                if section.value(key).map_or(false, |value| value.as_ref() == name) {
+                    change(&mut section, key);
+                    changed.push(id);
+                }
+            }
+        }
+    }
+    changed.dedup();
+    changed
+}
+
+/// Return `true` if the reference `name` is written to by a fetch ref-spec with the given `destination`,
+/// which may contain a single glob.
+fn matches_destination(destination: &BStr, name: &BStr) -> bool {
+    match destination.find_byte(b'*') {
+        Some(pos) => {
+            let (prefix, suffix) = (&destination[..pos], &destination[pos + 1..]);
+            name.len() >= prefix.len() + suffix.len() && name.starts_with(prefix) && name.ends_with(suffix)
+        }
+        None => destination == name,
+    }
+}
@@ -0,0 +1,286 @@
+use std::{
+    io::{BufWriter, Seek},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+    time::SystemTime,
+};
+
+use gix_features::progress::{Count, DynNestedProgress, Progress};
+use gix_pack::data::output;
+
+use crate::repack::{Error, Mode, Options, Outcome};
+
+/// Maintenance of packs
+impl crate::Repository {
+    /// Consolidate packs of this repository's object database as selected by the mode in `options` into a single new pack,
+    /// update the multi-pack index, and remove the packs that became redundant, similar to `git repack -d`.
+    ///
+    /// The new pack and multi-pack index are made visible before redundant packs are removed, so concurrent readers
+    /// always see all objects. Readers that already use a removed pack can continue to do so if their platform allows
+    /// open files to be removed, and readers that refresh their view of the object database will find the new pack.
+    /// Redundant packs that can't be removed are listed in the outcome.
+    ///
+    /// Loose objects and packs marked with a `.keep` file are left untouched, as are packs in alternate object databases.
+    /// `progress` is used to report the progress of pack creation and `should_interrupt` is polled to abort the operation.
+    pub fn repack(
+        &self,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::repack()");
+        if let Mode::Geometric { factor } = options.mode {
+            if factor < 2 {
+                return Err(Error::InvalidGeometricFactor { factor });
+            }
+        }
+        let object_hash = self.object_hash();
+        let pack_dir = self.objects.store_ref().path().join("pack");
+        let packs = list_packs(&pack_dir, object_hash)?;
+        let selected = select_packs(&packs, options.mode);
+
+        let mut outcome = Outcome::default();
+        let mut index_paths: Vec<PathBuf> = packs.iter().map(|p| p.index_path.clone()).collect();
+        if selected.len() > 1 {
+            let mut ids = Vec::new();
+            for idx in &selected {
+                let index = gix_pack::index::File::at(&packs[*idx].index_path, object_hash)?;
+                ids.extend(index.iter().map(|entry| entry.oid));
+            }
+            let bundle = self.write_pack(ids, &pack_dir, progress, should_interrupt, options.thread_limit)?;
+            let new_index_path = bundle.index_path.expect("written to a directory");
+            outcome.num_objects = bundle.index.num_objects;
+            outcome.consolidated_packs = selected
+                .iter()
+                .map(|idx| packs[*idx].index_path.clone())
+                .filter(|path| *path != new_index_path)
+                .collect();
+            index_paths.retain(|path| !outcome.consolidated_packs.contains(path));
+            if !index_paths.contains(&new_index_path) {
+                index_paths.push(new_index_path.clone());
+            }
+            outcome.index_path = Some(new_index_path);
+        }
+
+        let multi_index_path = pack_dir.join("multi-pack-index");
+        let needs_multi_index = (outcome.index_path.is_some() && multi_index_path.is_file()) || options.write_multi_pack_index;
+        if needs_multi_index && !index_paths.is_empty() {
+            let mut out = BufWriter::new(gix_lock::File::acquire_to_update_resource(
+                &multi_index_path,
+                gix_lock::acquire::Fail::Immediately,
+                None,
+            )?);
+            gix_pack::multi_index::File::write_from_index_paths(
+                index_paths,
+                &mut out,
+                progress,
+                should_interrupt,
+                gix_pack::multi_index::write::Options { object_hash },
+            )?;
+            out.into_inner().map_err(|err| Error::FlushMultiIndex(err.into_error()))?.commit()?;
+            outcome.multi_pack_index_path = Some(multi_index_path);
+        }
+
+        if outcome.consolidated_packs.is_empty() {
+            return Ok(outcome);
+        }
+        // Let our own handles know about the new state before removing packs so they don't fail to find objects,
+        // even though they would pick up the change on their own.
+        self.objects.store_ref().refresh()?;
+        for index_path in &outcome.consolidated_packs {
+            if !remove_pack(index_path) {
+                outcome.retained_packs.push(index_path.clone());
+            }
+        }
+        Ok(outcome)
+    }
+
+    /// Write all objects with `ids` into a new pack in `pack_dir` along with its index, and return the outcome of writing it.
+    /// `ids` may contain duplicates.
+    pub(crate) fn write_pack(
+        &self,
+        mut ids: Vec<gix_hash::ObjectId>,
+        pack_dir: &Path,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        thread_limit: Option<usize>,
+    ) -> Result<gix_pack::bundle::write::Outcome, Error> {
+        let object_hash = self.object_hash();
+        ids.sort();
+        ids.dedup();
+        let num_objects = ids.len();
+        let counts = ids
+            .into_iter()
+            .map(|id| output::Count {
+                id,
+                entry_pack_location: output::count::PackLocation::NotLookedUp,
+            })
+            .collect();
+
+        // Objects only held in memory aren't in packs, so it's fine to only look at the objects on disk.
+        let mut db = self.objects.clone().into_arc().map_err(Error::ConvertStore)?.into_inner();
+        db.prevent_pack_unload();
+        db.ignore_replacements = true;
+        let entries = gix_features::parallel::InOrderIter::from(output::entry::iter_from_counts(
+            counts,
+            db,
+            Box::new(progress.add_child("creating entries".into())),
+            output::entry::iter_from_counts::Options {
+                thread_limit,
+                mode: output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects,
+                allow_thin_pack: false,
+                chunk_size: 1000,
+                version: Default::default(),
+            },
+        ));
+
+        let mut pack = gix_tempfile::new(
+            pack_dir,
+            gix_tempfile::ContainingDirectory::Exists,
+            gix_tempfile::AutoRemove::Tempfile,
+        )
+        .map_err(Error::CreateTempfile)?;
+        let mut write_progress = progress.add_child("writing".into());
+        write_progress.init(None, gix_features::progress::bytes());
+        for written in output::bytes::FromEntriesIter::new(
+            entries,
+            &mut pack,
+            num_objects as u32,
+            gix_pack::data::Version::V2,
+            object_hash,
+        ) {
+            write_progress.inc_by(written? as usize);
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+        }
+
+        pack.rewind().map_err(Error::ReadPack)?;
+        let bundle = gix_pack::Bundle::write_to_directory(
+            &mut std::io::BufReader::new(&mut pack),
+            Some(pack_dir),
+            progress,
+            should_interrupt,
+            None::<gix_object::find::Never>,
+            gix_pack::bundle::write::Options {
+                thread_limit,
+                iteration_mode: gix_pack::data::input::Mode::Verify,
+                index_version: Default::default(),
+                object_hash,
+            },
+        )?;
+        if let Some(keep_path) = &bundle.keep_path {
+            std::fs::remove_file(keep_path).map_err(|source| Error::RemoveKeepFile {
+                path: keep_path.clone(),
+                source,
+            })?;
+        }
+        Ok(bundle)
+    }
+}
+
+/// A pack in the object database.
+struct Pack {
+    index_path: PathBuf,
+    num_objects: u32,
+    size_in_bytes: u64,
+    mtime: SystemTime,
+    is_kept: bool,
+}
+
+/// Return all packs in `pack_dir` with an index and a data file, sorted by index path.
+fn list_packs(pack_dir: &Path, object_hash: gix_hash::Kind) -> Result<Vec<Pack>, Error> {
+    let list_error = |source| Error::ListPacks {
+        path: pack_dir.to_owned(),
+        source,
+    };
+    let entries = match std::fs::read_dir(pack_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(list_error(err)),
+    };
+    let mut packs = Vec::new();
+    for entry in entries {
+        let index_path = entry.map_err(list_error)?.path();
+        if index_path.extension() != Some("idx".as_ref()) {
+            continue;
+        }
+        let Ok(data) = std::fs::metadata(index_path.with_extension("pack")) else {
+            continue;
+        };
+        let index = gix_pack::index::File::at(&index_path, object_hash)?;
+        packs.push(Pack {
+            num_objects: index.num_objects(),
+            size_in_bytes: data.len(),
+            mtime: data.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            is_kept: index_path.with_extension("keep").is_file(),
+            index_path,
+        });
+    }
+    packs.sort_by(|a, b| a.index_path.cmp(&b.index_path));
+    Ok(packs)
+}
+
+/// Return the indices into `packs` of all packs to consolidate according to `mode`.
+fn select_packs(packs: &[Pack], mode: Mode) -> Vec<usize> {
+    let mut candidates: Vec<_> = (0..packs.len()).filter(|idx| !packs[*idx].is_kept).collect();
+    match mode {
+        Mode::All => candidates,
+        Mode::Geometric { factor } => {
+            let factor = u64::from(factor);
+            candidates.sort_by_key(|idx| packs[*idx].num_objects);
+            let weight = |pos: usize| u64::from(packs[candidates[pos]].num_objects);
+
+            // Find the largest packs that already form a geometric progression…
+            let mut split = 0;
+            for pos in (1..candidates.len()).rev() {
+                if weight(pos) < factor * weight(pos - 1) {
+                    split = pos + 1;
+                    break;
+                }
+            }
+            // …and roll up all smaller packs, along with larger ones that would break the progression once these are combined.
+            let mut rolled_up_objects: u64 = (0..split).map(weight).sum();
+            while split < candidates.len() && weight(split) < factor * rolled_up_objects {
+                rolled_up_objects += weight(split);
+                split += 1;
+            }
+            candidates.truncate(split);
+            candidates
+        }
+        Mode::BatchSize(batch_size) => {
+            candidates.sort_by_key(|idx| packs[*idx].mtime);
+            let mut total_size = 0;
+            let mut selected = Vec::new();
+            for idx in candidates {
+                if total_size >= batch_size {
+                    break;
+                }
+                let size = packs[idx].size_in_bytes;
+                if size >= batch_size {
+                    continue;
+                }
+                total_size += size;
+                selected.push(idx);
+            }
+            if total_size < batch_size {
+                selected.clear();
+            }
+            selected
+        }
+    }
+}
+
+/// Remove all files belonging to the pack with `index_path`, starting with the index so the pack isn't picked up anymore,
+/// and return `true` if the index and the pack data could be removed.
+fn remove_pack(index_path: &Path) -> bool {
+    let remove = |path: &Path| match std::fs::remove_file(path) {
+        Ok(()) => true,
+        Err(err) => err.kind() == std::io::ErrorKind::NotFound,
+    };
+    remove(index_path)
+        && remove(&index_path.with_extension("pack"))
+        && ["rev", "bitmap", "mtimes"]
+            .into_iter()
+            .all(|extension| remove(&index_path.with_extension(extension)))
+}
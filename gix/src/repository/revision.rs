@@ -104,6 +104,64 @@ impl crate::Repository {
             .collect())
     }
 
+    /// Create a platform to name commits relative to the closest reference that contains them, similar to `git name-rev`.
+    ///
+    /// By default, all references are used.
+    #[doc(alias = "name-rev")]
+    #[cfg(feature = "revision")]
+    pub fn name_rev(&self) -> revision::name_rev::Platform<'_> {
+        revision::name_rev::Platform {
+            repo: self,
+            tags_only: false,
+            all: false,
+            matching: Vec::new(),
+            excluding: Vec::new(),
+        }
+    }
+
+    /// Return all references that contain `commit`, i.e. from which `commit` is reachable, similar to
+    /// `git for-each-ref --contains <commit>`.
+    ///
+    /// # Performance
+    ///
+    /// For repeated calls, prefer [`refs_containing_with_cache()`](Self::refs_containing_with_cache()) to reuse
+    /// what's known about the commit graph.
+    #[doc(alias = "branch_contains", alias = "tag_contains")]
+    #[cfg(feature = "revision")]
+    pub fn refs_containing(
+        &self,
+        commit: impl Into<gix_hash::ObjectId>,
+    ) -> Result<Vec<crate::Reference<'_>>, super::refs_containing::Error> {
+        let commit_graph = self.commit_graph_if_enabled()?;
+        let mut cache = gix_revision::contains::Cache::new(self.revision_graph(commit_graph.as_ref()));
+        self.refs_containing_with_cache(commit, &mut cache)
+    }
+
+    /// Like [`refs_containing()`](Self::refs_containing()), but uses `cache` to remember the commit graph
+    /// and reachability information across calls.
+    ///
+    /// The `cache` can be created with `gix_revision::contains::Cache::new(repo.revision_graph(commit_graph))`, ideally
+    /// using a [commit-graph](Self::commit_graph_if_enabled()) for generation numbers to stop traversals early.
+    #[cfg(feature = "revision")]
+    pub fn refs_containing_with_cache(
+        &self,
+        commit: impl Into<gix_hash::ObjectId>,
+        cache: &mut gix_revision::contains::Cache<'_, '_>,
+    ) -> Result<Vec<crate::Reference<'_>>, super::refs_containing::Error> {
+        let commit = commit.into();
+        let mut out = Vec::new();
+        for reference in self.references()?.all()? {
+            let mut reference = reference.map_err(super::refs_containing::Error::Iter)?;
+            let Ok(tip) = reference.peel_to_id_in_place() else {
+                continue;
+            };
+            if cache.contains(tip.detach(), commit)? {
+                out.push(crate::Reference::from_ref(reference.detach(), self));
+            }
+        }
+        Ok(out)
+    }
+
     /// Create the baseline for a revision walk by initializing it with the `tips` to start iterating on.
     ///
     /// It can be configured further before starting the actual walk.
@@ -0,0 +1,73 @@
+use std::io::Write;
+
+use crate::repository::update_server_info::Error;
+
+/// Support for serving the repository over 'dumb' transports.
+impl crate::Repository {
+    /// Write the auxiliary files that clients need to fetch from this repository when it's served as static files,
+    /// for instance using the 'dumb' HTTP protocol, similar to `git update-server-info`.
+    ///
+    /// This writes `info/refs` with all references and the objects they point to, including the peeled object of annotated tags,
+    /// along with `objects/info/packs` which lists all packs in the object database.
+    /// Both files are replaced atomically, and have to be updated whenever references or packs change.
+    pub fn update_server_info(&self) -> Result<(), Error> {
+        let _span = gix_trace::coarse!("gix::update_server_info()");
+        let mut refs = Vec::new();
+        for reference in self.references()?.all()? {
+            let mut reference = reference.map_err(Error::IterReferences)?;
+            let id = reference.follow_to_object()?;
+            let peeled = reference.peel_to_id_in_place()?;
+            let name = reference.name().as_bstr();
+            writeln!(refs, "{id}\t{name}").expect("write to memory works");
+            if peeled != id {
+                writeln!(refs, "{peeled}\t{name}^{{}}").expect("write to memory works");
+            }
+        }
+        write_atomically(&self.common_dir().join("info").join("refs"), self.common_dir(), &refs)?;
+
+        let objects_dir = self.objects.store_ref().path();
+        let pack_dir = objects_dir.join("pack");
+        let mut names = Vec::new();
+        match std::fs::read_dir(&pack_dir) {
+            Ok(entries) => {
+                for entry in entries {
+                    let path = entry
+                        .map_err(|source| Error::ListPacks {
+                            path: pack_dir.clone(),
+                            source,
+                        })?
+                        .path();
+                    if path.extension() == Some("pack".as_ref()) && path.with_extension("idx").is_file() {
+                        if let Some(name) = path.file_name().and_then(|name| name.to_str()) {
+                            names.push(name.to_owned());
+                        }
+                    }
+                }
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(source) => return Err(Error::ListPacks { path: pack_dir, source }),
+        }
+        names.sort();
+        let mut packs = Vec::new();
+        for name in names {
+            writeln!(packs, "P {name}").expect("write to memory works");
+        }
+        packs.push(b'\n');
+        write_atomically(&objects_dir.join("info").join("packs"), objects_dir, &packs)?;
+        Ok(())
+    }
+}
+
+fn write_atomically(path: &std::path::Path, boundary_directory: &std::path::Path, data: &[u8]) -> Result<(), Error> {
+    let mut lock = gix_lock::File::acquire_to_update_resource(
+        path,
+        gix_lock::acquire::Fail::Immediately,
+        Some(boundary_directory.to_owned()),
+    )?;
+    lock.write_all(data).map_err(|source| Error::Write {
+        path: path.to_owned(),
+        source,
+    })?;
+    lock.commit().map_err(|err| Error::Commit(err.error))?;
+    Ok(())
+}
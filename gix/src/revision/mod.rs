@@ -13,6 +13,10 @@ pub use walk::iter_impl::Walk;
 #[cfg(feature = "revision")]
 pub mod spec;
 
+///
+#[cfg(feature = "revision")]
+pub mod name_rev;
+
 /// The specification of a revision as parsed from a revision specification like `HEAD@{1}` or `v1.2.3...main`.
 /// It's typically created by [`repo.rev_parse()`][crate::Repository::rev_parse()].
 ///
@@ -0,0 +1,214 @@
+use std::borrow::Cow;
+
+use gix_hash::ObjectId;
+use gix_revision::name_rev::{Name, Tip};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    Repository,
+};
+
+/// The error returned by [`Platform::names()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    OpenCache(#[from] crate::repository::commit_graph_if_enabled::Error),
+    #[error(transparent)]
+    NameRev(#[from] gix_revision::name_rev::Error),
+    #[error(transparent)]
+    RefIter(#[from] crate::reference::iter::Error),
+    #[error(transparent)]
+    RefIterInit(#[from] crate::reference::iter::init::Error),
+}
+
+/// A platform to configure a `git name-rev` operation, as created by [`Repository::name_rev()`].
+pub struct Platform<'repo> {
+    pub(crate) repo: &'repo Repository,
+    pub(crate) tags_only: bool,
+    pub(crate) all: bool,
+    pub(crate) matching: Vec<BString>,
+    pub(crate) excluding: Vec<BString>,
+}
+
+/// The names of commits as produced by [`Platform::names()`].
+#[derive(Debug, Clone)]
+pub struct Names {
+    names: gix_hashtable::HashMap<ObjectId, Name>,
+    outcome: gix_revision::name_rev::Outcome<'static>,
+}
+
+impl Names {
+    /// Return the name of `id` like `v1.0~2^2`, or `None` if it isn't reachable from any of the selected references.
+    ///
+    /// Note that only the names of commits passed to [`Platform::names()`] are reliable, unless [`Platform::all()`] was set.
+    pub fn get(&self, id: impl AsRef<gix_hash::oid>) -> Option<BString> {
+        self.names.get(id.as_ref()).map(|name| self.outcome.format(name))
+    }
+
+    /// Return the amount of commits that were traversed to produce the names.
+    pub fn commits_seen(&self) -> u32 {
+        self.outcome.commits_seen
+    }
+}
+
+impl<'repo> Platform<'repo> {
+    /// If `true`, only use tags to name commits, like `git name-rev --tags`, which also removes the `refs/tags/`
+    /// prefix of the produced names.
+    /// Otherwise, all references are used, and names are shown without `refs/heads/` or `refs/` prefix.
+    pub fn tags_only(mut self, toggle: bool) -> Self {
+        self.tags_only = toggle;
+        self
+    }
+
+    /// If `true`, name all commits reachable from the selected references, instead of stopping the traversal early
+    /// at commits that can't contain any of the commits to name.
+    pub fn all(mut self, toggle: bool) -> Self {
+        self.all = toggle;
+        self
+    }
+
+    /// Only use references whose name matches any of the given glob `patterns`, like `git name-rev --refs`.
+    ///
+    /// Patterns may match the full reference name or any of its trailing components, so `v*` matches `refs/tags/v1.0`
+    /// and `feature/*` matches `refs/heads/feature/a`.
+    pub fn matching(mut self, patterns: impl IntoIterator<Item = impl Into<BString>>) -> Self {
+        self.matching = patterns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Do not use references whose name matches any of the given glob `patterns`, like `git name-rev --exclude`.
+    ///
+    /// See [`Self::matching()`] for details on how references are matched.
+    pub fn excluding(mut self, patterns: impl IntoIterator<Item = impl Into<BString>>) -> Self {
+        self.excluding = patterns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Name the given `commits` relative to the closest selected reference that contains them, using the commit-graph
+    /// to stop the traversal early if it is available.
+    ///
+    /// # Performance
+    ///
+    /// All `commits` are named in a single traversal, which is much faster than naming them one by one.
+    pub fn names(&self, commits: impl IntoIterator<Item = impl Into<ObjectId>>) -> Result<Names, Error> {
+        let cache = self.repo.commit_graph_if_enabled()?;
+        self.names_with_cache(commits, cache.as_ref())
+    }
+
+    /// Like [`Self::names()`], but with an optional commit-graph `cache` to reuse it across calls.
+    pub fn names_with_cache(
+        &self,
+        commits: impl IntoIterator<Item = impl Into<ObjectId>>,
+        cache: Option<&gix_commitgraph::Graph>,
+    ) -> Result<Names, Error> {
+        let commits: Vec<_> = commits.into_iter().map(Into::into).collect();
+        let platform = self.repo.references()?;
+        let refs = if self.tags_only {
+            platform.tags()?
+        } else {
+            platform.all()?
+        };
+        let tips = tips(
+            self.repo,
+            refs.filter_map(Result::ok)
+                .filter(|r| self.selection(r.name().as_bstr()).is_some()),
+            |name| {
+                let abbreviate = self.tags_only || self.selection(name.as_bstr()) == Some(true);
+                if abbreviate {
+                    name.shorten().to_owned().into()
+                } else {
+                    let name = name.as_bstr();
+                    name.strip_prefix(b"refs/heads/")
+                        .or_else(|| name.strip_prefix(b"refs/"))
+                        .unwrap_or(name)
+                        .as_bstr()
+                        .to_owned()
+                        .into()
+                }
+            },
+        );
+        let mut graph = self.repo.revision_graph(cache);
+        let outcome = gix_revision::name_rev(
+            &commits,
+            tips,
+            &mut graph,
+            gix_revision::name_rev::Options { all: self.all },
+        )?;
+        Ok(Names {
+            names: graph.detach(),
+            outcome,
+        })
+    }
+
+    /// Return the name of a single `commit`, or `None` if it isn't contained in any of the selected references.
+    pub fn name(&self, commit: impl Into<ObjectId>) -> Result<Option<BString>, Error> {
+        let commit = commit.into();
+        Ok(self.names(Some(commit))?.get(commit))
+    }
+
+    /// Return `None` if the reference `name` isn't selected, or `Some(abbreviate)` if it is, with `abbreviate` being `true`
+    /// if a pattern matched only the trailing components of `name`.
+    fn selection(&self, name: &BStr) -> Option<bool> {
+        if self
+            .excluding
+            .iter()
+            .any(|pattern| subpath_matches(name, pattern.as_ref()).is_some())
+        {
+            return None;
+        }
+        if self.matching.is_empty() {
+            return Some(false);
+        }
+        let mut selection = None;
+        for pos in self
+            .matching
+            .iter()
+            .filter_map(|pattern| subpath_matches(name, pattern.as_ref()))
+        {
+            selection = Some(selection.unwrap_or(false) || pos > 0);
+        }
+        selection
+    }
+}
+
+/// Return the position at which `pattern` matches `name` or any of its trailing components.
+fn subpath_matches(name: &BStr, pattern: &BStr) -> Option<usize> {
+    let mut pos = 0;
+    loop {
+        let subpath = name[pos..].as_bstr();
+        if gix_glob::wildmatch(pattern, subpath, gix_glob::wildmatch::Mode::empty()) {
+            return Some(pos);
+        }
+        pos += subpath.find_byte(b'/')? + 1;
+    }
+}
+
+/// Turn `refs` into tips for use with [`gix_revision::name_rev()`], naming each of them with `name`.
+///
+/// The date of tips is the date of the first annotated tag, or the commit date if the reference points to a commit directly.
+pub(crate) fn tips<'repo>(
+    repo: &'repo Repository,
+    refs: impl Iterator<Item = crate::Reference<'repo>>,
+    mut name: impl FnMut(&gix_ref::FullNameRef) -> Cow<'static, BStr>,
+) -> Vec<Tip<'static>> {
+    refs.filter_map(|mut r| {
+        let target_id = r.target().try_id().map(ToOwned::to_owned);
+        let peeled_id = r.peel_to_id_in_place().ok()?.detach();
+        let (peeled, tagger_date) = match target_id {
+            Some(target_id) if peeled_id != target_id => {
+                let tag = repo.find_object(target_id).ok()?.try_into_tag().ok()?;
+                (true, tag.tagger().ok()??.time.seconds)
+            }
+            _ => (false, repo.find_commit(peeled_id).ok()?.time().ok()?.seconds),
+        };
+        Some(Tip {
+            id: peeled_id,
+            name: name(r.name()),
+            from_tag: r.name().category() == Some(gix_ref::Category::Tag),
+            tagger_date,
+            peeled,
+        })
+    })
+    .collect()
+}
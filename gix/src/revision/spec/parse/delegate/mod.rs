@@ -44,6 +44,22 @@ impl<'repo> Delegate<'repo> {
         Error::from_errors(self.err)
     }
 
+    /// Parse all entries of `FETCH_HEAD` and resolve it to the first one, just like `git` does.
+    fn find_fetch_head(&self) -> Result<gix_ref::Reference, Error> {
+        let name: gix_ref::FullName = "FETCH_HEAD".try_into().expect("valid");
+        match self.repo.fetch_head()?.and_then(|entries| entries.into_iter().next()) {
+            Some(first) => Ok(gix_ref::Reference {
+                name,
+                target: gix_ref::Target::Object(first.id),
+                peeled: None,
+            }),
+            None => Err(gix_ref::file::find::existing::Error::NotFound {
+                name: gix_path::from_bstr(name.as_bstr()).into_owned(),
+            }
+            .into()),
+        }
+    }
+
     pub fn into_rev_spec(mut self) -> Result<crate::revision::Spec<'repo>, Error> {
         fn zero_or_one_objects_or_ambiguity_err(
             mut candidates: [Option<HashSet<ObjectId>>; 2],
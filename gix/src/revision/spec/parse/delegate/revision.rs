@@ -19,14 +19,19 @@ impl<'repo> delegate::Revision for Delegate<'repo> {
         if !self.err.is_empty() && self.refs[self.idx].is_some() {
             return None;
         }
-        match self.repo.refs.find(name) {
+        let res = if name == "FETCH_HEAD" {
+            self.find_fetch_head()
+        } else {
+            self.repo.refs.find(name).map_err(Into::into)
+        };
+        match res {
             Ok(r) => {
                 assert!(self.refs[self.idx].is_none(), "BUG: cannot set the same ref twice");
                 self.refs[self.idx] = Some(r);
                 Some(())
             }
             Err(err) => {
-                self.err.push(err.into());
+                self.err.push(err);
                 None
             }
         }
@@ -105,60 +110,87 @@ impl<'repo> delegate::Revision for Delegate<'repo> {
 
     fn reflog(&mut self, query: ReflogLookup) -> Option<()> {
         self.unset_disambiguate_call();
+        let r = match &mut self.refs[self.idx] {
+            Some(r) => r.clone().attach(self.repo),
+            val @ None => match self.repo.head().map(crate::Head::try_into_referent) {
+                Ok(Some(r)) => {
+                    *val = Some(r.clone().detach());
+                    r
+                }
+                Ok(None) => {
+                    self.err.push(Error::UnbornHeadsHaveNoRefLog);
+                    return None;
+                }
+                Err(err) => {
+                    self.err.push(err.into());
+                    return None;
+                }
+            },
+        };
+        let mut platform = r.log_iter();
         match query {
-            ReflogLookup::Date(_date) => {
-                // TODO: actually do this - this should be possible now despite incomplete date parsing
-                self.err.push(Error::Planned {
-                    dependency: "remote handling and ref-specs are fleshed out more",
-                });
-                None
-            }
-            ReflogLookup::Entry(no) => {
-                let r = match &mut self.refs[self.idx] {
-                    Some(r) => r.clone().attach(self.repo),
-                    val @ None => match self.repo.head().map(crate::Head::try_into_referent) {
-                        Ok(Some(r)) => {
-                            *val = Some(r.clone().detach());
-                            r
-                        }
-                        Ok(None) => {
-                            self.err.push(Error::UnbornHeadsHaveNoRefLog);
-                            return None;
-                        }
-                        Err(err) => {
-                            self.err.push(err.into());
-                            return None;
-                        }
-                    },
+            ReflogLookup::Date(date) => {
+                let Some(log) = platform.rev().ok().flatten() else {
+                    self.err.push(Error::MissingRefLog {
+                        reference: r.name().as_bstr().into(),
+                        action: "lookup entry by date",
+                    });
+                    return None;
                 };
-                let mut platform = r.log_iter();
-                match platform.rev().ok().flatten() {
-                    Some(mut it) => match it.nth(no).and_then(Result::ok) {
-                        Some(line) => {
-                            self.objs[self.idx]
-                                .get_or_insert_with(HashSet::default)
-                                .insert(line.new_oid);
-                            Some(())
-                        }
-                        None => {
-                            let available = platform.rev().ok().flatten().map_or(0, Iterator::count);
-                            self.err.push(Error::RefLogEntryOutOfRange {
-                                reference: r.detach(),
-                                desired: no,
-                                available,
-                            });
-                            None
-                        }
-                    },
+                // Like git, use the newest entry made at or before `date`, or fall back to the oldest state we know.
+                let mut oldest = None;
+                let mut found = None;
+                for line in log.filter_map(Result::ok) {
+                    if line.signature.time.seconds <= date.seconds {
+                        found = Some(line.new_oid);
+                        break;
+                    }
+                    oldest = Some(if line.previous_oid.is_null() {
+                        line.new_oid
+                    } else {
+                        line.previous_oid
+                    });
+                }
+                match found.or(oldest) {
+                    Some(id) => {
+                        self.objs[self.idx].get_or_insert_with(HashSet::default).insert(id);
+                        Some(())
+                    }
                     None => {
                         self.err.push(Error::MissingRefLog {
                             reference: r.name().as_bstr().into(),
-                            action: "lookup entry",
+                            action: "lookup entry by date",
                         });
                         None
                     }
                 }
             }
+            ReflogLookup::Entry(no) => match platform.rev().ok().flatten() {
+                Some(mut it) => match it.nth(no).and_then(Result::ok) {
+                    Some(line) => {
+                        self.objs[self.idx]
+                            .get_or_insert_with(HashSet::default)
+                            .insert(line.new_oid);
+                        Some(())
+                    }
+                    None => {
+                        let available = platform.rev().ok().flatten().map_or(0, Iterator::count);
+                        self.err.push(Error::RefLogEntryOutOfRange {
+                            reference: r.detach(),
+                            desired: no,
+                            available,
+                        });
+                        None
+                    }
+                },
+                None => {
+                    self.err.push(Error::MissingRefLog {
+                        reference: r.name().as_bstr().into(),
+                        action: "lookup entry",
+                    });
+                    None
+                }
+            },
         }
     }
 
@@ -0,0 +1,164 @@
+use gix_traverse::commit::Info;
+
+use super::iter;
+use crate::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    Repository,
+};
+
+/// A pattern to match the author, committer or message of commits with, similar to the values of
+/// `git log --author`, `--committer` or `--grep`.
+///
+/// Patterns match if any line of the text they are matched against matches.
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    /// Match lines that contain `text` verbatim, similar to `git log --fixed-strings`.
+    Fixed {
+        /// The text to search for.
+        text: BString,
+        /// If `true`, ASCII characters are compared case-insensitively, similar to `git log --regexp-ignore-case`.
+        ignore_case: bool,
+    },
+    /// Match lines with a regular expression, similar to `git log --extended-regexp`.
+    #[cfg(feature = "revparse-regex")]
+    Regex(regex::bytes::Regex),
+}
+
+impl Pattern {
+    /// Create a pattern that matches lines containing `text` verbatim, ignoring the case of ASCII characters if `ignore_case` is `true`.
+    pub fn fixed(text: impl Into<BString>, ignore_case: bool) -> Self {
+        let mut text = text.into();
+        if ignore_case {
+            text.make_ascii_lowercase();
+        }
+        Pattern::Fixed { text, ignore_case }
+    }
+
+    /// Create a pattern from the regular expression `pattern`, where `^` and `$` match at the beginning and end of each line,
+    /// ignoring the case of all characters if `ignore_case` is `true`.
+    #[cfg(feature = "revparse-regex")]
+    pub fn regex(pattern: &str, ignore_case: bool) -> Result<Self, regex::Error> {
+        regex::bytes::RegexBuilder::new(pattern)
+            .multi_line(true)
+            .case_insensitive(ignore_case)
+            .build()
+            .map(Pattern::Regex)
+    }
+
+    /// Return `true` if any line of `haystack` matches this pattern.
+    pub fn matches(&self, haystack: &BStr) -> bool {
+        match self {
+            Pattern::Fixed { text, ignore_case } => {
+                if *ignore_case {
+                    haystack.to_ascii_lowercase().find(text).is_some()
+                } else {
+                    haystack.find(text).is_some()
+                }
+            }
+            #[cfg(feature = "revparse-regex")]
+            Pattern::Regex(regex) => regex.is_match(haystack),
+        }
+    }
+}
+
+/// Determine how commits are simplified if the walk is [limited to paths](super::Platform::paths()),
+/// similar to the history simplification of `git log -- <path>`.
+///
+/// A commit is *TREESAME* to a parent if all paths have the same content in both.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Simplification {
+    /// Show commits that change the paths, and if a merge is TREESAME to one of its parents, only follow that parent.
+    ///
+    /// This is the default of `git log -- <path>`, which hides side-branches that didn't contribute to the final state of the paths.
+    #[default]
+    Default,
+    /// Follow all parents, and show commits that aren't TREESAME to all of their parents, like `git log --full-history -- <path>`.
+    FullHistory,
+    /// Like [`FullHistory`](Self::FullHistory), but rewrite the parents of shown commits to their closest shown ancestors,
+    /// and show merges only if they still have more than one parent afterwards, like `git log --simplify-merges -- <path>`.
+    ///
+    /// The rewritten parents are returned as [parent ids](super::Info::parent_ids).
+    SimplifyMerges,
+}
+
+/// All filters set on the [walk platform](super::Platform).
+#[derive(Default, Debug, Clone)]
+pub(crate) struct Filter {
+    pub authors: Vec<Pattern>,
+    pub committers: Vec<Pattern>,
+    pub grep: Vec<Pattern>,
+    pub all_match: bool,
+    pub invert_grep: bool,
+    pub since: Option<gix_date::SecondsSinceUnixEpoch>,
+    pub until: Option<gix_date::SecondsSinceUnixEpoch>,
+    pub min_parents: usize,
+    pub max_parents: Option<usize>,
+    pub paths: Vec<BString>,
+    #[cfg(feature = "blob-diff")]
+    pub follow: bool,
+    pub simplification: Simplification,
+    pub ancestry_path: bool,
+}
+
+impl Filter {
+    /// Return `true` if all commits have to be known before the first one can be returned.
+    pub fn is_limited(&self) -> bool {
+        !self.paths.is_empty() || self.ancestry_path
+    }
+
+    /// Return `true` if commits are filtered individually, after the walk was possibly limited.
+    pub fn is_selective(&self) -> bool {
+        self.needs_commit() || self.min_parents > 0 || self.max_parents.is_some()
+    }
+
+    /// Return `true` if the commit of `info` should be returned.
+    pub fn matches(&self, repo: &Repository, info: &Info) -> Result<bool, iter::Error> {
+        let parents = info.parent_ids.len();
+        if parents < self.min_parents || self.max_parents.map_or(false, |max| parents > max) {
+            return Ok(false);
+        }
+        if !self.needs_commit() {
+            return Ok(true);
+        }
+        let commit = repo.find_commit(info.id)?;
+        let matches = self.matches_commit(&commit.decode()?);
+        Ok(matches)
+    }
+
+    /// Return `true` if commits have to be decoded to decide if they are returned.
+    fn needs_commit(&self) -> bool {
+        !(self.authors.is_empty() && self.committers.is_empty() && self.grep.is_empty())
+            || self.since.is_some()
+            || self.until.is_some()
+    }
+
+    fn matches_commit(&self, commit: &gix_object::CommitRef<'_>) -> bool {
+        let time = commit.committer.time.seconds;
+        if self.since.map_or(false, |since| time < since) || self.until.map_or(false, |until| time > until) {
+            return false;
+        }
+        let signature_matches = |patterns: &[Pattern], signature: gix_actor::SignatureRef<'_>| {
+            if patterns.is_empty() {
+                return true;
+            }
+            let mut line = BString::from(signature.name);
+            line.push_str(" <");
+            line.push_str(signature.email);
+            line.push_byte(b'>');
+            patterns.iter().any(|pattern| pattern.matches(line.as_ref()))
+        };
+        if !signature_matches(&self.authors, commit.author) || !signature_matches(&self.committers, commit.committer) {
+            return false;
+        }
+        if self.grep.is_empty() {
+            return true;
+        }
+        let matches = |pattern: &Pattern| pattern.matches(commit.message);
+        let message_matches = if self.all_match {
+            self.grep.iter().all(matches)
+        } else {
+            self.grep.iter().any(matches)
+        };
+        message_matches != self.invert_grep
+    }
+}
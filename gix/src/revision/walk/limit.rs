@@ -0,0 +1,452 @@
+use std::collections::HashMap;
+
+use gix_hash::ObjectId;
+use gix_object::tree::EntryMode;
+use gix_traverse::commit::{Info, ParentIds};
+
+use super::{
+    filter::{Filter, Simplification},
+    iter,
+};
+use crate::{bstr::BString, Repository};
+
+/// An iterator that needs to see all commits of the walk before it can return the first one, to limit them to
+/// the ones that change given paths, possibly with history simplification, or to the ones on the ancestry path.
+pub(super) struct Limited<'repo> {
+    repo: &'repo Repository,
+    pending: Option<Pending<'repo>>,
+    ready: std::vec::IntoIter<Info>,
+}
+
+struct Pending<'repo> {
+    inner: Box<dyn Iterator<Item = Result<Info, iter::Error>> + 'repo>,
+    tips: Vec<ObjectId>,
+    bottoms: Vec<ObjectId>,
+    filter: Filter,
+}
+
+impl<'repo> Limited<'repo> {
+    /// Limit the commits produced by `inner`, which started at `tips` and stopped at `bottoms`, as configured by `filter`.
+    pub fn new(
+        repo: &'repo Repository,
+        inner: Box<dyn Iterator<Item = Result<Info, iter::Error>> + 'repo>,
+        tips: Vec<ObjectId>,
+        bottoms: Vec<ObjectId>,
+        filter: Filter,
+    ) -> Self {
+        Limited {
+            repo,
+            pending: Some(Pending {
+                inner,
+                tips,
+                bottoms,
+                filter,
+            }),
+            ready: Vec::new().into_iter(),
+        }
+    }
+}
+
+impl Iterator for Limited<'_> {
+    type Item = Result<Info, iter::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(pending) = self.pending.take() {
+            match limit(self.repo, pending) {
+                Ok(commits) => self.ready = commits.into_iter(),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+        self.ready.next().map(Ok)
+    }
+}
+
+fn limit(repo: &Repository, pending: Pending<'_>) -> Result<Vec<Info>, iter::Error> {
+    let Pending {
+        inner,
+        tips,
+        bottoms,
+        filter,
+    } = pending;
+    let commits = inner.collect::<Result<Vec<_>, _>>()?;
+    let graph = Graph::new(&commits);
+    let mut shown = vec![true; commits.len()];
+    let mut rewritten_parents = None;
+
+    if !filter.paths.is_empty() {
+        let order = graph.topological_order();
+        let mut entries = Entries {
+            repo,
+            path_sets: vec![filter.paths.clone()],
+            cache: HashMap::new(),
+        };
+        let compared = compare_with_parents(&graph, &order, &tips, &mut entries, &filter)?;
+        match filter.simplification {
+            Simplification::Default | Simplification::FullHistory => {
+                for (shown, compared) in shown.iter_mut().zip(&compared) {
+                    *shown = compared.as_ref().map_or(false, |compared| !compared.treesame);
+                }
+            }
+            Simplification::SimplifyMerges => {
+                rewritten_parents = Some(simplify_merges(&graph, &order, &compared, &mut shown));
+            }
+        }
+    }
+
+    if filter.ancestry_path {
+        if bottoms.is_empty() {
+            return Err(iter::Error::AncestryPathWithoutBottom);
+        }
+        let mut on_path = vec![false; commits.len()];
+        for &idx in graph.topological_order().iter().rev() {
+            on_path[idx] = commits[idx]
+                .parent_ids
+                .iter()
+                .any(|parent| bottoms.contains(parent) || graph.index(parent).map_or(false, |idx| on_path[idx]));
+        }
+        for (shown, on_path) in shown.iter_mut().zip(on_path) {
+            *shown &= on_path;
+        }
+    }
+
+    let mut rewritten_parents = rewritten_parents.map(Vec::into_iter);
+    Ok(commits
+        .into_iter()
+        .zip(shown)
+        .filter_map(|(mut info, shown)| {
+            if let Some(parent_ids) = rewritten_parents.as_mut().and_then(Iterator::next) {
+                info.parent_ids = parent_ids;
+            }
+            shown.then_some(info)
+        })
+        .collect())
+}
+
+/// How a commit compares to its parents with respect to the paths the walk is limited to.
+struct Compared {
+    /// If `true`, the commit is considered TREESAME and won't be shown if there is no simplification of merges.
+    treesame: bool,
+    /// For each parent that was compared, `true` if the paths are the same in the commit and the parent.
+    ///
+    /// With the default simplification, the comparison stops at the first relevant TREESAME parent.
+    parents: Vec<bool>,
+}
+
+/// Compare each commit reachable from `tips` with its parents, in `order`, and return `None` for commits that aren't reachable
+/// because they are behind a TREESAME parent with the default simplification.
+fn compare_with_parents(
+    graph: &Graph<'_>,
+    order: &[usize],
+    tips: &[ObjectId],
+    entries: &mut Entries<'_>,
+    filter: &Filter,
+) -> Result<Vec<Option<Compared>>, iter::Error> {
+    // The index into the path sets of `entries` for each reachable commit. These only differ when following renames.
+    let mut path_sets = vec![None; graph.commits.len()];
+    for tip in tips {
+        if let Some(idx) = graph.index(tip) {
+            path_sets[idx] = Some(0);
+        }
+    }
+
+    let mut out: Vec<Option<Compared>> = std::iter::repeat_with(|| None).take(graph.commits.len()).collect();
+    for &idx in order {
+        let Some(path_set) = path_sets[idx] else { continue };
+        let id = graph.commits[idx].id;
+        let parent_ids = &graph.commits[idx].parent_ids;
+        if parent_ids.is_empty() {
+            out[idx] = Some(Compared {
+                treesame: entries.is_absent(id, path_set)?,
+                parents: Vec::new(),
+            });
+            continue;
+        }
+
+        let mut parents = Vec::with_capacity(parent_ids.len());
+        let mut parent_path_sets = Vec::with_capacity(parent_ids.len());
+        let mut followed_only = None;
+        for (pos, parent) in parent_ids.iter().enumerate() {
+            let same = entries.treesame(id, *parent, path_set)?;
+            #[cfg_attr(not(feature = "blob-diff"), allow(unused_mut))]
+            let mut parent_path_set = path_set;
+            #[cfg(feature = "blob-diff")]
+            if filter.follow && !same {
+                if let Some(source) = entries.rename_source(id, *parent, path_set)? {
+                    parent_path_set = source;
+                }
+            }
+            parents.push(same);
+            parent_path_sets.push(parent_path_set);
+            if same && filter.simplification == Simplification::Default && graph.index(parent).is_some() {
+                followed_only = Some(pos);
+                break;
+            }
+        }
+
+        let treesame = match followed_only {
+            Some(_) => true,
+            None => {
+                let mut relevant = parent_ids
+                    .iter()
+                    .zip(&parents)
+                    .filter(|(parent, _)| graph.index(parent).is_some())
+                    .peekable();
+                if relevant.peek().is_some() {
+                    relevant.all(|(_, same)| *same)
+                } else {
+                    parents.iter().all(|same| *same)
+                }
+            }
+        };
+        let followed = match followed_only {
+            Some(pos) => pos..pos + 1,
+            None => 0..parent_ids.len(),
+        };
+        for pos in followed {
+            if let Some(parent_idx) = graph.index(&parent_ids[pos]) {
+                path_sets[parent_idx].get_or_insert(parent_path_sets[pos]);
+            }
+        }
+        out[idx] = Some(Compared { treesame, parents });
+    }
+    Ok(out)
+}
+
+/// Simplify each commit to itself or its closest ancestor that has to be shown, like `git log --simplify-merges` does,
+/// update `shown` accordingly and return the rewritten parents of each commit.
+fn simplify_merges(
+    graph: &Graph<'_>,
+    order: &[usize],
+    compared: &[Option<Compared>],
+    shown: &mut [bool],
+) -> Vec<ParentIds> {
+    let commits = graph.commits;
+    let mut simplified = vec![None::<ObjectId>; commits.len()];
+    let mut rewritten_parents = vec![ParentIds::new(); commits.len()];
+    let is_treesame_root = |id: &ObjectId| {
+        graph.index(id).map_or(false, |idx| {
+            commits[idx].parent_ids.is_empty() && compared[idx].as_ref().map_or(false, |compared| compared.treesame)
+        })
+    };
+
+    for &idx in order.iter().rev() {
+        let id = commits[idx].id;
+        let Some(compared) = compared[idx].as_ref() else {
+            shown[idx] = false;
+            continue;
+        };
+
+        // A commit is always TREESAME to its simplification.
+        let mut parents: Vec<(ObjectId, bool)> = Vec::with_capacity(commits[idx].parent_ids.len());
+        for (parent, same) in commits[idx].parent_ids.iter().zip(&compared.parents) {
+            let parent = graph
+                .index(parent)
+                .and_then(|parent_idx| simplified[parent_idx])
+                .unwrap_or(*parent);
+            if !parents.iter().any(|(existing, _)| *existing == parent) {
+                parents.push((parent, *same));
+            }
+        }
+
+        if parents.len() > 1 {
+            let mut marked: Vec<_> = parents
+                .iter()
+                .map(|(parent, _)| {
+                    is_treesame_root(parent)
+                        || parents
+                            .iter()
+                            .any(|(other, _)| other != parent && graph.is_ancestor(parent, other))
+                })
+                .collect();
+            let mut treesame_parents = parents.iter().zip(&marked).filter(|((_, same), _)| *same);
+            if treesame_parents.all(|(_, marked)| *marked) {
+                if let Some(pos) = parents.iter().position(|(_, same)| *same) {
+                    marked[pos] = false;
+                }
+            }
+            let mut marked = marked.into_iter();
+            parents.retain(|_| !marked.next().expect("one per parent"));
+        }
+
+        let relevant: Vec<_> = parents
+            .iter()
+            .filter(|(parent, _)| graph.index(parent).is_some())
+            .collect();
+        let treesame = if parents.is_empty() {
+            compared.treesame
+        } else if relevant.is_empty() {
+            parents.iter().all(|(_, same)| *same)
+        } else {
+            relevant.iter().all(|(_, same)| *same)
+        };
+        let sole_parent = match (parents.as_slice(), relevant.as_slice()) {
+            ([(parent, _)], _) | (_, [(parent, _)]) => Some(*parent),
+            _ => None,
+        };
+        let simplified_id = match sole_parent {
+            Some(parent) if treesame => parent,
+            _ => id,
+        };
+        simplified[idx] = Some(simplified_id);
+        shown[idx] = simplified_id == id && (!treesame || relevant.len() > 1);
+        rewritten_parents[idx] = parents.into_iter().map(|(parent, _)| parent).collect();
+    }
+    rewritten_parents
+}
+
+/// The commits of the walk with quick access to their parents.
+struct Graph<'a> {
+    commits: &'a [Info],
+    index_by_id: HashMap<ObjectId, usize>,
+}
+
+impl<'a> Graph<'a> {
+    fn new(commits: &'a [Info]) -> Self {
+        Graph {
+            commits,
+            index_by_id: commits.iter().enumerate().map(|(idx, info)| (info.id, idx)).collect(),
+        }
+    }
+
+    /// Return the index of `id`, or `None` if it's not part of the walk.
+    fn index(&self, id: &ObjectId) -> Option<usize> {
+        self.index_by_id.get(id).copied()
+    }
+
+    /// Return the indices of all commits such that children come before their parents.
+    fn topological_order(&self) -> Vec<usize> {
+        let mut children = vec![0usize; self.commits.len()];
+        for info in self.commits {
+            for parent_idx in info.parent_ids.iter().filter_map(|parent| self.index(parent)) {
+                children[parent_idx] += 1;
+            }
+        }
+        let mut stack: Vec<_> = (0..self.commits.len())
+            .rev()
+            .filter(|idx| children[*idx] == 0)
+            .collect();
+        let mut out = Vec::with_capacity(self.commits.len());
+        while let Some(idx) = stack.pop() {
+            out.push(idx);
+            for parent_idx in self.commits[idx]
+                .parent_ids
+                .iter()
+                .rev()
+                .filter_map(|parent| self.index(parent))
+            {
+                children[parent_idx] -= 1;
+                if children[parent_idx] == 0 {
+                    stack.push(parent_idx);
+                }
+            }
+        }
+        out
+    }
+
+    /// Return `true` if `ancestor` can be reached from `descendant` through commits of the walk.
+    fn is_ancestor(&self, ancestor: &ObjectId, descendant: &ObjectId) -> bool {
+        let mut seen = vec![false; self.commits.len()];
+        let mut queue: Vec<_> = self.index(descendant).into_iter().collect();
+        while let Some(idx) = queue.pop() {
+            for parent in &self.commits[idx].parent_ids {
+                if parent == ancestor {
+                    return true;
+                }
+                if let Some(parent_idx) = self.index(parent) {
+                    if !std::mem::replace(&mut seen[parent_idx], true) {
+                        queue.push(parent_idx);
+                    }
+                }
+            }
+        }
+        false
+    }
+}
+
+/// The id and mode of the tree entry at a path, or `None` if there is no such entry.
+type Entry = Option<(ObjectId, EntryMode)>;
+
+/// The tree entries of the paths to compare commits with, cached per commit.
+struct Entries<'repo> {
+    repo: &'repo Repository,
+    /// The sets of paths to compare, which only differ from the first one when following renames.
+    path_sets: Vec<Vec<BString>>,
+    cache: HashMap<(ObjectId, usize), Vec<Entry>>,
+}
+
+impl Entries<'_> {
+    fn get(&mut self, commit: ObjectId, path_set: usize) -> Result<&[Entry], iter::Error> {
+        if !self.cache.contains_key(&(commit, path_set)) {
+            let tree = self.repo.find_commit(commit)?.tree()?;
+            let mut entries = Vec::with_capacity(self.path_sets[path_set].len());
+            for path in &self.path_sets[path_set] {
+                entries.push(
+                    tree.lookup_entry(path.split(|b| *b == b'/'))?
+                        .map(|entry| (entry.object_id(), entry.mode())),
+                );
+            }
+            self.cache.insert((commit, path_set), entries);
+        }
+        Ok(&self.cache[&(commit, path_set)])
+    }
+
+    /// Return `true` if all paths have the same entries in `commit` and `parent`.
+    fn treesame(&mut self, commit: ObjectId, parent: ObjectId, path_set: usize) -> Result<bool, iter::Error> {
+        let entries = self.get(commit, path_set)?.to_vec();
+        Ok(entries == self.get(parent, path_set)?)
+    }
+
+    /// Return `true` if none of the paths exist in `commit`.
+    fn is_absent(&mut self, commit: ObjectId, path_set: usize) -> Result<bool, iter::Error> {
+        Ok(self.get(commit, path_set)?.iter().all(Option::is_none))
+    }
+
+    /// If the only path of `path_set` was added in `commit` compared to `parent`, return the index of the path set
+    /// with the path it was renamed from.
+    #[cfg(feature = "blob-diff")]
+    fn rename_source(
+        &mut self,
+        commit: ObjectId,
+        parent: ObjectId,
+        path_set: usize,
+    ) -> Result<Option<usize>, iter::Error> {
+        use crate::object::tree::diff::{change::Event, Action};
+
+        if self.path_sets[path_set].len() != 1
+            || self.get(commit, path_set)?[0].is_none()
+            || self.get(parent, path_set)?[0].is_some()
+        {
+            return Ok(None);
+        }
+        let path = &self.path_sets[path_set][0];
+        let old = self.repo.find_commit(parent)?.tree()?;
+        let new = self.repo.find_commit(commit)?.tree()?;
+        let mut source = None;
+        old.changes()?
+            .track_path()
+            .track_rewrites(Some(Default::default()))
+            .for_each_to_obtain_tree(&new, |change| {
+                if let Event::Rewrite {
+                    source_location,
+                    copy: false,
+                    ..
+                } = change.event
+                {
+                    if change.location == path {
+                        source = Some(source_location.to_owned());
+                    }
+                }
+                Ok::<_, std::convert::Infallible>(Action::Continue)
+            })?;
+        Ok(source.map(|source| {
+            let path_set = vec![source];
+            match self.path_sets.iter().position(|existing| *existing == path_set) {
+                Some(idx) => idx,
+                None => {
+                    self.path_sets.push(path_set);
+                    self.path_sets.len() - 1
+                }
+            }
+        }))
+    }
+}
@@ -0,0 +1,656 @@
+use gix_hash::ObjectId;
+use gix_object::FindExt;
+
+use crate::{bstr::BString, ext::ObjectIdExt, revision, Repository};
+
+///
+pub mod filter;
+pub use filter::{Pattern, Simplification};
+mod limit;
+
+/// The error returned by [`Platform::all()`] and [`Platform::selected()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    SimpleTraversal(#[from] gix_traverse::commit::simple::Error),
+    #[error(transparent)]
+    TopoTraversal(#[from] gix_traverse::commit::topo::Error),
+    #[error(transparent)]
+    ShallowCommits(#[from] crate::shallow::open::Error),
+    #[error(transparent)]
+    ConfigBoolean(#[from] crate::config::boolean::Error),
+}
+
+/// Specify how to sort commits during a [revision::Walk] traversal.
+///
+/// ### Sample History
+///
+/// The following history will be referred to for explaining how the sort order works, with the number denoting the commit timestamp
+/// (*their X-alignment doesn't matter*).
+///
+/// ```text
+/// ---1----2----4----7 <- second parent of 8
+///     \              \
+///      3----5----6----8---
+/// ```
+#[derive(Default, Debug, Copy, Clone)]
+pub enum Sorting {
+    /// Commits are sorted as they are mentioned in the commit graph.
+    ///
+    /// In the *sample history* the order would be `8, 6, 7, 5, 4, 3, 2, 1`
+    ///
+    /// ### Note
+    ///
+    /// This is not to be confused with `git log/rev-list --topo-order`, which is notably different from
+    /// as it avoids overlapping branches.
+    #[default]
+    BreadthFirst,
+    /// Commits are sorted by their commit time in descending order, that is newest first.
+    ///
+    /// The sorting applies to all currently queued commit ids and thus is full.
+    ///
+    /// In the *sample history* the order would be `8, 7, 6, 4, 5, 2, 3, 1`
+    ///
+    /// # Performance
+    ///
+    /// This mode benefits greatly from having an [object cache](crate::Repository::object_cache_size) configured
+    /// to avoid having to look up each commit twice.
+    ByCommitTimeNewestFirst,
+    /// This sorting is similar to `ByCommitTimeNewestFirst`, but adds a cutoff to not return commits older than
+    /// a given time, stopping the iteration once no younger commits is queued to be traversed.
+    ///
+    /// As the query is usually repeated with different cutoff dates, this search mode benefits greatly from an object cache.
+    ///
+    /// In the *sample history* and a cut-off date of 4, the returned list of commits would be `8, 7, 6, 4`
+    ByCommitTimeNewestFirstCutoffOlderThan {
+        /// The amount of seconds since unix epoch to use as cut-off time.
+        seconds: gix_date::SecondsSinceUnixEpoch,
+    },
+    /// Show no parents before all of their children are shown, and avoid showing commits of multiple lines of history
+    /// intermixed, like `git log --topo-order`.
+    ///
+    /// In the *sample history* the order would be `8, 6, 5, 3, 7, 4, 2, 1`
+    ///
+    /// # Performance
+    ///
+    /// With a commit-graph, its generation numbers are used to only explore as much of the history as needed
+    /// to return the next commit, so the first commits are returned quickly even in very large repositories.
+    /// Without it, all commits reachable from the tips are read before the first one is returned.
+    ///
+    /// ### Note
+    ///
+    /// [Pruned](Platform::with_pruned()) commits are excluded with all of their ancestors, without a cutoff date.
+    /// Shallow repositories aren't supported yet, the traversal fails once it encounters a missing parent.
+    TopoOrder,
+    /// Show no parents before all of their children are shown, but otherwise show commits by their commit time
+    /// in descending order, like `git log --date-order`.
+    ///
+    /// In the *sample history* the order would be `8, 7, 6, 5, 4, 3, 2, 1`
+    ///
+    /// Performance characteristics and limitations are the same as for [`TopoOrder`](Self::TopoOrder).
+    DateOrder,
+    /// Show no parents before all of their children are shown, but otherwise show commits by their author time
+    /// in descending order, like `git log --author-date-order`.
+    ///
+    /// Performance characteristics and limitations are the same as for [`TopoOrder`](Self::TopoOrder), but as author times
+    /// aren't part of the commit-graph, each commit is read from the object database.
+    AuthorDateOrder,
+}
+
+impl Sorting {
+    fn into_simple(self) -> Option<gix_traverse::commit::simple::Sorting> {
+        Some(match self {
+            Sorting::BreadthFirst => gix_traverse::commit::simple::Sorting::BreadthFirst,
+            Sorting::ByCommitTimeNewestFirst => gix_traverse::commit::simple::Sorting::ByCommitTimeNewestFirst,
+            Sorting::ByCommitTimeNewestFirstCutoffOlderThan { seconds } => {
+                gix_traverse::commit::simple::Sorting::ByCommitTimeNewestFirstCutoffOlderThan { seconds }
+            }
+            Sorting::TopoOrder | Sorting::DateOrder | Sorting::AuthorDateOrder => return None,
+        })
+    }
+
+    fn into_topo(self) -> Option<gix_traverse::commit::topo::Sorting> {
+        Some(match self {
+            Sorting::TopoOrder => gix_traverse::commit::topo::Sorting::TopoOrder,
+            Sorting::DateOrder => gix_traverse::commit::topo::Sorting::DateOrder,
+            Sorting::AuthorDateOrder => gix_traverse::commit::topo::Sorting::AuthorDateOrder,
+            Sorting::BreadthFirst
+            | Sorting::ByCommitTimeNewestFirst
+            | Sorting::ByCommitTimeNewestFirstCutoffOlderThan { .. } => return None,
+        })
+    }
+}
+
+/// Information about a commit that we obtained naturally as part of the iteration.
+#[derive(Debug, Clone)]
+pub struct Info<'repo> {
+    /// The detached id of the commit.
+    pub id: gix_hash::ObjectId,
+    /// All parent ids we have encountered. Note that these will be at most one if [`Parents::First`][gix_traverse::commit::Parents::First] is enabled.
+    pub parent_ids: gix_traverse::commit::ParentIds,
+    /// The time at which the commit was created. It will only be `Some(_)` if the chosen traversal was
+    /// taking dates into consideration.
+    pub commit_time: Option<gix_date::SecondsSinceUnixEpoch>,
+    /// If `true`, this commit isn't part of the traversal, but is a parent of a returned commit that was excluded from it,
+    /// as returned after all other commits if [boundary commits](Platform::boundary()) are requested.
+    pub boundary: bool,
+
+    repo: &'repo Repository,
+}
+
+/// Access
+impl<'repo> Info<'repo> {
+    /// Provide an attached version of our [`id`][Info::id] field.
+    pub fn id(&self) -> crate::Id<'repo> {
+        self.id.attach(self.repo)
+    }
+
+    /// Read the whole object from the object database.
+    ///
+    /// Note that this is an expensive operation which shouldn't be performed unless one needs more than parent ids
+    /// and commit time.
+    pub fn object(&self) -> Result<crate::Commit<'repo>, crate::object::find::existing::Error> {
+        Ok(self.id().object()?.into_commit())
+    }
+
+    /// Provide an iterator yielding attached versions of our [`parent_ids`][Info::parent_ids] field.
+    pub fn parent_ids(&self) -> impl Iterator<Item = crate::Id<'repo>> + '_ {
+        self.parent_ids.iter().map(|id| id.attach(self.repo))
+    }
+
+    /// Returns the commit-time of this commit.
+    ///
+    /// ### Panics
+    ///
+    /// If the iteration wasn't ordered by date.
+    pub fn commit_time(&self) -> gix_date::SecondsSinceUnixEpoch {
+        self.commit_time.expect("traversal involving date caused it to be set")
+    }
+}
+
+/// Initialization and detachment
+impl<'repo> Info<'repo> {
+    /// Create a new instance that represents `info`, but is attached to `repo` as well.
+    pub fn new(info: gix_traverse::commit::Info, repo: &'repo Repository) -> Self {
+        Info {
+            id: info.id,
+            parent_ids: info.parent_ids,
+            commit_time: info.commit_time,
+            boundary: false,
+            repo,
+        }
+    }
+    /// Consume this instance and remove the reference to the underlying repository.
+    ///
+    /// This is useful for sending instances across threads, for example.
+    pub fn detach(self) -> gix_traverse::commit::Info {
+        gix_traverse::commit::Info {
+            id: self.id,
+            parent_ids: self.parent_ids,
+            commit_time: self.commit_time,
+        }
+    }
+}
+
+/// A platform to traverse the revision graph by adding starting points as well as points which shouldn't be crossed,
+/// returned by [`Repository::rev_walk()`].
+///
+/// **Note that we automatically leverage the commitgraph data structure**, but if you know that additional information like
+/// author or commit messages will be required of *all* commits traversed here, it should be better to avoid trying to load it
+/// by [turning commit-graph support off][Platform::use_commit_graph()]. This certainly is a micro-optimization though.
+pub struct Platform<'repo> {
+    pub(crate) repo: &'repo Repository,
+    pub(crate) tips: Vec<ObjectId>,
+    pub(crate) prune: Vec<ObjectId>,
+    pub(crate) sorting: Sorting,
+    pub(crate) parents: gix_traverse::commit::Parents,
+    pub(crate) use_commit_graph: Option<bool>,
+    pub(crate) commit_graph: Option<gix_commitgraph::Graph>,
+    pub(crate) filter: filter::Filter,
+    pub(crate) boundary: bool,
+}
+
+impl<'repo> Platform<'repo> {
+    pub(crate) fn new(tips: impl IntoIterator<Item = impl Into<ObjectId>>, repo: &'repo Repository) -> Self {
+        revision::walk::Platform {
+            repo,
+            tips: tips.into_iter().map(Into::into).collect(),
+            sorting: Default::default(),
+            parents: Default::default(),
+            use_commit_graph: None,
+            commit_graph: None,
+            prune: Vec::new(),
+            filter: Default::default(),
+            boundary: false,
+        }
+    }
+}
+
+/// Create-time builder methods
+impl<'repo> Platform<'repo> {
+    /// Set the sort mode for commits to the given value. The default is to order topologically breadth-first.
+    pub fn sorting(mut self, sorting: Sorting) -> Self {
+        self.sorting = sorting;
+        self
+    }
+
+    /// Only traverse the first parent of the commit graph.
+    pub fn first_parent_only(mut self) -> Self {
+        self.parents = gix_traverse::commit::Parents::First;
+        self
+    }
+
+    /// Allow using the commitgraph, if present, if `toggle` is `true`, or disallow it with `false`. Set it to `None` to leave
+    /// control over this to the configuration of `core.commitGraph` (the default).
+    ///
+    /// Errors when loading the graph lead to falling back to the object database, it's treated as optional cache.
+    pub fn use_commit_graph(mut self, toggle: impl Into<Option<bool>>) -> Self {
+        self.use_commit_graph = toggle.into();
+        self
+    }
+
+    /// Set or unset the commit-graph to use for the iteration. This is useful if the caller wants to check if a commit-graph exists
+    /// and refer different implementations depending on the outcome.
+    ///
+    /// It interacts with [`use_commit_graph`][Platform::use_commit_graph()] as one would expect, but it's worth noting that if `None`,
+    /// with [`use_commit_graph`][Platform::use_commit_graph()] being `true`, a graph will still be used for iteration.
+    /// To turn the commit-graph off, call [`use_commit_graph(false)`][Platform::use_commit_graph()] instead.
+    pub fn with_commit_graph(mut self, graph: Option<gix_commitgraph::Graph>) -> Self {
+        self.commit_graph = graph;
+        self
+    }
+
+    /// Prune the commit with the given `ids` such that they won't be returned, and such that none of their ancestors is returned either.
+    ///
+    /// Note that unless a topological [sorting](Self::sorting) like [`TopoOrder`](Sorting::TopoOrder) is used,
+    /// this forces the sorting to [`ByCommitTimeNewestFirstCutoffOlderThan`](Sorting::ByCommitTimeNewestFirstCutoffOlderThan)
+    /// configured with the oldest available commit time, ensuring that no commits older than the oldest of `ids` will be
+    /// returned either.
+    ///
+    /// Also note that commits that can't be accessed or are missing are simply ignored for the purpose of obtaining the cutoff date.
+    #[doc(alias = "hide", alias = "git2")]
+    pub fn with_pruned(mut self, ids: impl IntoIterator<Item = impl Into<ObjectId>>) -> Self {
+        let mut cutoff = match self.sorting {
+            Sorting::ByCommitTimeNewestFirstCutoffOlderThan { seconds } => Some(seconds),
+            Sorting::BreadthFirst | Sorting::ByCommitTimeNewestFirst => None,
+            Sorting::TopoOrder | Sorting::DateOrder | Sorting::AuthorDateOrder => {
+                for id in ids.into_iter().map(Into::into) {
+                    if !self.prune.contains(&id) {
+                        self.prune.push(id);
+                    }
+                }
+                return self;
+            }
+        };
+        for id in ids.into_iter() {
+            let id = id.into();
+            if !self.prune.contains(&id) {
+                if let Some(time) = self.repo.find_commit(id).ok().and_then(|c| c.time().ok()) {
+                    if cutoff.is_none() || cutoff > Some(time.seconds) {
+                        cutoff = time.seconds.into();
+                    }
+                }
+                self.prune.push(id);
+            }
+        }
+
+        if let Some(cutoff) = cutoff {
+            self.sorting = Sorting::ByCommitTimeNewestFirstCutoffOlderThan { seconds: cutoff }
+        }
+        self
+    }
+}
+
+/// Filters, similar to the ones of `git log`
+///
+/// All filters are combined, so only commits that pass all of them are returned. Note that the [`paths`](Self::paths())
+/// and [`ancestry_path`](Self::ancestry_path()) filters need to see all commits of the walk before returning the first one.
+impl<'repo> Platform<'repo> {
+    /// Only return commits whose author, formatted as `Name <email>`, matches `pattern`, like `git log --author`.
+    ///
+    /// If called multiple times, commits whose author matches any of the patterns are returned.
+    pub fn author(mut self, pattern: Pattern) -> Self {
+        self.filter.authors.push(pattern);
+        self
+    }
+
+    /// Only return commits whose committer, formatted as `Name <email>`, matches `pattern`, like `git log --committer`.
+    ///
+    /// If called multiple times, commits whose committer matches any of the patterns are returned.
+    pub fn committer(mut self, pattern: Pattern) -> Self {
+        self.filter.committers.push(pattern);
+        self
+    }
+
+    /// Only return commits whose message matches `pattern`, like `git log --grep`.
+    ///
+    /// If called multiple times, commits whose message matches any of the patterns are returned,
+    /// unless [`all_match`](Self::all_match()) is set.
+    pub fn grep(mut self, pattern: Pattern) -> Self {
+        self.filter.grep.push(pattern);
+        self
+    }
+
+    /// If `toggle` is `true`, only return commits whose message matches all [`grep`](Self::grep()) patterns, like `git log --all-match`.
+    pub fn all_match(mut self, toggle: bool) -> Self {
+        self.filter.all_match = toggle;
+        self
+    }
+
+    /// If `toggle` is `true`, only return commits whose message doesn't match the [`grep`](Self::grep()) patterns,
+    /// like `git log --invert-grep`.
+    pub fn invert_grep(mut self, toggle: bool) -> Self {
+        self.filter.invert_grep = toggle;
+        self
+    }
+
+    /// Only return commits whose commit time is at or after `seconds` since the unix epoch, like `git log --since`.
+    ///
+    /// Note that older commits are still traversed, use [`Sorting::ByCommitTimeNewestFirstCutoffOlderThan`] to stop early.
+    pub fn since(mut self, seconds: gix_date::SecondsSinceUnixEpoch) -> Self {
+        self.filter.since = Some(seconds);
+        self
+    }
+
+    /// Only return commits whose commit time is at or before `seconds` since the unix epoch, like `git log --until`.
+    pub fn until(mut self, seconds: gix_date::SecondsSinceUnixEpoch) -> Self {
+        self.filter.until = Some(seconds);
+        self
+    }
+
+    /// Only return commits with at least `count` parents, like `git log --min-parents`.
+    ///
+    /// Use `2` to only return merges, like `git log --merges`.
+    pub fn min_parents(mut self, count: usize) -> Self {
+        self.filter.min_parents = count;
+        self
+    }
+
+    /// Only return commits with at most `count` parents, or set it to `None` to not limit the amount of parents,
+    /// like `git log --max-parents`.
+    ///
+    /// Use `1` to not return merges, like `git log --no-merges`.
+    pub fn max_parents(mut self, count: impl Into<Option<usize>>) -> Self {
+        self.filter.max_parents = count.into();
+        self
+    }
+
+    /// Only return commits that change any of the given `paths`, relative to the root of the repository,
+    /// like `git log -- <path>…`, simplified as configured by [`simplification`](Self::simplification()).
+    ///
+    /// Paths are matched literally, and a directory matches all paths within it.
+    pub fn paths(mut self, paths: impl IntoIterator<Item = impl Into<BString>>) -> Self {
+        self.filter.paths.extend(paths.into_iter().map(Into::into));
+        self
+    }
+
+    /// Only return commits that change the file at `path`, and continue with its previous name
+    /// when it was renamed, like `git log --follow -- <path>`.
+    #[cfg(feature = "blob-diff")]
+    pub fn follow(mut self, path: impl Into<BString>) -> Self {
+        self.filter.paths = vec![path.into()];
+        self.filter.follow = true;
+        self
+    }
+
+    /// Set how the history is simplified if it is limited to [`paths`](Self::paths()).
+    pub fn simplification(mut self, simplification: Simplification) -> Self {
+        self.filter.simplification = simplification;
+        self
+    }
+
+    /// If `toggle` is `true`, return the parents of returned commits that are excluded from the traversal after all
+    /// other commits, newest first, and with [`Info::boundary`] set, like `git log --boundary`.
+    ///
+    /// These are typically the [pruned commits](Self::with_pruned()) and those of their ancestors that are parents of returned commits.
+    pub fn boundary(mut self, toggle: bool) -> Self {
+        self.boundary = toggle;
+        self
+    }
+
+    /// If `toggle` is `true`, only return commits that are descendants of the [pruned commits](Self::with_pruned()),
+    /// like `git log --ancestry-path <pruned>..<tip>`.
+    ///
+    /// Note that it's an error if there are no pruned commits.
+    pub fn ancestry_path(mut self, toggle: bool) -> Self {
+        self.filter.ancestry_path = toggle;
+        self
+    }
+}
+
+/// Produce the iterator
+impl<'repo> Platform<'repo> {
+    /// For each commit, let `filter` return `true` if it and its parents should be included in the traversal, or `false`
+    /// if the traversal should exclude it and its ancestry entirely.
+    ///
+    /// If `filter` is None, no pruning of the graph will be performed which is the default.
+    ///
+    /// Note that with topological [sortings](Sorting::TopoOrder), commits for which `filter` returns `false` are
+    /// merely skipped, while their parents are still traversed. Use [`with_pruned()`](Self::with_pruned()) to exclude
+    /// the ancestry of commits instead.
+    pub fn selected(
+        self,
+        mut filter: impl FnMut(&gix_hash::oid) -> bool + 'repo,
+    ) -> Result<revision::Walk<'repo>, Error> {
+        let Platform {
+            repo,
+            tips,
+            sorting,
+            parents,
+            use_commit_graph,
+            commit_graph,
+            mut prune,
+            filter: commit_filter,
+            boundary,
+        } = self;
+        prune.sort();
+        let commit_graph = commit_graph.or(use_commit_graph
+            .map_or_else(|| repo.config.may_use_commit_graph(), Ok)?
+            .then(|| repo.commit_graph().ok())
+            .flatten());
+        let limit_tips = commit_filter.is_limited().then(|| (tips.clone(), prune.clone()));
+        let mut inner: Box<dyn Iterator<Item = Result<gix_traverse::commit::Info, iter::Error>> + 'repo> =
+            match sorting.into_topo() {
+                Some(topo_sorting) => Box::new(
+                    gix_traverse::commit::topo::Builder::from_iters(&repo.objects, tips, Some(prune))
+                        .with_predicate(filter)
+                        .sorting(topo_sorting)
+                        .parents(parents)
+                        .with_commit_graph(commit_graph)
+                        .build()?
+                        .map(|res| res.map_err(iter::Error::from)),
+                ),
+                None => Box::new(
+                    gix_traverse::commit::Simple::filtered(tips, &repo.objects, {
+                        // Note that specific shallow handling for commit-graphs isn't needed as these contain
+                        // all information there is, and exclude shallow parents to be structurally consistent.
+                        let shallow_commits = repo.shallow_commits()?;
+                        let mut grafted_parents_to_skip = Vec::new();
+                        let mut buf = Vec::new();
+                        move |id| {
+                            if !filter(id) {
+                                return false;
+                            }
+                            let id = id.to_owned();
+                            if prune.binary_search(&id).is_ok() {
+                                return false;
+                            }
+                            match shallow_commits.as_ref() {
+                                Some(commits) => {
+                                    if let Ok(idx) = grafted_parents_to_skip.binary_search(&id) {
+                                        grafted_parents_to_skip.remove(idx);
+                                        return false;
+                                    };
+                                    if commits.binary_search(&id).is_ok() {
+                                        if let Ok(commit) = repo.objects.find_commit_iter(&id, &mut buf) {
+                                            grafted_parents_to_skip.extend(commit.parent_ids());
+                                            grafted_parents_to_skip.sort();
+                                        }
+                                    };
+                                    true
+                                }
+                                None => true,
+                            }
+                        }
+                    })
+                    .sorting(sorting.into_simple().expect("non-topological sortings are simple"))?
+                    .parents(parents)
+                    .commit_graph(commit_graph)
+                    .map(|res| res.map_err(iter::Error::from)),
+                ),
+            };
+        let boundary = if boundary {
+            let traversed = std::rc::Rc::new(std::cell::RefCell::new(gix_hashtable::HashSet::default()));
+            inner = Box::new(inner.inspect({
+                let traversed = traversed.clone();
+                move |res| {
+                    if let Ok(info) = res {
+                        traversed.borrow_mut().insert(info.id);
+                    }
+                }
+            }));
+            Some(iter_impl::Boundary::new(traversed))
+        } else {
+            None
+        };
+        if let Some((tips, bottoms)) = limit_tips {
+            inner = Box::new(limit::Limited::new(repo, inner, tips, bottoms, commit_filter.clone()));
+        }
+        if commit_filter.is_selective() {
+            inner = Box::new(inner.filter_map(move |res| match res {
+                Ok(info) => match commit_filter.matches(repo, &info) {
+                    Ok(true) => Some(Ok(info)),
+                    Ok(false) => None,
+                    Err(err) => Some(Err(err)),
+                },
+                Err(err) => Some(Err(err)),
+            }));
+        }
+        Ok(revision::Walk { repo, inner, boundary })
+    }
+    /// Return an iterator to traverse all commits reachable as configured by the [Platform].
+    ///
+    /// # Performance
+    ///
+    /// It's highly recommended to set an [`object cache`](Repository::object_cache_size()) on the parent repo
+    /// to greatly speed up performance if the returned id is supposed to be looked up right after.
+    pub fn all(self) -> Result<revision::Walk<'repo>, Error> {
+        self.selected(|_| true)
+    }
+}
+
+///
+pub mod iter {
+    /// The error returned by the [Walk](crate::revision::Walk) iterator.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        SimpleTraversal(#[from] gix_traverse::commit::simple::Error),
+        #[error(transparent)]
+        TopoTraversal(#[from] gix_traverse::commit::topo::Error),
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        CommitTree(#[from] crate::object::commit::Error),
+        #[cfg(feature = "blob-diff")]
+        #[error(transparent)]
+        Rewrites(#[from] crate::diff::new_rewrites::Error),
+        #[cfg(feature = "blob-diff")]
+        #[error(transparent)]
+        DiffTrees(#[from] crate::object::tree::diff::for_each::Error),
+        #[error("The ancestry path can only be determined if at least one commit is pruned")]
+        AncestryPathWithoutBottom,
+    }
+}
+
+pub(crate) mod iter_impl {
+    use std::{cell::RefCell, rc::Rc};
+
+    use gix_hash::ObjectId;
+    use gix_hashtable::HashSet;
+
+    /// The iterator returned by [`crate::revision::walk::Platform::all()`].
+    pub struct Walk<'repo> {
+        pub(crate) repo: &'repo crate::Repository,
+        pub(crate) inner: Box<dyn Iterator<Item = Result<gix_traverse::commit::Info, super::iter::Error>> + 'repo>,
+        pub(crate) boundary: Option<Boundary>,
+    }
+
+    /// Keeps track of the parents of returned commits to produce the boundary commits once the traversal is done.
+    pub(crate) struct Boundary {
+        /// All commits produced by the traversal, before they were possibly filtered.
+        traversed: Rc<RefCell<HashSet<ObjectId>>>,
+        /// The parents of all returned commits, in order.
+        parents: Vec<ObjectId>,
+        /// The boundary commits left to return, once computed.
+        commits: Option<std::vec::IntoIter<gix_traverse::commit::Info>>,
+    }
+
+    impl Boundary {
+        pub(crate) fn new(traversed: Rc<RefCell<HashSet<ObjectId>>>) -> Self {
+            Boundary {
+                traversed,
+                parents: Vec::new(),
+                commits: None,
+            }
+        }
+
+        fn compute(&mut self, repo: &crate::Repository) -> Result<(), super::iter::Error> {
+            let traversed = self.traversed.borrow();
+            let mut seen = HashSet::default();
+            let mut commits = Vec::new();
+            for id in self.parents.drain(..) {
+                if traversed.contains(&id) || !seen.insert(id) {
+                    continue;
+                }
+                let commit = repo.find_commit(id)?;
+                commits.push(gix_traverse::commit::Info {
+                    id,
+                    parent_ids: commit.parent_ids().map(crate::Id::detach).collect(),
+                    commit_time: Some(commit.time()?.seconds),
+                });
+            }
+            commits.sort_by_key(|info| std::cmp::Reverse(info.commit_time));
+            self.commits = Some(commits.into_iter());
+            Ok(())
+        }
+    }
+
+    impl<'repo> Iterator for Walk<'repo> {
+        type Item = Result<super::Info<'repo>, super::iter::Error>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            let Some(boundary) = self.boundary.as_mut() else {
+                return self
+                    .inner
+                    .next()
+                    .map(|res| res.map(|info| super::Info::new(info, self.repo)));
+            };
+            if boundary.commits.is_none() {
+                match self.inner.next() {
+                    Some(Ok(info)) => {
+                        boundary.parents.extend(info.parent_ids.iter().copied());
+                        return Some(Ok(super::Info::new(info, self.repo)));
+                    }
+                    Some(Err(err)) => return Some(Err(err)),
+                    None => {
+                        if let Err(err) = boundary.compute(self.repo) {
+                            return Some(Err(err));
+                        }
+                    }
+                }
+            }
+            let info = boundary.commits.as_mut()?.next()?;
+            Some(Ok(super::Info {
+                boundary: true,
+                ..super::Info::new(info, self.repo)
+            }))
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use crate::bstr::{BStr, BString};
+use crate::status::{index_worktree, Platform};
+use crate::worktree::IndexPersistedOrInMemory;
+
+/// An item of the [`Iter`], providing either a change between `HEAD` and the index, or between the index and the worktree.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Item {
+    /// A change between the tree of `HEAD` and the index, i.e. a staged change.
+    TreeIndex(gix_status::tree_index::Change<'static>),
+    /// A change between the index and the worktree, i.e. an unstaged change or an untracked file.
+    IndexWorktree(index_worktree::iter::Item),
+}
+
+impl Item {
+    /// The repository-relative path of the entry contained in this item.
+    pub fn location(&self) -> &BStr {
+        match self {
+            Item::TreeIndex(change) => change.location(),
+            Item::IndexWorktree(item) => item.rela_path(),
+        }
+    }
+}
+
+/// An iterator over all changes between `HEAD`, the index and the worktree, akin to `git status`.
+///
+/// All staged changes, i.e. those between `HEAD` and the index, are computed ahead of time and are returned first,
+/// sorted by path. These are followed by the changes between the index and the worktree,
+/// which are produced by an [index-worktree iterator](index_worktree::Iter).
+pub struct Iter {
+    tree_index: std::vec::IntoIter<gix_status::tree_index::Change<'static>>,
+    tree_index_outcome: gix_status::tree_index::Outcome,
+    index_worktree: index_worktree::Iter,
+}
+
+/// The error returned by [Platform::into_iter()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Index(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    HeadTreeId(#[from] crate::reference::head_tree_id::Error),
+    #[error(transparent)]
+    TreeIndexStatus(#[from] crate::status::tree_index::Error),
+    #[error(transparent)]
+    IndexWorktree(#[from] index_worktree::iter::Error),
+}
+
+/// Lifecycle
+impl<'repo, Progress> Platform<'repo, Progress>
+where
+    Progress: gix_features::progress::Progress,
+{
+    /// Turn the platform into an iterator for all changes between `HEAD`, the index and the working tree.
+    ///
+    /// * `patterns`
+    ///     - Optional patterns to use to limit the paths to look at. If empty, all paths are considered.
+    #[doc(alias = "diff_tree_to_index", alias = "git2")]
+    pub fn into_iter(mut self, patterns: impl IntoIterator<Item = BString>) -> Result<Iter, Error> {
+        let index = match self.index.take() {
+            None => IndexPersistedOrInMemory::Persisted(self.repo.index_or_empty()?),
+            Some(index) => index,
+        };
+        let head_tree = match self.head_tree {
+            Some(id) => id,
+            None => crate::status::head_tree_id_or_empty_tree(self.repo)?,
+        };
+        let patterns: Vec<_> = patterns.into_iter().collect();
+
+        let mut changes = Vec::new();
+        let should_interrupt = self.should_interrupt.clone().unwrap_or_default();
+        let tree_index_outcome = self.repo.tree_index_status(
+            &head_tree,
+            &index,
+            &patterns,
+            self.tree_index_rewrites,
+            |change| {
+                changes.push(change.into_owned());
+                gix_diff::tree::visit::Action::Continue
+            },
+            &should_interrupt,
+        )?;
+
+        self.index = Some(index);
+        Ok(Iter {
+            tree_index: changes.into_iter(),
+            tree_index_outcome,
+            index_worktree: self.into_index_worktree_iter(patterns)?,
+        })
+    }
+}
+
+impl Iterator for Iter {
+    type Item = Result<Item, index_worktree::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.tree_index.next() {
+            Some(change) => Some(Ok(Item::TreeIndex(change))),
+            None => self.index_worktree.next().map(|res| res.map(Item::IndexWorktree)),
+        }
+    }
+}
+
+/// Access
+impl Iter {
+    /// Return the outcome of the comparison between `HEAD` and the index.
+    pub fn tree_index_outcome(&self) -> &gix_status::tree_index::Outcome {
+        &self.tree_index_outcome
+    }
+
+    /// Return the outcome of the comparison between the index and the worktree,
+    /// or `None` if the iterator isn't fully consumed.
+    pub fn index_worktree_outcome_mut(&mut self) -> Option<&mut index_worktree::iter::Outcome> {
+        self.index_worktree.outcome_mut()
+    }
+
+    /// Turn the iterator into the outcome of the comparison between the index and the worktree,
+    /// which is `None` on error or if the iteration isn't complete.
+    pub fn into_index_worktree_outcome(self) -> Option<index_worktree::iter::Outcome> {
+        self.index_worktree.into_outcome()
+    }
+}
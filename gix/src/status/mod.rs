@@ -13,6 +13,8 @@ where
     index: Option<crate::worktree::IndexPersistedOrInMemory>,
     submodules: Submodule,
     index_worktree_options: index_worktree::Options,
+    head_tree: Option<gix_hash::ObjectId>,
+    tree_index_rewrites: Option<gix_diff::Rewrites>,
     should_interrupt: Option<OwnedOrStaticAtomicBool>,
 }
 
@@ -69,6 +71,8 @@ pub enum Error {
     DirwalkOptions(#[from] config::boolean::Error),
     #[error(transparent)]
     ConfigureUntrackedFiles(#[from] config::key::GenericErrorWithValue),
+    #[error(transparent)]
+    TreeIndexRewrites(#[from] crate::diff::new_rewrites::Error),
 }
 
 /// Status
@@ -83,7 +87,8 @@ impl Repository {
     /// which implies that submodule information is provided by default.
     ///
     /// Note that `status.showUntrackedFiles` is respected, which leads to untracked files being
-    /// collapsed by default. If that needs to be controlled,
+    /// collapsed by default. Rename tracking between `HEAD` and the index is configured by `diff.renames`,
+    /// and enabled if it is not set, just like `git status` does. If that needs to be controlled,
     /// [configure the directory walk explicitly](Platform::dirwalk_options) or more [implicitly](Platform::untracked_files).
     ///
     /// Pass `progress` to receive progress information on file modifications on this repository.
@@ -110,6 +115,8 @@ impl Repository {
                 rewrites: None,
                 thread_limit: None,
             },
+            head_tree: None,
+            tree_index_rewrites: self.config.diff_renames()?.unwrap_or_default().into(),
         };
 
         let untracked = self
@@ -139,23 +146,40 @@ pub mod is_dirty {
         StatusPlatform(#[from] crate::status::Error),
         #[error(transparent)]
         CreateStatusIterator(#[from] crate::status::index_worktree::iter::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        HeadTreeId(#[from] crate::reference::head_tree_id::Error),
+        #[error(transparent)]
+        TreeIndexStatus(#[from] crate::status::tree_index::Error),
     }
 
     impl Repository {
         /// Returns `true` if the repository is dirty.
         /// This means it's changed in one of the following ways:
         ///
-        /// * the index was changed in comparison to its working tree
+        /// * the index was changed in comparison to the tree of `HEAD`
         /// * the working tree was changed in comparison to the index
         /// * submodules are taken in consideration, along with their `ignore` and `isActive` configuration
         ///
         /// Note that *untracked files* do *not* affect this flag.
-        ///
-        /// ### Incomplete Implementation Warning
-        ///
-        /// Currently, this does not compute changes between the head and the index.
-        // TODO: use iterator which also tests for head->index changes.
         pub fn is_dirty(&self) -> Result<bool, Error> {
+            let mut has_staged_changes = false;
+            self.tree_index_status(
+                &crate::status::head_tree_id_or_empty_tree(self)?,
+                &**self.index_or_empty()?,
+                None::<&str>,
+                None,
+                |_change| {
+                    has_staged_changes = true;
+                    gix_diff::tree::visit::Action::Cancel
+                },
+                &Default::default(),
+            )?;
+            if has_staged_changes {
+                return Ok(true);
+            }
+
             let is_dirty = self
                 .status(gix_features::progress::Discard)?
                 .index_worktree_rewrites(None)
@@ -172,7 +196,30 @@ pub mod is_dirty {
     }
 }
 
+/// Return the tree of `HEAD`, or the empty tree if `HEAD` is unborn.
+fn head_tree_id_or_empty_tree(repo: &Repository) -> Result<gix_hash::ObjectId, crate::reference::head_tree_id::Error> {
+    if repo
+        .head()
+        .map_err(crate::reference::head_commit::Error::from)?
+        .is_unborn()
+    {
+        Ok(gix_hash::ObjectId::empty_tree(repo.object_hash()))
+    } else {
+        Ok(repo.head_tree_id()?.detach())
+    }
+}
+
 mod platform;
 
 ///
 pub mod index_worktree;
+
+///
+pub mod tree_index;
+
+///
+pub mod iter;
+pub use iter::{Item, Iter};
+
+///
+pub mod porcelain_v2;
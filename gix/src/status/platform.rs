@@ -100,6 +100,22 @@ where
         self
     }
 
+    /// Set the tree to compare the index with to `id`, which defaults to the tree of `HEAD`,
+    /// or the empty tree if `HEAD` is unborn.
+    pub fn head_tree(mut self, id: impl Into<gix_hash::ObjectId>) -> Self {
+        self.head_tree = Some(id.into());
+        self
+    }
+
+    /// Configure the rename tracking between the tree of `HEAD` and the index with `rewrites`, which
+    /// defaults to what's configured in `diff.renames`, or to rename tracking if it's not configured.
+    ///
+    /// Use `None` to disable rename tracking, which allows to obtain staged changes without reading any blobs.
+    pub fn tree_index_track_renames(mut self, rewrites: impl Into<Option<gix_diff::Rewrites>>) -> Self {
+        self.tree_index_rewrites = rewrites.into();
+        self
+    }
+
     /// Adjust all options related to the index-worktree status.
     /// This is a catch-all in case there are no more specific methods that could be used instead to change
     /// the respective option.
@@ -0,0 +1,581 @@
+use crate::bstr::{BStr, BString, ByteSlice, ByteVec};
+use crate::status::{index_worktree, Item, Platform};
+use gix_index::entry::Mode;
+use gix_status::index_as_worktree::{Change as WorktreeChange, Conflict, EntryStatus};
+use gix_status::tree_index::Change as IndexChange;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+/// The status of a path on one side of the comparison, as shown in the `XY` field.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Code {
+    /// The path didn't change, shown as `.`.
+    Unmodified,
+    /// The content or the executable bit changed, shown as `M`.
+    Modified,
+    /// The kind of the entry changed, like from file to symlink, shown as `T`.
+    TypeChange,
+    /// The path was added, shown as `A`.
+    Added,
+    /// The path was deleted, shown as `D`.
+    Deleted,
+    /// The path is the destination of a rename, shown as `R`.
+    Renamed,
+    /// The path is the destination of a copy, shown as `C`.
+    Copied,
+    /// The path has conflicts that have to be resolved, shown as `U`.
+    Unmerged,
+}
+
+impl Code {
+    /// Return the character `git` uses to display this code.
+    pub fn as_char(&self) -> char {
+        match self {
+            Code::Unmodified => '.',
+            Code::Modified => 'M',
+            Code::TypeChange => 'T',
+            Code::Added => 'A',
+            Code::Deleted => 'D',
+            Code::Renamed => 'R',
+            Code::Copied => 'C',
+            Code::Unmerged => 'U',
+        }
+    }
+}
+
+/// The state of a submodule, shown as `S<c><m><u>` in the `<sub>` field.
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Submodule {
+    /// The commit checked out in the submodule differs from the one recorded in the index.
+    pub commit_changed: bool,
+    /// The submodule worktree has changes to tracked files.
+    pub has_tracked_changes: bool,
+    /// The submodule worktree has untracked files.
+    pub has_untracked_changes: bool,
+}
+
+/// A path that changed between `HEAD` and the index, or between the index and the worktree, or both.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Change {
+    /// The status of the path in the index compared to `HEAD`.
+    pub index_status: Code,
+    /// The status of the path in the worktree compared to the index.
+    pub worktree_status: Code,
+    /// `Some(_)` if the path is a submodule.
+    pub submodule: Option<Submodule>,
+    /// The mode of the path in `HEAD`, or an empty mode if it doesn't exist there.
+    pub head_mode: Mode,
+    /// The mode of the path in the index, or an empty mode if it doesn't exist there.
+    pub index_mode: Mode,
+    /// The mode of the path in the worktree, or an empty mode if it doesn't exist there.
+    pub worktree_mode: Mode,
+    /// The id of the path in `HEAD`, or the null id if it doesn't exist there.
+    pub head_id: gix_hash::ObjectId,
+    /// The id of the path in the index, or the null id if it doesn't exist there.
+    pub index_id: gix_hash::ObjectId,
+    /// The repository-relative path.
+    pub path: BString,
+}
+
+/// An entry of `git status --porcelain=v2`, which combines all information known about a path.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Entry {
+    /// An ordinary changed entry, shown as `1 <XY> <sub> <mH> <mI> <mW> <hH> <hI> <path>`.
+    Changed(Change),
+    /// A renamed or copied entry, shown as `2 <XY> <sub> <mH> <mI> <mW> <hH> <hI> <X><score> <path><sep><origPath>`.
+    ///
+    /// The fields of `change` that refer to `HEAD` refer to the source of the rename or copy.
+    RenamedOrCopied {
+        /// Information about the destination of the rename or copy.
+        change: Change,
+        /// The similarity between source and destination in percent.
+        score: u8,
+        /// The repository-relative path of the source of the rename or copy.
+        source_path: BString,
+    },
+    /// An unmerged entry, shown as `u <XY> <sub> <m1> <m2> <m3> <mW> <h1> <h2> <h3> <path>`.
+    Unmerged {
+        /// The status of the path in the index compared to `HEAD`, which tells if *we* deleted, added or modified the path.
+        index_status: Code,
+        /// The status of the path in the worktree compared to the index, which tells if *they* deleted, added or modified the path.
+        worktree_status: Code,
+        /// `Some(_)` if the path is a submodule.
+        submodule: Option<Submodule>,
+        /// The modes of the path in stage 1, 2 and 3, which are empty if the path isn't present in the respective stage.
+        stage_modes: [Mode; 3],
+        /// The mode of the path in the worktree, or an empty mode if it doesn't exist there.
+        worktree_mode: Mode,
+        /// The ids of the path in stage 1, 2 and 3, which are null if the path isn't present in the respective stage.
+        stage_ids: [gix_hash::ObjectId; 3],
+        /// The repository-relative path.
+        path: BString,
+    },
+    /// An untracked path, shown as `? <path>`. Directories end with a slash.
+    Untracked {
+        /// The repository-relative path.
+        path: BString,
+    },
+    /// An ignored path, shown as `! <path>`. Directories end with a slash.
+    Ignored {
+        /// The repository-relative path.
+        path: BString,
+    },
+}
+
+/// Access
+impl Entry {
+    /// Return the repository-relative path of this entry.
+    pub fn path(&self) -> &BStr {
+        match self {
+            Entry::Changed(Change { path, .. })
+            | Entry::RenamedOrCopied {
+                change: Change { path, .. },
+                ..
+            }
+            | Entry::Unmerged { path, .. }
+            | Entry::Untracked { path }
+            | Entry::Ignored { path } => path.as_ref(),
+        }
+    }
+
+    /// Write this entry just like `git status --porcelain=v2` would to `out`.
+    ///
+    /// If `nul_terminated` is `true`, paths are not quoted and entries as well as paths of renames are separated by NUL bytes,
+    /// like `git status --porcelain=v2 -z` would do. Otherwise, unusual paths are quoted like `core.quotePath` does by default.
+    pub fn write_to(&self, out: &mut dyn std::io::Write, nul_terminated: bool) -> std::io::Result<()> {
+        fn path(path: &BString, nul_terminated: bool) -> Cow<'_, BStr> {
+            if nul_terminated {
+                Cow::Borrowed(path.as_bstr())
+            } else {
+                quote_path(path.as_ref())
+            }
+        }
+        match self {
+            Entry::Changed(change) => {
+                write!(out, "1 ")?;
+                write_change(out, change)?;
+                out.write_all(&path(&change.path, nul_terminated))?;
+            }
+            Entry::RenamedOrCopied {
+                change,
+                score,
+                source_path,
+            } => {
+                write!(out, "2 ")?;
+                write_change(out, change)?;
+                let rewrite = if change.index_status == Code::Unmodified {
+                    change.worktree_status
+                } else {
+                    change.index_status
+                };
+                write!(out, "{}{score} ", rewrite.as_char())?;
+                out.write_all(&path(&change.path, nul_terminated))?;
+                out.write_all(if nul_terminated { b"\0" } else { b"\t" })?;
+                out.write_all(&path(source_path, nul_terminated))?;
+            }
+            Entry::Unmerged {
+                index_status,
+                worktree_status,
+                submodule,
+                stage_modes,
+                worktree_mode,
+                stage_ids,
+                path: rela_path,
+            } => {
+                write!(
+                    out,
+                    "u {}{} {} {:06o} {:06o} {:06o} {:06o} {} {} {} ",
+                    index_status.as_char(),
+                    worktree_status.as_char(),
+                    submodule_field(submodule.as_ref()),
+                    stage_modes[0].bits(),
+                    stage_modes[1].bits(),
+                    stage_modes[2].bits(),
+                    worktree_mode.bits(),
+                    stage_ids[0],
+                    stage_ids[1],
+                    stage_ids[2],
+                )?;
+                out.write_all(&path(rela_path, nul_terminated))?;
+            }
+            Entry::Untracked { path: rela_path } => {
+                write!(out, "? ")?;
+                out.write_all(&path(rela_path, nul_terminated))?;
+            }
+            Entry::Ignored { path: rela_path } => {
+                write!(out, "! ")?;
+                out.write_all(&path(rela_path, nul_terminated))?;
+            }
+        }
+        out.write_all(if nul_terminated { b"\0" } else { b"\n" })
+    }
+}
+
+fn write_change(out: &mut dyn std::io::Write, change: &Change) -> std::io::Result<()> {
+    write!(
+        out,
+        "{}{} {} {:06o} {:06o} {:06o} {} {} ",
+        change.index_status.as_char(),
+        change.worktree_status.as_char(),
+        submodule_field(change.submodule.as_ref()),
+        change.head_mode.bits(),
+        change.index_mode.bits(),
+        change.worktree_mode.bits(),
+        change.head_id,
+        change.index_id,
+    )
+}
+
+fn submodule_field(submodule: Option<&Submodule>) -> String {
+    match submodule {
+        None => "N...".into(),
+        Some(sm) => {
+            let flag = |set: bool, c: char| if set { c } else { '.' };
+            format!(
+                "S{}{}{}",
+                flag(sm.commit_changed, 'C'),
+                flag(sm.has_tracked_changes, 'M'),
+                flag(sm.has_untracked_changes, 'U')
+            )
+        }
+    }
+}
+
+/// Quote `path` if it contains control characters, double quotes, backslashes or bytes above `0x7f`, just like `git` does.
+fn quote_path(path: &BStr) -> Cow<'_, BStr> {
+    let needs_quoting = |b: u8| b < 0x20 || b == b'"' || b == b'\\' || b >= 0x7f;
+    if !path.iter().any(|b| needs_quoting(*b)) {
+        return Cow::Borrowed(path);
+    }
+    let mut out = BString::from("\"");
+    for &b in path.iter() {
+        match b {
+            b'\x07' => out.push_str(br"\a"),
+            b'\x08' => out.push_str(br"\b"),
+            b'\t' => out.push_str(br"\t"),
+            b'\n' => out.push_str(br"\n"),
+            b'\x0b' => out.push_str(br"\v"),
+            b'\x0c' => out.push_str(br"\f"),
+            b'\r' => out.push_str(br"\r"),
+            b'"' => out.push_str(br#"\""#),
+            b'\\' => out.push_str(br"\\"),
+            b if needs_quoting(b) => out.push_str(format!("\\{b:03o}")),
+            b => out.push_byte(b),
+        }
+    }
+    out.push_byte(b'"');
+    Cow::Owned(out)
+}
+
+/// The error returned by [Platform::into_porcelain_v2()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Iter(#[from] crate::status::iter::Error),
+    #[error(transparent)]
+    IndexWorktree(#[from] index_worktree::Error),
+}
+
+/// Lifecycle
+impl<'repo, Progress> Platform<'repo, Progress>
+where
+    Progress: gix_features::progress::Progress,
+{
+    /// Compute the complete status, with one [`Entry`] for each changed, unmerged, untracked or ignored path,
+    /// just like `git status --porcelain=v2` would.
+    ///
+    /// * `patterns`
+    ///     - Optional patterns to use to limit the paths to look at. If empty, all paths are considered.
+    ///
+    /// Changed entries come first, followed by unmerged, untracked and then ignored entries, each sorted by path.
+    pub fn into_porcelain_v2(self, patterns: impl IntoIterator<Item = BString>) -> Result<Vec<Entry>, Error> {
+        let repo = self.repo;
+        let null = gix_hash::ObjectId::null(repo.object_hash());
+        let mut iter = self.into_iter(patterns)?;
+        let mut tracked = BTreeMap::<BString, PathState>::new();
+        let (mut untracked, mut ignored) = (Vec::new(), Vec::new());
+        for item in iter.by_ref() {
+            match item? {
+                Item::TreeIndex(change) => {
+                    let state = tracked.entry(change.location().to_owned()).or_default();
+                    match change {
+                        IndexChange::Addition { entry_mode, id, .. } => {
+                            state.head = Some((Mode::empty(), null));
+                            state.index = Some((entry_mode, id));
+                            state.index_status = Code::Added;
+                        }
+                        IndexChange::Deletion { entry_mode, id, .. } => {
+                            state.head = Some((entry_mode, id));
+                            state.index = Some((Mode::empty(), null));
+                            state.worktree_mode = Some(Mode::empty());
+                            state.index_status = Code::Deleted;
+                        }
+                        IndexChange::Modification {
+                            previous_entry_mode,
+                            previous_id,
+                            entry_mode,
+                            id,
+                            ..
+                        } => {
+                            state.head = Some((previous_entry_mode, previous_id));
+                            state.index = Some((entry_mode, id));
+                            state.index_status = if is_type_change(previous_entry_mode, entry_mode) {
+                                Code::TypeChange
+                            } else {
+                                Code::Modified
+                            };
+                        }
+                        IndexChange::Rewrite {
+                            source_location,
+                            source_entry_mode,
+                            source_id,
+                            entry_mode,
+                            id,
+                            diff,
+                            copy,
+                            ..
+                        } => {
+                            state.head = Some((source_entry_mode, source_id));
+                            state.index = Some((entry_mode, id));
+                            state.index_status = if copy { Code::Copied } else { Code::Renamed };
+                            state.rewrite = Some((source_location.into_owned(), score(diff)));
+                        }
+                    }
+                }
+                Item::IndexWorktree(index_worktree::iter::Item::Modification {
+                    entry,
+                    rela_path,
+                    status,
+                    ..
+                }) => {
+                    let state = tracked.entry(rela_path.clone()).or_default();
+                    state.index.get_or_insert((entry.mode, entry.id));
+                    match status {
+                        EntryStatus::Conflict(conflict) => state.conflict = Some(conflict),
+                        EntryStatus::IntentToAdd => {
+                            state.head = Some((Mode::empty(), null));
+                            state.index = Some((Mode::empty(), null));
+                            state.worktree_mode = Some(entry.mode);
+                            state.worktree_status = Code::Added;
+                        }
+                        EntryStatus::NeedsUpdate(_) => {}
+                        EntryStatus::Change(change) => match change {
+                            WorktreeChange::Removed => {
+                                state.worktree_mode = Some(Mode::empty());
+                                state.worktree_status = Code::Deleted;
+                            }
+                            WorktreeChange::Type => {
+                                state.worktree_mode = Some(worktree_mode(repo, rela_path.as_ref()));
+                                state.worktree_status = Code::TypeChange;
+                            }
+                            WorktreeChange::Modification {
+                                executable_bit_changed, ..
+                            } => {
+                                state.worktree_mode = Some(if !executable_bit_changed {
+                                    entry.mode
+                                } else if entry.mode == Mode::FILE {
+                                    Mode::FILE_EXECUTABLE
+                                } else {
+                                    Mode::FILE
+                                });
+                                state.worktree_status = Code::Modified;
+                            }
+                            WorktreeChange::SubmoduleModification(status) => {
+                                state.submodule = Some(Submodule {
+                                    commit_changed: status.checked_out_head_id.is_some()
+                                        && status.checked_out_head_id != status.index_id,
+                                    has_tracked_changes: status.changes.as_ref().map_or(false, |changes| {
+                                        changes.iter().any(|change| {
+                                            !matches!(change, index_worktree::iter::Item::DirectoryContents { .. })
+                                        })
+                                    }),
+                                    has_untracked_changes: status.changes.as_ref().map_or(false, |changes| {
+                                        changes.iter().any(|change| {
+                                            matches!(change, index_worktree::iter::Item::DirectoryContents { .. })
+                                        })
+                                    }),
+                                });
+                                state.worktree_status = Code::Modified;
+                            }
+                        },
+                    }
+                }
+                Item::IndexWorktree(index_worktree::iter::Item::DirectoryContents { entry, .. }) => {
+                    let mut path = entry.rela_path;
+                    if entry.disk_kind == Some(gix_dir::entry::Kind::Directory) {
+                        path.push_byte(b'/');
+                    }
+                    match entry.status {
+                        gix_dir::entry::Status::Untracked => untracked.push(Entry::Untracked { path }),
+                        gix_dir::entry::Status::Ignored(_) => ignored.push(Entry::Ignored { path }),
+                        gix_dir::entry::Status::Tracked | gix_dir::entry::Status::Pruned => {}
+                    }
+                }
+                Item::IndexWorktree(index_worktree::iter::Item::Rewrite {
+                    source,
+                    dirwalk_entry,
+                    diff,
+                    copy,
+                    ..
+                }) => match source {
+                    index_worktree::iter::RewriteSource::RewriteFromIndex {
+                        source_entry,
+                        source_rela_path,
+                        ..
+                    } => {
+                        let state = tracked.entry(dirwalk_entry.rela_path).or_default();
+                        state.head = Some((source_entry.mode, source_entry.id));
+                        state.index = Some((source_entry.mode, source_entry.id));
+                        state.worktree_mode = Some(source_entry.mode);
+                        state.worktree_status = if copy { Code::Copied } else { Code::Renamed };
+                        state.rewrite = Some((source_rela_path, score(diff)));
+                    }
+                    index_worktree::iter::RewriteSource::CopyFromDirectoryEntry { .. } => {
+                        untracked.push(Entry::Untracked {
+                            path: dirwalk_entry.rela_path,
+                        });
+                    }
+                },
+            }
+        }
+
+        let index = iter.into_index_worktree_outcome().map(|out| out.index);
+        let mut out: Vec<_> = tracked
+            .into_iter()
+            .map(|(path, state)| {
+                if let Some(conflict) = state.conflict {
+                    let (index_status, worktree_status) = conflict_codes(conflict);
+                    let (mut stage_modes, mut stage_ids) = ([Mode::empty(); 3], [null; 3]);
+                    if let Some(index) = index.as_ref() {
+                        for entry in index
+                            .entry_range(path.as_ref())
+                            .map(|range| &index.entries()[range])
+                            .unwrap_or_default()
+                        {
+                            if let Some(stage) = (entry.stage() as usize).checked_sub(1).filter(|s| *s < 3) {
+                                stage_modes[stage] = entry.mode;
+                                stage_ids[stage] = entry.id;
+                            }
+                        }
+                    }
+                    let worktree_mode = worktree_mode(repo, path.as_ref());
+                    return Entry::Unmerged {
+                        index_status,
+                        worktree_status,
+                        submodule: stage_modes
+                            .iter()
+                            .any(gix_index::entry::Mode::is_submodule)
+                            .then(Submodule::default),
+                        stage_modes,
+                        worktree_mode,
+                        stage_ids,
+                        path,
+                    };
+                }
+
+                let (index_mode, index_id) = state.index.unwrap_or((Mode::empty(), null));
+                let (head_mode, head_id) = state.head.unwrap_or((index_mode, index_id));
+                let change = Change {
+                    index_status: state.index_status,
+                    worktree_status: state.worktree_status,
+                    submodule: state
+                        .submodule
+                        .or_else(|| (index_mode.is_submodule() || head_mode.is_submodule()).then(Submodule::default)),
+                    head_mode,
+                    index_mode,
+                    worktree_mode: state.worktree_mode.unwrap_or(index_mode),
+                    head_id,
+                    index_id,
+                    path,
+                };
+                match state.rewrite {
+                    Some((source_path, score)) => Entry::RenamedOrCopied {
+                        change,
+                        score,
+                        source_path,
+                    },
+                    None => Entry::Changed(change),
+                }
+            })
+            .collect();
+        out.sort_by_key(|entry| matches!(entry, Entry::Unmerged { .. }));
+        untracked.sort_by(|a: &Entry, b| a.path().cmp(b.path()));
+        ignored.sort_by(|a: &Entry, b| a.path().cmp(b.path()));
+        out.extend(untracked);
+        out.extend(ignored);
+        Ok(out)
+    }
+}
+
+/// Everything we know about a tracked path.
+struct PathState {
+    head: Option<(Mode, gix_hash::ObjectId)>,
+    index: Option<(Mode, gix_hash::ObjectId)>,
+    index_status: Code,
+    worktree_status: Code,
+    worktree_mode: Option<Mode>,
+    rewrite: Option<(BString, u8)>,
+    submodule: Option<Submodule>,
+    conflict: Option<Conflict>,
+}
+
+impl Default for PathState {
+    fn default() -> Self {
+        PathState {
+            head: None,
+            index: None,
+            index_status: Code::Unmodified,
+            worktree_status: Code::Unmodified,
+            worktree_mode: None,
+            rewrite: None,
+            submodule: None,
+            conflict: None,
+        }
+    }
+}
+
+/// Obtain the mode of the file at `rela_path` in the worktree of `repo`, or an empty mode if it doesn't exist.
+fn worktree_mode(repo: &crate::Repository, rela_path: &BStr) -> Mode {
+    repo.work_dir()
+        .and_then(|root| gix_path::try_from_bstr(rela_path).ok().map(|path| root.join(path)))
+        .and_then(|path| std::fs::symlink_metadata(path).ok())
+        .map_or(Mode::empty(), |md| {
+            if md.is_symlink() {
+                Mode::SYMLINK
+            } else if md.is_dir() {
+                Mode::COMMIT
+            } else if gix_fs::is_executable(&md) {
+                Mode::FILE_EXECUTABLE
+            } else {
+                Mode::FILE
+            }
+        })
+}
+
+fn score(diff: Option<gix_diff::blob::DiffLineStats>) -> u8 {
+    diff.map_or(100, |diff| (diff.similarity * 100.0).round() as u8)
+}
+
+fn is_type_change(lhs: Mode, rhs: Mode) -> bool {
+    let kind = |mode: Mode| {
+        if mode == Mode::FILE_EXECUTABLE {
+            Mode::FILE
+        } else {
+            mode
+        }
+    };
+    kind(lhs) != kind(rhs)
+}
+
+fn conflict_codes(conflict: Conflict) -> (Code, Code) {
+    match conflict {
+        Conflict::BothDeleted => (Code::Deleted, Code::Deleted),
+        Conflict::AddedByUs => (Code::Added, Code::Unmerged),
+        Conflict::DeletedByThem => (Code::Unmerged, Code::Deleted),
+        Conflict::AddedByThem => (Code::Unmerged, Code::Added),
+        Conflict::DeletedByUs => (Code::Deleted, Code::Unmerged),
+        Conflict::BothAdded => (Code::Added, Code::Added),
+        Conflict::BothModified => (Code::Unmerged, Code::Unmerged),
+    }
+}
@@ -0,0 +1,88 @@
+use crate::bstr::BStr;
+use crate::Repository;
+use std::sync::atomic::AtomicBool;
+
+/// The error returned by [Repository::tree_index_status()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+    #[error(transparent)]
+    AttributeStack(#[from] crate::config::attribute_stack::Error),
+    #[error(transparent)]
+    Pathspec(#[from] crate::pathspec::init::Error),
+    #[error(transparent)]
+    ResourceCache(#[from] crate::diff::resource_cache::Error),
+    #[error(transparent)]
+    TreeIndex(#[from] gix_status::tree_index::Error),
+}
+
+impl Repository {
+    /// Obtain the changes between the tree with `tree_id`, typically the one of `HEAD`, and `worktree_index`,
+    /// just like `git diff --cached` would, and call `cb` for each of them.
+    ///
+    /// * `tree_id`
+    ///     - The tree to compare the index with. If it's the empty tree, all entries of `worktree_index` are additions.
+    /// * `worktree_index`
+    ///     - The index to compare to `tree_id`, which may be sparse.
+    /// * `patterns`
+    ///     - Optional patterns to use to limit the paths to look at. If empty, all paths are considered.
+    /// * `rewrites`
+    ///     - If `Some(_)`, additions and deletions will be matched up to find renames and copies.
+    /// * `cb`
+    ///     - Receives each change in order of its path, and may return [`Action::Cancel`](gix_diff::tree::visit::Action::Cancel)
+    ///       to stop the operation early.
+    /// * `should_interrupt`
+    ///     - A flag to stop the whole operation.
+    ///
+    /// ### Note
+    ///
+    /// This is a lower-level method, prefer the [`status`](Repository::status()) method for greater ease of use.
+    pub fn tree_index_status(
+        &self,
+        tree_id: &gix_hash::oid,
+        worktree_index: &gix_index::State,
+        patterns: impl IntoIterator<Item = impl AsRef<BStr>>,
+        rewrites: Option<gix_diff::Rewrites>,
+        cb: impl FnMut(gix_status::tree_index::Change<'_>) -> gix_diff::tree::visit::Action,
+        should_interrupt: &AtomicBool,
+    ) -> Result<gix_status::tree_index::Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::tree_index_status");
+        let tree_index = if tree_id == gix_hash::ObjectId::empty_tree(self.object_hash()) {
+            gix_index::State::new(self.object_hash())
+        } else {
+            self.index_from_tree(tree_id)?.into()
+        };
+        let attrs = self.attributes_only(
+            worktree_index,
+            crate::worktree::stack::state::attributes::Source::IdMapping,
+        )?;
+        let pathspec = crate::Pathspec::new(
+            self,
+            false,
+            patterns,
+            true, /* inherit ignore case */
+            || Ok(attrs.inner.clone()),
+        )?;
+        let resource_cache = crate::diff::resource_cache(
+            self,
+            gix_diff::blob::pipeline::Mode::ToGit,
+            attrs.detach(),
+            gix_diff::blob::pipeline::WorktreeRoots::default(),
+        )?;
+
+        Ok(gix_status::tree_index(
+            &tree_index,
+            worktree_index,
+            cb,
+            &self.objects,
+            gix_status::tree_index::Context {
+                pathspec: pathspec.search,
+                resource_cache,
+                should_interrupt,
+            },
+            gix_status::tree_index::Options { rewrites },
+        )?)
+    }
+}
@@ -84,6 +84,25 @@ mod blocking_io {
         Ok(())
     }
 
+    #[test]
+    fn shallow_clone_only_follows_tags_into_received_history() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let (repo, _out) = gix::prepare_clone_bare(remote::repo("base").path(), tmp.path())?
+            .with_shallow(Shallow::DepthAtRemote(1.try_into().expect("non-zero")))
+            .fetch_only(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+
+        assert_eq!(
+            repo.find_reference("refs/tags/b-tag")?.id().object()?.kind,
+            gix::object::Kind::Tag,
+            "the annotated tag points to the tip of a branch and is received"
+        );
+        assert!(
+            repo.try_find_reference("refs/tags/annotated-detached-tag")?.is_none(),
+            "this tag points to a commit that isn't part of the received history, and isn't fetched as it would be without shallow"
+        );
+        Ok(())
+    }
+
     #[test]
     fn from_shallow_prohibited_with_option() -> crate::Result {
         let tmp = gix_testtools::tempfile::TempDir::new()?;
@@ -117,8 +136,8 @@ mod blocking_io {
             vec![
                 hex_to_id("2d9d136fb0765f2e24c44a0f91984318d580d03b"),
                 hex_to_id("dfd0954dabef3b64f458321ef15571cc1a46d552"),
-                hex_to_id("dfd0954dabef3b64f458321ef15571cc1a46d552"),
-            ]
+            ],
+            "duplicate shallow commits sent by the remote are only written once"
         );
         assert_eq!(
             repo.config_snapshot().boolean("my.marker"),
@@ -44,6 +44,27 @@ mod describe {
             assert_eq!(actual, "main");
             Ok(())
         }
+
+        #[test]
+        fn dirty_suffix_can_be_configured_on_the_platform() -> crate::Result {
+            let repo = named_subrepo_opts(
+                "make_submodules.sh",
+                "submodule-head-changed",
+                gix::open::Options::isolated(),
+            )?;
+
+            let describe = || -> crate::Result<_> { Ok(repo.head_commit()?.describe().names(SelectRef::AllRefs)) };
+            assert_eq!(
+                describe()?.dirty_suffix("modified".to_owned()).format()?.to_string(),
+                "main-modified"
+            );
+            assert_eq!(
+                describe()?.broken_suffix("broken".to_owned()).format()?.to_string(),
+                "main-dirty",
+                "the broken suffix implies a dirty check with the default suffix"
+            );
+            Ok(())
+        }
     }
 
     #[test]
@@ -69,6 +90,89 @@ mod describe {
         Ok(())
     }
 
+    #[test]
+    fn names_can_be_matched_and_excluded_by_pattern() -> crate::Result {
+        let repo = named_repo("make_commit_describe_multiple_tags.sh")?;
+        let head = repo.head_commit()?;
+        assert_eq!(
+            head.describe().matching(["v2*"]).format()?.to_string(),
+            "v2",
+            "only annotated tags are considered by default"
+        );
+        assert_eq!(
+            head.describe().excluding(["v4", "v5"]).format()?.to_string(),
+            "v2",
+            "excluded names are skipped"
+        );
+        assert_eq!(
+            head.describe().names(AllTags).matching(["l*"]).format()?.to_string(),
+            format!("l0-2-g{}", head.id.to_hex_with_len(7))
+        );
+        assert_eq!(
+            head.describe().names(AllRefs).matching(["ma*"]).format()?.to_string(),
+            "main",
+            "with all refs, branches can be matched without their prefix"
+        );
+        assert_eq!(
+            head.describe().matching(["none"]).try_format()?,
+            None,
+            "nothing matches"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn contains_names_commits_relative_to_the_oldest_containing_tag() -> crate::Result {
+        let repo = named_repo("make_commit_describe_multiple_tags.sh")?;
+        let head = repo.head_commit()?;
+        let parent = repo.rev_parse_single("HEAD~1")?.object()?.into_commit();
+        let root = repo.rev_parse_single("HEAD~2")?.object()?.into_commit();
+        for filter in [AnnotatedTags, AllTags, AllRefs] {
+            assert_eq!(
+                head.describe().names(filter).contains(true).format()?.to_string(),
+                "v2^0",
+                "{filter:?}: annotated tags are marked as peeled"
+            );
+            assert_eq!(
+                parent.describe().names(filter).contains(true).format()?.to_string(),
+                "v1^0",
+                "{filter:?}"
+            );
+            assert_eq!(
+                root.describe().names(filter).contains(true).format()?.to_string(),
+                "v1~1",
+                "{filter:?}"
+            );
+        }
+
+        assert_eq!(
+            parent
+                .describe()
+                .contains(true)
+                .matching(["v*"])
+                .excluding(["v1"])
+                .format()?
+                .to_string(),
+            "v0",
+            "lightweight tags are always used"
+        );
+        assert_eq!(
+            root.describe()
+                .contains(true)
+                .matching(["v*"])
+                .excluding(["v1"])
+                .format()?
+                .to_string(),
+            "v0~1"
+        );
+        assert_eq!(
+            head.describe().contains(true).matching(["l*"]).try_format()?,
+            None,
+            "no tag contains the commit"
+        );
+        Ok(())
+    }
+
     #[test]
     fn lightweight_tags_are_sorted_lexicographically() -> crate::Result {
         let repo = named_repo("make_commit_describe_multiple_tags.sh")?;
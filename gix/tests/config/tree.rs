@@ -176,6 +176,58 @@ mod status {
     }
 }
 
+mod trailer {
+    use gix::config::tree::{Key, Trailer};
+    use gix_object::commit::message::trailer::{IfExists, IfMissing, Placement};
+
+    use crate::config::tree::bcow;
+
+    #[test]
+    fn where_() -> crate::Result {
+        for (actual, expected) in [
+            ("end", Placement::End),
+            ("Start", Placement::Start),
+            ("after", Placement::After),
+            ("BEFORE", Placement::Before),
+        ] {
+            assert_eq!(Trailer::WHERE.try_into_placement(bcow(actual))?, expected);
+            assert!(Trailer::TOKEN_WHERE.validate(actual.into()).is_ok());
+        }
+        assert_eq!(
+            Trailer::WHERE
+                .try_into_placement(bcow("middle"))
+                .unwrap_err()
+                .to_string(),
+            "The key \"trailer.where=middle\" was invalid"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn if_exists() -> crate::Result {
+        for (actual, expected) in [
+            ("addIfDifferentNeighbor", IfExists::AddIfDifferentNeighbor),
+            ("addIfDifferent", IfExists::AddIfDifferent),
+            ("add", IfExists::Add),
+            ("replace", IfExists::Replace),
+            ("donothing", IfExists::DoNothing),
+        ] {
+            assert_eq!(Trailer::IF_EXISTS.try_into_if_exists(bcow(actual))?, expected);
+        }
+        assert!(Trailer::TOKEN_IF_EXISTS.validate("keep".into()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn if_missing() -> crate::Result {
+        for (actual, expected) in [("add", IfMissing::Add), ("doNothing", IfMissing::DoNothing)] {
+            assert_eq!(Trailer::IF_MISSING.try_into_if_missing(bcow(actual))?, expected);
+        }
+        assert!(Trailer::IF_MISSING.validate("replace".into()).is_err());
+        Ok(())
+    }
+}
+
 mod push {
     use crate::config::tree::bcow;
     use gix::config::tree::Push;
@@ -203,6 +255,52 @@ mod push {
     }
 }
 
+#[cfg(feature = "pull")]
+mod pull {
+    use crate::config::tree::bcow;
+    use gix::config::tree::Pull;
+    use gix::pull;
+
+    #[test]
+    fn rebase() -> crate::Result {
+        for (actual, expected) in [
+            ("true", pull::Rebase::True),
+            ("false", pull::Rebase::False),
+            ("merges", pull::Rebase::Merges),
+            ("m", pull::Rebase::Merges),
+            ("interactive", pull::Rebase::Interactive),
+            ("i", pull::Rebase::Interactive),
+        ] {
+            assert_eq!(Pull::REBASE.try_into_rebase(bcow(actual))?, expected);
+        }
+
+        assert_eq!(
+            Pull::REBASE.try_into_rebase(bcow("preserve")).unwrap_err().to_string(),
+            "The key \"pull.rebase=preserve\" was invalid",
+            "'preserve' was removed from git"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ff() -> crate::Result {
+        for (actual, expected) in [
+            ("true", pull::FastForward::Allow),
+            ("false", pull::FastForward::Never),
+            ("only", pull::FastForward::Only),
+        ] {
+            assert_eq!(Pull::FF.try_into_fast_forward(bcow(actual))?, expected);
+        }
+
+        assert_eq!(
+            Pull::FF.try_into_fast_forward(bcow("Only")).unwrap_err().to_string(),
+            "The key \"pull.ff=Only\" was invalid",
+            "case-sensitive comparisons"
+        );
+        Ok(())
+    }
+}
+
 mod fetch {
 
     #[test]
@@ -270,6 +368,83 @@ mod fetch {
 }
 
 #[cfg(feature = "blob-diff")]
+mod gc {
+    use std::time::{Duration, SystemTime};
+
+    use gix::config::tree::{Gc, Key};
+
+    use crate::config::tree::bcow;
+
+    #[test]
+    fn expiry() -> crate::Result {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(100 * 24 * 60 * 60);
+        for never in ["never", "false"] {
+            assert_eq!(Gc::PRUNE_EXPIRE.try_into_expiry(bcow(never), now)?, None);
+        }
+        for all in ["now", "all"] {
+            assert_eq!(
+                Gc::PRUNE_EXPIRE.try_into_expiry(bcow(all), now)?,
+                Some(gix::date::Time::new(100 * 24 * 60 * 60, 0))
+            );
+        }
+        for two_weeks in ["2.weeks.ago", "2 weeks ago"] {
+            assert_eq!(
+                Gc::REFLOG_EXPIRE.try_into_expiry(bcow(two_weeks), now)?,
+                Some(gix::date::Time::new(86 * 24 * 60 * 60, 0))
+            );
+            assert!(Gc::REFLOG_EXPIRE.validate(two_weeks.into()).is_ok());
+        }
+        assert_eq!(
+            Gc::PRUNE_EXPIRE
+                .try_into_expiry(bcow("whenever"), now)
+                .unwrap_err()
+                .to_string(),
+            "The key \"gc.pruneExpire=whenever\" was invalid"
+        );
+        assert!(Gc::PRUNE_EXPIRE.validate("whenever".into()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn pattern_reflog_expiry() {
+        assert!(Gc::PATTERN_REFLOG_EXPIRE.full_name(None).is_err());
+        assert_eq!(
+            Gc::PATTERN_REFLOG_EXPIRE
+                .full_name(Some("refs/remotes/*".into()))
+                .expect("valid"),
+            "gc.refs/remotes/*.reflogExpire"
+        );
+        assert_eq!(
+            Gc::PATTERN_REFLOG_EXPIRE_UNREACHABLE
+                .full_name(Some("refs/stash".into()))
+                .expect("valid"),
+            "gc.refs/stash.reflogExpireUnreachable"
+        );
+        assert_eq!(
+            Gc::REFLOG_EXPIRE_UNREACHABLE.full_name(None).expect("valid"),
+            "gc.reflogExpireUnreachable"
+        );
+        assert!(Gc::PATTERN_REFLOG_EXPIRE_UNREACHABLE
+            .validate("30.days.ago".into())
+            .is_ok());
+    }
+
+    #[test]
+    fn pack_refs() -> crate::Result {
+        for (value, is_bare, expected) in [
+            ("true", true, true),
+            ("false", false, false),
+            ("notbare", false, true),
+            ("notbare", true, false),
+        ] {
+            assert_eq!(Gc::PACK_REFS.try_into_pack_refs(bcow(value), is_bare)?, expected);
+            assert!(Gc::PACK_REFS.validate(value.into()).is_ok());
+        }
+        assert!(Gc::PACK_REFS.validate("sometimes".into()).is_err());
+        Ok(())
+    }
+}
+
 mod diff {
     use gix::{
         config::tree::{Diff, Key},
@@ -830,6 +1005,40 @@ mod gitoxide {
             assert!(gitoxide::Allow::PROTOCOL_FROM_USER.validate("0".into()).is_err());
         }
     }
+    mod ssh {
+        use gix::config::tree::{gitoxide, Key};
+
+        #[test]
+        fn strict_host_key_checking() {
+            for valid in ["yes", "accept-new", "no", "off"] {
+                assert!(gitoxide::Ssh::STRICT_HOST_KEY_CHECKING.validate(valid.into()).is_ok());
+            }
+            assert!(
+                gitoxide::Ssh::STRICT_HOST_KEY_CHECKING.validate("ask".into()).is_err(),
+                "asking interactively isn't supported"
+            );
+        }
+
+        #[test]
+        #[cfg(all(feature = "native-ssh", feature = "blocking-network-client"))]
+        fn strict_host_key_checking_conversion() -> crate::Result {
+            use gix_protocol::transport::client::native_ssh::StrictHostKeyChecking;
+
+            use crate::config::tree::bcow;
+            for (actual, expected) in [
+                ("yes", StrictHostKeyChecking::Yes),
+                ("accept-new", StrictHostKeyChecking::AcceptNew),
+                ("no", StrictHostKeyChecking::No),
+                ("off", StrictHostKeyChecking::No),
+            ] {
+                assert_eq!(
+                    gitoxide::Ssh::STRICT_HOST_KEY_CHECKING.try_into_strict_host_key_checking(bcow(actual))?,
+                    expected
+                );
+            }
+            Ok(())
+        }
+    }
     mod commit {
         use gix::config::tree::{gitoxide, Key};
 
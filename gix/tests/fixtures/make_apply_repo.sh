@@ -0,0 +1,25 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+seq 1 20 > file
+echo unchanged > other
+git add . && git commit -qm base
+git branch base
+
+sed -i.bak 's/^3$/three/;s/^17$/seventeen/' file && rm file.bak
+echo added > new
+git add new && git commit -qam change
+git branch change
+git diff base change > ../patch
+mv ../patch .git/patch
+
+git checkout -q --detach base
+sed -i.bak 's/^5$/five/;s/^15$/fifteen/' file && rm file.bak
+git commit -qam drifted
+git branch drifted
+git apply --3way .git/patch
+git commit -qm drifted-applied
+git branch drifted-applied
+
+git checkout -q base
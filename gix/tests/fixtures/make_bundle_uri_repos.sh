@@ -0,0 +1,39 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q base
+mkdir bundles
+(cd base
+  git checkout -q -b main
+  echo a > a && git add a && git commit -q -m c1
+  git bundle create -q ../bundles/1.bundle main
+  c1=$(git rev-parse HEAD)
+  echo b > b && git add b && git commit -q -m c2
+  git bundle create -q ../bundles/2.bundle main "^$c1"
+  git bundle create -q ../bundles/all.bundle main
+  echo c > c && git add c && git commit -q -m c3
+  git update-server-info
+)
+
+cat <<EOT >bundles/list
+[bundle]
+	version = 1
+	mode = all
+	heuristic = creationToken
+[bundle "two"]
+	uri = 2.bundle
+	creationToken = 2
+[bundle "one"]
+	uri = /bundles/1.bundle
+	creationToken = 1
+EOT
+
+cat <<EOT >bundles/any-list
+[bundle]
+	version = 1
+	mode = any
+[bundle "missing"]
+	uri = missing.bundle
+[bundle "all"]
+	uri = all.bundle
+EOT
@@ -0,0 +1,45 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+seq 1 5 > file
+echo original > other
+git add . && git commit -qm base
+
+git branch release
+git branch release-conflicting
+
+sed -i.bak 's/^1$/fix/' file && rm file.bak
+git commit -qam "fix the first line" -m "Signed-off-by: Contributor <contributor@example.com>"
+git tag fix
+
+echo changed > other
+git commit -qam "change other"
+
+git checkout -q release
+sed -i.bak 's/^5$/release/' file && rm file.bak
+git commit -qam "release change"
+
+git checkout -q release-conflicting
+sed -i.bak 's/^1$/conflict/' file && rm file.bak
+git commit -qam "conflicting change"
+
+git checkout -qb expected-cherry-pick release
+git cherry-pick -x fix
+
+git checkout -qb expected-revert main
+git revert --no-edit fix
+
+git checkout -qb mixed-trailers fix
+echo third > third && git add third
+git commit -qm "add third" -m "$(printf 'Signed-off-by: Contributor <contributor@example.com>\nreviewed in person')"
+
+git checkout -qb expected-cherry-pick-mixed-trailers release
+git cherry-pick -x mixed-trailers
+
+git checkout -qb merge main
+git merge -q --no-edit release
+git tag merged
+
+
+git checkout -q main
@@ -77,6 +77,18 @@ git init ssh-command-fallback
   git config gitoxide.ssh.commandWithoutShellFallback "ssh --fallback"
 )
 
+git init ssh-native
+(cd ssh-native
+  git config gitoxide.ssh.native true
+  git config gitoxide.ssh.identityFile /keys/first
+  git config --add gitoxide.ssh.identityFile /keys/second
+  git config gitoxide.ssh.identitiesOnly true
+  git config gitoxide.ssh.identityAgent none
+  git config gitoxide.ssh.userKnownHostsFile /known_hosts
+  git config gitoxide.ssh.strictHostKeyChecking accept-new
+  git config gitoxide.ssh.connectTimeout 5000
+)
+
 git init https-proxy-only
 (cd https-proxy-only
   git config gitoxide.https.proxy https
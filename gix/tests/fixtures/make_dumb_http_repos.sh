@@ -0,0 +1,24 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q base
+(cd base
+  git checkout -q -b main
+  echo a > a && git add a && git commit -q -m c1
+  git tag -a -m "annotated" v1.0
+  git branch other
+  echo b > b && git add b && git commit -q -m c2
+  git repack -adq
+  echo c > c && git add c && git commit -q -m c3
+  git tag lightweight
+  git update-server-info
+  cp .git/info/refs ../expected-info-refs
+  cp .git/objects/info/packs ../expected-objects-info-packs
+)
+
+git clone -q --shared base alternate
+(cd alternate
+  echo "../../../base/.git/objects" > .git/objects/info/alternates
+  echo d > d && git add d && git commit -q -m c4
+  git update-server-info
+)
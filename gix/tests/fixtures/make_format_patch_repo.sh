@@ -0,0 +1,33 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+seq 1 20 > file
+echo "to be renamed" > renamed-from
+echo "#!/bin/sh" > script
+echo "to be deleted" > deleted
+git add . && git commit -qm base
+git branch base
+
+sed -i.bak 's/^3$/three/;s/^17$/seventeen/' file && rm file.bak
+echo added > dir-added
+git add . && GIT_AUTHOR_NAME="Jöhn Dœ" GIT_AUTHOR_EMAIL="john@example.com" git commit -qm "Change a file and add another"
+git mv renamed-from renamed-to
+chmod +x script
+git add . && git commit -qm "Rename a file and make a script executable, which is described in a subject that needs wrapping
+
+The body explains why.
+
+Signed-off-by: Jane Doe <jane@example.com>"
+git rm -q deleted
+seq 1 5 >> file
+git commit -qam "Delete a file" -m "The naïve body isn't ASCII."
+git branch tip
+
+git format-patch -q --signature=test --cover-letter -o .git/patches base..tip
+git format-patch -q --stdout --no-signature --no-stat tip~1..tip > .git/single.patch
+git format-patch -q --stdout --no-signature base..tip > .git/mbox
+
+git checkout -q -b conflict base
+sed -i.bak 's/^19$/nineteen/' file && rm file.bak
+git commit -qam "Change the context of a hunk"
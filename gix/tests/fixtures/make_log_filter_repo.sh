@@ -0,0 +1,170 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function tick () {
+  if test -z "${tick+set}"; then
+    tick=1112911993
+  else
+    tick=$(($tick + 60))
+  fi
+  GIT_COMMITTER_DATE="$tick -0700"
+  GIT_AUTHOR_DATE="$tick -0700"
+  export GIT_COMMITTER_DATE GIT_AUTHOR_DATE
+}
+
+function commit () {
+  tick
+  git add -A
+  git commit -q --allow-empty "$@"
+}
+
+# Merge `$2` into the current branch without changing the tree, so it can be changed before committing.
+function merge () {
+  local message=$1 other=$2
+  git merge -q --no-ff --no-commit --allow-unrelated-histories -s ours "$other"
+}
+
+function write () {
+  local path=$1 content=$2
+  echo "$content" > "$path"
+}
+
+git init -q
+git checkout -q -b main
+
+# The history used in the documentation of `git log` to explain history simplification,
+# where all commits and merges are named like in the documentation.
+#
+#   .-A---M---N---O---P---Q
+#  /     /   /   /   /   /
+# I     B   C   D   E   Y
+#  \   /   /   /   /   /
+#   `-------------'   X
+write foo asdf
+write quux quux
+commit -m I
+git tag I
+
+write foo foo
+commit -m A
+
+git checkout -q -b b I
+write foo foo
+commit -m B
+
+git checkout -q main
+merge M b
+commit -m M
+
+git checkout -q -b c I
+write unrelated c
+commit -m C
+
+git checkout -q main
+merge N c
+write foo foobar
+write unrelated c
+commit -m N
+
+git checkout -q -b d I
+write foo baz
+commit -m D
+git tag D
+
+git checkout -q main
+merge O d
+write foo foobarbaz
+commit -m O
+
+git checkout -q -b e I
+write quux xyzzy
+commit -m E
+
+git checkout -q main
+merge P e
+write quux "quux xyzzy"
+commit -m P
+
+git checkout -q --orphan x
+git rm -q -rf .
+write side side
+commit -m X
+
+write side y
+commit -m Y
+
+git checkout -q main
+merge Q x
+git checkout x -- side
+commit -m Q
+git tag Q
+
+# A linear history with varied authors, committers and messages, and a file that gets renamed.
+git checkout -q --orphan renames
+git rm -q -rf .
+seq 1 10 > old-name
+GIT_AUTHOR_NAME="Alice Author" GIT_AUTHOR_EMAIL=alice@example.com commit -m "add old-name" -m "Fixes: #1"
+seq 1 11 > old-name
+GIT_COMMITTER_NAME="Charlie Committer" GIT_COMMITTER_EMAIL=charlie@example.com commit -m "extend old-name"
+write other other
+GIT_AUTHOR_NAME="Bob Builder" GIT_AUTHOR_EMAIL=bob@example.com commit -m "add other file" -m "Refs: #2"
+git mv old-name new-name
+GIT_AUTHOR_NAME="Alice Author" GIT_AUTHOR_EMAIL=alice@example.com commit -m "rename old-name to new-name"
+seq 1 12 > new-name
+commit -m "extend new-name" -m "Fixes: #3"
+
+# Two interleaved branches, with author dates on the side branch that are older than all others,
+# so that topological, commit date and author date order all differ.
+git checkout -q -b author-dates I
+commit -m "author-dates base"
+git branch author-dates-side
+commit -m "main 1"
+git checkout -q author-dates-side
+tick
+GIT_AUTHOR_DATE="1112911000 -0700" git commit -q --allow-empty -m "side 1"
+git checkout -q author-dates
+commit -m "main 2"
+git checkout -q author-dates-side
+tick
+GIT_AUTHOR_DATE="1112911100 -0700" git commit -q --allow-empty -m "side 2"
+git checkout -q author-dates
+merge "" author-dates-side
+commit -m "author-dates merge"
+git checkout -q main
+
+function baseline () {
+  local name=$1; shift
+  git log --format=%H "$@" > "$name.baseline"
+}
+
+baseline default Q -- foo
+baseline full-history --full-history Q -- foo
+baseline simplify-merges --simplify-merges Q -- foo
+git log --format="%H %P" --simplify-merges Q -- foo > simplify-merges-parents.baseline
+baseline multiple-paths Q -- foo side
+baseline ancestry-path --ancestry-path D..Q
+baseline merges --merges Q
+baseline no-merges --no-merges Q
+baseline first-parent --first-parent Q -- foo
+
+baseline follow --follow renames -- new-name
+baseline no-follow renames -- new-name
+baseline author --author=Alice renames
+baseline author-regex --author='^B.*r <bob@' renames
+baseline committer --committer=charlie renames
+baseline grep --grep=Fixes renames
+baseline grep-multiple --grep='#1' --grep='#2' renames
+baseline grep-all-match --all-match --grep=Fixes --grep='#3' renames
+baseline grep-ignore-case -i --grep=FIXES renames
+baseline invert-grep --invert-grep --grep=Fixes renames
+baseline author-and-grep --author=Alice --grep=Fixes renames
+baseline author-and-committer --author=Bob --committer=Charlie renames
+baseline since-until --since=1112912773 --until=1112912833 renames
+
+baseline topo-order --topo-order author-dates
+baseline date-order --date-order author-dates
+baseline author-date-order --author-date-order author-dates
+baseline topo-order-range --topo-order D..Q
+baseline boundary --boundary --topo-order D..Q
+
+git commit-graph write --no-progress --reachable
@@ -0,0 +1,68 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function tick () {
+  if test -z "${tick+set}"; then
+    tick=1112911993
+  else
+    tick=$((tick + 60))
+  fi
+  GIT_COMMITTER_DATE="$tick -0700"
+  GIT_AUTHOR_DATE="$tick -0700"
+  export GIT_COMMITTER_DATE GIT_AUTHOR_DATE
+}
+
+function commit () {
+  tick
+  git commit -q --allow-empty -m "$1"
+}
+
+git init -q
+git config merge.ff false
+
+git checkout -q -b main
+commit c1
+git tag -m "annotated v0.1" v0.1
+commit c2
+tick
+git tag v0.2-lightweight
+
+git checkout -q -b feature/a
+commit f1
+tick
+git tag -m "annotated feature" feature-done
+commit f2
+
+git checkout -q main
+commit c3
+tick
+git merge -q feature/a -m "merge feature"
+tick
+git tag -m "annotated v1.0" v1.0
+commit c4
+tick
+git tag -m "annotated v1.1" v1.1
+commit c5
+
+git checkout -q -b unmerged main~3
+commit u1
+
+git commit-graph write --no-progress --reachable
+git repack -adq
+
+function baseline () {
+  local name=${1:?first argument is the baseline name}
+  shift
+  for commit in $(git rev-list --all); do
+    echo "$commit $(git name-rev --name-only "$@" "$commit")"
+  done > "name-rev-$name.baseline"
+}
+
+baseline all
+baseline tags --tags
+baseline matching --refs='v1*' --refs='feature/*'
+baseline excluding --exclude='v1.1' --exclude='main'
+
+for commit in $(git rev-list --all); do
+  echo "$commit" $(git for-each-ref --format='%(refname)' --contains "$commit")
+done > contains.baseline
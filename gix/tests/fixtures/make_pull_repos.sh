@@ -0,0 +1,80 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q remote
+(cd remote
+  seq 1 5 > file
+  echo original > other
+  git add . && git commit -qm base
+)
+
+for name in fast-forward no-fast-forward diverged diverged-git-merge diverged-git-rebase local-changes staged-changes untracked conflict local-merge; do
+  git clone -q remote $name
+done
+
+(cd remote
+  echo new > new
+  sed -i.bak 's/^5$/remote/' file && rm file.bak
+  git add . && git commit -qm "remote change"
+)
+
+git clone -q remote ahead
+(cd ahead
+  echo local > other
+  git commit -qam "local change"
+)
+
+for name in diverged diverged-git-merge diverged-git-rebase; do
+  (cd $name
+    sed -i.bak 's/^1$/local/' file && rm file.bak
+    git commit -qam "local change"
+  )
+done
+
+(cd conflict
+  sed -i.bak 's/^5$/local/' file && rm file.bak
+  git commit -qam "conflicting change"
+)
+
+(cd local-merge
+  git checkout -qb side
+  echo side > other
+  git commit -qam "side change"
+  git checkout -q main
+  sed -i.bak 's/^1$/local/' file && rm file.bak
+  git commit -qam "local change"
+  git merge -q --no-edit side
+)
+
+(cd local-changes
+  echo modified >> file
+)
+
+(cd staged-changes
+  echo staged > other
+  echo added > staged
+  git add other staged
+)
+
+(cd untracked
+  echo untracked > new
+)
+
+for name in fast-forward no-fast-forward diverged local-changes staged-changes untracked ahead conflict local-merge; do
+  git -C $name fetch -q
+done
+
+git clone -q remote shallow-remote
+git clone -q --depth 1 file://$PWD/shallow-remote shallow
+(cd shallow-remote
+  echo remote > other
+  git commit -qam "another remote change"
+)
+(cd shallow
+  sed -i.bak 's/^1$/local/' file && rm file.bak
+  git commit -qam "local change"
+  git fetch -q
+)
+
+git -C diverged-git-merge pull -q --no-rebase --no-edit
+git -C diverged-git-rebase pull -q --rebase
@@ -0,0 +1,83 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function tick () {
+  if test -z "${tick+set}"; then
+    tick=1112911993
+  else
+    tick=$(($tick + 60))
+  fi
+  GIT_COMMITTER_DATE="$tick -0700"
+  GIT_AUTHOR_DATE="$tick -0700"
+  export GIT_COMMITTER_DATE GIT_AUTHOR_DATE
+}
+
+function commit () {
+  tick
+  git add -A
+  git commit -q "$@"
+}
+
+git init -q
+git checkout -q -b main
+
+cat <<EOF >code.rs
+fn first() {
+  one
+  two
+  three
+}
+
+fn second() {
+  four
+  five
+  six
+}
+EOF
+seq 1 10 > numbers
+commit -m base
+git tag base
+
+git checkout -q -b v1
+sed -i 's/two/TWO/' code.rs
+commit -m "uppercase two"
+seq 1 3 > new-file
+commit -m "add new file" -m "With a body."
+sed -i 's/five/FIVE/' code.rs
+commit -m "uppercase five"
+seq 1 11 > numbers
+commit -m "extend numbers"
+
+git checkout -q -b v2 base
+sed -i 's/two/TWO/' code.rs
+commit -m "uppercase two"
+seq 1 4 > new-file
+commit -m "add a new file" -m "With a body that changed."
+seq 1 11 > numbers
+commit -m "extend numbers"
+git mv numbers renamed
+chmod +x code.rs
+commit -m "rename numbers and change mode"
+printf 'binary\0data' > binary
+commit -m "add binary"
+
+git checkout -q -b v3 base
+sed -i 's/two/TWO/; s/three/THREE/' code.rs
+commit -m "uppercase two and three"
+seq 1 4 > new-file
+commit -m "add a new file" -m "With a body that changed."
+seq 1 11 > numbers
+commit -m "extend numbers"
+git mv numbers moved
+chmod +x code.rs
+commit -m "rename numbers and change mode"
+printf 'binary\0data' > binary
+commit -m "add a binary file"
+
+git checkout -q main
+
+git range-diff --no-color base..v1 base..v2 >range-diff.baseline
+git range-diff --no-color --creation-factor=100 base..v1 base..v2 >range-diff-creation-factor-100.baseline
+git range-diff --no-color --creation-factor=0 base..v1 base..v2 >range-diff-creation-factor-0.baseline
+git range-diff --no-color v1...v2 >range-diff-symmetric.baseline
+git range-diff --no-color --creation-factor=200 base..v2 base..v3 >range-diff-v2-v3.baseline
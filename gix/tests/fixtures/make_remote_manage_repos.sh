@@ -0,0 +1,64 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q base
+(cd base
+  git checkout -q -b main
+  echo content > file
+  git add . && git commit -qm initial
+  git branch feature
+  git branch other
+)
+
+git clone -q base clone
+(cd clone
+  git checkout -q --track origin/feature
+  git config branch.feature.pushRemote origin
+  git config remote.pushDefault origin
+  git remote add upstream ../base
+  git fetch -q upstream
+  git branch --track from-upstream upstream/other
+)
+
+for name in git-added git-renamed git-removed git-set-url git-set-head git-set-branches; do
+  cp -R clone $name
+done
+
+(cd git-added
+  git remote add added ../base
+)
+
+(cd git-renamed
+  git remote rename origin renamed
+)
+
+(cd git-removed
+  git remote remove origin
+)
+
+(cd git-set-url
+  git remote set-url origin ../other
+  git remote set-url --push upstream ../upstream-push
+)
+
+(cd git-set-head
+  git remote set-head upstream other
+  git remote set-head --delete origin
+)
+
+(cd git-set-branches
+  git remote set-branches origin main
+  git remote set-branches --add origin 'feature/*'
+  git remote set-branches upstream other
+)
+
+cp -R clone shared
+(cd shared
+  git remote add mirror ../base
+  git config --replace-all remote.mirror.fetch +refs/heads/main:refs/remotes/origin/main
+)
+
+cp -R shared git-removed-shared
+(cd git-removed-shared
+  git remote remove origin 2>/dev/null
+)
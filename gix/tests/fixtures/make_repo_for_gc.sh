@@ -0,0 +1,39 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+echo a > a
+git add a && git commit -q -m "first"
+git tag -a -m "annotated" v1
+git repack -q -d
+
+echo b > b
+git add b && git commit -q -m "second"
+
+git checkout -q -b other
+echo c > c
+git add c && git commit -q -m "other"
+git checkout -q main
+
+echo dropped > dropped
+git add dropped && git commit -q -m "dropped"
+git rev-parse HEAD > .git/dropped-commit
+git reset -q --hard HEAD~1
+
+echo staged > staged
+git add staged
+git rev-parse :staged > .git/staged-blob
+
+echo unreachable | git hash-object -w --stdin > .git/unreachable-blob
+
+git worktree add -q --detach linked
+(cd linked
+  echo linked > linked-file
+  git add linked-file && git commit -q -m "detached in linked worktree"
+  git rev-parse HEAD > ../.git/linked-commit
+  echo linked-staged > linked-staged
+  git add linked-staged
+  git rev-parse :linked-staged > ../.git/linked-staged-blob
+)
@@ -0,0 +1,27 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+for i in $(seq 40); do
+  echo "content $i" > "file$i"
+done
+git add . && git commit -q -m "initial"
+git repack -q -d
+
+for i in 1 2 3; do
+  echo "change $i" >> file1
+  git commit -q -am "change $i"
+  git repack -q -d
+done
+
+echo "kept" > kept
+git add kept && git commit -q -m "kept"
+ls .git/objects/pack/*.pack > packs-before
+git repack -q -d
+for pack in .git/objects/pack/*.pack; do
+  if ! grep -q "$pack" packs-before; then
+    touch "${pack%.pack}.keep"
+  fi
+done
+rm packs-before
@@ -13,3 +13,47 @@ git init -q untracked-only
   mkdir new
   touch new/untracked subdir/untracked
 )
+
+git init -q staged-only
+(cd staged-only
+  echo content > file
+  git add . && git commit -q -m init
+
+  echo change >> file
+  git add file
+)
+
+git init -q porcelain-v2
+(cd porcelain-v2
+  for name in modified-staged modified-unstaged modified-both deleted-staged deleted-unstaged conflict executable; do
+    echo "$name" > "$name"
+  done
+  seq 1 10 > renamed-from
+  git add . && git commit -q -m init
+
+  git checkout -q -b other
+  echo theirs > conflict
+  git commit -q -am theirs
+  git checkout -q -
+  echo ours > conflict
+  git commit -q -am ours
+  git merge -q other >/dev/null || true
+
+  echo change >> modified-staged
+  git add modified-staged
+  echo change >> modified-unstaged
+  echo change >> modified-both
+  git add modified-both
+  echo "more change" >> modified-both
+  git rm -q deleted-staged
+  rm deleted-unstaged
+  git mv renamed-from renamed-to
+  chmod +x executable
+  echo new > added-staged
+  git add added-staged
+  mkdir untracked-dir
+  echo untracked > untracked-dir/file
+  echo untracked > "untracked with space"
+
+  git status --porcelain=v2 > .git/baseline
+)
@@ -0,0 +1,106 @@
+use std::sync::atomic::AtomicBool;
+
+use gix::remote::{fetch::Status, Direction};
+use gix_features::progress;
+use gix_testtools::tempfile::TempDir;
+
+use super::dumb_http::serve_directory;
+
+/// Return the URL of the server that serves the bundles and the repository they were made from.
+fn server_url() -> crate::Result<String> {
+    let root = gix_testtools::scripted_fixture_read_only("make_bundle_uri_repos.sh")?;
+    Ok(serve_directory(root)?)
+}
+
+fn tokens(bundles: &[gix::remote::fetch::outcome::Bundle]) -> Vec<Option<u64>> {
+    bundles.iter().map(|b| b.creation_token).collect()
+}
+
+#[test]
+fn clone_is_bootstrapped_from_a_list_with_creation_tokens_which_is_remembered() -> crate::Result {
+    let url = server_url()?;
+    let list_url = format!("{url}/bundles/list");
+    let tmp = TempDir::new()?;
+    let (repo, outcome) = gix::prepare_clone_bare(format!("{url}/base/.git"), tmp.path())?
+        .with_bundle_uri(list_url.as_str())
+        .fetch_only(progress::Discard, &AtomicBool::default())?;
+
+    assert_eq!(
+        tokens(&outcome.bundles),
+        [Some(1), Some(2)],
+        "bundles are applied in order of their creation token"
+    );
+    assert!(outcome.bundles.iter().all(|b| b.write_pack_bundle.is_some()));
+    assert_eq!(
+        outcome.bundles[0].uri,
+        format!("{url}/bundles/1.bundle"),
+        "URIs are resolved relative to the list"
+    );
+    assert_eq!(
+        repo.find_reference("refs/bundles/main")?
+            .peel_to_commit()?
+            .message_raw()?,
+        "c2\n",
+        "branches of bundles are stored for use in negotiation"
+    );
+    let Status::Downloaded { download, .. } = outcome.status else {
+        panic!("the last commit isn't in any bundle and has to be fetched")
+    };
+    assert_eq!(download.loose_objects, 3, "only the objects of the last commit");
+    assert!(download.packs.is_empty());
+    assert_eq!(repo.head_commit()?.message_raw()?, "c3\n");
+
+    let repo = gix::open(repo.path())?;
+    let config = repo.config_snapshot();
+    assert_eq!(
+        config.string("fetch.bundleURI").expect("set").as_ref(),
+        list_url.as_str(),
+        "the list is remembered to allow incremental fetches"
+    );
+    assert_eq!(config.integer("fetch.bundleCreationToken"), Some(2));
+
+    let outcome = repo
+        .find_remote("origin")?
+        .connect(Direction::Fetch)?
+        .prepare_fetch(progress::Discard, Default::default())?
+        .receive(progress::Discard, &AtomicBool::default())?;
+    assert!(
+        outcome.bundles.is_empty(),
+        "there are no bundles newer than the ones we applied already"
+    );
+    assert!(matches!(outcome.status, Status::NoPackReceived { .. }));
+    Ok(())
+}
+
+#[test]
+fn fetch_applies_the_first_usable_bundle_of_a_list_with_any_mode() -> crate::Result {
+    let url = server_url()?;
+    let tmp = TempDir::new()?;
+    let repo = gix::init_bare(tmp.path())?;
+    let outcome = repo
+        .remote_at(format!("{url}/base/.git"))?
+        .with_refspecs(Some("+refs/heads/*:refs/remotes/origin/*"), Direction::Fetch)?
+        .connect(Direction::Fetch)?
+        .prepare_fetch(progress::Discard, Default::default())?
+        .with_bundle_uri(format!("{url}/bundles/any-list"))
+        .receive(progress::Discard, &AtomicBool::default())?;
+
+    assert_eq!(
+        tokens(&outcome.bundles),
+        [None],
+        "the missing bundle is skipped in favor of the next one"
+    );
+    assert_eq!(outcome.bundles[0].uri, format!("{url}/bundles/all.bundle"));
+    assert!(
+        matches!(outcome.status, Status::Downloaded { ref download, .. } if download.loose_objects == 3),
+        "only the last commit had to be fetched"
+    );
+
+    let repo = gix::open(repo.path())?;
+    assert_eq!(
+        repo.config_snapshot().string("fetch.bundleURI"),
+        None,
+        "lists without creation tokens don't support incremental fetches, and aren't remembered"
+    );
+    Ok(())
+}
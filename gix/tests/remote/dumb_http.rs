@@ -0,0 +1,180 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    sync::atomic::AtomicBool,
+};
+
+use gix::remote::{fetch, fetch::Status, Direction::Fetch};
+use gix_features::progress;
+use gix_testtools::tempfile::TempDir;
+
+use crate::util::restricted;
+
+/// Serve all files below `root` as static files over HTTP, like a web server that knows nothing about `git` would,
+/// and return the URL to reach `root` at.
+pub(super) fn serve_directory(root: PathBuf) -> std::io::Result<String> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let url = format!("http://{}", listener.local_addr()?);
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let root = root.clone();
+            std::thread::spawn(move || serve_file(stream, &root));
+        }
+    });
+    Ok(url)
+}
+
+fn serve_file(mut stream: TcpStream, root: &Path) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut header = String::new();
+    while reader.read_line(&mut header)? != 0 && header != "\r\n" {
+        header.clear();
+    }
+    let path = request_line.split(' ').nth(1).unwrap_or_default();
+    let path = path.split('?').next().unwrap_or_default().trim_start_matches('/');
+    match std::fs::read(root.join(path)) {
+        Ok(data) => {
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                data.len()
+            )?;
+            stream.write_all(&data)
+        }
+        Err(_) => write!(
+            stream,
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+        ),
+    }
+}
+
+fn url_of(repo_name: &str) -> crate::Result<String> {
+    let root = gix_testtools::scripted_fixture_read_only("make_dumb_http_repos.sh")?;
+    Ok(format!("{}/{repo_name}/.git", serve_directory(root)?))
+}
+
+fn fetch(repo: &gix::Repository, url: &str, refspec: &str) -> crate::Result<fetch::Outcome> {
+    Ok(repo
+        .remote_at(url)?
+        .with_refspecs(Some(refspec), Fetch)?
+        .connect(Fetch)?
+        .prepare_fetch(progress::Discard, Default::default())?
+        .receive(progress::Discard, &AtomicBool::default())?)
+}
+
+fn edited_refs(update_refs: &fetch::refs::update::Outcome) -> Vec<String> {
+    update_refs
+        .edits
+        .iter()
+        .map(|edit| edit.name.as_bstr().to_string())
+        .collect()
+}
+
+#[test]
+fn fetch_downloads_packs_and_loose_objects_that_are_missing() -> crate::Result {
+    let url = url_of("base")?;
+    let tmp = TempDir::new()?;
+    let repo = gix::init_bare(tmp.path())?;
+
+    let outcome = fetch(&repo, &url, "+refs/heads/other:refs/remotes/origin/other")?;
+    let Status::Downloaded { download, update_refs } = outcome.status else {
+        panic!("objects were downloaded")
+    };
+    assert_eq!(
+        download.loose_objects, 0,
+        "the commit of `other` is only available in a pack"
+    );
+    assert_eq!(download.packs.len(), 1);
+    assert!(
+        download.packs[0].keep_path.is_none(),
+        ".keep files are removed once refs are updated"
+    );
+    assert_eq!(
+        edited_refs(&update_refs),
+        ["refs/remotes/origin/other", "refs/tags/v1.0"],
+        "the annotated tag points to a commit that we now have, so it's included"
+    );
+
+    let outcome = fetch(&repo, &url, "+refs/heads/*:refs/remotes/origin/*")?;
+    let Status::Downloaded { download, update_refs } = outcome.status else {
+        panic!("objects were downloaded")
+    };
+    assert_eq!(
+        download.loose_objects, 3,
+        "the most recent commit, its tree and the new blob are loose on the remote"
+    );
+    assert!(download.packs.is_empty(), "the pack was already received");
+    assert_eq!(
+        edited_refs(&update_refs),
+        [
+            "refs/remotes/origin/main",
+            "refs/remotes/origin/other",
+            "refs/tags/lightweight",
+            "refs/tags/v1.0"
+        ],
+        "the lightweight tag is included as well now that its commit is present"
+    );
+    let head = repo.find_reference("refs/remotes/origin/main")?.peel_to_commit()?;
+    assert_eq!(head.message_raw()?, "c3\n");
+    assert_eq!(
+        head.tree()?.traverse().breadthfirst.files()?.len(),
+        3,
+        "all objects reachable from the tip are present"
+    );
+
+    let outcome = fetch(&repo, &url, "+refs/heads/*:refs/remotes/origin/*")?;
+    assert!(
+        matches!(outcome.status, Status::NoPackReceived { negotiate: None, .. }),
+        "nothing changed on the remote"
+    );
+    Ok(())
+}
+
+#[test]
+fn clone_sets_up_head_from_the_remote() -> crate::Result {
+    let url = url_of("base")?;
+    let tmp = TempDir::new()?;
+    let (repo, outcome) =
+        gix::prepare_clone_bare(url, tmp.path())?.fetch_only(progress::Discard, &AtomicBool::default())?;
+    assert!(matches!(outcome.status, Status::Downloaded { .. }));
+    assert_eq!(
+        repo.head_name()?.expect("not detached").as_bstr(),
+        "refs/heads/main",
+        "the remote HEAD is read to learn the symbolic ref"
+    );
+    assert_eq!(repo.head_commit()?.message_raw()?, "c3\n");
+    Ok(())
+}
+
+#[test]
+fn alternates_are_only_followed_if_redirects_are_allowed() -> crate::Result {
+    let url = url_of("alternate")?;
+    let tmp = TempDir::new()?;
+    let mut repo = gix::open_opts(gix::init_bare(tmp.path())?.path(), restricted())?;
+
+    let err = fetch(&repo, &url, "+refs/heads/main:refs/remotes/origin/main").unwrap_err();
+    assert!(
+        matches!(
+            err.downcast_ref::<fetch::Error>(),
+            Some(fetch::Error::Dumb(fetch::dumb::Error::ObjectNotFound { .. }))
+        ),
+        "the parent commit is only available in the alternate: {err:?}"
+    );
+
+    repo.config_snapshot_mut()
+        .set_raw_value(&gix::config::tree::Http::FOLLOW_REDIRECTS, "true")?;
+    let outcome = fetch(&repo, &url, "+refs/heads/main:refs/remotes/origin/main")?;
+    let Status::Downloaded { download, .. } = outcome.status else {
+        panic!("objects were downloaded")
+    };
+    assert_eq!(
+        download.loose_objects,
+        3 + 3,
+        "the loose objects of both the remote and its alternate"
+    );
+    assert_eq!(download.packs.len(), 1, "the pack of the alternate");
+    Ok(())
+}
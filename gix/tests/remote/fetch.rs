@@ -160,6 +160,204 @@ mod blocking_and_async_io {
         Ok(())
     }
 
+    #[test]
+    #[cfg(feature = "blocking-network-client")]
+    fn fetch_with_prune_deletes_refs_missing_on_remote() -> crate::Result {
+        let (mut repo, _tmp) = try_repo_rw("two-origins")?;
+        let id = repo.find_reference("refs/remotes/origin/main")?.id().detach();
+        for name in ["refs/remotes/origin/gone", "refs/tags/gone"] {
+            repo.reference(name, id, gix::refs::transaction::PreviousValue::MustNotExist, "test")?;
+        }
+
+        let mut fetch_pruned = |prune_tags: bool| -> crate::Result<Vec<String>> {
+            {
+                let mut config = repo.config_snapshot_mut();
+                config.set_value(&gix::config::tree::Fetch::PRUNE, "true")?;
+                config.set_value(&gix::config::tree::Fetch::PRUNE_TAGS, prune_tags.to_string().as_str())?;
+            }
+            let outcome = repo
+                .find_remote("origin")?
+                .connect(Fetch)?
+                .prepare_fetch(progress::Discard, Default::default())?
+                .receive(progress::Discard, &AtomicBool::default())?;
+            let update_refs = match outcome.status {
+                Status::Change { update_refs, .. } | Status::NoPackReceived { update_refs, .. } => update_refs,
+                _ => unreachable!("a fetch from a smart remote"),
+            };
+            Ok(update_refs
+                .iter_pruned()
+                .map(|name| name.as_bstr().to_string())
+                .collect())
+        };
+
+        assert_eq!(
+            fetch_pruned(false)?,
+            ["refs/remotes/origin/gone"],
+            "tags that are only followed aren't pruned by default"
+        );
+        assert_eq!(
+            fetch_pruned(true)?,
+            ["refs/tags/gone"],
+            "with `fetch.pruneTags` all tags are considered"
+        );
+        assert!(repo.try_find_reference("refs/remotes/origin/gone")?.is_none());
+        assert!(repo.try_find_reference("refs/tags/gone")?.is_none());
+        assert!(
+            repo.try_find_reference("refs/remotes/origin/main")?.is_some(),
+            "refs that exist on the remote are kept"
+        );
+        assert!(
+            repo.try_find_reference("refs/tags/b-tag")?.is_some(),
+            "tags that exist on the remote are kept"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "blocking-network-client")]
+    fn fetch_writes_fetch_head_with_tips_to_merge_first() -> crate::Result {
+        let (repo, _tmp) = try_repo_rw("two-origins")?;
+        assert!(repo.fetch_head()?.is_none(), "clones don't write FETCH_HEAD");
+
+        repo.find_remote("origin")?
+            .connect(Fetch)?
+            .prepare_fetch(progress::Discard, Default::default())?
+            .receive(progress::Discard, &AtomicBool::default())?;
+        let entries = repo.fetch_head()?.expect("written after fetch");
+        let first = &entries[0];
+        assert_eq!(
+            first.source,
+            gix::fetchhead::Source::Branch("main".into()),
+            "`branch.main.merge` determines what to merge"
+        );
+        assert!(first.for_merge);
+        assert!(
+            !first.url.ends_with(b"/") && !first.url.ends_with(b".git"),
+            "the url is trimmed like git does"
+        );
+        assert!(
+            entries[1..].iter().all(|e| !e.for_merge),
+            "everything else isn't merged"
+        );
+        assert!(
+            entries
+                .iter()
+                .any(|e| e.source == gix::fetchhead::Source::Tag("b-tag".into())),
+            "followed tags are listed as well"
+        );
+        assert_eq!(
+            repo.rev_parse_single("FETCH_HEAD")?,
+            repo.find_reference("refs/remotes/origin/main")?.id(),
+            "FETCH_HEAD resolves to its first entry"
+        );
+
+        repo.remote_at(base_repo_path())?
+            .with_refspecs(Some("refs/heads/b"), Fetch)?
+            .with_fetch_tags(fetch::Tags::None)
+            .connect(Fetch)?
+            .prepare_fetch(progress::Discard, Default::default())?
+            .receive(progress::Discard, &AtomicBool::default())?;
+        let entries = repo.fetch_head()?.expect("written after fetch");
+        assert_eq!(entries.len(), 1, "FETCH_HEAD only contains the last fetch");
+        assert_eq!(entries[0].source, gix::fetchhead::Source::Branch("b".into()));
+        assert!(entries[0].for_merge, "fetches by url merge what they fetched");
+        assert_eq!(
+            repo.rev_parse_single("FETCH_HEAD^{commit}")?,
+            repo.rev_parse_single("origin/b")?,
+            "navigation works as well"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "blocking-network-client")]
+    fn fetch_of_followed_tags_whose_target_is_present_locally() -> crate::Result {
+        let tmp = TempDir::new()?;
+        let repo = gix::init_bare(tmp.path())?;
+        let remote = repo
+            .remote_at(base_repo_path())?
+            .with_refspecs(Some("refs/heads/main:refs/remotes/origin/main"), Fetch)?;
+        remote
+            .clone()
+            .with_fetch_tags(fetch::Tags::None)
+            .connect(Fetch)?
+            .prepare_fetch(progress::Discard, Default::default())?
+            .receive(progress::Discard, &AtomicBool::default())?;
+        assert!(
+            repo.try_find_reference("refs/tags/b-tag")?.is_none(),
+            "no tag is fetched initially"
+        );
+
+        let outcome = remote
+            .with_fetch_tags(fetch::Tags::Included)
+            .connect(Fetch)?
+            .prepare_fetch(progress::Discard, Default::default())?
+            .receive(progress::Discard, &AtomicBool::default())?;
+        assert!(
+            matches!(outcome.status, Status::NoPackReceived { .. }),
+            "the branch didn't change, so there is no negotiation"
+        );
+        for name in ["refs/tags/b-tag", "refs/tags/i-tag"] {
+            assert_eq!(
+                repo.find_reference(name)?.id().object()?.kind,
+                gix::object::Kind::Tag,
+                "{name}: the annotated tag was fetched in a separate pass as it points to a commit we already have"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "blocking-network-client")]
+    fn fetch_with_ref_in_want_uses_wanted_refs_if_remote_ref_moved() -> crate::Result {
+        let server_tmp = gix_testtools::scripted_fixture_writable("make_remote_repos.sh")?;
+        let server_dir = server_tmp.path().join("base");
+        let mut config = std::fs::OpenOptions::new()
+            .append(true)
+            .open(server_dir.join(".git").join("config"))?;
+        std::io::Write::write_all(&mut config, b"[uploadpack]\n\tallowRefInWant = true\n")?;
+        let server = gix::open_opts(&server_dir, gix::open::Options::isolated())?;
+        let advertised_id = server.find_reference("main")?.id().detach();
+        let moved_id = server.find_reference("g")?.id().detach();
+
+        let tmp = TempDir::new()?;
+        let repo = gix::init_bare(tmp.path())?;
+        let remote = repo
+            .remote_at(server_dir.as_path())?
+            .with_refspecs(Some("refs/heads/main:refs/remotes/origin/main"), Fetch)?;
+        let prepare = remote
+            .connect(Fetch)?
+            .prepare_fetch(progress::Discard, Default::default())?;
+        assert_eq!(
+            prepare.ref_map().mappings[0].remote.as_id(),
+            Some(advertised_id.as_ref()),
+            "the ref is listed with the original value"
+        );
+
+        // The remote ref moves between listing the refs and fetching them.
+        std::fs::write(
+            server_dir.join(".git").join("refs").join("heads").join("main"),
+            format!("{moved_id}\n"),
+        )?;
+        let outcome = prepare.receive(progress::Discard, &AtomicBool::default())?;
+
+        assert!(
+            matches!(outcome.status, Status::Change { .. }),
+            "a pack with the moved ref was received"
+        );
+        assert_eq!(
+            outcome.ref_map.mappings[0].remote.as_id(),
+            Some(moved_id.as_ref()),
+            "the mapping was updated with the id from the wanted-refs section"
+        );
+        assert_eq!(
+            repo.find_reference("refs/remotes/origin/main")?.id(),
+            moved_id,
+            "the local ref points to what was actually sent instead of the stale advertised id"
+        );
+        Ok(())
+    }
+
     #[maybe_async::test(
         feature = "blocking-network-client",
         async(feature = "async-network-client-async-std", async_std::test)
@@ -453,7 +651,7 @@ mod blocking_and_async_io {
                 .await?;
 
             match res.status {
-                gix::remote::fetch::Status::Change { write_pack_bundle, update_refs, negotiate } => {
+                gix::remote::fetch::Status::Change { write_pack_bundle, update_refs, negotiate, .. } => {
                     assert_eq!(negotiate.rounds.len(), 1);
                     assert_eq!(write_pack_bundle.index.data_hash, hex_to_id(expected_data_hash), );
                     assert_eq!(write_pack_bundle.index.num_objects, 3 + num_objects_offset, "{fetch_tags:?}");
@@ -534,8 +732,10 @@ mod blocking_and_async_io {
                         write_pack_bundle,
                         update_refs,
                         negotiate,
+                        packfile_uris,
                     } => {
                         assert_eq!(negotiate.rounds.len(), 1);
+                        assert!(packfile_uris.is_empty(), "packfile-uris are only requested if configured");
                         assert_eq!(write_pack_bundle.pack_version, gix::odb::pack::data::Version::V2);
                         assert_eq!(write_pack_bundle.object_hash, repo.object_hash());
                         assert_eq!(write_pack_bundle.index.num_objects, 4, "{dry_run}: this value is 4 when git does it with 'consecutive' negotiation style, but could be 33 if completely naive.");
@@ -595,6 +795,7 @@ mod blocking_and_async_io {
                         );
                         update_refs
                     }
+                    fetch::Status::Downloaded { .. } => unreachable!("the remote is no static file server"),
                 };
 
                 assert_eq!(
@@ -64,7 +64,11 @@ pub(crate) fn into_daemon_remote_if_async<'repo, 'a>(
     }
 }
 
+#[cfg(any(feature = "blocking-http-transport-reqwest", feature = "blocking-http-transport-curl"))]
+mod bundle_uri;
 mod connect;
+#[cfg(any(feature = "blocking-http-transport-reqwest", feature = "blocking-http-transport-curl"))]
+mod dumb_http;
 pub(crate) mod fetch;
 mod ref_map;
 mod save;
@@ -0,0 +1,235 @@
+use gix::apply::{patch, Location, Options};
+
+fn patches(repo: &gix::Repository) -> crate::Result<Vec<patch::Patch>> {
+    let data = std::fs::read(repo.git_dir().join("patch"))?;
+    Ok(patch::parse(&data, Default::default())?)
+}
+
+fn tree(repo: &gix::Repository, spec: &str) -> crate::Result<gix::ObjectId> {
+    Ok(repo.rev_parse_single(format!("{spec}^{{tree}}").as_str())?.detach())
+}
+
+#[test]
+fn to_tree() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_apply_repo.sh")?;
+    let patches = patches(&repo)?;
+    let outcome = repo.apply_patches_to_tree(tree(&repo, "base")?, &patches, Options::default())?;
+    assert_eq!(outcome.tree, tree(&repo, "change")?);
+
+    assert!(
+        repo.apply_patches_to_tree(tree(&repo, "drifted")?, &patches, Options::default())
+            .is_err(),
+        "the context of both hunks changed"
+    );
+    let outcome = repo.apply_patches_to_tree(
+        tree(&repo, "drifted")?,
+        &patches,
+        Options {
+            three_way: Some(Default::default()),
+            ..Default::default()
+        },
+    )?;
+    assert!(outcome.conflicts.is_empty());
+    assert_eq!(
+        outcome.tree,
+        tree(&repo, "drifted-applied")?,
+        "the preimage is found in the object database by its abbreviated id"
+    );
+    Ok(())
+}
+
+#[test]
+fn to_worktree_and_index() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_apply_repo.sh")?;
+    let outcome = repo.apply_patches(&patches(&repo)?, Location::WorktreeAndIndex, Options::default())?;
+    assert!(outcome.rejected.is_empty() && outcome.conflicts.is_empty());
+
+    let workdir = repo.work_dir().expect("non-bare");
+    let expected = repo.index_from_tree(&tree(&repo, "change")?)?;
+    for path in ["file", "new"] {
+        let expected_id = expected.entry_by_path(path.into()).expect("present").id;
+        assert_eq!(
+            gix::objs::compute_hash(
+                repo.object_hash(),
+                gix::object::Kind::Blob,
+                &std::fs::read(workdir.join(path))?
+            ),
+            expected_id,
+            "the worktree was changed"
+        );
+        assert_eq!(
+            repo.open_index()?.entry_by_path(path.into()).expect("present").id,
+            expected_id,
+            "the index was changed and written"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn to_index_only() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_apply_repo.sh")?;
+    repo.apply_patches(&patches(&repo)?, Location::Index, Options::default())?;
+
+    let index = repo.open_index()?;
+    let expected = repo.index_from_tree(&tree(&repo, "change")?)?;
+    assert_eq!(
+        index.entries().iter().map(|entry| entry.id).collect::<Vec<_>>(),
+        expected.entries().iter().map(|entry| entry.id).collect::<Vec<_>>()
+    );
+    let workdir = repo.work_dir().expect("non-bare");
+    assert!(!workdir.join("new").exists(), "the worktree is untouched");
+    Ok(())
+}
+
+mod mailbox {
+    use gix::{
+        apply::{apply_mailbox, format_patch},
+        bstr::ByteSlice,
+    };
+
+    fn repo() -> crate::Result<gix::Repository> {
+        let opts = gix::open::Options::isolated().config_overrides([
+            "user.name=committer",
+            "user.email=committer@example.com",
+            "gitoxide.commit.committerDate=2000-01-02 00:00:00 +0000",
+        ]);
+        Ok(crate::util::repo_opts("make_format_patch_repo.sh", opts)?.to_thread_local())
+    }
+
+    fn id(repo: &gix::Repository, spec: &str) -> crate::Result<gix::ObjectId> {
+        Ok(repo.rev_parse_single(spec)?.detach())
+    }
+
+    #[test]
+    fn format_patch_with_cover_letter_matches_git() -> crate::Result {
+        let repo = repo()?;
+        let emails = repo.format_patch(
+            id(&repo, "base")?,
+            id(&repo, "tip")?,
+            &format_patch::Options {
+                cover_letter: true,
+                signature: Some("test".into()),
+                ..Default::default()
+            },
+        )?;
+
+        let patches_dir = repo.git_dir().join("patches");
+        let mut expected_names: Vec<_> = std::fs::read_dir(&patches_dir)?
+            .map(|entry| entry.map(|entry| entry.file_name().into_string().expect("valid UTF-8")))
+            .collect::<Result<_, _>>()?;
+        expected_names.sort();
+        assert_eq!(
+            emails.iter().map(|email| email.file_name.as_str()).collect::<Vec<_>>(),
+            expected_names,
+            "file names are the same, with the cover letter first"
+        );
+        assert_eq!(emails[0].commit, None, "the cover letter has no commit");
+        for email in &emails {
+            let expected = std::fs::read(patches_dir.join(&email.file_name))?;
+            assert_eq!(email.data.as_bstr(), expected.as_bstr(), "{}", email.file_name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn format_patch_of_single_commit_without_stat_matches_git() -> crate::Result {
+        let repo = repo()?;
+        let emails = repo.format_patch(
+            id(&repo, "tip~1")?,
+            id(&repo, "tip")?,
+            &format_patch::Options {
+                stat: false,
+                signature: None,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(emails.len(), 1);
+        assert_eq!(emails[0].commit, Some(id(&repo, "tip")?));
+        assert_eq!(emails[0].file_name, "0001-Delete-a-file.patch");
+        let expected = std::fs::read(repo.git_dir().join("single.patch"))?;
+        assert_eq!(
+            emails[0].data.as_bstr(),
+            expected.as_bstr(),
+            "a single patch isn't numbered"
+        );
+
+        let emails = repo.format_patch(id(&repo, "tip")?, id(&repo, "tip")?, &Default::default())?;
+        assert!(emails.is_empty(), "an empty range yields no mails");
+        Ok(())
+    }
+
+    #[test]
+    fn apply_mailbox_recreates_commits() -> crate::Result {
+        let repo = repo()?;
+        let mbox = std::fs::read(repo.git_dir().join("mbox"))?;
+        let outcome = repo.apply_mailbox(&mbox, id(&repo, "base")?, Default::default())?;
+        let apply_mailbox::Outcome::Applied { commits } = outcome else {
+            unreachable!("all patches apply cleanly")
+        };
+        assert_eq!(commits.len(), 3);
+
+        let expected = ["tip~2", "tip~1", "tip"]
+            .into_iter()
+            .map(|spec| id(&repo, spec))
+            .collect::<Result<Vec<_>, _>>()?;
+        for (actual, expected) in commits.iter().zip(expected) {
+            let (actual, expected) = (repo.find_commit(*actual)?, repo.find_commit(expected)?);
+            assert_eq!(actual.tree_id()?, expected.tree_id()?);
+            assert_eq!(actual.author()?, expected.author()?);
+            assert_eq!(actual.message_raw()?, expected.message_raw()?);
+        }
+        let tip = repo.find_commit(*commits.last().expect("three commits"))?;
+        assert_eq!(tip.committer()?.name, "committer", "the configured committer is used");
+        assert_eq!(tip.parent_ids().next().expect("one parent"), commits[1]);
+        Ok(())
+    }
+
+    #[test]
+    fn apply_mailbox_with_signoff_and_conflict() -> crate::Result {
+        let repo = repo()?;
+        let mbox = std::fs::read(repo.git_dir().join("single.patch"))?;
+        let outcome = repo.apply_mailbox(
+            &mbox,
+            id(&repo, "tip~1")?,
+            apply_mailbox::Options {
+                signoff: true,
+                ..Default::default()
+            },
+        )?;
+        let apply_mailbox::Outcome::Applied { commits } = outcome else {
+            unreachable!("the patch applies cleanly")
+        };
+        let commit = repo.find_commit(commits[0])?;
+        assert_eq!(
+            commit.message_raw()?,
+            "Delete a file\n\nThe naïve body isn't ASCII.\n\nSigned-off-by: committer <committer@example.com>\n"
+        );
+
+        let outcome = repo.apply_mailbox(
+            &mbox,
+            id(&repo, "conflict")?,
+            apply_mailbox::Options {
+                apply: gix::apply::Options {
+                    reject: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )?;
+        let apply_mailbox::Outcome::Conflict {
+            commits,
+            index,
+            mail,
+            outcome,
+        } = outcome
+        else {
+            unreachable!("the context of the hunk changed")
+        };
+        assert!(commits.is_empty());
+        assert_eq!(index, 0);
+        assert_eq!(outcome.rejected.len(), 1, "the hunk to 'file' is rejected");
+        assert_eq!(mail.message_ref().summary().as_ref(), "Delete a file");
+        Ok(())
+    }
+}
@@ -141,7 +141,7 @@ fn author_from_different_config_sections() -> crate::Result {
     let _env = Env::new()
         .set("GIT_CONFIG_GLOBAL", work_dir.join("global.config").to_str().unwrap())
         .set("GIT_CONFIG_SYSTEM", work_dir.join("system.config").to_str().unwrap())
-        .set("GIT_AUTHOR_DATE", "1979-02-26 18:30:00")
+        .set("GIT_AUTHOR_DATE", "1979-02-26 18:30:00 +0030")
         .set("GIT_COMMITTER_DATE", "1980-02-26 18:30:00 +0000")
         .set("EMAIL", "general@email-unused");
 
@@ -167,7 +167,7 @@ fn author_from_different_config_sections() -> crate::Result {
             name: "global name".into(),
             email: "local@example.com".into(),
             time: gix_date::Time {
-                seconds: 42,
+                seconds: 288900000,
                 offset: 1800,
                 sign: gix_date::time::Sign::Plus
             }
@@ -2,6 +2,74 @@ mod config_snapshot;
 mod identity;
 mod remote;
 
+mod trailer_options {
+    use gix_object::commit::message::trailer::{IfExists, IfMissing, Options, Placement, Token};
+
+    fn repo(overrides: &[&str]) -> gix::Repository {
+        let opts = gix::open::Options::isolated()
+            .strict_config(true)
+            .config_overrides(overrides.iter().copied());
+        crate::util::repo_opts("make_basic_repo.sh", opts)
+            .expect("valid")
+            .to_thread_local()
+    }
+
+    #[test]
+    fn defaults() -> crate::Result {
+        assert_eq!(repo(&[]).trailer_options()?, Options::default());
+        Ok(())
+    }
+
+    #[test]
+    fn global_and_per_token_values() -> crate::Result {
+        let repo = repo(&[
+            "trailer.separators=#:",
+            "trailer.where=start",
+            "trailer.ifExists=addIfDifferent",
+            "trailer.ifMissing=doNothing",
+            "trailer.sign.key=Signed-off-by",
+            "trailer.sign.where=after",
+            "trailer.change-id.ifexists=replace",
+            "trailer.change-id.ifmissing=add",
+        ]);
+        assert_eq!(
+            repo.trailer_options()?,
+            Options {
+                separators: "#:".into(),
+                placement: Placement::Start,
+                if_exists: IfExists::AddIfDifferent,
+                if_missing: IfMissing::DoNothing,
+                tokens: vec![
+                    Token {
+                        name: "sign".into(),
+                        key: Some("Signed-off-by".into()),
+                        placement: Some(Placement::After),
+                        ..Default::default()
+                    },
+                    Token {
+                        name: "change-id".into(),
+                        if_exists: Some(IfExists::Replace),
+                        if_missing: Some(IfMissing::Add),
+                        ..Default::default()
+                    }
+                ]
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_values_are_errors_in_strict_mode() {
+        assert_eq!(
+            repo(&["trailer.where=middle"])
+                .trailer_options()
+                .unwrap_err()
+                .to_string(),
+            "The key \"trailer.where=middle\" was invalid"
+        );
+    }
+}
+
 #[cfg(feature = "blocking-network-client")]
 mod ssh_options {
     use std::ffi::OsStr;
@@ -35,6 +103,41 @@ mod ssh_options {
     }
 }
 
+#[cfg(all(feature = "blocking-network-client", feature = "native-ssh"))]
+mod native_ssh_options {
+    use std::{path::PathBuf, time::Duration};
+
+    use gix::protocol::transport::client::native_ssh::StrictHostKeyChecking;
+
+    use crate::repository::config::repo;
+
+    #[test]
+    fn all_options() -> crate::Result {
+        let repo = repo("ssh-native");
+        let opts = repo.native_ssh_connect_options()?.expect("enabled");
+        assert_eq!(
+            opts.identity_files,
+            [PathBuf::from("/keys/first"), PathBuf::from("/keys/second")]
+        );
+        assert!(opts.identities_only);
+        assert!(opts.disable_agent, "'none' disables the agent");
+        assert_eq!(opts.known_hosts_files, [PathBuf::from("/known_hosts")]);
+        assert_eq!(opts.strict_host_key_checking, StrictHostKeyChecking::AcceptNew);
+        assert_eq!(opts.connect_timeout, Some(Duration::from_secs(5)));
+        Ok(())
+    }
+
+    #[test]
+    fn disabled_by_default() -> crate::Result {
+        let repo = repo("ssh-all-options");
+        assert!(
+            repo.native_ssh_connect_options()?.is_none(),
+            "the ssh program is used unless the built-in client is enabled"
+        );
+        Ok(())
+    }
+}
+
 #[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
 mod transport_options;
 
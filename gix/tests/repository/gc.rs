@@ -0,0 +1,349 @@
+use std::{collections::BTreeSet, sync::atomic::AtomicBool, time::SystemTime};
+
+use gix::{
+    gc::{Options, ReflogExpiry, ReflogExpiryPattern},
+    objs::Exists,
+    odb::loose::Store as LooseStore,
+    ObjectId,
+};
+
+fn id_in_git_dir(repo: &gix::Repository, name: &str) -> crate::Result<ObjectId> {
+    Ok(ObjectId::from_hex(
+        std::fs::read_to_string(repo.git_dir().join(name))?.trim().as_bytes(),
+    )?)
+}
+
+fn loose_objects(repo: &gix::Repository) -> crate::Result<BTreeSet<ObjectId>> {
+    Ok(LooseStore::at(repo.objects.store_ref().path(), repo.object_hash())
+        .iter()
+        .collect::<Result<_, _>>()?)
+}
+
+fn seconds_from_now(offset: i64) -> gix::date::Time {
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("after epoch")
+        .as_secs() as gix::date::SecondsSinceUnixEpoch;
+    gix::date::Time::new(now + offset, 0)
+}
+
+fn gc(repo: &gix::Repository, options: Options) -> Result<gix::gc::Outcome, gix::gc::Error> {
+    repo.gc(&mut gix::progress::Discard, &AtomicBool::default(), options)
+}
+
+#[test]
+fn packs_reachable_loose_objects_and_keeps_everything_else() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_repo_for_gc.sh")?;
+    let objects_before: BTreeSet<ObjectId> = repo.objects.iter()?.collect::<Result<_, _>>()?;
+    let unreachable_blob = id_in_git_dir(&repo, "unreachable-blob")?;
+
+    let outcome = gc(
+        &repo,
+        Options {
+            pack_refs: true,
+            repack: Some(gix::repack::Mode::All),
+            ..Default::default()
+        },
+    )?;
+    assert!(outcome.packed_refs > 0, "all branches and tags are packed");
+    assert_eq!(outcome.expired_reflog_entries, 0, "reflogs don't expire");
+    assert_eq!(outcome.pruned_loose_objects, 0, "nothing expires");
+    assert!(outcome.loose_objects_index_path.is_some());
+    assert_eq!(
+        outcome.num_reachable_objects,
+        objects_before.len() - 1,
+        "everything but the unreachable blob is reachable"
+    );
+    assert_eq!(
+        loose_objects(&repo)?,
+        Some(unreachable_blob).into_iter().collect(),
+        "only the unreachable blob remains loose"
+    );
+    assert!(
+        !repo.git_dir().join("gc.pid").exists(),
+        "the pid file is removed when done"
+    );
+
+    let repo = gix::open_opts(repo.git_dir(), crate::restricted())?;
+    let objects_after: BTreeSet<ObjectId> = repo.objects.iter()?.collect::<Result<_, _>>()?;
+    assert_eq!(objects_after, objects_before, "no object was lost");
+    for name in ["dropped-commit", "staged-blob", "linked-commit", "linked-staged-blob"] {
+        let id = id_in_git_dir(&repo, name)?;
+        assert!(
+            !loose_objects(&repo)?.contains(&id),
+            "{name} is reachable and was packed"
+        );
+    }
+    assert!(
+        !repo.git_dir().join("refs/heads/main").exists(),
+        "loose references were moved into packed-refs"
+    );
+    assert!(repo.find_reference("refs/heads/main").is_ok());
+    assert!(repo.find_reference("refs/tags/v1").is_ok());
+    Ok(())
+}
+
+#[test]
+fn unreachable_loose_objects_are_pruned_after_their_grace_period() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_repo_for_gc.sh")?;
+    let fresh_blob = repo.write_blob(b"written just now")?.detach();
+    let fixture_blob = id_in_git_dir(&repo, "unreachable-blob")?;
+
+    let outcome = gc(
+        &repo,
+        Options {
+            prune_expiry: Some(seconds_from_now(-24 * 60 * 60)),
+            ..Default::default()
+        },
+    )?;
+    assert!(
+        loose_objects(&repo)?.contains(&fresh_blob),
+        "recently written objects are protected by the grace period"
+    );
+    assert!(
+        outcome.pruned_loose_objects <= 1,
+        "at most the fixture blob is old enough"
+    );
+
+    let outcome = gc(
+        &repo,
+        Options {
+            prune_expiry: Some(seconds_from_now(60)),
+            ..Default::default()
+        },
+    )?;
+    assert!(outcome.pruned_loose_objects >= 1);
+    assert!(
+        loose_objects(&repo)?.is_empty(),
+        "all unreachable loose objects are gone"
+    );
+
+    let repo = gix::open_opts(repo.git_dir(), crate::restricted())?;
+    assert!(!repo.objects.exists(&fresh_blob));
+    assert!(!repo.objects.exists(&fixture_blob));
+    for name in ["dropped-commit", "staged-blob", "linked-commit", "linked-staged-blob"] {
+        assert!(
+            repo.objects.exists(&id_in_git_dir(&repo, name)?),
+            "{name} is still reachable"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn expired_reflogs_make_objects_unreachable() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_repo_for_gc.sh")?;
+    let dropped_commit = id_in_git_dir(&repo, "dropped-commit")?;
+
+    let outcome = gc(
+        &repo,
+        Options {
+            reflog_expiry: Some(ReflogExpiry {
+                expire: Some(seconds_from_now(0)),
+                ..Default::default()
+            }),
+            prune_expiry: Some(seconds_from_now(60)),
+            ..Default::default()
+        },
+    )?;
+    assert!(
+        outcome.expired_reflog_entries > 0,
+        "all reflog entries were written in the past"
+    );
+    let mut buf = Vec::new();
+    assert_eq!(
+        repo.refs.reflog_iter("HEAD", &mut buf)?.expect("still present").count(),
+        0,
+        "the reflog is empty now"
+    );
+
+    let repo = gix::open_opts(repo.git_dir(), crate::restricted())?;
+    assert!(
+        !repo.objects.exists(&dropped_commit),
+        "the commit was only reachable through the reflog"
+    );
+    assert!(
+        repo.objects.exists(&id_in_git_dir(&repo, "linked-commit")?),
+        "HEAD of the linked worktree keeps its commit alive"
+    );
+    assert!(repo.head_commit().is_ok());
+    Ok(())
+}
+
+#[test]
+fn reflog_entries_of_unreachable_commits_expire_separately() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_repo_for_gc.sh")?;
+    let dropped_commit = id_in_git_dir(&repo, "dropped-commit")?;
+    let entries = |name: &str| -> crate::Result<Vec<gix::refs::log::Line>> {
+        let mut buf = Vec::new();
+        Ok(repo
+            .refs
+            .reflog_iter(name, &mut buf)?
+            .expect("present")
+            .map(|line| line.map(|line| line.to_owned()))
+            .collect::<Result<_, _>>()?)
+    };
+    let refers_to_dropped_commit =
+        |line: &gix::refs::log::Line| line.previous_oid == dropped_commit || line.new_oid == dropped_commit;
+    let (head_before, main_before, other_before) = (
+        entries("HEAD")?,
+        entries("refs/heads/main")?,
+        entries("refs/heads/other")?,
+    );
+
+    let outcome = gc(
+        &repo,
+        Options {
+            reflog_expiry: Some(ReflogExpiry {
+                expire: None,
+                expire_unreachable: Some(seconds_from_now(60)),
+                patterns: Vec::new(),
+            }),
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        outcome.expired_reflog_entries, 4,
+        "the commit and the reset in HEAD and main"
+    );
+    for (name, before) in [("HEAD", head_before), ("refs/heads/main", main_before)] {
+        let expected: Vec<_> = before
+            .into_iter()
+            .filter(|line| !refers_to_dropped_commit(line))
+            .collect();
+        assert_eq!(
+            entries(name)?,
+            expected,
+            "only entries with unreachable commits are gone in {name}"
+        );
+    }
+    assert_eq!(
+        entries("refs/heads/other")?,
+        other_before,
+        "all commits in the reflog of 'other' are reachable from it"
+    );
+    Ok(())
+}
+
+#[test]
+fn reflog_expiry_dates_per_reference() {
+    let (expire, expire_unreachable, remote_expire) =
+        (seconds_from_now(-10), seconds_from_now(-5), seconds_from_now(-1));
+    let expiry = ReflogExpiry {
+        expire: Some(expire),
+        expire_unreachable: Some(expire_unreachable),
+        patterns: vec![
+            ReflogExpiryPattern {
+                pattern: "refs/remotes/*".into(),
+                expire: Some(remote_expire),
+                expire_unreachable: None,
+            },
+            ReflogExpiryPattern {
+                pattern: "refs/remotes/origin/*".into(),
+                expire: None,
+                expire_unreachable: None,
+            },
+        ],
+    };
+    assert_eq!(
+        expiry.dates_for("refs/heads/main".into()),
+        (Some(expire), Some(expire_unreachable))
+    );
+    assert_eq!(
+        expiry.dates_for("refs/remotes/origin/main".into()),
+        (Some(remote_expire), None),
+        "the first matching pattern wins, and `*` matches slashes"
+    );
+    assert_eq!(
+        expiry.dates_for("refs/stash".into()),
+        (None, None),
+        "the stash never expires by default"
+    );
+}
+
+#[test]
+fn a_recent_pid_file_prevents_running_unless_forced() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_repo_for_gc.sh")?;
+    let pid_file = repo.git_dir().join("gc.pid");
+    std::fs::write(&pid_file, "42 otherhost\n")?;
+
+    let err = gc(&repo, Options::default()).unwrap_err();
+    assert!(
+        matches!(&err, gix::gc::Error::AlreadyRunning { content, .. } if content == "42 otherhost"),
+        "{err:?}"
+    );
+    assert!(pid_file.is_file(), "the pid file of the other process is left alone");
+
+    gc(
+        &repo,
+        Options {
+            force: true,
+            ..Default::default()
+        },
+    )?;
+    assert!(!pid_file.exists(), "our own pid file is removed when done");
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn pid_files_of_processes_on_this_host_are_validated() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_repo_for_gc.sh")?;
+    let pid_file = repo.git_dir().join("gc.pid");
+    let hostname = std::process::Command::new("hostname").output()?.stdout;
+    let hostname = std::str::from_utf8(&hostname)?.trim();
+
+    let content = format!("{} {hostname}", std::process::id());
+    std::fs::write(&pid_file, format!("{content}\n"))?;
+    let err = gc(&repo, Options::default()).unwrap_err();
+    assert!(
+        matches!(&err, gix::gc::Error::AlreadyRunning { content: actual, .. } if *actual == content),
+        "the process that wrote the pid file is still running: {err:?}"
+    );
+
+    let mut child = std::process::Command::new("true").spawn()?;
+    let dead_pid = child.id();
+    child.wait()?;
+    std::fs::write(&pid_file, format!("{dead_pid} {hostname}\n"))?;
+    gc(&repo, Options::default())?;
+    assert!(
+        !pid_file.exists(),
+        "a pid file of a process that doesn't exist anymore is stale, and replaced by ours"
+    );
+    Ok(())
+}
+
+#[test]
+fn options_from_configuration() -> crate::Result {
+    let mut repo = crate::named_repo("make_repo_for_gc.sh")?;
+    let options = repo.gc_options()?;
+    assert!(options.pack_refs, "packing refs is the default");
+    assert!(options.prune_expiry.expect("set").seconds < seconds_from_now(-13 * 24 * 60 * 60).seconds);
+    let reflog_expiry = options.reflog_expiry.expect("set");
+    assert!(reflog_expiry.expire.expect("set").seconds < seconds_from_now(-89 * 24 * 60 * 60).seconds);
+    assert!(reflog_expiry.expire_unreachable.expect("set").seconds < seconds_from_now(-29 * 24 * 60 * 60).seconds);
+    assert!(reflog_expiry.patterns.is_empty());
+    assert!(options.repack.is_some());
+
+    let mut config = repo.config_snapshot_mut();
+    config.set_raw_value(&gix::config::tree::Gc::PRUNE_EXPIRE, "never")?;
+    config.set_raw_value(&gix::config::tree::Gc::REFLOG_EXPIRE, "now")?;
+    config.set_raw_value(&gix::config::tree::Gc::REFLOG_EXPIRE_UNREACHABLE, "never")?;
+    config.set_raw_value(&gix::config::tree::Gc::PACK_REFS, "notbare")?;
+    config.set_subsection_value(&gix::config::tree::Gc::PATTERN_REFLOG_EXPIRE, "refs/remotes/*", "now")?;
+    drop(config);
+    let options = repo.gc_options()?;
+    assert_eq!(options.prune_expiry, None);
+    let reflog_expiry = options.reflog_expiry.expect("set");
+    assert!(reflog_expiry.expire.expect("set").seconds >= seconds_from_now(-60).seconds);
+    assert_eq!(reflog_expiry.expire_unreachable, None);
+    assert_eq!(reflog_expiry.patterns.len(), 1);
+    assert_eq!(reflog_expiry.patterns[0].pattern, "refs/remotes/*");
+    assert!(reflog_expiry.patterns[0].expire.is_some());
+    assert_eq!(
+        reflog_expiry.patterns[0].expire_unreachable, None,
+        "unset values in patterns mean 'never'"
+    );
+    assert!(options.pack_refs, "the repository isn't bare");
+    Ok(())
+}
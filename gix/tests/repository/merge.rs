@@ -0,0 +1,179 @@
+use gix::merge::{cherry_pick, tree::ConflictKind};
+
+fn id(repo: &gix::Repository, spec: &str) -> crate::Result<gix::ObjectId> {
+    Ok(repo.rev_parse_single(spec)?.detach())
+}
+
+mod cherry_pick_ {
+    use super::*;
+
+    #[test]
+    fn clean_pick_creates_commit_without_touching_references() -> crate::Result {
+        let (repo, _tmp) = crate::repo_rw("make_cherry_pick_and_revert_repos.sh")?;
+        let head_before = repo.head_id()?;
+        let outcome = repo.cherry_pick(id(&repo, "fix")?, id(&repo, "release")?, Default::default())?;
+        let cherry_pick::Outcome::Commit(commit_id) = outcome else {
+            panic!("expected clean pick")
+        };
+
+        let commit = commit_id.object()?.into_commit();
+        let expected = repo.find_commit(id(&repo, "expected-cherry-pick")?)?;
+        assert_eq!(commit.tree_id()?, expected.tree_id()?, "the same tree as git produces it");
+        assert_eq!(
+            commit.message_raw()?,
+            expected.message_raw()?,
+            "the origin is recorded after the trailers, just like git does it"
+        );
+        assert_eq!(commit.author()?, expected.author()?, "the original author is kept");
+        assert_eq!(
+            commit.parent_ids().map(gix::Id::detach).collect::<Vec<_>>(),
+            [id(&repo, "release")?]
+        );
+        assert_eq!(repo.head_id()?, head_before, "references are untouched");
+        assert_eq!(repo.state(), None, "no state is written for clean picks");
+        Ok(())
+    }
+
+    #[test]
+    fn origin_is_added_to_trailer_blocks_with_non_trailer_lines() -> crate::Result {
+        let (repo, _tmp) = crate::repo_rw("make_cherry_pick_and_revert_repos.sh")?;
+        let outcome = repo.cherry_pick(id(&repo, "mixed-trailers")?, id(&repo, "release")?, Default::default())?;
+        let cherry_pick::Outcome::Commit(commit_id) = outcome else {
+            panic!("expected clean pick")
+        };
+        let expected = repo.find_commit(id(&repo, "expected-cherry-pick-mixed-trailers")?)?;
+        assert_eq!(
+            commit_id.object()?.into_commit().message_raw()?,
+            expected.message_raw()?,
+            "a Signed-off-by trailer makes this a trailer block for git, so no empty line is added"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn without_origin() -> crate::Result {
+        let (repo, _tmp) = crate::repo_rw("make_cherry_pick_and_revert_repos.sh")?;
+        let outcome = repo.cherry_pick(
+            id(&repo, "fix")?,
+            id(&repo, "release")?,
+            cherry_pick::Options {
+                record_origin: false,
+                ..Default::default()
+            },
+        )?;
+        let cherry_pick::Outcome::Commit(commit_id) = outcome else {
+            panic!("expected clean pick")
+        };
+        assert_eq!(
+            commit_id.object()?.into_commit().message_raw()?,
+            repo.find_commit(id(&repo, "fix")?)?.message_raw()?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn conflicts_write_state_and_message() -> crate::Result {
+        let (repo, _tmp) = crate::repo_rw("make_cherry_pick_and_revert_repos.sh")?;
+        let fix = id(&repo, "fix")?;
+        let outcome = repo.cherry_pick(fix, id(&repo, "release-conflicting")?, Default::default())?;
+        let cherry_pick::Outcome::Conflict { merge, message } = outcome else {
+            panic!("expected conflict")
+        };
+        assert_eq!(merge.conflicts.len(), 1);
+        assert_eq!(merge.conflicts[0].path, "file");
+        assert_eq!(merge.conflicts[0].kind, ConflictKind::Content);
+        assert_eq!(
+            message,
+            format!(
+                "fix the first line\n\nSigned-off-by: Contributor <contributor@example.com>\n(cherry picked from commit {fix})\n"
+            )
+        );
+
+        assert_eq!(repo.state(), Some(gix::state::InProgress::CherryPick));
+        assert_eq!(
+            std::fs::read_to_string(repo.path().join("CHERRY_PICK_HEAD"))?,
+            format!("{fix}\n")
+        );
+        assert_eq!(
+            std::fs::read_to_string(repo.path().join("MERGE_MSG"))?,
+            format!("{message}\n# Conflicts:\n#\tfile\n")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn merge_commits_need_mainline() -> crate::Result {
+        let (repo, _tmp) = crate::repo_rw("make_cherry_pick_and_revert_repos.sh")?;
+        let err = repo
+            .cherry_pick(id(&repo, "merged")?, id(&repo, "release")?, Default::default())
+            .unwrap_err();
+        assert!(matches!(err, cherry_pick::Error::MainlineMissing { .. }), "{err:?}");
+        Ok(())
+    }
+
+    #[test]
+    fn mainline_must_be_a_parent_of_the_merge_commit() -> crate::Result {
+        let (repo, _tmp) = crate::repo_rw("make_cherry_pick_and_revert_repos.sh")?;
+        let err = repo
+            .cherry_pick(
+                id(&repo, "merged")?,
+                id(&repo, "release")?,
+                cherry_pick::Options {
+                    mainline: Some(3),
+                    ..Default::default()
+                },
+            )
+            .unwrap_err();
+        assert!(
+            matches!(err, cherry_pick::Error::MainlineOutOfRange { mainline: 3, .. }),
+            "{err:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn mainline_is_rejected_for_non_merge_commits() -> crate::Result {
+        let (repo, _tmp) = crate::repo_rw("make_cherry_pick_and_revert_repos.sh")?;
+        let err = repo
+            .cherry_pick(
+                id(&repo, "fix")?,
+                id(&repo, "release")?,
+                cherry_pick::Options {
+                    mainline: Some(1),
+                    ..Default::default()
+                },
+            )
+            .unwrap_err();
+        assert!(
+            matches!(err, cherry_pick::Error::MainlineForNonMerge { .. }),
+            "like git, which fails with 'mainline was specified but commit is not a merge': {err:?}"
+        );
+        Ok(())
+    }
+}
+
+mod revert {
+    use super::*;
+
+    #[test]
+    fn clean_revert_on_top_of_head() -> crate::Result {
+        let (repo, _tmp) = crate::repo_rw("make_cherry_pick_and_revert_repos.sh")?;
+        let head_before = repo.head_id()?;
+        let fix = id(&repo, "fix")?;
+        let outcome = repo.revert(fix, Default::default())?;
+        let cherry_pick::Outcome::Commit(commit_id) = outcome else {
+            panic!("expected clean revert")
+        };
+
+        let commit = commit_id.object()?.into_commit();
+        let expected = repo.find_commit(id(&repo, "expected-revert")?)?;
+        assert_eq!(commit.tree_id()?, expected.tree_id()?, "the same tree as git produces it");
+        assert_eq!(commit.message_raw()?, expected.message_raw()?);
+        assert_eq!(
+            commit.parent_ids().map(gix::Id::detach).collect::<Vec<_>>(),
+            [head_before.detach()]
+        );
+        assert_eq!(repo.head_id()?, head_before, "references are untouched");
+        Ok(())
+    }
+}
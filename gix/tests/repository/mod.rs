@@ -1,16 +1,31 @@
 use gix::Repository;
 
+#[cfg(feature = "apply")]
+mod apply;
 mod config;
 #[cfg(feature = "excludes")]
 mod excludes;
 #[cfg(feature = "attributes")]
 mod filter;
+#[cfg(feature = "gc")]
+mod gc;
+#[cfg(feature = "merge")]
+mod merge;
 mod object;
 mod open;
 #[cfg(feature = "attributes")]
 mod pathspec;
+#[cfg(feature = "pull")]
+mod pull;
+#[cfg(feature = "apply")]
+mod range_diff;
 mod reference;
 mod remote;
+#[cfg(feature = "repack")]
+mod repack;
+#[cfg(feature = "revision")]
+mod revision;
+mod server_info;
 mod shallow;
 mod state;
 #[cfg(feature = "attributes")]
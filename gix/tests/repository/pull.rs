@@ -0,0 +1,375 @@
+use std::sync::atomic::AtomicBool;
+
+use gix::pull::{integrate, FastForward, Integration, Options, Rebase};
+
+fn repo_rw(name: &str) -> crate::Result<(gix::Repository, gix_testtools::tempfile::TempDir)> {
+    let tmp = gix_testtools::scripted_fixture_writable("make_pull_repos.sh")?;
+    let repo = gix::open_opts(tmp.path().join(name), crate::restricted())?;
+    Ok((repo, tmp))
+}
+
+fn reflog_messages(repo: &gix::Repository, name: &str) -> crate::Result<Vec<String>> {
+    Ok(repo
+        .find_reference(name)?
+        .log_iter()
+        .rev()?
+        .expect("log present")
+        .map(|line| line.map(|line| line.message.to_string()))
+        .collect::<Result<_, _>>()?)
+}
+
+fn read_file(repo: &gix::Repository, name: &str) -> std::io::Result<String> {
+    std::fs::read_to_string(repo.work_dir().expect("non-bare").join(name))
+}
+
+fn assert_clean(repo: &gix::Repository) -> crate::Result {
+    assert!(!repo.is_dirty()?, "the index and worktree match the new HEAD commit");
+    Ok(())
+}
+
+fn integrate(repo: &gix::Repository, options: Options) -> Result<Integration, integrate::Error> {
+    repo.integrate_fetch_head(&AtomicBool::default(), options)
+}
+
+#[test]
+fn fast_forward_updates_head_index_and_worktree() -> crate::Result {
+    let (repo, _tmp) = repo_rw("fast-forward")?;
+    let previous = repo.head_id()?.detach();
+    let upstream = repo.rev_parse_single("origin/main")?.detach();
+
+    let outcome = integrate(&repo, Default::default())?;
+    assert!(matches!(outcome, Integration::FastForward { previous: Some(p), new } if p == previous && new == upstream));
+    assert_eq!(repo.head_id()?, upstream);
+    assert_eq!(repo.rev_parse_single("ORIG_HEAD")?, previous);
+    assert_eq!(read_file(&repo, "new")?, "new\n", "added files are checked out");
+    assert_eq!(read_file(&repo, "file")?, "1\n2\n3\n4\nremote\n");
+    assert_clean(&repo)?;
+    assert_eq!(
+        reflog_messages(&repo, "refs/heads/main")?[0],
+        "pull: Fast-forward",
+        "the reflog message matches git"
+    );
+    assert_eq!(reflog_messages(&repo, "HEAD")?[0], "pull: Fast-forward");
+    Ok(())
+}
+
+#[test]
+fn already_contained_commits_leave_everything_untouched() -> crate::Result {
+    let (repo, _tmp) = repo_rw("ahead")?;
+    let head = repo.head_id()?.detach();
+    let outcome = integrate(&repo, Default::default())?;
+    assert!(matches!(outcome, Integration::UpToDate { head: h } if h == head));
+    assert_eq!(repo.head_id()?, head);
+    Ok(())
+}
+
+#[test]
+fn no_fast_forward_creates_merge_commit() -> crate::Result {
+    let (repo, _tmp) = repo_rw("no-fast-forward")?;
+    let previous = repo.head_id()?.detach();
+    let upstream = repo.rev_parse_single("origin/main")?.detach();
+    let Integration::Merge { commit, .. } = integrate(
+        &repo,
+        Options {
+            fast_forward: Some(FastForward::Never),
+            ..Default::default()
+        },
+    )?
+    else {
+        panic!("a merge commit is forced")
+    };
+    let commit = repo.find_commit(commit)?;
+    assert_eq!(
+        commit.parent_ids().map(gix::Id::detach).collect::<Vec<_>>(),
+        [previous, upstream]
+    );
+    assert_eq!(commit.tree_id()?, repo.find_commit(upstream)?.tree_id()?);
+    assert_clean(&repo)?;
+    Ok(())
+}
+
+#[test]
+fn only_fast_forward_fails_on_divergent_branches() -> crate::Result {
+    let (repo, _tmp) = repo_rw("diverged")?;
+    let head = repo.head_id()?.detach();
+    let err = integrate(
+        &repo,
+        Options {
+            fast_forward: Some(FastForward::Only),
+            rebase: Some(Rebase::True),
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, integrate::Error::NotPossibleToFastForward), "{err:?}");
+    assert_eq!(repo.head_id()?, head, "nothing changed");
+    Ok(())
+}
+
+#[test]
+fn divergent_branches_need_configuration() -> crate::Result {
+    let (mut repo, _tmp) = repo_rw("diverged")?;
+    let err = integrate(&repo, Default::default()).unwrap_err();
+    assert!(matches!(err, integrate::Error::DivergentBranches), "{err:?}");
+
+    repo.config_snapshot_mut()
+        .set_value(&gix::config::tree::Pull::FF, "only")?;
+    let err = integrate(&repo, Default::default()).unwrap_err();
+    assert!(
+        matches!(err, integrate::Error::NotPossibleToFastForward),
+        "pull.ff is respected: {err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn merge_divergent_branches_like_git() -> crate::Result {
+    let (mut repo, tmp) = repo_rw("diverged")?;
+    repo.config_snapshot_mut()
+        .set_value(&gix::config::tree::Pull::REBASE, "false")?;
+    let previous = repo.head_id()?.detach();
+    let Integration::Merge { commit, previous: p } = integrate(&repo, Default::default())? else {
+        panic!("branches are merged")
+    };
+    assert_eq!(p, previous);
+    assert_eq!(repo.head_id()?, commit);
+    assert_eq!(repo.rev_parse_single("ORIG_HEAD")?, previous);
+
+    let expected = gix::open_opts(tmp.path().join("diverged-git-merge"), crate::restricted())?;
+    let expected_commit = expected.head_commit()?;
+    let commit = repo.find_commit(commit)?;
+    assert_eq!(commit.tree_id()?, expected_commit.tree_id()?, "the same tree as git");
+    assert_eq!(
+        commit.message_raw()?,
+        expected_commit.message_raw()?,
+        "the same merge message as git"
+    );
+    assert_eq!(read_file(&repo, "file")?, "local\n2\n3\n4\nremote\n");
+    assert_eq!(read_file(&repo, "new")?, "new\n");
+    assert_clean(&repo)?;
+    assert_eq!(
+        reflog_messages(&repo, "refs/heads/main")?[0],
+        "pull: Merge made by the 'ort' strategy.",
+        "the same reflog message as git, which also includes its arguments"
+    );
+    Ok(())
+}
+
+#[test]
+fn rebase_divergent_branches_like_git() -> crate::Result {
+    let (mut repo, tmp) = repo_rw("diverged")?;
+    repo.config_snapshot_mut()
+        .set_subsection_value(&gix::config::tree::Branch::REBASE, "main", "true")?;
+    let previous = repo.head_id()?.detach();
+    let upstream = repo.rev_parse_single("origin/main")?.detach();
+    let Integration::Rebase { commits, onto, .. } = integrate(&repo, Default::default())? else {
+        panic!("branch.main.rebase is respected")
+    };
+    assert_eq!(onto, upstream);
+    assert_eq!(commits.len(), 1, "the local commit was replayed");
+    assert_eq!(repo.head_id()?, commits[0]);
+    assert_eq!(repo.rev_parse_single("ORIG_HEAD")?, previous);
+
+    let expected = gix::open_opts(tmp.path().join("diverged-git-rebase"), crate::restricted())?;
+    let expected_commit = expected.head_commit()?;
+    let commit = repo.find_commit(commits[0])?;
+    assert_eq!(commit.tree_id()?, expected_commit.tree_id()?, "the same tree as git");
+    assert_eq!(commit.message_raw()?, expected_commit.message_raw()?);
+    assert_eq!(commit.parent_ids().map(gix::Id::detach).collect::<Vec<_>>(), [upstream]);
+    assert_clean(&repo)?;
+    assert_eq!(
+        repo.head_name()?.expect("attached").as_bstr(),
+        "refs/heads/main",
+        "HEAD is attached to the branch again"
+    );
+    let finish = format!("pull (finish): refs/heads/main onto {upstream}");
+    assert_eq!(
+        reflog_messages(&repo, "refs/heads/main")?[0],
+        finish,
+        "the same reflog message as git, which also includes its arguments"
+    );
+    assert_eq!(
+        reflog_messages(&repo, "HEAD")?[..2],
+        [finish, "commit: local change".into()],
+        "HEAD isn't detached, so there are no start or pick entries"
+    );
+    Ok(())
+}
+
+#[test]
+fn rebase_in_shallow_repository() -> crate::Result {
+    let (repo, _tmp) = repo_rw("shallow")?;
+    assert!(repo.is_shallow());
+    let upstream = repo.rev_parse_single("origin/main")?.detach();
+    let Integration::Rebase { commits, .. } = integrate(
+        &repo,
+        Options {
+            rebase: Some(Rebase::True),
+            ..Default::default()
+        },
+    )?
+    else {
+        panic!("rebasing is forced")
+    };
+    assert_eq!(commits.len(), 1, "only the local commit is picked");
+    let commit = repo.find_commit(commits[0])?;
+    assert_eq!(commit.parent_ids().map(gix::Id::detach).collect::<Vec<_>>(), [upstream]);
+    assert_eq!(read_file(&repo, "file")?, "local\n2\n3\n4\nremote\n");
+    assert_eq!(read_file(&repo, "other")?, "remote\n");
+    assert_clean(&repo)?;
+    Ok(())
+}
+
+#[test]
+fn rebasing_local_merge_commits_fails() -> crate::Result {
+    let (repo, _tmp) = repo_rw("local-merge")?;
+    let head = repo.head_id()?.detach();
+    let err = integrate(
+        &repo,
+        Options {
+            rebase: Some(Rebase::True),
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    assert!(
+        matches!(err, integrate::Error::MergeCommitToRebase { commit } if commit == head),
+        "merge commits aren't dropped silently: {err:?}"
+    );
+    assert_eq!(repo.head_id()?, head, "nothing changed");
+    Ok(())
+}
+
+#[test]
+fn conflicts_leave_everything_untouched() -> crate::Result {
+    for rebase in [Rebase::False, Rebase::True] {
+        let (repo, _tmp) = repo_rw("conflict")?;
+        let head = repo.head_id()?.detach();
+        let outcome = integrate(
+            &repo,
+            Options {
+                rebase: Some(rebase),
+                ..Default::default()
+            },
+        )?;
+        let Integration::Conflict { picking, merge } = outcome else {
+            panic!("both sides changed the same line")
+        };
+        assert_eq!(picking, (rebase == Rebase::True).then_some(head));
+        assert_eq!(
+            merge.conflicts.iter().map(|c| c.path.to_string()).collect::<Vec<_>>(),
+            ["file"]
+        );
+        assert_eq!(repo.head_id()?, head, "HEAD is unchanged");
+        assert_eq!(repo.head_name()?.expect("attached").as_bstr(), "refs/heads/main");
+        assert!(
+            repo.try_find_reference("ORIG_HEAD")?.is_none(),
+            "ORIG_HEAD isn't written"
+        );
+        assert_eq!(repo.state(), None, "no merge or rebase state is written");
+        assert_eq!(
+            read_file(&repo, "file")?,
+            "1\n2\n3\n4\nlocal\n",
+            "no conflict markers are written"
+        );
+        assert_clean(&repo)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn local_changes_to_updated_files_prevent_integration() -> crate::Result {
+    let (repo, _tmp) = repo_rw("local-changes")?;
+    let head = repo.head_id()?.detach();
+    let err = integrate(&repo, Default::default()).unwrap_err();
+    assert!(
+        matches!(&err, integrate::Error::LocalChanges { paths } if paths == &["file"]),
+        "{err:?}"
+    );
+    assert_eq!(repo.head_id()?, head, "nothing changed");
+    assert!(
+        read_file(&repo, "file")?.ends_with("modified\n"),
+        "local changes are kept"
+    );
+    Ok(())
+}
+
+#[test]
+fn staged_changes_to_other_files_are_kept() -> crate::Result {
+    let (repo, _tmp) = repo_rw("staged-changes")?;
+    let upstream = repo.rev_parse_single("origin/main")?.detach();
+    let outcome = integrate(&repo, Default::default())?;
+    assert!(matches!(outcome, Integration::FastForward { new, .. } if new == upstream));
+    assert_eq!(read_file(&repo, "new")?, "new\n");
+
+    let index = repo.index()?;
+    for (path, expected) in [("other", "staged\n"), ("staged", "added\n"), ("new", "new\n")] {
+        let entry = index.entry_by_path(path.into()).expect("present");
+        assert_eq!(
+            repo.find_object(entry.id)?.data,
+            expected.as_bytes(),
+            "untouched paths keep their staged content, and touched ones are updated"
+        );
+    }
+    assert!(index.tree().is_none(), "the tree cache doesn't match anymore");
+    Ok(())
+}
+
+#[test]
+fn rebasing_requires_clean_index_and_worktree() -> crate::Result {
+    for (name, expected) in [
+        ("staged-changes", &["other", "staged"][..]),
+        ("local-changes", &["file"]),
+    ] {
+        let (repo, _tmp) = repo_rw(name)?;
+        let head = repo.head_id()?.detach();
+        let err = integrate(
+            &repo,
+            Options {
+                rebase: Some(Rebase::True),
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+        assert!(
+            matches!(&err, integrate::Error::UncommittedChanges { paths } if paths == expected),
+            "even fast-forwards fail, just like in git: {err:?}"
+        );
+        assert_eq!(repo.head_id()?, head, "nothing changed");
+    }
+    Ok(())
+}
+
+#[test]
+fn untracked_files_in_the_way_prevent_integration() -> crate::Result {
+    let (repo, _tmp) = repo_rw("untracked")?;
+    let head = repo.head_id()?.detach();
+    let err = integrate(&repo, Default::default()).unwrap_err();
+    assert!(
+        matches!(&err, integrate::Error::UntrackedFiles { paths } if paths == &["new"]),
+        "{err:?}"
+    );
+    assert_eq!(repo.head_id()?, head, "nothing changed");
+    assert_eq!(read_file(&repo, "new")?, "untracked\n");
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "blocking-network-client")]
+fn pull_fetches_and_integrates_the_upstream_branch() -> crate::Result {
+    let (mut repo, tmp) = repo_rw("diverged-git-merge")?;
+    let remote_dir = tmp.path().join("remote");
+    repo.config_snapshot_mut().set_subsection_value(
+        &gix::config::tree::Remote::URL,
+        "origin",
+        gix::path::into_bstr(remote_dir.as_path()).as_ref(),
+    )?;
+    let before = repo.head_id()?.detach();
+    let outcome = repo.pull(gix::progress::Discard, &AtomicBool::default(), Default::default())?;
+    assert!(
+        matches!(outcome.integration, Integration::UpToDate { head } if head == before),
+        "git already merged the upstream branch"
+    );
+    assert!(repo.fetch_head()?.is_some(), "FETCH_HEAD was written");
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use gix::{
+    bstr::ByteSlice,
+    range_diff::{Options, Status},
+};
+
+fn repo() -> crate::Result<gix::Repository> {
+    crate::util::named_repo("make_range_diff_repo.sh")
+}
+
+fn id(repo: &gix::Repository, spec: &str) -> crate::Result<gix::ObjectId> {
+    Ok(repo.rev_parse_single(spec)?.detach())
+}
+
+fn range_diff(
+    repo: &gix::Repository,
+    old: &str,
+    new: &str,
+    creation_factor: u32,
+) -> crate::Result<gix::range_diff::Outcome> {
+    let (old_start, old_end) = old.split_once("..").expect("range");
+    let (new_start, new_end) = new.split_once("..").expect("range");
+    Ok(repo.range_diff(
+        id(repo, old_start)?..id(repo, old_end)?,
+        id(repo, new_start)?..id(repo, new_end)?,
+        Options { creation_factor },
+    )?)
+}
+
+#[test]
+fn output_matches_git() -> crate::Result {
+    let repo = repo()?;
+    let merge_base = repo
+        .merge_base(id(&repo, "v1")?, id(&repo, "v2")?)?
+        .detach()
+        .to_string();
+    let symmetric_old = format!("{merge_base}..v1");
+    let symmetric_new = format!("{merge_base}..v2");
+    for (baseline, old, new, creation_factor) in [
+        ("range-diff.baseline", "base..v1", "base..v2", 60),
+        ("range-diff-creation-factor-100.baseline", "base..v1", "base..v2", 100),
+        ("range-diff-creation-factor-0.baseline", "base..v1", "base..v2", 0),
+        (
+            "range-diff-symmetric.baseline",
+            symmetric_old.as_str(),
+            symmetric_new.as_str(),
+            60,
+        ),
+        ("range-diff-v2-v3.baseline", "base..v2", "base..v3", 200),
+    ] {
+        let outcome = range_diff(&repo, old, new, creation_factor)?;
+        let mut actual = Vec::new();
+        outcome.write_to(&mut actual)?;
+        let expected = std::fs::read(repo.work_dir().expect("non-bare").join(baseline))?;
+        assert_eq!(actual.as_bstr(), expected.as_bstr(), "{baseline}");
+    }
+    Ok(())
+}
+
+#[test]
+fn pairs_refer_to_commits_of_both_series() -> crate::Result {
+    let repo = repo()?;
+    let outcome = range_diff(&repo, "base..v1", "base..v2", 60)?;
+    assert_eq!(outcome.old.len(), 4);
+    assert_eq!(outcome.new.len(), 5);
+    assert_eq!(
+        outcome
+            .pairs
+            .iter()
+            .map(|pair| (pair.old, pair.new, pair.status))
+            .collect::<Vec<_>>(),
+        [
+            (Some(0), Some(0), Status::Unchanged),
+            (Some(1), Some(1), Status::Changed),
+            (Some(2), None, Status::Removed),
+            (Some(3), Some(2), Status::Unchanged),
+            (None, Some(3), Status::Added),
+            (None, Some(4), Status::Added),
+        ]
+    );
+    assert_eq!(outcome.new[1].id, id(&repo, "v2~3")?);
+    assert_eq!(outcome.new[1].subject, "add a new file");
+    assert!(
+        outcome.new[1]
+            .patch
+            .diff()
+            .starts_with(b" ## new-file (new) ##\n@@\n+1\n"),
+        "the diff of a patch starts at the section of the first file"
+    );
+
+    let outcome = range_diff(&repo, "v1..v1", "base..v2", 60)?;
+    assert!(outcome.old.is_empty());
+    assert!(outcome.pairs.iter().all(|pair| pair.status == Status::Added));
+    Ok(())
+}
@@ -350,3 +350,291 @@ mod find_default_remote {
         Ok(())
     }
 }
+
+mod manage {
+    use gix::remote::{manage, Direction};
+
+    fn repo_rw(name: &str) -> crate::Result<(gix::Repository, gix_testtools::tempfile::TempDir)> {
+        let tmp = gix_testtools::scripted_fixture_writable("make_remote_manage_repos.sh")?;
+        let repo = gix::open_opts(tmp.path().join(name), crate::restricted())?;
+        Ok((repo, tmp))
+    }
+
+    fn local_config(repo: &gix::Repository) -> std::io::Result<String> {
+        std::fs::read_to_string(repo.common_dir().join("config"))
+    }
+
+    fn expected_config(tmp: &gix_testtools::tempfile::TempDir, name: &str) -> std::io::Result<String> {
+        std::fs::read_to_string(tmp.path().join(name).join(".git").join("config"))
+    }
+
+    fn in_memory_local_config(repo: &gix::Repository) -> std::io::Result<String> {
+        let mut out = Vec::new();
+        for section in repo
+            .config_snapshot()
+            .plumbing()
+            .sections()
+            .filter(|section| section.meta().source == gix::config::Source::Local)
+        {
+            section.write_to(&mut out)?;
+        }
+        Ok(String::from_utf8(out).expect("valid UTF-8"))
+    }
+
+    fn remote_refs(repo: &gix::Repository) -> crate::Result<Vec<String>> {
+        let mut out = Vec::new();
+        for reference in repo.references()?.prefixed("refs/remotes/")? {
+            let reference = reference?;
+            out.push(match reference.target() {
+                gix_ref::TargetRef::Symbolic(target) => {
+                    format!("{} -> {}", reference.name().as_bstr(), target.as_bstr())
+                }
+                gix_ref::TargetRef::Object(_) => reference.name().as_bstr().to_string(),
+            });
+        }
+        Ok(out)
+    }
+
+    #[test]
+    fn add() -> crate::Result {
+        let (mut repo, tmp) = repo_rw("clone")?;
+        let remote = repo.remote_add("added", "../base")?;
+        assert_eq!(remote.name().expect("named").as_bstr(), "added");
+        assert_eq!(
+            remote.url(Direction::Fetch).expect("set").to_bstring(),
+            "../base",
+            "the in-memory configuration is updated as well"
+        );
+        assert_eq!(
+            local_config(&repo)?,
+            expected_config(&tmp, "git-added")?,
+            "it's the same as git does it"
+        );
+
+        let err = repo.remote_add("added", "other").unwrap_err();
+        assert!(matches!(err, manage::add::Error::Exists { .. }), "{err:?}");
+        let err = repo.remote_add("invalid:name", "other").unwrap_err();
+        assert!(matches!(err, manage::add::Error::Name(_)), "{err:?}");
+        Ok(())
+    }
+
+    #[test]
+    fn rename() -> crate::Result {
+        let (mut repo, tmp) = repo_rw("clone")?;
+        let main_id = repo.find_reference("refs/remotes/origin/main")?.id().detach();
+        repo.remote_rename("origin".into(), "renamed")?;
+
+        assert_eq!(
+            local_config(&repo)?,
+            expected_config(&tmp, "git-renamed")?,
+            "it's the same as git does it"
+        );
+        let expected = gix::open_opts(tmp.path().join("git-renamed"), crate::restricted())?;
+        assert_eq!(
+            remote_refs(&repo)?,
+            remote_refs(&expected)?,
+            "packed and loose refs are moved"
+        );
+        assert_eq!(
+            remote_refs(&repo)?[0],
+            "refs/remotes/renamed/HEAD -> refs/remotes/renamed/main",
+            "symbolic refs are adjusted"
+        );
+        assert_eq!(repo.find_reference("refs/remotes/renamed/main")?.id(), main_id);
+        assert_eq!(
+            repo.find_reference("refs/remotes/renamed/main")?
+                .log_iter()
+                .rev()?
+                .expect("log present")
+                .next()
+                .expect("one line")?
+                .message,
+            "remote: renamed refs/remotes/origin/main to refs/remotes/renamed/main"
+        );
+
+        assert!(
+            repo.try_find_remote("origin").is_none(),
+            "the in-memory configuration is updated"
+        );
+        assert_eq!(
+            repo.find_remote("renamed")?.refspecs(Direction::Fetch)[0]
+                .to_ref()
+                .to_bstring(),
+            "+refs/heads/*:refs/remotes/renamed/*"
+        );
+        assert_eq!(
+            repo.branch_remote_name("main", Direction::Fetch)
+                .expect("set")
+                .as_bstr(),
+            "renamed"
+        );
+
+        let err = repo.remote_rename("renamed".into(), "upstream").unwrap_err();
+        assert!(matches!(err, manage::rename::Error::Exists { .. }), "{err:?}");
+        let err = repo.remote_rename("origin".into(), "new").unwrap_err();
+        assert!(matches!(err, manage::rename::Error::NotFound { .. }), "{err:?}");
+        assert_eq!(
+            in_memory_local_config(&repo)?,
+            local_config(&repo)?,
+            "the in-memory configuration matches what was written"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rename_changes_nothing_if_references_cannot_be_moved() -> crate::Result {
+        let (mut repo, _tmp) = repo_rw("clone")?;
+        let other_id = repo.head_commit()?.tree_id()?.detach();
+        repo.reference(
+            "refs/remotes/renamed/main",
+            other_id,
+            gix_ref::transaction::PreviousValue::MustNotExist,
+            "in the way",
+        )?;
+        let config_before = local_config(&repo)?;
+        let refs_before = remote_refs(&repo)?;
+
+        let err = repo.remote_rename("origin".into(), "renamed").unwrap_err();
+        assert!(matches!(err, manage::rename::Error::EditReferences(_)), "{err:?}");
+        assert_eq!(
+            local_config(&repo)?,
+            config_before,
+            "the configuration file is unchanged"
+        );
+        assert_eq!(remote_refs(&repo)?, refs_before, "references are unchanged as well");
+        assert!(
+            repo.find_remote("origin").is_ok() && repo.try_find_remote("renamed").is_none(),
+            "the in-memory configuration is unchanged"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn remove() -> crate::Result {
+        let (mut repo, tmp) = repo_rw("clone")?;
+        repo.remote_remove("origin".into())?;
+
+        assert_eq!(
+            local_config(&repo)?,
+            expected_config(&tmp, "git-removed")?,
+            "it's the same as git does it"
+        );
+        let expected = gix::open_opts(tmp.path().join("git-removed"), crate::restricted())?;
+        assert_eq!(
+            remote_refs(&repo)?,
+            remote_refs(&expected)?,
+            "remote tracking branches are deleted"
+        );
+        assert!(
+            repo.try_find_remote("origin").is_none(),
+            "the in-memory configuration is updated"
+        );
+        assert!(repo.branch_remote_name("main", Direction::Fetch).is_none());
+        assert!(repo.find_remote("upstream").is_ok(), "other remotes are kept");
+
+        let err = repo.remote_remove("origin".into()).unwrap_err();
+        assert!(matches!(err, manage::remove::Error::NotFound { .. }), "{err:?}");
+        assert_eq!(
+            in_memory_local_config(&repo)?,
+            local_config(&repo)?,
+            "the in-memory configuration matches what was written"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn remove_keeps_references_written_by_other_remotes() -> crate::Result {
+        let (mut repo, tmp) = repo_rw("shared")?;
+        repo.remote_remove("origin".into())?;
+
+        assert_eq!(
+            local_config(&repo)?,
+            expected_config(&tmp, "git-removed-shared")?,
+            "it's the same as git does it"
+        );
+        let expected = gix::open_opts(tmp.path().join("git-removed-shared"), crate::restricted())?;
+        let refs = remote_refs(&repo)?;
+        assert_eq!(refs, remote_refs(&expected)?, "it's the same as git does it");
+        assert!(
+            refs.iter().any(|name| name == "refs/remotes/origin/main"),
+            "the remote `mirror` also fetches into it"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn set_url() -> crate::Result {
+        let (mut repo, tmp) = repo_rw("clone")?;
+        repo.remote_set_url("origin".into(), "../other", Direction::Fetch)?;
+        repo.remote_set_url("upstream".into(), "../upstream-push", Direction::Push)?;
+
+        assert_eq!(
+            local_config(&repo)?,
+            expected_config(&tmp, "git-set-url")?,
+            "it's the same as git does it"
+        );
+        let upstream = repo.find_remote("upstream")?;
+        assert_eq!(upstream.url(Direction::Fetch).expect("set").to_bstring(), "../base");
+        assert_eq!(
+            upstream.url(Direction::Push).expect("set").to_bstring(),
+            "../upstream-push"
+        );
+
+        let err = repo
+            .remote_set_url("missing".into(), "../other", Direction::Fetch)
+            .unwrap_err();
+        assert!(matches!(err, manage::set_url::Error::NotFound { .. }), "{err:?}");
+        Ok(())
+    }
+
+    #[test]
+    fn set_head() -> crate::Result {
+        let (mut repo, tmp) = repo_rw("clone")?;
+        repo.remote_set_head("upstream".into(), Some("other".into()))?;
+        repo.remote_set_head("origin".into(), None)?;
+
+        let expected = gix::open_opts(tmp.path().join("git-set-head"), crate::restricted())?;
+        assert_eq!(
+            remote_refs(&repo)?,
+            remote_refs(&expected)?,
+            "it's the same as git does it"
+        );
+
+        repo.remote_set_head("origin".into(), None)?;
+        let err = repo
+            .remote_set_head("origin".into(), Some("missing".into()))
+            .unwrap_err();
+        assert!(
+            matches!(err, manage::set_head::Error::TrackingBranchNotFound { .. }),
+            "{err:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn set_branches() -> crate::Result {
+        let (mut repo, tmp) = repo_rw("clone")?;
+        repo.remote_set_branches("origin".into(), ["main"], false)?;
+        repo.remote_set_branches("origin".into(), ["feature/*"], true)?;
+        repo.remote_set_branches("upstream".into(), ["other"], false)?;
+
+        assert_eq!(
+            local_config(&repo)?,
+            expected_config(&tmp, "git-set-branches")?,
+            "it's the same as git does it"
+        );
+        assert_eq!(
+            repo.find_remote("origin")?
+                .refspecs(Direction::Fetch)
+                .iter()
+                .map(|spec| spec.to_ref().to_bstring())
+                .collect::<Vec<_>>(),
+            [
+                "+refs/heads/feature/*:refs/remotes/origin/feature/*",
+                "+refs/heads/main:refs/remotes/origin/main"
+            ],
+            "the in-memory configuration is updated"
+        );
+        Ok(())
+    }
+}
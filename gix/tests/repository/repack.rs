@@ -0,0 +1,150 @@
+use std::{collections::BTreeSet, path::PathBuf, sync::atomic::AtomicBool};
+
+use gix::{
+    objs::Exists,
+    repack::{Mode, Options},
+};
+
+fn pack_indices(repo: &gix::Repository) -> crate::Result<BTreeSet<PathBuf>> {
+    let mut indices = BTreeSet::new();
+    for entry in std::fs::read_dir(repo.objects.store_ref().path().join("pack"))? {
+        let path = entry?.path();
+        if path.extension() == Some("idx".as_ref()) {
+            indices.insert(path);
+        }
+    }
+    Ok(indices)
+}
+
+fn all_objects(repo: &gix::Repository) -> crate::Result<BTreeSet<gix::ObjectId>> {
+    Ok(repo.objects.iter()?.collect::<Result<_, _>>()?)
+}
+
+fn repack(repo: &gix::Repository, options: Options) -> crate::Result<gix::repack::Outcome> {
+    Ok(repo.repack(&mut gix::progress::Discard, &AtomicBool::default(), options)?)
+}
+
+#[test]
+fn geometric_consolidates_small_packs_only() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_repo_with_many_packs.sh")?;
+    let objects_before = all_objects(&repo)?;
+    let packs_before = pack_indices(&repo)?;
+    assert_eq!(packs_before.len(), 5, "one large pack, three small ones and one kept pack");
+
+    let other_reader = repo.clone();
+    assert!(other_reader.objects.exists(objects_before.first().expect("objects present")));
+
+    let outcome = repack(&repo, Options::default())?;
+    assert_eq!(outcome.consolidated_packs.len(), 3, "the large and the kept pack remain");
+    assert_eq!(outcome.num_objects, 3 * 3, "each small pack has a commit, a tree and a blob");
+    assert!(outcome.retained_packs.is_empty(), "all redundant packs could be removed");
+    assert_eq!(outcome.multi_pack_index_path, None, "there was no multi-pack index to update");
+
+    let packs_after = pack_indices(&repo)?;
+    assert_eq!(packs_after.len(), 3);
+    assert!(packs_after.contains(outcome.index_path.as_ref().expect("new pack")));
+    for consolidated in &outcome.consolidated_packs {
+        assert!(!consolidated.exists() && !consolidated.with_extension("pack").exists());
+    }
+
+    for id in &objects_before {
+        assert!(
+            other_reader.objects.exists(id),
+            "readers that were active before find all objects in the new pack"
+        );
+    }
+    let repo = gix::open_opts(repo.git_dir(), crate::restricted())?;
+    assert_eq!(all_objects(&repo)?, objects_before, "no object was lost");
+
+    let outcome = repack(&repo, Options::default())?;
+    assert_eq!(outcome.index_path, None, "the packs form a geometric progression now");
+    assert_eq!(pack_indices(&repo)?, packs_after, "nothing changed");
+    Ok(())
+}
+
+#[test]
+fn all_with_multi_pack_index() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_repo_with_many_packs.sh")?;
+    let objects_before = all_objects(&repo)?;
+
+    let outcome = repack(
+        &repo,
+        Options {
+            mode: Mode::All,
+            write_multi_pack_index: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.consolidated_packs.len(), 4, "all but the kept pack");
+    assert_eq!(pack_indices(&repo)?.len(), 2, "the kept pack and the new one");
+
+    let multi_index_path = outcome.multi_pack_index_path.expect("written on request");
+    let multi_index = gix::odb::pack::multi_index::File::at(&multi_index_path)?;
+    assert_eq!(multi_index.num_indices(), 2, "it covers all packs");
+    assert_eq!(multi_index.num_objects() as usize, objects_before.len());
+
+    let repo = gix::open_opts(repo.git_dir(), crate::restricted())?;
+    assert_eq!(all_objects(&repo)?, objects_before, "no object was lost");
+    for id in &objects_before {
+        assert!(repo.objects.exists(id));
+    }
+
+    let outcome = repack(&repo, Options::default())?;
+    assert_eq!(outcome.index_path, None, "there is nothing to consolidate");
+    assert_eq!(outcome.multi_pack_index_path, None, "an unchanged multi-pack index isn't rewritten");
+    Ok(())
+}
+
+#[test]
+fn batch_size_needs_enough_small_packs() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_repo_with_many_packs.sh")?;
+    let packs_before = pack_indices(&repo)?;
+
+    let outcome = repack(
+        &repo,
+        Options {
+            mode: Mode::BatchSize(u64::MAX),
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.index_path, None, "all packs together are smaller than the batch size");
+    assert_eq!(pack_indices(&repo)?, packs_before);
+
+    let largest_pack_size = packs_before
+        .iter()
+        .map(|index| std::fs::metadata(index.with_extension("pack")).map(|md| md.len()))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .max()
+        .expect("packs present");
+    let outcome = repack(
+        &repo,
+        Options {
+            mode: Mode::BatchSize(largest_pack_size),
+            ..Default::default()
+        },
+    )?;
+    assert!(
+        outcome.consolidated_packs.len() >= 2,
+        "enough small packs are combined to reach the size of the largest pack, which is left alone"
+    );
+    assert!(outcome.retained_packs.is_empty());
+    Ok(())
+}
+
+#[test]
+fn geometric_factor_must_be_at_least_two() -> crate::Result {
+    let repo = crate::named_repo("make_repo_with_many_packs.sh")?;
+    let err = repo
+        .repack(
+            &mut gix::progress::Discard,
+            &AtomicBool::default(),
+            Options {
+                mode: Mode::Geometric { factor: 1 },
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(err, gix::repack::Error::InvalidGeometricFactor { factor: 1 }));
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use gix::bstr::ByteSlice;
+
+use crate::util::named_repo;
+
+fn baseline(repo: &gix::Repository, name: &str) -> crate::Result<Vec<(gix::ObjectId, String)>> {
+    let baseline = std::fs::read(repo.work_dir().expect("non-bare").join(name))?;
+    baseline
+        .lines()
+        .map(|line| {
+            let (id, rest) = line.split_once_str(" ").unwrap_or((line, b""));
+            Ok((gix::ObjectId::from_hex(id)?, rest.to_str()?.to_owned()))
+        })
+        .collect()
+}
+
+mod name_rev {
+    use super::baseline;
+    use crate::util::named_repo;
+
+    fn assert_baseline(
+        name: &str,
+        configure: impl Fn(gix::revision::name_rev::Platform<'_>) -> gix::revision::name_rev::Platform<'_>,
+    ) -> crate::Result {
+        let repo = named_repo("make_name_rev_repo.sh")?;
+        let expected = baseline(&repo, &format!("name-rev-{name}.baseline"))?;
+        assert_eq!(expected.len(), 9, "all commits are covered");
+        for (id, expected) in &expected {
+            let actual = configure(repo.name_rev()).name(*id)?;
+            assert_eq!(
+                actual.map_or_else(|| "undefined".into(), |name| name.to_string()),
+                *expected,
+                "{name}: {id}"
+            );
+        }
+
+        let names = configure(repo.name_rev()).names(expected.iter().map(|(id, _)| *id))?;
+        for (id, expected) in &expected {
+            assert_eq!(
+                names
+                    .get(id)
+                    .map_or_else(|| "undefined".into(), |name| name.to_string()),
+                *expected,
+                "{name}: {id} (batch)"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn all_refs() -> crate::Result {
+        assert_baseline("all", |p| p)
+    }
+
+    #[test]
+    fn tags_only() -> crate::Result {
+        assert_baseline("tags", |p| p.tags_only(true))
+    }
+
+    #[test]
+    fn matching() -> crate::Result {
+        assert_baseline("matching", |p| p.matching(["v1*", "feature/*"]))
+    }
+
+    #[test]
+    fn excluding() -> crate::Result {
+        assert_baseline("excluding", |p| p.excluding(["v1.1", "main"]))
+    }
+
+    #[test]
+    fn all_names_every_reachable_commit() -> crate::Result {
+        let repo = named_repo("make_name_rev_repo.sh")?;
+        let expected = baseline(&repo, "name-rev-all.baseline")?;
+        let names = repo.name_rev().all(true).names(None::<gix::ObjectId>)?;
+        for (id, expected) in &expected {
+            assert_eq!(names.get(id).expect("all are reachable").to_string(), *expected);
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn refs_containing() -> crate::Result {
+    let repo = named_repo("make_name_rev_repo.sh")?;
+    let expected = baseline(&repo, "contains.baseline")?;
+    assert_eq!(expected.len(), 9, "all commits are covered");
+
+    let commit_graph = repo.commit_graph_if_enabled()?;
+    assert!(commit_graph.is_some(), "generation numbers are used");
+    let mut cache = gix_revision::contains::Cache::new(repo.revision_graph(commit_graph.as_ref()));
+    for (id, expected) in expected {
+        let actual: Vec<_> = repo
+            .refs_containing(id)?
+            .iter()
+            .map(|r| r.name().as_bstr().to_string())
+            .collect();
+        assert_eq!(actual.join(" "), expected, "{id}");
+
+        let actual: Vec<_> = repo
+            .refs_containing_with_cache(id, &mut cache)?
+            .iter()
+            .map(|r| r.name().as_bstr().to_string())
+            .collect();
+        assert_eq!(actual.join(" "), expected, "{id} (cached)");
+    }
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use crate::util::restricted;
+
+#[test]
+fn update_server_info_writes_the_same_files_as_git() -> crate::Result {
+    let tmp = gix_testtools::scripted_fixture_writable("make_dumb_http_repos.sh")?;
+    let repo = gix::open_opts(tmp.path().join("base"), restricted())?;
+    let refs_path = repo.git_dir().join("info").join("refs");
+    let packs_path = repo.objects.store_ref().path().join("info").join("packs");
+    std::fs::remove_file(&refs_path)?;
+    std::fs::remove_file(&packs_path)?;
+
+    repo.update_server_info()?;
+    assert_eq!(
+        std::fs::read(&refs_path)?,
+        std::fs::read(tmp.path().join("expected-info-refs"))?,
+        "all refs are listed in order, with annotated tags being peeled"
+    );
+    assert_eq!(
+        std::fs::read(&packs_path)?,
+        std::fs::read(tmp.path().join("expected-objects-info-packs"))?
+    );
+
+    repo.update_server_info()?;
+    assert_eq!(
+        std::fs::read(&refs_path)?,
+        std::fs::read(tmp.path().join("expected-info-refs"))?,
+        "existing files are replaced"
+    );
+    Ok(())
+}
@@ -0,0 +1,255 @@
+use gix::{
+    revision::walk::{Pattern, Simplification, Sorting},
+    ObjectId,
+};
+
+fn repo() -> crate::Result<gix::Repository> {
+    crate::util::named_repo("make_log_filter_repo.sh")
+}
+
+fn baseline(repo: &gix::Repository, name: &str) -> crate::Result<Vec<ObjectId>> {
+    let path = repo.work_dir().expect("non-bare").join(format!("{name}.baseline"));
+    Ok(std::fs::read_to_string(path)?
+        .lines()
+        .map(|line| ObjectId::from_hex(line.split(' ').next().expect("id").as_bytes()))
+        .collect::<Result<_, _>>()?)
+}
+
+fn walk<'repo>(repo: &'repo gix::Repository, tip: &str) -> crate::Result<gix::revision::walk::Platform<'repo>> {
+    Ok(repo
+        .rev_walk(Some(repo.rev_parse_single(tip)?.detach()))
+        .sorting(Sorting::ByCommitTimeNewestFirst))
+}
+
+fn ids(walk: gix::revision::walk::Platform<'_>) -> crate::Result<Vec<ObjectId>> {
+    Ok(walk
+        .all()?
+        .map(|info| info.map(|info| info.id))
+        .collect::<Result<_, _>>()?)
+}
+
+#[test]
+fn paths_with_history_simplification() -> crate::Result {
+    let repo = repo()?;
+    for (name, simplification, paths) in [
+        ("default", Simplification::Default, &["foo"][..]),
+        ("full-history", Simplification::FullHistory, &["foo"]),
+        ("simplify-merges", Simplification::SimplifyMerges, &["foo"]),
+        ("multiple-paths", Simplification::Default, &["foo", "side"]),
+    ] {
+        let actual = ids(walk(&repo, "refs/tags/Q")?
+            .paths(paths.iter().copied())
+            .simplification(simplification))?;
+        assert_eq!(actual, baseline(&repo, name)?, "{name}");
+    }
+
+    let actual = ids(walk(&repo, "refs/tags/Q")?.first_parent_only().paths(Some("foo")))?;
+    assert_eq!(actual, baseline(&repo, "first-parent")?);
+    Ok(())
+}
+
+#[test]
+fn simplify_merges_rewrites_parents() -> crate::Result {
+    let repo = repo()?;
+    let expected = std::fs::read_to_string(
+        repo.work_dir()
+            .expect("non-bare")
+            .join("simplify-merges-parents.baseline"),
+    )?;
+    let actual: Vec<_> = walk(&repo, "refs/tags/Q")?
+        .paths(Some("foo"))
+        .simplification(Simplification::SimplifyMerges)
+        .all()?
+        .map(|info| {
+            info.map(|info| {
+                std::iter::once(info.id)
+                    .chain(info.parent_ids.iter().copied())
+                    .map(|id| id.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    assert_eq!(actual, expected.lines().map(str::trim_end).collect::<Vec<_>>());
+    Ok(())
+}
+
+#[test]
+fn ancestry_path() -> crate::Result {
+    let repo = repo()?;
+    let bottom = repo.rev_parse_single("refs/tags/D")?.detach();
+    let actual = ids(walk(&repo, "refs/tags/Q")?
+        .with_pruned(Some(bottom))
+        .ancestry_path(true))?;
+    assert_eq!(actual, baseline(&repo, "ancestry-path")?);
+
+    let err = walk(&repo, "refs/tags/Q")?
+        .ancestry_path(true)
+        .all()?
+        .next()
+        .expect("an error")
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        gix::revision::walk::iter::Error::AncestryPathWithoutBottom
+    ));
+    Ok(())
+}
+
+#[test]
+fn merges_and_no_merges() -> crate::Result {
+    let repo = repo()?;
+    assert_eq!(
+        ids(walk(&repo, "refs/tags/Q")?.min_parents(2))?,
+        baseline(&repo, "merges")?
+    );
+    assert_eq!(
+        ids(walk(&repo, "refs/tags/Q")?.max_parents(1))?,
+        baseline(&repo, "no-merges")?
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "blob-diff")]
+fn follow_renames() -> crate::Result {
+    let repo = repo()?;
+    assert_eq!(
+        ids(walk(&repo, "renames")?.follow("new-name"))?,
+        baseline(&repo, "follow")?
+    );
+    assert_eq!(
+        ids(walk(&repo, "renames")?.paths(Some("new-name")))?,
+        baseline(&repo, "no-follow")?
+    );
+    Ok(())
+}
+
+#[test]
+fn signatures_and_messages() -> crate::Result {
+    let repo = repo()?;
+    let fixed = |text: &str| Pattern::fixed(text, false);
+    for (name, walk) in [
+        ("author", walk(&repo, "renames")?.author(fixed("Alice"))),
+        ("committer", walk(&repo, "renames")?.committer(fixed("charlie"))),
+        ("grep", walk(&repo, "renames")?.grep(fixed("Fixes"))),
+        (
+            "grep-multiple",
+            walk(&repo, "renames")?.grep(fixed("#1")).grep(fixed("#2")),
+        ),
+        (
+            "grep-all-match",
+            walk(&repo, "renames")?
+                .grep(fixed("Fixes"))
+                .grep(fixed("#3"))
+                .all_match(true),
+        ),
+        (
+            "grep-ignore-case",
+            walk(&repo, "renames")?.grep(Pattern::fixed("FIXES", true)),
+        ),
+        (
+            "invert-grep",
+            walk(&repo, "renames")?.grep(fixed("Fixes")).invert_grep(true),
+        ),
+        (
+            "author-and-grep",
+            walk(&repo, "renames")?.author(fixed("Alice")).grep(fixed("Fixes")),
+        ),
+        (
+            "author-and-committer",
+            walk(&repo, "renames")?.author(fixed("Bob")).committer(fixed("Charlie")),
+        ),
+        (
+            "since-until",
+            walk(&repo, "renames")?.since(1112912773).until(1112912833),
+        ),
+    ] {
+        assert_eq!(ids(walk)?, baseline(&repo, name)?, "{name}");
+    }
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "revparse-regex")]
+fn regex_patterns() -> crate::Result {
+    let repo = repo()?;
+    assert_eq!(
+        ids(walk(&repo, "renames")?.author(Pattern::regex("^B.*r <bob@", false)?))?,
+        baseline(&repo, "author-regex")?
+    );
+    Ok(())
+}
+
+#[test]
+fn topological_sortings() -> crate::Result {
+    let repo = repo()?;
+    for use_commit_graph in [false, true] {
+        for (name, sorting) in [
+            ("topo-order", Sorting::TopoOrder),
+            ("date-order", Sorting::DateOrder),
+            ("author-date-order", Sorting::AuthorDateOrder),
+        ] {
+            let actual = ids(walk(&repo, "author-dates")?
+                .sorting(sorting)
+                .use_commit_graph(use_commit_graph))?;
+            assert_eq!(
+                actual,
+                baseline(&repo, name)?,
+                "{name}, commit-graph: {use_commit_graph}"
+            );
+        }
+
+        let bottom = repo.rev_parse_single("refs/tags/D")?.detach();
+        let actual = ids(walk(&repo, "refs/tags/Q")?
+            .sorting(Sorting::TopoOrder)
+            .use_commit_graph(use_commit_graph)
+            .with_pruned(Some(bottom)))?;
+        assert_eq!(
+            actual,
+            baseline(&repo, "topo-order-range")?,
+            "pruned commits are excluded with all of their ancestors, without cutoff"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn boundary() -> crate::Result {
+    let repo = repo()?;
+    let bottom = repo.rev_parse_single("refs/tags/D")?.detach();
+    for sorting in [Sorting::TopoOrder, Sorting::DateOrder] {
+        let commits = walk(&repo, "refs/tags/Q")?
+            .sorting(sorting)
+            .with_pruned(Some(bottom))
+            .boundary(true)
+            .all()?
+            .collect::<Result<Vec<_>, _>>()?;
+        if matches!(sorting, Sorting::TopoOrder) {
+            assert_eq!(
+                commits.iter().map(|info| info.id).collect::<Vec<_>>(),
+                baseline(&repo, "boundary")?
+            );
+        }
+        assert_eq!(
+            commits.iter().filter(|info| info.boundary).count(),
+            2,
+            "the pruned commit and the root that it shares with the side branches"
+        );
+        assert!(
+            commits
+                .iter()
+                .skip_while(|info| !info.boundary)
+                .all(|info| info.boundary),
+            "boundary commits come last"
+        );
+    }
+
+    let actual = ids(walk(&repo, "refs/tags/Q")?.boundary(true))?;
+    assert_eq!(
+        actual,
+        ids(walk(&repo, "refs/tags/Q")?)?,
+        "without pruned commits, there is no boundary"
+    );
+    Ok(())
+}
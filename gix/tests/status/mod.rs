@@ -131,8 +131,104 @@ mod index_worktree {
     }
 }
 
+mod iter {
+    use crate::status::repo;
+    use gix::status::plumbing::tree_index::Change;
+    use gix::status::Item;
+
+    #[test]
+    fn staged_changes_come_first() -> crate::Result {
+        let repo = repo("porcelain-v2")?;
+        let items: Vec<_> = repo
+            .status(gix::progress::Discard)?
+            .index_worktree_options_mut(|opts| {
+                opts.sorting =
+                    Some(gix::status::plumbing::index_as_worktree_with_renames::Sorting::ByPathCaseSensitive);
+            })
+            .into_iter(None)?
+            .collect::<Result<_, _>>()?;
+        let staged: Vec<_> = items
+            .iter()
+            .map_while(|item| match item {
+                Item::TreeIndex(change) => Some(change),
+                Item::IndexWorktree(_) => None,
+            })
+            .collect();
+        assert_eq!(
+            staged
+                .iter()
+                .map(|change| change.location().to_string())
+                .collect::<Vec<_>>(),
+            [
+                "added-staged",
+                "deleted-staged",
+                "modified-both",
+                "modified-staged",
+                "renamed-to"
+            ],
+            "conflicts aren't staged changes, and are reported by the index-worktree comparison instead"
+        );
+        assert!(
+            matches!(staged[4], Change::Rewrite { source_location, copy: false, .. } if source_location.as_ref() == "renamed-from"),
+            "renames are tracked by default"
+        );
+        assert!(
+            items[staged.len()..]
+                .iter()
+                .all(|item| matches!(item, Item::IndexWorktree(_))),
+            "unstaged changes follow"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn staged_renames_can_be_disabled() -> crate::Result {
+        let repo = repo("porcelain-v2")?;
+        let mut iter = repo
+            .status(gix::progress::Discard)?
+            .tree_index_track_renames(None)
+            .untracked_files(gix::status::UntrackedFiles::None)
+            .into_iter(["renamed-*".into()])?;
+        let items: Vec<_> = iter.by_ref().collect::<Result<_, _>>()?;
+        assert_eq!(
+            items.iter().map(|item| item.location().to_string()).collect::<Vec<_>>(),
+            ["renamed-from", "renamed-to"],
+            "a deletion and an addition"
+        );
+        assert_eq!(iter.tree_index_outcome().rewrites, None);
+        Ok(())
+    }
+}
+
+mod porcelain_v2 {
+    use crate::status::repo;
+    use gix::bstr::ByteSlice;
+
+    #[test]
+    fn matches_git() -> crate::Result {
+        let repo = repo("porcelain-v2")?;
+        let mut actual = Vec::new();
+        for entry in repo.status(gix::progress::Discard)?.into_porcelain_v2(None)? {
+            entry.write_to(&mut actual, false)?;
+        }
+        let expected = std::fs::read(repo.git_dir().join("baseline"))?;
+        pretty_assertions::assert_eq!(actual.as_bstr(), expected.as_bstr());
+        Ok(())
+    }
+}
+
 mod is_dirty {
-    use crate::status::submodule_repo;
+    use crate::status::{repo, submodule_repo};
+
+    #[test]
+    fn staged_changes_are_picked_up() -> crate::Result {
+        let repo = repo("staged-only")?;
+        assert!(
+            repo.is_dirty()?,
+            "changes between HEAD and the index make the repository dirty"
+        );
+        Ok(())
+    }
 
     #[test]
     fn various_changes_positive() -> crate::Result {
@@ -167,8 +263,14 @@ mod is_dirty {
 
     #[test]
     fn no_changes() -> crate::Result {
-        let repo = submodule_repo("with-submodules")?;
+        let repo = submodule_repo("with-submodules-after-clone")?;
         assert!(!repo.is_dirty()?, "there are no changes");
+
+        let repo = submodule_repo("with-submodules")?;
+        assert!(
+            repo.is_dirty()?,
+            "the submodule and the modified `.gitmodules` file are only staged"
+        );
         Ok(())
     }
 }
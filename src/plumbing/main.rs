@@ -450,6 +450,8 @@ pub fn main() -> Result<()> {
             handshake_info,
             negotiation_info,
             open_negotiation_graph,
+            prune,
+            no_prune,
             remote,
             shallow,
             ref_spec,
@@ -463,6 +465,7 @@ pub fn main() -> Result<()> {
                 open_negotiation_graph,
                 shallow: shallow.into(),
                 ref_specs: ref_spec,
+                prune: (prune || no_prune).then_some(prune),
             };
             prepare_and_run(
                 "fetch",
@@ -1102,6 +1105,10 @@ pub fn main() -> Result<()> {
                 max_candidates,
                 rev_spec,
                 dirty_suffix,
+                broken_suffix,
+                contains,
+                matching,
+                excluding,
             } => prepare_and_run(
                 "commit-describe",
                 trace,
@@ -1124,6 +1131,10 @@ pub fn main() -> Result<()> {
                             max_candidates,
                             always,
                             dirty_suffix: dirty_suffix.map(|suffix| suffix.unwrap_or_else(|| "dirty".to_string())),
+                            broken_suffix: broken_suffix.map(|suffix| suffix.unwrap_or_else(|| "broken".to_string())),
+                            contains,
+                            matching,
+                            excluding,
                         },
                     )
                 },
@@ -1193,6 +1204,62 @@ pub fn main() -> Result<()> {
                     )
                 },
             ),
+            odb::Subcommands::Repack {
+                all,
+                geometric,
+                batch_size,
+                write_midx,
+            } => prepare_and_run(
+                "odb-repack",
+                trace,
+                auto_verbose,
+                progress,
+                progress_keep_open,
+                core::repository::odb::repack::PROGRESS_RANGE,
+                move |progress, out, _err| {
+                    let mode = match (all, geometric, batch_size) {
+                        (true, _, _) => gix::repack::Mode::All,
+                        (_, _, Some(batch_size)) => gix::repack::Mode::BatchSize(batch_size),
+                        (_, factor, None) => gix::repack::Mode::Geometric {
+                            factor: factor.unwrap_or(2),
+                        },
+                    };
+                    core::repository::odb::repack(
+                        repository(Mode::Strict)?,
+                        progress,
+                        out,
+                        &should_interrupt,
+                        core::repository::odb::repack::Options {
+                            format,
+                            mode,
+                            write_multi_pack_index: write_midx,
+                            thread_limit,
+                        },
+                    )
+                },
+            ),
+            odb::Subcommands::Gc { prune, force } => prepare_and_run(
+                "odb-gc",
+                trace,
+                auto_verbose,
+                progress,
+                progress_keep_open,
+                core::repository::odb::gc::PROGRESS_RANGE,
+                move |progress, out, _err| {
+                    core::repository::odb::gc(
+                        repository(Mode::Strict)?,
+                        progress,
+                        out,
+                        &should_interrupt,
+                        core::repository::odb::gc::Options {
+                            format,
+                            prune,
+                            force,
+                            thread_limit,
+                        },
+                    )
+                },
+            ),
             odb::Subcommands::Entries => prepare_and_run(
                 "odb-entries",
                 trace,
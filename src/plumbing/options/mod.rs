@@ -377,6 +377,14 @@ pub mod fetch {
         #[clap(long, value_name = "PATH", short = 'g')]
         pub open_negotiation_graph: Option<std::path::PathBuf>,
 
+        /// Remove remote-tracking refs that don't exist on the remote anymore, overriding `fetch.prune`.
+        #[clap(long, short = 'p', conflicts_with = "no_prune")]
+        pub prune: bool,
+
+        /// Don't remove any refs even if `fetch.prune` or `remote.<name>.prune` are set.
+        #[clap(long)]
+        pub no_prune: bool,
+
         #[clap(flatten)]
         pub shallow: ShallowOptions,
 
@@ -633,6 +641,36 @@ pub mod odb {
             #[clap(long)]
             extra_header_lookup: bool,
         },
+        /// Consolidate packs to keep their amount low, and remove the packs that became redundant.
+        ///
+        /// By default, the smallest packs are consolidated so that the remaining ones form a geometric progression with factor 2.
+        /// Loose objects and packs with a `.keep` file are left untouched.
+        Repack {
+            /// Consolidate all packs into a single one.
+            #[clap(long, conflicts_with_all = ["geometric", "batch_size"])]
+            all: bool,
+            /// Consolidate the smallest packs so that each remaining pack has at least FACTOR times as many objects as the next smaller one.
+            #[clap(long, value_name = "FACTOR", conflicts_with = "batch_size")]
+            geometric: Option<u32>,
+            /// Consolidate the oldest packs that are smaller than BYTES until their total size reaches it.
+            #[clap(long, value_name = "BYTES")]
+            batch_size: Option<u64>,
+            /// Write a multi-pack index even if there is none yet.
+            #[clap(long)]
+            write_midx: bool,
+        },
+        /// Remove unreachable loose objects and consolidate loose objects, references and packs, similar to `git gc`.
+        ///
+        /// Unreachable loose objects are removed once they are older than `gc.pruneExpire`, and reflog entries
+        /// once they are older than `gc.reflogExpire`.
+        Gc {
+            /// Remove unreachable loose objects older than DATE instead of `gc.pruneExpire`, with `now` and `never` being valid too.
+            #[clap(long, value_name = "DATE")]
+            prune: Option<String>,
+            /// Run even if another garbage collection seems to be running according to the `gc.pid` file.
+            #[clap(long)]
+            force: bool,
+        },
     }
 }
 
@@ -713,6 +751,22 @@ pub mod commit {
             #[clap(short = 'd', long)]
             dirty_suffix: Option<Option<String>>,
 
+            /// Set the suffix to append if it can't be determined if the repository is dirty, instead of failing.
+            #[clap(long)]
+            broken_suffix: Option<Option<String>>,
+
+            /// Find the closest tag that contains the commit, instead of the one it contains.
+            #[clap(long)]
+            contains: bool,
+
+            /// Only consider tags matching the given glob pattern, without the `refs/tags/` prefix.
+            #[clap(long = "match", short = 'm', value_name = "PATTERN")]
+            matching: Vec<String>,
+
+            /// Do not consider tags matching the given glob pattern, without the `refs/tags/` prefix.
+            #[clap(long = "exclude", short = 'x', value_name = "PATTERN")]
+            excluding: Vec<String>,
+
             /// A specification of the revision to use, or the current `HEAD` if unset.
             rev_spec: Option<String>,
         },
@@ -178,10 +178,6 @@ static GIT_CONFIG: &[Record] = &[
         config: "branch.autoSetupRebase",
         usage: Planned("For when we allow setting up upstream branches")
     },
-    Record {
-        config: "branch.<name>.rebase",
-        usage: Planned("For when we can merge, rebase should be supported")
-    },
     Record {
         config: "branch.<name>.description",
         usage: NotPlanned("No plan to implement 'format-patch' or 'request-pull' summary")
@@ -237,14 +233,6 @@ static GIT_CONFIG: &[Record] = &[
         config: "fetch.unpackLimit",
         usage: Planned("")
     },
-    Record {
-        config: "fetch.prune",
-        usage: Planned("")
-    },
-    Record {
-        config: "fetch.pruneTags",
-        usage: Planned("")
-    },
     Record {
         config: "fetch.writeCommitGraph",
         usage: Planned("")
@@ -391,7 +391,7 @@ mod clap {
 
         fn parse_ref(&self, cmd: &Command, arg: Option<&Arg>, value: &OsStr) -> Result<Self::Value, Error> {
             StringValueParser::new()
-                .try_map(|arg| gix::date::parse(&arg, Some(std::time::SystemTime::now())))
+                .try_map(|arg| gix::date::parse::approximate(&arg, Some(std::time::SystemTime::now())))
                 .parse_ref(cmd, arg, value)
         }
     }